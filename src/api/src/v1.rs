@@ -16,6 +16,11 @@ pub mod column_def;
 
 pub mod meta {
     pub use greptime_proto::v1::meta::*;
+
+    /// The DDL tasks of Metasrv that are not in greptime-proto yet.
+    pub mod ddl {
+        tonic::include_proto!("greptime.v1.meta.ddl");
+    }
 }
 
 pub use greptime_proto::v1::*;
@@ -40,3 +40,23 @@ pub fn try_as_column_schema(column_def: &ColumnDef) -> Result<ColumnSchema> {
             column: &column_def.name,
         })
 }
+
+pub fn try_as_column_def(column_schema: &ColumnSchema) -> Result<ColumnDef> {
+    let data_type = ColumnDataTypeWrapper::try_from(column_schema.data_type.clone())?;
+
+    let default_constraint = column_schema
+        .default_constraint()
+        .map(|constraint| Vec::<u8>::try_from(constraint.clone()))
+        .transpose()
+        .context(error::ConvertColumnDefaultConstraintSnafu {
+            column: &column_schema.name,
+        })?
+        .unwrap_or_default();
+
+    Ok(ColumnDef {
+        name: column_schema.name.clone(),
+        datatype: data_type.datatype() as i32,
+        is_nullable: column_schema.is_nullable(),
+        default_constraint,
+    })
+}
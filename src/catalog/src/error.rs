@@ -225,6 +225,16 @@ pub enum Error {
     #[snafu(display("Illegal access to catalog: {} and schema: {}", catalog, schema))]
     QueryAccessDenied { catalog: String, schema: String },
 
+    #[snafu(display("Failed to plan view: {}, source: {}", view, source))]
+    PlanView {
+        view: String,
+        location: Location,
+        source: BoxedError,
+    },
+
+    #[snafu(display("View {} references itself, location: {}", view, location))]
+    CyclicView { view: String, location: Location },
+
     #[snafu(display("Invalid system table definition: {err_msg}"))]
     InvalidSystemTableDef { err_msg: String, location: Location },
 
@@ -291,9 +301,10 @@ impl ErrorExt for Error {
             Error::SystemCatalogTableScanExec { source, .. } => source.status_code(),
             Error::InvalidTableInfoInCatalog { source, .. } => source.status_code(),
 
-            Error::CompileScriptInternal { source, .. } | Error::Internal { source, .. } => {
-                source.status_code()
-            }
+            Error::CompileScriptInternal { source, .. }
+            | Error::Internal { source, .. }
+            | Error::PlanView { source, .. } => source.status_code(),
+            Error::CyclicView { .. } => StatusCode::InvalidArguments,
 
             Error::Unimplemented { .. } | Error::NotSupported { .. } => StatusCode::Unsupported,
             Error::QueryAccessDenied { .. } => StatusCode::AccessDenied,
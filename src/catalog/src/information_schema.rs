@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod columns;
+mod processlist;
 mod tables;
 
 use std::any::Any;
@@ -25,6 +26,7 @@ use common_query::prelude::Expr;
 use common_recordbatch::{RecordBatchStreamAdaptor, SendableRecordBatchStream};
 use datatypes::schema::SchemaRef;
 use futures_util::StreamExt;
+use session::context::UserInfo;
 use snafu::ResultExt;
 use store_api::storage::ScanRequest;
 use table::error::{SchemaConversionSnafu, TablesRecordBatchSnafu};
@@ -32,15 +34,19 @@ use table::{Result as TableResult, Table, TableRef};
 
 use self::columns::InformationSchemaColumns;
 use crate::error::Result;
+use crate::information_schema::processlist::InformationSchemaProcesslist;
 use crate::information_schema::tables::InformationSchemaTables;
+use crate::process_manager::ProcessManagerRef;
 use crate::CatalogManager;
 
 const TABLES: &str = "tables";
 const COLUMNS: &str = "columns";
+const PROCESSLIST: &str = "processlist";
 
 pub struct InformationSchemaProvider {
     catalog_name: String,
     catalog_manager: Weak<dyn CatalogManager>,
+    process_manager: Option<ProcessManagerRef>,
 }
 
 impl InformationSchemaProvider {
@@ -48,8 +54,15 @@ impl InformationSchemaProvider {
         Self {
             catalog_name,
             catalog_manager,
+            process_manager: None,
         }
     }
+
+    /// Provides the `processlist` table listing the statements registered in `process_manager`.
+    pub fn with_process_manager(mut self, process_manager: ProcessManagerRef) -> Self {
+        self.process_manager = Some(process_manager);
+        self
+    }
 }
 
 impl InformationSchemaProvider {
@@ -63,6 +76,12 @@ impl InformationSchemaProvider {
                 self.catalog_name.clone(),
                 self.catalog_manager.clone(),
             )) as _,
+            PROCESSLIST => match &self.process_manager {
+                Some(process_manager) => {
+                    Arc::new(InformationSchemaProcesslist::new(process_manager.clone())) as _
+                }
+                None => return Ok(None),
+            },
             _ => {
                 return Ok(None);
             }
@@ -78,6 +97,12 @@ pub trait InformationStreamBuilder: Send + Sync {
     fn to_stream(&self) -> Result<SendableRecordBatchStream>;
 
     fn schema(&self) -> SchemaRef;
+
+    /// Returns a builder of the rows visible to the user, or `None` if the rows
+    /// don't depend on the user.
+    fn for_user(&self, _user_info: &UserInfo) -> Option<Arc<dyn InformationStreamBuilder>> {
+        None
+    }
 }
 
 pub struct InformationTable {
@@ -88,6 +113,14 @@ impl InformationTable {
     pub fn new(stream_builder: Arc<dyn InformationStreamBuilder>) -> Self {
         Self { stream_builder }
     }
+
+    /// Returns a table of the rows visible to the user, or `None` if the rows
+    /// don't depend on the user.
+    pub fn for_user(&self, user_info: &UserInfo) -> Option<TableRef> {
+        self.stream_builder
+            .for_user(user_info)
+            .map(|stream_builder| Arc::new(Self::new(stream_builder)) as _)
+    }
 }
 
 #[async_trait]
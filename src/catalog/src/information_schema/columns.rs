@@ -16,7 +16,7 @@ use std::sync::{Arc, Weak};
 
 use arrow_schema::SchemaRef as ArrowSchemaRef;
 use common_catalog::consts::{
-    SEMANTIC_TYPE_FIELD, SEMANTIC_TYPE_PRIMARY_KEY, SEMANTIC_TYPE_TIME_INDEX,
+    INTERNAL_SCHEMA_NAME, SEMANTIC_TYPE_FIELD, SEMANTIC_TYPE_PRIMARY_KEY, SEMANTIC_TYPE_TIME_INDEX,
 };
 use common_error::prelude::BoxedError;
 use common_query::physical_plan::TaskContext;
@@ -143,6 +143,9 @@ impl InformationSchemaColumnsBuilder {
             .context(UpgradeWeakCatalogManagerRefSnafu)?;
 
         for schema_name in catalog_manager.schema_names(&catalog_name).await? {
+            if schema_name == INTERNAL_SCHEMA_NAME {
+                continue;
+            }
             if !catalog_manager
                 .schema_exist(&catalog_name, &schema_name)
                 .await?
@@ -0,0 +1,175 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_recordbatch::{RecordBatch, RecordBatches, SendableRecordBatchStream};
+use datatypes::prelude::{ConcreteDataType, ScalarVectorBuilder, VectorRef};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::timestamp::TimestampMillisecond;
+use datatypes::vectors::{
+    StringVectorBuilder, TimestampMillisecondVectorBuilder, UInt64VectorBuilder,
+};
+use session::context::UserInfo;
+use snafu::ResultExt;
+
+use crate::error::{CreateRecordBatchSnafu, Result};
+use crate::information_schema::InformationStreamBuilder;
+use crate::process_manager::ProcessManagerRef;
+
+/// The `information_schema.processlist` table, listing the statements running on
+/// this frontend. Users other than superusers only see their own statements.
+///
+/// Columns are based on <https://dev.mysql.com/doc/refman/8.0/en/information-schema-processlist-table.html>
+pub(super) struct InformationSchemaProcesslist {
+    schema: SchemaRef,
+    process_manager: ProcessManagerRef,
+    user_info: Arc<UserInfo>,
+}
+
+impl InformationSchemaProcesslist {
+    pub(super) fn new(process_manager: ProcessManagerRef) -> Self {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("id", ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new("user", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("channel", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("catalog", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("db", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(
+                "start_time",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new("time", ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new("state", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("info", ConcreteDataType::string_datatype(), false),
+        ]));
+        Self {
+            schema,
+            process_manager,
+            user_info: Arc::new(UserInfo::default()),
+        }
+    }
+
+    fn make_processlist(&self) -> Result<RecordBatch> {
+        let processes = self.process_manager.list_visible_to(&self.user_info);
+        let mut ids = UInt64VectorBuilder::with_capacity(processes.len());
+        let mut users = StringVectorBuilder::with_capacity(processes.len());
+        let mut hosts = StringVectorBuilder::with_capacity(processes.len());
+        let mut channels = StringVectorBuilder::with_capacity(processes.len());
+        let mut catalogs = StringVectorBuilder::with_capacity(processes.len());
+        let mut dbs = StringVectorBuilder::with_capacity(processes.len());
+        let mut start_times = TimestampMillisecondVectorBuilder::with_capacity(processes.len());
+        let mut times = UInt64VectorBuilder::with_capacity(processes.len());
+        let mut states = StringVectorBuilder::with_capacity(processes.len());
+        let mut infos = StringVectorBuilder::with_capacity(processes.len());
+
+        for process in processes {
+            let conn_info = process.conn_info();
+            ids.push(Some(process.id()));
+            users.push(Some(process.user()));
+            hosts.push(
+                conn_info
+                    .and_then(|info| info.client_addr)
+                    .map(|addr| addr.to_string())
+                    .as_deref(),
+            );
+            channels.push(conn_info.map(|info| info.channel.to_string()).as_deref());
+            catalogs.push(Some(process.catalog()));
+            dbs.push(Some(process.schema()));
+            start_times.push(Some(TimestampMillisecond::new(process.start_time())));
+            times.push(Some(process.elapsed().as_secs()));
+            states.push(Some(&process.stage().to_string()));
+            infos.push(Some(process.query()));
+        }
+
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(ids.finish()),
+            Arc::new(users.finish()),
+            Arc::new(hosts.finish()),
+            Arc::new(channels.finish()),
+            Arc::new(catalogs.finish()),
+            Arc::new(dbs.finish()),
+            Arc::new(start_times.finish()),
+            Arc::new(times.finish()),
+            Arc::new(states.finish()),
+            Arc::new(infos.finish()),
+        ];
+        RecordBatch::new(self.schema.clone(), columns).context(CreateRecordBatchSnafu)
+    }
+}
+
+impl InformationStreamBuilder for InformationSchemaProcesslist {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn to_stream(&self) -> Result<SendableRecordBatchStream> {
+        let batch = self.make_processlist()?;
+        let batches = RecordBatches::try_new(self.schema.clone(), vec![batch])
+            .context(CreateRecordBatchSnafu)?;
+        Ok(batches.as_stream())
+    }
+
+    fn for_user(&self, user_info: &UserInfo) -> Option<Arc<dyn InformationStreamBuilder>> {
+        Some(Arc::new(Self {
+            schema: self.schema.clone(),
+            process_manager: self.process_manager.clone(),
+            user_info: Arc::new(user_info.clone()),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use session::context::QueryContext;
+
+    use super::*;
+    use crate::process_manager::ProcessManager;
+
+    #[tokio::test]
+    async fn test_processlist() {
+        let process_manager = Arc::new(ProcessManager::default());
+        let _handle = process_manager.register("SELECT 1", &QueryContext::new());
+
+        let table = InformationSchemaProcesslist::new(process_manager);
+        let mut stream = table.to_stream().unwrap();
+        let batch = stream.next().await.unwrap().unwrap();
+        assert_eq!(1, batch.num_rows());
+        assert_eq!(10, batch.num_columns());
+        let info = batch.column_by_name("info").unwrap();
+        assert_eq!(Some("SELECT 1"), info.get_ref(0).as_string().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_processlist_for_user() {
+        let process_manager = Arc::new(ProcessManager::default());
+        let query_ctx = QueryContext::new();
+        query_ctx.set_current_user(UserInfo::new("alice"));
+        let _alice = process_manager.register("SELECT 1", &query_ctx);
+        query_ctx.set_current_user(UserInfo::new("bob"));
+        let _bob = process_manager.register("SELECT 2", &query_ctx);
+
+        let table = InformationSchemaProcesslist::new(process_manager)
+            .for_user(&UserInfo::new("bob"))
+            .unwrap();
+        let mut stream = table.to_stream().unwrap();
+        let batch = stream.next().await.unwrap().unwrap();
+        assert_eq!(1, batch.num_rows());
+        let info = batch.column_by_name("info").unwrap();
+        assert_eq!(Some("SELECT 2"), info.get_ref(0).as_string().unwrap());
+    }
+}
@@ -15,7 +15,7 @@
 use std::sync::{Arc, Weak};
 
 use arrow_schema::SchemaRef as ArrowSchemaRef;
-use common_catalog::consts::INFORMATION_SCHEMA_NAME;
+use common_catalog::consts::{INFORMATION_SCHEMA_NAME, INTERNAL_SCHEMA_NAME};
 use common_error::prelude::BoxedError;
 use common_query::physical_plan::TaskContext;
 use common_recordbatch::adapter::RecordBatchStreamAdapter;
@@ -137,7 +137,7 @@ impl InformationSchemaTablesBuilder {
             .context(UpgradeWeakCatalogManagerRefSnafu)?;
 
         for schema_name in catalog_manager.schema_names(&catalog_name).await? {
-            if schema_name == INFORMATION_SCHEMA_NAME {
+            if schema_name == INFORMATION_SCHEMA_NAME || schema_name == INTERNAL_SCHEMA_NAME {
                 continue;
             }
             if !catalog_manager
@@ -153,13 +153,23 @@ impl InformationSchemaTablesBuilder {
             {
                 let Some(table) = catalog_manager.table(&catalog_name, &schema_name, &table_name).await? else { continue };
                 let table_info = table.table_info();
+                let table_type = table.table_type();
+                // Views have neither table ids nor engines.
+                let (table_id, engine) = if table_type == TableType::View {
+                    (None, None)
+                } else {
+                    (
+                        Some(table_info.ident.table_id),
+                        Some(table_info.meta.engine.as_str()),
+                    )
+                };
                 self.add_table(
                     &catalog_name,
                     &schema_name,
                     &table_name,
-                    table.table_type(),
-                    Some(table_info.ident.table_id),
-                    Some(&table_info.meta.engine),
+                    table_type,
+                    table_id,
+                    engine,
                 );
             }
         }
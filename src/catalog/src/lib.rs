@@ -22,6 +22,7 @@ use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
 
 use api::v1::meta::{RegionStat, TableName};
+use common_meta::heartbeat::{FOLLOWER_REGION_ROLE, REGION_ROLE_ATTR};
 use common_telemetry::{info, warn};
 use snafu::ResultExt;
 use table::engine::{EngineContext, TableEngineRef};
@@ -30,12 +31,14 @@ use table::requests::CreateTableRequest;
 use table::TableRef;
 
 use crate::error::{CreateTableSnafu, Result};
+use crate::process_manager::ProcessManagerRef;
 
 pub mod error;
 pub mod helper;
 pub mod information_schema;
 pub mod local;
 mod metrics;
+pub mod process_manager;
 pub mod remote;
 pub mod system;
 pub mod table_source;
@@ -67,6 +70,16 @@ pub trait CatalogManager: Send + Sync {
     /// This method will/should fail if catalog or schema not exist
     async fn register_table(&self, request: RegisterTableRequest) -> Result<bool>;
 
+    /// Replaces the definition of an existing view with the view in `request` in
+    /// one step, keeping the view's table id. Used by `CREATE OR REPLACE VIEW`.
+    ///
+    /// # Errors
+    ///
+    /// This method will/should fail if the view not exist
+    async fn replace_view(&self, _request: RegisterTableRequest) -> Result<()> {
+        error::NotSupportedSnafu { op: "replace_view" }.fail()
+    }
+
     /// Deregisters a table within given catalog/schema to catalog manager
     async fn deregister_table(&self, request: DeregisterTableRequest) -> Result<()>;
 
@@ -96,6 +109,11 @@ pub trait CatalogManager: Send + Sync {
         schema: &str,
         table_name: &str,
     ) -> Result<Option<TableRef>>;
+
+    /// Returns the registry of running statements, if the manager serves queries.
+    fn process_manager(&self) -> Option<ProcessManagerRef> {
+        None
+    }
 }
 
 pub type CatalogManagerRef = Arc<dyn CatalogManager>;
@@ -166,6 +184,17 @@ pub(crate) async fn handle_system_table_request<'a, M: CatalogManager>(
         let table_name = &req.create_table_request.table_name;
         let table_id = req.create_table_request.id;
 
+        // System tables may be put into the internal schema, which is created on demand.
+        if !manager.schema_exist(catalog_name, schema_name).await? {
+            let _ = manager
+                .register_schema(RegisterSchemaRequest {
+                    catalog: catalog_name.clone(),
+                    schema: schema_name.clone(),
+                })
+                .await?;
+            info!("Created schema for system tables: {catalog_name}.{schema_name}");
+        }
+
         let table = manager.table(catalog_name, schema_name, table_name).await?;
         let table = if let Some(table) = table {
             table
@@ -232,7 +261,7 @@ pub async fn datanode_stat(catalog_manager: &CatalogManagerRef) -> (u64, Vec<Reg
                                 table_name: table_name.clone(),
                             }),
                             approximate_bytes: stat.disk_usage_bytes as i64,
-                            attrs: HashMap::from([("engine_name".to_owned(), engine.clone())]),
+                            attrs: region_stat_attrs(engine, stat.read_only),
                             ..Default::default()
                         });
 
@@ -247,3 +276,11 @@ pub async fn datanode_stat(catalog_manager: &CatalogManagerRef) -> (u64, Vec<Reg
     }
     (region_number, region_stats)
 }
+
+fn region_stat_attrs(engine: &str, read_only: bool) -> HashMap<String, String> {
+    let mut attrs = HashMap::from([("engine_name".to_owned(), engine.to_owned())]);
+    if read_only {
+        let _ = attrs.insert(REGION_ROLE_ATTR.to_owned(), FOLLOWER_REGION_ROLE.to_owned());
+    }
+    attrs
+}
@@ -31,9 +31,10 @@ use metrics::increment_gauge;
 use snafu::{ensure, OptionExt, ResultExt};
 use table::engine::manager::TableEngineManagerRef;
 use table::engine::EngineContext;
-use table::metadata::TableId;
+use table::metadata::{RawTableInfo, TableId, TableInfo, TableType};
 use table::requests::OpenTableRequest;
 use table::table::numbers::{NumbersTable, NUMBERS_TABLE_NAME};
+use table::table::view::ViewTable;
 use table::table::TableIdProvider;
 use table::TableRef;
 
@@ -45,9 +46,10 @@ use crate::error::{
 };
 use crate::information_schema::InformationSchemaProvider;
 use crate::local::memory::MemoryCatalogManager;
+use crate::process_manager::{ProcessManager, ProcessManagerRef};
 use crate::system::{
-    decode_system_catalog, Entry, SystemCatalogTable, TableEntry, ENTRY_TYPE_INDEX, KEY_INDEX,
-    VALUE_INDEX,
+    decode_system_catalog, Entry, SystemCatalogTable, TableEntry, ViewEntry, ENTRY_TYPE_INDEX,
+    KEY_INDEX, VALUE_INDEX,
 };
 use crate::tables::SystemCatalog;
 use crate::{
@@ -64,6 +66,7 @@ pub struct LocalCatalogManager {
     init_lock: Mutex<bool>,
     register_lock: Mutex<()>,
     system_table_requests: Mutex<Vec<RegisterSystemTableRequest>>,
+    process_manager: ProcessManagerRef,
 }
 
 impl LocalCatalogManager {
@@ -85,6 +88,7 @@ impl LocalCatalogManager {
             init_lock: Mutex::new(false),
             register_lock: Mutex::new(()),
             system_table_requests: Mutex::new(Vec::default()),
+            process_manager: Arc::new(ProcessManager::default()),
         })
     }
 
@@ -247,6 +251,10 @@ impl LocalCatalogManager {
                     info!("Registered table: {:?}", t);
                     max_table_id = max_table_id.max(t.table_id);
                 }
+                Entry::View(v) => {
+                    max_table_id = max_table_id.max(v.table_info.ident.table_id);
+                    self.register_view(v).await?;
+                }
             }
         }
         Ok(max_table_id)
@@ -270,6 +278,7 @@ impl LocalCatalogManager {
             table_name: t.table_name.clone(),
             table_id: t.table_id,
             region_numbers: vec![0],
+            read_only: false,
         };
         let engine = self
             .engine_manager
@@ -306,6 +315,29 @@ impl LocalCatalogManager {
         Ok(())
     }
 
+    async fn register_view(&self, v: ViewEntry) -> Result<()> {
+        self.check_catalog_schema_exist(&v.catalog_name, &v.schema_name)
+            .await?;
+
+        let table_info: TableInfo = v
+            .table_info
+            .try_into()
+            .context(error::InvalidTableInfoInCatalogSnafu)?;
+        let register_request = RegisterTableRequest {
+            catalog: v.catalog_name.clone(),
+            schema: v.schema_name.clone(),
+            table_name: v.view_name.clone(),
+            table_id: table_info.ident.table_id,
+            table: Arc::new(ViewTable::new(Arc::new(table_info))),
+        };
+        let _ = self.catalogs.register_table(register_request).await?;
+        info!(
+            "Registered view: {}",
+            format_full_table_name(&v.catalog_name, &v.schema_name, &v.view_name)
+        );
+        Ok(())
+    }
+
     async fn check_state(&self) -> Result<()> {
         let started = self.init_lock.lock().await;
         ensure!(
@@ -390,20 +422,35 @@ impl CatalogManager for LocalCatalogManager {
                 Ok(false)
             } else {
                 // table does not exist
-                let engine = request.table.table_info().meta.engine.to_string();
+                let mut request = request;
+                if request.table.table_type() == TableType::View {
+                    // Views have no table engine to allocate their ids.
+                    let table_id = self.next_table_id.fetch_add(1, Ordering::Relaxed);
+                    let view = ViewTable::new_with_table_id(&request.table.table_info(), table_id);
+                    request.table_id = table_id;
+                    request.table = Arc::new(view);
+                }
+                let table_info = request.table.table_info();
                 let table_name = request.table_name.clone();
                 let table_id = request.table_id;
                 let _ = self.catalogs.register_table(request).await?;
-                let _ = self
-                    .system
-                    .register_table(
-                        catalog_name.clone(),
-                        schema_name.clone(),
-                        table_name,
-                        table_id,
-                        engine,
-                    )
-                    .await?;
+                if table_info.table_type == TableType::View {
+                    let _ = self
+                        .system
+                        .register_view(RawTableInfo::from(table_info.as_ref().clone()))
+                        .await?;
+                } else {
+                    let _ = self
+                        .system
+                        .register_table(
+                            catalog_name.clone(),
+                            schema_name.clone(),
+                            table_name,
+                            table_id,
+                            table_info.meta.engine.to_string(),
+                        )
+                        .await?;
+                }
                 increment_gauge!(
                     crate::metrics::METRIC_CATALOG_MANAGER_TABLE_COUNT,
                     1.0,
@@ -414,6 +461,39 @@ impl CatalogManager for LocalCatalogManager {
         }
     }
 
+    async fn replace_view(&self, request: RegisterTableRequest) -> Result<()> {
+        self.check_state().await?;
+
+        let _lock = self.register_lock.lock().await;
+        let existing = self
+            .catalogs
+            .table(&request.catalog, &request.schema, &request.table_name)
+            .await?
+            .filter(|t| t.table_type() == TableType::View)
+            .with_context(|| TableNotExistSnafu {
+                table: format_full_table_name(
+                    &request.catalog,
+                    &request.schema,
+                    &request.table_name,
+                ),
+            })?;
+        let table_id = existing.table_info().ident.table_id;
+        let mut request = request;
+        request.table = Arc::new(ViewTable::new_with_table_id(
+            &request.table.table_info(),
+            table_id,
+        ));
+        request.table_id = table_id;
+
+        // The view entry is keyed by name, so inserting it again replaces the old one.
+        let table_info = request.table.table_info();
+        let _ = self
+            .system
+            .register_view(RawTableInfo::from(table_info.as_ref().clone()))
+            .await?;
+        self.catalogs.replace_table_sync(request)
+    }
+
     async fn rename_table(&self, request: RenameTableRequest) -> Result<bool> {
         self.check_state().await?;
 
@@ -468,18 +548,22 @@ impl CatalogManager for LocalCatalogManager {
                 schema,
                 table_name,
             } = &request;
-            let table_id = self
+            let table_info = self
                 .catalogs
                 .table(catalog, schema, table_name)
                 .await?
                 .with_context(|| error::TableNotExistSnafu {
                     table: format_full_table_name(catalog, schema, table_name),
                 })?
-                .table_info()
-                .ident
-                .table_id;
+                .table_info();
 
-            self.system.deregister_table(&request, table_id).await?;
+            if table_info.table_type == TableType::View {
+                self.system.deregister_view(&request).await?;
+            } else {
+                self.system
+                    .deregister_table(&request, table_info.ident.table_id)
+                    .await?;
+            }
             self.catalogs.deregister_table(request).await
         }
     }
@@ -542,7 +626,8 @@ impl CatalogManager for LocalCatalogManager {
         if schema_name == INFORMATION_SCHEMA_NAME {
             let manager: CatalogManagerRef = self.catalogs.clone() as _;
             let provider =
-                InformationSchemaProvider::new(catalog_name.to_string(), Arc::downgrade(&manager));
+                InformationSchemaProvider::new(catalog_name.to_string(), Arc::downgrade(&manager))
+                    .with_process_manager(self.process_manager.clone());
             return provider.table(table_name);
         }
 
@@ -582,6 +667,10 @@ impl CatalogManager for LocalCatalogManager {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn process_manager(&self) -> Option<ProcessManagerRef> {
+        Some(self.process_manager.clone())
+    }
 }
 
 #[cfg(test)]
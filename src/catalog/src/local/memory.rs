@@ -19,6 +19,7 @@ use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, RwLock};
 
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, MIN_USER_TABLE_ID};
+use common_catalog::format_full_table_name;
 use metrics::{decrement_gauge, increment_gauge};
 use snafu::OptionExt;
 use table::metadata::TableId;
@@ -119,6 +120,10 @@ impl CatalogManager for MemoryCatalogManager {
         Ok(true)
     }
 
+    async fn replace_view(&self, request: RegisterTableRequest) -> Result<()> {
+        self.replace_table_sync(request)
+    }
+
     async fn deregister_table(&self, request: DeregisterTableRequest) -> Result<()> {
         let mut catalogs = self.catalogs.write().unwrap();
         let schema = catalogs
@@ -303,6 +308,34 @@ impl MemoryCatalogManager {
         Ok(schema.insert(request.table_name, request.table).is_none())
     }
 
+    /// Replaces an existing table with the table in `request` under one lock, so
+    /// readers see either the old or the new table.
+    pub fn replace_table_sync(&self, request: RegisterTableRequest) -> Result<()> {
+        let mut catalogs = self.catalogs.write().unwrap();
+        let schema = catalogs
+            .get_mut(&request.catalog)
+            .with_context(|| CatalogNotFoundSnafu {
+                catalog_name: &request.catalog,
+            })?
+            .get_mut(&request.schema)
+            .with_context(|| SchemaNotFoundSnafu {
+                catalog: &request.catalog,
+                schema: &request.schema,
+            })?;
+
+        let table = schema
+            .get_mut(&request.table_name)
+            .with_context(|| TableNotFoundSnafu {
+                table_info: format_full_table_name(
+                    &request.catalog,
+                    &request.schema,
+                    &request.table_name,
+                ),
+            })?;
+        *table = request.table;
+        Ok(())
+    }
+
     #[cfg(any(test, feature = "testing"))]
     pub fn new_with_table(table: TableRef) -> Self {
         let manager = Self::default();
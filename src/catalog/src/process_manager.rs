@@ -0,0 +1,313 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Registry of the statements running on a frontend, listed by `SHOW PROCESSLIST`
+//! and cancelled by `KILL QUERY`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+
+use common_time::util::current_time_millis;
+use parking_lot::{Mutex, RwLock};
+use session::context::{ConnInfo, QueryContext, UserInfo};
+use tokio_util::sync::CancellationToken;
+
+pub type ProcessId = u64;
+
+/// Stage of a running statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryStage {
+    /// The statement is being planned and executed.
+    Executing,
+    /// The results are being streamed to the client.
+    Streaming,
+}
+
+impl fmt::Display for QueryStage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QueryStage::Executing => write!(f, "executing"),
+            QueryStage::Streaming => write!(f, "streaming"),
+        }
+    }
+}
+
+/// A running statement.
+#[derive(Debug)]
+pub struct Process {
+    id: ProcessId,
+    user: String,
+    conn_info: Option<Arc<ConnInfo>>,
+    catalog: String,
+    schema: String,
+    query: String,
+    /// Start time in milliseconds since the epoch.
+    start_time: i64,
+    start: Instant,
+    stage: Mutex<QueryStage>,
+    cancellation: CancellationToken,
+}
+
+impl Process {
+    pub fn id(&self) -> ProcessId {
+        self.id
+    }
+
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+
+    pub fn conn_info(&self) -> Option<&ConnInfo> {
+        self.conn_info.as_deref()
+    }
+
+    pub fn catalog(&self) -> &str {
+        &self.catalog
+    }
+
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn start_time(&self) -> i64 {
+        self.start_time
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    pub fn stage(&self) -> QueryStage {
+        *self.stage.lock()
+    }
+
+    pub fn is_killed(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// Waits until the statement is killed.
+    pub async fn killed(&self) {
+        self.cancellation.cancelled().await
+    }
+}
+
+/// Decides which users are superusers, who can see and kill the statements of
+/// all users. The other users only see and kill their own statements.
+pub trait SuperuserChecker: Send + Sync {
+    fn is_superuser(&self, user_info: &UserInfo) -> bool;
+}
+
+#[derive(Default)]
+pub struct ProcessManager {
+    next_id: AtomicU64,
+    processes: RwLock<HashMap<ProcessId, Arc<Process>>>,
+    superuser_checker: RwLock<Option<Weak<dyn SuperuserChecker>>>,
+}
+
+impl fmt::Debug for ProcessManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProcessManager")
+            .field("next_id", &self.next_id)
+            .field("processes", &self.processes)
+            .finish()
+    }
+}
+
+pub type ProcessManagerRef = Arc<ProcessManager>;
+
+impl ProcessManager {
+    /// Registers a running statement, which is deregistered when the returned
+    /// handle is dropped.
+    pub fn register(self: &Arc<Self>, query: &str, query_ctx: &QueryContext) -> ProcessHandle {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let process = Arc::new(Process {
+            id,
+            user: query_ctx.current_user().username().to_string(),
+            conn_info: query_ctx.conn_info(),
+            catalog: query_ctx.current_catalog(),
+            schema: query_ctx.current_schema(),
+            query: query.to_string(),
+            start_time: current_time_millis(),
+            start: Instant::now(),
+            stage: Mutex::new(QueryStage::Executing),
+            cancellation: CancellationToken::new(),
+        });
+        let _ = self.processes.write().insert(id, process.clone());
+
+        ProcessHandle {
+            manager: self.clone(),
+            process,
+        }
+    }
+
+    /// Sets the checker of superusers. The checker is held weakly as it usually
+    /// holds the catalog manager, which holds this process manager. Without a
+    /// checker, there are no superusers.
+    pub fn set_superuser_checker(&self, checker: Weak<dyn SuperuserChecker>) {
+        *self.superuser_checker.write() = Some(checker);
+    }
+
+    pub fn is_superuser(&self, user_info: &UserInfo) -> bool {
+        self.superuser_checker
+            .read()
+            .as_ref()
+            .and_then(Weak::upgrade)
+            .map(|checker| checker.is_superuser(user_info))
+            .unwrap_or(false)
+    }
+
+    /// Returns true if the user can see and kill the statement.
+    pub fn is_visible_to(&self, process: &Process, user_info: &UserInfo) -> bool {
+        process.user == user_info.username() || self.is_superuser(user_info)
+    }
+
+    /// Returns the running statements ordered by id.
+    pub fn list(&self) -> Vec<Arc<Process>> {
+        let mut processes = self.processes.read().values().cloned().collect::<Vec<_>>();
+        processes.sort_unstable_by_key(|process| process.id);
+        processes
+    }
+
+    /// Returns the running statements the user can see, ordered by id.
+    pub fn list_visible_to(&self, user_info: &UserInfo) -> Vec<Arc<Process>> {
+        let is_superuser = self.is_superuser(user_info);
+        self.list()
+            .into_iter()
+            .filter(|process| is_superuser || process.user == user_info.username())
+            .collect()
+    }
+
+    pub fn get(&self, id: ProcessId) -> Option<Arc<Process>> {
+        self.processes.read().get(&id).cloned()
+    }
+
+    /// Kills the statement, returns false if it isn't running.
+    pub fn kill(&self, id: ProcessId) -> bool {
+        if let Some(process) = self.get(id) {
+            process.cancellation.cancel();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Handle of a registered statement.
+#[derive(Debug)]
+pub struct ProcessHandle {
+    manager: ProcessManagerRef,
+    process: Arc<Process>,
+}
+
+impl ProcessHandle {
+    pub fn process(&self) -> &Arc<Process> {
+        &self.process
+    }
+
+    pub fn set_stage(&self, stage: QueryStage) {
+        *self.process.stage.lock() = stage;
+    }
+}
+
+impl Drop for ProcessHandle {
+    fn drop(&mut self) {
+        let _ = self.manager.processes.write().remove(&self.process.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use session::context::{Channel, UserInfo};
+
+    use super::*;
+
+    #[test]
+    fn test_register_and_kill() {
+        let manager = Arc::new(ProcessManager::default());
+        let query_ctx = QueryContext::new();
+        query_ctx.set_current_user(UserInfo::new("alice"));
+        query_ctx.set_conn_info(ConnInfo::new(None, Channel::Mysql));
+
+        let first = manager.register("SELECT 1", &query_ctx);
+        let second = manager.register("SELECT 2", &query_ctx);
+        assert_ne!(first.process().id(), second.process().id());
+
+        let processes = manager.list();
+        assert_eq!(2, processes.len());
+        assert_eq!("SELECT 1", processes[0].query());
+        assert_eq!("alice", processes[0].user());
+        assert_eq!(Channel::Mysql, processes[0].conn_info().unwrap().channel);
+        assert_eq!(QueryStage::Executing, processes[0].stage());
+
+        second.set_stage(QueryStage::Streaming);
+        assert_eq!(
+            QueryStage::Streaming,
+            manager.get(second.process().id()).unwrap().stage()
+        );
+
+        assert!(manager.kill(first.process().id()));
+        assert!(first.process().is_killed());
+        assert!(!second.process().is_killed());
+
+        let id = first.process().id();
+        drop(first);
+        assert!(manager.get(id).is_none());
+        assert!(!manager.kill(id));
+        assert_eq!(1, manager.list().len());
+    }
+
+    struct AdminChecker;
+
+    impl SuperuserChecker for AdminChecker {
+        fn is_superuser(&self, user_info: &UserInfo) -> bool {
+            user_info.username() == "admin"
+        }
+    }
+
+    #[test]
+    fn test_list_visible_to() {
+        let manager = Arc::new(ProcessManager::default());
+        let query_ctx = QueryContext::new();
+        query_ctx.set_current_user(UserInfo::new("alice"));
+        let alice = manager.register("SELECT 1", &query_ctx);
+        query_ctx.set_current_user(UserInfo::new("bob"));
+        let _bob = manager.register("SELECT 2", &query_ctx);
+
+        let alice_info = UserInfo::new("alice");
+        let admin_info = UserInfo::new("admin");
+        let processes = manager.list_visible_to(&alice_info);
+        assert_eq!(1, processes.len());
+        assert_eq!("SELECT 1", processes[0].query());
+        assert!(manager.is_visible_to(alice.process(), &alice_info));
+        // No superusers without a checker.
+        assert!(manager.list_visible_to(&admin_info).is_empty());
+        assert!(!manager.is_visible_to(alice.process(), &admin_info));
+
+        let checker: Arc<dyn SuperuserChecker> = Arc::new(AdminChecker);
+        manager.set_superuser_checker(Arc::downgrade(&checker));
+        assert_eq!(2, manager.list_visible_to(&admin_info).len());
+        assert!(manager.is_visible_to(alice.process(), &admin_info));
+        assert_eq!(1, manager.list_visible_to(&alice_info).len());
+
+        drop(checker);
+        assert!(manager.list_visible_to(&admin_info).is_empty());
+    }
+}
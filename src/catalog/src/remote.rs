@@ -25,6 +25,7 @@ pub use manager::RemoteCatalogManager;
 use crate::error::Error;
 
 mod client;
+pub mod lease;
 mod manager;
 
 #[cfg(feature = "testing")]
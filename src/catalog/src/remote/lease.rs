@@ -0,0 +1,179 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A lease on a key in the kv backend, held by at most one node at a time.
+//!
+//! Background jobs that must not run on every frontend, like refreshing
+//! materialized views, run only on the node that holds their lease. The holder
+//! renews the lease on every round of its job; when it stops doing so, another
+//! node takes the lease over once it expires.
+//!
+//! A round may outlast the lease, so the holder renews it with [Lease::renew]
+//! right before writing the results of the round, and drops them if the lease
+//! was lost. The write is expected to finish within the ttl.
+
+use std::time::Duration;
+
+use common_time::util::current_time_millis;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{GenericSnafu, Result};
+use crate::remote::{Kv, KvBackendRef};
+
+/// Prefix of the keys of leases in the kv backend.
+const LEASE_KEY_PREFIX: &str = "__lease";
+
+/// Attempts to acquire a lease before giving up for the current round.
+const MAX_ACQUIRE_ATTEMPTS: usize = 3;
+
+pub struct Lease {
+    backend: KvBackendRef,
+    key: String,
+    /// Unique id of this node.
+    holder: String,
+    ttl: Duration,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LeaseValue {
+    holder: String,
+    expire_at_millis: i64,
+}
+
+impl Lease {
+    /// Creates the lease named `name`, held by `holder` for `ttl` after each
+    /// acquisition.
+    pub fn new(backend: KvBackendRef, name: &str, holder: String, ttl: Duration) -> Self {
+        Self {
+            backend,
+            key: format!("{LEASE_KEY_PREFIX}-{name}"),
+            holder,
+            ttl,
+        }
+    }
+
+    /// Acquires the lease if it's free or expired, or renews it if this node
+    /// holds it. Returns whether this node holds the lease afterwards.
+    pub async fn acquire(&self) -> Result<bool> {
+        let now = current_time_millis();
+        let value = self.new_value(now)?;
+
+        // The lease is assumed to be free at first, a failed compare-and-set tells
+        // the current value.
+        let mut current: Option<Vec<u8>> = None;
+        for _ in 0..MAX_ACQUIRE_ATTEMPTS {
+            if let Some(current) = &current {
+                let lease = decode_value(current)?;
+                if lease.holder != self.holder && lease.expire_at_millis > now {
+                    return Ok(false);
+                }
+            }
+            match self
+                .backend
+                .compare_and_set(
+                    self.key.as_bytes(),
+                    current.as_deref().unwrap_or_default(),
+                    &value,
+                )
+                .await?
+            {
+                Ok(()) => return Ok(true),
+                Err(value) => current = value,
+            }
+        }
+        Ok(false)
+    }
+
+    /// Renews the lease only if this node holds it and it hasn't expired, so
+    /// another node may have taken it over in between. Returns whether this node
+    /// still holds the lease.
+    pub async fn renew(&self) -> Result<bool> {
+        let Some(Kv(_, current)) = self.backend.get(self.key.as_bytes()).await? else {
+            return Ok(false);
+        };
+        let lease = decode_value(&current)?;
+        let now = current_time_millis();
+        if lease.holder != self.holder || lease.expire_at_millis <= now {
+            return Ok(false);
+        }
+        let value = self.new_value(now)?;
+        Ok(self
+            .backend
+            .compare_and_set(self.key.as_bytes(), &current, &value)
+            .await?
+            .is_ok())
+    }
+
+    fn new_value(&self, now: i64) -> Result<Vec<u8>> {
+        serde_json::to_vec(&LeaseValue {
+            holder: self.holder.clone(),
+            expire_at_millis: now + self.ttl.as_millis() as i64,
+        })
+        .map_err(|e| GenericSnafu { msg: e.to_string() }.build())
+    }
+}
+
+fn decode_value(value: &[u8]) -> Result<LeaseValue> {
+    serde_json::from_slice(value).map_err(|e| GenericSnafu { msg: e.to_string() }.build())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::remote::mock::MockKvBackend;
+
+    #[tokio::test]
+    async fn test_acquire_lease() {
+        let backend: KvBackendRef = Arc::new(MockKvBackend::default());
+        let ttl = Duration::from_millis(100);
+        let lease_a = Lease::new(backend.clone(), "job", "a".to_string(), ttl);
+        let lease_b = Lease::new(backend.clone(), "job", "b".to_string(), ttl);
+        let other = Lease::new(backend, "other_job", "b".to_string(), ttl);
+
+        assert!(lease_a.acquire().await.unwrap());
+        assert!(!lease_b.acquire().await.unwrap());
+        // leases of different names are independent
+        assert!(other.acquire().await.unwrap());
+        // the holder renews its lease
+        assert!(lease_a.acquire().await.unwrap());
+        assert!(!lease_b.acquire().await.unwrap());
+
+        // others take the lease over once it expires
+        tokio::time::sleep(ttl * 2).await;
+        assert!(lease_b.acquire().await.unwrap());
+        assert!(!lease_a.acquire().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_renew_lease() {
+        let backend: KvBackendRef = Arc::new(MockKvBackend::default());
+        let ttl = Duration::from_millis(100);
+        let lease_a = Lease::new(backend.clone(), "job", "a".to_string(), ttl);
+        let lease_b = Lease::new(backend, "job", "b".to_string(), ttl);
+
+        // a free lease is not renewed
+        assert!(!lease_a.renew().await.unwrap());
+        assert!(lease_a.acquire().await.unwrap());
+        assert!(lease_a.renew().await.unwrap());
+        assert!(!lease_b.renew().await.unwrap());
+
+        // an expired lease is not renewed even if nobody has taken it over
+        tokio::time::sleep(ttl * 2).await;
+        assert!(!lease_a.renew().await.unwrap());
+        assert!(lease_b.acquire().await.unwrap());
+        assert!(!lease_a.renew().await.unwrap());
+    }
+}
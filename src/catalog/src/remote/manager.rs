@@ -546,6 +546,7 @@ async fn open_or_create_table(
         table_name: table_name.clone(),
         table_id,
         region_numbers: region_numbers.clone(),
+        read_only: false,
     };
     let engine =
         engine_manager
@@ -32,7 +32,7 @@ use serde::{Deserialize, Serialize};
 use snafu::{ensure, OptionExt, ResultExt};
 use store_api::storage::ScanRequest;
 use table::engine::{EngineContext, TableEngineRef};
-use table::metadata::{TableId, TableInfoRef};
+use table::metadata::{RawTableInfo, TableId, TableInfoRef};
 use table::requests::{
     CreateTableRequest, DeleteRequest, InsertRequest, OpenTableRequest, TableOptions,
 };
@@ -99,6 +99,7 @@ impl SystemCatalogTable {
             table_name: SYSTEM_CATALOG_TABLE_NAME.to_string(),
             table_id: SYSTEM_CATALOG_TABLE_ID,
             region_numbers: vec![0],
+            read_only: false,
         };
         let schema = build_system_catalog_schema();
         let ctx = EngineContext::default();
@@ -217,6 +218,35 @@ pub fn build_table_insert_request(
     )
 }
 
+/// Formats key string for view entry in system catalog. Views have no table ids
+/// so they are keyed by names.
+#[inline]
+pub fn format_view_entry_key(catalog: &str, schema: &str, view_name: &str) -> String {
+    format!("{catalog}.{schema}.{view_name}")
+}
+
+pub fn build_view_insert_request(table_info: RawTableInfo) -> InsertRequest {
+    let entry_key = format_view_entry_key(
+        &table_info.catalog_name,
+        &table_info.schema_name,
+        &table_info.name,
+    );
+    build_insert_request(
+        EntryType::View,
+        entry_key.as_bytes(),
+        serde_json::to_string(&ViewEntryValue { table_info })
+            .unwrap()
+            .as_bytes(),
+    )
+}
+
+pub(crate) fn build_view_deletion_request(request: &DeregisterTableRequest) -> DeleteRequest {
+    let view_key = format_view_entry_key(&request.catalog, &request.schema, &request.table_name);
+    DeleteRequest {
+        key_column_values: build_primary_key_columns(EntryType::View, view_key.as_bytes()),
+    }
+}
+
 pub(crate) fn build_table_deletion_request(
     request: &DeregisterTableRequest,
     table_id: TableId,
@@ -345,6 +375,27 @@ pub fn decode_system_catalog(
                 engine: table_meta.engine,
             }))
         }
+
+        EntryType::View => {
+            // As for view entry, the key is a string with format: `<catalog_name>.<schema_name>.<view_name>`
+            // and the value is a JSON string of the view's table info, which contains its definition.
+            let view_parts = key.splitn(3, '.').collect::<Vec<_>>();
+            ensure!(
+                view_parts.len() == 3,
+                InvalidKeySnafu {
+                    key: Some(key.to_string())
+                }
+            );
+            let value = value.context(EmptyValueSnafu)?;
+            let view_meta: ViewEntryValue =
+                serde_json::from_slice(value).context(ValueDeserializeSnafu)?;
+            Ok(Entry::View(ViewEntry {
+                catalog_name: view_parts[0].to_string(),
+                schema_name: view_parts[1].to_string(),
+                view_name: view_parts[2].to_string(),
+                table_info: view_meta.table_info,
+            }))
+        }
     }
 }
 
@@ -353,6 +404,7 @@ pub enum EntryType {
     Catalog = 1,
     Schema = 2,
     Table = 3,
+    View = 4,
 }
 
 impl TryFrom<u8> for EntryType {
@@ -363,6 +415,7 @@ impl TryFrom<u8> for EntryType {
             b if b == Self::Catalog as u8 => Ok(Self::Catalog),
             b if b == Self::Schema as u8 => Ok(Self::Schema),
             b if b == Self::Table as u8 => Ok(Self::Table),
+            b if b == Self::View as u8 => Ok(Self::View),
             b => InvalidEntryTypeSnafu {
                 entry_type: Some(b),
             }
@@ -376,6 +429,7 @@ pub enum Entry {
     Catalog(CatalogEntry),
     Schema(SchemaEntry),
     Table(TableEntry),
+    View(ViewEntry),
 }
 
 #[derive(Debug, PartialEq, Eq, Ord, PartialOrd)]
@@ -409,6 +463,35 @@ pub struct TableEntryValue {
     pub engine: String,
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub struct ViewEntry {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub view_name: String,
+    pub table_info: RawTableInfo,
+}
+
+impl PartialOrd for ViewEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ViewEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (&self.catalog_name, &self.schema_name, &self.view_name).cmp(&(
+            &other.catalog_name,
+            &other.schema_name,
+            &other.view_name,
+        ))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ViewEntryValue {
+    pub table_info: RawTableInfo,
+}
+
 fn mito_engine() -> String {
     MITO_ENGINE.to_string()
 }
@@ -427,6 +510,7 @@ mod tests {
     use storage::EngineImpl;
     use table::metadata::TableType;
     use table::metadata::TableType::Base;
+    use table::table::view::ViewTable;
 
     use super::*;
 
@@ -481,6 +565,41 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_decode_view() {
+        let schema = Arc::new(datatypes::schema::Schema::new(vec![ColumnSchema::new(
+            "number",
+            ConcreteDataType::uint32_datatype(),
+            false,
+        )]));
+        let table_info = RawTableInfo::from(ViewTable::build_table_info(
+            "some_catalog",
+            "some_schema",
+            "some_view",
+            "SELECT number FROM numbers".to_string(),
+            schema,
+        ));
+        let value = serde_json::to_string(&ViewEntryValue {
+            table_info: table_info.clone(),
+        })
+        .unwrap();
+        let entry = decode_system_catalog(
+            Some(EntryType::View as u8),
+            Some(format_view_entry_key("some_catalog", "some_schema", "some_view").as_bytes()),
+            Some(value.as_bytes()),
+        )
+        .unwrap();
+        assert_eq!(
+            Entry::View(ViewEntry {
+                catalog_name: "some_catalog".to_string(),
+                schema_name: "some_schema".to_string(),
+                view_name: "some_view".to_string(),
+                table_info,
+            }),
+            entry
+        );
+    }
+
     #[test]
     pub fn test_decode_mismatch() {
         assert!(decode_system_catalog(
@@ -496,7 +615,8 @@ mod tests {
         assert_eq!(EntryType::Catalog, EntryType::try_from(1).unwrap());
         assert_eq!(EntryType::Schema, EntryType::try_from(2).unwrap());
         assert_eq!(EntryType::Table, EntryType::try_from(3).unwrap());
-        assert!(EntryType::try_from(4).is_err());
+        assert_eq!(EntryType::View, EntryType::try_from(4).unwrap());
+        assert!(EntryType::try_from(5).is_err());
     }
 
     pub async fn prepare_table_engine() -> (TempDir, TableEngineRef) {
@@ -17,22 +17,52 @@ use std::sync::Arc;
 
 use common_catalog::consts::INFORMATION_SCHEMA_NAME;
 use common_catalog::format_full_table_name;
+use common_error::prelude::BoxedError;
 use datafusion::common::{ResolvedTableReference, TableReference};
-use datafusion::datasource::provider_as_source;
-use datafusion::logical_expr::TableSource;
-use session::context::QueryContext;
-use snafu::{ensure, OptionExt};
+use datafusion::datasource::{provider_as_source, ViewTable};
+use datafusion::logical_expr::{LogicalPlan, TableSource};
+use session::context::{QueryContext, ReadPreference, UserInfo};
+use snafu::{ensure, OptionExt, ResultExt};
+use table::metadata::TableType;
 use table::table::adapter::DfTableProviderAdapter;
+use table::table::view::VIEW_DEFINITION_KEY;
+use table::TableRef;
 
-use crate::error::{QueryAccessDeniedSnafu, Result, TableNotExistSnafu};
+use crate::error::{
+    CyclicViewSnafu, DatafusionSnafu, NotSupportedSnafu, PlanViewSnafu, QueryAccessDeniedSnafu,
+    Result, TableNotExistSnafu,
+};
+use crate::information_schema::InformationTable;
 use crate::CatalogManagerRef;
 
+/// Plans the query that defines a view, which the view is expanded into when
+/// it is queried.
+#[async_trait::async_trait]
+pub trait ViewPlanner: Send + Sync {
+    /// Plans `definition` with tables resolved by `table_provider`.
+    async fn plan_view(
+        &self,
+        definition: &str,
+        table_provider: DfTableSourceProvider,
+    ) -> std::result::Result<LogicalPlan, BoxedError>;
+}
+
+pub type ViewPlannerRef = Arc<dyn ViewPlanner>;
+
 pub struct DfTableSourceProvider {
     catalog_manager: CatalogManagerRef,
     resolved_tables: HashMap<String, Arc<dyn TableSource>>,
     disallow_cross_schema_query: bool,
     default_catalog: String,
     default_schema: String,
+    view_planner: Option<ViewPlannerRef>,
+    read_preference: ReadPreference,
+    /// The user issuing the query, who may only see some rows of the
+    /// information schema tables.
+    user_info: Arc<UserInfo>,
+    /// Views being expanded by the outer providers, used to detect views
+    /// that reference themselves.
+    expanding_views: Vec<String>,
 }
 
 impl DfTableSourceProvider {
@@ -47,9 +77,19 @@ impl DfTableSourceProvider {
             resolved_tables: HashMap::new(),
             default_catalog: query_ctx.current_catalog(),
             default_schema: query_ctx.current_schema(),
+            view_planner: None,
+            read_preference: query_ctx.read_preference(),
+            user_info: query_ctx.current_user(),
+            expanding_views: vec![],
         }
     }
 
+    /// Sets the planner to expand views with. Views can't be queried without it.
+    pub fn with_view_planner(mut self, view_planner: ViewPlannerRef) -> Self {
+        self.view_planner = Some(view_planner);
+        self
+    }
+
     pub fn resolve_table_ref<'a>(
         &'a self,
         table_ref: TableReference<'a>,
@@ -108,22 +148,161 @@ impl DfTableSourceProvider {
             .with_context(|| TableNotExistSnafu {
                 table: format_full_table_name(catalog_name, schema_name, table_name),
             })?;
+        let table = table
+            .as_any()
+            .downcast_ref::<InformationTable>()
+            .and_then(|table| table.for_user(&self.user_info))
+            .unwrap_or(table);
 
-        let provider = DfTableProviderAdapter::new(table);
-        let source = provider_as_source(Arc::new(provider));
+        let source = if table.table_type() == TableType::View {
+            self.expand_view(table, &resolved_name).await?
+        } else {
+            let table = match self.read_preference {
+                ReadPreference::Follower => table.with_follower_reads().unwrap_or(table),
+                ReadPreference::Leader => table,
+            };
+            let provider = DfTableProviderAdapter::new(table);
+            provider_as_source(Arc::new(provider))
+        };
         let _ = self.resolved_tables.insert(resolved_name, source.clone());
         Ok(source)
     }
+
+    async fn expand_view(&self, view: TableRef, view_name: &str) -> Result<Arc<dyn TableSource>> {
+        ensure!(
+            !self.expanding_views.iter().any(|name| name == view_name),
+            CyclicViewSnafu { view: view_name }
+        );
+        let view_planner = self
+            .view_planner
+            .clone()
+            .with_context(|| NotSupportedSnafu {
+                op: format!("query view {view_name}"),
+            })?;
+
+        let view_info = view.table_info();
+        let definition = view_info
+            .meta
+            .options
+            .extra_options
+            .get(VIEW_DEFINITION_KEY)
+            .cloned()
+            .unwrap_or_default();
+
+        // Tables in the definition are resolved against the view's own catalog and schema.
+        let mut expanding_views = self.expanding_views.clone();
+        expanding_views.push(view_name.to_string());
+        let table_provider = DfTableSourceProvider {
+            catalog_manager: self.catalog_manager.clone(),
+            resolved_tables: HashMap::new(),
+            disallow_cross_schema_query: self.disallow_cross_schema_query,
+            default_catalog: view_info.catalog_name.clone(),
+            default_schema: view_info.schema_name.clone(),
+            view_planner: Some(view_planner.clone()),
+            read_preference: self.read_preference,
+            user_info: self.user_info.clone(),
+            expanding_views,
+        };
+
+        let plan = view_planner
+            .plan_view(&definition, table_provider)
+            .await
+            .context(PlanViewSnafu { view: view_name })?;
+        let view = ViewTable::try_new(plan, Some(definition)).context(DatafusionSnafu {
+            msg: "Failed to create view",
+        })?;
+        Ok(provider_as_source(Arc::new(view)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::borrow::Cow;
 
+    use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+    use datafusion::logical_expr::LogicalPlanBuilder;
+    use datatypes::schema::Schema;
     use session::context::QueryContext;
+    use table::table::view::ViewTable as GreptimeViewTable;
 
     use super::*;
     use crate::local::MemoryCatalogManager;
+    use crate::RegisterTableRequest;
+
+    /// Takes the definition as the name of the only table the view scans.
+    struct ScanTablePlanner;
+
+    #[async_trait::async_trait]
+    impl ViewPlanner for ScanTablePlanner {
+        async fn plan_view(
+            &self,
+            definition: &str,
+            mut table_provider: DfTableSourceProvider,
+        ) -> std::result::Result<LogicalPlan, BoxedError> {
+            let source = table_provider
+                .resolve_table(TableReference::bare(definition))
+                .await
+                .map_err(BoxedError::new)?;
+            Ok(LogicalPlanBuilder::scan(definition, source, None)
+                .unwrap()
+                .build()
+                .unwrap())
+        }
+    }
+
+    fn register_view(catalog_manager: &MemoryCatalogManager, name: &str, definition: &str) {
+        let table_info = GreptimeViewTable::build_table_info(
+            DEFAULT_CATALOG_NAME,
+            DEFAULT_SCHEMA_NAME,
+            name,
+            definition.to_string(),
+            Arc::new(Schema::new(vec![])),
+        );
+        let _ = catalog_manager
+            .register_table_sync(RegisterTableRequest {
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name: name.to_string(),
+                table_id: 0,
+                table: Arc::new(GreptimeViewTable::new(Arc::new(table_info))),
+            })
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resolve_view() {
+        let catalog_manager = MemoryCatalogManager::new_with_table(Arc::new(
+            table::table::numbers::NumbersTable::default(),
+        ));
+        register_view(&catalog_manager, "v1", "numbers");
+        register_view(&catalog_manager, "v2", "v1");
+        register_view(&catalog_manager, "cyclic1", "cyclic2");
+        register_view(&catalog_manager, "cyclic2", "cyclic1");
+        let catalog_manager = Arc::new(catalog_manager);
+        let query_ctx = QueryContext::with(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME);
+
+        let mut table_provider =
+            DfTableSourceProvider::new(catalog_manager.clone(), false, &query_ctx);
+        // Views can't be expanded without a planner.
+        assert!(table_provider
+            .resolve_table(TableReference::bare("v1"))
+            .await
+            .is_err());
+
+        let mut table_provider = DfTableSourceProvider::new(catalog_manager, false, &query_ctx)
+            .with_view_planner(Arc::new(ScanTablePlanner));
+        let source = table_provider
+            .resolve_table(TableReference::bare("v2"))
+            .await
+            .unwrap();
+        assert!(source.get_logical_plan().is_some());
+
+        let err = table_provider
+            .resolve_table(TableReference::bare("cyclic1"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("references itself"), "{err}");
+    }
 
     #[test]
     fn test_validate_table_ref() {
@@ -18,13 +18,13 @@ use std::sync::Arc;
 
 use common_telemetry::logging;
 use snafu::ResultExt;
-use table::metadata::TableId;
+use table::metadata::{RawTableInfo, TableId};
 use table::Table;
 
 use crate::error::{self, InsertCatalogRecordSnafu, Result as CatalogResult};
 use crate::system::{
     build_schema_insert_request, build_table_deletion_request, build_table_insert_request,
-    SystemCatalogTable,
+    build_view_deletion_request, build_view_insert_request, SystemCatalogTable,
 };
 use crate::DeregisterTableRequest;
 
@@ -86,6 +86,29 @@ impl SystemCatalog {
             })
     }
 
+    pub async fn register_view(&self, table_info: RawTableInfo) -> crate::error::Result<usize> {
+        let request = build_view_insert_request(table_info);
+        self.information_schema
+            .system
+            .insert(request)
+            .await
+            .context(InsertCatalogRecordSnafu)
+    }
+
+    pub(crate) async fn deregister_view(
+        &self,
+        request: &DeregisterTableRequest,
+    ) -> CatalogResult<()> {
+        self.information_schema
+            .system
+            .delete(build_view_deletion_request(request))
+            .await
+            .map(|_| ())
+            .with_context(|_| error::DeregisterTableSnafu {
+                request: request.clone(),
+            })
+    }
+
     pub async fn register_schema(
         &self,
         catalog: String,
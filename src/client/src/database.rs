@@ -24,6 +24,7 @@ use api::v1::{
 use arrow_flight::{FlightData, Ticket};
 use common_error::prelude::*;
 use common_grpc::flight::{flight_messages_to_recordbatches, FlightDecoder, FlightMessage};
+use common_grpc::tracing_context::traced_request;
 use common_query::Output;
 use common_telemetry::{logging, timer};
 use futures_util::{TryFutureExt, TryStreamExt};
@@ -143,7 +144,7 @@ impl Database {
         let mut client = self.client.make_database_client()?.inner;
         let request = self.to_rpc_request(request);
         let response = client
-            .handle(request)
+            .handle(traced_request(request))
             .await?
             .into_inner()
             .response
@@ -246,7 +247,7 @@ impl Database {
 
         let flight_data: Vec<FlightData> = client
             .mut_inner()
-            .do_get(request)
+            .do_get(traced_request(request))
             .and_then(|response| response.into_inner().try_collect())
             .await
             .map_err(|e| {
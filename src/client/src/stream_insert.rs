@@ -18,6 +18,7 @@ use api::v1::{
     greptime_response, AffectedRows, AuthHeader, GreptimeRequest, GreptimeResponse, InsertRequest,
     InsertRequests, RequestHeader,
 };
+use common_grpc::tracing_context::traced_request;
 use snafu::OptionExt;
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
@@ -58,12 +59,10 @@ impl StreamInserter {
         channel_size: usize,
     ) -> StreamInserter {
         let (send, recv) = tokio::sync::mpsc::channel(channel_size);
+        let request = traced_request(ReceiverStream::new(recv));
 
         let join: JoinHandle<std::result::Result<Response<GreptimeResponse>, Status>> =
-            tokio::spawn(async move {
-                let recv_stream = ReceiverStream::new(recv);
-                client.handle_requests(recv_stream).await
-            });
+            tokio::spawn(async move { client.handle_requests(request).await });
 
         StreamInserter {
             sender: send,
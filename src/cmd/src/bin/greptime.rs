@@ -19,7 +19,7 @@ use std::fmt;
 use clap::Parser;
 use cmd::error::Result;
 use cmd::options::{Options, TopLevelOptions};
-use cmd::{cli, datanode, frontend, metasrv, standalone};
+use cmd::{cli, datanode, frontend, metasrv, standalone, tool};
 use common_telemetry::logging::{error, info, TracingOptions};
 use metrics::gauge;
 
@@ -44,6 +44,7 @@ pub enum Application {
     Metasrv(metasrv::Instance),
     Standalone(standalone::Instance),
     Cli(cli::Instance),
+    Tool(tool::Instance),
 }
 
 impl Application {
@@ -54,6 +55,7 @@ impl Application {
             Application::Metasrv(instance) => instance.start().await,
             Application::Standalone(instance) => instance.start().await,
             Application::Cli(instance) => instance.start().await,
+            Application::Tool(instance) => instance.start().await,
         }
     }
 
@@ -64,6 +66,7 @@ impl Application {
             Application::Metasrv(instance) => instance.stop().await,
             Application::Standalone(instance) => instance.stop().await,
             Application::Cli(instance) => instance.stop().await,
+            Application::Tool(instance) => instance.stop().await,
         }
     }
 }
@@ -98,6 +101,8 @@ enum SubCommand {
     Standalone(standalone::Command),
     #[clap(name = "cli")]
     Cli(cli::Command),
+    #[clap(name = "tool")]
+    Tool(tool::Command),
 }
 
 impl SubCommand {
@@ -123,6 +128,10 @@ impl SubCommand {
                 let app = cmd.build().await?;
                 Ok(Application::Cli(app))
             }
+            (SubCommand::Tool(cmd), Options::Cli(_)) => {
+                let app = cmd.build().await?;
+                Ok(Application::Tool(app))
+            }
 
             _ => unreachable!(),
         }
@@ -135,6 +144,7 @@ impl SubCommand {
             SubCommand::Metasrv(cmd) => cmd.load_options(top_level_opts),
             SubCommand::Standalone(cmd) => cmd.load_options(top_level_opts),
             SubCommand::Cli(cmd) => cmd.load_options(top_level_opts),
+            SubCommand::Tool(cmd) => cmd.load_options(top_level_opts),
         }
     }
 }
@@ -147,6 +157,7 @@ impl fmt::Display for SubCommand {
             SubCommand::Metasrv(..) => write!(f, "greptime-metasrv"),
             SubCommand::Standalone(..) => write!(f, "greptime-standalone"),
             SubCommand::Cli(_) => write!(f, "greptime-cli"),
+            SubCommand::Tool(_) => write!(f, "greptime-tool"),
         }
     }
 }
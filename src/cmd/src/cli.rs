@@ -13,23 +13,35 @@
 // limitations under the License.
 
 mod cmd;
+mod export;
 mod helper;
+mod import;
 mod repl;
+mod util;
 
+use async_trait::async_trait;
 use clap::Parser;
 use common_telemetry::logging::LoggingOptions;
 pub use repl::Repl;
 
+use crate::cli::export::ExportCommand;
+use crate::cli::import::ImportCommand;
 use crate::error::Result;
 use crate::options::{Options, TopLevelOptions};
 
+/// A tool run by the `cli` subcommand.
+#[async_trait]
+pub trait Tool {
+    async fn do_work(&mut self) -> Result<()>;
+}
+
 pub struct Instance {
-    repl: Repl,
+    tool: Box<dyn Tool>,
 }
 
 impl Instance {
     pub async fn start(&mut self) -> Result<()> {
-        self.repl.run().await
+        self.tool.do_work().await
     }
 
     pub async fn stop(&self) -> Result<()> {
@@ -63,12 +75,16 @@ impl Command {
 #[derive(Parser)]
 enum SubCommand {
     Attach(AttachCommand),
+    Export(ExportCommand),
+    Import(ImportCommand),
 }
 
 impl SubCommand {
     async fn build(self) -> Result<Instance> {
         match self {
             SubCommand::Attach(cmd) => cmd.build().await,
+            SubCommand::Export(cmd) => cmd.build().await,
+            SubCommand::Import(cmd) => cmd.build().await,
         }
     }
 }
@@ -86,7 +102,9 @@ pub(crate) struct AttachCommand {
 impl AttachCommand {
     async fn build(self) -> Result<Instance> {
         let repl = Repl::try_new(&self).await?;
-        Ok(Instance { repl })
+        Ok(Instance {
+            tool: Box::new(repl),
+        })
     }
 }
 
@@ -0,0 +1,226 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use clap::Parser;
+use client::{Client, Database, DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_catalog::consts::INFORMATION_SCHEMA_NAME;
+use common_datasource::object_store::{build_backend, parse_url};
+use common_telemetry::logging;
+use futures::StreamExt;
+use object_store::ObjectStore;
+use snafu::ResultExt;
+
+use crate::cli::util::{
+    execute_sql, format_options, normalize_dir, parse_options, quote_ident, quote_string,
+    string_values, CREATE_TABLES_FILE, STATEMENT_SEPARATOR,
+};
+use crate::cli::{Instance, Tool};
+use crate::error::{BuildBackendSnafu, Result, WriteObjectSnafu};
+
+/// Exports `CREATE TABLE` statements and data of databases to a directory.
+#[derive(Debug, Default, Parser)]
+pub struct ExportCommand {
+    /// Address of the gRPC server to connect, e.g. 127.0.0.1:4001.
+    #[clap(long)]
+    addr: String,
+
+    /// Directory to export to, a local path or an S3 url such as `s3://bucket/backup/`.
+    /// Data files are written by the server, so a local directory must be
+    /// accessible from the server.
+    #[clap(long)]
+    output_dir: String,
+
+    /// Database to export, all databases are exported if not specified.
+    #[clap(long)]
+    database: Option<String>,
+
+    /// Number of databases exported in parallel.
+    #[clap(long, default_value = "1")]
+    parallelism: usize,
+
+    /// Start of the time range of exported data (inclusive), e.g. `2023-01-01 00:00:00`.
+    #[clap(long)]
+    start_time: Option<String>,
+
+    /// End of the time range of exported data (exclusive), e.g. `2023-02-01 00:00:00`.
+    #[clap(long)]
+    end_time: Option<String>,
+
+    /// Format of exported data files.
+    #[clap(long, default_value = "parquet")]
+    format: String,
+
+    /// Connection options of the output directory in the form of `KEY=VALUE`,
+    /// e.g. `ACCESS_KEY_ID=...`. Can be specified multiple times.
+    #[clap(long)]
+    connection: Vec<String>,
+}
+
+impl ExportCommand {
+    pub async fn build(&self) -> Result<Instance> {
+        let connection = parse_options(&self.connection)?;
+        let output_dir = normalize_dir(&self.output_dir);
+        let object_store = build_backend(&output_dir, &connection)
+            .context(BuildBackendSnafu { url: &output_dir })?;
+
+        let mut with = HashMap::from([("format".to_string(), self.format.clone())]);
+        if let Some(start_time) = &self.start_time {
+            let _ = with.insert("start_time".to_string(), start_time.clone());
+        }
+        if let Some(end_time) = &self.end_time {
+            let _ = with.insert("end_time".to_string(), end_time.clone());
+        }
+
+        Ok(Instance {
+            tool: Box::new(Export {
+                client: Client::with_urls([&self.addr]),
+                database: self.database.clone(),
+                output_dir,
+                parallelism: self.parallelism.max(1),
+                with,
+                connection,
+                object_store,
+            }),
+        })
+    }
+}
+
+pub struct Export {
+    client: Client,
+    database: Option<String>,
+    output_dir: String,
+    parallelism: usize,
+    /// Options of `COPY DATABASE`.
+    with: HashMap<String, String>,
+    connection: HashMap<String, String>,
+    object_store: ObjectStore,
+}
+
+impl Export {
+    async fn databases(&self) -> Result<Vec<String>> {
+        if let Some(database) = &self.database {
+            return Ok(vec![database.clone()]);
+        }
+        let database = Database::new(
+            DEFAULT_CATALOG_NAME,
+            DEFAULT_SCHEMA_NAME,
+            self.client.clone(),
+        );
+        let batches = execute_sql(&database, "SHOW DATABASES").await?;
+        Ok(string_values(&batches, 0)
+            .into_iter()
+            .filter(|name| name != INFORMATION_SCHEMA_NAME)
+            .collect())
+    }
+
+    async fn export_database(&self, name: &str) -> Result<()> {
+        let database = Database::new(DEFAULT_CATALOG_NAME, name, self.client.clone());
+        let dir = format!("{}{}/", self.output_dir, name);
+
+        let mut statements = vec![];
+        for table in string_values(&execute_sql(&database, "SHOW TABLES").await?, 0) {
+            // Like `COPY DATABASE`, skips the numbers table.
+            if table == "numbers" {
+                continue;
+            }
+            let sql = format!("SHOW CREATE TABLE {}", quote_ident(&table));
+            let batches = execute_sql(&database, &sql).await?;
+            statements.extend(string_values(&batches, 1));
+        }
+        // Views are created after the tables they select from.
+        statements.sort_by_key(|statement| is_create_view(statement));
+        let mut create_tables = statements.join(STATEMENT_SEPARATOR);
+        create_tables.push_str(STATEMENT_SEPARATOR);
+
+        let (_, _, path) = parse_url(&dir).context(BuildBackendSnafu { url: &dir })?;
+        let path = format!("{path}{CREATE_TABLES_FILE}");
+        self.object_store
+            .write(&path, create_tables)
+            .await
+            .context(WriteObjectSnafu { path: &path })?;
+        logging::info!(
+            "Exported {} CREATE TABLE statements of database {} to {}",
+            statements.len(),
+            name,
+            path
+        );
+
+        let sql = format!(
+            "COPY DATABASE {} TO {} WITH ({}) CONNECTION ({})",
+            quote_ident(name),
+            quote_string(&dir),
+            format_options(&self.with),
+            format_options(&self.connection)
+        );
+        let _ = execute_sql(&database, &sql).await?;
+        logging::info!("Exported data of database {} to {}", name, dir);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Tool for Export {
+    async fn do_work(&mut self) -> Result<()> {
+        let this = &*self;
+        let databases = this.databases().await?;
+        let results = futures::stream::iter(databases.iter())
+            .map(|database| async move {
+                let result = this.export_database(database).await;
+                if let Err(e) = &result {
+                    logging::error!(e; "Failed to export database {}", database);
+                }
+                result
+            })
+            .buffer_unordered(this.parallelism)
+            .collect::<Vec<_>>()
+            .await;
+
+        results.into_iter().collect::<Result<Vec<_>>>().map(|_| ())
+    }
+}
+
+fn is_create_view(statement: &str) -> bool {
+    statement
+        .trim_start()
+        .get(.."CREATE VIEW".len())
+        .map_or(false, |prefix| prefix.eq_ignore_ascii_case("CREATE VIEW"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_views_after_tables() {
+        let mut statements = vec![
+            "CREATE VIEW v AS SELECT * FROM t".to_string(),
+            "CREATE TABLE t (ts TIMESTAMP TIME INDEX)".to_string(),
+            "create view w AS SELECT * FROM v".to_string(),
+            "CREATE EXTERNAL TABLE e WITH (location='/tmp/e.csv', format='csv')".to_string(),
+        ];
+        statements.sort_by_key(|statement| is_create_view(statement));
+        assert_eq!(
+            vec![
+                "CREATE TABLE t (ts TIMESTAMP TIME INDEX)",
+                "CREATE EXTERNAL TABLE e WITH (location='/tmp/e.csv', format='csv')",
+                "CREATE VIEW v AS SELECT * FROM t",
+                "create view w AS SELECT * FROM v",
+            ],
+            statements
+        );
+    }
+}
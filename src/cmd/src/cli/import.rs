@@ -0,0 +1,214 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use clap::Parser;
+use client::{Client, Database, DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_datasource::file_format::Format;
+use common_datasource::lister::{Lister, Source};
+use common_datasource::object_store::{build_backend, parse_url};
+use common_telemetry::logging;
+use futures::StreamExt;
+use object_store::{EntryMode, ObjectStore};
+use snafu::ResultExt;
+
+use crate::cli::util::{
+    execute_sql, format_options, normalize_dir, parse_options, quote_ident, quote_string,
+    CREATE_TABLES_FILE, STATEMENT_SEPARATOR,
+};
+use crate::cli::{Instance, Tool};
+use crate::error::{
+    BuildBackendSnafu, ListObjectsSnafu, ParseFormatSnafu, ReadObjectSnafu, Result,
+};
+
+/// Imports databases exported by the `export` subcommand.
+#[derive(Debug, Default, Parser)]
+pub struct ImportCommand {
+    /// Address of the gRPC server to connect, e.g. 127.0.0.1:4001.
+    #[clap(long)]
+    addr: String,
+
+    /// Directory to import from, a local path or an S3 url such as `s3://bucket/backup/`.
+    /// Data files are read by the server, so a local directory must be
+    /// accessible from the server.
+    #[clap(long)]
+    input_dir: String,
+
+    /// Database to import, all databases in the input directory are imported
+    /// if not specified.
+    #[clap(long)]
+    database: Option<String>,
+
+    /// Number of databases imported in parallel.
+    #[clap(long, default_value = "1")]
+    parallelism: usize,
+
+    /// Format of exported data files.
+    #[clap(long, default_value = "parquet")]
+    format: String,
+
+    /// Connection options of the input directory in the form of `KEY=VALUE`,
+    /// e.g. `ACCESS_KEY_ID=...`. Can be specified multiple times.
+    #[clap(long)]
+    connection: Vec<String>,
+}
+
+impl ImportCommand {
+    pub async fn build(&self) -> Result<Instance> {
+        let connection = parse_options(&self.connection)?;
+        let input_dir = normalize_dir(&self.input_dir);
+        let object_store = build_backend(&input_dir, &connection)
+            .context(BuildBackendSnafu { url: &input_dir })?;
+
+        let with = HashMap::from([("format".to_string(), self.format.clone())]);
+        let suffix = Format::try_from(&with).context(ParseFormatSnafu)?.suffix();
+
+        Ok(Instance {
+            tool: Box::new(Import {
+                client: Client::with_urls([&self.addr]),
+                database: self.database.clone(),
+                input_dir,
+                parallelism: self.parallelism.max(1),
+                with,
+                suffix,
+                connection,
+                object_store,
+            }),
+        })
+    }
+}
+
+pub struct Import {
+    client: Client,
+    database: Option<String>,
+    input_dir: String,
+    parallelism: usize,
+    /// Options of `COPY FROM`.
+    with: HashMap<String, String>,
+    /// Suffix of data files.
+    suffix: &'static str,
+    connection: HashMap<String, String>,
+    object_store: ObjectStore,
+}
+
+impl Import {
+    /// Returns the path of `dir` in the object store.
+    fn store_path(&self, dir: &str) -> Result<String> {
+        let (_, _, path) = parse_url(dir).context(BuildBackendSnafu { url: dir })?;
+        Ok(path)
+    }
+
+    async fn list(&self, dir: &str) -> Result<Vec<object_store::Entry>> {
+        let path = self.store_path(dir)?;
+        Lister::new(self.object_store.clone(), Source::Dir, path.clone(), None)
+            .list()
+            .await
+            .context(ListObjectsSnafu { path })
+    }
+
+    async fn databases(&self) -> Result<Vec<String>> {
+        if let Some(database) = &self.database {
+            return Ok(vec![database.clone()]);
+        }
+        let mut databases = vec![];
+        for entry in self.list(&self.input_dir).await? {
+            let mode = self
+                .object_store
+                .stat(entry.path())
+                .await
+                .context(ReadObjectSnafu { path: entry.path() })?
+                .mode();
+            if mode == EntryMode::DIR {
+                databases.push(entry.name().trim_end_matches('/').to_string());
+            }
+        }
+        Ok(databases)
+    }
+
+    async fn import_database(&self, name: &str) -> Result<()> {
+        let default_database = Database::new(
+            DEFAULT_CATALOG_NAME,
+            DEFAULT_SCHEMA_NAME,
+            self.client.clone(),
+        );
+        let _ = execute_sql(
+            &default_database,
+            &format!("CREATE DATABASE IF NOT EXISTS {}", quote_ident(name)),
+        )
+        .await?;
+
+        let database = Database::new(DEFAULT_CATALOG_NAME, name, self.client.clone());
+        let dir = format!("{}{}/", self.input_dir, name);
+
+        let path = format!("{}{}", self.store_path(&dir)?, CREATE_TABLES_FILE);
+        let content = self
+            .object_store
+            .read(&path)
+            .await
+            .context(ReadObjectSnafu { path: &path })?;
+        let content = String::from_utf8_lossy(&content);
+        for statement in content.split(STATEMENT_SEPARATOR) {
+            let statement = statement.trim();
+            if !statement.is_empty() {
+                let _ = execute_sql(&database, statement).await?;
+            }
+        }
+
+        let mut tables = 0;
+        for entry in self.list(&dir).await? {
+            let Some(table) = entry.name().strip_suffix(self.suffix) else {
+                continue;
+            };
+            let sql = format!(
+                "COPY {} FROM {} WITH ({}) CONNECTION ({})",
+                quote_ident(table),
+                quote_string(&format!("{dir}{}", entry.name())),
+                format_options(&self.with),
+                format_options(&self.connection)
+            );
+            let _ = execute_sql(&database, &sql).await?;
+            tables += 1;
+        }
+        logging::info!(
+            "Imported {} tables of database {} from {}",
+            tables,
+            name,
+            dir
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Tool for Import {
+    async fn do_work(&mut self) -> Result<()> {
+        let this = &*self;
+        let databases = this.databases().await?;
+        let results = futures::stream::iter(databases.iter())
+            .map(|database| async move {
+                let result = this.import_database(database).await;
+                if let Err(e) = &result {
+                    logging::error!(e; "Failed to import database {}", database);
+                }
+                result
+            })
+            .buffer_unordered(this.parallelism)
+            .collect::<Vec<_>>()
+            .await;
+
+        results.into_iter().collect::<Result<Vec<_>>>().map(|_| ())
+    }
+}
@@ -16,6 +16,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
 
+use async_trait::async_trait;
 use catalog::remote::CachedMetaKvBackend;
 use client::client_manager::DatanodeClients;
 use client::{Client, Database, DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
@@ -43,7 +44,7 @@ use substrait::{DFLogicalSubstraitConvertor, SubstraitPlan};
 
 use crate::cli::cmd::ReplCommand;
 use crate::cli::helper::RustylineHelper;
-use crate::cli::AttachCommand;
+use crate::cli::{AttachCommand, Tool};
 use crate::error::{
     CollectRecordBatchesSnafu, ParseSqlSnafu, PlanStatementSnafu, PrettyPrintRecordBatchesSnafu,
     ReadlineSnafu, ReplCreationSnafu, RequestDatabaseSnafu, Result, StartMetaClientSnafu,
@@ -222,6 +223,13 @@ impl Repl {
     }
 }
 
+#[async_trait]
+impl Tool for Repl {
+    async fn do_work(&mut self) -> Result<()> {
+        self.run().await
+    }
+}
+
 impl Drop for Repl {
     fn drop(&mut self) {
         if self.rl.helper().is_some() {
@@ -0,0 +1,138 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+
+use client::Database;
+use common_query::Output;
+use common_recordbatch::{RecordBatch, RecordBatches};
+use datatypes::value::Value;
+use snafu::{OptionExt, ResultExt};
+
+use crate::error::{CollectRecordBatchesSnafu, IllegalConfigSnafu, RequestDatabaseSnafu, Result};
+
+/// File of the `CREATE TABLE` statements of a database in the export dir.
+pub(crate) const CREATE_TABLES_FILE: &str = "create_tables.sql";
+
+/// Separates statements in [CREATE_TABLES_FILE].
+pub(crate) const STATEMENT_SEPARATOR: &str = ";\n\n";
+
+/// Parses options in the form of `KEY=VALUE`.
+pub(crate) fn parse_options(options: &[String]) -> Result<HashMap<String, String>> {
+    options
+        .iter()
+        .map(|option| {
+            option
+                .split_once('=')
+                .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+                .with_context(|| IllegalConfigSnafu {
+                    msg: format!("invalid option {option}, expect KEY=VALUE"),
+                })
+        })
+        .collect()
+}
+
+/// Formats options to the body of `WITH` or `CONNECTION` clauses.
+pub(crate) fn format_options(options: &HashMap<String, String>) -> String {
+    options
+        .iter()
+        .collect::<BTreeMap<_, _>>()
+        .into_iter()
+        .map(|(k, v)| format!("{k}={}", quote_string(v)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Quotes an identifier, e.g. a table name, in double quotes.
+pub(crate) fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// Quotes a string literal, e.g. a path, in single quotes.
+pub(crate) fn quote_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// Executes the sql and collects its output.
+pub(crate) async fn execute_sql(database: &Database, sql: &str) -> Result<Vec<RecordBatch>> {
+    let output = database
+        .sql(sql)
+        .await
+        .context(RequestDatabaseSnafu { sql })?;
+    match output {
+        Output::Stream(stream) => Ok(RecordBatches::try_collect(stream)
+            .await
+            .context(CollectRecordBatchesSnafu)?
+            .take()),
+        Output::RecordBatches(batches) => Ok(batches.take()),
+        Output::AffectedRows(_) => Ok(vec![]),
+    }
+}
+
+/// Returns the string values in the `column`-th column of `batches`.
+pub(crate) fn string_values(batches: &[RecordBatch], column: usize) -> Vec<String> {
+    batches
+        .iter()
+        .filter(|batch| column < batch.num_columns())
+        .flat_map(|batch| {
+            let vector = batch.column(column);
+            (0..vector.len()).filter_map(|i| match vector.get(i) {
+                Value::String(s) => Some(s.as_utf8().to_string()),
+                _ => None,
+            })
+        })
+        .collect()
+}
+
+/// Appends a `/` to the dir if it doesn't end with one.
+pub(crate) fn normalize_dir(dir: &str) -> String {
+    if dir.ends_with('/') {
+        dir.to_string()
+    } else {
+        format!("{dir}/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format_options() {
+        let options = parse_options(&[
+            "format=parquet".to_string(),
+            "start_time = 2023-01-01 00:00:00".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(
+            "format='parquet', start_time='2023-01-01 00:00:00'",
+            format_options(&options)
+        );
+
+        assert!(parse_options(&["format".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!("\"my table\"", quote_ident("my table"));
+        assert_eq!("\"a\"\"b\"", quote_ident("a\"b"));
+        assert_eq!("'/tmp/it''s/'", quote_string("/tmp/it's/"));
+    }
+
+    #[test]
+    fn test_normalize_dir() {
+        assert_eq!("/tmp/backup/", normalize_dir("/tmp/backup"));
+        assert_eq!("s3://bucket/backup/", normalize_dir("s3://bucket/backup/"));
+    }
+}
@@ -280,6 +280,8 @@ mod tests {
                 max_inflight_tasks: 3,
                 max_files_in_level0: 7,
                 max_purge_tasks: 32,
+                sst_purge_delay: Duration::from_secs(60),
+                cold_check_interval: Duration::from_secs(60 * 60),
                 sst_write_buffer_size: ReadableSize::mb(8),
             },
             options.storage.compaction,
@@ -289,7 +291,8 @@ mod tests {
                 checkpoint_margin: Some(9),
                 gc_duration: Some(Duration::from_secs(7)),
                 checkpoint_on_startup: true,
-                compress: true
+                compress: true,
+                refresh_interval: Duration::from_secs(10),
             },
             options.storage.manifest,
         );
@@ -153,6 +153,71 @@ pub enum Error {
         location: Location,
         source: catalog::error::Error,
     },
+
+    #[snafu(display("Failed to build backend of {}, source: {}", url, source))]
+    BuildBackend {
+        url: String,
+        location: Location,
+        source: common_datasource::error::Error,
+    },
+
+    #[snafu(display("Failed to parse data file format, source: {}", source))]
+    ParseFormat {
+        location: Location,
+        source: common_datasource::error::Error,
+    },
+
+    #[snafu(display("Failed to list objects in {}, source: {}", path, source))]
+    ListObjects {
+        path: String,
+        location: Location,
+        source: common_datasource::error::Error,
+    },
+
+    #[snafu(display("Failed to read object {}, source: {}", path, source))]
+    ReadObject {
+        path: String,
+        location: Location,
+        source: object_store::Error,
+    },
+
+    #[snafu(display("Failed to write object {}, source: {}", path, source))]
+    WriteObject {
+        path: String,
+        location: Location,
+        source: object_store::Error,
+    },
+
+    #[snafu(display("Failed to build object store, source: {}", source))]
+    BuildObjectStore {
+        location: Location,
+        source: object_store::Error,
+    },
+
+    #[snafu(display("Failed to copy WAL directory {}, source: {}", path, source))]
+    CopyWal {
+        path: String,
+        location: Location,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("Failed to open log store, source: {}", source))]
+    OpenLogStore {
+        location: Location,
+        source: log_store::error::Error,
+    },
+
+    #[snafu(display("Failed to inspect region, source: {}", source))]
+    InspectRegion {
+        location: Location,
+        source: storage::error::Error,
+    },
+
+    #[snafu(display("Failed to encode json, source: {}", source))]
+    EncodeJson {
+        location: Location,
+        source: serde_json::Error,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -183,6 +248,16 @@ impl ErrorExt for Error {
             }
             Error::SubstraitEncodeLogicalPlan { source, .. } => source.status_code(),
             Error::StartCatalogManager { source, .. } => source.status_code(),
+            Error::BuildBackend { source, .. }
+            | Error::ParseFormat { source, .. }
+            | Error::ListObjects { source, .. } => source.status_code(),
+            Error::ReadObject { .. }
+            | Error::WriteObject { .. }
+            | Error::BuildObjectStore { .. }
+            | Error::CopyWal { .. } => StatusCode::StorageUnavailable,
+            Error::OpenLogStore { source, .. } => source.status_code(),
+            Error::InspectRegion { source, .. } => source.status_code(),
+            Error::EncodeJson { .. } => StatusCode::Unexpected,
         }
     }
 
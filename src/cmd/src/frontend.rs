@@ -19,6 +19,7 @@ use common_base::Plugins;
 use common_telemetry::logging;
 use frontend::frontend::FrontendOptions;
 use frontend::instance::{FrontendInstance, Instance as FeInstance};
+use frontend::rbac::RBAC_USER_PROVIDER;
 use frontend::service_config::{InfluxdbOptions, PromOptions};
 use meta_client::MetaClientOptions;
 use servers::auth::UserProviderRef;
@@ -212,6 +213,26 @@ impl StartCommand {
             .await
             .context(error::StartFrontendSnafu)?;
 
+        if let Some(superusers) = rbac_superusers(&self.user_provider) {
+            instance
+                .enable_rbac(superusers)
+                .await
+                .context(error::StartFrontendSnafu)?;
+        }
+
+        instance
+            .enable_audit_log(&opts.audit_log)
+            .context(error::StartFrontendSnafu)?;
+
+        instance
+            .enable_slow_query_log(&opts.slow_query)
+            .await
+            .context(error::StartFrontendSnafu)?;
+
+        instance.enable_query_cache(&opts.query_cache);
+
+        instance.enable_write_limit(&opts.write_limit);
+
         instance
             .build_servers(&opts)
             .await
@@ -225,12 +246,25 @@ pub fn load_frontend_plugins(user_provider: &Option<String>) -> Result<Plugins>
     let plugins = Plugins::new();
 
     if let Some(provider) = user_provider {
-        let provider = auth::user_provider_from_option(provider).context(IllegalAuthConfigSnafu)?;
-        plugins.insert::<UserProviderRef>(provider);
+        // RBAC user provider is enabled on the frontend instance after it's built.
+        if rbac_superusers(user_provider).is_none() {
+            let provider =
+                auth::user_provider_from_option(provider).context(IllegalAuthConfigSnafu)?;
+            plugins.insert::<UserProviderRef>(provider);
+        }
     }
     Ok(plugins)
 }
 
+/// Returns the superusers option if the user provider is `rbac_user_provider`,
+/// e.g. `cmd:root=pwd` of `rbac_user_provider:cmd:root=pwd`.
+pub fn rbac_superusers(user_provider: &Option<String>) -> Option<&str> {
+    user_provider
+        .as_deref()?
+        .strip_prefix(RBAC_USER_PROVIDER)?
+        .strip_prefix(':')
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::Write;
@@ -350,6 +384,19 @@ mod tests {
         let _ = result.unwrap();
     }
 
+    #[test]
+    fn test_rbac_user_provider_option() {
+        let user_provider = Some("rbac_user_provider:cmd:root=pwd".to_string());
+        assert_eq!(Some("cmd:root=pwd"), rbac_superusers(&user_provider));
+        // It's enabled on the frontend instance, not loaded as plugin.
+        let plugins = load_frontend_plugins(&user_provider).unwrap();
+        assert!(plugins.get::<UserProviderRef>().is_none());
+
+        let user_provider = Some("static_user_provider:cmd:root=pwd".to_string());
+        assert_eq!(None, rbac_superusers(&user_provider));
+        assert_eq!(None, rbac_superusers(&None));
+    }
+
     #[test]
     fn test_top_level_options() {
         let cmd = StartCommand {
@@ -20,12 +20,17 @@ use common_telemetry::info;
 use common_telemetry::logging::LoggingOptions;
 use datanode::datanode::{Datanode, DatanodeOptions, ProcedureConfig, StorageConfig, WalConfig};
 use datanode::instance::InstanceRef;
+use frontend::audit::AuditLogOptions;
 use frontend::frontend::FrontendOptions;
 use frontend::instance::{FrontendInstance, Instance as FeInstance};
+use frontend::query_cache::QueryCacheOptions;
 use frontend::service_config::{
-    GrpcOptions, InfluxdbOptions, MysqlOptions, OpentsdbOptions, PostgresOptions, PromOptions,
-    PrometheusOptions,
+    GraphiteOptions, GrpcOptions, InfluxdbOptions, MysqlOptions, OpentsdbOptions, PostgresOptions,
+    PromOptions, PrometheusOptions, StatsdOptions,
 };
+use frontend::slow_query::SlowQueryOptions;
+use frontend::write_limit::WriteLimitOptions;
+use query::query_engine::options::QueryMemoryOptions;
 use serde::{Deserialize, Serialize};
 use servers::http::HttpOptions;
 use servers::tls::{TlsMode, TlsOption};
@@ -36,7 +41,7 @@ use crate::error::{
     IllegalConfigSnafu, Result, ShutdownDatanodeSnafu, ShutdownFrontendSnafu, StartDatanodeSnafu,
     StartFrontendSnafu,
 };
-use crate::frontend::load_frontend_plugins;
+use crate::frontend::{load_frontend_plugins, rbac_superusers};
 use crate::options::{MixOptions, Options, TopLevelOptions};
 
 #[derive(Parser)]
@@ -88,6 +93,8 @@ pub struct StandaloneOptions {
     pub mysql_options: Option<MysqlOptions>,
     pub postgres_options: Option<PostgresOptions>,
     pub opentsdb_options: Option<OpentsdbOptions>,
+    pub graphite_options: Option<GraphiteOptions>,
+    pub statsd_options: Option<StatsdOptions>,
     pub influxdb_options: Option<InfluxdbOptions>,
     pub prometheus_options: Option<PrometheusOptions>,
     pub prom_options: Option<PromOptions>,
@@ -95,6 +102,11 @@ pub struct StandaloneOptions {
     pub storage: StorageConfig,
     pub procedure: ProcedureConfig,
     pub logging: LoggingOptions,
+    pub audit_log: AuditLogOptions,
+    pub slow_query: SlowQueryOptions,
+    pub query_memory: QueryMemoryOptions,
+    pub query_cache: QueryCacheOptions,
+    pub write_limit: WriteLimitOptions,
 }
 
 impl Default for StandaloneOptions {
@@ -107,6 +119,8 @@ impl Default for StandaloneOptions {
             mysql_options: Some(MysqlOptions::default()),
             postgres_options: Some(PostgresOptions::default()),
             opentsdb_options: Some(OpentsdbOptions::default()),
+            graphite_options: None,
+            statsd_options: None,
             influxdb_options: Some(InfluxdbOptions::default()),
             prometheus_options: Some(PrometheusOptions::default()),
             prom_options: Some(PromOptions::default()),
@@ -114,6 +128,11 @@ impl Default for StandaloneOptions {
             storage: StorageConfig::default(),
             procedure: ProcedureConfig::default(),
             logging: LoggingOptions::default(),
+            audit_log: AuditLogOptions::default(),
+            slow_query: SlowQueryOptions::default(),
+            query_memory: QueryMemoryOptions::default(),
+            query_cache: QueryCacheOptions::default(),
+            write_limit: WriteLimitOptions::default(),
         }
     }
 }
@@ -127,11 +146,18 @@ impl StandaloneOptions {
             mysql_options: self.mysql_options,
             postgres_options: self.postgres_options,
             opentsdb_options: self.opentsdb_options,
+            graphite_options: self.graphite_options,
+            statsd_options: self.statsd_options,
             influxdb_options: self.influxdb_options,
             prometheus_options: self.prometheus_options,
             prom_options: self.prom_options,
             meta_client_options: None,
             logging: self.logging,
+            audit_log: self.audit_log,
+            slow_query: self.slow_query,
+            query_memory: self.query_memory,
+            query_cache: self.query_cache,
+            write_limit: self.write_limit,
         }
     }
 
@@ -141,6 +167,7 @@ impl StandaloneOptions {
             wal: self.wal,
             storage: self.storage,
             procedure: self.procedure,
+            query_memory: self.query_memory,
             ..Default::default()
         }
     }
@@ -314,6 +341,26 @@ impl StartCommand {
 
         let mut frontend = build_frontend(plugins.clone(), datanode.get_instance()).await?;
 
+        if let Some(superusers) = rbac_superusers(&self.user_provider) {
+            frontend
+                .enable_rbac(superusers)
+                .await
+                .context(StartFrontendSnafu)?;
+        }
+
+        frontend
+            .enable_audit_log(&fe_opts.audit_log)
+            .context(StartFrontendSnafu)?;
+
+        frontend
+            .enable_slow_query_log(&fe_opts.slow_query)
+            .await
+            .context(StartFrontendSnafu)?;
+
+        frontend.enable_query_cache(&fe_opts.query_cache);
+
+        frontend.enable_write_limit(&fe_opts.write_limit);
+
         frontend
             .build_servers(&fe_opts)
             .await
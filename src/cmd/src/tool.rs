@@ -0,0 +1,338 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Offline inspection of region files, for diagnosing regions that fail to open.
+//! The datanode owning the files should be stopped before inspecting them.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::{fs, io};
+
+use clap::Parser;
+use common_recordbatch::RecordBatches;
+use common_telemetry::logging::LoggingOptions;
+use common_time::Timestamp;
+use log_store::raft_engine::log_store::RaftEngineLogStore;
+use log_store::LogConfig;
+use object_store::services::Fs;
+use object_store::ObjectStore;
+use snafu::ResultExt;
+use storage::inspect::{self, ManifestDump};
+use storage::manifest::manifest_compress_type;
+
+use crate::error::{
+    BuildObjectStoreSnafu, CollectRecordBatchesSnafu, CopyWalSnafu, EncodeJsonSnafu,
+    IllegalConfigSnafu, InspectRegionSnafu, OpenLogStoreSnafu, PrettyPrintRecordBatchesSnafu,
+    Result,
+};
+use crate::options::{Options, TopLevelOptions};
+
+pub struct Instance {
+    subcmd: SubCommand,
+}
+
+impl Instance {
+    pub async fn start(&mut self) -> Result<()> {
+        self.subcmd.run().await
+    }
+
+    pub async fn stop(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Parser)]
+pub struct Command {
+    #[clap(subcommand)]
+    subcmd: SubCommand,
+}
+
+impl Command {
+    pub async fn build(self) -> Result<Instance> {
+        Ok(Instance {
+            subcmd: self.subcmd,
+        })
+    }
+
+    pub fn load_options(&self, top_level_opts: TopLevelOptions) -> Result<Options> {
+        let mut logging_opts = LoggingOptions::default();
+        if let Some(dir) = top_level_opts.log_dir {
+            logging_opts.dir = dir;
+        }
+        if top_level_opts.log_level.is_some() {
+            logging_opts.level = top_level_opts.log_level;
+        }
+        Ok(Options::Cli(Box::new(logging_opts)))
+    }
+}
+
+#[derive(Parser)]
+enum SubCommand {
+    /// Lists manifest versions and their actions of a region.
+    Manifest(ManifestCommand),
+    /// Prints metas of files in each level of a region.
+    Levels(LevelsCommand),
+    /// Prints statistics and rows of a SST file.
+    Sst(SstCommand),
+    /// Decodes WAL entries of a region.
+    Wal(WalCommand),
+}
+
+impl SubCommand {
+    async fn run(&self) -> Result<()> {
+        match self {
+            SubCommand::Manifest(cmd) => cmd.run().await,
+            SubCommand::Levels(cmd) => cmd.run().await,
+            SubCommand::Sst(cmd) => cmd.run().await,
+            SubCommand::Wal(cmd) => cmd.run().await,
+        }
+    }
+}
+
+#[derive(Debug, Default, Parser)]
+struct ManifestCommand {
+    /// Directory of the region, which contains SST files and the `manifest` directory.
+    #[clap(long)]
+    region_dir: String,
+    /// Whether the manifest files are compressed.
+    #[clap(long, action)]
+    compress_manifest: bool,
+}
+
+#[allow(clippy::print_stdout)]
+impl ManifestCommand {
+    async fn run(&self) -> Result<()> {
+        let dump = read_manifest(&self.region_dir, self.compress_manifest).await?;
+
+        match &dump.checkpoint {
+            Some(checkpoint) => println!(
+                "Checkpoint: {}",
+                serde_json::to_string(checkpoint).context(EncodeJsonSnafu)?
+            ),
+            None => println!("Checkpoint: none"),
+        }
+        for (version, action_list) in &dump.actions {
+            println!(
+                "Version {}: {}",
+                version,
+                serde_json::to_string(action_list).context(EncodeJsonSnafu)?
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Parser)]
+struct LevelsCommand {
+    /// Directory of the region, which contains SST files and the `manifest` directory.
+    #[clap(long)]
+    region_dir: String,
+    /// Whether the manifest files are compressed.
+    #[clap(long, action)]
+    compress_manifest: bool,
+}
+
+#[allow(clippy::print_stdout)]
+impl LevelsCommand {
+    async fn run(&self) -> Result<()> {
+        let dump = read_manifest(&self.region_dir, self.compress_manifest).await?;
+        let data = dump.manifest_data();
+        println!(
+            "Region: {}, committed sequence: {}",
+            data.metadata.name, data.committed_sequence
+        );
+        if let Some(version) = &data.version {
+            println!(
+                "Manifest version: {}, flushed sequence: {:?}",
+                version.manifest_version, version.flushed_sequence
+            );
+        }
+
+        for (level, files) in dump.level_files() {
+            println!("Level {}: {} files", level, files.len());
+            for file in files {
+                println!(
+                    "  {}  time range: {}  size: {}",
+                    file.file_id.as_parquet(),
+                    format_time_range(&file.time_range),
+                    file.file_size
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Parser)]
+struct SstCommand {
+    /// Path of the SST file.
+    #[clap(long)]
+    file: String,
+    /// Max number of rows to print.
+    #[clap(long, default_value = "10")]
+    rows: usize,
+}
+
+#[allow(clippy::print_stdout)]
+impl SstCommand {
+    async fn run(&self) -> Result<()> {
+        let path = Path::new(&self.file);
+        let (dir, file_name) = match (path.parent(), path.file_name()) {
+            (Some(dir), Some(file_name)) => (dir, file_name.to_string_lossy()),
+            _ => {
+                return IllegalConfigSnafu {
+                    msg: format!("invalid SST file path {}", self.file),
+                }
+                .fail()
+            }
+        };
+        let object_store = new_fs_object_store(&dir.to_string_lossy())?;
+
+        let stats = inspect::read_sst_stats(&object_store, &file_name)
+            .await
+            .context(InspectRegionSnafu)?;
+        println!("Rows: {}", stats.num_rows);
+        println!("Row groups: {}", stats.num_row_groups);
+        println!("Time range: {}", format_time_range(&stats.time_range));
+        println!("Schema:");
+        for column in stats.schema.schema().column_schemas() {
+            println!("  {}: {:?}", column.name, column.data_type);
+        }
+        println!("Metadata:");
+        for kv in &stats.key_value_metadata {
+            println!("  {}: {}", kv.key, kv.value.as_deref().unwrap_or_default());
+        }
+
+        let rows = inspect::read_sst_rows(&object_store, &file_name, Some(self.rows))
+            .await
+            .context(InspectRegionSnafu)?;
+        println!(
+            "{}",
+            rows.pretty_print().context(PrettyPrintRecordBatchesSnafu)?
+        );
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Parser)]
+struct WalCommand {
+    /// Directory of the WAL.
+    #[clap(long)]
+    wal_dir: String,
+    /// Id of the region.
+    #[clap(long)]
+    region_id: u64,
+    /// Sequence to start reading from.
+    #[clap(long, default_value = "0")]
+    start_seq: u64,
+}
+
+#[allow(clippy::print_stdout)]
+impl WalCommand {
+    async fn run(&self) -> Result<()> {
+        // Opening the log store may truncate corrupted log files, which are what
+        // we'd like to inspect, so a copy of the WAL is opened instead.
+        let copy_dir =
+            std::env::temp_dir().join(format!("greptime-wal-inspect-{}", std::process::id()));
+        copy_dir_all(Path::new(&self.wal_dir), &copy_dir).context(CopyWalSnafu {
+            path: &self.wal_dir,
+        })?;
+        let result = self.print_entries(copy_dir.clone()).await;
+        let _ = fs::remove_dir_all(&copy_dir);
+        result
+    }
+
+    async fn print_entries(&self, wal_dir: PathBuf) -> Result<()> {
+        let config = LogConfig {
+            log_file_dir: wal_dir.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let log_store = RaftEngineLogStore::try_new_without_gc(config)
+            .await
+            .context(OpenLogStoreSnafu)?;
+
+        let entries =
+            inspect::read_wal_entries(Arc::new(log_store), self.region_id, self.start_seq)
+                .await
+                .context(InspectRegionSnafu)?;
+        for entry in entries {
+            let Some(payload) = entry.payload else {
+                println!(
+                    "Sequence: {}, last manifest version: {}, no payload",
+                    entry.sequence, entry.header.last_manifest_version
+                );
+                continue;
+            };
+            println!(
+                "Sequence: {}, last manifest version: {}, mutations: {}",
+                entry.sequence,
+                entry.header.last_manifest_version,
+                payload.mutations.len()
+            );
+            for mutation in payload.mutations {
+                let batches =
+                    RecordBatches::try_new(payload.schema.clone(), vec![mutation.record_batch])
+                        .context(CollectRecordBatchesSnafu)?;
+                println!(
+                    "{:?}:\n{}",
+                    mutation.op_type,
+                    batches
+                        .pretty_print()
+                        .context(PrettyPrintRecordBatchesSnafu)?
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn copy_dir_all(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_all(&entry.path(), &target)?;
+        } else {
+            let _ = fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+async fn read_manifest(region_dir: &str, compress: bool) -> Result<ManifestDump> {
+    let object_store = new_fs_object_store(region_dir)?;
+    inspect::read_manifest("manifest/", object_store, manifest_compress_type(compress))
+        .await
+        .context(InspectRegionSnafu)
+}
+
+fn new_fs_object_store(root: &str) -> Result<ObjectStore> {
+    let mut builder = Fs::default();
+    let _ = builder.root(root);
+    Ok(ObjectStore::new(builder)
+        .context(BuildObjectStoreSnafu)?
+        .finish())
+}
+
+fn format_time_range(time_range: &Option<(Timestamp, Timestamp)>) -> String {
+    match time_range {
+        Some((start, end)) => format!(
+            "[{}, {}]",
+            start.to_iso8601_string(),
+            end.to_iso8601_string()
+        ),
+        None => "unknown".to_string(),
+    }
+}
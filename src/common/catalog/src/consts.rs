@@ -17,6 +17,9 @@ pub const INFORMATION_SCHEMA_NAME: &str = "information_schema";
 pub const SYSTEM_CATALOG_TABLE_NAME: &str = "system_catalog";
 pub const DEFAULT_CATALOG_NAME: &str = "greptime";
 pub const DEFAULT_SCHEMA_NAME: &str = "public";
+/// Schema of the internal system tables, such as users and privileges, which
+/// is hidden from `SHOW DATABASES` and only accessible to superusers.
+pub const INTERNAL_SCHEMA_NAME: &str = "greptime_private";
 
 /// Reserves [0,MIN_USER_TABLE_ID) for internal usage.
 /// User defined table id starts from this value.
@@ -29,6 +32,18 @@ pub const SYSTEM_CATALOG_TABLE_ID: u32 = 0;
 pub const SCRIPTS_TABLE_ID: u32 = 1;
 /// numbers table id
 pub const NUMBERS_TABLE_ID: u32 = 2;
+/// users table id
+pub const USERS_TABLE_ID: u32 = 3;
+/// privileges table id
+pub const PRIVILEGES_TABLE_ID: u32 = 4;
+/// materialized views table id
+pub const MATERIALIZED_VIEWS_TABLE_ID: u32 = 5;
+/// script schedules table id
+pub const SCRIPT_SCHEDULES_TABLE_ID: u32 = 6;
+/// script runs table id
+pub const SCRIPT_RUNS_TABLE_ID: u32 = 7;
+/// slow queries table id
+pub const SLOW_QUERIES_TABLE_ID: u32 = 8;
 
 pub const MITO_ENGINE: &str = "mito";
 pub const IMMUTABLE_FILE_ENGINE: &str = "file";
@@ -147,6 +147,20 @@ pub enum Error {
 
     #[snafu(display("Buffered writer closed"))]
     BufferedWriterClosed { location: Location },
+
+    #[snafu(display(
+        "Unsupported data type {} of column {} in ORC files",
+        data_type,
+        column
+    ))]
+    UnsupportedOrcType {
+        column: String,
+        data_type: String,
+        location: Location,
+    },
+
+    #[snafu(display("Value of column {} overflows its ORC type", column))]
+    OrcValueOverflow { column: String, location: Location },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -171,7 +185,9 @@ impl ErrorExt for Error {
             | ReadParquetSnafu { .. }
             | ParquetToSchema { .. }
             | ParseFormat { .. }
-            | MergeSchema { .. } => StatusCode::InvalidArguments,
+            | MergeSchema { .. }
+            | UnsupportedOrcType { .. }
+            | OrcValueOverflow { .. } => StatusCode::InvalidArguments,
 
             JoinHandle { .. }
             | ReadRecordBatch { .. }
@@ -205,6 +221,8 @@ impl ErrorExt for Error {
             AsyncWrite { location, .. } => Some(*location),
             EncodeRecordBatch { location, .. } => Some(*location),
             BufferedWriterClosed { location, .. } => Some(*location),
+            UnsupportedOrcType { location, .. } => Some(*location),
+            OrcValueOverflow { location, .. } => Some(*location),
 
             UnsupportedBackendProtocol { location, .. } => Some(*location),
             EmptyHostPath { location, .. } => Some(*location),
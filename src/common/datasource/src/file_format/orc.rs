@@ -12,23 +12,34 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod proto;
+mod pruning;
+mod writer;
+
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
-use arrow_schema::{Schema, SchemaRef};
+use arrow_schema::{ArrowError, Schema, SchemaRef};
 use async_trait::async_trait;
 use datafusion::arrow::record_batch::RecordBatch as DfRecordBatch;
 use datafusion::error::{DataFusionError, Result as DfResult};
-use datafusion::physical_plan::RecordBatchStream;
-use futures::Stream;
+use datafusion::physical_expr::PhysicalExpr;
+use datafusion::physical_optimizer::pruning::PruningPredicate;
+use datafusion::physical_plan::file_format::{FileMeta, FileOpenFuture, FileOpener};
+use datafusion::physical_plan::{RecordBatchStream, SendableRecordBatchStream};
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt, TryStreamExt};
 use object_store::ObjectStore;
 use orc_rust::arrow_reader::{create_arrow_schema, Cursor};
 use orc_rust::async_arrow_reader::ArrowStreamReader;
 pub use orc_rust::error::Error as OrcError;
 use orc_rust::reader::Reader;
 use snafu::ResultExt;
-use tokio::io::{AsyncRead, AsyncSeek};
+use tokio::io::{AsyncRead, AsyncSeek, AsyncWriteExt};
 
+use self::pruning::OrcTail;
+pub use self::writer::OrcWriter;
 use crate::error::{self, Result};
 use crate::file_format::FileFormat;
 
@@ -87,6 +98,140 @@ impl<T: AsyncRead + AsyncSeek + Unpin + Send + 'static> Stream for OrcArrowStrea
     }
 }
 
+pub struct OrcOpener {
+    object_store: Arc<ObjectStore>,
+    output_schema: SchemaRef,
+    projection: Option<Vec<usize>>,
+    pruning_predicate: Option<Arc<PruningPredicate>>,
+}
+
+impl OrcOpener {
+    /// Return a new [`OrcOpener`], which only reads the columns of `output_schema` in `projection`.
+    pub fn new(
+        object_store: ObjectStore,
+        output_schema: SchemaRef,
+        projection: Option<Vec<usize>>,
+    ) -> Self {
+        Self {
+            object_store: Arc::from(object_store),
+            output_schema,
+            projection,
+            pruning_predicate: None,
+        }
+    }
+
+    /// Skips stripes whose statistics show that no row matches `predicate` over the
+    /// `output_schema`. Rows of the remaining stripes are not filtered.
+    pub fn with_predicate(mut self, predicate: Option<Arc<dyn PhysicalExpr>>) -> Self {
+        self.pruning_predicate = predicate
+            .and_then(|predicate| {
+                PruningPredicate::try_new(predicate, self.output_schema.clone()).ok()
+            })
+            .map(Arc::new);
+        self
+    }
+}
+
+impl FileOpener for OrcOpener {
+    fn open(&self, meta: FileMeta) -> DfResult<FileOpenFuture> {
+        let object_store = self.object_store.clone();
+        let output_schema = self.output_schema.clone();
+        let projected_schema = match &self.projection {
+            Some(projection) => Arc::new(self.output_schema.project(projection)?),
+            None => self.output_schema.clone(),
+        };
+        let pruning_predicate = self.pruning_predicate.clone();
+
+        Ok(Box::pin(async move {
+            let path = meta.location().to_string();
+            let tail = match &pruning_predicate {
+                Some(_) => OrcTail::read(&object_store, &path)
+                    .await
+                    .map_err(|e| DataFusionError::External(Box::new(e)))?,
+                None => None,
+            };
+            let stripes = tail
+                .as_ref()
+                .zip(pruning_predicate.as_ref())
+                .and_then(|(tail, predicate)| tail.prune(predicate, &output_schema));
+
+            let Some(stripes) = stripes else {
+                let reader = object_store
+                    .reader(&path)
+                    .await
+                    .map_err(|e| DataFusionError::External(Box::new(e)))?;
+                return open_orc_stream(reader, projected_schema).await;
+            };
+
+            // Each stripe is read into memory as a file holding only the stripe.
+            let tail = Arc::new(tail.unwrap());
+            let stream = futures::stream::iter(stripes)
+                .then(move |stripe| {
+                    let object_store = object_store.clone();
+                    let path = path.clone();
+                    let tail = tail.clone();
+                    let projected_schema = projected_schema.clone();
+                    async move {
+                        let file = tail
+                            .read_stripe(&object_store, &path, stripe)
+                            .await
+                            .map_err(|e| ArrowError::ExternalError(Box::new(e)))?;
+                        open_orc_stream(std::io::Cursor::new(file), projected_schema)
+                            .await
+                            .map_err(|e| ArrowError::ExternalError(Box::new(e)))
+                    }
+                })
+                .try_flatten();
+
+            Ok(stream.boxed())
+        }))
+    }
+}
+
+/// Reads the columns of `projected_schema` from the ORC file of `reader`.
+async fn open_orc_stream<R: AsyncRead + AsyncSeek + Unpin + Send + 'static>(
+    reader: R,
+    projected_schema: SchemaRef,
+) -> DfResult<BoxStream<'static, std::result::Result<DfRecordBatch, ArrowError>>> {
+    let reader = Reader::new_async(reader)
+        .await
+        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+    // Only decodes the projected columns.
+    let column_names = projected_schema
+        .fields()
+        .iter()
+        .map(|field| field.name().as_str())
+        .collect::<Vec<_>>();
+    let cursor =
+        Cursor::new(reader, &column_names).map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+    let stream = ArrowStreamReader::new(cursor, None)
+        .map_err(|e| ArrowError::ExternalError(Box::new(e)))
+        .and_then(move |batch| futures::future::ready(project_batch(&projected_schema, batch)));
+
+    Ok(stream.boxed())
+}
+
+/// Reorders columns of `batch` read from files to the `projected_schema`.
+fn project_batch(
+    projected_schema: &SchemaRef,
+    batch: DfRecordBatch,
+) -> std::result::Result<DfRecordBatch, ArrowError> {
+    let columns = projected_schema
+        .fields()
+        .iter()
+        .map(|field| {
+            batch
+                .schema()
+                .index_of(field.name())
+                .map(|i| batch.column(i).clone())
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    DfRecordBatch::try_new(projected_schema.clone(), columns)
+}
+
 #[async_trait]
 impl FileFormat for OrcFormat {
     async fn infer_schema(&self, store: &ObjectStore, path: &str) -> Result<Schema> {
@@ -100,3 +245,42 @@ impl FileFormat for OrcFormat {
         Ok(schema)
     }
 }
+
+/// Writes the `stream` to an ORC file at `path`, returning the rows written.
+pub async fn stream_to_orc(
+    mut stream: SendableRecordBatchStream,
+    store: ObjectStore,
+    path: &str,
+) -> Result<usize> {
+    let mut writer = OrcWriter::try_new(stream.schema())?;
+    let mut object_writer = store
+        .writer(path)
+        .await
+        .context(error::WriteObjectSnafu { path })?;
+
+    let mut rows = 0;
+    while let Some(batch) = stream.next().await {
+        let batch = batch.context(error::ReadRecordBatchSnafu)?;
+        writer.write(&batch)?;
+        rows += batch.num_rows();
+
+        // Stripes are written once they are flushed.
+        let bytes = writer.take_bytes();
+        if !bytes.is_empty() {
+            object_writer
+                .write_all(&bytes)
+                .await
+                .context(error::AsyncWriteSnafu)?;
+        }
+    }
+    object_writer
+        .write_all(&writer.finish()?)
+        .await
+        .context(error::AsyncWriteSnafu)?;
+    object_writer
+        .shutdown()
+        .await
+        .context(error::AsyncWriteSnafu)?;
+
+    Ok(rows)
+}
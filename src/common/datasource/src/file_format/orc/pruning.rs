@@ -0,0 +1,410 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Skips stripes of ORC files whose statistics show they can't match the filters.
+//!
+//! The ORC reader always reads every stripe of a file, so each selected stripe is
+//! read by itself as a file holding only the stripe, whose tail is rebuilt from the
+//! tail of the original file.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, UInt64Array};
+use arrow_schema::{DataType, Schema, TimeUnit};
+use async_compression::tokio::write::{DeflateDecoder, ZstdDecoder};
+use datafusion::common::{Column, ScalarValue};
+use datafusion::physical_optimizer::pruning::{PruningPredicate, PruningStatistics};
+use object_store::ObjectStore;
+use prost::Message;
+use snafu::ResultExt;
+use tokio::io::AsyncWriteExt;
+
+use super::proto::{
+    ColumnStatistics, CompressionKind, Footer, Metadata, PostScript, StripeInformation,
+    StripeStatistics,
+};
+use crate::error::{self, Result};
+
+/// Bytes read from the end of a file at first, which usually hold the whole tail.
+const TAIL_READ_SIZE: u64 = 16 * 1024;
+const MAGIC: &[u8] = b"ORC";
+/// The first writer version whose string statistics are right (HIVE-8732).
+const STRING_STATISTICS_WRITER_VERSION: u32 = 1;
+/// Compression chunk size of a rebuilt tail if the original file doesn't specify it.
+const DEFAULT_COMPRESSION_BLOCK_SIZE: u64 = 256 * 1024;
+
+/// The postscript, footer and metadata at the end of an ORC file.
+pub(crate) struct OrcTail {
+    postscript: PostScript,
+    footer: Footer,
+    metadata: Metadata,
+}
+
+impl OrcTail {
+    /// Reads the tail of the file at `path`. Returns `None` if the tail can't be decoded,
+    /// e.g. it's compressed by a codec we don't support, so the file is read as a whole
+    /// and the reader reports it if it's invalid.
+    pub(crate) async fn read(store: &ObjectStore, path: &str) -> Result<Option<Self>> {
+        let file_len = store
+            .stat(path)
+            .await
+            .context(error::ReadObjectSnafu { path })?
+            .content_length();
+        let mut buf = store
+            .range_read(path, file_len.saturating_sub(TAIL_READ_SIZE)..file_len)
+            .await
+            .context(error::ReadObjectSnafu { path })?;
+
+        let Some(&postscript_len) = buf.last() else {
+            return Ok(None);
+        };
+        let postscript_len = postscript_len as usize;
+        if buf.len() < postscript_len + 1 {
+            return Ok(None);
+        }
+        let postscript_start = buf.len() - postscript_len - 1;
+        let Ok(postscript) = PostScript::decode(&buf[postscript_start..buf.len() - 1]) else {
+            return Ok(None);
+        };
+
+        let tail_len =
+            postscript.metadata_length() + postscript.footer_length() + postscript_len as u64 + 1;
+        if tail_len > file_len {
+            return Ok(None);
+        }
+        if tail_len > buf.len() as u64 {
+            buf = store
+                .range_read(path, file_len - tail_len..file_len)
+                .await
+                .context(error::ReadObjectSnafu { path })?;
+        }
+        let tail = &buf[buf.len() - tail_len as usize..];
+        let (metadata, tail) = tail.split_at(postscript.metadata_length() as usize);
+        let footer = &tail[..postscript.footer_length() as usize];
+
+        let compression = postscript.compression();
+        let (Some(footer), Some(metadata)) = (
+            decompress(compression, footer).await,
+            decompress(compression, metadata).await,
+        ) else {
+            return Ok(None);
+        };
+        let (Ok(footer), Ok(metadata)) = (Footer::decode(&*footer), Metadata::decode(&*metadata))
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self {
+            postscript,
+            footer,
+            metadata,
+        }))
+    }
+
+    /// Returns the stripes of the file that may match `predicate`, or `None` if all of them
+    /// may match or there are no statistics to prune them.
+    pub(crate) fn prune(
+        &self,
+        predicate: &PruningPredicate,
+        schema: &Schema,
+    ) -> Option<Vec<usize>> {
+        let stripes = &self.footer.stripes;
+        if stripes.is_empty() || self.metadata.stripe_stats.len() != stripes.len() {
+            return None;
+        }
+        let statistics = StripePruningStatistics::new(self, schema);
+        let selected = predicate
+            .prune(&statistics)
+            .ok()?
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, selected)| selected.then_some(i))
+            .collect::<Vec<_>>();
+        (selected.len() < stripes.len()).then_some(selected)
+    }
+
+    /// Reads the stripe `index` of the file at `path` as a file holding only the stripe.
+    pub(crate) async fn read_stripe(
+        &self,
+        store: &ObjectStore,
+        path: &str,
+        index: usize,
+    ) -> Result<Vec<u8>> {
+        let stripe = &self.footer.stripes[index];
+        let offset = stripe.offset();
+        let stripe_len = stripe.index_length() + stripe.data_length() + stripe.footer_length();
+
+        let mut file = MAGIC.to_vec();
+        file.extend(
+            store
+                .range_read(path, offset..offset + stripe_len)
+                .await
+                .context(error::ReadObjectSnafu { path })?,
+        );
+
+        let stripe_stats = self.metadata.stripe_stats[index].clone();
+        let metadata = Metadata {
+            stripe_stats: vec![stripe_stats.clone()],
+        };
+        let footer = Footer {
+            header_length: Some(MAGIC.len() as u64),
+            content_length: Some(file.len() as u64),
+            stripes: vec![StripeInformation {
+                offset: Some(MAGIC.len() as u64),
+                ..stripe.clone()
+            }],
+            number_of_rows: stripe.number_of_rows,
+            statistics: stripe_stats.col_stats,
+            ..self.footer.clone()
+        };
+        let metadata = self.compress(&metadata.encode_to_vec());
+        let footer = self.compress(&footer.encode_to_vec());
+        let postscript = PostScript {
+            footer_length: Some(footer.len() as u64),
+            metadata_length: Some(metadata.len() as u64),
+            ..self.postscript.clone()
+        }
+        .encode_to_vec();
+
+        file.extend_from_slice(&metadata);
+        file.extend_from_slice(&footer);
+        file.extend_from_slice(&postscript);
+        file.push(postscript.len() as u8);
+        Ok(file)
+    }
+
+    /// Wraps `bytes` in uncompressed chunks if the file is compressed.
+    fn compress(&self, bytes: &[u8]) -> Vec<u8> {
+        if self.postscript.compression() == CompressionKind::None {
+            return bytes.to_vec();
+        }
+        let block_size = match self.postscript.compression_block_size() {
+            0 => DEFAULT_COMPRESSION_BLOCK_SIZE,
+            size => size,
+        };
+        let mut out = Vec::with_capacity(bytes.len() + 3);
+        for chunk in bytes.chunks(block_size as usize) {
+            let header = (chunk.len() as u32) << 1 | 1;
+            out.extend_from_slice(&header.to_le_bytes()[..3]);
+            out.extend_from_slice(chunk);
+        }
+        out
+    }
+}
+
+/// Decompresses the chunks of `bytes`, returning `None` if they are invalid or compressed
+/// by a codec we don't support.
+async fn decompress(compression: CompressionKind, bytes: &[u8]) -> Option<Vec<u8>> {
+    if compression == CompressionKind::None {
+        return Some(bytes.to_vec());
+    }
+
+    let mut out = Vec::with_capacity(bytes.len() * 2);
+    let mut rest = bytes;
+    while !rest.is_empty() {
+        if rest.len() < 3 {
+            return None;
+        }
+        let header = u32::from_le_bytes([rest[0], rest[1], rest[2], 0]);
+        let (len, original) = ((header >> 1) as usize, header & 1 == 1);
+        let chunk = rest.get(3..3 + len)?;
+        rest = &rest[3 + len..];
+        if original {
+            out.extend_from_slice(chunk);
+            continue;
+        }
+        match compression {
+            CompressionKind::Zlib => {
+                let mut decoder = DeflateDecoder::new(&mut out);
+                decoder.write_all(chunk).await.ok()?;
+                decoder.shutdown().await.ok()?;
+            }
+            CompressionKind::Zstd => {
+                let mut decoder = ZstdDecoder::new(&mut out);
+                decoder.write_all(chunk).await.ok()?;
+                decoder.shutdown().await.ok()?;
+            }
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+/// Statistics of the stripes of a file for a [PruningPredicate].
+struct StripePruningStatistics<'a> {
+    schema: &'a Schema,
+    stripes: &'a [StripeInformation],
+    stats: &'a [StripeStatistics],
+    /// ORC column ids of the top-level fields.
+    column_ids: HashMap<&'a str, usize>,
+    string_stats: bool,
+}
+
+impl<'a> StripePruningStatistics<'a> {
+    fn new(tail: &'a OrcTail, schema: &'a Schema) -> Self {
+        let column_ids = tail
+            .footer
+            .types
+            .first()
+            .map(|root| {
+                root.field_names
+                    .iter()
+                    .zip(&root.subtypes)
+                    .map(|(name, id)| (name.as_str(), *id as usize))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            schema,
+            stripes: &tail.footer.stripes,
+            stats: &tail.metadata.stripe_stats,
+            column_ids,
+            string_stats: tail.postscript.writer_version() >= STRING_STATISTICS_WRITER_VERSION,
+        }
+    }
+
+    fn column_stats(&self, column: &Column) -> Option<(&DataType, Vec<Option<&ColumnStatistics>>)> {
+        let field = self.schema.field_with_name(&column.name).ok()?;
+        let id = *self.column_ids.get(column.name.as_str())?;
+        let stats = self
+            .stats
+            .iter()
+            .map(|stripe| stripe.col_stats.get(id))
+            .collect();
+        Some((field.data_type(), stats))
+    }
+
+    fn bounds(&self, column: &Column, min: bool) -> Option<ArrayRef> {
+        let (data_type, stats) = self.column_stats(column)?;
+        let null = ScalarValue::try_from(data_type).ok()?;
+        let values = stats.into_iter().map(|stats| {
+            stats
+                .and_then(|stats| self.bound(stats, data_type, min))
+                .unwrap_or_else(|| null.clone())
+        });
+        ScalarValue::iter_to_array(values).ok()
+    }
+
+    /// Returns the minimum or maximum of a column in a stripe as a value of `data_type`.
+    fn bound(
+        &self,
+        stats: &ColumnStatistics,
+        data_type: &DataType,
+        min: bool,
+    ) -> Option<ScalarValue> {
+        let int = || {
+            let stats = stats.int_statistics.as_ref()?;
+            if min {
+                stats.minimum
+            } else {
+                stats.maximum
+            }
+        };
+        let value = match data_type {
+            DataType::Int8 => ScalarValue::Int8(Some(int()?.try_into().ok()?)),
+            DataType::Int16 => ScalarValue::Int16(Some(int()?.try_into().ok()?)),
+            DataType::Int32 => ScalarValue::Int32(Some(int()?.try_into().ok()?)),
+            DataType::Int64 => ScalarValue::Int64(Some(int()?)),
+            DataType::UInt8 => ScalarValue::UInt8(Some(int()?.try_into().ok()?)),
+            DataType::UInt16 => ScalarValue::UInt16(Some(int()?.try_into().ok()?)),
+            DataType::UInt32 => ScalarValue::UInt32(Some(int()?.try_into().ok()?)),
+            DataType::UInt64 => ScalarValue::UInt64(Some(int()?.try_into().ok()?)),
+            DataType::Float32 | DataType::Float64 => {
+                let stats = stats.double_statistics.as_ref()?;
+                let value = if min { stats.minimum } else { stats.maximum }?;
+                if *data_type == DataType::Float32 {
+                    ScalarValue::Float32(Some(value as f32))
+                } else {
+                    ScalarValue::Float64(Some(value))
+                }
+            }
+            DataType::Utf8 | DataType::LargeUtf8 if self.string_stats => {
+                let stats = stats.string_statistics.as_ref()?;
+                let value = (if min { &stats.minimum } else { &stats.maximum }).clone()?;
+                if *data_type == DataType::Utf8 {
+                    ScalarValue::Utf8(Some(value))
+                } else {
+                    ScalarValue::LargeUtf8(Some(value))
+                }
+            }
+            DataType::Date32 => {
+                let stats = stats.date_statistics.as_ref()?;
+                ScalarValue::Date32(Some(if min { stats.minimum } else { stats.maximum }?))
+            }
+            DataType::Timestamp(unit, tz) => {
+                // Statistics are in milliseconds, the maximum is rounded down.
+                let stats = stats.timestamp_statistics.as_ref()?;
+                let millis = if min {
+                    stats.minimum_utc
+                } else {
+                    stats.maximum_utc
+                }?;
+                let (scale, rest) = match unit {
+                    TimeUnit::Second => {
+                        let value = millis.div_euclid(1_000);
+                        return Some(ScalarValue::TimestampSecond(Some(value), tz.clone()));
+                    }
+                    TimeUnit::Millisecond => (1, 0),
+                    TimeUnit::Microsecond => (1_000, 999),
+                    TimeUnit::Nanosecond => (1_000_000, 999_999),
+                };
+                let value = millis.checked_mul(scale)?;
+                let value = if min { value } else { value.checked_add(rest)? };
+                match unit {
+                    TimeUnit::Millisecond => {
+                        ScalarValue::TimestampMillisecond(Some(value), tz.clone())
+                    }
+                    TimeUnit::Microsecond => {
+                        ScalarValue::TimestampMicrosecond(Some(value), tz.clone())
+                    }
+                    _ => ScalarValue::TimestampNanosecond(Some(value), tz.clone()),
+                }
+            }
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+impl<'a> PruningStatistics for StripePruningStatistics<'a> {
+    fn min_values(&self, column: &Column) -> Option<ArrayRef> {
+        self.bounds(column, true)
+    }
+
+    fn max_values(&self, column: &Column) -> Option<ArrayRef> {
+        self.bounds(column, false)
+    }
+
+    fn num_containers(&self) -> usize {
+        self.stripes.len()
+    }
+
+    fn null_counts(&self, column: &Column) -> Option<ArrayRef> {
+        let (_, stats) = self.column_stats(column)?;
+        let counts = stats
+            .into_iter()
+            .zip(self.stripes)
+            .map(|(stats, stripe)| {
+                let stats = stats?;
+                if stats.has_null == Some(false) {
+                    return Some(0);
+                }
+                stripe.number_of_rows().checked_sub(stats.number_of_values?)
+            })
+            .collect::<Vec<_>>();
+        Some(Arc::new(UInt64Array::from(counts)))
+    }
+}
@@ -0,0 +1,888 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use arrow::array::{
+    as_boolean_array, as_generic_binary_array, as_largestring_array, as_primitive_array,
+    as_string_array, Array, ArrayRef,
+};
+use arrow::datatypes::{
+    Date32Type, Date64Type, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type,
+    TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
+    TimestampSecondType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow::record_batch::RecordBatch;
+use arrow_schema::{DataType, SchemaRef, TimeUnit};
+use prost::Message;
+use snafu::OptionExt;
+
+use super::proto::{
+    self, ColumnEncodingKind, CompressionKind, StreamKind, StripeInformation, TypeKind,
+};
+use crate::error::{self, Result};
+
+/// Rows of a stripe, stripes are flushed once they reach it.
+const DEFAULT_STRIPE_ROWS: usize = 128 * 1024;
+/// Strings longer than it are left out of the statistics.
+const MAX_STATISTICS_STRING_LENGTH: usize = 1024;
+/// Maximum values of a run of RLE v2.
+const MAX_RLE_V2_RUN: usize = 512;
+/// Seconds from the UNIX epoch to 2015-01-01 00:00:00 UTC, which ORC timestamps are
+/// relative to.
+const ORC_EPOCH_SECONDS: i64 = 1_420_070_400;
+/// The writer version telling readers that timestamp statistics are in UTC (ORC-135).
+const WRITER_VERSION: u32 = 6;
+const MAGIC: &str = "ORC";
+
+/// Writes record batches to an ORC file without compression.
+///
+/// Bytes of flushed stripes are kept in the writer until [`OrcWriter::take_bytes`] is called,
+/// so the file can be streamed to the storage while it's written.
+pub struct OrcWriter {
+    schema: SchemaRef,
+    columns: Vec<ColumnWriter>,
+    stripe_rows: usize,
+    /// Rows of the stripe being written.
+    rows: usize,
+    root_file_stats: ColumnStats,
+    /// Offset of the next stripe in the file.
+    offset: u64,
+    stripes: Vec<StripeInformation>,
+    stripe_stats: Vec<proto::StripeStatistics>,
+    buffer: Vec<u8>,
+}
+
+impl OrcWriter {
+    pub fn try_new(schema: SchemaRef) -> Result<Self> {
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|field| ColumnWriter::try_new(field.name(), field.data_type()))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            schema,
+            columns,
+            stripe_rows: DEFAULT_STRIPE_ROWS,
+            rows: 0,
+            root_file_stats: ColumnStats::default(),
+            offset: MAGIC.len() as u64,
+            stripes: Vec::new(),
+            stripe_stats: Vec::new(),
+            buffer: MAGIC.as_bytes().to_vec(),
+        })
+    }
+
+    /// Sets the rows of a stripe.
+    pub fn with_stripe_rows(mut self, stripe_rows: usize) -> Self {
+        self.stripe_rows = stripe_rows.max(1);
+        self
+    }
+
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        let mut start = 0;
+        while start < batch.num_rows() {
+            let len = (self.stripe_rows - self.rows).min(batch.num_rows() - start);
+            for (column, array) in self.columns.iter_mut().zip(batch.columns()) {
+                column.append(&array.slice(start, len))?;
+            }
+            self.rows += len;
+            start += len;
+            if self.rows >= self.stripe_rows {
+                self.flush_stripe();
+            }
+        }
+        Ok(())
+    }
+
+    /// Takes the bytes written so far.
+    pub fn take_bytes(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.buffer)
+    }
+
+    /// Flushes the last stripe and the file tail, returning the bytes not taken yet.
+    pub fn finish(mut self) -> Result<Vec<u8>> {
+        self.flush_stripe();
+
+        let metadata = proto::Metadata {
+            stripe_stats: std::mem::take(&mut self.stripe_stats),
+        }
+        .encode_to_vec();
+
+        let mut types = vec![proto::Type {
+            kind: Some(TypeKind::Struct as i32),
+            subtypes: (1..=self.columns.len() as u32).collect(),
+            field_names: self
+                .schema
+                .fields()
+                .iter()
+                .map(|field| field.name().clone())
+                .collect(),
+            ..Default::default()
+        }];
+        types.extend(self.columns.iter().map(|column| proto::Type {
+            kind: Some(column.kind as i32),
+            ..Default::default()
+        }));
+        let mut statistics = vec![self.root_file_stats.to_proto(TypeKind::Struct)];
+        statistics.extend(
+            self.columns
+                .iter()
+                .map(|column| column.file_stats.to_proto(column.kind)),
+        );
+        let footer = proto::Footer {
+            header_length: Some(MAGIC.len() as u64),
+            content_length: Some(self.offset),
+            stripes: std::mem::take(&mut self.stripes),
+            types,
+            number_of_rows: Some(self.root_file_stats.values),
+            statistics,
+            row_index_stride: Some(0),
+            ..Default::default()
+        }
+        .encode_to_vec();
+
+        let postscript = proto::PostScript {
+            footer_length: Some(footer.len() as u64),
+            compression: Some(CompressionKind::None as i32),
+            compression_block_size: Some(256 * 1024),
+            version: vec![0, 12],
+            metadata_length: Some(metadata.len() as u64),
+            writer_version: Some(WRITER_VERSION),
+            magic: Some(MAGIC.to_string()),
+        }
+        .encode_to_vec();
+
+        let mut bytes = self.take_bytes();
+        bytes.extend_from_slice(&metadata);
+        bytes.extend_from_slice(&footer);
+        bytes.extend_from_slice(&postscript);
+        bytes.push(postscript.len() as u8);
+        Ok(bytes)
+    }
+
+    fn flush_stripe(&mut self) {
+        if self.rows == 0 {
+            return;
+        }
+
+        let mut data = Vec::new();
+        let mut streams = Vec::new();
+        let mut encodings = vec![encoding(ColumnEncodingKind::Direct)];
+        let root_stats = ColumnStats {
+            values: self.rows as u64,
+            ..Default::default()
+        };
+        let mut stats = vec![root_stats.to_proto(TypeKind::Struct)];
+        self.root_file_stats.merge(&root_stats);
+        for (i, column) in self.columns.iter_mut().enumerate() {
+            column.flush(i as u32 + 1, &mut data, &mut streams);
+            encodings.push(encoding(column.encoding()));
+            stats.push(column.stats.to_proto(column.kind));
+            column.file_stats.merge(&column.stats);
+            column.stats = ColumnStats::default();
+        }
+
+        let footer = proto::StripeFooter {
+            streams,
+            columns: encodings,
+            writer_timezone: Some("UTC".to_string()),
+        }
+        .encode_to_vec();
+        self.stripes.push(StripeInformation {
+            offset: Some(self.offset),
+            index_length: Some(0),
+            data_length: Some(data.len() as u64),
+            footer_length: Some(footer.len() as u64),
+            number_of_rows: Some(self.rows as u64),
+        });
+        self.stripe_stats
+            .push(proto::StripeStatistics { col_stats: stats });
+        self.offset += (data.len() + footer.len()) as u64;
+        self.buffer.extend_from_slice(&data);
+        self.buffer.extend_from_slice(&footer);
+        self.rows = 0;
+    }
+}
+
+fn encoding(kind: ColumnEncodingKind) -> proto::ColumnEncoding {
+    proto::ColumnEncoding {
+        kind: Some(kind as i32),
+        dictionary_size: None,
+    }
+}
+
+/// Values of a column buffered for the stripe being written, nulls are left out.
+enum ColumnValues {
+    Booleans(Vec<bool>),
+    Bytes(Vec<u8>),
+    Integers(Vec<i64>),
+    Floats(Vec<f32>),
+    Doubles(Vec<f64>),
+    Binaries { data: Vec<u8>, lengths: Vec<i64> },
+    Timestamps { seconds: Vec<i64>, nanos: Vec<i64> },
+}
+
+struct ColumnWriter {
+    name: String,
+    kind: TypeKind,
+    present: Vec<bool>,
+    values: ColumnValues,
+    /// Statistics of the stripe being written.
+    stats: ColumnStats,
+    file_stats: ColumnStats,
+}
+
+impl ColumnWriter {
+    fn try_new(name: &str, data_type: &DataType) -> Result<Self> {
+        let kind = match data_type {
+            DataType::Boolean => TypeKind::Boolean,
+            DataType::Int8 => TypeKind::Byte,
+            DataType::Int16 | DataType::UInt8 => TypeKind::Short,
+            DataType::Int32 | DataType::UInt16 => TypeKind::Int,
+            DataType::Int64 | DataType::UInt32 | DataType::UInt64 => TypeKind::Long,
+            DataType::Float32 => TypeKind::Float,
+            DataType::Float64 => TypeKind::Double,
+            DataType::Utf8 | DataType::LargeUtf8 => TypeKind::String,
+            DataType::Binary | DataType::LargeBinary => TypeKind::Binary,
+            DataType::Date32 => TypeKind::Date,
+            DataType::Date64 | DataType::Timestamp(_, _) => TypeKind::Timestamp,
+            _ => {
+                return error::UnsupportedOrcTypeSnafu {
+                    column: name,
+                    data_type: data_type.to_string(),
+                }
+                .fail()
+            }
+        };
+        let values = match kind {
+            TypeKind::Boolean => ColumnValues::Booleans(Vec::new()),
+            TypeKind::Byte => ColumnValues::Bytes(Vec::new()),
+            TypeKind::Float => ColumnValues::Floats(Vec::new()),
+            TypeKind::Double => ColumnValues::Doubles(Vec::new()),
+            TypeKind::String | TypeKind::Binary => ColumnValues::Binaries {
+                data: Vec::new(),
+                lengths: Vec::new(),
+            },
+            TypeKind::Timestamp => ColumnValues::Timestamps {
+                seconds: Vec::new(),
+                nanos: Vec::new(),
+            },
+            _ => ColumnValues::Integers(Vec::new()),
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            kind,
+            present: Vec::new(),
+            values,
+            stats: ColumnStats::default(),
+            file_stats: ColumnStats::default(),
+        })
+    }
+
+    fn encoding(&self) -> ColumnEncodingKind {
+        match self.kind {
+            TypeKind::Boolean | TypeKind::Byte | TypeKind::Float | TypeKind::Double => {
+                ColumnEncodingKind::Direct
+            }
+            _ => ColumnEncodingKind::DirectV2,
+        }
+    }
+
+    fn append(&mut self, array: &ArrayRef) -> Result<()> {
+        self.stats.values += (array.len() - array.null_count()) as u64;
+        self.stats.has_null |= array.null_count() > 0;
+        self.present
+            .extend((0..array.len()).map(|i| array.is_valid(i)));
+
+        match array.data_type() {
+            DataType::Boolean => {
+                for value in as_boolean_array(array).iter().flatten() {
+                    self.stats.trues += value as u64;
+                    if let ColumnValues::Booleans(values) = &mut self.values {
+                        values.push(value);
+                    }
+                }
+            }
+            DataType::Int8 => {
+                self.append_integers(as_primitive_array::<Int8Type>(array).iter().flatten())
+            }
+            DataType::Int16 => {
+                self.append_integers(as_primitive_array::<Int16Type>(array).iter().flatten())
+            }
+            DataType::Int32 => {
+                self.append_integers(as_primitive_array::<Int32Type>(array).iter().flatten())
+            }
+            DataType::Int64 => {
+                self.append_integers(as_primitive_array::<Int64Type>(array).iter().flatten())
+            }
+            DataType::UInt8 => {
+                self.append_integers(as_primitive_array::<UInt8Type>(array).iter().flatten())
+            }
+            DataType::UInt16 => {
+                self.append_integers(as_primitive_array::<UInt16Type>(array).iter().flatten())
+            }
+            DataType::UInt32 => {
+                self.append_integers(as_primitive_array::<UInt32Type>(array).iter().flatten())
+            }
+            DataType::UInt64 => {
+                let values = as_primitive_array::<UInt64Type>(array)
+                    .iter()
+                    .flatten()
+                    .map(i64::try_from)
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .ok()
+                    .context(error::OrcValueOverflowSnafu { column: &self.name })?;
+                self.append_integers(values)
+            }
+            DataType::Date32 => {
+                self.append_integers(as_primitive_array::<Date32Type>(array).iter().flatten())
+            }
+            DataType::Float32 => {
+                for value in as_primitive_array::<Float32Type>(array).iter().flatten() {
+                    self.stats.update_double(value as f64);
+                    if let ColumnValues::Floats(values) = &mut self.values {
+                        values.push(value);
+                    }
+                }
+            }
+            DataType::Float64 => {
+                for value in as_primitive_array::<Float64Type>(array).iter().flatten() {
+                    self.stats.update_double(value);
+                    if let ColumnValues::Doubles(values) = &mut self.values {
+                        values.push(value);
+                    }
+                }
+            }
+            DataType::Utf8 => {
+                self.append_binaries(as_string_array(array).iter().flatten().map(str::as_bytes))
+            }
+            DataType::LargeUtf8 => self.append_binaries(
+                as_largestring_array(array)
+                    .iter()
+                    .flatten()
+                    .map(str::as_bytes),
+            ),
+            DataType::Binary => {
+                self.append_binaries(as_generic_binary_array::<i32>(array).iter().flatten())
+            }
+            DataType::LargeBinary => {
+                self.append_binaries(as_generic_binary_array::<i64>(array).iter().flatten())
+            }
+            DataType::Date64 => self.append_timestamps(
+                as_primitive_array::<Date64Type>(array).iter().flatten(),
+                TimeUnit::Millisecond,
+            ),
+            DataType::Timestamp(TimeUnit::Second, _) => self.append_timestamps(
+                as_primitive_array::<TimestampSecondType>(array)
+                    .iter()
+                    .flatten(),
+                TimeUnit::Second,
+            ),
+            DataType::Timestamp(TimeUnit::Millisecond, _) => self.append_timestamps(
+                as_primitive_array::<TimestampMillisecondType>(array)
+                    .iter()
+                    .flatten(),
+                TimeUnit::Millisecond,
+            ),
+            DataType::Timestamp(TimeUnit::Microsecond, _) => self.append_timestamps(
+                as_primitive_array::<TimestampMicrosecondType>(array)
+                    .iter()
+                    .flatten(),
+                TimeUnit::Microsecond,
+            ),
+            DataType::Timestamp(TimeUnit::Nanosecond, _) => self.append_timestamps(
+                as_primitive_array::<TimestampNanosecondType>(array)
+                    .iter()
+                    .flatten(),
+                TimeUnit::Nanosecond,
+            ),
+            data_type => {
+                return error::UnsupportedOrcTypeSnafu {
+                    column: &self.name,
+                    data_type: data_type.to_string(),
+                }
+                .fail()
+            }
+        }
+        Ok(())
+    }
+
+    fn append_integers<T: Into<i64>>(&mut self, values: impl IntoIterator<Item = T>) {
+        for value in values {
+            let value = value.into();
+            self.stats.update_bounds(StatValue::Int(value));
+            self.stats.int_sum = self.stats.int_sum.and_then(|sum| sum.checked_add(value));
+            match &mut self.values {
+                ColumnValues::Bytes(values) => values.push(value as u8),
+                ColumnValues::Integers(values) => values.push(value),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    fn append_binaries<'a>(&mut self, values: impl Iterator<Item = &'a [u8]>) {
+        let ColumnValues::Binaries { data, lengths } = &mut self.values else {
+            unreachable!()
+        };
+        for value in values {
+            if self.kind == TypeKind::String {
+                self.stats.update_bytes_bounds(value);
+            }
+            self.stats.length_sum += value.len() as i64;
+            data.extend_from_slice(value);
+            lengths.push(value.len() as i64);
+        }
+    }
+
+    fn append_timestamps(&mut self, values: impl Iterator<Item = i64>, unit: TimeUnit) {
+        let ColumnValues::Timestamps { seconds, nanos } = &mut self.values else {
+            unreachable!()
+        };
+        let units_per_second = match unit {
+            TimeUnit::Second => 1,
+            TimeUnit::Millisecond => 1_000,
+            TimeUnit::Microsecond => 1_000_000,
+            TimeUnit::Nanosecond => 1_000_000_000,
+        };
+        for value in values {
+            let second = value.div_euclid(units_per_second);
+            let nano = value.rem_euclid(units_per_second) * (1_000_000_000 / units_per_second);
+            self.stats.update_bounds(StatValue::Int(
+                second
+                    .saturating_mul(1_000)
+                    .saturating_add(nano / 1_000_000),
+            ));
+            // Readers subtract one second from negative timestamps with more than a
+            // millisecond of nanos, as seconds are rounded toward zero by the writers.
+            let second = if second < 0 && nano > 999_999 {
+                second + 1
+            } else {
+                second
+            };
+            seconds.push(second.saturating_sub(ORC_EPOCH_SECONDS));
+            nanos.push(encode_nanos(nano));
+        }
+    }
+
+    /// Writes the streams of the buffered values to `data`.
+    fn flush(&mut self, column: u32, data: &mut Vec<u8>, streams: &mut Vec<proto::Stream>) {
+        let mut write_stream = |kind: StreamKind, bytes: Vec<u8>| {
+            streams.push(proto::Stream {
+                kind: Some(kind as i32),
+                column: Some(column),
+                length: Some(bytes.len() as u64),
+            });
+            data.extend_from_slice(&bytes);
+        };
+
+        let present = std::mem::take(&mut self.present);
+        if present.iter().any(|present| !present) {
+            write_stream(StreamKind::Present, encode_booleans(&present));
+        }
+        match &mut self.values {
+            ColumnValues::Booleans(values) => {
+                write_stream(StreamKind::Data, encode_booleans(&std::mem::take(values)))
+            }
+            ColumnValues::Bytes(values) => {
+                write_stream(StreamKind::Data, encode_byte_rle(&std::mem::take(values)))
+            }
+            ColumnValues::Integers(values) => write_stream(
+                StreamKind::Data,
+                encode_rle_v2(&std::mem::take(values), true),
+            ),
+            ColumnValues::Floats(values) => write_stream(
+                StreamKind::Data,
+                std::mem::take(values)
+                    .into_iter()
+                    .flat_map(f32::to_le_bytes)
+                    .collect(),
+            ),
+            ColumnValues::Doubles(values) => write_stream(
+                StreamKind::Data,
+                std::mem::take(values)
+                    .into_iter()
+                    .flat_map(f64::to_le_bytes)
+                    .collect(),
+            ),
+            ColumnValues::Binaries {
+                data: values,
+                lengths,
+            } => {
+                write_stream(StreamKind::Data, std::mem::take(values));
+                write_stream(
+                    StreamKind::Length,
+                    encode_rle_v2(&std::mem::take(lengths), false),
+                );
+            }
+            ColumnValues::Timestamps { seconds, nanos } => {
+                write_stream(
+                    StreamKind::Data,
+                    encode_rle_v2(&std::mem::take(seconds), true),
+                );
+                write_stream(
+                    StreamKind::Secondary,
+                    encode_rle_v2(&std::mem::take(nanos), false),
+                );
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+enum StatValue {
+    Int(i64),
+    Double(f64),
+    Bytes(Vec<u8>),
+}
+
+#[derive(Debug, Clone)]
+struct ColumnStats {
+    /// Number of non-null values.
+    values: u64,
+    has_null: bool,
+    /// Minimum and maximum of the values, `None` if there is no value or they are unknown.
+    bounds: Option<(StatValue, StatValue)>,
+    /// Whether the bounds are unknown, e.g. a string is too long or a float is NaN.
+    unbounded: bool,
+    /// `None` if the sum overflows.
+    int_sum: Option<i64>,
+    double_sum: f64,
+    length_sum: i64,
+    trues: u64,
+}
+
+impl Default for ColumnStats {
+    fn default() -> Self {
+        Self {
+            values: 0,
+            has_null: false,
+            bounds: None,
+            unbounded: false,
+            int_sum: Some(0),
+            double_sum: 0.0,
+            length_sum: 0,
+            trues: 0,
+        }
+    }
+}
+
+impl ColumnStats {
+    fn update_bounds(&mut self, value: StatValue) {
+        if self.unbounded {
+            return;
+        }
+        match &mut self.bounds {
+            Some((min, max)) => {
+                if value < *min {
+                    *min = value;
+                } else if value > *max {
+                    *max = value;
+                }
+            }
+            None => self.bounds = Some((value.clone(), value)),
+        }
+    }
+
+    fn update_bytes_bounds(&mut self, value: &[u8]) {
+        if value.len() > MAX_STATISTICS_STRING_LENGTH {
+            self.set_unbounded();
+            return;
+        }
+        let changed = match &self.bounds {
+            Some((StatValue::Bytes(min), StatValue::Bytes(max))) => {
+                value < min.as_slice() || value > max.as_slice()
+            }
+            _ => true,
+        };
+        if changed {
+            self.update_bounds(StatValue::Bytes(value.to_vec()));
+        }
+    }
+
+    fn update_double(&mut self, value: f64) {
+        if value.is_nan() {
+            self.set_unbounded();
+        } else {
+            self.update_bounds(StatValue::Double(value));
+        }
+        self.double_sum += value;
+    }
+
+    fn set_unbounded(&mut self) {
+        self.unbounded = true;
+        self.bounds = None;
+    }
+
+    fn merge(&mut self, other: &ColumnStats) {
+        self.values += other.values;
+        self.has_null |= other.has_null;
+        if other.unbounded {
+            self.set_unbounded();
+        }
+        if let Some((min, max)) = &other.bounds {
+            self.update_bounds(min.clone());
+            self.update_bounds(max.clone());
+        }
+        self.int_sum = self
+            .int_sum
+            .zip(other.int_sum)
+            .and_then(|(sum, other)| sum.checked_add(other));
+        self.double_sum += other.double_sum;
+        self.length_sum += other.length_sum;
+        self.trues += other.trues;
+    }
+
+    fn to_proto(&self, kind: TypeKind) -> proto::ColumnStatistics {
+        let mut stats = proto::ColumnStatistics {
+            number_of_values: Some(self.values),
+            has_null: Some(self.has_null),
+            ..Default::default()
+        };
+        let int = |value: &StatValue| match value {
+            StatValue::Int(value) => Some(*value),
+            _ => None,
+        };
+        let (min, max) = match &self.bounds {
+            Some((min, max)) => (Some(min), Some(max)),
+            None => (None, None),
+        };
+        match kind {
+            TypeKind::Boolean => {
+                stats.bucket_statistics = Some(proto::BucketStatistics {
+                    count: vec![self.trues],
+                })
+            }
+            TypeKind::Byte | TypeKind::Short | TypeKind::Int | TypeKind::Long => {
+                stats.int_statistics = Some(proto::IntegerStatistics {
+                    minimum: min.and_then(int),
+                    maximum: max.and_then(int),
+                    sum: self.int_sum,
+                })
+            }
+            TypeKind::Float | TypeKind::Double => {
+                let double = |value: &StatValue| match value {
+                    StatValue::Double(value) => Some(*value),
+                    _ => None,
+                };
+                stats.double_statistics = Some(proto::DoubleStatistics {
+                    minimum: min.and_then(double),
+                    maximum: max.and_then(double),
+                    sum: Some(self.double_sum),
+                })
+            }
+            TypeKind::String => {
+                let string = |value: &StatValue| match value {
+                    StatValue::Bytes(value) => String::from_utf8(value.clone()).ok(),
+                    _ => None,
+                };
+                stats.string_statistics = Some(proto::StringStatistics {
+                    minimum: min.and_then(string),
+                    maximum: max.and_then(string),
+                    sum: Some(self.length_sum),
+                })
+            }
+            TypeKind::Binary => {
+                stats.binary_statistics = Some(proto::BinaryStatistics {
+                    sum: Some(self.length_sum),
+                })
+            }
+            TypeKind::Date => {
+                let date = |value: &StatValue| int(value).map(|value| value as i32);
+                stats.date_statistics = Some(proto::DateStatistics {
+                    minimum: min.and_then(date),
+                    maximum: max.and_then(date),
+                })
+            }
+            TypeKind::Timestamp => {
+                stats.timestamp_statistics = Some(proto::TimestampStatistics {
+                    minimum: min.and_then(int),
+                    maximum: max.and_then(int),
+                    minimum_utc: min.and_then(int),
+                    maximum_utc: max.and_then(int),
+                })
+            }
+            _ => {}
+        }
+        stats
+    }
+}
+
+/// Encodes nanos with their trailing decimal zeros in the lowest 3 bits.
+fn encode_nanos(nanos: i64) -> i64 {
+    if nanos == 0 {
+        return 0;
+    }
+    if nanos % 100 != 0 {
+        return nanos << 3;
+    }
+    let (mut nanos, mut zeros) = (nanos / 100, 1);
+    while nanos % 10 == 0 && zeros < 7 {
+        nanos /= 10;
+        zeros += 1;
+    }
+    nanos << 3 | zeros
+}
+
+/// Packs booleans into bits from the most significant one, then encodes them by byte RLE.
+fn encode_booleans(values: &[bool]) -> Vec<u8> {
+    let bytes = values
+        .chunks(8)
+        .map(|bits| {
+            bits.iter()
+                .enumerate()
+                .fold(0u8, |byte, (i, bit)| byte | ((*bit as u8) << (7 - i)))
+        })
+        .collect::<Vec<_>>();
+    encode_byte_rle(&bytes)
+}
+
+/// Encodes bytes in runs of 3 to 130 repeated bytes and up to 128 literals.
+fn encode_byte_rle(values: &[u8]) -> Vec<u8> {
+    fn write_literals(out: &mut Vec<u8>, literals: &[u8]) {
+        for chunk in literals.chunks(128) {
+            out.push((256 - chunk.len()) as u8);
+            out.extend_from_slice(chunk);
+        }
+    }
+
+    let mut out = Vec::with_capacity(values.len() + values.len() / 128 + 1);
+    let (mut literal_start, mut i) = (0, 0);
+    while i < values.len() {
+        let mut run = 1;
+        while i + run < values.len() && run < 130 && values[i + run] == values[i] {
+            run += 1;
+        }
+        if run >= 3 {
+            write_literals(&mut out, &values[literal_start..i]);
+            out.push((run - 3) as u8);
+            out.push(values[i]);
+            literal_start = i + run;
+        }
+        i += run;
+    }
+    write_literals(&mut out, &values[literal_start..]);
+    out
+}
+
+/// Encodes integers by the direct sub-encoding of RLE v2, zigzag encoding them if `signed`.
+fn encode_rle_v2(values: &[i64], signed: bool) -> Vec<u8> {
+    let mut out = Vec::new();
+    for run in values.chunks(MAX_RLE_V2_RUN) {
+        let encoded = run
+            .iter()
+            .map(|value| {
+                if signed {
+                    ((value << 1) ^ (value >> 63)) as u64
+                } else {
+                    *value as u64
+                }
+            })
+            .collect::<Vec<_>>();
+        let bits = 64
+            - encoded
+                .iter()
+                .fold(0u64, |acc, value| acc | value)
+                .leading_zeros();
+        let (width, code) = direct_width(bits.max(1) as usize);
+        let len = run.len() - 1;
+        out.push(0b0100_0000 | (code << 1) | (len >> 8) as u8);
+        out.push(len as u8);
+        write_bits(&mut out, &encoded, width);
+    }
+    out
+}
+
+/// Returns the closest bit width the direct sub-encoding supports and its code.
+fn direct_width(bits: usize) -> (usize, u8) {
+    match bits {
+        1..=24 => (bits, bits as u8 - 1),
+        25..=26 => (26, 24),
+        27..=28 => (28, 25),
+        29..=30 => (30, 26),
+        31..=32 => (32, 27),
+        33..=40 => (40, 28),
+        41..=48 => (48, 29),
+        49..=56 => (56, 30),
+        _ => (64, 31),
+    }
+}
+
+/// Packs the lowest `width` bits of each value from the most significant one.
+fn write_bits(out: &mut Vec<u8>, values: &[u64], width: usize) {
+    let (mut current, mut free) = (0u8, 8);
+    for value in values {
+        let mut remaining = width;
+        while remaining > 0 {
+            let take = remaining.min(free);
+            let bits = (value >> (remaining - take)) & ((1u64 << take) - 1);
+            current |= (bits as u8) << (free - take);
+            free -= take;
+            remaining -= take;
+            if free == 0 {
+                out.push(current);
+                current = 0;
+                free = 8;
+            }
+        }
+    }
+    if free < 8 {
+        out.push(current);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_byte_rle() {
+        assert_eq!(encode_byte_rle(&[]), Vec::<u8>::new());
+        assert_eq!(encode_byte_rle(&[1, 2]), vec![0xfe, 1, 2]);
+        assert_eq!(encode_byte_rle(&[7; 100]), vec![97, 7]);
+        assert_eq!(
+            encode_byte_rle(&[1, 7, 7, 7, 2]),
+            vec![0xff, 1, 0, 7, 0xff, 2]
+        );
+    }
+
+    #[test]
+    fn test_encode_booleans() {
+        let values = [true, false, true, true, false, false, false, false, true];
+        assert_eq!(
+            encode_booleans(&values),
+            vec![0xfe, 0b1011_0000, 0b1000_0000]
+        );
+    }
+
+    #[test]
+    fn test_encode_rle_v2() {
+        // The example of the direct sub-encoding in the ORC specification.
+        let values = [23713, 43806, 57005, 48879];
+        assert_eq!(
+            encode_rle_v2(&values, false),
+            vec![0x5e, 0x03, 0x5c, 0xa1, 0xab, 0x1e, 0xde, 0xad, 0xbe, 0xef]
+        );
+        // Zigzag encoded -1, 1 and -2 are 1, 2 and 3.
+        assert_eq!(encode_rle_v2(&[-1, 1, -2], true), vec![0x42, 0x02, 0x6c]);
+    }
+
+    #[test]
+    fn test_encode_nanos() {
+        assert_eq!(encode_nanos(0), 0);
+        assert_eq!(encode_nanos(1), 1 << 3);
+        assert_eq!(encode_nanos(1_000), 1 << 3 | 2);
+        assert_eq!(encode_nanos(100_000_000), 1 << 3 | 7);
+    }
+}
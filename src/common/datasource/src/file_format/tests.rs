@@ -17,8 +17,13 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::vec;
 
+use arrow::array::{Int64Array, StringArray};
+use arrow::record_batch::RecordBatch;
+use arrow_schema::{DataType, Field, Schema};
 use datafusion::assert_batches_eq;
 use datafusion::execution::context::TaskContext;
+use datafusion::logical_expr::Operator;
+use datafusion::physical_expr::expressions::{binary, col, lit};
 use datafusion::physical_plan::file_format::{FileOpener, FileScanConfig, FileStream, ParquetExec};
 use datafusion::physical_plan::metrics::ExecutionPlanMetricsSet;
 use datafusion::physical_plan::ExecutionPlan;
@@ -30,9 +35,10 @@ use crate::compression::CompressionType;
 use crate::error;
 use crate::file_format::csv::{CsvConfigBuilder, CsvOpener};
 use crate::file_format::json::JsonOpener;
+use crate::file_format::orc::{OrcOpener, OrcWriter};
 use crate::file_format::parquet::DefaultParquetFileReaderFactory;
 use crate::file_format::Format;
-use crate::test_util::{self, scan_config, test_basic_schema, test_store};
+use crate::test_util::{self, scan_config, test_basic_schema, test_store, test_tmp_store};
 
 struct Test<'a, T: FileOpener> {
     config: FileScanConfig,
@@ -153,6 +159,149 @@ async fn test_csv_opener() {
     }
 }
 
+#[tokio::test]
+async fn test_orc_opener() {
+    let store = test_store("/");
+
+    // Columns are in a different order from the file.
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("str_direct", DataType::Utf8, true),
+        Field::new("double_a", DataType::Float64, true),
+    ]));
+    let orc_opener = OrcOpener::new(store.clone(), schema.clone(), None);
+
+    let path = &test_util::get_data_dir("tests/orc/test.orc")
+        .display()
+        .to_string();
+    let tests = [
+        Test {
+            config: scan_config(schema.clone(), None, path),
+            opener: orc_opener,
+            expected: vec![
+                "+------------+----------+",
+                "| str_direct | double_a |",
+                "+------------+----------+",
+                "| a          | 1.0      |",
+                "| cccccc     | 2.0      |",
+                "|            | 3.0      |",
+                "| ddd        | 4.0      |",
+                "| ee         | 5.0      |",
+                "+------------+----------+",
+            ],
+        },
+        Test {
+            config: FileScanConfig {
+                projection: Some(vec![1]),
+                ..scan_config(schema.clone(), Some(1), path)
+            },
+            opener: OrcOpener::new(store, schema.clone(), Some(vec![1])),
+            expected: vec![
+                "+----------+",
+                "| double_a |",
+                "+----------+",
+                "| 1.0      |",
+                "+----------+",
+            ],
+        },
+    ];
+
+    for test in tests {
+        test.run().await;
+    }
+}
+
+#[tokio::test]
+async fn test_orc_writer_and_stripe_pruning() {
+    let (store, dir) = test_tmp_store("test_orc_writer_and_stripe_pruning");
+    let path = &dir.path().join("test.orc").display().to_string();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("num", DataType::Int64, true),
+        Field::new("str", DataType::Utf8, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int64Array::from(vec![
+                Some(1),
+                Some(2),
+                Some(5),
+                Some(6),
+                Some(3),
+                None,
+            ])),
+            Arc::new(StringArray::from(vec!["a", "b", "c", "d", "e", "f"])),
+        ],
+    )
+    .unwrap();
+    // Writes 3 stripes of 2 rows.
+    let mut writer = OrcWriter::try_new(schema.clone())
+        .unwrap()
+        .with_stripe_rows(2);
+    writer.write(&batch).unwrap();
+    let mut bytes = writer.take_bytes();
+    bytes.extend(writer.finish().unwrap());
+    store.write(path, bytes).await.unwrap();
+
+    let opener = |op, value: i64| {
+        let predicate = binary(col("num", &schema).unwrap(), op, lit(value), &schema).unwrap();
+        OrcOpener::new(store.clone(), schema.clone(), None).with_predicate(Some(predicate))
+    };
+    let tests = [
+        Test {
+            config: scan_config(schema.clone(), None, path),
+            opener: OrcOpener::new(store.clone(), schema.clone(), None),
+            expected: vec![
+                "+-----+-----+",
+                "| num | str |",
+                "+-----+-----+",
+                "| 1   | a   |",
+                "| 2   | b   |",
+                "| 5   | c   |",
+                "| 6   | d   |",
+                "| 3   | e   |",
+                "|     | f   |",
+                "+-----+-----+",
+            ],
+        },
+        // Only reads the second stripe.
+        Test {
+            config: scan_config(schema.clone(), None, path),
+            opener: opener(Operator::Gt, 4),
+            expected: vec![
+                "+-----+-----+",
+                "| num | str |",
+                "+-----+-----+",
+                "| 5   | c   |",
+                "| 6   | d   |",
+                "+-----+-----+",
+            ],
+        },
+        // Rows of the selected stripes are not filtered.
+        Test {
+            config: scan_config(schema.clone(), None, path),
+            opener: opener(Operator::Lt, 2),
+            expected: vec![
+                "+-----+-----+",
+                "| num | str |",
+                "+-----+-----+",
+                "| 1   | a   |",
+                "| 2   | b   |",
+                "+-----+-----+",
+            ],
+        },
+        Test {
+            config: scan_config(schema.clone(), None, path),
+            opener: opener(Operator::Gt, 10),
+            expected: vec!["++", "++"],
+        },
+    ];
+
+    for test in tests {
+        test.run().await;
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_parquet_exec() {
     let store = test_store("/");
@@ -29,6 +29,7 @@ pub struct Lister {
     source: Source,
     path: String,
     regex: Option<Regex>,
+    recursive: bool,
 }
 
 impl Lister {
@@ -43,17 +44,26 @@ impl Lister {
             source,
             path,
             regex,
+            recursive: false,
         }
     }
 
+    /// Lists files in subdirectories of the dir as well, e.g. files in
+    /// Hive-style partition directories.
+    pub fn with_recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
     pub async fn list(&self) -> Result<Vec<Entry>> {
         match &self.source {
             Source::Dir => {
-                let streamer = self
-                    .object_store
-                    .list(&self.path)
-                    .await
-                    .context(error::ListObjectsSnafu { path: &self.path })?;
+                let streamer = if self.recursive {
+                    self.object_store.scan(&self.path).await
+                } else {
+                    self.object_store.list(&self.path).await
+                }
+                .context(error::ListObjectsSnafu { path: &self.path })?;
 
                 streamer
                     .try_filter(|f| {
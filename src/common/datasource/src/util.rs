@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 pub fn find_dir_and_filename(path: &str) -> (String, Option<String>) {
     if path.is_empty() {
         ("/".to_string(), None)
@@ -27,6 +29,21 @@ pub fn find_dir_and_filename(path: &str) -> (String, Option<String>) {
     }
 }
 
+/// Parses the values of Hive-style partition columns from the directories of
+/// a file `path`, e.g. `logs/dt=2023-06-01/host=a/1.parquet`. Returns `None` if
+/// any of the columns is missing in the path.
+pub fn parse_partition_values(path: &str, partition_columns: &[String]) -> Option<Vec<String>> {
+    let (dir, _) = find_dir_and_filename(path);
+    let values = dir
+        .split('/')
+        .filter_map(|segment| segment.split_once('='))
+        .collect::<HashMap<_, _>>();
+    partition_columns
+        .iter()
+        .map(|column| values.get(column.as_str()).map(|value| value.to_string()))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -122,4 +139,27 @@ mod tests {
             assert_eq!(test.expected_filename, filename)
         }
     }
+
+    #[test]
+    fn test_parse_partition_values() {
+        let columns = vec!["dt".to_string(), "host".to_string()];
+        assert_eq!(
+            Some(vec!["2023-06-01".to_string(), "a".to_string()]),
+            parse_partition_values("logs/dt=2023-06-01/host=a/1.parquet", &columns)
+        );
+        assert_eq!(
+            Some(vec!["2023-06-01".to_string(), "a".to_string()]),
+            parse_partition_values("/logs/host=a/dt=2023-06-01/1.parquet", &columns)
+        );
+        assert_eq!(
+            None,
+            parse_partition_values("logs/dt=2023-06-01/1.parquet", &columns)
+        );
+        // values are not parsed from the file name
+        assert_eq!(
+            None,
+            parse_partition_values("logs/dt=2023-06-01/host=a", &columns)
+        );
+        assert_eq!(Some(vec![]), parse_partition_values("logs/1.parquet", &[]));
+    }
 }
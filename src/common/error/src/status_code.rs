@@ -47,6 +47,8 @@ pub enum StatusCode {
     PlanQuery = 3000,
     /// The query engine fail to execute query.
     EngineExecuteQuery = 3001,
+    /// The query exceeds its memory limit.
+    ResourcesExhausted = 3002,
     // ====== End of query related status code =========
 
     // ====== Begin of catalog related status code =====
@@ -66,6 +68,10 @@ pub enum StatusCode {
     // ====== Begin of server related status code =====
     /// Runtime resources exhausted, like creating threads failed.
     RuntimeResourcesExhausted = 6000,
+    /// The request exceeds rate limits, and may be retried later.
+    RateLimited = 6001,
+    /// The request exceeds quotas, like the max number of tables.
+    QuotaExceeded = 6002,
     // ====== End of server related status code =======
 
     // ====== Begin of auth related status code =====
@@ -95,6 +101,7 @@ impl StatusCode {
         match self {
             StatusCode::StorageUnavailable
             | StatusCode::RuntimeResourcesExhausted
+            | StatusCode::RateLimited
             | StatusCode::Internal => true,
 
             StatusCode::Success
@@ -106,6 +113,8 @@ impl StatusCode {
             | StatusCode::InvalidSyntax
             | StatusCode::PlanQuery
             | StatusCode::EngineExecuteQuery
+            | StatusCode::ResourcesExhausted
+            | StatusCode::QuotaExceeded
             | StatusCode::TableAlreadyExists
             | StatusCode::TableNotFound
             | StatusCode::TableColumnNotFound
@@ -136,6 +145,9 @@ impl StatusCode {
             StatusCode::Success
             | StatusCode::InvalidArguments
             | StatusCode::InvalidSyntax
+            | StatusCode::ResourcesExhausted
+            | StatusCode::RateLimited
+            | StatusCode::QuotaExceeded
             | StatusCode::TableAlreadyExists
             | StatusCode::TableNotFound
             | StatusCode::TableColumnNotFound
@@ -15,6 +15,7 @@
 pub mod aggregate;
 pub mod expression;
 pub mod function;
+pub mod gapfill;
 pub mod function_registry;
 pub mod math;
 pub mod numpy;
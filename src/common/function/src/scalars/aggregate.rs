@@ -12,24 +12,37 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod approx_percentile;
 mod argmax;
 mod argmin;
 mod diff;
+mod first;
+mod histogram;
+mod increase;
+mod last;
 mod mean;
 mod percentile;
 mod polyval;
+mod rate;
 mod scipy_stats_norm_cdf;
 mod scipy_stats_norm_pdf;
+mod utils;
 
 use std::sync::Arc;
 
+pub use approx_percentile::{ApproxPercentileAccumulatorCreator, DdSketch};
 pub use argmax::ArgmaxAccumulatorCreator;
 pub use argmin::ArgminAccumulatorCreator;
 use common_query::logical_plan::AggregateFunctionCreatorRef;
 pub use diff::DiffAccumulatorCreator;
+pub use first::FirstAccumulatorCreator;
+pub use histogram::HistogramAccumulatorCreator;
+pub use increase::IncreaseAccumulatorCreator;
+pub use last::LastAccumulatorCreator;
 pub use mean::MeanAccumulatorCreator;
 pub use percentile::PercentileAccumulatorCreator;
 pub use polyval::PolyvalAccumulatorCreator;
+pub use rate::RateAccumulatorCreator;
 pub use scipy_stats_norm_cdf::ScipyStatsNormCdfAccumulatorCreator;
 pub use scipy_stats_norm_pdf::ScipyStatsNormPdfAccumulatorCreator;
 
@@ -94,5 +107,11 @@ impl AggregateFunctions {
         register_aggr_func!("percentile", 2, PercentileAccumulatorCreator);
         register_aggr_func!("scipystatsnormcdf", 2, ScipyStatsNormCdfAccumulatorCreator);
         register_aggr_func!("scipystatsnormpdf", 2, ScipyStatsNormPdfAccumulatorCreator);
+        register_aggr_func!("first", 2, FirstAccumulatorCreator);
+        register_aggr_func!("last", 2, LastAccumulatorCreator);
+        register_aggr_func!("increase", 2, IncreaseAccumulatorCreator);
+        register_aggr_func!("rate", 2, RateAccumulatorCreator);
+        register_aggr_func!("histogram", 4, HistogramAccumulatorCreator);
+        register_aggr_func!("approx_percentile", 2, ApproxPercentileAccumulatorCreator);
     }
 }
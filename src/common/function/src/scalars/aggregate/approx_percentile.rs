@@ -0,0 +1,411 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use common_function_macro::{as_aggr_func_creator, AggrFuncTypeStore};
+use common_query::error::{
+    BadAccumulatorImplSnafu, CreateAccumulatorSnafu, DowncastVectorSnafu, FromScalarValueSnafu,
+    InvalidFuncArgsSnafu, Result,
+};
+use common_query::logical_plan::{Accumulator, AggregateFunctionCreator};
+use common_query::prelude::*;
+use datatypes::prelude::*;
+use datatypes::value::ListValue;
+use datatypes::vectors::{Float64Vector, Int64Vector, ListVector, UInt64Vector};
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::scalars::aggregate::utils::{constant_f64_arg, to_f64_vector};
+
+const NAME: &str = "APPROX_PERCENTILE";
+
+/// The estimated percentile is within 1% of the exact value.
+const RELATIVE_ACCURACY: f64 = 0.01;
+
+/// Max number of bins of a [DdSketch], which covers the positive or negative
+/// values across about 17 orders of magnitude within the accuracy.
+const MAX_BINS: usize = 2048;
+
+/// A mergeable quantile sketch, see DDSketch <https://arxiv.org/abs/1908.10693>.
+///
+/// Values are counted in logarithmically sized bins, so the size of the
+/// sketch only depends on the range of the values instead of the count. There
+/// are at most [MAX_BINS] bins, the bins of the smallest absolute values are
+/// collapsed when exceeded, which loses the accuracy of these values only.
+/// Bins of positive and negative values are keyed by `index << 1` and
+/// `(index << 1) | 1`.
+#[derive(Debug)]
+pub struct DdSketch {
+    gamma: f64,
+    bins: BTreeMap<i64, u64>,
+    zeros: u64,
+    count: u64,
+}
+
+impl Default for DdSketch {
+    fn default() -> Self {
+        Self {
+            gamma: (1.0 + RELATIVE_ACCURACY) / (1.0 - RELATIVE_ACCURACY),
+            bins: BTreeMap::new(),
+            zeros: 0,
+            count: 0,
+        }
+    }
+}
+
+impl DdSketch {
+    pub fn add(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        self.count += 1;
+        if value == 0.0 {
+            self.zeros += 1;
+            return;
+        }
+        let index = (value.abs().ln() / self.gamma.ln()).ceil() as i64;
+        let key = if value > 0.0 {
+            index << 1
+        } else {
+            (index << 1) | 1
+        };
+        *self.bins.entry(key).or_default() += 1;
+        self.collapse();
+    }
+
+    /// Merges a bin of another sketch, [DdSketch::collapse] must be called
+    /// after all bins are merged.
+    pub fn merge_bin(&mut self, key: i64, count: u64) {
+        *self.bins.entry(key).or_default() += count;
+        self.count += count;
+    }
+
+    /// Collapses the bins of the smallest absolute values into their next
+    /// bins of the same sign until there are at most [MAX_BINS] bins.
+    pub fn collapse(&mut self) {
+        let mut excess = self.bins.len().saturating_sub(MAX_BINS);
+        if excess == 0 {
+            return;
+        }
+        let keys = self.bins.keys().copied().collect::<Vec<_>>();
+        // The smallest remaining bins of positive and negative values.
+        let mut smallest = [None, None];
+        for key in keys {
+            if excess == 0 {
+                break;
+            }
+            let sign = (key & 1) as usize;
+            if let Some(smaller) = smallest[sign] {
+                let count = self.bins.remove(&smaller).unwrap_or_default();
+                *self.bins.entry(key).or_default() += count;
+                excess -= 1;
+            }
+            smallest[sign] = Some(key);
+        }
+    }
+
+    pub fn merge_zeros(&mut self, count: u64) {
+        self.zeros += count;
+        self.count += count;
+    }
+
+    /// Estimates the `q`-quantile, `q` is in `[0, 1]`.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = q * (self.count - 1) as f64;
+        let mut seen = 0;
+        // From the smallest negative value to the largest positive value.
+        let negatives = self
+            .bins
+            .iter()
+            .rev()
+            .filter(|(key, _)| *key & 1 == 1)
+            .map(|(key, count)| (-self.bin_value(*key >> 1), *count));
+        let zeros = std::iter::once((0.0, self.zeros));
+        let positives = self
+            .bins
+            .iter()
+            .filter(|(key, _)| *key & 1 == 0)
+            .map(|(key, count)| (self.bin_value(*key >> 1), *count));
+        for (value, count) in negatives.chain(zeros).chain(positives) {
+            seen += count;
+            if seen as f64 > rank {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    /// Returns the representative value of the bin with `index`, whose
+    /// relative error to all values in the bin is within the accuracy.
+    fn bin_value(&self, index: i64) -> f64 {
+        2.0 * self.gamma.powf(index as f64) / (self.gamma + 1.0)
+    }
+}
+
+/// `approx_percentile(value, p)` estimates the `p`-th percentile (0 to 100)
+/// like `percentile`, but in bounded memory with a [DdSketch], whose bins are
+/// kept as states to be merged across partitions.
+#[derive(Debug, Default)]
+pub struct ApproxPercentile {
+    sketch: DdSketch,
+    p: Option<f64>,
+}
+
+impl ApproxPercentile {
+    fn set_percentile(&mut self, p: f64) -> Result<()> {
+        ensure!(
+            (0.0..=100.0).contains(&p) && self.p.map_or(true, |existing| existing == p),
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The percentile of \"{NAME}\" must be a constant between 0 and 100, have: {p}"
+                ),
+            }
+        );
+        self.p = Some(p);
+        Ok(())
+    }
+}
+
+impl Accumulator for ApproxPercentile {
+    fn state(&self) -> Result<Vec<Value>> {
+        let (keys, counts): (Vec<_>, Vec<_>) = self
+            .sketch
+            .bins
+            .iter()
+            .map(|(key, count)| (Value::from(*key), Value::from(*count)))
+            .unzip();
+        Ok(vec![
+            Value::List(ListValue::new(
+                Some(Box::new(keys)),
+                ConcreteDataType::int64_datatype(),
+            )),
+            Value::List(ListValue::new(
+                Some(Box::new(counts)),
+                ConcreteDataType::uint64_datatype(),
+            )),
+            self.sketch.zeros.into(),
+            self.p.into(),
+        ])
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        ensure!(values.len() == 2, InvalidInputStateSnafu);
+        if values[0].is_empty() {
+            return Ok(());
+        }
+
+        self.set_percentile(constant_f64_arg(&values[1], NAME, "percentile")?)?;
+        for value in to_f64_vector(&values[0])?.iter_data().flatten() {
+            self.sketch.add(value);
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+        ensure!(
+            states.len() == 4,
+            BadAccumulatorImplSnafu {
+                err_msg: "expect 4 states in `merge_batch`",
+            }
+        );
+
+        let keys = downcast::<ListVector>(&states[0])?;
+        let counts = downcast::<ListVector>(&states[1])?;
+        for (keys, counts) in keys.values_iter().zip(counts.values_iter()) {
+            let keys = keys.context(FromScalarValueSnafu)?;
+            let counts = counts.context(FromScalarValueSnafu)?;
+            if let (Some(keys), Some(counts)) = (keys, counts) {
+                let keys = downcast::<Int64Vector>(&keys)?;
+                let counts = downcast::<UInt64Vector>(&counts)?;
+                for (key, count) in keys.iter_data().zip(counts.iter_data()) {
+                    if let (Some(key), Some(count)) = (key, count) {
+                        self.sketch.merge_bin(key, count);
+                    }
+                }
+            }
+        }
+        self.sketch.collapse();
+        for zeros in downcast::<UInt64Vector>(&states[2])?.iter_data().flatten() {
+            self.sketch.merge_zeros(zeros);
+        }
+        for p in downcast::<Float64Vector>(&states[3])?.iter_data().flatten() {
+            self.set_percentile(p)?;
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<Value> {
+        let value = self
+            .p
+            .and_then(|p| self.sketch.quantile(p / 100.0))
+            .map(Value::from)
+            .unwrap_or(Value::Null);
+        Ok(value)
+    }
+}
+
+fn downcast<T: Vector + 'static>(vector: &VectorRef) -> Result<&T> {
+    vector
+        .as_any()
+        .downcast_ref::<T>()
+        .with_context(|| DowncastVectorSnafu {
+            err_msg: format!(
+                "expect {}, got vector type {}",
+                std::any::type_name::<T>(),
+                vector.vector_type_name()
+            ),
+        })
+}
+
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+pub struct ApproxPercentileAccumulatorCreator {}
+
+impl AggregateFunctionCreator for ApproxPercentileAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        let creator: AccumulatorCreatorFunction = Arc::new(move |types: &[ConcreteDataType]| {
+            ensure!(
+                types.len() == 2
+                    && types
+                        .iter()
+                        .all(|t| ConcreteDataType::numerics().contains(t)),
+                CreateAccumulatorSnafu {
+                    err_msg: format!(
+                        "\"{NAME}\" aggregate function expects two numbers, have: {types:?}"
+                    ),
+                }
+            );
+            Ok(Box::new(ApproxPercentile::default()))
+        });
+        creator
+    }
+
+    fn output_type(&self) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::float64_datatype())
+    }
+
+    fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+        Ok(vec![
+            ConcreteDataType::list_datatype(ConcreteDataType::int64_datatype()),
+            ConcreteDataType::list_datatype(ConcreteDataType::uint64_datatype()),
+            ConcreteDataType::uint64_datatype(),
+            ConcreteDataType::float64_datatype(),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use datatypes::vectors::ConstantVector;
+
+    use super::*;
+
+    fn percentile(p: f64, len: usize) -> VectorRef {
+        Arc::new(ConstantVector::new(
+            Arc::new(Float64Vector::from_vec(vec![p])),
+            len,
+        ))
+    }
+
+    fn assert_accurate(expected: f64, actual: Value) {
+        let Value::Float64(actual) = actual else {
+            panic!("expect float64, have: {actual:?}");
+        };
+        assert!(
+            (actual.0 - expected).abs() <= expected.abs() * RELATIVE_ACCURACY,
+            "expected: {expected}, actual: {actual}"
+        );
+    }
+
+    #[test]
+    fn test_sketch() {
+        let mut sketch = DdSketch::default();
+        assert_eq!(None, sketch.quantile(0.5));
+        for value in -500..=1000 {
+            sketch.add(value as f64);
+        }
+        assert_eq!(Some(0.0), sketch.quantile(500.0 / 1500.0));
+        for (q, expected) in [(0.0, -500.0), (0.1, -350.0), (0.9, 850.0), (1.0, 1000.0)] {
+            assert_accurate(expected, Value::from(sketch.quantile(q).unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_sketch_collapse() {
+        let mut sketch = DdSketch::default();
+        let gamma = sketch.gamma;
+        for i in -2048..2048 {
+            sketch.add(gamma.powi(i));
+            sketch.add(-gamma.powi(i));
+        }
+        assert_eq!(MAX_BINS, sketch.bins.len());
+        assert_eq!(8192, sketch.count);
+        // The largest absolute values are still accurate.
+        assert_accurate(
+            -gamma.powi(2047),
+            Value::from(sketch.quantile(0.0).unwrap()),
+        );
+        assert_accurate(gamma.powi(2047), Value::from(sketch.quantile(1.0).unwrap()));
+    }
+
+    #[test]
+    fn test_update_and_merge_batch() {
+        let mut approx = ApproxPercentile::default();
+        approx.update_batch(&[]).unwrap();
+        assert_eq!(Value::Null, approx.evaluate().unwrap());
+
+        let values: VectorRef = Arc::new(Float64Vector::from_vec(
+            (1..=100).map(|v| v as f64).collect(),
+        ));
+        approx
+            .update_batch(&[values.clone(), percentile(90.0, 100)])
+            .unwrap();
+        assert_accurate(90.0, approx.evaluate().unwrap());
+        assert!(approx
+            .update_batch(&[values, percentile(50.0, 100)])
+            .is_err());
+
+        // Merges the states of two partitions.
+        let mut merged = ApproxPercentile::default();
+        let types = ApproxPercentileAccumulatorCreator::default()
+            .state_types()
+            .unwrap();
+        for _ in 0..2 {
+            let states = approx
+                .state()
+                .unwrap()
+                .into_iter()
+                .zip(types.iter())
+                .map(|(state, data_type)| {
+                    let mut builder = data_type.create_mutable_vector(1);
+                    builder.push_value_ref(state.as_value_ref());
+                    builder.to_vector()
+                })
+                .collect::<Vec<_>>();
+            merged.merge_batch(&states).unwrap();
+        }
+        assert_eq!(200, merged.sketch.count);
+        assert_accurate(90.0, merged.evaluate().unwrap());
+    }
+}
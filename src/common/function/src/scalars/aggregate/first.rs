@@ -0,0 +1,198 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_function_macro::{as_aggr_func_creator, AggrFuncTypeStore};
+use common_query::error::{BadAccumulatorImplSnafu, CreateAccumulatorSnafu, Result};
+use common_query::logical_plan::{Accumulator, AggregateFunctionCreator};
+use common_query::prelude::*;
+use common_time::Timestamp;
+use datatypes::prelude::*;
+use snafu::ensure;
+
+/// `first(value, ts)` and `last(value, ts)` return the value with the smallest
+/// or the largest timestamp in the group, null values are ignored.
+#[derive(Debug, Default)]
+pub struct FirstLast {
+    last: bool,
+    selected: Option<(Value, Timestamp)>,
+}
+
+impl FirstLast {
+    fn new(last: bool) -> Self {
+        Self {
+            last,
+            selected: None,
+        }
+    }
+
+    fn update(&mut self, values: &VectorRef, timestamps: &VectorRef) {
+        for i in 0..values.len() {
+            let value = values.get(i);
+            let Some(ts) = timestamps.get(i).as_timestamp() else {
+                continue;
+            };
+            if value.is_null() {
+                continue;
+            }
+            let replace = match &self.selected {
+                Some((_, selected)) if self.last => ts > *selected,
+                Some((_, selected)) => ts < *selected,
+                None => true,
+            };
+            if replace {
+                self.selected = Some((value, ts));
+            }
+        }
+    }
+}
+
+impl Accumulator for FirstLast {
+    fn state(&self) -> Result<Vec<Value>> {
+        match &self.selected {
+            Some((value, ts)) => Ok(vec![value.clone(), Value::Timestamp(*ts)]),
+            None => Ok(vec![Value::Null, Value::Null]),
+        }
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        ensure!(values.len() == 2, InvalidInputStateSnafu);
+
+        self.update(&values[0], &values[1]);
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+        ensure!(
+            states.len() == 2,
+            BadAccumulatorImplSnafu {
+                err_msg: "expect 2 states in `merge_batch`",
+            }
+        );
+
+        self.update(&states[0], &states[1]);
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<Value> {
+        Ok(self
+            .selected
+            .as_ref()
+            .map(|(value, _)| value.clone())
+            .unwrap_or(Value::Null))
+    }
+}
+
+pub(crate) fn create_first_last(
+    name: &str,
+    types: &[ConcreteDataType],
+    last: bool,
+) -> Result<Box<dyn Accumulator>> {
+    ensure!(
+        types.len() == 2 && matches!(types[1], ConcreteDataType::Timestamp(_)),
+        CreateAccumulatorSnafu {
+            err_msg: format!(
+                "\"{name}\" aggregate function expects a value and a timestamp, have: {types:?}"
+            ),
+        }
+    );
+    Ok(Box::new(FirstLast::new(last)))
+}
+
+pub(crate) fn first_last_state_types(
+    input_types: Vec<ConcreteDataType>,
+) -> Result<Vec<ConcreteDataType>> {
+    ensure!(input_types.len() == 2, InvalidInputStateSnafu);
+    Ok(input_types)
+}
+
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+pub struct FirstAccumulatorCreator {}
+
+impl AggregateFunctionCreator for FirstAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        Arc::new(|types: &[ConcreteDataType]| create_first_last("FIRST", types, false))
+    }
+
+    fn output_type(&self) -> Result<ConcreteDataType> {
+        Ok(first_last_state_types(self.input_types()?)?.remove(0))
+    }
+
+    fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+        first_last_state_types(self.input_types()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use datatypes::vectors::{Int32Vector, StringVector, TimestampMillisecondVector};
+
+    use super::*;
+
+    #[test]
+    fn test_update_batch() {
+        let values: VectorRef = Arc::new(StringVector::from(vec![
+            Some("a"),
+            Some("b"),
+            None,
+            Some("d"),
+        ]));
+        let timestamps: VectorRef = Arc::new(TimestampMillisecondVector::from(vec![
+            Some(2),
+            Some(1),
+            Some(0),
+            None,
+        ]));
+
+        let mut first = FirstLast::new(false);
+        first.update_batch(&[]).unwrap();
+        assert_eq!(Value::Null, first.evaluate().unwrap());
+        first
+            .update_batch(&[values.clone(), timestamps.clone()])
+            .unwrap();
+        assert_eq!(Value::from("b"), first.evaluate().unwrap());
+
+        let mut last = FirstLast::new(true);
+        last.update_batch(&[values, timestamps]).unwrap();
+        assert_eq!(Value::from("a"), last.evaluate().unwrap());
+    }
+
+    #[test]
+    fn test_merge_batch() {
+        let mut last = FirstLast::new(true);
+        let values: VectorRef = Arc::new(Int32Vector::from(vec![Some(1), None, Some(3)]));
+        let timestamps: VectorRef = Arc::new(TimestampMillisecondVector::from(vec![
+            Some(10),
+            None,
+            Some(5),
+        ]));
+        last.merge_batch(&[values, timestamps]).unwrap();
+        assert_eq!(
+            vec![
+                Value::from(1),
+                Value::Timestamp(Timestamp::new_millisecond(10))
+            ],
+            last.state().unwrap()
+        );
+        assert_eq!(Value::from(1), last.evaluate().unwrap());
+    }
+}
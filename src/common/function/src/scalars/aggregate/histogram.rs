@@ -0,0 +1,272 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_function_macro::{as_aggr_func_creator, AggrFuncTypeStore};
+use common_query::error::{
+    BadAccumulatorImplSnafu, CreateAccumulatorSnafu, DowncastVectorSnafu, FromScalarValueSnafu,
+    InvalidFuncArgsSnafu, Result,
+};
+use common_query::logical_plan::{Accumulator, AggregateFunctionCreator};
+use common_query::prelude::*;
+use datatypes::prelude::*;
+use datatypes::value::ListValue;
+use datatypes::vectors::{ListVector, UInt64Vector};
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::scalars::aggregate::utils::{constant_f64_arg, to_f64_vector};
+
+const NAME: &str = "HISTOGRAM";
+
+/// `histogram(value, min, max, buckets)` counts the values into `buckets`
+/// equal width buckets between `min` and `max`. Like TimescaleDB, the result
+/// has `buckets + 2` counts, the first one counts values less than `min` and
+/// the last one counts values not less than `max`.
+#[derive(Debug, Default)]
+pub struct Histogram {
+    bounds: Option<(f64, f64, usize)>,
+    counts: Vec<u64>,
+}
+
+impl Histogram {
+    fn set_bounds(&mut self, min: f64, max: f64, buckets: f64) -> Result<()> {
+        ensure!(
+            min < max && buckets >= 1.0 && buckets.fract() == 0.0,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "\"{NAME}\" expects min < max and a positive number of buckets, \
+                    have: min={min}, max={max}, buckets={buckets}"
+                ),
+            }
+        );
+        let bounds = (min, max, buckets as usize);
+        if let Some(existing) = self.bounds {
+            ensure!(
+                existing == bounds,
+                InvalidFuncArgsSnafu {
+                    err_msg: format!("The bounds of \"{NAME}\" must be constant"),
+                }
+            );
+        } else {
+            self.bounds = Some(bounds);
+            self.counts = vec![0; bounds.2 + 2];
+        }
+        Ok(())
+    }
+
+    fn bucket(&self, value: f64) -> usize {
+        let (min, max, buckets) = self.bounds.unwrap();
+        if value < min {
+            0
+        } else if value >= max {
+            buckets + 1
+        } else {
+            (((value - min) / (max - min) * buckets as f64) as usize + 1).min(buckets)
+        }
+    }
+
+    fn merge_counts(&mut self, counts: &UInt64Vector) -> Result<()> {
+        if self.counts.is_empty() {
+            self.counts = vec![0; counts.len()];
+        }
+        ensure!(
+            self.counts.len() == counts.len(),
+            BadAccumulatorImplSnafu {
+                err_msg: "histograms with different buckets can't be merged",
+            }
+        );
+        for (count, other) in self.counts.iter_mut().zip(counts.iter_data()) {
+            *count += other.unwrap_or_default();
+        }
+        Ok(())
+    }
+}
+
+impl Accumulator for Histogram {
+    fn state(&self) -> Result<Vec<Value>> {
+        let counts = self.counts.iter().map(|c| Value::from(*c)).collect();
+        Ok(vec![Value::List(ListValue::new(
+            Some(Box::new(counts)),
+            ConcreteDataType::uint64_datatype(),
+        ))])
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        ensure!(values.len() == 4, InvalidInputStateSnafu);
+        if values[0].is_empty() {
+            return Ok(());
+        }
+
+        self.set_bounds(
+            constant_f64_arg(&values[1], NAME, "min")?,
+            constant_f64_arg(&values[2], NAME, "max")?,
+            constant_f64_arg(&values[3], NAME, "buckets")?,
+        )?;
+        for value in to_f64_vector(&values[0])?.iter_data().flatten() {
+            if !value.is_nan() {
+                let bucket = self.bucket(value);
+                self.counts[bucket] += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+        ensure!(
+            states.len() == 1,
+            BadAccumulatorImplSnafu {
+                err_msg: "expect 1 state in `merge_batch`",
+            }
+        );
+
+        let states = states[0]
+            .as_any()
+            .downcast_ref::<ListVector>()
+            .with_context(|| DowncastVectorSnafu {
+                err_msg: format!(
+                    "expect ListVector, got vector type {}",
+                    states[0].vector_type_name()
+                ),
+            })?;
+        for counts in states.values_iter() {
+            let Some(counts) = counts.context(FromScalarValueSnafu)? else {
+                continue;
+            };
+            if counts.is_empty() {
+                continue;
+            }
+            let counts = counts
+                .as_any()
+                .downcast_ref::<UInt64Vector>()
+                .with_context(|| DowncastVectorSnafu {
+                    err_msg: format!(
+                        "expect UInt64Vector, got vector type {}",
+                        counts.vector_type_name()
+                    ),
+                })?;
+            self.merge_counts(counts)?;
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<Value> {
+        if self.counts.is_empty() {
+            return Ok(Value::Null);
+        }
+        Ok(self.state()?.remove(0))
+    }
+}
+
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+pub struct HistogramAccumulatorCreator {}
+
+impl AggregateFunctionCreator for HistogramAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        let creator: AccumulatorCreatorFunction = Arc::new(move |types: &[ConcreteDataType]| {
+            ensure!(
+                types.len() == 4
+                    && types
+                        .iter()
+                        .all(|t| ConcreteDataType::numerics().contains(t)),
+                CreateAccumulatorSnafu {
+                    err_msg: format!(
+                        "\"{NAME}\" aggregate function expects four numbers, have: {types:?}"
+                    ),
+                }
+            );
+            Ok(Box::new(Histogram::default()))
+        });
+        creator
+    }
+
+    fn output_type(&self) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::list_datatype(
+            ConcreteDataType::uint64_datatype(),
+        ))
+    }
+
+    fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+        Ok(vec![self.output_type()?])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use datatypes::vectors::{ConstantVector, Float64Vector, Int64Vector};
+
+    use super::*;
+
+    fn constant(value: i64, len: usize) -> VectorRef {
+        Arc::new(ConstantVector::new(
+            Arc::new(Int64Vector::from_vec(vec![value])),
+            len,
+        ))
+    }
+
+    fn counts(counts: Vec<u64>) -> Value {
+        Value::List(ListValue::new(
+            Some(Box::new(counts.into_iter().map(Value::from).collect())),
+            ConcreteDataType::uint64_datatype(),
+        ))
+    }
+
+    #[test]
+    fn test_update_batch() {
+        let mut histogram = Histogram::default();
+        histogram.update_batch(&[]).unwrap();
+        assert_eq!(Value::Null, histogram.evaluate().unwrap());
+
+        let values: VectorRef = Arc::new(Float64Vector::from(vec![
+            Some(-1.0),
+            Some(0.0),
+            Some(4.9),
+            None,
+            Some(5.0),
+            Some(9.99),
+            Some(10.0),
+        ]));
+        histogram
+            .update_batch(&[values, constant(0, 7), constant(10, 7), constant(2, 7)])
+            .unwrap();
+        assert_eq!(counts(vec![1, 2, 2, 1]), histogram.evaluate().unwrap());
+
+        let values: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0]));
+        let result =
+            histogram.update_batch(&[values, constant(0, 1), constant(5, 1), constant(2, 1)]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_invalid_bounds() {
+        let values: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0]));
+        for (min, max, buckets) in [(1, 1, 2), (0, 10, 0)] {
+            let mut histogram = Histogram::default();
+            let result = histogram.update_batch(&[
+                values.clone(),
+                constant(min, 1),
+                constant(max, 1),
+                constant(buckets, 1),
+            ]);
+            assert!(result.is_err());
+        }
+    }
+}
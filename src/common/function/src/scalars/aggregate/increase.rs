@@ -0,0 +1,337 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_function_macro::{as_aggr_func_creator, AggrFuncTypeStore};
+use common_query::error::{
+    BadAccumulatorImplSnafu, CreateAccumulatorSnafu, DowncastVectorSnafu, Result,
+};
+use common_query::logical_plan::{Accumulator, AggregateFunctionCreator};
+use common_query::prelude::*;
+use datatypes::prelude::*;
+use datatypes::vectors::{Float64Vector, Int64Vector};
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::scalars::aggregate::utils::{timestamp_millis, to_f64_vector};
+
+/// `increase(value, ts)` returns how much a counter increases in the group,
+/// and `rate(value, ts)` returns the per-second increase. When the counter
+/// decreases, it's treated as a reset and counted from zero. Unlike PromQL,
+/// the result is not extrapolated to the boundaries of the range.
+///
+/// Only the first and the last samples and the increase between them are
+/// kept. Samples in a batch are sorted by timestamp, but samples of different
+/// batches or states are expected to be in time order: when their time ranges
+/// overlap, the increase between them is unknown and ignored.
+#[derive(Debug, Default)]
+pub struct Increase {
+    per_second: bool,
+    segment: Option<Segment>,
+}
+
+/// The first and the last samples as `(ts, value)` and the increase between
+/// them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Segment {
+    first: (i64, f64),
+    last: (i64, f64),
+    increase: f64,
+}
+
+impl Segment {
+    fn new(ts: i64, value: f64) -> Self {
+        Self {
+            first: (ts, value),
+            last: (ts, value),
+            increase: 0.0,
+        }
+    }
+
+    /// Merges two segments, `other` may be before or after `self`.
+    fn merge(self, other: Segment) -> Segment {
+        let (earlier, later) = if other.first.0 < self.first.0 {
+            (other, self)
+        } else {
+            (self, other)
+        };
+        let gap = if later.first.0 >= earlier.last.0 {
+            counter_delta(earlier.last.1, later.first.1)
+        } else {
+            0.0
+        };
+        Segment {
+            first: earlier.first,
+            last: if later.last.0 >= earlier.last.0 {
+                later.last
+            } else {
+                earlier.last
+            },
+            increase: earlier.increase + later.increase + gap,
+        }
+    }
+}
+
+/// Returns the increase from `prev` to `curr`, a decrease is a counter reset.
+fn counter_delta(prev: f64, curr: f64) -> f64 {
+    if curr < prev {
+        curr
+    } else {
+        curr - prev
+    }
+}
+
+impl Increase {
+    pub(crate) fn new(per_second: bool) -> Self {
+        Self {
+            per_second,
+            segment: None,
+        }
+    }
+
+    fn merge(&mut self, segment: Segment) {
+        self.segment = Some(match self.segment {
+            Some(existing) => existing.merge(segment),
+            None => segment,
+        });
+    }
+
+    fn push(&mut self, values: &Float64Vector, timestamps: &VectorRef) {
+        let mut samples = (0..values.len())
+            .filter_map(|i| Some((timestamp_millis(timestamps, i)?, values.get_data(i)?)))
+            .collect::<Vec<_>>();
+        samples.sort_unstable_by_key(|(ts, _)| *ts);
+
+        let mut samples = samples.into_iter();
+        let Some((ts, value)) = samples.next() else {
+            return;
+        };
+        let segment = samples.fold(Segment::new(ts, value), |mut segment, sample| {
+            segment.increase += counter_delta(segment.last.1, sample.1);
+            segment.last = sample;
+            segment
+        });
+        self.merge(segment);
+    }
+
+    /// Returns the increase and the duration in milliseconds, requires at
+    /// least two samples at different timestamps.
+    fn increase(&self) -> Option<(f64, i64)> {
+        let segment = self.segment?;
+        let duration = segment.last.0 - segment.first.0;
+        (duration > 0).then_some((segment.increase, duration))
+    }
+}
+
+impl Accumulator for Increase {
+    fn state(&self) -> Result<Vec<Value>> {
+        match &self.segment {
+            Some(segment) => Ok(vec![
+                Value::from(segment.first.0),
+                Value::from(segment.first.1),
+                Value::from(segment.last.0),
+                Value::from(segment.last.1),
+                Value::from(segment.increase),
+            ]),
+            None => Ok(vec![Value::Null; 5]),
+        }
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        ensure!(values.len() == 2, InvalidInputStateSnafu);
+
+        self.push(&to_f64_vector(&values[0])?, &values[1]);
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+        ensure!(
+            states.len() == 5,
+            BadAccumulatorImplSnafu {
+                err_msg: "expect 5 states in `merge_batch`",
+            }
+        );
+
+        let first_ts = downcast::<Int64Vector>(&states[0])?;
+        let first_value = downcast::<Float64Vector>(&states[1])?;
+        let last_ts = downcast::<Int64Vector>(&states[2])?;
+        let last_value = downcast::<Float64Vector>(&states[3])?;
+        let increase = downcast::<Float64Vector>(&states[4])?;
+        for i in 0..first_ts.len() {
+            if let (
+                Some(first_ts),
+                Some(first_value),
+                Some(last_ts),
+                Some(last_value),
+                Some(increase),
+            ) = (
+                first_ts.get_data(i),
+                first_value.get_data(i),
+                last_ts.get_data(i),
+                last_value.get_data(i),
+                increase.get_data(i),
+            ) {
+                self.merge(Segment {
+                    first: (first_ts, first_value),
+                    last: (last_ts, last_value),
+                    increase,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<Value> {
+        let Some((increase, duration)) = self.increase() else {
+            return Ok(Value::Null);
+        };
+        if !self.per_second {
+            return Ok(Value::from(increase));
+        }
+        Ok(Value::from(increase * 1000.0 / duration as f64))
+    }
+}
+
+fn downcast<T: Vector + 'static>(vector: &VectorRef) -> Result<&T> {
+    vector
+        .as_any()
+        .downcast_ref::<T>()
+        .with_context(|| DowncastVectorSnafu {
+            err_msg: format!(
+                "expect {}, got vector type {}",
+                std::any::type_name::<T>(),
+                vector.vector_type_name()
+            ),
+        })
+}
+
+pub(crate) fn create_increase(
+    name: &str,
+    types: &[ConcreteDataType],
+    per_second: bool,
+) -> Result<Box<dyn Accumulator>> {
+    ensure!(
+        types.len() == 2
+            && ConcreteDataType::numerics().contains(&types[0])
+            && matches!(types[1], ConcreteDataType::Timestamp(_)),
+        CreateAccumulatorSnafu {
+            err_msg: format!(
+                "\"{name}\" aggregate function expects a number and a timestamp, have: {types:?}"
+            ),
+        }
+    );
+    Ok(Box::new(Increase::new(per_second)))
+}
+
+pub(crate) fn increase_state_types() -> Vec<ConcreteDataType> {
+    vec![
+        ConcreteDataType::int64_datatype(),
+        ConcreteDataType::float64_datatype(),
+        ConcreteDataType::int64_datatype(),
+        ConcreteDataType::float64_datatype(),
+        ConcreteDataType::float64_datatype(),
+    ]
+}
+
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+pub struct IncreaseAccumulatorCreator {}
+
+impl AggregateFunctionCreator for IncreaseAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        Arc::new(|types: &[ConcreteDataType]| create_increase("INCREASE", types, false))
+    }
+
+    fn output_type(&self) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::float64_datatype())
+    }
+
+    fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+        Ok(increase_state_types())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use datatypes::vectors::{Int32Vector, TimestampSecondVector};
+
+    use super::*;
+
+    #[test]
+    fn test_update_batch() {
+        let mut increase = Increase::new(false);
+        increase.update_batch(&[]).unwrap();
+        assert_eq!(Value::Null, increase.evaluate().unwrap());
+
+        // The counter resets between 8 and 3.
+        let values: VectorRef = Arc::new(Int32Vector::from(vec![
+            Some(3),
+            Some(5),
+            None,
+            Some(1),
+            Some(8),
+        ]));
+        let timestamps: VectorRef = Arc::new(TimestampSecondVector::from(vec![
+            Some(40),
+            Some(50),
+            Some(20),
+            Some(0),
+            Some(10),
+        ]));
+        increase
+            .update_batch(&[values.clone(), timestamps.clone()])
+            .unwrap();
+        assert_eq!(Value::from(12.0), increase.evaluate().unwrap());
+
+        let mut rate = Increase::new(true);
+        rate.update_batch(&[values, timestamps]).unwrap();
+        assert_eq!(Value::from(0.24), rate.evaluate().unwrap());
+    }
+
+    #[test]
+    fn test_merge_batch() {
+        let mut first = Increase::new(false);
+        let values: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 4.0]));
+        let timestamps: VectorRef = Arc::new(TimestampSecondVector::from_vec(vec![0, 20]));
+        first.update_batch(&[values, timestamps]).unwrap();
+        let mut second = Increase::new(false);
+        let values: VectorRef = Arc::new(Float64Vector::from_vec(vec![2.0, 6.0]));
+        let timestamps: VectorRef = Arc::new(TimestampSecondVector::from_vec(vec![30, 40]));
+        second.update_batch(&[values, timestamps]).unwrap();
+
+        // The states are merged in any order, the counter resets between them.
+        let mut merged = Increase::new(false);
+        for accumulator in [second, first] {
+            let states = accumulator
+                .state()
+                .unwrap()
+                .into_iter()
+                .zip(increase_state_types())
+                .map(|(state, data_type)| {
+                    let mut builder = data_type.create_mutable_vector(1);
+                    builder.push_value_ref(state.as_value_ref());
+                    builder.to_vector()
+                })
+                .collect::<Vec<_>>();
+            merged.merge_batch(&states).unwrap();
+        }
+        assert_eq!(Value::from(9.0), merged.evaluate().unwrap());
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_function_macro::{as_aggr_func_creator, AggrFuncTypeStore};
+use common_query::error::Result;
+use common_query::logical_plan::AggregateFunctionCreator;
+use common_query::prelude::*;
+use datatypes::prelude::*;
+
+use crate::scalars::aggregate::first::{create_first_last, first_last_state_types};
+
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+pub struct LastAccumulatorCreator {}
+
+impl AggregateFunctionCreator for LastAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        Arc::new(|types: &[ConcreteDataType]| create_first_last("LAST", types, true))
+    }
+
+    fn output_type(&self) -> Result<ConcreteDataType> {
+        Ok(first_last_state_types(self.input_types()?)?.remove(0))
+    }
+
+    fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+        first_last_state_types(self.input_types()?)
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers shared by the time series aggregate functions.
+
+use common_query::error::{FromArrowArraySnafu, InvalidFuncArgsSnafu, Result, TypeCastSnafu};
+use common_time::timestamp::TimeUnit;
+use datatypes::arrow::compute;
+use datatypes::arrow::datatypes::DataType;
+use datatypes::prelude::*;
+use datatypes::vectors::Float64Vector;
+use snafu::{ensure, ResultExt};
+
+/// Casts a numeric vector to a float64 vector.
+pub(crate) fn to_f64_vector(vector: &VectorRef) -> Result<Float64Vector> {
+    let array =
+        compute::cast(&vector.to_arrow_array(), &DataType::Float64).context(TypeCastSnafu {
+            typ: DataType::Float64,
+        })?;
+    Float64Vector::try_from_arrow_array(array).context(FromArrowArraySnafu)
+}
+
+/// Returns the timestamp at `index` in milliseconds.
+pub(crate) fn timestamp_millis(vector: &VectorRef, index: usize) -> Option<i64> {
+    vector
+        .get(index)
+        .as_timestamp()
+        .and_then(|ts| ts.convert_to(TimeUnit::Millisecond))
+        .map(|ts| ts.value())
+}
+
+/// Gets the value of a float argument which must be the same in all rows,
+/// like the bounds of `histogram`. `vector` must not be empty.
+pub(crate) fn constant_f64_arg(vector: &VectorRef, function: &str, arg: &str) -> Result<f64> {
+    let values = to_f64_vector(vector)?;
+    let first = values.get_data(0);
+    ensure!(
+        first.is_some() && values.iter_data().all(|v| v == first),
+        InvalidFuncArgsSnafu {
+            err_msg: format!("The {arg} of \"{function}\" must be a non-null constant"),
+        }
+    );
+    Ok(first.unwrap())
+}
@@ -0,0 +1,43 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Functions to fill the gaps between time buckets. `time_bucket_gapfill` only
+//! buckets the timestamps at runtime, the missing buckets are generated by the
+//! query planner, which also treats `locf` and `interpolate` as fill markers.
+
+mod interpolate;
+mod locf;
+mod time_bucket_gapfill;
+
+use std::sync::Arc;
+
+use interpolate::InterpolateFunction;
+use locf::LocfFunction;
+pub use time_bucket_gapfill::{parse_bucket_interval, time_bucket, TimeBucketGapfillFunction};
+
+use crate::scalars::function_registry::FunctionRegistry;
+
+pub const TIME_BUCKET_GAPFILL: &str = "time_bucket_gapfill";
+pub const LOCF: &str = "locf";
+pub const INTERPOLATE: &str = "interpolate";
+
+pub(crate) struct GapfillFunction;
+
+impl GapfillFunction {
+    pub fn register(registry: &FunctionRegistry) {
+        registry.register(Arc::new(TimeBucketGapfillFunction::default()));
+        registry.register(Arc::new(LocfFunction::default()));
+        registry.register(Arc::new(InterpolateFunction::default()));
+    }
+}
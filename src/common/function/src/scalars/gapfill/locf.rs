@@ -0,0 +1,93 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use common_query::error::{InvalidFuncArgsSnafu, Result};
+use common_query::prelude::{Signature, Volatility};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::vectors::VectorRef;
+use snafu::ensure;
+
+use crate::scalars::function::{Function, FunctionContext};
+use crate::scalars::gapfill::LOCF;
+
+/// `locf(value)` carries the last observed value forward into the buckets
+/// generated by a `time_bucket_gapfill` query. The filling is done by the planner, so
+/// evaluating it just returns the value itself.
+#[derive(Clone, Debug, Default)]
+pub struct LocfFunction;
+
+impl Function for LocfFunction {
+    fn name(&self) -> &str {
+        LOCF
+    }
+
+    fn return_type(&self, input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(input_types
+            .first()
+            .cloned()
+            .unwrap_or_else(ConcreteDataType::float64_datatype))
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::any(1, Volatility::Immutable)
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 1,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect exactly one, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+        Ok(columns[0].clone())
+    }
+}
+
+impl fmt::Display for LocfFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LOCF")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::vectors::Float64Vector;
+
+    use super::*;
+
+    #[test]
+    fn test_locf() {
+        let f = LocfFunction::default();
+        assert_eq!("locf", f.name());
+        assert_eq!(
+            ConcreteDataType::int64_datatype(),
+            f.return_type(&[ConcreteDataType::int64_datatype()])
+                .unwrap()
+        );
+
+        let values: VectorRef = Arc::new(Float64Vector::from(vec![Some(1.0), None]));
+        let vector = f
+            .eval(FunctionContext::default(), &[values.clone()])
+            .unwrap();
+        assert_eq!(values, vector);
+        assert!(f.eval(FunctionContext::default(), &[]).is_err());
+    }
+}
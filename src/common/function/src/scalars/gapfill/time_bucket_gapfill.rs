@@ -0,0 +1,181 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::sync::Arc;
+
+use common_query::error::{InvalidFuncArgsSnafu, Result, UnsupportedInputDataTypeSnafu};
+use common_query::prelude::{Signature, Volatility};
+use common_time::timestamp::TimeUnit;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::value::Value;
+use datatypes::vectors::{TimestampMillisecondVector, VectorRef};
+use snafu::ensure;
+
+use crate::scalars::function::{Function, FunctionContext};
+use crate::scalars::gapfill::TIME_BUCKET_GAPFILL;
+
+/// `time_bucket_gapfill(interval, ts, start, end)` truncates `ts` to the start
+/// of its bucket in milliseconds. `start` and `end` are only used by the
+/// planner to generate the missing buckets.
+#[derive(Clone, Debug, Default)]
+pub struct TimeBucketGapfillFunction;
+
+/// Parses the bucket interval like `1m` or `30s` into milliseconds.
+pub fn parse_bucket_interval(interval: &str) -> Result<i64> {
+    let millis = humantime::parse_duration(interval)
+        .map(|d| d.as_millis() as i64)
+        .map_err(|e| {
+            InvalidFuncArgsSnafu {
+                err_msg: format!("Invalid bucket interval '{interval}': {e}"),
+            }
+            .build()
+        })?;
+    ensure!(
+        millis > 0,
+        InvalidFuncArgsSnafu {
+            err_msg: format!("Bucket interval must be positive, have: '{interval}'"),
+        }
+    );
+    Ok(millis)
+}
+
+/// Returns the start of the bucket which `ts` belongs to, buckets are aligned
+/// to the unix epoch.
+#[inline]
+pub fn time_bucket(ts: i64, interval: i64) -> i64 {
+    ts.div_euclid(interval) * interval
+}
+
+impl Function for TimeBucketGapfillFunction {
+    fn name(&self) -> &str {
+        TIME_BUCKET_GAPFILL
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::timestamp_millisecond_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::any(4, Volatility::Immutable)
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 4,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect exactly four, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+        ensure!(
+            matches!(
+                (columns[0].data_type(), columns[1].data_type()),
+                (ConcreteDataType::String(_), ConcreteDataType::Timestamp(_))
+            ),
+            UnsupportedInputDataTypeSnafu {
+                function: TIME_BUCKET_GAPFILL,
+                datatypes: columns.iter().map(|c| c.data_type()).collect::<Vec<_>>(),
+            }
+        );
+        if columns[1].is_empty() {
+            return Ok(Arc::new(TimestampMillisecondVector::from_vec(vec![])));
+        }
+
+        let interval = match columns[0].get(0) {
+            Value::String(interval) => parse_bucket_interval(interval.as_utf8())?,
+            _ => {
+                return InvalidFuncArgsSnafu {
+                    err_msg: "The bucket interval must not be null",
+                }
+                .fail()
+            }
+        };
+        let buckets = (0..columns[1].len())
+            .map(|i| {
+                columns[1]
+                    .get(i)
+                    .as_timestamp()
+                    .and_then(|ts| ts.convert_to(TimeUnit::Millisecond))
+                    .map(|ts| time_bucket(ts.value(), interval))
+            })
+            .collect::<Vec<_>>();
+        Ok(Arc::new(TimestampMillisecondVector::from(buckets)))
+    }
+}
+
+impl fmt::Display for TimeBucketGapfillFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TIME_BUCKET_GAPFILL")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_query::prelude::TypeSignature;
+    use datatypes::vectors::{ConstantVector, StringVector, TimestampSecondVector};
+
+    use super::*;
+
+    #[test]
+    fn test_time_bucket_gapfill() {
+        let f = TimeBucketGapfillFunction::default();
+        assert_eq!("time_bucket_gapfill", f.name());
+        assert_eq!(
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            f.return_type(&[]).unwrap()
+        );
+        assert!(matches!(
+            f.signature(),
+            Signature {
+                type_signature: TypeSignature::Any(4),
+                volatility: Volatility::Immutable
+            }
+        ));
+
+        let interval = Arc::new(ConstantVector::new(
+            Arc::new(StringVector::from(vec!["1m"])),
+            4,
+        ));
+        let bound = Arc::new(ConstantVector::new(
+            Arc::new(StringVector::from(vec!["1970-01-01 00:00:00"])),
+            4,
+        ));
+        let ts = Arc::new(TimestampSecondVector::from(vec![
+            Some(0),
+            Some(59),
+            None,
+            Some(-1),
+        ]));
+        let args: Vec<VectorRef> = vec![interval, ts, bound.clone(), bound];
+        let vector = f.eval(FunctionContext::default(), &args).unwrap();
+        let expect: VectorRef = Arc::new(TimestampMillisecondVector::from(vec![
+            Some(0),
+            Some(0),
+            None,
+            Some(-60_000),
+        ]));
+        assert_eq!(expect, vector);
+    }
+
+    #[test]
+    fn test_parse_bucket_interval() {
+        assert_eq!(90_000, parse_bucket_interval("1m 30s").unwrap());
+        assert_eq!(10, parse_bucket_interval("10ms").unwrap());
+        assert!(parse_bucket_interval("0s").is_err());
+        assert!(parse_bucket_interval("1x").is_err());
+    }
+}
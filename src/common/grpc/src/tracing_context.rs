@@ -0,0 +1,131 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Carriers of the tracing context in gRPC metadata and HTTP headers.
+
+use std::task::{Context, Poll};
+
+use common_telemetry::tracing::instrument::{Instrument, Instrumented};
+use common_telemetry::tracing::{info_span, Span};
+use common_telemetry::tracing_context::{self, Extractor, Injector};
+use tonic::codegen::http::{HeaderMap, Request};
+use tonic::metadata::{Ascii, MetadataKey, MetadataMap, MetadataValue};
+use tower::{Layer, Service};
+
+/// Injects the tracing context into gRPC metadata.
+pub struct MetadataInjector<'a>(pub &'a mut MetadataMap);
+
+impl<'a> Injector for MetadataInjector<'a> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(key), Ok(value)) = (
+            MetadataKey::<Ascii>::from_bytes(key.as_bytes()),
+            MetadataValue::<Ascii>::try_from(value.as_str()),
+        ) {
+            let _ = self.0.insert(key, value);
+        }
+    }
+}
+
+/// Extracts the tracing context from HTTP headers, which also carry the gRPC metadata.
+pub struct HeaderExtractor<'a>(pub &'a HeaderMap);
+
+impl<'a> Extractor for HeaderExtractor<'a> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+/// Wraps `message` in a gRPC request carrying the context of the current span.
+pub fn traced_request<T>(message: T) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    tracing_context::inject_current_context(&mut MetadataInjector(request.metadata_mut()));
+    request
+}
+
+/// Creates the span of an incoming HTTP or gRPC request, whose parent is the remote
+/// context in the request headers.
+pub fn make_request_span<B>(request: &Request<B>) -> Span {
+    let span = info_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri(),
+    );
+    tracing_context::set_remote_parent(&span, &HeaderExtractor(request.headers()));
+    span
+}
+
+/// Layer that handles each HTTP or gRPC request in the span made by [make_request_span].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingContextLayer;
+
+impl<S> Layer<S> for TracingContextLayer {
+    type Service = TracingContextService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TracingContextService { inner }
+    }
+}
+
+/// Service created by [TracingContextLayer].
+#[derive(Debug, Clone)]
+pub struct TracingContextService<S> {
+    inner: S,
+}
+
+impl<S, B> Service<Request<B>> for TracingContextService<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Instrumented<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let span = make_request_span(&request);
+        let future = {
+            let _enter = span.enter();
+            self.inner.call(request)
+        };
+        future.instrument(span)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_telemetry::tracing_context::TRACE_PARENT_HEADER;
+
+    use super::*;
+
+    #[test]
+    fn test_metadata_carriers() {
+        let trace_parent = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+        let mut metadata = MetadataMap::new();
+        MetadataInjector(&mut metadata).set(TRACE_PARENT_HEADER, trace_parent.to_string());
+        // Invalid keys are ignored.
+        MetadataInjector(&mut metadata).set("invalid key", "value".to_string());
+
+        let headers = metadata.into_headers();
+        let extractor = HeaderExtractor(&headers);
+        assert_eq!(Some(trace_parent), extractor.get(TRACE_PARENT_HEADER));
+        assert_eq!(vec![TRACE_PARENT_HEADER], extractor.keys());
+    }
+}
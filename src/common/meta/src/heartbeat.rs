@@ -15,3 +15,8 @@
 pub mod handler;
 pub mod mailbox;
 pub mod utils;
+
+/// Attribute of a region stat in heartbeats that reports the role of the region.
+pub const REGION_ROLE_ATTR: &str = "region_role";
+/// Value of [REGION_ROLE_ATTR] for read-only follower regions.
+pub const FOLLOWER_REGION_ROLE: &str = "follower";
@@ -68,6 +68,8 @@ pub enum Instruction {
     OpenRegion(RegionIdent),
     CloseRegion(RegionIdent),
     InvalidateTableCache(TableIdent),
+    /// Opens a read-only follower of the region.
+    OpenFollowerRegion(RegionIdent),
 }
 
 impl Display for Instruction {
@@ -76,6 +78,9 @@ impl Display for Instruction {
             Self::OpenRegion(region) => write!(f, "Instruction::OpenRegion({})", region),
             Self::CloseRegion(region) => write!(f, "Instruction::CloseRegion({})", region),
             Self::InvalidateTableCache(table) => write!(f, "Instruction::Invalidate({})", table),
+            Self::OpenFollowerRegion(region) => {
+                write!(f, "Instruction::OpenFollowerRegion({})", region)
+            }
         }
     }
 }
@@ -86,6 +91,7 @@ pub enum InstructionReply {
     OpenRegion(SimpleReply),
     CloseRegion(SimpleReply),
     InvalidateTableCache(SimpleReply),
+    OpenFollowerRegion(SimpleReply),
 }
 
 impl Display for InstructionReply {
@@ -96,6 +102,9 @@ impl Display for InstructionReply {
             Self::InvalidateTableCache(reply) => {
                 write!(f, "InstructionReply::Invalidate({})", reply)
             }
+            Self::OpenFollowerRegion(reply) => {
+                write!(f, "InstructionReply::OpenFollowerRegion({})", reply)
+            }
         }
     }
 }
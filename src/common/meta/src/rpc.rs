@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod ddl;
 pub mod lock;
 pub mod router;
 pub mod store;
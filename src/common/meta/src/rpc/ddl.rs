@@ -0,0 +1,151 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::meta::ddl::{
+    AllocateTableIdResponse as PbAllocateTableIdResponse, AlterTableRequest as PbAlterTableRequest,
+    AlterTableResponse as PbAlterTableResponse,
+};
+use api::v1::meta::ResponseHeader;
+use api::v1::AlterExpr;
+use prost::Message;
+
+use crate::error::{self, Result};
+use crate::rpc::util;
+
+#[derive(Debug, Clone)]
+pub struct AlterTableRequest {
+    pub alter_expr: AlterExpr,
+}
+
+impl From<AlterTableRequest> for PbAlterTableRequest {
+    fn from(req: AlterTableRequest) -> Self {
+        Self {
+            header: vec![],
+            alter_expr: req.alter_expr.encode_to_vec(),
+        }
+    }
+}
+
+impl AlterTableRequest {
+    #[inline]
+    pub fn new(alter_expr: AlterExpr) -> Self {
+        Self { alter_expr }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AlterTableResponse {
+    pub header: ResponseHeader,
+}
+
+impl TryFrom<PbAlterTableResponse> for AlterTableResponse {
+    type Error = error::Error;
+
+    fn try_from(pb: PbAlterTableResponse) -> Result<Self> {
+        let header = decode_response_header(&pb.header)?;
+        Ok(Self { header })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AllocateTableIdResponse {
+    pub header: ResponseHeader,
+    pub table_id: u32,
+}
+
+impl TryFrom<PbAllocateTableIdResponse> for AllocateTableIdResponse {
+    type Error = error::Error;
+
+    fn try_from(pb: PbAllocateTableIdResponse) -> Result<Self> {
+        let header = decode_response_header(&pb.header)?;
+        Ok(Self {
+            header,
+            table_id: pb.table_id,
+        })
+    }
+}
+
+fn decode_response_header(header: &[u8]) -> Result<ResponseHeader> {
+    let header = ResponseHeader::decode(header).map_err(|e| {
+        error::InvalidProtoMsgSnafu {
+            err_msg: format!("invalid response header: {e}"),
+        }
+        .build()
+    })?;
+    util::check_response_header(Some(&header))?;
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::alter_expr::Kind;
+    use api::v1::meta::Error as PbError;
+    use api::v1::{AlterExpr, RenameTable};
+
+    use super::*;
+
+    #[test]
+    fn test_alter_table_request_trans() {
+        let expr = AlterExpr {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "t1".to_string(),
+            kind: Some(Kind::RenameTable(RenameTable {
+                new_table_name: "t2".to_string(),
+            })),
+        };
+        let req = AlterTableRequest::new(expr.clone());
+        let into_req: PbAlterTableRequest = req.into();
+
+        assert!(into_req.header.is_empty());
+        assert_eq!(
+            expr,
+            AlterExpr::decode(into_req.alter_expr.as_slice()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_alter_table_response_trans() {
+        let pb = PbAlterTableResponse {
+            header: ResponseHeader::success(1).encode_to_vec(),
+        };
+        let res = AlterTableResponse::try_from(pb).unwrap();
+        assert_eq!(1, res.header.cluster_id);
+
+        let pb = PbAlterTableResponse {
+            header: ResponseHeader::failed(1, PbError::is_not_leader()).encode_to_vec(),
+        };
+        assert!(AlterTableResponse::try_from(pb).is_err());
+
+        let pb = PbAlterTableResponse { header: vec![0xff] };
+        assert!(AlterTableResponse::try_from(pb).is_err());
+    }
+
+    #[test]
+    fn test_allocate_table_id_response_trans() {
+        let pb = PbAllocateTableIdResponse {
+            header: ResponseHeader::success(1).encode_to_vec(),
+            table_id: 1024,
+        };
+        let res = AllocateTableIdResponse::try_from(pb).unwrap();
+        assert_eq!(1, res.header.cluster_id);
+        assert_eq!(1024, res.table_id);
+
+        let pb = PbAllocateTableIdResponse {
+            header: ResponseHeader::failed(1, PbError::is_not_leader()).encode_to_vec(),
+            table_id: 0,
+        };
+        assert!(AllocateTableIdResponse::try_from(pb).is_err());
+    }
+}
@@ -284,6 +284,53 @@ impl TableRoute {
             .get(&region_number)
             .and_then(|x| x.as_ref())
     }
+
+    pub fn find_follower_regions(&self, datanode: &Peer) -> Vec<u32> {
+        self.region_routes
+            .iter()
+            .filter(|x| x.follower_peers.contains(datanode))
+            .map(|x| x.region.id as u32)
+            .collect()
+    }
+
+    /// Finds the followers that serve reads in place of the `leader`, with the regions
+    /// each of them follows.
+    ///
+    /// Datanodes scan all regions of the table they hold, so each region of the `leader` is
+    /// matched with one of its followers which leads no region of the table and follows
+    /// only regions of the `leader` that are not matched yet. Returns `None` if any region
+    /// of the `leader` can't be matched.
+    pub fn find_followers_of_leader(&self, leader: &Peer) -> Option<HashMap<Peer, Vec<u32>>> {
+        let mut unmatched = self
+            .find_leader_regions(leader)
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let mut followers = HashMap::new();
+        for region_route in self
+            .region_routes
+            .iter()
+            .filter(|x| x.leader_peer.as_ref() == Some(leader))
+        {
+            if !unmatched.contains(&(region_route.region.id as u32)) {
+                continue;
+            }
+            let (follower, regions) = region_route.follower_peers.iter().find_map(|follower| {
+                if !self.find_leader_regions(follower).is_empty() {
+                    return None;
+                }
+                let regions = self.find_follower_regions(follower);
+                regions
+                    .iter()
+                    .all(|x| unmatched.contains(x))
+                    .then_some((follower, regions))
+            })?;
+            for region in &regions {
+                let _ = unmatched.remove(region);
+            }
+            let _ = followers.insert(follower.clone(), regions);
+        }
+        Some(followers)
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -700,4 +747,75 @@ mod tests {
         assert_eq!(into_raw.0, raw_peers);
         assert_eq!(into_raw.1, raw_table_route);
     }
+
+    #[test]
+    fn test_find_followers_of_leader() {
+        let region_route = |id, leader, followers: Vec<Peer>| RegionRoute {
+            region: Region {
+                id,
+                name: format!("r{id}"),
+                partition: None,
+                attrs: HashMap::new(),
+            },
+            leader_peer: Some(leader),
+            follower_peers: followers,
+        };
+        // region distribution:
+        // region id => leader peer id + [follower peer id]
+        // 1 => 1 + [3]
+        // 2 => 1 + [3, 4]
+        // 3 => 2 + [1]
+        // 4 => 5 + [4, 6]
+        // 5 => 5 + [7]
+        let table_route = TableRoute::new(
+            Table {
+                id: 1,
+                table_name: TableName::new("c1", "s1", "t1"),
+                table_schema: vec![],
+            },
+            vec![
+                region_route(1, Peer::new(1, "a1"), vec![Peer::new(3, "a3")]),
+                region_route(
+                    2,
+                    Peer::new(1, "a1"),
+                    vec![Peer::new(3, "a3"), Peer::new(4, "a4")],
+                ),
+                region_route(3, Peer::new(2, "a2"), vec![Peer::new(1, "a1")]),
+                region_route(
+                    4,
+                    Peer::new(5, "a5"),
+                    vec![Peer::new(4, "a4"), Peer::new(6, "a6")],
+                ),
+                region_route(5, Peer::new(5, "a5"), vec![Peer::new(7, "a7")]),
+            ],
+        );
+
+        assert_eq!(
+            vec![1, 2],
+            table_route.find_follower_regions(&Peer::new(3, "a3"))
+        );
+        assert_eq!(
+            vec![2, 4],
+            table_route.find_follower_regions(&Peer::new(4, "a4"))
+        );
+
+        // Peer 4 also follows region 4 of peer 5, so only peer 3 matches.
+        assert_eq!(
+            Some(HashMap::from([(Peer::new(3, "a3"), vec![1, 2])])),
+            table_route.find_followers_of_leader(&Peer::new(1, "a1"))
+        );
+        // The only follower of peer 2 leads other regions.
+        assert_eq!(
+            None,
+            table_route.find_followers_of_leader(&Peer::new(2, "a2"))
+        );
+        // Regions are matched with different followers.
+        assert_eq!(
+            Some(HashMap::from([
+                (Peer::new(6, "a6"), vec![4]),
+                (Peer::new(7, "a7"), vec![5]),
+            ])),
+            table_route.find_followers_of_leader(&Peer::new(5, "a5"))
+        );
+    }
 }
@@ -16,7 +16,7 @@ use std::any::Any;
 
 use arrow::error::ArrowError;
 use common_error::prelude::*;
-use common_recordbatch::error::Error as RecordbatchError;
+use common_recordbatch::error::{datafusion_status_code, Error as RecordbatchError};
 use datafusion_common::DataFusionError;
 use datatypes::arrow;
 use datatypes::arrow::datatypes::DataType as ArrowDatatype;
@@ -192,8 +192,9 @@ impl ErrorExt for Error {
             | Error::ConvertArrowSchema { source, .. }
             | Error::FromArrowArray { source, .. } => source.status_code(),
 
-            Error::ExecuteRepeatedly { .. } | Error::GeneralDataFusion { .. } => {
-                StatusCode::Unexpected
+            Error::ExecuteRepeatedly { .. } => StatusCode::Unexpected,
+            Error::GeneralDataFusion { source, .. } => {
+                datafusion_status_code(source, StatusCode::Unexpected)
             }
 
             Error::UnsupportedInputDataType { .. }
@@ -17,6 +17,7 @@ use std::any::Any;
 
 use common_error::ext::BoxedError;
 use common_error::prelude::*;
+use datafusion_common::DataFusionError;
 use datatypes::prelude::ConcreteDataType;
 use snafu::Location;
 
@@ -54,7 +55,7 @@ pub enum Error {
 
     #[snafu(display("Failed to poll stream, source: {}", source))]
     PollStream {
-        source: datafusion::error::DataFusionError,
+        source: DataFusionError,
         location: Location,
     },
 
@@ -66,7 +67,7 @@ pub enum Error {
 
     #[snafu(display("Failed to init Recordbatch stream, source: {}", source))]
     InitRecordbatchStream {
-        source: datafusion_common::DataFusionError,
+        source: DataFusionError,
         location: Location,
     },
 
@@ -109,11 +110,13 @@ impl ErrorExt for Error {
         match self {
             Error::NewDfRecordBatch { .. } => StatusCode::InvalidArguments,
 
+            Error::PollStream { source, .. } | Error::InitRecordbatchStream { source, .. } => {
+                datafusion_status_code(source, StatusCode::Internal)
+            }
+
             Error::DataTypes { .. }
             | Error::CreateRecordBatches { .. }
-            | Error::PollStream { .. }
             | Error::Format { .. }
-            | Error::InitRecordbatchStream { .. }
             | Error::ColumnNotExists { .. }
             | Error::ProjectArrowRecordBatch { .. } => StatusCode::Internal,
 
@@ -129,3 +132,14 @@ impl ErrorExt for Error {
         self
     }
 }
+
+/// Returns the status code of a DataFusion error, or `default` if the error
+/// isn't categorized, e.g. [StatusCode::ResourcesExhausted] if the query
+/// exceeds its memory limit.
+pub fn datafusion_status_code(error: &DataFusionError, default: StatusCode) -> StatusCode {
+    match error {
+        DataFusionError::ResourcesExhausted(_) => StatusCode::ResourcesExhausted,
+        DataFusionError::Context(_, source) => datafusion_status_code(source, default),
+        _ => default,
+    }
+}
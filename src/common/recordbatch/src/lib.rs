@@ -22,6 +22,7 @@ use std::sync::Arc;
 
 use datafusion::physical_plan::memory::MemoryStream;
 pub use datafusion::physical_plan::SendableRecordBatchStream as DfSendableRecordBatchStream;
+use datafusion::physical_plan::{DisplayableExecutionPlan, ExecutionPlan};
 use datatypes::arrow::compute::SortOptions;
 pub use datatypes::arrow::record_batch::RecordBatch as DfRecordBatch;
 use datatypes::arrow::util::pretty;
@@ -39,6 +40,12 @@ pub trait RecordBatchStream: Stream<Item = Result<RecordBatch>> {
     fn output_ordering(&self) -> Option<&[OrderOption]> {
         None
     }
+
+    /// Returns the physical plan producing this stream, if known. Metrics of the
+    /// plan are collected as the stream is polled.
+    fn physical_plan(&self) -> Option<Arc<dyn ExecutionPlan>> {
+        None
+    }
 }
 
 pub type SendableRecordBatchStream = Pin<Box<dyn RecordBatchStream + Send>>;
@@ -226,6 +233,49 @@ impl Stream for RecordBatchStreamAdaptor {
     }
 }
 
+/// Attaches the physical plan to the stream it produces, see
+/// [RecordBatchStream::physical_plan].
+pub struct RecordBatchStreamWithPlan {
+    plan: Arc<dyn ExecutionPlan>,
+    stream: SendableRecordBatchStream,
+}
+
+impl RecordBatchStreamWithPlan {
+    pub fn new(plan: Arc<dyn ExecutionPlan>, stream: SendableRecordBatchStream) -> Self {
+        Self { plan, stream }
+    }
+}
+
+impl RecordBatchStream for RecordBatchStreamWithPlan {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+
+    fn output_ordering(&self) -> Option<&[OrderOption]> {
+        self.stream.output_ordering()
+    }
+
+    fn physical_plan(&self) -> Option<Arc<dyn ExecutionPlan>> {
+        Some(self.plan.clone())
+    }
+}
+
+impl Stream for RecordBatchStreamWithPlan {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.stream).poll_next(ctx)
+    }
+}
+
+/// Formats the physical plan with the metrics collected so far, like the output
+/// of `EXPLAIN ANALYZE`.
+pub fn display_plan_with_metrics(plan: &dyn ExecutionPlan) -> String {
+    DisplayableExecutionPlan::with_metrics(plan)
+        .indent()
+        .to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
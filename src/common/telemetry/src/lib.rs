@@ -16,6 +16,7 @@ pub mod logging;
 mod macros;
 pub mod metric;
 mod panic_hook;
+pub mod tracing_context;
 
 pub use logging::{init_default_ut_logging, init_global_logging};
 pub use metric::init_default_metrics_recorder;
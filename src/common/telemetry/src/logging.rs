@@ -17,8 +17,10 @@ use std::env;
 use std::sync::{Arc, Mutex, Once};
 
 use once_cell::sync::Lazy;
-use opentelemetry::global;
 use opentelemetry::sdk::propagation::TraceContextPropagator;
+use opentelemetry::sdk::{trace, Resource};
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
 use serde::{Deserialize, Serialize};
 pub use tracing::{event, span, Level};
 use tracing_appender::non_blocking::WorkerGuard;
@@ -38,6 +40,8 @@ pub struct LoggingOptions {
     pub dir: String,
     pub level: Option<String>,
     pub enable_jaeger_tracing: bool,
+    /// Endpoint of the OTLP collector to export traces to, e.g. `http://localhost:4317`.
+    pub otlp_endpoint: Option<String>,
 }
 
 impl Default for LoggingOptions {
@@ -46,6 +50,7 @@ impl Default for LoggingOptions {
             dir: "/tmp/greptimedb/logs".to_string(),
             level: None,
             enable_jaeger_tracing: false,
+            otlp_endpoint: None,
         }
     }
 }
@@ -184,20 +189,63 @@ pub fn init_global_logging(
         .with(file_logging_layer)
         .with(err_file_logging_layer.with_filter(filter::LevelFilter::ERROR));
 
-    if enable_jaeger_tracing {
-        // Jaeger layer.
+    if enable_jaeger_tracing || opts.otlp_endpoint.is_some() {
+        // Propagates the tracing context across processes.
         global::set_text_map_propagator(TraceContextPropagator::new());
-        let tracer = opentelemetry_jaeger::new_pipeline()
+    }
+
+    // Failing to install an exporter only disables tracing, the errors are logged once the
+    // subscriber is set.
+    let mut install_errors = vec![];
+
+    // Jaeger layer.
+    let jaeger_layer = if enable_jaeger_tracing {
+        match opentelemetry_jaeger::new_pipeline()
             .with_service_name(app_name)
             .install_batch(opentelemetry::runtime::Tokio)
-            .expect("install");
-        let jaeger_layer = Some(tracing_opentelemetry::layer().with_tracer(tracer));
-        let subscriber = subscriber.with(jaeger_layer);
-        tracing::subscriber::set_global_default(subscriber)
-            .expect("error setting global tracing subscriber");
+        {
+            Ok(tracer) => Some(tracing_opentelemetry::layer().with_tracer(tracer)),
+            Err(e) => {
+                install_errors.push(format!("Failed to install Jaeger tracing exporter: {e}"));
+                None
+            }
+        }
     } else {
-        tracing::subscriber::set_global_default(subscriber)
-            .expect("error setting global tracing subscriber");
+        None
+    };
+
+    // OTLP layer.
+    let otlp_layer =
+        if let Some(endpoint) = &opts.otlp_endpoint {
+            match opentelemetry_otlp::new_pipeline()
+                .tracing()
+                .with_exporter(
+                    opentelemetry_otlp::new_exporter()
+                        .tonic()
+                        .with_endpoint(endpoint),
+                )
+                .with_trace_config(trace::config().with_resource(Resource::new(vec![
+                    KeyValue::new("service.name", app_name.to_string()),
+                ])))
+                .install_batch(opentelemetry::runtime::Tokio)
+            {
+                Ok(tracer) => Some(tracing_opentelemetry::layer().with_tracer(tracer)),
+                Err(e) => {
+                    install_errors.push(format!(
+                        "Failed to install OTLP tracing exporter, endpoint: {endpoint}: {e}"
+                    ));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+    let subscriber = subscriber.with(jaeger_layer).with(otlp_layer);
+    tracing::subscriber::set_global_default(subscriber)
+        .expect("error setting global tracing subscriber");
+    for install_error in install_errors {
+        crate::error!("{install_error}");
     }
 
     guards
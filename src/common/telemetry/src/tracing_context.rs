@@ -0,0 +1,63 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Propagates tracing context across processes in the W3C trace context format.
+
+use opentelemetry::global;
+pub use opentelemetry::propagation::{Extractor, Injector};
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Header carrying the W3C trace context.
+pub const TRACE_PARENT_HEADER: &str = "traceparent";
+
+/// Injects the context of the current span into `injector`.
+///
+/// Nothing is injected if tracing is disabled.
+pub fn inject_current_context(injector: &mut dyn Injector) {
+    let context = Span::current().context();
+    global::get_text_map_propagator(|propagator| propagator.inject_context(&context, injector));
+}
+
+/// Sets the parent of `span` to the remote context extracted from `extractor`.
+pub fn set_remote_parent(span: &Span, extractor: &dyn Extractor) {
+    let context = global::get_text_map_propagator(|propagator| propagator.extract(extractor));
+    span.set_parent(context);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use opentelemetry::sdk::propagation::TraceContextPropagator;
+    use opentelemetry::trace::TraceContextExt;
+
+    use super::*;
+
+    #[test]
+    fn test_propagate_context() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let trace_parent = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+        let carrier = HashMap::from([(TRACE_PARENT_HEADER.to_string(), trace_parent.to_string())]);
+
+        let context = global::get_text_map_propagator(|propagator| propagator.extract(&carrier));
+        assert!(context.span().span_context().is_remote());
+
+        let mut injected = HashMap::new();
+        global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&context, &mut injected)
+        });
+        assert_eq!(trace_parent, injected[TRACE_PARENT_HEADER]);
+    }
+}
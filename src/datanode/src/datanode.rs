@@ -23,6 +23,7 @@ use common_error::prelude::BoxedError;
 use common_telemetry::info;
 use common_telemetry::logging::LoggingOptions;
 use meta_client::MetaClientOptions;
+use query::query_engine::options::QueryMemoryOptions;
 use secrecy::SecretString;
 use serde::{Deserialize, Serialize};
 use servers::http::HttpOptions;
@@ -67,6 +68,11 @@ pub struct StorageConfig {
     pub global_ttl: Option<Duration>,
     #[serde(flatten)]
     pub store: ObjectStoreConfig,
+    /// Object store to move SSTs to once they are older than the `cold_after`
+    /// option of their tables.
+    ///
+    /// Default value is `None`, which means all SSTs stay in `store`.
+    pub cold_store: Option<ObjectStoreConfig>,
     pub compaction: CompactionConfig,
     pub manifest: RegionManifestConfig,
     pub flush: FlushConfig,
@@ -219,6 +225,9 @@ pub struct RegionManifestConfig {
     pub checkpoint_on_startup: bool,
     /// Whether to compress manifest and checkpoint file by gzip
     pub compress: bool,
+    /// Interval to refresh manifests of follower regions.
+    #[serde(with = "humantime_serde")]
+    pub refresh_interval: Duration,
 }
 
 impl Default for RegionManifestConfig {
@@ -228,6 +237,7 @@ impl Default for RegionManifestConfig {
             gc_duration: Some(Duration::from_secs(600)),
             checkpoint_on_startup: false,
             compress: false,
+            refresh_interval: Duration::from_secs(10),
         }
     }
 }
@@ -242,6 +252,14 @@ pub struct CompactionConfig {
     pub max_files_in_level0: usize,
     /// Max task number for SST purge task after compaction.
     pub max_purge_tasks: usize,
+    /// Delay to delete SST files after compaction, which should be longer than the manifest
+    /// refresh interval plus the longest query, so follower regions can finish their reads.
+    #[serde(with = "humantime_serde")]
+    pub sst_purge_delay: Duration,
+    /// Interval to compact regions with SSTs older than the `cold_after` option of their
+    /// tables, so they are moved to the cold store even without new writes.
+    #[serde(with = "humantime_serde")]
+    pub cold_check_interval: Duration,
     /// Buffer threshold while writing SST files
     pub sst_write_buffer_size: ReadableSize,
 }
@@ -252,6 +270,8 @@ impl Default for CompactionConfig {
             max_inflight_tasks: 4,
             max_files_in_level0: 8,
             max_purge_tasks: 32,
+            sst_purge_delay: Duration::from_secs(60),
+            cold_check_interval: Duration::from_secs(60 * 60),
             sst_write_buffer_size: ReadableSize::mb(8),
         }
     }
@@ -305,6 +325,7 @@ impl From<&DatanodeOptions> for StorageEngineConfig {
             manifest_gc_duration: value.storage.manifest.gc_duration,
             max_files_in_l0: value.storage.compaction.max_files_in_level0,
             max_purge_tasks: value.storage.compaction.max_purge_tasks,
+            sst_purge_delay: value.storage.compaction.sst_purge_delay,
             sst_write_buffer_size: value.storage.compaction.sst_write_buffer_size,
             max_flush_tasks: value.storage.flush.max_flush_tasks,
             region_write_buffer_size: value.storage.flush.region_write_buffer_size,
@@ -312,6 +333,8 @@ impl From<&DatanodeOptions> for StorageEngineConfig {
             auto_flush_interval: value.storage.flush.auto_flush_interval,
             global_write_buffer_size: value.storage.flush.global_write_buffer_size,
             global_ttl: value.storage.global_ttl,
+            manifest_refresh_interval: value.storage.manifest.refresh_interval,
+            cold_check_interval: value.storage.compaction.cold_check_interval,
         }
     }
 }
@@ -351,6 +374,7 @@ pub struct DatanodeOptions {
     pub storage: StorageConfig,
     pub procedure: ProcedureConfig,
     pub logging: LoggingOptions,
+    pub query_memory: QueryMemoryOptions,
 }
 
 impl Default for DatanodeOptions {
@@ -369,6 +393,7 @@ impl Default for DatanodeOptions {
             procedure: ProcedureConfig::default(),
             logging: LoggingOptions::default(),
             heartbeat_interval_millis: 5000,
+            query_memory: QueryMemoryOptions::default(),
         }
     }
 }
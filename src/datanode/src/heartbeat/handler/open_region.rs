@@ -28,7 +28,7 @@ use common_telemetry::{error, warn};
 use snafu::ResultExt;
 use store_api::storage::RegionNumber;
 use table::engine::manager::TableEngineManagerRef;
-use table::engine::EngineContext;
+use table::engine::{region_number, EngineContext};
 use table::requests::OpenTableRequest;
 
 use crate::error::{self, Result};
@@ -46,12 +46,17 @@ impl HeartbeatResponseHandler for OpenRegionHandler {
         matches!(
             ctx.incoming_message,
             Some((_, Instruction::OpenRegion { .. }))
+                | Some((_, Instruction::OpenFollowerRegion { .. }))
         )
     }
 
     async fn handle(&self, ctx: &mut HeartbeatResponseHandlerContext) -> MetaResult<HandleControl> {
-        let Some((meta, Instruction::OpenRegion(region_ident))) = ctx.incoming_message.take() else {
-            unreachable!("OpenRegionHandler: should be guarded by 'is_acceptable'");
+        let (meta, region_ident, read_only) = match ctx.incoming_message.take() {
+            Some((meta, Instruction::OpenRegion(region_ident))) => (meta, region_ident, false),
+            Some((meta, Instruction::OpenFollowerRegion(region_ident))) => {
+                (meta, region_ident, true)
+            }
+            _ => unreachable!("OpenRegionHandler: should be guarded by 'is_acceptable'"),
         };
 
         let mailbox = ctx.mailbox.clone();
@@ -66,17 +71,19 @@ impl HeartbeatResponseHandler for OpenRegionHandler {
                 table_name: table_ident.table.clone(),
                 table_id: table_ident.table_id,
                 region_numbers: vec![region_ident.region_number],
+                read_only,
             };
             let result = self_ref
                 .open_region_inner(table_ident.engine.clone(), request)
                 .await;
 
-            if matches!(result, Ok(true)) {
+            // Followers are not leased, they are closed along with the table.
+            if !read_only && matches!(result, Ok(true)) {
                 region_alive_keepers.register_region(&region_ident).await;
             }
 
             if let Err(e) = mailbox
-                .send((meta, OpenRegionHandler::map_result(result)))
+                .send((meta, OpenRegionHandler::map_result(result, read_only)))
                 .await
             {
                 error!(e; "Failed to send reply to mailbox");
@@ -99,30 +106,35 @@ impl OpenRegionHandler {
         }
     }
 
-    fn map_result(result: Result<bool>) -> InstructionReply {
-        result.map_or_else(
-            |error| {
-                InstructionReply::OpenRegion(SimpleReply {
-                    result: false,
-                    error: Some(error.to_string()),
-                })
+    fn map_result(result: Result<bool>, read_only: bool) -> InstructionReply {
+        let reply = result.map_or_else(
+            |error| SimpleReply {
+                result: false,
+                error: Some(error.to_string()),
             },
-            |result| {
-                InstructionReply::OpenRegion(SimpleReply {
-                    result,
-                    error: None,
-                })
+            |result| SimpleReply {
+                result,
+                error: None,
             },
-        )
+        );
+        if read_only {
+            InstructionReply::OpenFollowerRegion(reply)
+        } else {
+            InstructionReply::OpenRegion(reply)
+        }
     }
 
     /// Returns true if a table or target regions have been opened.
+    ///
+    /// Read-only regions don't count as opened for a writable request, they have to be
+    /// reopened by the engine.
     async fn regions_opened(
         &self,
         catalog_name: &str,
         schema_name: &str,
         table_name: &str,
         region_numbers: &[RegionNumber],
+        read_only: bool,
     ) -> Result<bool> {
         if let Some(table) = self
             .catalog_manager
@@ -151,6 +163,16 @@ impl OpenRegionHandler {
                     return Ok(false);
                 }
             }
+            if !read_only {
+                // Tables without region stats never have read-only regions.
+                let region_stats = table.region_stats().unwrap_or_default();
+                let has_read_only = region_stats.iter().any(|stat| {
+                    stat.read_only && region_numbers.contains(&region_number(stat.region_id))
+                });
+                if has_read_only {
+                    return Ok(false);
+                }
+            }
             return Ok(true);
         }
         Ok(false)
@@ -162,6 +184,7 @@ impl OpenRegionHandler {
             schema_name,
             table_name,
             region_numbers,
+            read_only,
             ..
         } = &request;
         let engine =
@@ -173,7 +196,13 @@ impl OpenRegionHandler {
         let ctx = EngineContext::default();
 
         if self
-            .regions_opened(catalog_name, schema_name, table_name, region_numbers)
+            .regions_opened(
+                catalog_name,
+                schema_name,
+                table_name,
+                region_numbers,
+                *read_only,
+            )
             .await?
         {
             return Ok(true);
@@ -161,16 +161,21 @@ impl Instance {
         plugins: Arc<Plugins>,
     ) -> Result<(InstanceRef, Option<HeartbeatTask>)> {
         let object_store = store::new_object_store(&opts.storage.store).await?;
+        let cold_object_store = match &opts.storage.cold_store {
+            Some(cold_store) => Some(store::new_object_store(cold_store).await?),
+            None => None,
+        };
         let log_store = Arc::new(create_log_store(&opts.storage.store, &opts.wal).await?);
 
         let mito_engine = Arc::new(DefaultEngine::new(
             TableEngineConfig {
                 compress_manifest: opts.storage.manifest.compress,
             },
-            EngineImpl::new(
+            EngineImpl::with_cold_object_store(
                 StorageEngineConfig::from(opts),
                 log_store.clone(),
                 object_store.clone(),
+                cold_object_store,
                 compaction_scheduler,
             )
             .unwrap(),
@@ -266,6 +271,7 @@ impl Instance {
         };
 
         catalog_manager.start().await.context(CatalogSnafu)?;
+        plugins.insert(opts.query_memory.clone());
         let factory = QueryEngineFactory::new_with_plugins(
             catalog_manager.clone(),
             false,
@@ -32,12 +32,23 @@ impl SqlHandler {
     ) -> Result<CreateTableRequest> {
         let mut options = stmt.options;
 
-        let (files, schema) =
-            prepare_immutable_file_table_files_and_schema(&options, &stmt.columns)
-                .await
-                .context(error::PrepareImmutableTableSnafu)?;
+        let partition_columns = stmt
+            .partition_columns
+            .iter()
+            .map(|column| column.value.clone())
+            .collect::<Vec<_>>();
+        let (files, schema) = prepare_immutable_file_table_files_and_schema(
+            &options,
+            &stmt.columns,
+            &partition_columns,
+        )
+        .await
+        .context(error::PrepareImmutableTableSnafu)?;
 
-        let meta = ImmutableFileTableOptions { files };
+        let meta = ImmutableFileTableOptions {
+            files,
+            partition_columns,
+        };
         let _ = options.insert(
             IMMUTABLE_TABLE_META_KEY.to_string(),
             serde_json::to_string(&meta).context(error::EncodeJsonSnafu)?,
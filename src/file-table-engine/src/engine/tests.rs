@@ -57,6 +57,7 @@ async fn test_open_table() {
         // the test table id is 1
         table_id,
         region_numbers: vec![0],
+        read_only: false,
     };
 
     let TestEngineComponents {
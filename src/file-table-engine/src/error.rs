@@ -171,6 +171,27 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Failed to parse partition values from path: {}", path))]
+    InvalidPartitionPath { path: String, location: Location },
+
+    #[snafu(display("Failed to build regex of file pattern, source: {}", source))]
+    BuildRegex {
+        location: Location,
+        source: regex::Error,
+    },
+
+    #[snafu(display("Failed to list partition files, source: {}", source))]
+    ListPartitionFiles {
+        location: Location,
+        source: common_datasource::error::Error,
+    },
+
+    #[snafu(display("Failed to prune partitions: {}", source))]
+    PrunePartitions {
+        source: DataFusionError,
+        location: Location,
+    },
+
     #[snafu(display("Failed to convert schema: {}", source))]
     ConvertSchema {
         source: datatypes::error::Error,
@@ -196,11 +217,13 @@ impl ErrorExt for Error {
             | ProjectSchema { .. }
             | MissingRequiredField { .. }
             | ConvertSchema { .. }
+            | BuildRegex { .. }
             | UnsupportedFormat { .. } => StatusCode::InvalidArguments,
 
             BuildBackend { source, .. } => source.status_code(),
             BuildStreamAdapter { source, .. } => source.status_code(),
             ParseFileFormat { source, .. } => source.status_code(),
+            ListPartitionFiles { source, .. } => source.status_code(),
 
             WriteTableManifest { .. }
             | DeleteTableManifest { .. }
@@ -213,7 +236,9 @@ impl ErrorExt for Error {
             | DropTable { .. }
             | WriteImmutableManifest { .. }
             | BuildStream { .. }
-            | ParquetScanPlan { .. } => StatusCode::Unexpected,
+            | ParquetScanPlan { .. }
+            | InvalidPartitionPath { .. }
+            | PrunePartitions { .. } => StatusCode::Unexpected,
         }
     }
 
@@ -12,10 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use common_datasource::file_format::csv::{CsvConfigBuilder, CsvFormat, CsvOpener};
 use common_datasource::file_format::json::{JsonFormat, JsonOpener};
+use common_datasource::file_format::orc::{OrcFormat, OrcOpener};
 use common_datasource::file_format::parquet::{DefaultParquetFileReaderFactory, ParquetFormat};
 use common_datasource::file_format::Format;
 use common_query::physical_plan::{PhysicalPlanAdapter, PhysicalPlanRef};
@@ -25,6 +27,7 @@ use common_recordbatch::SendableRecordBatchStream;
 use datafusion::common::ToDFSchema;
 use datafusion::datasource::listing::PartitionedFile;
 use datafusion::datasource::object_store::ObjectStoreUrl;
+use datafusion::logical_expr::utils::expr_to_columns;
 use datafusion::optimizer::utils::conjunction;
 use datafusion::physical_expr::create_physical_expr;
 use datafusion::physical_expr::execution_props::ExecutionProps;
@@ -32,7 +35,9 @@ use datafusion::physical_plan::file_format::{
     FileOpener, FileScanConfig, FileStream, ParquetExec, ParquetOpener,
 };
 use datafusion::physical_plan::metrics::ExecutionPlanMetricsSet;
-use datatypes::arrow::datatypes::Schema as ArrowSchema;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::scalar::ScalarValue;
+use datatypes::arrow::datatypes::{DataType, Schema as ArrowSchema};
 use datatypes::schema::{Schema, SchemaRef};
 use object_store::ObjectStore;
 use snafu::ResultExt;
@@ -45,6 +50,67 @@ const DEFAULT_BATCH_SIZE: usize = 8192;
 #[derive(Debug, Clone, Copy, Default)]
 pub struct CreateScanPlanContext {}
 
+/// Returns the schema of columns stored in files, i.e. the table schema without
+/// the trailing partition columns.
+fn file_arrow_schema(config: &ScanPlanConfig) -> Arc<ArrowSchema> {
+    let table_schema = config.table_schema.arrow_schema();
+    if config.partition_columns.is_empty() {
+        return table_schema.clone();
+    }
+    let num_file_columns = table_schema.fields().len() - config.partition_columns.len();
+    Arc::new(ArrowSchema::new(
+        table_schema.fields()[..num_file_columns].to_vec(),
+    ))
+}
+
+/// Returns the projection over the file columns, skipping partition columns
+/// which are filled by the [FileStream] itself.
+fn file_projection(config: &ScanPlanConfig) -> Option<Vec<usize>> {
+    let num_file_columns =
+        config.table_schema.arrow_schema().fields().len() - config.partition_columns.len();
+    config.projection.map(|projection| {
+        projection
+            .iter()
+            .copied()
+            .filter(|i| *i < num_file_columns)
+            .collect()
+    })
+}
+
+fn build_file_scan_config(config: &ScanPlanConfig) -> FileScanConfig {
+    let file_groups = config
+        .files
+        .iter()
+        .enumerate()
+        .map(|(i, filename)| {
+            let mut file = PartitionedFile::new(filename.to_string(), 0);
+            if let Some(values) = config.partition_values.get(i) {
+                file.partition_values = values
+                    .iter()
+                    .map(|value| ScalarValue::Utf8(Some(value.clone())))
+                    .collect();
+            }
+            file
+        })
+        .collect::<Vec<_>>();
+
+    FileScanConfig {
+        object_store_url: ObjectStoreUrl::parse("empty://").unwrap(), // won't be used
+        file_schema: file_arrow_schema(config),
+        file_groups: vec![file_groups],
+        statistics: Default::default(),
+        projection: config.projection.cloned(),
+        limit: config.limit,
+        table_partition_cols: config
+            .partition_columns
+            .iter()
+            .map(|column| (column.clone(), DataType::Utf8))
+            .collect(),
+        output_ordering: None,
+        infinite_source: false,
+    }
+}
+
 fn build_csv_opener(
     file_schema: Arc<ArrowSchema>,
     config: &ScanPlanConfig,
@@ -53,7 +119,7 @@ fn build_csv_opener(
     let csv_config = CsvConfigBuilder::default()
         .batch_size(DEFAULT_BATCH_SIZE)
         .file_schema(file_schema)
-        .file_projection(config.projection.cloned())
+        .file_projection(file_projection(config))
         .delimiter(format.delimiter)
         .has_header(format.has_header)
         .build()
@@ -70,10 +136,10 @@ fn build_json_opener(
     config: &ScanPlanConfig,
     format: &JsonFormat,
 ) -> Result<JsonOpener> {
-    let projected_schema = if let Some(projection) = config.projection {
+    let projected_schema = if let Some(projection) = file_projection(config) {
         Arc::new(
             file_schema
-                .project(projection)
+                .project(&projection)
                 .context(error::ProjectSchemaSnafu)?,
         )
     } else {
@@ -87,39 +153,43 @@ fn build_json_opener(
     ))
 }
 
+fn build_orc_opener(file_schema: Arc<ArrowSchema>, config: &ScanPlanConfig) -> OrcOpener {
+    // Stripes are pruned by the filters over file columns only.
+    let filters = config
+        .filters
+        .iter()
+        .map(|f| f.df_expr().clone())
+        .filter(|expr| {
+            let mut columns = HashSet::new();
+            expr_to_columns(expr, &mut columns).is_ok()
+                && columns
+                    .iter()
+                    .all(|column| file_schema.field_with_name(&column.name).is_ok())
+        })
+        .collect::<Vec<_>>();
+    let predicate = conjunction(filters).and_then(|expr| {
+        let df_schema = file_schema.clone().to_dfschema_ref().ok()?;
+        create_physical_expr(&expr, &df_schema, &file_schema, &ExecutionProps::new()).ok()
+    });
+
+    OrcOpener::new(config.store.clone(), file_schema, file_projection(config))
+        .with_predicate(predicate)
+}
+
 fn build_scan_plan<T: FileOpener + Send + 'static>(
     opener: T,
-    file_schema: Arc<ArrowSchema>,
-    files: &[String],
-    projection: Option<&Vec<usize>>,
-    limit: Option<usize>,
+    config: &ScanPlanConfig,
 ) -> Result<PhysicalPlanRef> {
-    let adapter = build_record_batch_stream(opener, file_schema, files, projection, limit)?;
+    let adapter = build_record_batch_stream(opener, config)?;
     Ok(Arc::new(StreamScanAdapter::new(adapter)))
 }
 
 fn build_record_batch_stream<T: FileOpener + Send + 'static>(
     opener: T,
-    file_schema: Arc<ArrowSchema>,
-    files: &[String],
-    projection: Option<&Vec<usize>>,
-    limit: Option<usize>,
+    config: &ScanPlanConfig,
 ) -> Result<SendableRecordBatchStream> {
     let stream = FileStream::new(
-        &FileScanConfig {
-            object_store_url: ObjectStoreUrl::parse("empty://").unwrap(), // won't be used
-            file_schema,
-            file_groups: vec![files
-                .iter()
-                .map(|filename| PartitionedFile::new(filename.to_string(), 0))
-                .collect::<Vec<_>>()],
-            statistics: Default::default(),
-            projection: projection.cloned(),
-            limit,
-            table_partition_cols: vec![],
-            output_ordering: None,
-            infinite_source: false,
-        },
+        &build_file_scan_config(config),
         0, // partition: hard-code
         opener,
         &ExecutionPlanMetricsSet::new(),
@@ -135,15 +205,8 @@ fn new_csv_scan_plan(
     config: &ScanPlanConfig,
     format: &CsvFormat,
 ) -> Result<PhysicalPlanRef> {
-    let file_schema = config.file_schema.arrow_schema().clone();
-    let opener = build_csv_opener(file_schema.clone(), config, format)?;
-    build_scan_plan(
-        opener,
-        file_schema,
-        config.files,
-        config.projection,
-        config.limit,
-    )
+    let opener = build_csv_opener(file_arrow_schema(config), config, format)?;
+    build_scan_plan(opener, config)
 }
 
 fn new_csv_stream(
@@ -151,15 +214,8 @@ fn new_csv_stream(
     config: &ScanPlanConfig,
     format: &CsvFormat,
 ) -> Result<SendableRecordBatchStream> {
-    let file_schema = config.file_schema.arrow_schema().clone();
-    let opener = build_csv_opener(file_schema.clone(), config, format)?;
-    build_record_batch_stream(
-        opener,
-        file_schema,
-        config.files,
-        config.projection,
-        config.limit,
-    )
+    let opener = build_csv_opener(file_arrow_schema(config), config, format)?;
+    build_record_batch_stream(opener, config)
 }
 
 fn new_json_scan_plan(
@@ -167,15 +223,8 @@ fn new_json_scan_plan(
     config: &ScanPlanConfig,
     format: &JsonFormat,
 ) -> Result<PhysicalPlanRef> {
-    let file_schema = config.file_schema.arrow_schema().clone();
-    let opener = build_json_opener(file_schema.clone(), config, format)?;
-    build_scan_plan(
-        opener,
-        file_schema,
-        config.files,
-        config.projection,
-        config.limit,
-    )
+    let opener = build_json_opener(file_arrow_schema(config), config, format)?;
+    build_scan_plan(opener, config)
 }
 
 fn new_json_stream(
@@ -183,15 +232,26 @@ fn new_json_stream(
     config: &ScanPlanConfig,
     format: &JsonFormat,
 ) -> Result<SendableRecordBatchStream> {
-    let file_schema = config.file_schema.arrow_schema().clone();
-    let opener = build_json_opener(file_schema.clone(), config, format)?;
-    build_record_batch_stream(
-        opener,
-        file_schema,
-        config.files,
-        config.projection,
-        config.limit,
-    )
+    let opener = build_json_opener(file_arrow_schema(config), config, format)?;
+    build_record_batch_stream(opener, config)
+}
+
+fn new_orc_scan_plan(
+    _ctx: &CreateScanPlanContext,
+    config: &ScanPlanConfig,
+    _format: &OrcFormat,
+) -> Result<PhysicalPlanRef> {
+    let opener = build_orc_opener(file_arrow_schema(config), config);
+    build_scan_plan(opener, config)
+}
+
+fn new_orc_stream(
+    _ctx: &CreateScanPlanContext,
+    config: &ScanPlanConfig,
+    _format: &OrcFormat,
+) -> Result<SendableRecordBatchStream> {
+    let opener = build_orc_opener(file_arrow_schema(config), config);
+    build_record_batch_stream(opener, config)
 }
 
 fn new_parquet_scan_plan(
@@ -199,30 +259,10 @@ fn new_parquet_scan_plan(
     config: &ScanPlanConfig,
     _format: &ParquetFormat,
 ) -> Result<PhysicalPlanRef> {
-    let file_schema = config.file_schema.arrow_schema().clone();
-    let ScanPlanConfig {
-        files,
-        projection,
-        limit,
-        filters,
-        store,
-        ..
-    } = config;
+    let file_schema = file_arrow_schema(config);
+    let ScanPlanConfig { filters, store, .. } = config;
 
-    let scan_config = FileScanConfig {
-        object_store_url: ObjectStoreUrl::parse("empty://").unwrap(), // won't be used
-        file_schema: file_schema.clone(),
-        file_groups: vec![files
-            .iter()
-            .map(|filename| PartitionedFile::new(filename.to_string(), 0))
-            .collect::<Vec<_>>()],
-        statistics: Default::default(),
-        projection: projection.cloned(),
-        limit: *limit,
-        table_partition_cols: vec![],
-        output_ordering: None,
-        infinite_source: false,
-    };
+    let scan_config = build_file_scan_config(config);
 
     let filters = filters
         .iter()
@@ -246,17 +286,8 @@ fn new_parquet_scan_plan(
         Arc::new(DefaultParquetFileReaderFactory::new(store.clone())),
     );
 
-    let projected_schema = if let Some(projection) = config.projection {
-        Arc::new(
-            file_schema
-                .project(projection)
-                .context(error::ProjectSchemaSnafu)?,
-        )
-    } else {
-        file_schema
-    };
-
-    let schema = Schema::try_from(projected_schema).context(error::ConvertSchemaSnafu)?;
+    // The projected schema includes the partition columns.
+    let schema = Schema::try_from(exec.schema()).context(error::ConvertSchemaSnafu)?;
 
     Ok(Arc::new(PhysicalPlanAdapter::new(
         Arc::new(schema),
@@ -269,30 +300,15 @@ fn new_parquet_stream(
     config: &ScanPlanConfig,
     _format: &ParquetFormat,
 ) -> Result<SendableRecordBatchStream> {
-    let file_schema = config.file_schema.arrow_schema().clone();
+    let file_schema = file_arrow_schema(config);
     let ScanPlanConfig {
-        files,
-        projection,
         limit,
         filters,
         store,
         ..
     } = config;
 
-    let scan_config = FileScanConfig {
-        object_store_url: ObjectStoreUrl::parse("empty://").unwrap(), // won't be used
-        file_schema: file_schema.clone(),
-        file_groups: vec![files
-            .iter()
-            .map(|filename| PartitionedFile::new(filename.to_string(), 0))
-            .collect::<Vec<_>>()],
-        statistics: Default::default(),
-        projection: projection.cloned(),
-        limit: *limit,
-        table_partition_cols: vec![],
-        output_ordering: None,
-        infinite_source: false,
-    };
+    let scan_config = build_file_scan_config(config);
 
     let filters = filters
         .iter()
@@ -314,13 +330,13 @@ fn new_parquet_stream(
 
     let parquet_opener = ParquetOpener {
         partition_index: 0, // partition: hard-code. This is only for statistics purpose
-        projection: Arc::from(projection.cloned().unwrap_or_default()),
+        projection: Arc::from(file_projection(config).unwrap_or_default()),
         batch_size: DEFAULT_BATCH_SIZE,
         limit: *limit,
         predicate: filters,
         pruning_predicate: None,
         page_pruning_predicate: None,
-        table_schema: file_schema.clone(),
+        table_schema: file_schema,
         metadata_size_hint: None,
         metrics: ExecutionPlanMetricsSet::new(),
         parquet_file_reader_factory: Arc::new(DefaultParquetFileReaderFactory::new(store.clone())),
@@ -344,8 +360,13 @@ fn new_parquet_stream(
 
 #[derive(Debug, Clone)]
 pub struct ScanPlanConfig<'a> {
-    pub file_schema: SchemaRef,
+    /// Schema of the table, with partition columns at the end.
+    pub table_schema: SchemaRef,
     pub files: &'a Vec<String>,
+    /// Hive-style partition columns, see [ImmutableFileTableOptions](crate::table::immutable::ImmutableFileTableOptions).
+    pub partition_columns: &'a [String],
+    /// Values of partition columns for each file in `files`.
+    pub partition_values: &'a [Vec<String>],
     pub projection: Option<&'a Vec<usize>>,
     pub filters: &'a [Expr],
     pub limit: Option<usize>,
@@ -361,7 +382,7 @@ pub fn create_physical_plan(
         Format::Csv(format) => new_csv_scan_plan(ctx, config, format),
         Format::Json(format) => new_json_scan_plan(ctx, config, format),
         Format::Parquet(format) => new_parquet_scan_plan(ctx, config, format),
-        _ => error::UnsupportedFormatSnafu { format: *format }.fail(),
+        Format::Orc(format) => new_orc_scan_plan(ctx, config, format),
     }
 }
 
@@ -374,6 +395,6 @@ pub fn create_stream(
         Format::Csv(format) => new_csv_stream(ctx, config, format),
         Format::Json(format) => new_json_stream(ctx, config, format),
         Format::Parquet(format) => new_parquet_stream(ctx, config, format),
-        _ => error::UnsupportedFormatSnafu { format: *format }.fail(),
+        Format::Orc(format) => new_orc_stream(ctx, config, format),
     }
 }
@@ -13,22 +13,37 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use common_datasource::file_format::Format;
+use common_datasource::lister::{Lister, Source};
 use common_datasource::object_store::build_backend;
+use common_datasource::util::{find_dir_and_filename, parse_partition_values};
 use common_error::prelude::BoxedError;
 use common_query::physical_plan::PhysicalPlanRef;
 use common_query::prelude::Expr;
 use common_recordbatch::SendableRecordBatchStream;
+use datafusion::arrow::array::{ArrayRef, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::cast::as_boolean_array;
+use datafusion::common::ToDFSchema;
+use datafusion::error::Result as DfResult;
+use datafusion::logical_expr::utils::expr_to_columns;
+use datafusion::logical_expr::Expr as DfExpr;
+use datafusion::optimizer::utils::conjunction;
+use datafusion::physical_expr::create_physical_expr;
+use datafusion::physical_expr::execution_props::ExecutionProps;
 use datatypes::schema::SchemaRef;
 use object_store::ObjectStore;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use snafu::{OptionExt, ResultExt};
 use store_api::storage::{RegionNumber, ScanRequest};
 use table::error::{self as table_error, Result as TableResult};
-use table::metadata::{RawTableInfo, TableInfo, TableInfoRef, TableType};
+use table::metadata::{FilterPushDownType, RawTableInfo, TableInfo, TableInfoRef, TableType};
 use table::{requests, Table};
 
 use super::format::create_stream;
@@ -42,7 +57,12 @@ use crate::table::format::{create_physical_plan, CreateScanPlanContext, ScanPlan
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(default)]
 pub struct ImmutableFileTableOptions {
+    /// Files listed when the table was created. Files of partitioned tables are
+    /// listed again on each scan, so new partitions are visible without a refresh.
     pub files: Vec<String>,
+    /// Hive-style partition columns, whose values are parsed from `key=value`
+    /// directories in the path of each file.
+    pub partition_columns: Vec<String>,
 }
 
 pub struct ImmutableFileTable {
@@ -51,6 +71,11 @@ pub struct ImmutableFileTable {
     table_info: Arc<TableInfo>,
     object_store: ObjectStore,
     files: Vec<String>,
+    partition_columns: Vec<String>,
+    // values of partition columns for each file
+    partition_values: Vec<Vec<String>>,
+    // lists files in partition directories on each scan, only for partitioned tables
+    partition_lister: Option<Lister>,
     format: Format,
 }
 
@@ -82,14 +107,26 @@ impl Table for ImmutableFileTable {
         filters: &[Expr],
         limit: Option<usize>,
     ) -> TableResult<PhysicalPlanRef> {
+        let (files, partition_values) = self
+            .list_files()
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        let (files, partition_values, filters) = self
+            .prune_files(files, partition_values, filters)
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+
         create_physical_plan(
             &self.format,
             &CreateScanPlanContext::default(),
             &ScanPlanConfig {
-                file_schema: self.schema(),
-                files: &self.files,
+                table_schema: self.schema(),
+                files: &files,
+                partition_columns: &self.partition_columns,
+                partition_values: &partition_values,
                 projection,
-                filters,
+                filters: &filters,
                 limit,
                 store: self.object_store.clone(),
             },
@@ -99,14 +136,26 @@ impl Table for ImmutableFileTable {
     }
 
     async fn scan_to_stream(&self, request: ScanRequest) -> TableResult<SendableRecordBatchStream> {
+        let (files, partition_values) = self
+            .list_files()
+            .await
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+        let (files, partition_values, filters) = self
+            .prune_files(files, partition_values, &request.filters)
+            .map_err(BoxedError::new)
+            .context(table_error::TableOperationSnafu)?;
+
         create_stream(
             &self.format,
             &CreateScanPlanContext::default(),
             &ScanPlanConfig {
-                file_schema: self.schema(),
-                files: &self.files,
+                table_schema: self.schema(),
+                files: &files,
+                partition_columns: &self.partition_columns,
+                partition_values: &partition_values,
                 projection: request.projection.as_ref(),
-                filters: &request.filters,
+                filters: &filters,
                 limit: request.limit,
                 store: self.object_store.clone(),
             },
@@ -115,6 +164,24 @@ impl Table for ImmutableFileTable {
         .context(table_error::TableOperationSnafu)
     }
 
+    /// Filters only referencing partition columns are used to prune files, and filters
+    /// only referencing file columns are used to prune stripes of ORC files.
+    fn supports_filters_pushdown(&self, filters: &[&Expr]) -> TableResult<Vec<FilterPushDownType>> {
+        Ok(filters
+            .iter()
+            .map(|filter| {
+                if self.is_partition_filter(filter.df_expr())
+                    || (matches!(self.format, Format::Orc(_))
+                        && self.is_file_filter(filter.df_expr()))
+                {
+                    FilterPushDownType::Inexact
+                } else {
+                    FilterPushDownType::Unsupported
+                }
+            })
+            .collect())
+    }
+
     async fn flush(
         &self,
         _region_number: Option<RegionNumber>,
@@ -153,15 +220,156 @@ impl ImmutableFileTable {
 
         let object_store = build_backend(url, options).context(error::BuildBackendSnafu)?;
 
+        let (partition_values, partition_lister) = if meta.partition_columns.is_empty() {
+            (vec![], None)
+        } else {
+            let partition_values = meta
+                .files
+                .iter()
+                .map(|path| {
+                    parse_partition_values(path, &meta.partition_columns)
+                        .context(error::InvalidPartitionPathSnafu { path })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let (dir, filename) = find_dir_and_filename(url);
+            let source = if let Some(filename) = filename {
+                Source::Filename(filename)
+            } else {
+                Source::Dir
+            };
+            let regex = options
+                .get(requests::IMMUTABLE_TABLE_PATTERN_KEY)
+                .map(|x| Regex::new(x))
+                .transpose()
+                .context(error::BuildRegexSnafu)?;
+            let lister = Lister::new(object_store.clone(), source, dir, regex).with_recursive(true);
+            (partition_values, Some(lister))
+        };
+
         Ok(Self {
             metadata,
             table_info,
             object_store,
             files: meta.files,
+            partition_columns: meta.partition_columns,
+            partition_values,
+            partition_lister,
             format,
         })
     }
 
+    /// Returns the files to scan and their partition values.
+    ///
+    /// Files of a partitioned table are listed from the partition directories
+    /// every time, as partitions are usually added after the table is created.
+    /// Files not in partition directories are skipped. Files of other tables
+    /// are the ones listed when the table was created.
+    async fn list_files(&self) -> Result<(Vec<String>, Vec<Vec<String>>)> {
+        let Some(lister) = &self.partition_lister else {
+            return Ok((self.files.clone(), self.partition_values.clone()));
+        };
+
+        Ok(lister
+            .list()
+            .await
+            .context(error::ListPartitionFilesSnafu)?
+            .into_iter()
+            .filter(|entry| !entry.path().ends_with('/'))
+            .filter_map(|entry| {
+                parse_partition_values(entry.path(), &self.partition_columns)
+                    .map(|values| (entry.path().to_string(), values))
+            })
+            .unzip())
+    }
+
+    /// Returns whether the `filter` only references partition columns.
+    fn is_partition_filter(&self, filter: &DfExpr) -> bool {
+        let mut columns = HashSet::new();
+        if self.partition_columns.is_empty() || expr_to_columns(filter, &mut columns).is_err() {
+            return false;
+        }
+        !columns.is_empty()
+            && columns
+                .iter()
+                .all(|column| self.partition_columns.contains(&column.name))
+    }
+
+    fn is_file_filter(&self, filter: &DfExpr) -> bool {
+        let mut columns = HashSet::new();
+        if expr_to_columns(filter, &mut columns).is_err() {
+            return false;
+        }
+        !columns.is_empty()
+            && columns
+                .iter()
+                .all(|column| !self.partition_columns.contains(&column.name))
+    }
+
+    /// Prunes files by filters on partition columns. Returns the remaining files,
+    /// their partition values and the filters left to the file readers.
+    fn prune_files(
+        &self,
+        files: Vec<String>,
+        partition_values: Vec<Vec<String>>,
+        filters: &[Expr],
+    ) -> Result<(Vec<String>, Vec<Vec<String>>, Vec<Expr>)> {
+        let (partition_filters, file_filters): (Vec<_>, Vec<_>) = filters
+            .iter()
+            .cloned()
+            .partition(|filter| self.is_partition_filter(filter.df_expr()));
+
+        let predicate = conjunction(partition_filters.iter().map(|f| f.df_expr().clone()));
+        let Some(predicate) = predicate else {
+            return Ok((files, partition_values, file_filters));
+        };
+
+        let selected = self
+            .evaluate_partition_predicate(&partition_values, predicate)
+            .context(error::PrunePartitionsSnafu)?;
+        let (files, partition_values) = files
+            .into_iter()
+            .zip(partition_values)
+            .zip(selected)
+            .filter(|(_, selected)| *selected)
+            .map(|(file_and_values, _)| file_and_values)
+            .unzip();
+
+        Ok((files, partition_values, file_filters))
+    }
+
+    /// Evaluates the `predicate` against partition values, one row per file.
+    fn evaluate_partition_predicate(
+        &self,
+        partition_values: &[Vec<String>],
+        predicate: DfExpr,
+    ) -> DfResult<Vec<bool>> {
+        let schema = Arc::new(ArrowSchema::new(
+            self.partition_columns
+                .iter()
+                .map(|column| Field::new(column, DataType::Utf8, false))
+                .collect(),
+        ));
+        let columns = (0..self.partition_columns.len())
+            .map(|i| {
+                Arc::new(StringArray::from_iter_values(
+                    partition_values.iter().map(|values| &values[i]),
+                )) as ArrayRef
+            })
+            .collect();
+        let batch = RecordBatch::try_new(schema.clone(), columns)?;
+
+        let df_schema = schema.clone().to_dfschema_ref()?;
+        let predicate =
+            create_physical_expr(&predicate, &df_schema, &schema, &ExecutionProps::new())?;
+        let result = predicate.evaluate(&batch)?.into_array(batch.num_rows());
+        let result = as_boolean_array(&result)?;
+
+        Ok((0..result.len())
+            .map(|i| result.is_valid(i) && result.value(i))
+            .collect())
+    }
+
     pub async fn create(
         table_name: &str,
         table_dir: &str,
@@ -196,3 +404,151 @@ impl ImmutableFileTable {
         Ok((metadata, table_info))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use common_test_util::temp_dir::create_temp_dir;
+    use datafusion::prelude::{col, lit};
+
+    use super::*;
+    use crate::test_util;
+
+    fn new_table(meta: ImmutableFileTableOptions) -> Result<ImmutableFileTable> {
+        new_table_with_location("mock_path", meta)
+    }
+
+    fn new_table_with_location(
+        location: &str,
+        meta: ImmutableFileTableOptions,
+    ) -> Result<ImmutableFileTable> {
+        let mut table_info = test_util::build_test_table_info();
+        let options = &mut table_info.meta.options.extra_options;
+        let _ = options.insert(
+            requests::IMMUTABLE_TABLE_LOCATION_KEY.to_string(),
+            location.to_string(),
+        );
+        let _ = options.insert(
+            requests::IMMUTABLE_TABLE_META_KEY.to_string(),
+            serde_json::to_string(&meta).unwrap(),
+        );
+        let _ = options.insert(
+            requests::IMMUTABLE_TABLE_FORMAT_KEY.to_string(),
+            "csv".to_string(),
+        );
+
+        ImmutableFileTable::new(table_info, test_util::build_test_table_metadata())
+    }
+
+    fn new_partitioned_table() -> ImmutableFileTable {
+        new_table(ImmutableFileTableOptions {
+            files: vec![
+                "data/dt=2023-05-01/host=a/1.csv".to_string(),
+                "data/dt=2023-05-01/host=b/1.csv".to_string(),
+                "data/dt=2023-05-02/host=a/1.csv".to_string(),
+            ],
+            partition_columns: vec!["dt".to_string(), "host".to_string()],
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_prune_files() {
+        let table = new_partitioned_table();
+        assert_eq!(
+            vec![
+                vec!["2023-05-01".to_string(), "a".to_string()],
+                vec!["2023-05-01".to_string(), "b".to_string()],
+                vec!["2023-05-02".to_string(), "a".to_string()],
+            ],
+            table.partition_values
+        );
+
+        let partition_filter = Expr::from(col("dt").eq(lit("2023-05-01")));
+        let file_filter = Expr::from(col("cpu").gt(lit(1.0)));
+        let prune = |filters: &[Expr]| {
+            table.prune_files(table.files.clone(), table.partition_values.clone(), filters)
+        };
+        let (files, values, filters) =
+            prune(&[partition_filter.clone(), file_filter.clone()]).unwrap();
+        assert_eq!(
+            vec![
+                "data/dt=2023-05-01/host=a/1.csv".to_string(),
+                "data/dt=2023-05-01/host=b/1.csv".to_string(),
+            ],
+            files
+        );
+        assert_eq!(2, values.len());
+        assert_eq!(vec![file_filter.clone()], filters);
+
+        let filter = Expr::from(
+            col("dt")
+                .eq(lit("2023-05-02"))
+                .and(col("host").eq(lit("b"))),
+        );
+        let (files, values, _) = prune(&[filter]).unwrap();
+        assert!(files.is_empty());
+        assert!(values.is_empty());
+
+        let (files, _, filters) = prune(&[file_filter.clone()]).unwrap();
+        assert_eq!(3, files.len());
+        assert_eq!(vec![file_filter.clone()], filters);
+
+        assert_eq!(
+            vec![FilterPushDownType::Inexact, FilterPushDownType::Unsupported],
+            table
+                .supports_filters_pushdown(&[&partition_filter, &file_filter])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid_partition_path() {
+        let result = new_table(ImmutableFileTableOptions {
+            files: vec!["data/1.csv".to_string()],
+            partition_columns: vec!["dt".to_string()],
+        });
+        assert!(matches!(
+            result,
+            Err(error::Error::InvalidPartitionPath { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_new_partition_files() {
+        let dir = create_temp_dir("test_list_new_partition_files");
+        let root = dir.path().to_string_lossy().to_string();
+        let write_file = |path: &str| {
+            let path = format!("{root}/{path}");
+            std::fs::create_dir_all(std::path::Path::new(&path).parent().unwrap()).unwrap();
+            std::fs::write(path, "host,cpu\n").unwrap();
+        };
+        write_file("dt=2023-05-01/1.csv");
+        write_file("1.csv");
+
+        let table = new_table_with_location(
+            &format!("{root}/"),
+            ImmutableFileTableOptions {
+                files: vec![format!("{root}/dt=2023-05-01/1.csv")],
+                partition_columns: vec!["dt".to_string()],
+            },
+        )
+        .unwrap();
+        // Listed paths are relative to the root of the fs backend.
+        let prefix = root.trim_start_matches('/');
+        let (files, values) = table.list_files().await.unwrap();
+        assert_eq!(vec![format!("{prefix}/dt=2023-05-01/1.csv")], files);
+        assert_eq!(vec![vec!["2023-05-01".to_string()]], values);
+
+        // Partitions added after the table is created are visible.
+        write_file("dt=2023-05-02/1.csv");
+        let (mut files, _) = table.list_files().await.unwrap();
+        files.sort();
+        assert_eq!(
+            vec![
+                format!("{prefix}/dt=2023-05-01/1.csv"),
+                format!("{prefix}/dt=2023-05-02/1.csv"),
+            ],
+            files
+        );
+    }
+}
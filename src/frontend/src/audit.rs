@@ -0,0 +1,152 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write;
+
+use common_error::status_code::StatusCode;
+use common_telemetry::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use servers::interceptor::{QueryExecution, SqlQueryInterceptor};
+use session::context::QueryContextRef;
+use snafu::ResultExt;
+use tracing_appender::non_blocking::{NonBlocking, NonBlockingBuilder, WorkerGuard};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+use crate::error::{CreateAuditLogDirSnafu, Error, Result};
+
+const AUDIT_LOG_FILE_NAME: &str = "audit";
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct AuditLogOptions {
+    pub enable: bool,
+    pub dir: String,
+}
+
+impl Default for AuditLogOptions {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            dir: "/tmp/greptimedb/audit".to_string(),
+        }
+    }
+}
+
+/// Writes every executed sql statement and PromQL query, along with its user,
+/// connection and outcome, as a json line into hourly rotated files.
+/// Credentials in statements are redacted.
+pub struct AuditLogInterceptor {
+    writer: NonBlocking,
+    _guard: WorkerGuard,
+}
+
+impl AuditLogInterceptor {
+    pub fn try_new(opts: &AuditLogOptions) -> Result<Self> {
+        std::fs::create_dir_all(&opts.dir).context(CreateAuditLogDirSnafu { dir: &opts.dir })?;
+
+        let appender = RollingFileAppender::new(Rotation::HOURLY, &opts.dir, AUDIT_LOG_FILE_NAME);
+        // Audit records must not be dropped, writing is blocked instead if the
+        // background writer falls behind.
+        let (writer, guard) = NonBlockingBuilder::default().lossy(false).finish(appender);
+        Ok(Self {
+            writer,
+            _guard: guard,
+        })
+    }
+}
+
+impl SqlQueryInterceptor for AuditLogInterceptor {
+    type Error = Error;
+
+    fn on_execute_finished(&self, execution: &QueryExecution, query_ctx: QueryContextRef) {
+        let mut line = audit_record(execution, &query_ctx).to_string();
+        line.push('\n');
+
+        let mut writer = self.writer.clone();
+        if let Err(e) = writer.write_all(line.as_bytes()) {
+            warn!("Failed to write audit log, error: {}", e);
+        }
+    }
+}
+
+fn audit_record(execution: &QueryExecution, query_ctx: &QueryContextRef) -> Value {
+    let conn_info = query_ctx.conn_info();
+    let (status, error) = match &execution.error {
+        Some((status_code, msg)) => (status_code.to_string(), Some(msg.as_str())),
+        None => (StatusCode::Success.to_string(), None),
+    };
+    json!({
+        "timestamp": common_time::util::current_time_rfc3339(),
+        "user": query_ctx.current_user().username(),
+        "client_addr": conn_info
+            .as_ref()
+            .and_then(|conn_info| conn_info.client_addr)
+            .map(|addr| addr.to_string()),
+        "channel": conn_info.as_ref().map(|conn_info| conn_info.channel.to_string()),
+        "catalog": query_ctx.current_catalog(),
+        "schema": query_ctx.current_schema(),
+        "query": execution.query,
+        "elapsed_ms": execution.elapsed.as_millis() as u64,
+        "rows": execution.rows,
+        "status": status,
+        "error": error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use session::context::{Channel, ConnInfo, QueryContext, UserInfo};
+
+    use super::*;
+
+    #[test]
+    fn test_audit_record() {
+        let query_ctx = QueryContext::arc();
+        query_ctx.set_current_user(UserInfo::new("alice"));
+        query_ctx.set_conn_info(ConnInfo::new(
+            Some("127.0.0.1:4002".parse().unwrap()),
+            Channel::Mysql,
+        ));
+
+        let execution = QueryExecution {
+            query: "SELECT * FROM numbers".to_string(),
+            elapsed: Duration::from_millis(42),
+            rows: 10,
+            error: None,
+        };
+        let record = audit_record(&execution, &query_ctx);
+        assert_eq!("alice", record["user"]);
+        assert_eq!("127.0.0.1:4002", record["client_addr"]);
+        assert_eq!("mysql", record["channel"]);
+        assert_eq!("greptime", record["catalog"]);
+        assert_eq!("public", record["schema"]);
+        assert_eq!("SELECT * FROM numbers", record["query"]);
+        assert_eq!(42, record["elapsed_ms"]);
+        assert_eq!(10, record["rows"]);
+        assert_eq!("Success", record["status"]);
+        assert!(record["error"].is_null());
+
+        let execution = QueryExecution {
+            error: Some((StatusCode::TableNotFound, "Table not found".to_string())),
+            ..execution
+        };
+        let record = audit_record(&execution, &QueryContext::arc());
+        assert!(record["client_addr"].is_null());
+        assert_eq!("TableNotFound", record["status"]);
+        assert_eq!("Table not found", record["error"]);
+    }
+}
@@ -13,10 +13,10 @@
 // limitations under the License.
 
 use std::any::Any;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use api::v1::CreateTableExpr;
+use api::v1::{CreateDatabaseExpr, CreateTableExpr};
 use catalog::error::{
     self as catalog_err, InternalSnafu, InvalidCatalogValueSnafu, InvalidSystemTableDefSnafu,
     Result as CatalogResult, UnimplementedSnafu,
@@ -26,6 +26,7 @@ use catalog::helper::{
     TableGlobalKey, TableGlobalValue,
 };
 use catalog::information_schema::InformationSchemaProvider;
+use catalog::process_manager::{ProcessManager, ProcessManagerRef};
 use catalog::remote::{Kv, KvBackendRef, KvCacheInvalidatorRef};
 use catalog::{
     CatalogManager, DeregisterTableRequest, RegisterSchemaRequest, RegisterSystemTableRequest,
@@ -33,14 +34,18 @@ use catalog::{
 };
 use client::client_manager::DatanodeClients;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, INFORMATION_SCHEMA_NAME};
+use common_catalog::format_full_table_name;
 use common_error::prelude::BoxedError;
 use common_meta::table_name::TableName;
 use common_telemetry::warn;
 use futures::StreamExt;
 use futures_util::TryStreamExt;
 use partition::manager::PartitionRuleManagerRef;
+use session::context::QueryContext;
 use snafu::prelude::*;
+use table::metadata::{RawTableInfo, TableId, TableType};
 use table::table::numbers::NumbersTable;
+use table::table::view::ViewTable;
 use table::TableRef;
 
 use crate::expr_factory;
@@ -59,6 +64,8 @@ pub struct FrontendCatalogManager {
     // Once we have some standalone distributed table creator (like create distributed table procedure),
     // we should use that.
     dist_instance: Option<Arc<DistInstance>>,
+
+    process_manager: ProcessManagerRef,
 }
 
 impl FrontendCatalogManager {
@@ -74,9 +81,25 @@ impl FrontendCatalogManager {
             partition_manager,
             datanode_clients,
             dist_instance: None,
+            process_manager: Arc::new(ProcessManager::default()),
         }
     }
 
+    /// Allocates the table id of a view from Metasrv, where tables take their ids from as well.
+    async fn next_view_id(&self) -> CatalogResult<TableId> {
+        let Some(dist_instance) = self.dist_instance.as_ref() else {
+            return UnimplementedSnafu {
+                operation: "allocate table id for view",
+            }
+            .fail();
+        };
+        dist_instance
+            .allocate_table_id()
+            .await
+            .map_err(BoxedError::new)
+            .context(InternalSnafu)
+    }
+
     pub fn set_dist_instance(&mut self, dist_instance: Arc<DistInstance>) {
         self.dist_instance = Some(dist_instance)
     }
@@ -133,11 +156,110 @@ impl CatalogManager for FrontendCatalogManager {
     }
 
     // TODO(LFC): Handle the table caching in (de)register_table.
-    async fn register_table(&self, _request: RegisterTableRequest) -> CatalogResult<bool> {
-        Ok(true)
+    async fn register_table(&self, request: RegisterTableRequest) -> CatalogResult<bool> {
+        let table_info = request.table.table_info();
+        if table_info.table_type != TableType::View {
+            return Ok(true);
+        }
+
+        // Views have no regions on datanodes, they only live in the catalog.
+        let table_id = self.next_view_id().await?;
+        let table_info = ViewTable::new_with_table_id(&table_info, table_id).table_info();
+        let table_global_key = TableGlobalKey {
+            catalog_name: request.catalog,
+            schema_name: request.schema,
+            table_name: request.table_name,
+        };
+        let table_global_value = TableGlobalValue {
+            node_id: 0,
+            regions_id_map: HashMap::new(),
+            table_info: RawTableInfo::from(table_info.as_ref().clone()),
+        }
+        .as_bytes()
+        .context(InvalidCatalogValueSnafu)?;
+        let result = self
+            .backend
+            .compare_and_set(
+                table_global_key.to_string().as_bytes(),
+                &[],
+                &table_global_value,
+            )
+            .await?;
+        Ok(result.is_ok())
+    }
+
+    async fn replace_view(&self, request: RegisterTableRequest) -> CatalogResult<()> {
+        let table_global_key = TableGlobalKey {
+            catalog_name: request.catalog.clone(),
+            schema_name: request.schema.clone(),
+            table_name: request.table_name.clone(),
+        }
+        .to_string();
+        let full_table_name =
+            format_full_table_name(&request.catalog, &request.schema, &request.table_name);
+
+        let existing = self
+            .backend
+            .get(table_global_key.as_bytes())
+            .await?
+            .map(|kv| kv.1);
+        let existing_value = existing
+            .as_ref()
+            .map(|v| TableGlobalValue::from_bytes(v).context(InvalidCatalogValueSnafu))
+            .transpose()?
+            .filter(|v| v.table_info.table_type == TableType::View)
+            .with_context(|| catalog_err::TableNotExistSnafu {
+                table: &full_table_name,
+            })?;
+
+        let table_info = ViewTable::new_with_table_id(
+            &request.table.table_info(),
+            existing_value.table_info.ident.table_id,
+        )
+        .table_info();
+        let table_global_value = TableGlobalValue {
+            node_id: 0,
+            regions_id_map: HashMap::new(),
+            table_info: RawTableInfo::from(table_info.as_ref().clone()),
+        }
+        .as_bytes()
+        .context(InvalidCatalogValueSnafu)?;
+        // Only replaces the view this request is based on, a concurrent replacement
+        // or drop fails this one instead of being overwritten.
+        let result = self
+            .backend
+            .compare_and_set(
+                table_global_key.as_bytes(),
+                existing.as_deref().unwrap_or_default(),
+                &table_global_value,
+            )
+            .await?;
+        ensure!(
+            result.is_ok(),
+            catalog_err::GenericSnafu {
+                msg: format!("View {full_table_name} was modified concurrently"),
+            }
+        );
+
+        self.invalidate_table(&request.catalog, &request.schema, &request.table_name)
+            .await;
+        Ok(())
     }
 
     async fn deregister_table(&self, request: DeregisterTableRequest) -> CatalogResult<()> {
+        let table_global_key = TableGlobalKey {
+            catalog_name: request.catalog.clone(),
+            schema_name: request.schema.clone(),
+            table_name: request.table_name.clone(),
+        }
+        .to_string();
+        if let Some(kv) = self.backend.get(table_global_key.as_bytes()).await? {
+            let v = TableGlobalValue::from_bytes(kv.1).context(InvalidCatalogValueSnafu)?;
+            if v.table_info.table_type == TableType::View {
+                return self.backend.delete(table_global_key.as_bytes()).await;
+            }
+        }
+
         let table_name = TableName::new(request.catalog, request.schema, request.table_name);
         self.partition_manager
             .table_routes()
@@ -179,6 +301,21 @@ impl CatalogManager for FrontendCatalogManager {
                 return Ok(());
             }
 
+            // System tables may be put into the internal schema, which is created on demand.
+            let expr = CreateDatabaseExpr {
+                database_name: request.schema_name.clone(),
+                create_if_not_exists: true,
+            };
+            let query_ctx = Arc::new(QueryContext::with(
+                &request.catalog_name,
+                &request.schema_name,
+            ));
+            let _ = dist_instance
+                .handle_create_database(expr, query_ctx)
+                .await
+                .map_err(BoxedError::new)
+                .context(InternalSnafu)?;
+
             let time_index = request
                 .schema
                 .column_schemas
@@ -353,7 +490,8 @@ impl CatalogManager for FrontendCatalogManager {
             };
 
             let provider =
-                InformationSchemaProvider::new(catalog.to_string(), Arc::downgrade(&manager));
+                InformationSchemaProvider::new(catalog.to_string(), Arc::downgrade(&manager))
+                    .with_process_manager(self.process_manager.clone());
             return provider.table(table_name);
         }
 
@@ -366,11 +504,15 @@ impl CatalogManager for FrontendCatalogManager {
             return Ok(None);
         };
         let v = TableGlobalValue::from_bytes(kv.1).context(InvalidCatalogValueSnafu)?;
+        let table_type = v.table_info.table_type;
         let table_info = Arc::new(
             v.table_info
                 .try_into()
                 .context(catalog_err::InvalidTableInfoInCatalogSnafu)?,
         );
+        if table_type == TableType::View {
+            return Ok(Some(Arc::new(ViewTable::new(table_info))));
+        }
         let table = Arc::new(DistTable::new(
             TableName::new(catalog, schema, table_name),
             table_info,
@@ -382,4 +524,8 @@ impl CatalogManager for FrontendCatalogManager {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn process_manager(&self) -> Option<ProcessManagerRef> {
+        Some(self.process_manager.clone())
+    }
 }
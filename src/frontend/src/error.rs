@@ -14,10 +14,10 @@
 
 use std::any::Any;
 
-use common_datasource::file_format::Format;
 use common_error::prelude::*;
 use datafusion::parquet;
 use datatypes::arrow::error::ArrowError;
+use datatypes::prelude::ConcreteDataType;
 use datatypes::value::Value;
 use snafu::Location;
 use store_api::storage::RegionNumber;
@@ -387,6 +387,24 @@ pub enum Error {
         source: servers::error::Error,
     },
 
+    #[snafu(display("Failed to create logical plan for InfluxQL query, source: {}", source))]
+    InfluxqlQueryPlan {
+        #[snafu(backtrace)]
+        source: servers::error::Error,
+    },
+
+    #[snafu(display("Failed to create logical plan for OpenTSDB query, source: {}", source))]
+    OpentsdbQueryPlan {
+        #[snafu(backtrace)]
+        source: servers::error::Error,
+    },
+
+    #[snafu(display("Table {} has no time index column", table_name))]
+    MissingTimeIndexColumn {
+        table_name: String,
+        location: Location,
+    },
+
     #[snafu(display("Failed to describe schema for given statement, source: {}", source))]
     DescribeStatement {
         #[snafu(backtrace)]
@@ -444,9 +462,6 @@ pub enum Error {
         source: common_datasource::error::Error,
     },
 
-    #[snafu(display("Unsupported format: {:?}", format))]
-    UnsupportedFormat { location: Location, format: Format },
-
     #[snafu(display("Failed to parse file format, source: {}", source))]
     ParseFileFormat {
         #[snafu(backtrace)]
@@ -553,6 +568,12 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Failed to decode object from json, source: {}", source))]
+    DecodeJson {
+        source: serde_json::error::Error,
+        location: Location,
+    },
+
     #[snafu(display("Failed to prepare immutable table: {}", source))]
     PrepareImmutableTable {
         #[snafu(backtrace)]
@@ -565,6 +586,104 @@ pub enum Error {
         value: String,
         location: Location,
     },
+
+    #[snafu(display("Failed to check privileges, source: {}", source))]
+    Auth {
+        location: Location,
+        source: servers::auth::Error,
+    },
+
+    #[snafu(display("User or role already exists: {}", name))]
+    PrincipalExists { name: String, location: Location },
+
+    #[snafu(display("User or role not found: {}", name))]
+    PrincipalNotFound { name: String, location: Location },
+
+    #[snafu(display("Failed to collect record batches, source: {}", source))]
+    CollectRecordbatch {
+        location: Location,
+        source: common_recordbatch::error::Error,
+    },
+
+    #[snafu(display("Invalid column {} in system table {}", column, table_name))]
+    InvalidSystemTableColumn {
+        table_name: String,
+        column: String,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to create audit log dir: {}, source: {}", dir, source))]
+    CreateAuditLogDir {
+        dir: String,
+        location: Location,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("Materialized view not found: {}", name))]
+    MaterializedViewNotFound { name: String, location: Location },
+
+    #[snafu(display("Invalid refresh interval: {}, source: {}", value, source))]
+    InvalidRefreshInterval {
+        value: String,
+        location: Location,
+        source: humantime::DurationError,
+    },
+
+    #[snafu(display(
+        "Failed to convert data type {:?} to SQL data type, source: {}",
+        datatype,
+        source
+    ))]
+    ConvertSqlType {
+        datatype: ConcreteDataType,
+        #[snafu(backtrace)]
+        source: sql::error::Error,
+    },
+
+    #[snafu(display("Query {} was killed", id))]
+    QueryKilled { id: u64, location: Location },
+
+    #[snafu(display("Unknown process id: {}", id))]
+    ProcessNotFound { id: u64, location: Location },
+
+    #[snafu(display("Failed to split query result by steps, source: {}", source))]
+    SplitQueryResult {
+        location: Location,
+        source: datatypes::error::Error,
+    },
+
+    #[snafu(display("Failed to build cached query result, source: {}", source))]
+    BuildCachedResult {
+        location: Location,
+        source: common_recordbatch::error::Error,
+    },
+
+    #[snafu(display(
+        "Writes to {} exceed the limit of {} {} per second",
+        target,
+        limit,
+        unit
+    ))]
+    RateLimited {
+        target: String,
+        limit: u64,
+        unit: String,
+        location: Location,
+    },
+
+    #[snafu(display("Database {} exceeds the quota of {} tables", database, max_tables))]
+    TableQuotaExceeded {
+        database: String,
+        max_tables: usize,
+        location: Location,
+    },
+
+    #[snafu(display("Table {} exceeds the quota of {} columns", table, max_columns))]
+    ColumnQuotaExceeded {
+        table: String,
+        max_columns: usize,
+        location: Location,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -585,8 +704,7 @@ impl ErrorExt for Error {
             | Error::InvalidSchema { .. }
             | Error::PrepareImmutableTable { .. }
             | Error::BuildCsvConfig { .. }
-            | Error::ProjectSchema { .. }
-            | Error::UnsupportedFormat { .. } => StatusCode::InvalidArguments,
+            | Error::ProjectSchema { .. } => StatusCode::InvalidArguments,
 
             Error::NotSupported { .. } => StatusCode::Unsupported,
 
@@ -594,6 +712,8 @@ impl ErrorExt for Error {
 
             Error::RuntimeResource { source, .. } => source.status_code(),
             Error::PrometheusRemoteQueryPlan { source, .. }
+            | Error::InfluxqlQueryPlan { source, .. }
+            | Error::OpentsdbQueryPlan { source, .. }
             | Error::ExecutePromql { source, .. } => source.status_code(),
 
             Error::SqlExecIntercepted { source, .. } => source.status_code(),
@@ -631,9 +751,11 @@ impl ErrorExt for Error {
 
             Error::IncompleteGrpcResult { .. }
             | Error::ContextValueNotFound { .. }
-            | Error::EncodeJson { .. } => StatusCode::Unexpected,
+            | Error::EncodeJson { .. }
+            | Error::DecodeJson { .. } => StatusCode::Unexpected,
 
             Error::TableNotFound { .. } => StatusCode::TableNotFound,
+            Error::MissingTimeIndexColumn { .. } => StatusCode::InvalidArguments,
 
             Error::JoinTask { .. }
             | Error::BuildParquetRecordBatchStream { .. }
@@ -688,6 +810,25 @@ impl ErrorExt for Error {
 
             Error::WriteParquet { source, .. } => source.status_code(),
             Error::InvalidCopyParameter { .. } => StatusCode::InvalidArguments,
+
+            Error::Auth { source, .. } => source.status_code(),
+            Error::PrincipalExists { .. } | Error::PrincipalNotFound { .. } => {
+                StatusCode::InvalidArguments
+            }
+            Error::CollectRecordbatch { source, .. } => source.status_code(),
+            Error::InvalidSystemTableColumn { .. } => StatusCode::Unexpected,
+            Error::CreateAuditLogDir { .. } => StatusCode::Internal,
+            Error::MaterializedViewNotFound { .. } => StatusCode::TableNotFound,
+            Error::InvalidRefreshInterval { .. } => StatusCode::InvalidArguments,
+            Error::ConvertSqlType { source, .. } => source.status_code(),
+            Error::QueryKilled { .. } => StatusCode::Cancelled,
+            Error::ProcessNotFound { .. } => StatusCode::InvalidArguments,
+            Error::SplitQueryResult { source, .. } => source.status_code(),
+            Error::BuildCachedResult { source, .. } => source.status_code(),
+            Error::RateLimited { .. } => StatusCode::RateLimited,
+            Error::TableQuotaExceeded { .. } | Error::ColumnQuotaExceeded { .. } => {
+                StatusCode::QuotaExceeded
+            }
         }
     }
 
@@ -94,11 +94,23 @@ pub(crate) async fn create_external_expr(
 
     let mut options = create.options;
 
-    let (files, schema) = prepare_immutable_file_table_files_and_schema(&options, &create.columns)
-        .await
-        .context(error::PrepareImmutableTableSnafu)?;
+    let partition_columns = create
+        .partition_columns
+        .iter()
+        .map(|column| column.value.clone())
+        .collect::<Vec<_>>();
+    let (files, schema) = prepare_immutable_file_table_files_and_schema(
+        &options,
+        &create.columns,
+        &partition_columns,
+    )
+    .await
+    .context(error::PrepareImmutableTableSnafu)?;
 
-    let meta = ImmutableFileTableOptions { files };
+    let meta = ImmutableFileTableOptions {
+        files,
+        partition_columns,
+    };
     let _ = options.insert(
         IMMUTABLE_TABLE_META_KEY.to_string(),
         serde_json::to_string(&meta).context(error::EncodeJsonSnafu)?,
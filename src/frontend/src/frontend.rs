@@ -14,14 +14,19 @@
 
 use common_telemetry::logging::LoggingOptions;
 use meta_client::MetaClientOptions;
+use query::query_engine::options::QueryMemoryOptions;
 use serde::{Deserialize, Serialize};
 use servers::http::HttpOptions;
 use servers::Mode;
 
+use crate::audit::AuditLogOptions;
+use crate::query_cache::QueryCacheOptions;
 use crate::service_config::{
-    GrpcOptions, InfluxdbOptions, MysqlOptions, OpentsdbOptions, PostgresOptions, PromOptions,
-    PrometheusOptions,
+    GraphiteOptions, GrpcOptions, InfluxdbOptions, MysqlOptions, OpentsdbOptions, PostgresOptions,
+    PromOptions, PrometheusOptions, StatsdOptions,
 };
+use crate::slow_query::SlowQueryOptions;
+use crate::write_limit::WriteLimitOptions;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default)]
@@ -32,11 +37,18 @@ pub struct FrontendOptions {
     pub mysql_options: Option<MysqlOptions>,
     pub postgres_options: Option<PostgresOptions>,
     pub opentsdb_options: Option<OpentsdbOptions>,
+    pub graphite_options: Option<GraphiteOptions>,
+    pub statsd_options: Option<StatsdOptions>,
     pub influxdb_options: Option<InfluxdbOptions>,
     pub prometheus_options: Option<PrometheusOptions>,
     pub prom_options: Option<PromOptions>,
     pub meta_client_options: Option<MetaClientOptions>,
     pub logging: LoggingOptions,
+    pub audit_log: AuditLogOptions,
+    pub slow_query: SlowQueryOptions,
+    pub query_memory: QueryMemoryOptions,
+    pub query_cache: QueryCacheOptions,
+    pub write_limit: WriteLimitOptions,
 }
 
 impl Default for FrontendOptions {
@@ -48,11 +60,18 @@ impl Default for FrontendOptions {
             mysql_options: Some(MysqlOptions::default()),
             postgres_options: Some(PostgresOptions::default()),
             opentsdb_options: Some(OpentsdbOptions::default()),
+            graphite_options: None,
+            statsd_options: None,
             influxdb_options: Some(InfluxdbOptions::default()),
             prometheus_options: Some(PrometheusOptions::default()),
             prom_options: Some(PromOptions::default()),
             meta_client_options: None,
             logging: LoggingOptions::default(),
+            audit_log: AuditLogOptions::default(),
+            slow_query: SlowQueryOptions::default(),
+            query_memory: QueryMemoryOptions::default(),
+            query_cache: QueryCacheOptions::default(),
+            write_limit: WriteLimitOptions::default(),
         }
     }
 }
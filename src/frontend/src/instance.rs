@@ -13,6 +13,7 @@
 // limitations under the License.
 
 pub mod distributed;
+mod graphite;
 mod grpc;
 mod influxdb;
 mod opentsdb;
@@ -22,49 +23,60 @@ mod standalone;
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use api::v1::alter_expr::Kind;
 use api::v1::ddl_request::Expr as DdlExpr;
 use api::v1::greptime_request::Request;
 use api::v1::meta::Role;
 use api::v1::{AddColumns, AlterExpr, Column, DdlRequest, InsertRequest, InsertRequests};
+use async_stream::try_stream;
 use async_trait::async_trait;
+use catalog::process_manager::{ProcessHandle, QueryStage, SuperuserChecker};
+use catalog::remote::lease::Lease;
 use catalog::remote::CachedMetaKvBackend;
 use catalog::CatalogManagerRef;
 use client::client_manager::DatanodeClients;
 use common_base::Plugins;
 use common_catalog::consts::MITO_ENGINE;
 use common_error::ext::BoxedError;
+use common_error::prelude::{ErrorExt, StatusCode};
 use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
 use common_meta::heartbeat::handler::parse_mailbox_message::ParseMailboxMessageHandler;
 use common_meta::heartbeat::handler::HandlerGroupExecutor;
 use common_query::Output;
-use common_telemetry::logging::{debug, info};
+use common_recordbatch::{display_plan_with_metrics, util, RecordBatchStreamAdaptor};
+use common_telemetry::logging::{debug, info, warn};
 use common_telemetry::timer;
+use datafusion::physical_plan::ExecutionPlan;
 use datafusion::sql::sqlparser::ast::ObjectName;
 use datanode::instance::sql::table_idents_to_full_name;
 use datanode::instance::InstanceRef as DnInstanceRef;
 use datatypes::schema::Schema;
 use distributed::DistInstance;
-use futures::future;
+use futures::{future, StreamExt};
 use meta_client::client::{MetaClient, MetaClientBuilder};
 use meta_client::MetaClientOptions;
 use partition::manager::PartitionRuleManager;
 use partition::route::TableRoutes;
+use promql_parser::parser::EvalStmt;
+use prost::Message;
 use query::parser::{PromQuery, QueryLanguageParser, QueryStatement};
 use query::plan::LogicalPlan;
 use query::query_engine::options::{validate_catalog_and_schema, QueryOptions};
 use query::query_engine::DescribeResult;
 use query::{QueryEngineFactory, QueryEngineRef};
+use servers::auth::user_provider::StaticUserProvider;
+use servers::auth::{Privilege, UserProviderRef};
 use servers::error as server_error;
 use servers::error::{ExecuteQuerySnafu, ParsePromQLSnafu};
-use servers::interceptor::{SqlQueryInterceptor, SqlQueryInterceptorRef};
+use servers::interceptor::{report_execution, SqlQueryInterceptor, SqlQueryInterceptorRef};
 use servers::prom::PromHandler;
 use servers::query_handler::grpc::{GrpcQueryHandler, GrpcQueryHandlerRef};
 use servers::query_handler::sql::SqlQueryHandler;
 use servers::query_handler::{
-    InfluxdbLineProtocolHandler, OpentsdbProtocolHandler, PrometheusProtocolHandler, ScriptHandler,
+    GraphiteProtocolHandler, InfluxdbLineProtocolHandler, OpentsdbProtocolHandler,
+    PrometheusProtocolHandler, ScriptHandler,
 };
 use session::context::QueryContextRef;
 use snafu::prelude::*;
@@ -72,28 +84,41 @@ use sql::dialect::Dialect;
 use sql::parser::ParserContext;
 use sql::statements::copy::CopyTable;
 use sql::statements::statement::Statement;
+use sql::util::{redact_sql, split_and_redact_sql};
+use table::engine::TableReference;
+use table::TableRef;
 
+use crate::audit::{AuditLogInterceptor, AuditLogOptions};
 use crate::catalog::FrontendCatalogManager;
 use crate::error::{
-    self, Error, ExecLogicalPlanSnafu, ExecutePromqlSnafu, ExternalSnafu,
-    InvalidInsertRequestSnafu, MissingMetasrvOptsSnafu, ParseSqlSnafu, PlanStatementSnafu, Result,
-    SqlExecInterceptedSnafu,
+    self, CollectRecordbatchSnafu, Error, ExecLogicalPlanSnafu, ExecutePromqlSnafu, ExternalSnafu,
+    InvalidInsertRequestSnafu, MissingMetasrvOptsSnafu, ParseSqlSnafu, PlanStatementSnafu,
+    QueryKilledSnafu, Result, SqlExecInterceptedSnafu,
 };
 use crate::expr_factory::{CreateExprFactoryRef, DefaultCreateExprFactory};
 use crate::frontend::FrontendOptions;
 use crate::heartbeat::handler::invalidate_table_cache::InvalidateTableCacheHandler;
 use crate::heartbeat::HeartbeatTask;
 use crate::instance::standalone::StandaloneGrpcQueryHandler;
+use crate::materialized_view::{MaterializedViewManager, REFRESH_LEASE_NAME, REFRESH_LEASE_TTL};
 use crate::metrics;
-use crate::script::ScriptExecutor;
+use crate::query_cache::{
+    self, PlanCache, PlanCacheKey, PlanCacheRef, PromCacheKey, PromQueryCache, PromQueryCacheRef,
+    QueryCacheOptions, StepRange,
+};
+use crate::rbac::RbacUserProvider;
+use crate::script::{ScriptExecutor, SCRIPT_RUN_LEASE_NAME, SCRIPT_RUN_LEASE_TTL};
 use crate::server::{start_server, ServerHandlers, Services};
+use crate::slow_query::{SlowQuery, SlowQueryLog, SlowQueryLogRef, SlowQueryOptions};
 use crate::statement::StatementExecutor;
+use crate::write_limit::{TableWrites, WriteLimitOptions, WriteLimiter};
 
 #[async_trait]
 pub trait FrontendInstance:
     GrpcQueryHandler<Error = Error>
     + SqlQueryHandler<Error = Error>
     + OpentsdbProtocolHandler
+    + GraphiteProtocolHandler
     + InfluxdbLineProtocolHandler
     + PrometheusProtocolHandler
     + ScriptHandler
@@ -113,6 +138,7 @@ pub struct Instance {
     catalog_manager: CatalogManagerRef,
     script_executor: Arc<ScriptExecutor>,
     statement_executor: Arc<StatementExecutor>,
+    materialized_views: Arc<MaterializedViewManager>,
     query_engine: QueryEngineRef,
     grpc_query_handler: GrpcQueryHandlerRef<Error>,
 
@@ -125,6 +151,11 @@ pub struct Instance {
     servers: Arc<ServerHandlers>,
 
     heartbeat_task: Option<HeartbeatTask>,
+
+    slow_query_log: Option<SlowQueryLogRef>,
+
+    prom_query_cache: Option<PromQueryCacheRef>,
+    plan_cache: Option<PlanCacheRef>,
 }
 
 impl Instance {
@@ -136,6 +167,8 @@ impl Instance {
 
         let datanode_clients = Arc::new(DatanodeClients::default());
 
+        plugins.insert(opts.query_memory.clone());
+
         Self::try_new_distributed_with(meta_client, datanode_clients, plugins).await
     }
 
@@ -174,13 +207,42 @@ impl Instance {
         )
         .query_engine();
 
-        let script_executor =
-            Arc::new(ScriptExecutor::new(catalog_manager.clone(), query_engine.clone()).await?);
+        // Frontends elect the one to run scheduled scripts by the lease.
+        let run_lease = Lease::new(
+            meta_backend.clone(),
+            SCRIPT_RUN_LEASE_NAME,
+            uuid::Uuid::new_v4().to_string(),
+            SCRIPT_RUN_LEASE_TTL,
+        );
+        let script_executor = Arc::new(
+            ScriptExecutor::new(
+                catalog_manager.clone(),
+                query_engine.clone(),
+                Some(run_lease),
+            )
+            .await?,
+        );
+        // Frontends elect the one to refresh materialized views by the lease.
+        let refresh_lease = Lease::new(
+            meta_backend.clone(),
+            REFRESH_LEASE_NAME,
+            uuid::Uuid::new_v4().to_string(),
+            REFRESH_LEASE_TTL,
+        );
+        let materialized_views = Arc::new(
+            MaterializedViewManager::try_new(
+                catalog_manager.clone(),
+                query_engine.clone(),
+                Some(refresh_lease),
+            )
+            .await?,
+        );
 
         let statement_executor = Arc::new(StatementExecutor::new(
             catalog_manager.clone(),
             query_engine.clone(),
             dist_instance.clone(),
+            materialized_views.clone(),
         ));
 
         plugins.insert::<StatementExecutorRef>(statement_executor.clone());
@@ -205,11 +267,15 @@ impl Instance {
             script_executor,
             create_expr_factory: Arc::new(DefaultCreateExprFactory),
             statement_executor,
+            materialized_views,
             query_engine,
             grpc_query_handler: dist_instance,
             plugins: plugins.clone(),
             servers: Arc::new(HashMap::new()),
             heartbeat_task,
+            slow_query_log: None,
+            prom_query_cache: None,
+            plan_cache: None,
         })
     }
 
@@ -237,6 +303,7 @@ impl Instance {
             .enable_router()
             .enable_store()
             .enable_heartbeat()
+            .enable_ddl()
             .channel_manager(channel_manager)
             .build();
         meta_client
@@ -249,13 +316,19 @@ impl Instance {
     pub async fn try_new_standalone(dn_instance: DnInstanceRef) -> Result<Self> {
         let catalog_manager = dn_instance.catalog_manager();
         let query_engine = dn_instance.query_engine();
-        let script_executor =
-            Arc::new(ScriptExecutor::new(catalog_manager.clone(), query_engine.clone()).await?);
+        let script_executor = Arc::new(
+            ScriptExecutor::new(catalog_manager.clone(), query_engine.clone(), None).await?,
+        );
+        let materialized_views = Arc::new(
+            MaterializedViewManager::try_new(catalog_manager.clone(), query_engine.clone(), None)
+                .await?,
+        );
 
         let statement_executor = Arc::new(StatementExecutor::new(
             catalog_manager.clone(),
             query_engine.clone(),
             dn_instance.clone(),
+            materialized_views.clone(),
         ));
 
         Ok(Instance {
@@ -263,11 +336,15 @@ impl Instance {
             script_executor,
             create_expr_factory: Arc::new(DefaultCreateExprFactory),
             statement_executor,
+            materialized_views,
             query_engine,
             grpc_query_handler: StandaloneGrpcQueryHandler::arc(dn_instance.clone()),
             plugins: Default::default(),
             servers: Arc::new(HashMap::new()),
             heartbeat_task: None,
+            slow_query_log: None,
+            prom_query_cache: None,
+            plan_cache: None,
         })
     }
 
@@ -282,19 +359,81 @@ impl Instance {
         &self.catalog_manager
     }
 
+    /// Returns all tables in current schema, ordered by their names.
+    async fn current_schema_tables(&self, ctx: &QueryContextRef) -> Result<Vec<TableRef>> {
+        let catalog_name = ctx.current_catalog();
+        let schema_name = ctx.current_schema();
+        let mut table_names = self
+            .catalog_manager
+            .table_names(&catalog_name, &schema_name)
+            .await
+            .context(error::CatalogSnafu)?;
+        table_names.sort();
+
+        let mut tables = Vec::with_capacity(table_names.len());
+        for table_name in table_names {
+            if let Some(table) = self
+                .catalog_manager
+                .table(&catalog_name, &schema_name, &table_name)
+                .await
+                .context(error::CatalogSnafu)?
+            {
+                tables.push(table);
+            }
+        }
+        Ok(tables)
+    }
+
     /// Handle batch inserts
     pub async fn handle_inserts(
         &self,
         requests: InsertRequests,
         ctx: QueryContextRef,
     ) -> Result<Output> {
-        let _ = future::join_all(
+        let catalog_name = &ctx.current_catalog();
+        let schema_name = &ctx.current_schema();
+        for insert in &requests.inserts {
+            self.statement_executor
+                .authorize(
+                    Privilege::Write,
+                    catalog_name,
+                    schema_name,
+                    Some(&insert.table_name),
+                    &ctx,
+                )
+                .await?;
+        }
+
+        let writes = requests
+            .inserts
+            .iter()
+            .map(|insert| TableWrites {
+                table: TableReference::full(catalog_name, schema_name, &insert.table_name),
+                rows: insert.row_count as u64,
+                bytes: insert.encoded_len() as u64,
+            })
+            .collect::<Vec<_>>();
+        // Rate limited requests are rejected before creating or altering tables.
+        self.statement_executor.acquire_writes(&writes)?;
+
+        let results = future::join_all(
             requests
                 .inserts
                 .iter()
                 .map(|x| self.create_or_alter_table_on_demand(ctx.clone(), x)),
         )
         .await;
+        // Tables or columns created by concurrent inserts in the meantime are fine.
+        for result in results {
+            if let Err(e) = result {
+                if !matches!(
+                    e.status_code(),
+                    StatusCode::TableAlreadyExists | StatusCode::TableColumnExists
+                ) {
+                    return Err(e);
+                }
+            }
+        }
 
         let query = Request::Inserts(requests);
         GrpcQueryHandler::do_query(&*self.grpc_query_handler, query, ctx).await
@@ -320,10 +459,22 @@ impl Instance {
             .context(error::CatalogSnafu)?;
         match table {
             None => {
+                self.statement_executor
+                    .authorize(
+                        Privilege::Ddl,
+                        catalog_name,
+                        schema_name,
+                        Some(table_name.as_str()),
+                        &ctx,
+                    )
+                    .await?;
                 info!(
                     "Table {}.{}.{} does not exist, try create table",
                     catalog_name, schema_name, table_name,
                 );
+                self.statement_executor
+                    .check_new_table(catalog_name, schema_name, table_name, columns.len())
+                    .await?;
                 let _ = self
                     .create_table_by_columns(ctx, table_name, columns, MITO_ENGINE)
                     .await?;
@@ -340,6 +491,23 @@ impl Instance {
                 if let Some(add_columns) = common_grpc_expr::find_new_columns(&schema, columns)
                     .context(error::FindNewColumnsOnInsertionSnafu)?
                 {
+                    self.statement_executor
+                        .authorize(
+                            Privilege::Ddl,
+                            catalog_name,
+                            schema_name,
+                            Some(table_name.as_str()),
+                            &ctx,
+                        )
+                        .await?;
+                    self.statement_executor
+                        .check_new_columns(
+                            catalog_name,
+                            schema_name,
+                            table_name,
+                            add_columns.add_columns.len(),
+                        )
+                        .await?;
                     info!(
                         "Find new columns {:?} on insertion, try to alter table: {}.{}.{}",
                         add_columns, catalog_name, schema_name, table_name
@@ -420,6 +588,107 @@ impl Instance {
         self.plugins = map;
     }
 
+    /// Enables role-based access control, see [crate::rbac]. `superusers` are in
+    /// the same format as the options of static user provider, e.g. `cmd:root=pwd`.
+    ///
+    /// Must be called after plugins are set, since it replaces the user provider
+    /// and statement executor in plugins.
+    pub async fn enable_rbac(&mut self, superusers: &str) -> Result<()> {
+        let superusers = StaticUserProvider::try_from(superusers).context(error::AuthSnafu)?;
+        let rbac =
+            Arc::new(RbacUserProvider::try_new(self.catalog_manager.clone(), superusers).await?);
+        self.plugins.insert::<UserProviderRef>(rbac.clone());
+        if let Some(process_manager) = self.catalog_manager.process_manager() {
+            let checker: Arc<dyn SuperuserChecker> = rbac.clone();
+            process_manager.set_superuser_checker(Arc::downgrade(&checker));
+        }
+
+        let statement_executor = Arc::new((*self.statement_executor).clone().with_rbac(rbac));
+        self.plugins
+            .insert::<StatementExecutorRef>(statement_executor.clone());
+        self.statement_executor = statement_executor;
+
+        info!("Role-based access control is enabled");
+        Ok(())
+    }
+
+    /// Enables audit log of sql queries, which is installed as the
+    /// [SqlQueryInterceptor] in plugins.
+    pub fn enable_audit_log(&self, opts: &AuditLogOptions) -> Result<()> {
+        if !opts.enable {
+            return Ok(());
+        }
+
+        if self
+            .plugins
+            .get::<SqlQueryInterceptorRef<Error>>()
+            .is_some()
+        {
+            warn!("Sql query interceptor in plugins is replaced by audit log");
+        }
+        let interceptor = AuditLogInterceptor::try_new(opts)?;
+        self.plugins
+            .insert::<SqlQueryInterceptorRef<Error>>(Arc::new(interceptor));
+
+        info!("Audit log is enabled, dir: {}", opts.dir);
+        Ok(())
+    }
+
+    /// Enables the slow query log of statements tracked in the process list. It
+    /// isn't enabled if the catalog manager provides no process list.
+    pub async fn enable_slow_query_log(&mut self, opts: &SlowQueryOptions) -> Result<()> {
+        if !opts.enable {
+            return Ok(());
+        }
+        if self.catalog_manager.process_manager().is_none() {
+            warn!(
+                "Slow query log is not enabled, since statements are not tracked in a process list"
+            );
+            return Ok(());
+        }
+
+        let slow_query_log = SlowQueryLog::try_new(opts, self.catalog_manager.clone()).await?;
+        self.slow_query_log = Some(Arc::new(slow_query_log));
+
+        info!("Slow query log is enabled, threshold: {:?}", opts.threshold);
+        Ok(())
+    }
+
+    /// Enables the caches of PromQL range query results and query plans, see
+    /// [crate::query_cache].
+    pub fn enable_query_cache(&mut self, opts: &QueryCacheOptions) {
+        if !opts.enable {
+            return;
+        }
+
+        self.prom_query_cache = Some(Arc::new(PromQueryCache::new(opts)));
+        self.plan_cache = Some(Arc::new(PlanCache::new(opts)));
+
+        info!("Query cache is enabled, max age: {:?}", opts.max_age);
+    }
+
+    /// Enables the limits of write rates and quotas of tables and columns, see
+    /// [crate::write_limit].
+    ///
+    /// Like [Self::enable_rbac], it replaces the statement executor in plugins.
+    pub fn enable_write_limit(&mut self, opts: &WriteLimitOptions) {
+        if !opts.enable {
+            return;
+        }
+
+        let write_limiter = Arc::new(WriteLimiter::new(opts));
+        let statement_executor = Arc::new(
+            (*self.statement_executor)
+                .clone()
+                .with_write_limiter(write_limiter),
+        );
+        self.plugins
+            .insert::<StatementExecutorRef>(statement_executor.clone());
+        self.statement_executor = statement_executor;
+
+        info!("Write limit is enabled: {:?}", opts);
+    }
+
     pub fn plugins(&self) -> Arc<Plugins> {
         self.plugins.clone()
     }
@@ -445,6 +714,9 @@ impl FrontendInstance for Instance {
             heartbeat_task.start().await?;
         }
 
+        self.materialized_views.start();
+        self.script_executor.start();
+
         futures::future::try_join_all(self.servers.values().map(start_server))
             .await
             .context(error::StartServerSnafu)
@@ -456,12 +728,256 @@ fn parse_stmt(sql: &str, dialect: &(dyn Dialect + Send + Sync)) -> Result<Vec<St
     ParserContext::create_with_dialect(sql, dialect).context(ParseSqlSnafu)
 }
 
+/// A statement registered in the process list. Once it finishes, i.e. this is
+/// dropped, it's removed from the process list and recorded to the slow query
+/// log if it took too long.
+struct TrackedExecution {
+    handle: ProcessHandle,
+    slow_query_log: Option<SlowQueryLogRef>,
+    /// Rows affected, or returned to client.
+    rows: usize,
+    /// Physical plan producing the output stream.
+    plan: Option<Arc<dyn ExecutionPlan>>,
+}
+
+impl Drop for TrackedExecution {
+    fn drop(&mut self) {
+        let Some(slow_query_log) = &self.slow_query_log else { return };
+        let process = self.handle.process();
+        if !slow_query_log.is_slow(process.elapsed()) {
+            return;
+        }
+
+        let plan = if slow_query_log.record_plan() {
+            self.plan.as_deref().map(display_plan_with_metrics)
+        } else {
+            None
+        };
+        slow_query_log.record(SlowQuery::new(process, self.rows, plan));
+    }
+}
+
+/// Keeps the statement tracked until its output stream is consumed or dropped,
+/// and stops the stream once the statement is killed. Dropping the inner stream
+/// also cancels the remote streams it reads from datanodes.
+fn track_output(output: Output, mut execution: TrackedExecution) -> Output {
+    let mut stream = match output {
+        Output::Stream(stream) => stream,
+        output => {
+            execution.rows = match &output {
+                Output::AffectedRows(rows) => *rows,
+                Output::RecordBatches(batches) => {
+                    batches.iter().map(|batch| batch.num_rows()).sum()
+                }
+                Output::Stream(_) => unreachable!(),
+            };
+            return output;
+        }
+    };
+
+    execution.handle.set_stage(QueryStage::Streaming);
+    execution.plan = stream.physical_plan();
+    let schema = stream.schema();
+    let output_ordering = stream.output_ordering().map(|ordering| ordering.to_vec());
+    let stream = try_stream! {
+        let process = execution.handle.process().clone();
+        loop {
+            let batch = tokio::select! {
+                batch = stream.next() => batch,
+                _ = process.killed() => {
+                    Err(BoxedError::new(QueryKilledSnafu { id: process.id() }.build()))
+                        .context(common_recordbatch::error::ExternalSnafu)?
+                }
+            };
+            match batch {
+                Some(batch) => {
+                    let batch = batch?;
+                    execution.rows += batch.num_rows();
+                    yield batch
+                }
+                None => break,
+            }
+        }
+        drop(execution);
+    };
+    Output::Stream(Box::pin(RecordBatchStreamAdaptor {
+        schema,
+        stream: Box::pin(stream),
+        output_ordering,
+    }))
+}
+
+/// Whether the statement only reads data, which can be stopped at any time.
+fn is_read_only(stmt: &QueryStatement) -> bool {
+    match stmt {
+        QueryStatement::Sql(stmt) => matches!(
+            stmt,
+            Statement::Query(_)
+                | Statement::Explain(_)
+                | Statement::Tql(_)
+                | Statement::ShowDatabases(_)
+                | Statement::ShowTables(_)
+                | Statement::ShowCreateTable(_)
+                | Statement::ShowProcesslist(_)
+                | Statement::DescribeTable(_)
+        ),
+        QueryStatement::Promql(_) => true,
+    }
+}
+
 impl Instance {
-    async fn query_statement(&self, stmt: Statement, query_ctx: QueryContextRef) -> Result<Output> {
+    async fn query_statement(
+        &self,
+        stmt: Statement,
+        query: &str,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
         check_permission(self.plugins.clone(), &stmt, &query_ctx)?;
 
         let stmt = QueryStatement::Sql(stmt);
-        self.statement_executor.execute_stmt(stmt, query_ctx).await
+        self.execute_tracked(stmt, query, query_ctx).await
+    }
+
+    /// Executes the statement registered in the process list, so that it can be
+    /// listed by `SHOW PROCESSLIST` and cancelled by `KILL QUERY`.
+    ///
+    /// Only read-only statements are cancelled at once. Other statements like
+    /// DDLs and writes always run to the end, since stopping them halfway may
+    /// leave partial changes, only their output streams are cancelled.
+    async fn execute_tracked(
+        &self,
+        stmt: QueryStatement,
+        query: &str,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let Some(process_manager) = self.catalog_manager.process_manager() else {
+            return self.statement_executor.execute_stmt(stmt, query_ctx).await;
+        };
+
+        let execution = TrackedExecution {
+            handle: process_manager.register(query, &query_ctx),
+            slow_query_log: self.slow_query_log.clone(),
+            rows: 0,
+            plan: None,
+        };
+        let process = execution.handle.process().clone();
+        if !is_read_only(&stmt) {
+            let output = self
+                .statement_executor
+                .execute_stmt(stmt, query_ctx)
+                .await?;
+            return Ok(track_output(output, execution));
+        }
+        let output = tokio::select! {
+            output = self.statement_executor.execute_stmt(stmt, query_ctx) => output?,
+            _ = process.killed() => {
+                return QueryKilledSnafu { id: process.id() }.fail();
+            }
+        };
+        Ok(track_output(output, execution))
+    }
+
+    /// Executes the PromQL range query with the results of steps cached before,
+    /// only the steps not cached yet are evaluated.
+    async fn execute_promql_cached(
+        &self,
+        cache: &PromQueryCache,
+        mut stmt: EvalStmt,
+        query: &str,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let Some(range) = StepRange::try_new(&stmt) else {
+            return self
+                .execute_tracked(QueryStatement::Promql(stmt), query, query_ctx)
+                .await;
+        };
+        // Cached results are served without planning the query, whose privileges
+        // are checked when it's planned.
+        self.statement_executor
+            .check_promql_privileges(&stmt, &query_ctx)
+            .await?;
+        let key = PromCacheKey::new(&stmt, query, &query_ctx);
+
+        let prefix = cache.get_prefix(&key, &range);
+        if let Some(prefix) = &prefix {
+            if prefix.tail_start() > range.end {
+                return prefix.batches().map(Output::RecordBatches);
+            }
+            stmt.start = query_cache::from_millis(prefix.tail_start());
+        }
+
+        let tail = self
+            .execute_tracked(
+                QueryStatement::Promql(stmt.clone()),
+                query,
+                query_ctx.clone(),
+            )
+            .await?;
+        let tail = match tail {
+            Output::Stream(stream) => util::collect_batches(stream)
+                .await
+                .context(CollectRecordbatchSnafu)?,
+            Output::RecordBatches(batches) => batches,
+            Output::AffectedRows(rows) => return Ok(Output::AffectedRows(rows)),
+        };
+
+        if let Some(output) = cache.put(key, &range, prefix, tail).await? {
+            return Ok(Output::RecordBatches(output));
+        }
+
+        // The cached steps are stale, evaluates the whole range again.
+        stmt.start = query_cache::from_millis(range.start);
+        self.execute_tracked(QueryStatement::Promql(stmt), query, query_ctx)
+            .await
+    }
+
+    /// Plans the statement to describe, the plans of queries are cached as the
+    /// same queries are prepared repeatedly by clients. Privileges are checked
+    /// whether the plan is cached or not, so revoked privileges take effect at
+    /// once.
+    async fn plan_described(
+        &self,
+        stmt: Statement,
+        query_ctx: QueryContextRef,
+    ) -> Result<LogicalPlan> {
+        let key = match (&self.plan_cache, &stmt) {
+            (Some(plan_cache), Statement::Query(query)) => {
+                let key = PlanCacheKey::new(query, &query_ctx);
+                if let Some(plan) = plan_cache.get(&key, &self.catalog_manager).await? {
+                    self.statement_executor
+                        .check_plan_privileges(&plan, &query_ctx)
+                        .await?;
+                    return Ok(plan);
+                }
+                Some(key)
+            }
+            _ => None,
+        };
+
+        let plan = self
+            .query_engine
+            .planner()
+            .plan(QueryStatement::Sql(stmt), query_ctx.clone())
+            .await
+            .context(PlanStatementSnafu)?;
+        self.statement_executor
+            .check_plan_privileges(&plan, &query_ctx)
+            .await?;
+
+        if let (Some(plan_cache), Some(key)) = (&self.plan_cache, key) {
+            plan_cache.insert(key, plan.clone()).await;
+        }
+        Ok(plan)
+    }
+
+    async fn exec_plan(&self, plan: LogicalPlan, query_ctx: QueryContextRef) -> Result<Output> {
+        self.statement_executor
+            .check_plan_privileges(&plan, &query_ctx)
+            .await?;
+        self.query_engine
+            .execute(plan, query_ctx)
+            .await
+            .context(ExecLogicalPlanSnafu)
     }
 }
 
@@ -473,32 +989,67 @@ impl SqlQueryHandler for Instance {
         let _timer = timer!(metrics::METRIC_HANDLE_SQL_ELAPSED);
         let query_interceptor_opt = self.plugins.get::<SqlQueryInterceptorRef<Error>>();
         let query_interceptor = query_interceptor_opt.as_ref();
+        let start = Instant::now();
         let query = match query_interceptor.pre_parsing(query, query_ctx.clone()) {
             Ok(q) => q,
-            Err(e) => return vec![Err(e)],
+            Err(e) => {
+                return vec![report_execution(
+                    query_interceptor,
+                    &redact_sql(query, query_ctx.sql_dialect()),
+                    start,
+                    Err(e),
+                    query_ctx,
+                )]
+            }
         };
 
         match parse_stmt(query.as_ref(), query_ctx.sql_dialect())
             .and_then(|stmts| query_interceptor.post_parsing(stmts, query_ctx.clone()))
         {
             Ok(stmts) => {
+                // Each statement is reported with its own text, credentials redacted.
+                let mut texts = split_and_redact_sql(&query, query_ctx.sql_dialect());
+                if texts.len() != stmts.len() {
+                    // Statements may be rewritten by the interceptor.
+                    texts = vec![redact_sql(&query, query_ctx.sql_dialect()); stmts.len()];
+                }
+
                 let mut results = Vec::with_capacity(stmts.len());
-                for stmt in stmts {
+                for (stmt, text) in stmts.into_iter().zip(texts) {
+                    let start = Instant::now();
                     // TODO(sunng87): figure out at which stage we can call
                     // this hook after ArrowFlight adoption. We need to provide
                     // LogicalPlan as to this hook.
                     if let Err(e) = query_interceptor.pre_execute(&stmt, None, query_ctx.clone()) {
-                        results.push(Err(e));
+                        results.push(report_execution(
+                            query_interceptor,
+                            &text,
+                            start,
+                            Err(e),
+                            query_ctx.clone(),
+                        ));
                         break;
                     }
-                    match self.query_statement(stmt, query_ctx.clone()).await {
+                    match self.query_statement(stmt, &text, query_ctx.clone()).await {
                         Ok(output) => {
                             let output_result =
                                 query_interceptor.post_execute(output, query_ctx.clone());
-                            results.push(output_result);
+                            results.push(report_execution(
+                                query_interceptor,
+                                &text,
+                                start,
+                                output_result,
+                                query_ctx.clone(),
+                            ));
                         }
                         Err(e) => {
-                            results.push(Err(e));
+                            results.push(report_execution(
+                                query_interceptor,
+                                &text,
+                                start,
+                                Err(e),
+                                query_ctx.clone(),
+                            ));
                             break;
                         }
                     }
@@ -506,17 +1057,26 @@ impl SqlQueryHandler for Instance {
                 results
             }
             Err(e) => {
-                vec![Err(e)]
+                vec![report_execution(
+                    query_interceptor,
+                    &redact_sql(&query, query_ctx.sql_dialect()),
+                    start,
+                    Err(e),
+                    query_ctx,
+                )]
             }
         }
     }
 
     async fn do_exec_plan(&self, plan: LogicalPlan, query_ctx: QueryContextRef) -> Result<Output> {
         let _timer = timer!(metrics::METRIC_EXEC_PLAN_ELAPSED);
-        self.query_engine
-            .execute(plan, query_ctx)
-            .await
-            .context(ExecLogicalPlanSnafu)
+        let start = Instant::now();
+        // Prepared statements are executed as plans, without the query string.
+        let query = plan.display_indent().to_string();
+        let result = self.exec_plan(plan, query_ctx.clone()).await;
+
+        let query_interceptor = self.plugins.get::<SqlQueryInterceptorRef<Error>>();
+        report_execution(query_interceptor.as_ref(), &query, start, result, query_ctx)
     }
 
     async fn do_promql_query(
@@ -541,12 +1101,7 @@ impl SqlQueryHandler for Instance {
             stmt,
             Statement::Insert(_) | Statement::Query(_) | Statement::Delete(_)
         ) {
-            let plan = self
-                .query_engine
-                .planner()
-                .plan(QueryStatement::Sql(stmt), query_ctx)
-                .await
-                .context(PlanStatementSnafu)?;
+            let plan = self.plan_described(stmt, query_ctx).await?;
             self.query_engine
                 .describe(plan)
                 .await
@@ -571,14 +1126,39 @@ impl PromHandler for Instance {
         &self,
         query: &PromQuery,
         query_ctx: QueryContextRef,
+    ) -> server_error::Result<Output> {
+        let start = Instant::now();
+        let result = self.do_prom_query(query, query_ctx.clone()).await;
+
+        let query_interceptor = self.plugins.get::<SqlQueryInterceptorRef<Error>>();
+        report_execution(
+            query_interceptor.as_ref(),
+            &query.query,
+            start,
+            result,
+            query_ctx,
+        )
+    }
+}
+
+impl Instance {
+    async fn do_prom_query(
+        &self,
+        query: &PromQuery,
+        query_ctx: QueryContextRef,
     ) -> server_error::Result<Output> {
         let stmt = QueryLanguageParser::parse_promql(query).with_context(|_| ParsePromQLSnafu {
             query: query.clone(),
         })?;
 
-        self.statement_executor
-            .execute_stmt(stmt, query_ctx)
-            .await
+        let result = match (&self.prom_query_cache, stmt) {
+            (Some(cache), QueryStatement::Promql(stmt)) => {
+                self.execute_promql_cached(cache, stmt, &query.query, query_ctx)
+                    .await
+            }
+            (_, stmt) => self.execute_tracked(stmt, &query.query, query_ctx).await,
+        };
+        result
             .map_err(BoxedError::new)
             .with_context(|_| ExecuteQuerySnafu {
                 query: format!("{query:?}"),
@@ -605,9 +1185,17 @@ pub fn check_permission(
         Statement::Query(_) | Statement::Explain(_) | Statement::Tql(_) | Statement::Delete(_) => {}
         // database ops won't be checked
         Statement::CreateDatabase(_) | Statement::ShowDatabases(_) | Statement::Use(_) => {}
+        // processes are not bound to any schema
+        Statement::ShowProcesslist(_) | Statement::Kill(_) => {}
         // show create table and alter are not supported yet
         Statement::ShowCreateTable(_) | Statement::CreateExternalTable(_) | Statement::Alter(_) => {
         }
+        // users and roles are not bound to any schema
+        Statement::CreateUser(_)
+        | Statement::CreateRole(_)
+        | Statement::DropUser(_)
+        | Statement::Grant(_)
+        | Statement::Revoke(_) => {}
 
         Statement::Insert(insert) => {
             validate_param(insert.table_name(), query_ctx)?;
@@ -618,6 +1206,18 @@ pub fn check_permission(
         Statement::DropTable(drop_stmt) => {
             validate_param(drop_stmt.table_name(), query_ctx)?;
         }
+        Statement::CreateView(stmt) => {
+            validate_param(&stmt.name, query_ctx)?;
+        }
+        Statement::DropView(stmt) => {
+            validate_param(stmt.view_name(), query_ctx)?;
+        }
+        Statement::CreateMaterializedView(stmt) => {
+            validate_param(&stmt.name, query_ctx)?;
+        }
+        Statement::DropMaterializedView(stmt) => {
+            validate_param(stmt.view_name(), query_ctx)?;
+        }
         Statement::ShowTables(stmt) => {
             if let Some(database) = &stmt.database {
                 validate_catalog_and_schema(&query_ctx.current_catalog(), database, query_ctx)
@@ -679,6 +1279,8 @@ mod tests {
     use std::collections::HashMap;
 
     use api::v1::column::Values;
+    use catalog::process_manager::ProcessManager;
+    use common_error::prelude::{ErrorExt, StatusCode};
     use datatypes::prelude::{ConcreteDataType, Value};
     use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema};
     use query::query_engine::options::QueryOptions;
@@ -688,6 +1290,24 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_is_read_only() {
+        for (sql, expected) in [
+            ("SELECT * FROM monitor", true),
+            ("EXPLAIN SELECT * FROM monitor", true),
+            ("SHOW TABLES", true),
+            ("INSERT INTO monitor VALUES (1, 2)", false),
+            ("DELETE FROM monitor WHERE ts = 1", false),
+            ("CREATE DATABASE test", false),
+            ("COPY monitor TO '/tmp/monitor.parquet'", false),
+        ] {
+            let stmt = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {})
+                .unwrap()
+                .remove(0);
+            assert_eq!(expected, is_read_only(&QueryStatement::Sql(stmt)), "{sql}");
+        }
+    }
+
     #[test]
     fn test_validate_insert_request() {
         let schema = Schema::new(vec![
@@ -860,4 +1480,41 @@ mod tests {
         let sql = "DESC TABLE {catalog}{schema}demo;";
         replace_test(sql, plugins, &query_ctx);
     }
+
+    #[tokio::test]
+    async fn test_kill_streaming_output() {
+        let process_manager = Arc::new(ProcessManager::default());
+        let handle = process_manager.register("SELECT a FROM t", &QueryContext::new());
+        let id = handle.process().id();
+
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "a",
+            ConcreteDataType::int32_datatype(),
+            true,
+        )]));
+        let stream = RecordBatchStreamAdaptor {
+            schema,
+            stream: Box::pin(futures::stream::pending()),
+            output_ordering: None,
+        };
+        let execution = TrackedExecution {
+            handle,
+            slow_query_log: None,
+            rows: 0,
+            plan: None,
+        };
+        let Output::Stream(mut stream) = track_output(Output::Stream(Box::pin(stream)), execution) else {
+            unreachable!()
+        };
+        assert_eq!(
+            QueryStage::Streaming,
+            process_manager.get(id).unwrap().stage()
+        );
+
+        assert!(process_manager.kill(id));
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(StatusCode::Cancelled, err.status_code());
+        assert!(stream.next().await.is_none());
+        assert!(process_manager.get(id).is_none());
+    }
 }
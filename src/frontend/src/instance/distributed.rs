@@ -21,8 +21,8 @@ use api::helper::ColumnDataTypeWrapper;
 use api::v1::ddl_request::Expr as DdlExpr;
 use api::v1::greptime_request::Request;
 use api::v1::{
-    column_def, AlterExpr, CreateDatabaseExpr, CreateTableExpr, DeleteRequest, DropTableExpr,
-    FlushTableExpr, InsertRequests, TableId,
+    column_def, AlterExpr, CreateDatabaseExpr, CreateTableExpr, DeleteRequest, FlushTableExpr,
+    InsertRequests, TableId,
 };
 use async_trait::async_trait;
 use catalog::helper::{SchemaKey, SchemaValue};
@@ -33,6 +33,7 @@ use client::Database;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_catalog::format_full_table_name;
 use common_error::prelude::BoxedError;
+use common_meta::rpc::ddl::AlterTableRequest as MetaAlterTableRequest;
 use common_meta::rpc::router::{
     CreateRequest as MetaCreateRequest, DeleteRequest as MetaDeleteRequest,
     Partition as MetaPartition, RouteRequest, RouteResponse,
@@ -46,7 +47,7 @@ use datanode::sql::SqlHandler;
 use datatypes::prelude::ConcreteDataType;
 use datatypes::schema::RawSchema;
 use meta_client::client::MetaClient;
-use partition::manager::PartitionInfo;
+use partition::manager::{PartitionInfo, TableRouteCacheInvalidator};
 use partition::partition::{PartitionBound, PartitionDef};
 use query::error::QueryExecutionSnafu;
 use query::query_engine::SqlStatementExecutor;
@@ -61,16 +62,14 @@ use store_api::storage::RegionNumber;
 use table::engine::{self, TableReference};
 use table::metadata::{RawTableInfo, RawTableMeta, TableIdent, TableType};
 use table::requests::TableOptions;
-use table::table::AlterContext;
 use table::TableRef;
 
 use crate::catalog::FrontendCatalogManager;
 use crate::error::{
-    self, AlterExprToRequestSnafu, CatalogEntrySerdeSnafu, CatalogSnafu, ColumnDataTypeSnafu,
-    DeserializePartitionSnafu, InvokeDatanodeSnafu, ParseSqlSnafu, PrimaryKeyNotFoundSnafu,
-    RequestDatanodeSnafu, RequestMetaSnafu, Result, SchemaExistsSnafu, StartMetaClientSnafu,
-    TableAlreadyExistSnafu, TableNotFoundSnafu, TableSnafu, ToTableDeleteRequestSnafu,
-    UnrecognizedTableOptionSnafu,
+    self, CatalogEntrySerdeSnafu, CatalogSnafu, ColumnDataTypeSnafu, DeserializePartitionSnafu,
+    InvokeDatanodeSnafu, ParseSqlSnafu, PrimaryKeyNotFoundSnafu, RequestDatanodeSnafu,
+    RequestMetaSnafu, Result, SchemaExistsSnafu, StartMetaClientSnafu, TableAlreadyExistSnafu,
+    TableNotFoundSnafu, TableSnafu, ToTableDeleteRequestSnafu, UnrecognizedTableOptionSnafu,
 };
 use crate::expr_factory;
 use crate::instance::distributed::inserter::DistInserter;
@@ -109,6 +108,16 @@ impl DistInstance {
             .context(CatalogSnafu)
     }
 
+    /// Allocates a table id from Metasrv for a table without regions, like a view.
+    pub(crate) async fn allocate_table_id(&self) -> Result<table::metadata::TableId> {
+        let response = self
+            .meta_client
+            .allocate_table_id()
+            .await
+            .context(RequestMetaSnafu)?;
+        Ok(response.table_id)
+    }
+
     pub async fn create_table(
         &self,
         create_table: &mut CreateTableExpr,
@@ -199,26 +208,6 @@ impl DistInstance {
             }
         );
 
-        for datanode in table_route.find_leaders() {
-            let client = self.datanode_clients.get_client(&datanode).await;
-            let client = Database::new(&table_name.catalog_name, &table_name.schema_name, client);
-
-            let regions = table_route.find_leader_regions(&datanode);
-            let mut create_expr_for_region = create_table.clone();
-            create_expr_for_region.region_numbers = regions;
-
-            debug!(
-                "Creating table {:?} on Datanode {:?} with regions {:?}",
-                create_table, datanode, create_expr_for_region.region_numbers,
-            );
-
-            let _timer = common_telemetry::timer!(crate::metrics::DIST_CREATE_TABLE_IN_DATANODE);
-            let _ = client
-                .create(create_expr_for_region)
-                .await
-                .context(RequestDatanodeSnafu)?;
-        }
-
         // Since the table information created on meta does not go through KvBackend, so we
         // manually invalidate the cache here.
         //
@@ -248,7 +237,7 @@ impl DistInstance {
                 table_name: table_name.to_string(),
             })?;
 
-        let route_response = self
+        let _ = self
             .meta_client
             .delete_route(MetaDeleteRequest {
                 table_name: table_name.clone(),
@@ -266,24 +255,6 @@ impl DistInstance {
             .await
             .context(CatalogSnafu)?;
 
-        let expr = DropTableExpr {
-            catalog_name: table_name.catalog_name.clone(),
-            schema_name: table_name.schema_name.clone(),
-            table_name: table_name.table_name.clone(),
-        };
-        for table_route in route_response.table_routes.iter() {
-            for datanode in table_route.find_leaders() {
-                debug!("Dropping table {table_name} on Datanode {datanode:?}");
-
-                let client = self.datanode_clients.get_client(&datanode).await;
-                let client = Database::new(&expr.catalog_name, &expr.schema_name, client);
-                let _ = client
-                    .drop_table(expr.clone())
-                    .await
-                    .context(RequestDatanodeSnafu)?;
-            }
-        }
-
         // Since the table information dropped on meta does not go through KvBackend, so we
         // manually invalidate the cache here.
         //
@@ -439,6 +410,11 @@ impl DistInstance {
     }
 
     async fn show_create_table(&self, table_name: TableName, table: TableRef) -> Result<Output> {
+        if table.table_type() == TableType::View {
+            return query::sql::show_create_table(table, None)
+                .context(error::ExecuteStatementSnafu);
+        }
+
         let partitions = self
             .catalog_manager
             .partition_manager()
@@ -454,7 +430,7 @@ impl DistInstance {
     }
 
     /// Handles distributed database creation
-    async fn handle_create_database(
+    pub(crate) async fn handle_create_database(
         &self,
         expr: CreateDatabaseExpr,
         query_ctx: QueryContextRef,
@@ -528,7 +504,7 @@ impl DistInstance {
 
         let table_name = expr.table_name.as_str();
 
-        let table = self
+        let _ = self
             .catalog_manager
             .table(catalog_name, schema_name, table_name)
             .await
@@ -537,17 +513,34 @@ impl DistInstance {
                 table_name: format_full_table_name(catalog_name, schema_name, table_name),
             })?;
 
-        let request = common_grpc_expr::alter_expr_to_request(
-            table.table_info().ident.table_id,
-            expr.clone(),
-        )
-        .context(AlterExprToRequestSnafu)?;
-
-        let mut context = AlterContext::with_capacity(1);
+        let table_name = TableName::new(catalog_name, schema_name, table_name);
+        let expr = AlterExpr {
+            catalog_name: table_name.catalog_name.clone(),
+            schema_name: table_name.schema_name.clone(),
+            ..expr
+        };
 
-        let _ = context.insert(expr);
+        // Metasrv alters the table on Datanodes and updates its metadata in a
+        // procedure, which is resumed if this Frontend or Metasrv crashes.
+        let _ = self
+            .meta_client
+            .alter_table(MetaAlterTableRequest::new(expr))
+            .await
+            .context(RequestMetaSnafu)?;
 
-        table.alter(context, &request).await.context(TableSnafu)?;
+        // Metasrv broadcasts the invalidation to Frontends by heartbeats, this
+        // one invalidates the cache at once to see its own ALTER TABLE.
+        self.catalog_manager()
+            .invalidate_table(
+                &table_name.catalog_name,
+                &table_name.schema_name,
+                &table_name.table_name,
+            )
+            .await;
+        self.catalog_manager()
+            .partition_manager()
+            .invalidate_table_route(&table_name)
+            .await;
 
         Ok(Output::AffectedRows(0))
     }
@@ -12,11 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use api::v1::alter_expr::Kind;
+use api::v1::ddl_request::Expr as DdlExpr;
 use api::v1::greptime_request::Request;
 use api::v1::query_request::Query;
+use api::v1::AlterExpr;
 use async_trait::async_trait;
 use common_query::Output;
 use query::parser::PromQuery;
+use servers::auth::Privilege;
 use servers::interceptor::{GrpcQueryInterceptor, GrpcQueryInterceptorRef};
 use servers::query_handler::grpc::GrpcQueryHandler;
 use servers::query_handler::sql::SqlQueryHandler;
@@ -78,6 +82,8 @@ impl GrpcQueryHandler for Instance {
                 }
             }
             Request::Ddl(_) | Request::Delete(_) => {
+                self.check_grpc_privileges(&request, &ctx).await?;
+                self.check_grpc_quotas(&request).await?;
                 GrpcQueryHandler::do_query(self.grpc_query_handler.as_ref(), request, ctx.clone())
                     .await?
             }
@@ -87,3 +93,88 @@ impl GrpcQueryHandler for Instance {
         Ok(output)
     }
 }
+
+impl Instance {
+    /// Checks the privileges required by DDL and delete requests, which are
+    /// executed without going through the statement executor.
+    async fn check_grpc_privileges(&self, request: &Request, ctx: &QueryContextRef) -> Result<()> {
+        let (privilege, catalog, schema, table) = match request {
+            Request::Delete(request) => (
+                Privilege::Write,
+                ctx.current_catalog(),
+                ctx.current_schema(),
+                Some(request.table_name.clone()),
+            ),
+            Request::Ddl(request) => match &request.expr {
+                Some(DdlExpr::CreateDatabase(expr)) => (
+                    Privilege::Ddl,
+                    ctx.current_catalog(),
+                    expr.database_name.clone(),
+                    None,
+                ),
+                Some(DdlExpr::CreateTable(expr)) => (
+                    Privilege::Ddl,
+                    expr.catalog_name.clone(),
+                    expr.schema_name.clone(),
+                    Some(expr.table_name.clone()),
+                ),
+                Some(DdlExpr::Alter(expr)) => (
+                    Privilege::Ddl,
+                    expr.catalog_name.clone(),
+                    expr.schema_name.clone(),
+                    Some(expr.table_name.clone()),
+                ),
+                Some(DdlExpr::DropTable(expr)) => (
+                    Privilege::Ddl,
+                    expr.catalog_name.clone(),
+                    expr.schema_name.clone(),
+                    Some(expr.table_name.clone()),
+                ),
+                Some(DdlExpr::FlushTable(expr)) => (
+                    Privilege::Ddl,
+                    expr.catalog_name.clone(),
+                    expr.schema_name.clone(),
+                    Some(expr.table_name.clone()),
+                ),
+                None => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
+        self.statement_executor
+            .authorize(privilege, &catalog, &schema, table.as_deref(), ctx)
+            .await
+    }
+
+    /// Checks the quotas of tables and columns of DDL requests.
+    async fn check_grpc_quotas(&self, request: &Request) -> Result<()> {
+        let Request::Ddl(request) = request else { return Ok(()) };
+        match &request.expr {
+            Some(DdlExpr::CreateTable(expr)) => {
+                self.statement_executor
+                    .check_new_table(
+                        &expr.catalog_name,
+                        &expr.schema_name,
+                        &expr.table_name,
+                        expr.column_defs.len(),
+                    )
+                    .await
+            }
+            Some(DdlExpr::Alter(AlterExpr {
+                catalog_name,
+                schema_name,
+                table_name,
+                kind: Some(Kind::AddColumns(add_columns)),
+            })) => {
+                self.statement_executor
+                    .check_new_columns(
+                        catalog_name,
+                        schema_name,
+                        table_name,
+                        add_columns.add_columns.len(),
+                    )
+                    .await
+            }
+            _ => Ok(()),
+        }
+    }
+}
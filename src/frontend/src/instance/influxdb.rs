@@ -13,14 +13,166 @@
 // limitations under the License.
 
 use async_trait::async_trait;
+use common_catalog::format_full_table_name;
 use common_error::prelude::BoxedError;
+use common_query::Output;
+use common_telemetry::logging;
+use common_time::util::current_time_millis;
+use servers::auth::Privilege;
+use servers::error as server_error;
+use servers::influxdb::influxql::{self, InfluxqlSeries, InfluxqlStatement, SelectStatement};
 use servers::influxdb::InfluxdbRequest;
 use servers::query_handler::InfluxdbLineProtocolHandler;
 use session::context::QueryContextRef;
-use snafu::ResultExt;
+use snafu::{OptionExt, ResultExt};
+use table::TableRef;
 
+use crate::error::{
+    CatalogSnafu, ExecLogicalPlanSnafu, InfluxqlQueryPlanSnafu, MissingTimeIndexColumnSnafu,
+    ReadTableSnafu, Result, TableNotFoundSnafu,
+};
 use crate::instance::Instance;
 
+impl Instance {
+    async fn influxql_table(&self, ctx: &QueryContextRef, table_name: &str) -> Result<TableRef> {
+        let catalog_name = ctx.current_catalog();
+        let schema_name = ctx.current_schema();
+        self.catalog_manager
+            .table(&catalog_name, &schema_name, table_name)
+            .await
+            .context(CatalogSnafu)?
+            .with_context(|| TableNotFoundSnafu {
+                table_name: format_full_table_name(&catalog_name, &schema_name, table_name),
+            })
+    }
+
+    /// Returns tables of `SHOW TAG KEYS` or `SHOW FIELD KEYS`, either the given
+    /// measurement or all tables in current schema.
+    async fn influxql_show_tables(
+        &self,
+        ctx: &QueryContextRef,
+        measurement: &Option<String>,
+    ) -> Result<Vec<TableRef>> {
+        if let Some(measurement) = measurement {
+            return Ok(vec![self.influxql_table(ctx, measurement).await?]);
+        }
+
+        self.current_schema_tables(ctx).await
+    }
+
+    /// Executes a SELECT statement, `now` is the nanosecond timestamp that
+    /// `now()` evaluates to.
+    async fn influxql_select(
+        &self,
+        ctx: &QueryContextRef,
+        stmt: &SelectStatement,
+        now: i64,
+    ) -> Result<Output> {
+        self.statement_executor
+            .authorize(
+                Privilege::Read,
+                &ctx.current_catalog(),
+                &ctx.current_schema(),
+                Some(&stmt.measurement),
+                ctx,
+            )
+            .await?;
+        let table = self.influxql_table(ctx, &stmt.measurement).await?;
+        let time_index = table
+            .schema()
+            .timestamp_column()
+            .with_context(|| MissingTimeIndexColumnSnafu {
+                table_name: &stmt.measurement,
+            })?
+            .name
+            .clone();
+
+        let dataframe = self
+            .query_engine
+            .read_table(table)
+            .with_context(|_| ReadTableSnafu {
+                table_name: &stmt.measurement,
+            })?;
+
+        let logical_plan = influxql::select_to_plan(dataframe, &time_index, stmt, now)
+            .context(InfluxqlQueryPlanSnafu)?;
+
+        logging::debug!(
+            "InfluxQL query, measurement: {}, logical plan: {}",
+            stmt.measurement,
+            logical_plan.display_indent(),
+        );
+
+        self.query_engine
+            .execute(logical_plan, ctx.clone())
+            .await
+            .context(ExecLogicalPlanSnafu)
+    }
+
+    async fn handle_influxql(
+        &self,
+        ctx: &QueryContextRef,
+        stmt: &InfluxqlStatement,
+    ) -> server_error::Result<Vec<InfluxqlSeries>> {
+        let result = match stmt {
+            InfluxqlStatement::Select(select) => {
+                let now = current_time_millis() * 1_000_000;
+                let output = self
+                    .influxql_select(ctx, select, now)
+                    .await
+                    .map_err(BoxedError::new)
+                    .with_context(|_| server_error::ExecuteQuerySnafu {
+                        query: format!("{stmt:?}"),
+                    })?;
+                return influxql::output_to_series(select, output, now).await;
+            }
+            InfluxqlStatement::ShowMeasurements { limit } => self
+                .catalog_manager
+                .table_names(&ctx.current_catalog(), &ctx.current_schema())
+                .await
+                .context(CatalogSnafu)
+                .map(|names| influxql::measurements_to_series(names, *limit)),
+            InfluxqlStatement::ShowTagKeys { measurement } => self
+                .influxql_show_tables(ctx, measurement)
+                .await
+                .map(|tables| {
+                    tables
+                        .into_iter()
+                        .map(|table| {
+                            let info = table.table_info();
+                            let tag_keys = info.meta.row_key_column_names().cloned().collect();
+                            influxql::tag_keys_to_series(&info.name, tag_keys)
+                        })
+                        .collect()
+                }),
+            InfluxqlStatement::ShowFieldKeys { measurement } => self
+                .influxql_show_tables(ctx, measurement)
+                .await
+                .map(|tables| {
+                    tables
+                        .into_iter()
+                        .map(|table| {
+                            let info = table.table_info();
+                            let schema = &info.meta.schema;
+                            let fields = info
+                                .meta
+                                .field_column_names()
+                                .filter_map(|name| schema.column_schema_by_name(name))
+                                .map(|c| (c.name.clone(), c.data_type.clone()))
+                                .collect();
+                            influxql::field_keys_to_series(&info.name, fields)
+                        })
+                        .collect()
+                }),
+        };
+        result
+            .map_err(BoxedError::new)
+            .with_context(|_| server_error::ExecuteQuerySnafu {
+                query: format!("{stmt:?}"),
+            })
+    }
+}
+
 #[async_trait]
 impl InfluxdbLineProtocolHandler for Instance {
     async fn exec(
@@ -36,4 +188,12 @@ impl InfluxdbLineProtocolHandler for Instance {
             .context(servers::error::ExecuteGrpcQuerySnafu)?;
         Ok(())
     }
+
+    async fn query(
+        &self,
+        stmt: &InfluxqlStatement,
+        ctx: QueryContextRef,
+    ) -> servers::error::Result<Vec<InfluxqlSeries>> {
+        self.handle_influxql(&ctx, stmt).await
+    }
 }
@@ -14,15 +14,158 @@
 
 use api::v1::InsertRequests;
 use async_trait::async_trait;
+use common_catalog::format_full_table_name;
 use common_error::prelude::BoxedError;
+use common_query::Output;
+use common_telemetry::logging;
+use servers::auth::Privilege;
 use servers::error as server_error;
 use servers::opentsdb::codec::DataPoint;
+use servers::opentsdb::query::{
+    self, OpentsdbQuery, OpentsdbQueryResult, SuggestRequest, SuggestType,
+};
 use servers::query_handler::OpentsdbProtocolHandler;
 use session::context::QueryContextRef;
 use snafu::prelude::*;
 
+use crate::error::{
+    CatalogSnafu, ExecLogicalPlanSnafu, OpentsdbQueryPlanSnafu, ReadTableSnafu, Result,
+    TableNotFoundSnafu,
+};
 use crate::instance::Instance;
 
+impl Instance {
+    async fn opentsdb_query(
+        &self,
+        ctx: &QueryContextRef,
+        q: &OpentsdbQuery,
+    ) -> Result<(Vec<String>, Output)> {
+        let catalog_name = ctx.current_catalog();
+        let schema_name = ctx.current_schema();
+        self.statement_executor
+            .authorize(
+                Privilege::Read,
+                &catalog_name,
+                &schema_name,
+                Some(&q.metric),
+                ctx,
+            )
+            .await?;
+        let table = self
+            .catalog_manager
+            .table(&catalog_name, &schema_name, &q.metric)
+            .await
+            .context(CatalogSnafu)?
+            .with_context(|| TableNotFoundSnafu {
+                table_name: format_full_table_name(&catalog_name, &schema_name, &q.metric),
+            })?;
+        let tag_columns = table
+            .table_info()
+            .meta
+            .row_key_column_names()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let dataframe = self
+            .query_engine
+            .read_table(table)
+            .with_context(|_| ReadTableSnafu {
+                table_name: &q.metric,
+            })?;
+        let logical_plan =
+            query::query_to_plan(dataframe, &tag_columns, q).context(OpentsdbQueryPlanSnafu)?;
+
+        logging::debug!(
+            "OpenTSDB query, metric: {}, logical plan: {}",
+            q.metric,
+            logical_plan.display_indent(),
+        );
+
+        let output = self
+            .query_engine
+            .execute(logical_plan, ctx.clone())
+            .await
+            .context(ExecLogicalPlanSnafu)?;
+        Ok((tag_columns, output))
+    }
+
+    async fn opentsdb_suggest(
+        &self,
+        ctx: &QueryContextRef,
+        request: &SuggestRequest,
+    ) -> Result<Vec<String>> {
+        // Each table in current schema stores an OpenTSDB metric.
+        let tables = self.current_schema_tables(ctx).await?;
+        let candidates = match request.suggest_type {
+            SuggestType::Metrics => tables
+                .iter()
+                .map(|table| table.table_info().name.clone())
+                .collect(),
+            SuggestType::Tagk => tables
+                .iter()
+                .flat_map(|table| {
+                    table
+                        .table_info()
+                        .meta
+                        .row_key_column_names()
+                        .cloned()
+                        .collect::<Vec<_>>()
+                })
+                .collect(),
+            SuggestType::Tagv => {
+                let mut values = vec![];
+                for table in tables {
+                    let info = table.table_info();
+                    // Skip tables the user is not allowed to read.
+                    if self
+                        .statement_executor
+                        .authorize(
+                            Privilege::Read,
+                            &info.catalog_name,
+                            &info.schema_name,
+                            Some(&info.name),
+                            ctx,
+                        )
+                        .await
+                        .is_err()
+                    {
+                        continue;
+                    }
+                    let tag_columns = info
+                        .meta
+                        .row_key_column_names()
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    if tag_columns.is_empty() {
+                        continue;
+                    }
+                    let dataframe =
+                        self.query_engine
+                            .read_table(table.clone())
+                            .with_context(|_| ReadTableSnafu {
+                                table_name: &info.name,
+                            })?;
+                    let logical_plan =
+                        query::tag_values_to_plan(dataframe, &tag_columns, &request.q, request.max)
+                            .context(OpentsdbQueryPlanSnafu)?;
+                    let output = self
+                        .query_engine
+                        .execute(logical_plan, ctx.clone())
+                        .await
+                        .context(ExecLogicalPlanSnafu)?;
+                    values.extend(
+                        query::output_to_strings(output)
+                            .await
+                            .context(OpentsdbQueryPlanSnafu)?,
+                    );
+                }
+                values
+            }
+        };
+        Ok(query::collect_suggestions(request, candidates))
+    }
+}
+
 #[async_trait]
 impl OpentsdbProtocolHandler for Instance {
     async fn exec(&self, data_point: &DataPoint, ctx: QueryContextRef) -> server_error::Result<()> {
@@ -38,4 +181,32 @@ impl OpentsdbProtocolHandler for Instance {
             })?;
         Ok(())
     }
+
+    async fn query(
+        &self,
+        q: &OpentsdbQuery,
+        ctx: QueryContextRef,
+    ) -> server_error::Result<Vec<OpentsdbQueryResult>> {
+        let (tag_columns, output) = self
+            .opentsdb_query(&ctx, q)
+            .await
+            .map_err(BoxedError::new)
+            .with_context(|_| server_error::ExecuteQuerySnafu {
+                query: format!("{q:?}"),
+            })?;
+        query::output_to_results(q, &tag_columns, output).await
+    }
+
+    async fn suggest(
+        &self,
+        request: &SuggestRequest,
+        ctx: QueryContextRef,
+    ) -> server_error::Result<Vec<String>> {
+        self.opentsdb_suggest(&ctx, request)
+            .await
+            .map_err(BoxedError::new)
+            .with_context(|_| server_error::ExecuteQuerySnafu {
+                query: format!("{request:?}"),
+            })
+    }
 }
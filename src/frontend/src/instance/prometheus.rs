@@ -22,6 +22,7 @@ use common_recordbatch::RecordBatches;
 use common_telemetry::logging;
 use metrics::counter;
 use prost::Message;
+use servers::auth::Privilege;
 use servers::error::{self, Result as ServerResult};
 use servers::prometheus::{self, Metrics};
 use servers::query_handler::{PrometheusProtocolHandler, PrometheusResponse};
@@ -85,6 +86,15 @@ impl Instance {
         table_name: &str,
         query: &Query,
     ) -> Result<Output> {
+        self.statement_executor
+            .authorize(
+                Privilege::Read,
+                catalog_name,
+                schema_name,
+                Some(table_name),
+                ctx,
+            )
+            .await?;
         let table = self
             .catalog_manager
             .table(catalog_name, schema_name, table_name)
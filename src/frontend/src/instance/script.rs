@@ -47,4 +47,16 @@ impl ScriptHandler for Instance {
             .execute_script(schema, name, params)
             .await
     }
+
+    async fn schedule_script(
+        &self,
+        schema: &str,
+        name: &str,
+        schedule: Option<&str>,
+        target_table: Option<&str>,
+    ) -> servers::error::Result<()> {
+        self.script_executor
+            .schedule_script(schema, name, schedule, target_table)
+            .await
+    }
 }
@@ -15,15 +15,21 @@
 #![feature(assert_matches)]
 #![feature(trait_upcasting)]
 
+pub mod audit;
 pub mod catalog;
 pub mod error;
 pub mod expr_factory;
 pub mod frontend;
 pub mod heartbeat;
 pub mod instance;
+pub mod materialized_view;
 pub(crate) mod metrics;
+pub mod query_cache;
+pub mod rbac;
 mod script;
 mod server;
 pub mod service_config;
+pub mod slow_query;
 pub mod statement;
 pub mod table;
+pub mod write_limit;
@@ -0,0 +1,880 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Materialized views, a.k.a. continuous aggregates.
+//!
+//! A materialized view is an aggregation query grouped by a `date_bin` time
+//! bucket, whose results are stored in a table with the same name as the view.
+//! Definitions of materialized views are persisted in the system table
+//! `materialized_views`, and views are refreshed in the background at their
+//! refresh intervals.
+//!
+//! Refreshes are incremental: each view keeps a watermark for every region of
+//! its source table, the start of the latest time bucket the region has rows
+//! in. Only source rows at or after the lowest of the watermarks are
+//! aggregated again, and the results are upserted into the view's table, so
+//! the latest (possibly incomplete) buckets are recomputed while earlier
+//! buckets are left as they are. A region lagging behind the others, like a
+//! partition whose writers are late, holds the watermarks back, so its rows are
+//! still aggregated when they arrive. Rows older than their own region's
+//! watermark are not reflected.
+//!
+//! In distributed mode only the frontend holding the refresh lease refreshes
+//! views, see [Lease].
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use catalog::remote::lease::Lease;
+use catalog::{CatalogManagerRef, RegisterSystemTableRequest};
+use common_catalog::consts::{
+    DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, MATERIALIZED_VIEWS_TABLE_ID, MITO_ENGINE,
+};
+use common_catalog::format_full_table_name;
+use common_meta::table_name::TableName;
+use common_query::Output;
+use common_recordbatch::{util as record_util, RecordBatch};
+use common_telemetry::logging;
+use common_time::timestamp::TimeUnit;
+use common_time::util;
+use datafusion_common::tree_node::{Transformed, TreeNode, VisitRecursion};
+use datafusion_common::{Column, OwnedTableReference};
+use datafusion_expr::expr::ScalarFunction;
+use datafusion_expr::{
+    lit, max, BuiltinScalarFunction, Expr, Filter, LogicalPlan as DfLogicalPlan, LogicalPlanBuilder,
+};
+use datatypes::prelude::{ConcreteDataType, ScalarVector, Value, Vector};
+use datatypes::schema::{ColumnSchema, RawSchema};
+use datatypes::value::timestamp_to_scalar_value;
+use datatypes::vectors::{Int64Vector, StringVector, TimestampMillisecondVector, VectorRef};
+use partition::partition::PartitionRuleRef;
+use query::parser::QueryStatement;
+use query::plan::LogicalPlan;
+use query::QueryEngineRef;
+use session::context::{QueryContext, QueryContextRef};
+use snafu::{ensure, OptionExt, ResultExt};
+use sql::dialect::GreptimeDbDialect;
+use sql::parser::ParserContext;
+use sql::statements::statement::Statement;
+use store_api::storage::{RegionNumber, ScanRequest};
+use table::metadata::TableType;
+use table::requests::{CreateTableRequest, DeleteRequest, InsertRequest, TableOptions};
+use table::TableRef;
+
+use crate::catalog::FrontendCatalogManager;
+use crate::error::{
+    CatalogSnafu, CollectRecordbatchSnafu, DecodeJsonSnafu, EncodeJsonSnafu, ExecLogicalPlanSnafu,
+    FindTablePartitionRuleSnafu, InvalidSqlSnafu, InvalidSystemTableColumnSnafu, ParseSqlSnafu,
+    PlanStatementSnafu, Result, TableNotFoundSnafu, TableSnafu,
+};
+use crate::table::DistTable;
+
+pub const MATERIALIZED_VIEWS_TABLE_NAME: &str = "materialized_views";
+
+/// Refresh interval of materialized views created without `refresh_interval` option.
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often materialized views are checked whether their refresh intervals
+/// have elapsed.
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Name of the lease held by the frontend that refreshes materialized views.
+pub const REFRESH_LEASE_NAME: &str = "materialized_view_refresh";
+
+/// How long the refresh lease is held without being renewed. The holder renews
+/// it before refreshing each view.
+pub const REFRESH_LEASE_TTL: Duration = Duration::from_secs(30);
+
+/// A materialized view persisted in the materialized views table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaterializedView {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub view_name: String,
+    /// The aggregation query that defines the view.
+    pub definition: String,
+    pub refresh_interval: Duration,
+    /// Start of the latest time bucket each region of the source table has rows
+    /// in, in milliseconds. Empty if the view has never been refreshed.
+    pub watermarks: BTreeMap<RegionNumber, i64>,
+    pub gmt_created: i64,
+}
+
+impl MaterializedView {
+    pub fn full_name(&self) -> String {
+        format_full_table_name(&self.catalog_name, &self.schema_name, &self.view_name)
+    }
+}
+
+/// Persists materialized views and refreshes them.
+pub struct MaterializedViewManager {
+    catalog_manager: CatalogManagerRef,
+    query_engine: QueryEngineRef,
+    /// Full names of views to when they were last refreshed by this frontend.
+    last_refreshed: Mutex<HashMap<String, Instant>>,
+    /// Lease of refreshing views in the background, `None` in standalone mode
+    /// where there is only one frontend.
+    refresh_lease: Option<Lease>,
+}
+
+impl MaterializedViewManager {
+    pub async fn try_new(
+        catalog_manager: CatalogManagerRef,
+        query_engine: QueryEngineRef,
+        refresh_lease: Option<Lease>,
+    ) -> Result<Self> {
+        // Like the scripts table, this table is put into default catalog and schema.
+        let request = CreateTableRequest {
+            id: MATERIALIZED_VIEWS_TABLE_ID,
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: MATERIALIZED_VIEWS_TABLE_NAME.to_string(),
+            desc: Some("Materialized views table".to_string()),
+            schema: build_materialized_views_schema(),
+            region_numbers: vec![0],
+            primary_key_indices: vec![0, 1, 2],
+            create_if_not_exists: true,
+            table_options: TableOptions::default(),
+            engine: MITO_ENGINE.to_string(),
+        };
+        catalog_manager
+            .register_system_table(RegisterSystemTableRequest {
+                create_table_request: request,
+                open_hook: None,
+            })
+            .await
+            .context(CatalogSnafu)?;
+
+        Ok(Self {
+            catalog_manager,
+            query_engine,
+            last_refreshed: Mutex::new(HashMap::new()),
+            refresh_lease,
+        })
+    }
+
+    /// Starts refreshing materialized views in the background.
+    pub fn start(self: &Arc<Self>) {
+        let manager = self.clone();
+        let _handle = common_runtime::spawn_bg(async move {
+            let mut interval = tokio::time::interval(CHECK_INTERVAL);
+            loop {
+                let _ = interval.tick().await;
+                if let Err(e) = manager.refresh_due().await {
+                    logging::error!(e; "Failed to refresh materialized views");
+                }
+            }
+        });
+    }
+
+    /// Refreshes the materialized views whose refresh intervals have elapsed
+    /// since they were last refreshed.
+    async fn refresh_due(&self) -> Result<()> {
+        for view in self.list().await? {
+            let full_name = view.full_name();
+            let is_due = self
+                .last_refreshed
+                .lock()
+                .unwrap()
+                .get(&full_name)
+                .map(|last| last.elapsed() >= view.refresh_interval)
+                .unwrap_or(true);
+            if !is_due {
+                continue;
+            }
+            // Renewed for each view, as refreshing all views may outlast the lease.
+            if !self.hold_refresh_lease().await? {
+                return Ok(());
+            }
+
+            // A failed view is retried in its next refresh interval.
+            if let Err(e) = self.refresh(&view).await {
+                logging::error!(e; "Failed to refresh materialized view {}", full_name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether this frontend holds the lease of refreshing views.
+    async fn hold_refresh_lease(&self) -> Result<bool> {
+        match &self.refresh_lease {
+            Some(lease) => lease.acquire().await.context(CatalogSnafu),
+            None => Ok(true),
+        }
+    }
+
+    /// Returns whether this frontend still holds the lease of refreshing views,
+    /// renewing it for the writes that follow.
+    async fn renew_refresh_lease(&self) -> Result<bool> {
+        match &self.refresh_lease {
+            Some(lease) => lease.renew().await.context(CatalogSnafu),
+            None => Ok(true),
+        }
+    }
+
+    /// Aggregates source rows since the view's lowest watermark and upserts the
+    /// results into the view's table, then advances the watermarks.
+    pub async fn refresh(&self, view: &MaterializedView) -> Result<()> {
+        let full_name = view.full_name();
+        let _ = self
+            .last_refreshed
+            .lock()
+            .unwrap()
+            .insert(full_name.clone(), Instant::now());
+
+        let target = self
+            .catalog_manager
+            .table(&view.catalog_name, &view.schema_name, &view.view_name)
+            .await
+            .context(CatalogSnafu)?
+            .with_context(|| TableNotFoundSnafu {
+                table_name: &full_name,
+            })?;
+
+        let query_ctx = Arc::new(QueryContext::with(&view.catalog_name, &view.schema_name));
+        let mut stmts = ParserContext::create_with_dialect(&view.definition, &GreptimeDbDialect {})
+            .context(ParseSqlSnafu)?;
+        ensure!(
+            stmts.len() == 1 && matches!(stmts[0], Statement::Query(_)),
+            InvalidSqlSnafu {
+                err_msg: format!("Invalid definition of materialized view {full_name}"),
+            }
+        );
+        let plan = self
+            .query_engine
+            .planner()
+            .plan(QueryStatement::Sql(stmts.remove(0)), query_ctx.clone())
+            .await
+            .context(PlanStatementSnafu)?;
+
+        let LogicalPlan::DfPlan(plan) = plan;
+        let (source_name, source) = self
+            .source_table(&plan, &view.catalog_name, &view.schema_name)
+            .await?;
+        let source_table_name = source_name.to_string();
+        let partition_rule = self.partition_rule(&source).await?;
+        let regions = match &partition_rule {
+            Some(partition_rule) => self
+                .frontend_catalog_manager()
+                .map(|m| m.partition_manager())
+                .map(|m| m.find_regions_by_filters(partition_rule.clone(), &[]))
+                .transpose()
+                .context(FindTablePartitionRuleSnafu {
+                    table_name: &source_table_name,
+                })?
+                .unwrap_or_default(),
+            None => source.table_info().meta.region_numbers.clone(),
+        };
+        // Rows of regions without watermarks, which are new or have never had
+        // rows, are all aggregated.
+        let since = regions
+            .iter()
+            .map(|region| view.watermarks.get(region).copied())
+            .min()
+            .flatten();
+        let plan = match since {
+            Some(since) => filter_since_watermark(plan, source_name, &source, since)?,
+            None => plan,
+        };
+
+        // Latest buckets are found before aggregating, so rows written in between
+        // are aggregated again in the next refresh instead of being skipped.
+        let latest_buckets = self
+            .latest_buckets(
+                &plan,
+                &source_table_name,
+                partition_rule,
+                &regions,
+                query_ctx.clone(),
+            )
+            .await?;
+        let batches = self.execute(plan, query_ctx).await?;
+        for batch in batches {
+            if batch.num_rows() == 0 {
+                continue;
+            }
+
+            let columns_values = batch
+                .schema
+                .column_schemas()
+                .iter()
+                .zip(batch.columns())
+                .map(|(column, vector)| (column.name.clone(), vector.clone()))
+                .collect();
+            let _ = target
+                .insert(InsertRequest {
+                    catalog_name: view.catalog_name.clone(),
+                    schema_name: view.schema_name.clone(),
+                    table_name: view.view_name.clone(),
+                    columns_values,
+                    region_number: 0,
+                })
+                .await
+                .context(TableSnafu)?;
+        }
+
+        let watermarks = advance_watermarks(&view.watermarks, &regions, &latest_buckets);
+        if watermarks != view.watermarks {
+            // Another frontend may have taken the lease over during a long refresh and
+            // advanced the watermarks already, which must not be overwritten. The rows
+            // upserted above are aggregated again in its refreshes.
+            if !self.renew_refresh_lease().await? {
+                logging::warn!(
+                    "Lost the lease of refreshing views, skip advancing watermarks of {}",
+                    full_name
+                );
+                return Ok(());
+            }
+            self.insert(&MaterializedView {
+                watermarks: watermarks.clone(),
+                ..view.clone()
+            })
+            .await?;
+        }
+        logging::debug!(
+            "Refreshed materialized view {}, watermarks: {:?}",
+            full_name,
+            watermarks
+        );
+        Ok(())
+    }
+
+    /// Finds the start of the latest time bucket of each source region's rows
+    /// read by `plan`, the plan of a view's query.
+    async fn latest_buckets(
+        &self,
+        plan: &DfLogicalPlan,
+        source_table_name: &str,
+        partition_rule: Option<PartitionRuleRef>,
+        regions: &[RegionNumber],
+        query_ctx: QueryContextRef,
+    ) -> Result<HashMap<RegionNumber, i64>> {
+        let partition_columns = partition_rule
+            .as_ref()
+            .map(|rule| rule.partition_columns())
+            .unwrap_or_default();
+        let plan = build_latest_buckets_plan(plan, &partition_columns)?;
+        let batches = self.execute(plan, query_ctx).await?;
+
+        let mut latest_buckets = HashMap::new();
+        for batch in batches {
+            let columns = batch.columns();
+            // Safety: the plan outputs partition columns followed by the latest bucket.
+            let buckets = &columns[partition_columns.len()];
+            for i in 0..batch.num_rows() {
+                let Some(bucket) = buckets
+                    .get(i)
+                    .as_timestamp()
+                    .and_then(|ts| ts.convert_to(TimeUnit::Millisecond))
+                    .map(|ts| ts.value()) else { continue };
+                let row_regions = match &partition_rule {
+                    Some(partition_rule) => {
+                        let values = columns[..partition_columns.len()]
+                            .iter()
+                            .map(|c| c.get(i))
+                            .collect::<Vec<_>>();
+                        let region = partition_rule.find_region(&values).with_context(|_| {
+                            FindTablePartitionRuleSnafu {
+                                table_name: source_table_name,
+                            }
+                        })?;
+                        vec![region]
+                    }
+                    // Without a partition rule the rows are not told apart by regions.
+                    None => regions.to_vec(),
+                };
+                for region in row_regions {
+                    let latest = latest_buckets.entry(region).or_insert(bucket);
+                    *latest = (*latest).max(bucket);
+                }
+            }
+        }
+        Ok(latest_buckets)
+    }
+
+    async fn execute(
+        &self,
+        plan: DfLogicalPlan,
+        query_ctx: QueryContextRef,
+    ) -> Result<Vec<RecordBatch>> {
+        let output = self
+            .query_engine
+            .execute(LogicalPlan::DfPlan(plan), query_ctx)
+            .await
+            .context(ExecLogicalPlanSnafu)?;
+        Ok(match output {
+            Output::Stream(stream) => record_util::collect(stream)
+                .await
+                .context(CollectRecordbatchSnafu)?,
+            Output::RecordBatches(batches) => batches.take(),
+            Output::AffectedRows(_) => vec![],
+        })
+    }
+
+    fn frontend_catalog_manager(&self) -> Option<&FrontendCatalogManager> {
+        self.catalog_manager
+            .as_any()
+            .downcast_ref::<FrontendCatalogManager>()
+    }
+
+    /// Returns the partition rule of the source table in distributed mode.
+    async fn partition_rule(&self, source: &TableRef) -> Result<Option<PartitionRuleRef>> {
+        let Some(catalog_manager) = self.frontend_catalog_manager() else { return Ok(None) };
+        if source.as_any().downcast_ref::<DistTable>().is_none() {
+            return Ok(None);
+        }
+        let table_info = source.table_info();
+        let table_name = TableName::new(
+            &table_info.catalog_name,
+            &table_info.schema_name,
+            &table_info.name,
+        );
+        catalog_manager
+            .partition_manager()
+            .find_table_partition_rule(&table_name)
+            .await
+            .with_context(|_| FindTablePartitionRuleSnafu {
+                table_name: table_name.to_string(),
+            })
+            .map(Some)
+    }
+
+    /// Returns the only table the plan reads from, along with its name in the
+    /// plan. Materialized views must be defined on exactly one table.
+    pub(crate) async fn source_table(
+        &self,
+        plan: &DfLogicalPlan,
+        catalog: &str,
+        schema: &str,
+    ) -> Result<(OwnedTableReference, TableRef)> {
+        let mut scans = vec![];
+        let _ = plan.apply(&mut |plan| {
+            if let DfLogicalPlan::TableScan(scan) = plan {
+                scans.push(scan.table_name.clone());
+            }
+            Ok(VisitRecursion::Continue)
+        });
+        ensure!(
+            scans.len() == 1,
+            InvalidSqlSnafu {
+                err_msg: "A materialized view must read from exactly one table",
+            }
+        );
+        let source_name = scans.remove(0);
+
+        let resolved = source_name.clone().resolve(catalog, schema);
+        let source = self
+            .catalog_manager
+            .table(&resolved.catalog, &resolved.schema, &resolved.table)
+            .await
+            .context(CatalogSnafu)?
+            .with_context(|| TableNotFoundSnafu {
+                table_name: source_name.to_string(),
+            })?;
+        ensure!(
+            source.table_type() == TableType::Base && source.schema().timestamp_column().is_some(),
+            InvalidSqlSnafu {
+                err_msg: format!(
+                    "Source of a materialized view must be a table with time index, found: {source_name}"
+                ),
+            }
+        );
+        Ok((source_name, source))
+    }
+
+    pub async fn list(&self) -> Result<Vec<MaterializedView>> {
+        let mut views = vec![];
+        // The system table may not be created yet before the catalog manager starts.
+        let Some(table) = self.find_table().await? else { return Ok(views) };
+        let stream = table
+            .scan_to_stream(ScanRequest::default())
+            .await
+            .context(TableSnafu)?;
+        let batches = record_util::collect(stream)
+            .await
+            .context(CollectRecordbatchSnafu)?;
+
+        for batch in batches {
+            let catalogs = string_column(&batch, "catalog_name")?;
+            let schemas = string_column(&batch, "schema_name")?;
+            let names = string_column(&batch, "view_name")?;
+            let definitions = string_column(&batch, "definition")?;
+            let refresh_intervals = column(&batch, "refresh_interval")?;
+            let watermarks = string_column(&batch, "watermarks")?;
+            let gmt_created = column(&batch, "gmt_created")?;
+            for i in 0..batch.num_rows() {
+                let (Some(catalog_name), Some(schema_name), Some(view_name), Some(definition)) = (
+                    catalogs.get_data(i),
+                    schemas.get_data(i),
+                    names.get_data(i),
+                    definitions.get_data(i),
+                ) else { continue };
+                let watermarks = watermarks
+                    .get_data(i)
+                    .map(|w| serde_json::from_str(w).context(DecodeJsonSnafu))
+                    .transpose()?
+                    .unwrap_or_default();
+                views.push(MaterializedView {
+                    catalog_name: catalog_name.to_string(),
+                    schema_name: schema_name.to_string(),
+                    view_name: view_name.to_string(),
+                    definition: definition.to_string(),
+                    refresh_interval: as_i64(refresh_intervals.get(i))
+                        .map(|ms| Duration::from_millis(ms as u64))
+                        .unwrap_or(DEFAULT_REFRESH_INTERVAL),
+                    watermarks,
+                    gmt_created: as_i64(gmt_created.get(i)).unwrap_or_default(),
+                });
+            }
+        }
+        Ok(views)
+    }
+
+    pub async fn find(
+        &self,
+        catalog: &str,
+        schema: &str,
+        view_name: &str,
+    ) -> Result<Option<MaterializedView>> {
+        Ok(self.list().await?.into_iter().find(|view| {
+            view.catalog_name == catalog
+                && view.schema_name == schema
+                && view.view_name == view_name
+        }))
+    }
+
+    /// Inserts the view, or updates it if it already exists.
+    pub async fn insert(&self, view: &MaterializedView) -> Result<()> {
+        let mut columns_values = view_key(&view.catalog_name, &view.schema_name, &view.view_name);
+        columns_values.extend([
+            (
+                "definition".to_string(),
+                Arc::new(StringVector::from(vec![view.definition.as_str()])) as VectorRef,
+            ),
+            (
+                "refresh_interval".to_string(),
+                Arc::new(Int64Vector::from_slice([
+                    view.refresh_interval.as_millis() as i64
+                ])) as VectorRef,
+            ),
+            (
+                "watermarks".to_string(),
+                Arc::new(StringVector::from(vec![serde_json::to_string(
+                    &view.watermarks,
+                )
+                .context(EncodeJsonSnafu)?])) as VectorRef,
+            ),
+            (
+                "gmt_created".to_string(),
+                Arc::new(TimestampMillisecondVector::from_slice([view.gmt_created])) as VectorRef,
+            ),
+            (
+                "gmt_modified".to_string(),
+                Arc::new(TimestampMillisecondVector::from_slice([
+                    util::current_time_millis(),
+                ])) as VectorRef,
+            ),
+        ]);
+
+        let _ = self
+            .table()
+            .await?
+            .insert(InsertRequest {
+                catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+                schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name: MATERIALIZED_VIEWS_TABLE_NAME.to_string(),
+                columns_values,
+                region_number: 0,
+            })
+            .await
+            .context(TableSnafu)?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, catalog: &str, schema: &str, view_name: &str) -> Result<()> {
+        let _ = self
+            .table()
+            .await?
+            .delete(DeleteRequest {
+                key_column_values: view_key(catalog, schema, view_name),
+            })
+            .await
+            .context(TableSnafu)?;
+        let _ = self
+            .last_refreshed
+            .lock()
+            .unwrap()
+            .remove(&format_full_table_name(catalog, schema, view_name));
+        Ok(())
+    }
+
+    async fn find_table(&self) -> Result<Option<TableRef>> {
+        self.catalog_manager
+            .table(
+                DEFAULT_CATALOG_NAME,
+                DEFAULT_SCHEMA_NAME,
+                MATERIALIZED_VIEWS_TABLE_NAME,
+            )
+            .await
+            .context(CatalogSnafu)
+    }
+
+    async fn table(&self) -> Result<TableRef> {
+        self.find_table()
+            .await?
+            .with_context(|| TableNotFoundSnafu {
+                table_name: MATERIALIZED_VIEWS_TABLE_NAME,
+            })
+    }
+}
+
+/// Filters the rows of the source table, whose name in the plan is `source_name`,
+/// by `time index >= watermark`.
+fn filter_since_watermark(
+    plan: DfLogicalPlan,
+    source_name: OwnedTableReference,
+    source: &TableRef,
+    watermark: i64,
+) -> Result<DfLogicalPlan> {
+    // Safety: source tables are checked to have time index.
+    let ts_column = source.schema().timestamp_column().cloned().unwrap();
+    let unit = match &ts_column.data_type {
+        ConcreteDataType::Timestamp(t) => t.unit(),
+        _ => TimeUnit::Millisecond,
+    };
+    let watermark = common_time::Timestamp::new_millisecond(watermark)
+        .convert_to(unit)
+        .map(|ts| ts.value())
+        .unwrap_or(watermark);
+    let predicate = Expr::Column(Column::new(Some(source_name.clone()), &ts_column.name))
+        .gt_eq(lit(timestamp_to_scalar_value(unit, Some(watermark))));
+
+    plan.transform_up(&|plan| match plan {
+        DfLogicalPlan::TableScan(scan) if scan.table_name == source_name => {
+            let filter =
+                Filter::try_new(predicate.clone(), Arc::new(DfLogicalPlan::TableScan(scan)))?;
+            Ok(Transformed::Yes(DfLogicalPlan::Filter(filter)))
+        }
+        _ => Ok(Transformed::No(plan)),
+    })
+    .map_err(|e| {
+        InvalidSqlSnafu {
+            err_msg: format!("Failed to filter materialized view source by watermark: {e}"),
+        }
+        .build()
+    })
+}
+
+/// Builds the plan that finds the latest time bucket of the rows of each
+/// partition, from the aggregation in the plan of a view's query.
+fn build_latest_buckets_plan(
+    plan: &DfLogicalPlan,
+    partition_columns: &[String],
+) -> Result<DfLogicalPlan> {
+    let mut aggregate = None;
+    let _ = plan.apply(&mut |plan| {
+        if let DfLogicalPlan::Aggregate(a) = plan {
+            aggregate = Some(a.clone());
+            return Ok(VisitRecursion::Stop);
+        }
+        Ok(VisitRecursion::Continue)
+    });
+    let bucket = aggregate.as_ref().and_then(|aggregate| {
+        aggregate
+            .group_expr
+            .iter()
+            .map(|expr| expr.clone().unalias())
+            .find(|expr| {
+                matches!(
+                    expr,
+                    Expr::ScalarFunction(ScalarFunction {
+                        fun: BuiltinScalarFunction::DateBin,
+                        ..
+                    })
+                )
+            })
+    });
+    let (Some(aggregate), Some(bucket)) = (aggregate, bucket) else {
+        return InvalidSqlSnafu {
+            err_msg: "A materialized view must be grouped by a date_bin time bucket",
+        }
+        .fail();
+    };
+
+    let group_expr = partition_columns
+        .iter()
+        .map(|c| Expr::Column(Column::from_name(c)))
+        .collect::<Vec<_>>();
+    LogicalPlanBuilder::from(aggregate.input.as_ref().clone())
+        .aggregate(group_expr, vec![max(bucket)])
+        .and_then(|builder| builder.build())
+        .map_err(|e| {
+            InvalidSqlSnafu {
+                err_msg: format!("Failed to plan latest buckets of materialized view: {e}"),
+            }
+            .build()
+        })
+}
+
+/// Advances the watermarks of `regions` to their latest buckets. Regions without
+/// rows take the lowest watermark of the others, rows older than it have all
+/// been aggregated.
+fn advance_watermarks(
+    watermarks: &BTreeMap<RegionNumber, i64>,
+    regions: &[RegionNumber],
+    latest_buckets: &HashMap<RegionNumber, i64>,
+) -> BTreeMap<RegionNumber, i64> {
+    let mut advanced = regions
+        .iter()
+        .filter_map(|region| {
+            let watermark = watermarks.get(region).copied();
+            let latest = latest_buckets.get(region).copied();
+            watermark.max(latest).map(|w| (*region, w))
+        })
+        .collect::<BTreeMap<_, _>>();
+    if let Some(lowest) = advanced.values().min().copied() {
+        for region in regions {
+            let _ = advanced.entry(*region).or_insert(lowest);
+        }
+    }
+    advanced
+}
+
+fn view_key(catalog: &str, schema: &str, view_name: &str) -> HashMap<String, VectorRef> {
+    HashMap::from([
+        (
+            "catalog_name".to_string(),
+            Arc::new(StringVector::from(vec![catalog])) as VectorRef,
+        ),
+        (
+            "schema_name".to_string(),
+            Arc::new(StringVector::from(vec![schema])) as VectorRef,
+        ),
+        (
+            "view_name".to_string(),
+            Arc::new(StringVector::from(vec![view_name])) as VectorRef,
+        ),
+        (
+            "timestamp".to_string(),
+            // Timestamp in key part is intentionally left to 0
+            Arc::new(TimestampMillisecondVector::from_slice([0])) as VectorRef,
+        ),
+    ])
+}
+
+fn column<'a>(batch: &'a RecordBatch, column: &str) -> Result<&'a VectorRef> {
+    batch
+        .column_by_name(column)
+        .with_context(|| InvalidSystemTableColumnSnafu {
+            table_name: MATERIALIZED_VIEWS_TABLE_NAME,
+            column,
+        })
+}
+
+fn string_column<'a>(batch: &'a RecordBatch, column: &str) -> Result<&'a StringVector> {
+    batch
+        .column_by_name(column)
+        .and_then(|c| c.as_any().downcast_ref::<StringVector>())
+        .with_context(|| InvalidSystemTableColumnSnafu {
+            table_name: MATERIALIZED_VIEWS_TABLE_NAME,
+            column,
+        })
+}
+
+fn as_i64(value: Value) -> Option<i64> {
+    match value {
+        Value::Int64(v) => Some(v),
+        Value::Timestamp(ts) => Some(ts.value()),
+        _ => None,
+    }
+}
+
+fn build_materialized_views_schema() -> RawSchema {
+    RawSchema::new(vec![
+        ColumnSchema::new("catalog_name", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("schema_name", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("view_name", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new(
+            "timestamp",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )
+        .with_time_index(true),
+        ColumnSchema::new("definition", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new(
+            "refresh_interval",
+            ConcreteDataType::int64_datatype(),
+            false,
+        ),
+        ColumnSchema::new("watermarks", ConcreteDataType::string_datatype(), true),
+        ColumnSchema::new(
+            "gmt_created",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "gmt_modified",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advance_watermarks() {
+        let regions = [0, 1, 2];
+
+        // never refreshed, region 2 has no rows
+        let latest_buckets = HashMap::from([(0, 2000), (1, 1000)]);
+        let watermarks = advance_watermarks(&BTreeMap::new(), &regions, &latest_buckets);
+        assert_eq!(
+            BTreeMap::from([(0, 2000), (1, 1000), (2, 1000)]),
+            watermarks
+        );
+
+        // a lagging region advances on its own, without skipping others' rows
+        let latest_buckets = HashMap::from([(0, 2000), (1, 1500), (2, 3000)]);
+        let watermarks = advance_watermarks(&watermarks, &regions, &latest_buckets);
+        assert_eq!(
+            BTreeMap::from([(0, 2000), (1, 1500), (2, 3000)]),
+            watermarks
+        );
+
+        // watermarks never go back, and removed regions are dropped
+        let latest_buckets = HashMap::from([(0, 1000)]);
+        let watermarks = advance_watermarks(&watermarks, &[0, 1], &latest_buckets);
+        assert_eq!(BTreeMap::from([(0, 2000), (1, 1500)]), watermarks);
+
+        // no rows at all
+        let watermarks = advance_watermarks(&BTreeMap::new(), &regions, &HashMap::new());
+        assert!(watermarks.is_empty());
+    }
+
+    #[test]
+    fn test_as_i64() {
+        assert_eq!(Some(60_000), as_i64(Value::Int64(60_000)));
+        assert_eq!(
+            Some(1000),
+            as_i64(Value::Timestamp(common_time::Timestamp::new_millisecond(
+                1000
+            )))
+        );
+        assert_eq!(None, as_i64(Value::Null));
+    }
+}
@@ -16,12 +16,16 @@ pub(crate) const METRIC_HANDLE_SQL_ELAPSED: &str = "frontend.handle_sql_elapsed"
 pub(crate) const METRIC_EXEC_PLAN_ELAPSED: &str = "frontend.exec_plan_elapsed";
 pub(crate) const METRIC_HANDLE_SCRIPTS_ELAPSED: &str = "frontend.handle_scripts_elapsed";
 pub(crate) const METRIC_RUN_SCRIPT_ELAPSED: &str = "frontend.run_script_elapsed";
+pub(crate) const METRIC_PROMQL_CACHE_HIT_STEPS: &str = "frontend.promql_cache.hit_steps";
+pub(crate) const METRIC_PROMQL_CACHE_MISS_STEPS: &str = "frontend.promql_cache.miss_steps";
+pub(crate) const METRIC_PLAN_CACHE_HIT: &str = "frontend.plan_cache.hit";
+pub(crate) const METRIC_PLAN_CACHE_MISS: &str = "frontend.plan_cache.miss";
+pub(crate) const METRIC_WRITE_RATE_LIMITED: &str = "frontend.write.rate_limited";
 
 /// frontend metrics
 /// Metrics for creating table in dist mode.
 pub const DIST_CREATE_TABLE: &str = "frontend.dist.create_table";
 pub const DIST_CREATE_TABLE_IN_META: &str = "frontend.dist.create_table.update_meta";
-pub const DIST_CREATE_TABLE_IN_DATANODE: &str = "frontend.dist.create_table.invoke_datanode";
 pub const DIST_INGEST_ROW_COUNT: &str = "frontend.dist.ingest_rows";
 
 /// The samples count of Prometheus remote write.
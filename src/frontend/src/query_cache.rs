@@ -0,0 +1,806 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Caches of repeated queries.
+//!
+//! Dashboards re-issue the same PromQL range queries on every refresh, with
+//! their ranges moved forward. [PromQueryCache] keeps the results of these
+//! queries per step, so a refreshed query only evaluates the steps that are not
+//! cached yet. [PlanCache] keeps the logical plans of queries prepared by
+//! clients, which are planned again on each preparation otherwise.
+//!
+//! Steps later than `now - lookback_delta - freshness_window` are not cached,
+//! as rows may still be written into their lookback windows. The cache may
+//! still serve results as old as the configured max age, e.g. rows written
+//! later than the freshness window into steps that are cached are not visible
+//! until the steps expire.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use catalog::CatalogManagerRef;
+use common_recordbatch::{RecordBatch, RecordBatches};
+use common_time::util::current_time_millis;
+use datafusion::datasource::{source_as_provider, ViewTable};
+use datafusion_common::tree_node::{TreeNode, VisitRecursion};
+use datafusion_expr::{Expr, LogicalPlan as DfLogicalPlan, TableScan};
+use datatypes::prelude::{ConcreteDataType, ScalarVector};
+use datatypes::schema::SchemaRef;
+use datatypes::types::TimestampType;
+use datatypes::vectors::{TimestampMillisecondVector, UInt32Vector};
+use metrics::{counter, increment_counter};
+use moka::future::{Cache, CacheBuilder};
+use promql_parser::parser::EvalStmt;
+use query::plan::LogicalPlan;
+use serde::{Deserialize, Serialize};
+use session::context::QueryContextRef;
+use snafu::ResultExt;
+use sql::statements::query::Query;
+use table::metadata::{TableIdent, TableType};
+use table::table::adapter::DfTableProviderAdapter;
+use table::table::view::VIEW_DEFINITION_KEY;
+use table::TableRef;
+
+use crate::error::{BuildCachedResultSnafu, CatalogSnafu, Result, SplitQueryResultSnafu};
+use crate::metrics::{
+    METRIC_PLAN_CACHE_HIT, METRIC_PLAN_CACHE_MISS, METRIC_PROMQL_CACHE_HIT_STEPS,
+    METRIC_PROMQL_CACHE_MISS_STEPS,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct QueryCacheOptions {
+    pub enable: bool,
+    /// Cached results and plans older than this are discarded.
+    #[serde(with = "humantime_serde")]
+    pub max_age: Duration,
+    /// Steps later than `now - lookback_delta - freshness_window` are not
+    /// cached, leaving rows written late this window to be visible.
+    #[serde(with = "humantime_serde")]
+    pub freshness_window: Duration,
+    /// Max number of PromQL range queries whose results are cached.
+    pub promql_capacity: u64,
+    /// Max number of cached logical plans.
+    pub plan_capacity: u64,
+}
+
+impl Default for QueryCacheOptions {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            max_age: Duration::from_secs(60),
+            freshness_window: Duration::from_secs(60),
+            promql_capacity: 1024,
+            plan_capacity: 1024,
+        }
+    }
+}
+
+/// Identifies a PromQL range query regardless of its range.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PromCacheKey {
+    /// Results are not shared among users, who may have different privileges.
+    user: String,
+    catalog: String,
+    schema: String,
+    query: String,
+    step: Duration,
+    lookback: Duration,
+}
+
+impl PromCacheKey {
+    pub fn new(stmt: &EvalStmt, query: &str, query_ctx: &QueryContextRef) -> Self {
+        Self {
+            user: query_ctx.current_user().username().to_string(),
+            catalog: query_ctx.current_catalog(),
+            schema: query_ctx.current_schema(),
+            query: query.to_string(),
+            step: stmt.interval,
+            lookback: stmt.lookback_delta,
+        }
+    }
+}
+
+/// The range of a PromQL range query, in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepRange {
+    pub start: i64,
+    pub end: i64,
+    pub step: i64,
+}
+
+impl StepRange {
+    /// Returns `None` if the query can't be cached: its start is not aligned to
+    /// the step, so its steps are not shared by queries with moved ranges.
+    pub fn try_new(stmt: &EvalStmt) -> Option<Self> {
+        let start = to_millis(stmt.start)?;
+        let end = to_millis(stmt.end)?;
+        let step = i64::try_from(stmt.interval.as_millis()).ok()?;
+
+        (step > 0 && start < end && start % step == 0).then_some(Self { start, end, step })
+    }
+
+    fn steps_from(&self, start: i64) -> impl Iterator<Item = i64> {
+        (start..=self.end).step_by(self.step as usize)
+    }
+
+    fn contains_step(&self, timestamp: i64) -> bool {
+        timestamp >= self.start
+            && timestamp <= self.end
+            && (timestamp - self.start) % self.step == 0
+    }
+}
+
+fn to_millis(time: SystemTime) -> Option<i64> {
+    let millis = time.duration_since(UNIX_EPOCH).ok()?.as_millis();
+    i64::try_from(millis).ok()
+}
+
+pub fn from_millis(millis: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(millis as u64)
+}
+
+/// The rows of a query evaluated at a step.
+struct CachedStep {
+    /// Empty if no series has a value at this step.
+    batches: Vec<RecordBatch>,
+    computed_at: Instant,
+}
+
+/// The results of a PromQL range query, split by steps.
+struct CachedResult {
+    schema: SchemaRef,
+    steps: BTreeMap<i64, CachedStep>,
+}
+
+/// The leading steps of a query found in the cache.
+pub struct CachedPrefix {
+    result: Arc<CachedResult>,
+    /// The start of the query.
+    start: i64,
+    /// The first step not cached, which is past the end of the query if all
+    /// steps are cached.
+    tail_start: i64,
+}
+
+impl CachedPrefix {
+    pub fn tail_start(&self) -> i64 {
+        self.tail_start
+    }
+
+    fn steps(&self) -> impl Iterator<Item = (&i64, &CachedStep)> {
+        self.result.steps.range(self.start..self.tail_start)
+    }
+
+    /// Returns the rows of the cached steps.
+    pub fn batches(&self) -> Result<RecordBatches> {
+        let batches = self
+            .steps()
+            .flat_map(|(_, step)| step.batches.iter().cloned())
+            .collect();
+        RecordBatches::try_new(self.result.schema.clone(), batches).context(BuildCachedResultSnafu)
+    }
+}
+
+pub struct PromQueryCache {
+    max_age: Duration,
+    freshness_window: Duration,
+    results: Cache<PromCacheKey, Arc<CachedResult>>,
+}
+
+pub type PromQueryCacheRef = Arc<PromQueryCache>;
+
+impl PromQueryCache {
+    pub fn new(opts: &QueryCacheOptions) -> Self {
+        Self {
+            max_age: opts.max_age,
+            freshness_window: opts.freshness_window,
+            results: CacheBuilder::new(opts.promql_capacity)
+                .time_to_live(opts.max_age)
+                .build(),
+        }
+    }
+
+    /// Finds the leading steps of the range that are cached and not expired.
+    pub fn get_prefix(&self, key: &PromCacheKey, range: &StepRange) -> Option<CachedPrefix> {
+        let prefix = self.results.get(key).and_then(|result| {
+            let tail_start = range
+                .steps_from(range.start)
+                .find(|timestamp| {
+                    !result
+                        .steps
+                        .get(timestamp)
+                        .map(|step| step.computed_at.elapsed() <= self.max_age)
+                        .unwrap_or(false)
+                })
+                .unwrap_or(range.end + 1);
+
+            (tail_start > range.start).then_some(CachedPrefix {
+                result,
+                start: range.start,
+                tail_start,
+            })
+        });
+
+        let tail_start = prefix
+            .as_ref()
+            .map(|prefix| prefix.tail_start)
+            .unwrap_or(range.start);
+        let hit_steps = range
+            .steps_from(range.start)
+            .take_while(|t| *t < tail_start)
+            .count();
+        let miss_steps = range.steps_from(tail_start).count();
+        counter!(METRIC_PROMQL_CACHE_HIT_STEPS, hit_steps as u64);
+        counter!(METRIC_PROMQL_CACHE_MISS_STEPS, miss_steps as u64);
+
+        prefix
+    }
+
+    /// Caches the steps of the range, from the cached prefix and the rows of the
+    /// evaluated tail, then returns the rows of all the steps. Cached steps out
+    /// of the range are dropped, as the ranges of refreshed queries move forward.
+    /// Steps that may still change, see [crate::query_cache], are not cached.
+    ///
+    /// Returns `None` if the prefix doesn't match the tail, e.g. the columns are
+    /// changed by altering the table, then the whole range should be evaluated.
+    pub async fn put(
+        &self,
+        key: PromCacheKey,
+        range: &StepRange,
+        prefix: Option<CachedPrefix>,
+        tail: RecordBatches,
+    ) -> Result<Option<RecordBatches>> {
+        let schema = tail.schema();
+        if let Some(prefix) = &prefix {
+            if prefix.result.schema.arrow_schema() != schema.arrow_schema() {
+                self.results.invalidate(&key).await;
+                return Ok(None);
+            }
+        }
+        let tail_start = prefix
+            .as_ref()
+            .map(|prefix| prefix.tail_start)
+            .unwrap_or(range.start);
+
+        let Some(mut tail_steps) = split_by_step(&tail, range, tail_start)? else {
+            // The rows are not at the steps, this is not a query we can cache.
+            self.results.invalidate(&key).await;
+            return merge(prefix, tail).map(Some);
+        };
+
+        let fresh_after =
+            current_time_millis() - (key.lookback + self.freshness_window).as_millis() as i64;
+        let now = Instant::now();
+        let mut steps = BTreeMap::new();
+        if let Some(prefix) = &prefix {
+            for (timestamp, step) in prefix.steps() {
+                let _ = steps.insert(
+                    *timestamp,
+                    CachedStep {
+                        batches: step.batches.clone(),
+                        computed_at: step.computed_at,
+                    },
+                );
+            }
+        }
+        for timestamp in range
+            .steps_from(tail_start)
+            .take_while(|timestamp| *timestamp <= fresh_after)
+        {
+            let _ = steps.insert(
+                timestamp,
+                CachedStep {
+                    batches: tail_steps.remove(&timestamp).unwrap_or_default(),
+                    computed_at: now,
+                },
+            );
+        }
+        let result = Arc::new(CachedResult { schema, steps });
+        self.results.insert(key, result).await;
+
+        merge(prefix, tail).map(Some)
+    }
+}
+
+/// Concatenates the rows of the cached prefix and the evaluated tail.
+fn merge(prefix: Option<CachedPrefix>, tail: RecordBatches) -> Result<RecordBatches> {
+    let Some(prefix) = prefix else { return Ok(tail) };
+
+    let schema = tail.schema();
+    let mut batches = prefix.batches()?.take();
+    batches.extend(tail.take());
+    RecordBatches::try_new(schema, batches).context(BuildCachedResultSnafu)
+}
+
+/// Splits the rows by the steps they are evaluated at. Returns `None` if there
+/// is no millisecond timestamp column, or any row is not at a step of the range
+/// from `tail_start`.
+fn split_by_step(
+    batches: &RecordBatches,
+    range: &StepRange,
+    tail_start: i64,
+) -> Result<Option<BTreeMap<i64, Vec<RecordBatch>>>> {
+    let schema = batches.schema();
+    let Some(ts_index) = schema.column_schemas().iter().position(|column| {
+        matches!(
+            column.data_type,
+            ConcreteDataType::Timestamp(TimestampType::Millisecond(_))
+        )
+    }) else {
+        return Ok(None);
+    };
+
+    let mut steps: BTreeMap<i64, Vec<RecordBatch>> = BTreeMap::new();
+    for batch in batches.iter() {
+        let Some(timestamps) = batch
+            .column(ts_index)
+            .as_any()
+            .downcast_ref::<TimestampMillisecondVector>() else {
+            return Ok(None);
+        };
+
+        let mut rows: BTreeMap<i64, Vec<u32>> = BTreeMap::new();
+        for row in 0..batch.num_rows() {
+            let Some(timestamp) = timestamps.get_data(row) else { return Ok(None) };
+            let timestamp: i64 = timestamp.into();
+            if timestamp < tail_start || !range.contains_step(timestamp) {
+                return Ok(None);
+            }
+            rows.entry(timestamp).or_default().push(row as u32);
+        }
+
+        for (timestamp, rows) in rows {
+            let indices = UInt32Vector::from_vec(rows);
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|column| column.take(&indices))
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .context(SplitQueryResultSnafu)?;
+            let batch =
+                RecordBatch::new(schema.clone(), columns).context(BuildCachedResultSnafu)?;
+            steps.entry(timestamp).or_default().push(batch);
+        }
+    }
+    Ok(Some(steps))
+}
+
+/// Identifies a query planned in a database.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlanCacheKey {
+    catalog: String,
+    schema: String,
+    time_zone: Option<String>,
+    sql: String,
+}
+
+impl PlanCacheKey {
+    pub fn new(query: &Query, query_ctx: &QueryContextRef) -> Self {
+        Self {
+            catalog: query_ctx.current_catalog(),
+            schema: query_ctx.current_schema(),
+            time_zone: query_ctx.time_zone().map(|time_zone| time_zone.to_string()),
+            sql: query.to_string(),
+        }
+    }
+}
+
+/// A table or view scanned by a cached plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PlanDependency {
+    catalog: String,
+    schema: String,
+    name: String,
+    kind: DependencyKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DependencyKind {
+    /// The id and version of a table, which change once the table is recreated
+    /// or altered.
+    Table(TableIdent),
+    /// The definition of a view.
+    View(String),
+}
+
+impl PlanDependency {
+    /// Whether the table or view is still the same as when the plan is cached.
+    fn matches(&self, table: Option<TableRef>) -> bool {
+        let Some(table) = table else { return false };
+        match &self.kind {
+            DependencyKind::Table(ident) => {
+                table.table_type() != TableType::View && table.table_info().ident == *ident
+            }
+            DependencyKind::View(definition) => {
+                table.table_type() == TableType::View
+                    && table
+                        .table_info()
+                        .meta
+                        .options
+                        .extra_options
+                        .get(VIEW_DEFINITION_KEY)
+                        == Some(definition)
+            }
+        }
+    }
+}
+
+/// Collects the tables and views scanned by the plan, including its subqueries
+/// and the plans of its views. Returns `None` if the plan scans a table that is
+/// not from the catalog, whose changes can't be found out.
+fn collect_dependencies(
+    plan: &DfLogicalPlan,
+    catalog: &str,
+    schema: &str,
+    dependencies: &mut Vec<PlanDependency>,
+) -> Option<()> {
+    let mut result = Some(());
+    let _ = plan.apply(&mut |node| {
+        if let DfLogicalPlan::TableScan(scan) = node {
+            result = result.and_then(|_| collect_scan(scan, catalog, schema, dependencies));
+        }
+        for expr in node.expressions() {
+            let _ = expr.apply(&mut |expr| {
+                if let Expr::ScalarSubquery(subquery)
+                | Expr::Exists { subquery, .. }
+                | Expr::InSubquery { subquery, .. } = expr
+                {
+                    result = result.and_then(|_| {
+                        collect_dependencies(&subquery.subquery, catalog, schema, dependencies)
+                    });
+                }
+                Ok(VisitRecursion::Continue)
+            });
+        }
+        Ok(VisitRecursion::Continue)
+    });
+    result
+}
+
+fn collect_scan(
+    scan: &TableScan,
+    catalog: &str,
+    schema: &str,
+    dependencies: &mut Vec<PlanDependency>,
+) -> Option<()> {
+    let provider = source_as_provider(&scan.source).ok()?;
+    if let Some(adapter) = provider.as_any().downcast_ref::<DfTableProviderAdapter>() {
+        let table_info = adapter.table().table_info();
+        dependencies.push(PlanDependency {
+            catalog: table_info.catalog_name.clone(),
+            schema: table_info.schema_name.clone(),
+            name: table_info.name.clone(),
+            kind: DependencyKind::Table(table_info.ident.clone()),
+        });
+        return Some(());
+    }
+
+    let view = provider.as_any().downcast_ref::<ViewTable>()?;
+    let name = scan.table_name.clone().resolve(catalog, schema);
+    dependencies.push(PlanDependency {
+        catalog: name.catalog.to_string(),
+        schema: name.schema.to_string(),
+        name: name.table.to_string(),
+        kind: DependencyKind::View(view.definition()?.clone()),
+    });
+    // Tables in the view are resolved against the view's own catalog and schema.
+    collect_dependencies(
+        view.logical_plan(),
+        &name.catalog,
+        &name.schema,
+        dependencies,
+    )
+}
+
+struct CachedPlan {
+    plan: LogicalPlan,
+    dependencies: Vec<PlanDependency>,
+}
+
+/// Caches the logical plans of queries. A cached plan is discarded once a table
+/// or view it scans is dropped, recreated or altered, no matter which frontend
+/// executes the DDL, as the tables and views are looked up in the catalog on
+/// each hit. Privileges are checked by callers whether a plan is cached or not.
+pub struct PlanCache {
+    plans: Cache<PlanCacheKey, Arc<CachedPlan>>,
+}
+
+pub type PlanCacheRef = Arc<PlanCache>;
+
+impl PlanCache {
+    pub fn new(opts: &QueryCacheOptions) -> Self {
+        Self {
+            plans: CacheBuilder::new(opts.plan_capacity)
+                .time_to_live(opts.max_age)
+                .build(),
+        }
+    }
+
+    /// Returns the cached plan if the tables and views it scans are unchanged.
+    pub async fn get(
+        &self,
+        key: &PlanCacheKey,
+        catalog_manager: &CatalogManagerRef,
+    ) -> Result<Option<LogicalPlan>> {
+        let Some(cached) = self.plans.get(key) else {
+            increment_counter!(METRIC_PLAN_CACHE_MISS);
+            return Ok(None);
+        };
+
+        for dependency in &cached.dependencies {
+            let table = catalog_manager
+                .table(&dependency.catalog, &dependency.schema, &dependency.name)
+                .await
+                .context(CatalogSnafu)?;
+            if !dependency.matches(table) {
+                self.plans.invalidate(key).await;
+                increment_counter!(METRIC_PLAN_CACHE_MISS);
+                return Ok(None);
+            }
+        }
+        increment_counter!(METRIC_PLAN_CACHE_HIT);
+        Ok(Some(cached.plan.clone()))
+    }
+
+    /// Caches the plan planned in the catalog and schema of the key.
+    pub async fn insert(&self, key: PlanCacheKey, plan: LogicalPlan) {
+        let mut dependencies = Vec::new();
+        let LogicalPlan::DfPlan(df_plan) = &plan;
+        if collect_dependencies(df_plan, &key.catalog, &key.schema, &mut dependencies).is_none() {
+            return;
+        }
+        self.plans
+            .insert(key, Arc::new(CachedPlan { plan, dependencies }))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::datasource::{provider_as_source, DefaultTableSource};
+    use datafusion_expr::LogicalPlanBuilder;
+    use datatypes::prelude::VectorRef;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{Float64Vector, StringVector};
+    use promql_parser::parser;
+    use session::context::QueryContext;
+    use table::table::numbers::{NumbersTable, NUMBERS_TABLE_NAME};
+
+    use super::*;
+
+    fn new_eval_stmt(start: i64, end: i64, step: i64) -> EvalStmt {
+        EvalStmt {
+            expr: parser::parse("some_metric").unwrap(),
+            start: from_millis(start),
+            end: from_millis(end),
+            interval: Duration::from_millis(step as u64),
+            lookback_delta: Duration::from_secs(300),
+        }
+    }
+
+    fn new_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new("value", ConcreteDataType::float64_datatype(), true),
+        ]))
+    }
+
+    /// Rows of `hosts` at each step of the range.
+    fn new_result(range: &StepRange, from: i64, hosts: &[&str]) -> RecordBatches {
+        let mut host_column = vec![];
+        let mut ts_column = vec![];
+        let mut value_column = vec![];
+        for host in hosts {
+            for timestamp in range.steps_from(from) {
+                host_column.push(host.to_string());
+                ts_column.push(timestamp);
+                value_column.push(timestamp as f64);
+            }
+        }
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(host_column)),
+            Arc::new(TimestampMillisecondVector::from_vec(ts_column)),
+            Arc::new(Float64Vector::from_vec(value_column)),
+        ];
+        let schema = new_schema();
+        let batch = RecordBatch::new(schema.clone(), columns).unwrap();
+        RecordBatches::try_new(schema, vec![batch]).unwrap()
+    }
+
+    fn num_rows(batches: &RecordBatches) -> usize {
+        batches.iter().map(|batch| batch.num_rows()).sum()
+    }
+
+    #[test]
+    fn test_query_cache_options() {
+        let opts: QueryCacheOptions = toml::from_str(
+            r#"
+            enable = true
+            max_age = "5m"
+            "#,
+        )
+        .unwrap();
+        assert!(opts.enable);
+        assert_eq!(Duration::from_secs(300), opts.max_age);
+        assert_eq!(Duration::from_secs(60), opts.freshness_window);
+        assert_eq!(1024, opts.promql_capacity);
+        assert_eq!(1024, opts.plan_capacity);
+    }
+
+    #[test]
+    fn test_step_range() {
+        let range = StepRange::try_new(&new_eval_stmt(60_000, 120_000, 15_000)).unwrap();
+        assert_eq!(
+            vec![60_000, 75_000, 90_000, 105_000, 120_000],
+            range.steps_from(range.start).collect::<Vec<_>>()
+        );
+        assert!(range.contains_step(75_000));
+        assert!(!range.contains_step(80_000));
+        assert!(!range.contains_step(135_000));
+
+        // Not aligned to the step.
+        assert!(StepRange::try_new(&new_eval_stmt(61_000, 120_000, 15_000)).is_none());
+        // Instant query.
+        assert!(StepRange::try_new(&new_eval_stmt(60_000, 60_000, 15_000)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prom_query_cache() {
+        let cache = PromQueryCache::new(&QueryCacheOptions {
+            enable: true,
+            ..Default::default()
+        });
+        let query_ctx = QueryContext::arc();
+        let stmt = new_eval_stmt(0, 60_000, 15_000);
+        let key = PromCacheKey::new(&stmt, "some_metric", &query_ctx);
+        let range = StepRange::try_new(&stmt).unwrap();
+        assert!(cache.get_prefix(&key, &range).is_none());
+
+        let result = new_result(&range, range.start, &["host1", "host2"]);
+        let output = cache
+            .put(key.clone(), &range, None, result)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(10, num_rows(&output));
+
+        // The range moves forward by 2 steps, only the last 2 steps are evaluated.
+        let range = StepRange::try_new(&new_eval_stmt(30_000, 90_000, 15_000)).unwrap();
+        let prefix = cache.get_prefix(&key, &range).unwrap();
+        assert_eq!(75_000, prefix.tail_start());
+        assert_eq!(6, num_rows(&prefix.batches().unwrap()));
+
+        let tail = new_result(&range, prefix.tail_start(), &["host1", "host2"]);
+        let output = cache
+            .put(key.clone(), &range, Some(prefix), tail)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(10, num_rows(&output));
+
+        // All steps are cached now.
+        let prefix = cache.get_prefix(&key, &range).unwrap();
+        assert!(prefix.tail_start() > range.end);
+        assert_eq!(10, num_rows(&prefix.batches().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_prom_query_cache_schema_changed() {
+        let cache = PromQueryCache::new(&QueryCacheOptions::default());
+        let query_ctx = QueryContext::arc();
+        let stmt = new_eval_stmt(0, 60_000, 15_000);
+        let key = PromCacheKey::new(&stmt, "some_metric", &query_ctx);
+        let range = StepRange::try_new(&stmt).unwrap();
+        let result = new_result(&range, range.start, &["host1"]);
+        let _ = cache.put(key.clone(), &range, None, result).await.unwrap();
+
+        let range = StepRange::try_new(&new_eval_stmt(15_000, 75_000, 15_000)).unwrap();
+        let prefix = cache.get_prefix(&key, &range).unwrap();
+        let tail = RecordBatches::empty();
+        assert!(cache
+            .put(key.clone(), &range, Some(prefix), tail)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(cache.get_prefix(&key, &range).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prom_query_cache_expired() {
+        let cache = PromQueryCache::new(&QueryCacheOptions {
+            max_age: Duration::from_millis(100),
+            ..Default::default()
+        });
+        let query_ctx = QueryContext::arc();
+        let stmt = new_eval_stmt(0, 60_000, 15_000);
+        let key = PromCacheKey::new(&stmt, "some_metric", &query_ctx);
+        let range = StepRange::try_new(&stmt).unwrap();
+        let result = new_result(&range, range.start, &["host1"]);
+        let _ = cache.put(key.clone(), &range, None, result).await.unwrap();
+        assert!(cache.get_prefix(&key, &range).is_some());
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(cache.get_prefix(&key, &range).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prom_query_cache_fresh_steps() {
+        let cache = PromQueryCache::new(&QueryCacheOptions::default());
+        let query_ctx = QueryContext::arc();
+        // The lookback of the statement is 5m, and the freshness window is 1m.
+        let start = (current_time_millis() - 600_000) / 60_000 * 60_000;
+        let stmt = new_eval_stmt(start, start + 540_000, 60_000);
+        let key = PromCacheKey::new(&stmt, "some_metric", &query_ctx);
+        let range = StepRange::try_new(&stmt).unwrap();
+        let result = new_result(&range, range.start, &["host1"]);
+        let output = cache
+            .put(key.clone(), &range, None, result)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(10, num_rows(&output));
+
+        // Only the steps not later than 6m ago are cached, so the first step not
+        // cached is at most 5m ago.
+        let prefix = cache.get_prefix(&key, &range).unwrap();
+        assert!(prefix.tail_start() <= current_time_millis() - 300_000);
+        assert!(prefix.tail_start() < range.end);
+    }
+
+    #[test]
+    fn test_plan_dependencies() {
+        let table: TableRef = Arc::new(NumbersTable::new(1));
+        let adapter = Arc::new(DfTableProviderAdapter::new(table.clone()));
+        let scan = LogicalPlanBuilder::scan("numbers", provider_as_source(adapter), None)
+            .unwrap()
+            .build()
+            .unwrap();
+        let view = ViewTable::try_new(scan, Some("SELECT * FROM numbers".to_string())).unwrap();
+        let plan =
+            LogicalPlanBuilder::scan("v", Arc::new(DefaultTableSource::new(Arc::new(view))), None)
+                .unwrap()
+                .build()
+                .unwrap();
+
+        let mut dependencies = Vec::new();
+        collect_dependencies(&plan, "greptime", "public", &mut dependencies).unwrap();
+        assert_eq!(2, dependencies.len());
+        assert_eq!(
+            ("greptime", "public", "v"),
+            (
+                dependencies[0].catalog.as_str(),
+                dependencies[0].schema.as_str(),
+                dependencies[0].name.as_str()
+            )
+        );
+        assert_eq!(
+            DependencyKind::View("SELECT * FROM numbers".to_string()),
+            dependencies[0].kind
+        );
+
+        let numbers = &dependencies[1];
+        assert_eq!(NUMBERS_TABLE_NAME, numbers.name);
+        assert!(numbers.matches(Some(table)));
+        // The table is dropped or recreated.
+        assert!(!numbers.matches(None));
+        assert!(!numbers.matches(Some(Arc::new(NumbersTable::new(2)))));
+    }
+}
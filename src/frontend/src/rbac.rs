@@ -0,0 +1,741 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Role-based access control.
+//!
+//! Users, roles and their privileges are persisted in two system tables,
+//! `users` and `privileges`, and managed by `CREATE USER`, `CREATE ROLE`,
+//! `DROP USER`, `DROP ROLE`, `GRANT` and `REVOKE` statements. Only superusers,
+//! which are configured in the same format as the static user provider, are
+//! allowed to execute these statements, and they have all privileges on all
+//! tables.
+//!
+//! The system tables are in the internal schema [INTERNAL_SCHEMA_NAME], which
+//! is hidden from `SHOW DATABASES`. Tables in the internal schema are only
+//! accessible to superusers, whatever privileges are granted.
+//!
+//! Passwords are stored as `SHA1(SHA1(password))` without salt, since the MySQL
+//! native password authentication requires the server to keep exactly this
+//! hash. That's why the users table must never be readable by other users.
+//!
+//! A user has a privilege on a table if the privilege is granted on the table,
+//! on its database or on all databases (`*.*`), to the user or to any role
+//! granted to the user. Anonymous requests, i.e. those without authentication
+//! such as the requests of the OpenTSDB, Graphite and StatsD TCP servers, have
+//! no privilege at all.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use catalog::process_manager::SuperuserChecker;
+use catalog::{CatalogManagerRef, RegisterSystemTableRequest};
+use common_catalog::consts::{
+    DEFAULT_CATALOG_NAME, INTERNAL_SCHEMA_NAME, MITO_ENGINE, PRIVILEGES_TABLE_ID, USERS_TABLE_ID,
+};
+use common_error::prelude::BoxedError;
+use common_recordbatch::{util as record_util, RecordBatch};
+use common_telemetry::logging;
+use common_time::util;
+use datatypes::prelude::{ConcreteDataType, ScalarVector};
+use datatypes::schema::{ColumnSchema, RawSchema};
+use datatypes::vectors::{BinaryVector, StringVector, TimestampMillisecondVector, VectorRef};
+use servers::auth::user_provider::{auth_with_password_hash, hash_password, StaticUserProvider};
+use servers::auth::{
+    AuthBackendSnafu, Identity, Password, PermissionDeniedSnafu, Privilege, UserNotFoundSnafu,
+    UserProvider,
+};
+use session::context::UserInfo;
+use snafu::{ensure, OptionExt, ResultExt};
+use store_api::storage::ScanRequest;
+use table::requests::{CreateTableRequest, DeleteRequest, InsertRequest, TableOptions};
+use table::TableRef;
+
+use crate::error::{
+    CatalogSnafu, CollectRecordbatchSnafu, InvalidSystemTableColumnSnafu, PrincipalExistsSnafu,
+    PrincipalNotFoundSnafu, Result, TableNotFoundSnafu, TableSnafu,
+};
+
+pub const RBAC_USER_PROVIDER: &str = "rbac_user_provider";
+
+pub const USERS_TABLE_NAME: &str = "users";
+pub const PRIVILEGES_TABLE_NAME: &str = "privileges";
+
+/// Cached users and privileges are reloaded after this duration, so that changes
+/// made by other frontends take effect.
+const CACHE_TTL: Duration = Duration::from_secs(10);
+
+const KIND_USER: &str = "user";
+const KIND_ROLE: &str = "role";
+/// Role memberships are stored in the privileges table with this privilege,
+/// with the role name as object.
+const PRIVILEGE_ROLE: &str = "ROLE";
+const ALL_OBJECTS: &str = "*";
+
+/// Formats the object a privilege is granted on: `*` for all tables,
+/// `catalog.schema.*` for all tables in a schema or `catalog.schema.table`.
+pub fn privilege_object(catalog: &str, schema: &str, table: Option<&str>) -> String {
+    format!("{catalog}.{schema}.{}", table.unwrap_or(ALL_OBJECTS))
+}
+
+#[derive(Debug, Clone)]
+struct Principal {
+    is_role: bool,
+    password_hash: Vec<u8>,
+}
+
+#[derive(Debug, Default)]
+struct RbacState {
+    principals: HashMap<String, Principal>,
+    /// Grantee to its `(privilege, object)`s.
+    grants: HashMap<String, HashSet<(String, String)>>,
+    loaded_at: Option<Instant>,
+}
+
+impl RbacState {
+    fn is_expired(&self) -> bool {
+        self.loaded_at
+            .map(|loaded_at| loaded_at.elapsed() >= CACHE_TTL)
+            .unwrap_or(true)
+    }
+
+    fn has_privilege(
+        &self,
+        username: &str,
+        privilege: Privilege,
+        catalog: &str,
+        schema: &str,
+        table: Option<&str>,
+    ) -> bool {
+        let mut objects = vec![
+            ALL_OBJECTS.to_string(),
+            privilege_object(catalog, schema, None),
+        ];
+        if table.is_some() {
+            objects.push(privilege_object(catalog, schema, table));
+        }
+
+        let Some(grants) = self.grants.get(username) else { return false };
+        let roles = grants
+            .iter()
+            .filter(|(p, _)| p == PRIVILEGE_ROLE)
+            .filter_map(|(_, role)| self.grants.get(role));
+
+        std::iter::once(grants).chain(roles).any(|grants| {
+            objects
+                .iter()
+                .any(|object| grants.contains(&(privilege.to_string(), object.clone())))
+        })
+    }
+}
+
+/// A [UserProvider] that authenticates users and checks their privileges by
+/// users, roles and privileges persisted in system tables.
+pub struct RbacUserProvider {
+    catalog_manager: CatalogManagerRef,
+    superusers: StaticUserProvider,
+    state: RwLock<Arc<RbacState>>,
+}
+
+impl RbacUserProvider {
+    pub async fn try_new(
+        catalog_manager: CatalogManagerRef,
+        superusers: StaticUserProvider,
+    ) -> Result<Self> {
+        for (table_id, table_name, schema, primary_key_indices) in [
+            (
+                USERS_TABLE_ID,
+                USERS_TABLE_NAME,
+                build_users_schema(),
+                vec![0],
+            ),
+            (
+                PRIVILEGES_TABLE_ID,
+                PRIVILEGES_TABLE_NAME,
+                build_privileges_schema(),
+                vec![0, 1, 2],
+            ),
+        ] {
+            let request = CreateTableRequest {
+                id: table_id,
+                catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+                schema_name: INTERNAL_SCHEMA_NAME.to_string(),
+                table_name: table_name.to_string(),
+                desc: Some(format!("{table_name} table")),
+                schema,
+                region_numbers: vec![0],
+                primary_key_indices,
+                create_if_not_exists: true,
+                table_options: TableOptions::default(),
+                engine: MITO_ENGINE.to_string(),
+            };
+            catalog_manager
+                .register_system_table(RegisterSystemTableRequest {
+                    create_table_request: request,
+                    open_hook: None,
+                })
+                .await
+                .context(CatalogSnafu)?;
+        }
+
+        Ok(Self {
+            catalog_manager,
+            superusers,
+            state: RwLock::new(Arc::new(RbacState::default())),
+        })
+    }
+
+    pub fn is_superuser(&self, user_info: &UserInfo) -> bool {
+        !user_info.is_anonymous() && self.superusers.has_user(user_info.username())
+    }
+
+    /// Ensures the user is a superuser, who is allowed to manage users and roles.
+    pub fn ensure_superuser(&self, user_info: &UserInfo) -> servers::auth::Result<()> {
+        ensure!(
+            self.is_superuser(user_info),
+            PermissionDeniedSnafu {
+                username: user_info.username(),
+                privilege: "SUPERUSER",
+                object: ALL_OBJECTS,
+            }
+        );
+        Ok(())
+    }
+
+    /// Creates a user or, if `password` is `None`, a role.
+    /// Returns false if it already exists and `if_not_exists` is true.
+    pub async fn create_principal(
+        &self,
+        name: &str,
+        password: Option<&str>,
+        if_not_exists: bool,
+    ) -> Result<bool> {
+        let state = self.reload().await?;
+        if self.superusers.has_user(name) || state.principals.contains_key(name) {
+            ensure!(if_not_exists, PrincipalExistsSnafu { name });
+            return Ok(false);
+        }
+
+        let (kind, password_hash) = match password {
+            Some(password) => (KIND_USER, hash_password(password.as_bytes())),
+            None => (KIND_ROLE, vec![]),
+        };
+        let now = util::current_time_millis();
+        let columns_values = HashMap::from([
+            (
+                "name".to_string(),
+                Arc::new(StringVector::from(vec![name])) as VectorRef,
+            ),
+            (
+                "kind".to_string(),
+                Arc::new(StringVector::from(vec![kind])) as VectorRef,
+            ),
+            (
+                "password".to_string(),
+                Arc::new(BinaryVector::from(vec![password_hash])) as VectorRef,
+            ),
+            (
+                "timestamp".to_string(),
+                // Timestamp in key part is intentionally left to 0
+                Arc::new(TimestampMillisecondVector::from_slice([0])) as VectorRef,
+            ),
+            (
+                "gmt_created".to_string(),
+                Arc::new(TimestampMillisecondVector::from_slice([now])) as VectorRef,
+            ),
+        ]);
+        self.insert(USERS_TABLE_NAME, columns_values).await?;
+        logging::info!("Created {kind}: {name}");
+
+        let _ = self.reload().await?;
+        Ok(true)
+    }
+
+    /// Drops a user or role, with all its privileges and role memberships.
+    /// Returns false if it doesn't exist and `if_exists` is true.
+    pub async fn drop_principal(&self, name: &str, is_role: bool, if_exists: bool) -> Result<bool> {
+        let state = self.reload().await?;
+        if !state
+            .principals
+            .get(name)
+            .map(|p| p.is_role == is_role)
+            .unwrap_or(false)
+        {
+            ensure!(if_exists, PrincipalNotFoundSnafu { name });
+            return Ok(false);
+        }
+
+        let key_column_values = HashMap::from([
+            (
+                "name".to_string(),
+                Arc::new(StringVector::from(vec![name])) as VectorRef,
+            ),
+            (
+                "timestamp".to_string(),
+                Arc::new(TimestampMillisecondVector::from_slice([0])) as VectorRef,
+            ),
+        ]);
+        self.delete(USERS_TABLE_NAME, key_column_values).await?;
+
+        let mut grants = state
+            .grants
+            .get(name)
+            .map(|grants| {
+                grants
+                    .iter()
+                    .map(|(privilege, object)| (name, privilege.as_str(), object.as_str()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if is_role {
+            for (grantee, grantee_grants) in &state.grants {
+                if grantee_grants.contains(&(PRIVILEGE_ROLE.to_string(), name.to_string())) {
+                    grants.push((grantee.as_str(), PRIVILEGE_ROLE, name));
+                }
+            }
+        }
+        for (grantee, privilege, object) in grants {
+            self.delete_grant(grantee, privilege, object).await?;
+        }
+        logging::info!(
+            "Dropped {}: {name}",
+            if is_role { KIND_ROLE } else { KIND_USER }
+        );
+
+        let _ = self.reload().await?;
+        Ok(true)
+    }
+
+    /// Grants privileges on `object`, see [privilege_object], to a user or role.
+    pub async fn grant(&self, grantee: &str, privileges: &[Privilege], object: &str) -> Result<()> {
+        let state = self.reload().await?;
+        ensure!(
+            state.principals.contains_key(grantee),
+            PrincipalNotFoundSnafu { name: grantee }
+        );
+
+        for privilege in privileges {
+            self.insert_grant(grantee, privilege.as_str(), object)
+                .await?;
+        }
+
+        let _ = self.reload().await?;
+        Ok(())
+    }
+
+    pub async fn revoke(
+        &self,
+        grantee: &str,
+        privileges: &[Privilege],
+        object: &str,
+    ) -> Result<()> {
+        for privilege in privileges {
+            self.delete_grant(grantee, privilege.as_str(), object)
+                .await?;
+        }
+
+        let _ = self.reload().await?;
+        Ok(())
+    }
+
+    /// Grants a role to a user. Granting roles to roles is not supported.
+    pub async fn grant_role(&self, grantee: &str, role: &str) -> Result<()> {
+        let state = self.reload().await?;
+        ensure!(
+            state
+                .principals
+                .get(role)
+                .map(|p| p.is_role)
+                .unwrap_or(false),
+            PrincipalNotFoundSnafu { name: role }
+        );
+        ensure!(
+            state
+                .principals
+                .get(grantee)
+                .map(|p| !p.is_role)
+                .unwrap_or(false),
+            PrincipalNotFoundSnafu { name: grantee }
+        );
+
+        self.insert_grant(grantee, PRIVILEGE_ROLE, role).await?;
+
+        let _ = self.reload().await?;
+        Ok(())
+    }
+
+    pub async fn revoke_role(&self, grantee: &str, role: &str) -> Result<()> {
+        self.delete_grant(grantee, PRIVILEGE_ROLE, role).await?;
+
+        let _ = self.reload().await?;
+        Ok(())
+    }
+
+    async fn state(&self) -> Result<Arc<RbacState>> {
+        let state = self.state.read().unwrap().clone();
+        if state.is_expired() {
+            self.reload().await
+        } else {
+            Ok(state)
+        }
+    }
+
+    async fn reload(&self) -> Result<Arc<RbacState>> {
+        let state = Arc::new(self.load().await?);
+        *self.state.write().unwrap() = state.clone();
+        Ok(state)
+    }
+
+    async fn load(&self) -> Result<RbacState> {
+        let mut state = RbacState {
+            loaded_at: Some(Instant::now()),
+            ..Default::default()
+        };
+
+        // System tables may not be created yet before the catalog manager starts.
+        for batch in self.scan(USERS_TABLE_NAME).await? {
+            let names = string_column(&batch, USERS_TABLE_NAME, "name")?;
+            let kinds = string_column(&batch, USERS_TABLE_NAME, "kind")?;
+            let passwords = binary_column(&batch, USERS_TABLE_NAME, "password")?;
+            for i in 0..batch.num_rows() {
+                let (Some(name), Some(kind)) = (names.get_data(i), kinds.get_data(i)) else { continue };
+                let _ = state.principals.insert(
+                    name.to_string(),
+                    Principal {
+                        is_role: kind == KIND_ROLE,
+                        password_hash: passwords.get_data(i).unwrap_or_default().to_vec(),
+                    },
+                );
+            }
+        }
+
+        for batch in self.scan(PRIVILEGES_TABLE_NAME).await? {
+            let grantees = string_column(&batch, PRIVILEGES_TABLE_NAME, "grantee")?;
+            let privileges = string_column(&batch, PRIVILEGES_TABLE_NAME, "privilege")?;
+            let objects = string_column(&batch, PRIVILEGES_TABLE_NAME, "object")?;
+            for i in 0..batch.num_rows() {
+                let (Some(grantee), Some(privilege), Some(object)) =
+                    (grantees.get_data(i), privileges.get_data(i), objects.get_data(i)) else { continue };
+                let _ = state
+                    .grants
+                    .entry(grantee.to_string())
+                    .or_default()
+                    .insert((privilege.to_string(), object.to_string()));
+            }
+        }
+
+        Ok(state)
+    }
+
+    async fn find_table(&self, table_name: &str) -> Result<Option<TableRef>> {
+        self.catalog_manager
+            .table(DEFAULT_CATALOG_NAME, INTERNAL_SCHEMA_NAME, table_name)
+            .await
+            .context(CatalogSnafu)
+    }
+
+    async fn table(&self, table_name: &str) -> Result<TableRef> {
+        self.find_table(table_name)
+            .await?
+            .with_context(|| TableNotFoundSnafu { table_name })
+    }
+
+    async fn scan(&self, table_name: &str) -> Result<Vec<RecordBatch>> {
+        let Some(table) = self.find_table(table_name).await? else { return Ok(vec![]) };
+        let stream = table
+            .scan_to_stream(ScanRequest::default())
+            .await
+            .context(TableSnafu)?;
+        record_util::collect(stream)
+            .await
+            .context(CollectRecordbatchSnafu)
+    }
+
+    async fn insert(
+        &self,
+        table_name: &str,
+        columns_values: HashMap<String, VectorRef>,
+    ) -> Result<()> {
+        let _ = self
+            .table(table_name)
+            .await?
+            .insert(InsertRequest {
+                catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+                schema_name: INTERNAL_SCHEMA_NAME.to_string(),
+                table_name: table_name.to_string(),
+                columns_values,
+                region_number: 0,
+            })
+            .await
+            .context(TableSnafu)?;
+        Ok(())
+    }
+
+    async fn delete(
+        &self,
+        table_name: &str,
+        key_column_values: HashMap<String, VectorRef>,
+    ) -> Result<()> {
+        let _ = self
+            .table(table_name)
+            .await?
+            .delete(DeleteRequest { key_column_values })
+            .await
+            .context(TableSnafu)?;
+        Ok(())
+    }
+
+    async fn insert_grant(&self, grantee: &str, privilege: &str, object: &str) -> Result<()> {
+        let mut columns_values = grant_key(grantee, privilege, object);
+        let _ = columns_values.insert(
+            "gmt_created".to_string(),
+            Arc::new(TimestampMillisecondVector::from_slice([
+                util::current_time_millis(),
+            ])) as VectorRef,
+        );
+        self.insert(PRIVILEGES_TABLE_NAME, columns_values).await
+    }
+
+    async fn delete_grant(&self, grantee: &str, privilege: &str, object: &str) -> Result<()> {
+        self.delete(PRIVILEGES_TABLE_NAME, grant_key(grantee, privilege, object))
+            .await
+    }
+}
+
+#[async_trait]
+impl UserProvider for RbacUserProvider {
+    fn name(&self) -> &str {
+        RBAC_USER_PROVIDER
+    }
+
+    async fn authenticate(
+        &self,
+        id: Identity<'_>,
+        password: Password<'_>,
+    ) -> servers::auth::Result<UserInfo> {
+        let Identity::UserId(username, _) = id.clone();
+        if self.superusers.has_user(username) {
+            return self.superusers.authenticate(id, password).await;
+        }
+
+        let state = self
+            .state()
+            .await
+            .map_err(BoxedError::new)
+            .context(AuthBackendSnafu)?;
+        let principal = state
+            .principals
+            .get(username)
+            .filter(|p| !p.is_role)
+            .context(UserNotFoundSnafu { username })?;
+        auth_with_password_hash(username, password, &principal.password_hash)?;
+
+        Ok(UserInfo::new(username))
+    }
+
+    async fn authorize(
+        &self,
+        _catalog: &str,
+        _schema: &str,
+        _user_info: &UserInfo,
+    ) -> servers::auth::Result<()> {
+        // Privileges are checked on tables.
+        Ok(())
+    }
+
+    async fn authorize_privilege(
+        &self,
+        user_info: &UserInfo,
+        privilege: Privilege,
+        catalog: &str,
+        schema: &str,
+        table: Option<&str>,
+    ) -> servers::auth::Result<()> {
+        if self.is_superuser(user_info) {
+            return Ok(());
+        }
+
+        let username = user_info.username();
+        let granted = if user_info.is_anonymous() || schema == INTERNAL_SCHEMA_NAME {
+            false
+        } else {
+            let state = self
+                .state()
+                .await
+                .map_err(BoxedError::new)
+                .context(AuthBackendSnafu)?;
+            state.has_privilege(username, privilege, catalog, schema, table)
+        };
+        ensure!(
+            granted,
+            PermissionDeniedSnafu {
+                username,
+                privilege: privilege.to_string(),
+                object: privilege_object(catalog, schema, table),
+            }
+        );
+        Ok(())
+    }
+}
+
+impl SuperuserChecker for RbacUserProvider {
+    fn is_superuser(&self, user_info: &UserInfo) -> bool {
+        RbacUserProvider::is_superuser(self, user_info)
+    }
+}
+
+fn grant_key(grantee: &str, privilege: &str, object: &str) -> HashMap<String, VectorRef> {
+    HashMap::from([
+        (
+            "grantee".to_string(),
+            Arc::new(StringVector::from(vec![grantee])) as VectorRef,
+        ),
+        (
+            "privilege".to_string(),
+            Arc::new(StringVector::from(vec![privilege])) as VectorRef,
+        ),
+        (
+            "object".to_string(),
+            Arc::new(StringVector::from(vec![object])) as VectorRef,
+        ),
+        (
+            "timestamp".to_string(),
+            // Timestamp in key part is intentionally left to 0
+            Arc::new(TimestampMillisecondVector::from_slice([0])) as VectorRef,
+        ),
+    ])
+}
+
+fn string_column<'a>(
+    batch: &'a RecordBatch,
+    table_name: &str,
+    column: &str,
+) -> Result<&'a StringVector> {
+    batch
+        .column_by_name(column)
+        .and_then(|c| c.as_any().downcast_ref::<StringVector>())
+        .with_context(|| InvalidSystemTableColumnSnafu { table_name, column })
+}
+
+fn binary_column<'a>(
+    batch: &'a RecordBatch,
+    table_name: &str,
+    column: &str,
+) -> Result<&'a BinaryVector> {
+    batch
+        .column_by_name(column)
+        .and_then(|c| c.as_any().downcast_ref::<BinaryVector>())
+        .with_context(|| InvalidSystemTableColumnSnafu { table_name, column })
+}
+
+fn build_users_schema() -> RawSchema {
+    RawSchema::new(vec![
+        ColumnSchema::new("name", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("kind", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("password", ConcreteDataType::binary_datatype(), false),
+        ColumnSchema::new(
+            "timestamp",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )
+        .with_time_index(true),
+        ColumnSchema::new(
+            "gmt_created",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        ),
+    ])
+}
+
+fn build_privileges_schema() -> RawSchema {
+    RawSchema::new(vec![
+        ColumnSchema::new("grantee", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("privilege", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("object", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new(
+            "timestamp",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )
+        .with_time_index(true),
+        ColumnSchema::new(
+            "gmt_created",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grants(entries: &[(&str, &str)]) -> HashSet<(String, String)> {
+        entries
+            .iter()
+            .map(|(p, o)| (p.to_string(), o.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_has_privilege() {
+        let mut state = RbacState::default();
+        let _ = state.grants.insert(
+            "alice".to_string(),
+            grants(&[
+                ("READ", "greptime.public.*"),
+                ("WRITE", "greptime.public.cpu"),
+                ("ROLE", "admin"),
+            ]),
+        );
+        let _ = state
+            .grants
+            .insert("admin".to_string(), grants(&[("DDL", "*")]));
+        let _ = state
+            .grants
+            .insert("bob".to_string(), grants(&[("READ", "greptime.test.mem")]));
+
+        let check = |user, privilege, schema, table| {
+            state.has_privilege(user, privilege, "greptime", schema, table)
+        };
+
+        assert!(check("alice", Privilege::Read, "public", Some("cpu")));
+        assert!(check("alice", Privilege::Read, "public", None));
+        assert!(!check("alice", Privilege::Read, "test", Some("cpu")));
+        assert!(check("alice", Privilege::Write, "public", Some("cpu")));
+        assert!(!check("alice", Privilege::Write, "public", Some("mem")));
+        // granted by role
+        assert!(check("alice", Privilege::Ddl, "test", Some("mem")));
+        assert!(check("alice", Privilege::Ddl, "test", None));
+
+        assert!(check("bob", Privilege::Read, "test", Some("mem")));
+        assert!(!check("bob", Privilege::Read, "test", None));
+        assert!(!check("bob", Privilege::Ddl, "test", Some("mem")));
+
+        assert!(!check("carol", Privilege::Read, "public", Some("cpu")));
+    }
+
+    #[test]
+    fn test_privilege_object() {
+        assert_eq!(
+            "greptime.public.cpu",
+            privilege_object("greptime", "public", Some("cpu"))
+        );
+        assert_eq!(
+            "greptime.public.*",
+            privilege_object("greptime", "public", None)
+        );
+    }
+}
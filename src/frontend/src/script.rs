@@ -13,13 +13,21 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::time::Duration;
 
+use catalog::remote::lease::Lease;
 use catalog::CatalogManagerRef;
 use common_query::Output;
 use query::QueryEngineRef;
 
 use crate::error::Result;
 
+/// Name of the lease of running scheduled scripts.
+pub const SCRIPT_RUN_LEASE_NAME: &str = "script_schedule_run";
+/// The lease is renewed each time the schedules are checked, so it only expires
+/// when its holder is down.
+pub const SCRIPT_RUN_LEASE_TTL: Duration = Duration::from_secs(30);
+
 #[cfg(not(feature = "python"))]
 mod dummy {
     use super::*;
@@ -30,6 +38,7 @@ mod dummy {
         pub async fn new(
             _catalog_manager: CatalogManagerRef,
             _query_engine: QueryEngineRef,
+            _run_lease: Option<Lease>,
         ) -> Result<Self> {
             Ok(Self {})
         }
@@ -51,34 +60,67 @@ mod dummy {
         ) -> servers::error::Result<Output> {
             servers::error::NotSupportedSnafu { feat: "script" }.fail()
         }
+
+        pub async fn schedule_script(
+            &self,
+            _schema: &str,
+            _name: &str,
+            _schedule: Option<&str>,
+            _target_table: Option<&str>,
+        ) -> servers::error::Result<()> {
+            servers::error::NotSupportedSnafu { feat: "script" }.fail()
+        }
+
+        pub fn start(&self) {}
     }
 }
 
 #[cfg(feature = "python")]
 mod python {
+    use std::sync::Arc;
+
     use common_error::prelude::BoxedError;
     use common_telemetry::logging::error;
     use script::manager::ScriptManager;
+    use script::scheduler::ScriptScheduler;
     use snafu::ResultExt;
 
     use super::*;
 
     pub struct ScriptExecutor {
-        script_manager: ScriptManager,
+        script_manager: Arc<ScriptManager>,
+        scheduler: Arc<ScriptScheduler>,
     }
 
     impl ScriptExecutor {
+        /// Creates the executor, whose scheduled scripts only run when it holds
+        /// the `run_lease`, or always if there's no lease.
         pub async fn new(
             catalog_manager: CatalogManagerRef,
             query_engine: QueryEngineRef,
+            run_lease: Option<Lease>,
         ) -> Result<Self> {
-            Ok(Self {
-                script_manager: ScriptManager::new(catalog_manager, query_engine)
+            let script_manager = Arc::new(
+                ScriptManager::new(catalog_manager.clone(), query_engine)
+                    .await
+                    .context(crate::error::StartScriptManagerSnafu)?,
+            );
+            let scheduler = Arc::new(
+                ScriptScheduler::try_new(catalog_manager, script_manager.clone(), run_lease)
                     .await
                     .context(crate::error::StartScriptManagerSnafu)?,
+            );
+            Ok(Self {
+                script_manager,
+                scheduler,
             })
         }
 
+        /// Starts running scheduled scripts in the background.
+        pub fn start(&self) {
+            self.scheduler.start();
+        }
+
         pub async fn insert_script(
             &self,
             schema: &str,
@@ -113,6 +155,34 @@ mod python {
                 })
                 .context(servers::error::ExecuteScriptSnafu { name })
         }
+
+        pub async fn schedule_script(
+            &self,
+            schema: &str,
+            name: &str,
+            schedule: Option<&str>,
+            target_table: Option<&str>,
+        ) -> servers::error::Result<()> {
+            let result = match (schedule, target_table) {
+                (Some(schedule), Some(target_table)) => {
+                    self.scheduler
+                        .schedule(schema, name, schedule, target_table)
+                        .await
+                }
+                (Some(schedule), None) => script::error::InvalidScheduleSnafu {
+                    schedule,
+                    reason: "missing target table",
+                }
+                .fail(),
+                (None, _) => self.scheduler.unschedule(schema, name).await,
+            };
+            result
+                .map_err(|e| {
+                    error!(e; "Instance failed to schedule script");
+                    BoxedError::new(e)
+                })
+                .context(servers::error::ScheduleScriptSnafu { name })
+        }
     }
 }
 
@@ -22,6 +22,8 @@ use common_telemetry::info;
 use servers::auth::UserProviderRef;
 use servers::configurator::ConfiguratorRef;
 use servers::error::Error::InternalIo;
+use servers::graphite::template::Templates;
+use servers::graphite::{GraphiteProtocol, GraphiteServer};
 use servers::grpc::GrpcServer;
 use servers::http::HttpServerBuilder;
 use servers::metrics_handler::MetricsHandler;
@@ -32,6 +34,7 @@ use servers::prom::PromServer;
 use servers::query_handler::grpc::ServerGrpcQueryHandlerAdaptor;
 use servers::query_handler::sql::ServerSqlQueryHandlerAdaptor;
 use servers::server::Server;
+use servers::statsd::StatsdServer;
 use snafu::ResultExt;
 
 use crate::error::Error::StartServer;
@@ -149,6 +152,66 @@ impl Services {
             set_opentsdb_handler = true;
         }
 
+        if let Some(opts) = &opts.graphite_options {
+            let templates = Arc::new(
+                Templates::try_new(&opts.templates, &opts.separator)
+                    .context(error::StartServerSnafu)?,
+            );
+
+            let io_runtime = Arc::new(
+                RuntimeBuilder::default()
+                    .worker_threads(opts.runtime_size)
+                    .thread_name("graphite-io-handlers")
+                    .build()
+                    .context(error::RuntimeResourceSnafu)?,
+            );
+
+            let addr = parse_addr(&opts.addr)?;
+            let server = GraphiteServer::create_server(
+                instance.clone(),
+                templates.clone(),
+                GraphiteProtocol::Plaintext,
+                io_runtime.clone(),
+            );
+            result.push((server, addr));
+
+            if let Some(pickle_addr) = &opts.pickle_addr {
+                let addr = parse_addr(pickle_addr)?;
+                let server = GraphiteServer::create_server(
+                    instance.clone(),
+                    templates,
+                    GraphiteProtocol::Pickle,
+                    io_runtime,
+                );
+                result.push((server, addr));
+            }
+        }
+
+        if let Some(opts) = &opts.statsd_options {
+            let addr = parse_addr(&opts.addr)?;
+            let templates = Arc::new(
+                Templates::try_new(&opts.templates, &opts.separator)
+                    .context(error::StartServerSnafu)?,
+            );
+
+            let io_runtime = Arc::new(
+                RuntimeBuilder::default()
+                    .worker_threads(opts.runtime_size)
+                    .thread_name("statsd-io-handlers")
+                    .build()
+                    .context(error::RuntimeResourceSnafu)?,
+            );
+
+            let server = StatsdServer::create_server(
+                instance.clone(),
+                templates,
+                opts.flush_interval,
+                opts.percentiles.clone(),
+                io_runtime,
+            );
+            result.push((server, addr));
+        }
+
         if let Some(http_options) = &opts.http_options {
             let http_addr = parse_addr(&http_options.addr)?;
 
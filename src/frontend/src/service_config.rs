@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod graphite;
 pub mod grpc;
 pub mod influxdb;
 pub mod mysql;
@@ -19,7 +20,9 @@ pub mod opentsdb;
 pub mod postgres;
 pub mod prom;
 pub mod prometheus;
+pub mod statsd;
 
+pub use graphite::GraphiteOptions;
 pub use grpc::GrpcOptions;
 pub use influxdb::InfluxdbOptions;
 pub use mysql::MysqlOptions;
@@ -27,3 +30,4 @@ pub use opentsdb::OpentsdbOptions;
 pub use postgres::PostgresOptions;
 pub use prom::PromOptions;
 pub use prometheus::PrometheusOptions;
+pub use statsd::StatsdOptions;
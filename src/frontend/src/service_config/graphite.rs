@@ -0,0 +1,58 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphiteOptions {
+    /// The address of Graphite plaintext protocol server.
+    pub addr: String,
+    /// The address of Graphite pickle protocol server, disabled if not set.
+    pub pickle_addr: Option<String>,
+    pub runtime_size: usize,
+    /// Templates extracting measurement, field and tags from metric paths, in the
+    /// form of `[filter] <template> [tag1=value1,...]`. For example, the filter
+    /// `servers.*` with the template `.host.measurement.field` skips the first part
+    /// of `servers.web01.cpu.idle` and writes field `idle` of measurement `cpu`
+    /// with tag `host=web01`.
+    pub templates: Vec<String>,
+    /// Joins multiple parts of a measurement, field or tag.
+    pub separator: String,
+}
+
+impl Default for GraphiteOptions {
+    fn default() -> Self {
+        Self {
+            addr: "127.0.0.1:2003".to_string(),
+            pickle_addr: Some("127.0.0.1:2004".to_string()),
+            runtime_size: 2,
+            templates: vec![],
+            separator: ".".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GraphiteOptions;
+
+    #[test]
+    fn test_graphite_options() {
+        let default = GraphiteOptions::default();
+        assert_eq!(default.addr, "127.0.0.1:2003".to_string());
+        assert_eq!(default.pickle_addr, Some("127.0.0.1:2004".to_string()));
+        assert_eq!(default.separator, ".".to_string());
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatsdOptions {
+    pub addr: String,
+    pub runtime_size: usize,
+    /// The interval to write aggregated metrics.
+    #[serde(with = "humantime_serde")]
+    pub flush_interval: Duration,
+    /// Percentiles calculated for timers.
+    pub percentiles: Vec<f64>,
+    /// Templates extracting measurement, field and tags from metric names, see
+    /// [GraphiteOptions](crate::service_config::GraphiteOptions).
+    pub templates: Vec<String>,
+    pub separator: String,
+}
+
+impl Default for StatsdOptions {
+    fn default() -> Self {
+        Self {
+            addr: "127.0.0.1:8125".to_string(),
+            runtime_size: 2,
+            flush_interval: Duration::from_secs(10),
+            percentiles: vec![90.0],
+            templates: vec![],
+            separator: ".".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statsd_options() {
+        let default = StatsdOptions::default();
+        assert_eq!(default.addr, "127.0.0.1:8125".to_string());
+        assert_eq!(default.flush_interval, Duration::from_secs(10));
+
+        let opts: StatsdOptions = toml::from_str(
+            r#"
+            flush_interval = "1m"
+            templates = ["measurement.field"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(opts.flush_interval, Duration::from_secs(60));
+        assert_eq!(opts.templates, vec!["measurement.field".to_string()]);
+    }
+}
@@ -0,0 +1,345 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Slow query log.
+//!
+//! Statements, including SQL, TQL and PromQL queries, that take longer than the
+//! configured threshold are logged along with their user, database, duration,
+//! rows returned and, optionally, their physical plans annotated with execution
+//! metrics like `EXPLAIN ANALYZE`. They can also be persisted into the
+//! `slow_queries` system table in the internal schema to be queried by SQL.
+//!
+//! Only statements tracked in the process list are logged, so the slow query
+//! log requires a catalog manager providing a process manager, like the ones
+//! of frontend and standalone. Credentials in the statements are redacted.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use catalog::process_manager::{Process, ProcessId};
+use catalog::{CatalogManagerRef, RegisterSystemTableRequest};
+use common_catalog::consts::{
+    DEFAULT_CATALOG_NAME, INTERNAL_SCHEMA_NAME, MITO_ENGINE, SLOW_QUERIES_TABLE_ID,
+};
+use common_telemetry::logging;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, RawSchema};
+use datatypes::vectors::{StringVector, TimestampMillisecondVector, UInt64Vector, VectorRef};
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt};
+use sql::dialect::GreptimeDbDialect;
+use sql::util::redact_sql;
+use table::requests::{CreateTableRequest, InsertRequest, TableOptions};
+
+use crate::error::{CatalogSnafu, Result, TableNotFoundSnafu, TableSnafu};
+
+pub const SLOW_QUERIES_TABLE_NAME: &str = "slow_queries";
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct SlowQueryOptions {
+    pub enable: bool,
+    /// Statements taking longer than this are logged.
+    #[serde(with = "humantime_serde")]
+    pub threshold: Duration,
+    /// Whether to log the physical plans with execution metrics.
+    pub record_plan: bool,
+    /// Whether to persist slow queries into the `slow_queries` system table in
+    /// the internal schema.
+    pub persist: bool,
+}
+
+impl Default for SlowQueryOptions {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            threshold: Duration::from_secs(5),
+            record_plan: true,
+            persist: false,
+        }
+    }
+}
+
+/// A statement whose execution took longer than the threshold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlowQuery {
+    /// Id of the statement in the process list.
+    pub process_id: ProcessId,
+    pub user: String,
+    pub catalog: String,
+    pub schema: String,
+    pub query: String,
+    /// Start time in milliseconds since the epoch.
+    pub start_time: i64,
+    pub elapsed: Duration,
+    /// Rows affected, or returned to client.
+    pub rows: usize,
+    /// The physical plan with execution metrics, if recorded.
+    pub plan: Option<String>,
+}
+
+impl SlowQuery {
+    pub fn new(process: &Process, rows: usize, plan: Option<String>) -> Self {
+        Self {
+            process_id: process.id(),
+            user: process.user().to_string(),
+            catalog: process.catalog().to_string(),
+            schema: process.schema().to_string(),
+            query: redact_sql(process.query(), &GreptimeDbDialect {}),
+            start_time: process.start_time(),
+            elapsed: process.elapsed(),
+            rows,
+            plan,
+        }
+    }
+}
+
+pub struct SlowQueryLog {
+    threshold: Duration,
+    record_plan: bool,
+    /// Slow queries are persisted via the catalog manager if present.
+    catalog_manager: Option<CatalogManagerRef>,
+}
+
+pub type SlowQueryLogRef = Arc<SlowQueryLog>;
+
+impl SlowQueryLog {
+    pub async fn try_new(
+        opts: &SlowQueryOptions,
+        catalog_manager: CatalogManagerRef,
+    ) -> Result<Self> {
+        let catalog_manager = if opts.persist {
+            // Like the RBAC tables, this table is put into the internal schema,
+            // since the statements of all users are visible in it.
+            let request = CreateTableRequest {
+                id: SLOW_QUERIES_TABLE_ID,
+                catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+                schema_name: INTERNAL_SCHEMA_NAME.to_string(),
+                table_name: SLOW_QUERIES_TABLE_NAME.to_string(),
+                desc: Some("Slow queries table".to_string()),
+                schema: build_slow_queries_schema(),
+                region_numbers: vec![0],
+                // The process id tells apart statements of the same user
+                // starting at the same time.
+                primary_key_indices: vec![0, 1, 2, 3],
+                create_if_not_exists: true,
+                table_options: TableOptions::default(),
+                engine: MITO_ENGINE.to_string(),
+            };
+            catalog_manager
+                .register_system_table(RegisterSystemTableRequest {
+                    create_table_request: request,
+                    open_hook: None,
+                })
+                .await
+                .context(CatalogSnafu)?;
+            Some(catalog_manager)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            threshold: opts.threshold,
+            record_plan: opts.record_plan,
+            catalog_manager,
+        })
+    }
+
+    pub fn is_slow(&self, elapsed: Duration) -> bool {
+        elapsed >= self.threshold
+    }
+
+    pub fn record_plan(&self) -> bool {
+        self.record_plan
+    }
+
+    /// Logs the slow query, and persists it in the background if enabled.
+    pub fn record(&self, slow_query: SlowQuery) {
+        logging::warn!(
+            "Slow query, user: {}, db: {}-{}, elapsed: {:?}, rows: {}, query: {}{}",
+            slow_query.user,
+            slow_query.catalog,
+            slow_query.schema,
+            slow_query.elapsed,
+            slow_query.rows,
+            slow_query.query,
+            slow_query
+                .plan
+                .as_ref()
+                .map(|plan| format!(", plan:\n{plan}"))
+                .unwrap_or_default(),
+        );
+
+        if let Some(catalog_manager) = &self.catalog_manager {
+            let catalog_manager = catalog_manager.clone();
+            let _handle = common_runtime::spawn_bg(async move {
+                if let Err(e) = persist(&catalog_manager, &slow_query).await {
+                    logging::error!(e; "Failed to persist slow query");
+                }
+            });
+        }
+    }
+}
+
+async fn persist(catalog_manager: &CatalogManagerRef, slow_query: &SlowQuery) -> Result<()> {
+    let table = catalog_manager
+        .table(
+            DEFAULT_CATALOG_NAME,
+            INTERNAL_SCHEMA_NAME,
+            SLOW_QUERIES_TABLE_NAME,
+        )
+        .await
+        .context(CatalogSnafu)?
+        .context(TableNotFoundSnafu {
+            table_name: SLOW_QUERIES_TABLE_NAME,
+        })?;
+
+    let _ = table
+        .insert(InsertRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: INTERNAL_SCHEMA_NAME.to_string(),
+            table_name: SLOW_QUERIES_TABLE_NAME.to_string(),
+            columns_values: slow_query_columns(slow_query),
+            region_number: 0,
+        })
+        .await
+        .context(TableSnafu)?;
+    Ok(())
+}
+
+fn slow_query_columns(slow_query: &SlowQuery) -> HashMap<String, VectorRef> {
+    HashMap::from([
+        (
+            "user".to_string(),
+            Arc::new(StringVector::from(vec![slow_query.user.as_str()])) as VectorRef,
+        ),
+        (
+            "catalog".to_string(),
+            Arc::new(StringVector::from(vec![slow_query.catalog.as_str()])) as VectorRef,
+        ),
+        (
+            "schema".to_string(),
+            Arc::new(StringVector::from(vec![slow_query.schema.as_str()])) as VectorRef,
+        ),
+        (
+            "process_id".to_string(),
+            Arc::new(UInt64Vector::from_slice([slow_query.process_id])) as VectorRef,
+        ),
+        (
+            "query".to_string(),
+            Arc::new(StringVector::from(vec![slow_query.query.as_str()])) as VectorRef,
+        ),
+        (
+            "elapsed_ms".to_string(),
+            Arc::new(UInt64Vector::from_slice([
+                slow_query.elapsed.as_millis() as u64
+            ])) as VectorRef,
+        ),
+        (
+            "rows".to_string(),
+            Arc::new(UInt64Vector::from_slice([slow_query.rows as u64])) as VectorRef,
+        ),
+        (
+            "plan".to_string(),
+            Arc::new(StringVector::from(vec![slow_query.plan.clone()])) as VectorRef,
+        ),
+        (
+            "start_time".to_string(),
+            Arc::new(TimestampMillisecondVector::from_slice([
+                slow_query.start_time
+            ])) as VectorRef,
+        ),
+    ])
+}
+
+fn build_slow_queries_schema() -> RawSchema {
+    RawSchema::new(vec![
+        ColumnSchema::new("user", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("catalog", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("schema", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("process_id", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("query", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("elapsed_ms", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("rows", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("plan", ConcreteDataType::string_datatype(), true),
+        ColumnSchema::new(
+            "start_time",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )
+        .with_time_index(true),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use catalog::process_manager::ProcessManager;
+    use datatypes::value::Value;
+    use session::context::QueryContext;
+
+    use super::*;
+
+    #[test]
+    fn test_slow_query_options() {
+        let opts: SlowQueryOptions = toml::from_str(
+            r#"
+            enable = true
+            threshold = "500ms"
+            "#,
+        )
+        .unwrap();
+        assert!(opts.enable);
+        assert_eq!(Duration::from_millis(500), opts.threshold);
+        assert!(opts.record_plan);
+        assert!(!opts.persist);
+    }
+
+    #[test]
+    fn test_slow_query_columns() {
+        let slow_query = SlowQuery {
+            process_id: 1,
+            user: "alice".to_string(),
+            catalog: "greptime".to_string(),
+            schema: "public".to_string(),
+            query: "SELECT * FROM numbers".to_string(),
+            start_time: 1000,
+            elapsed: Duration::from_millis(1500),
+            rows: 10,
+            plan: None,
+        };
+        let columns = slow_query_columns(&slow_query);
+        let schema = build_slow_queries_schema();
+        assert_eq!(schema.column_schemas.len(), columns.len());
+        for column in &schema.column_schemas {
+            let vector = &columns[&column.name];
+            assert_eq!(1, vector.len());
+            assert_eq!(column.data_type, vector.data_type());
+        }
+        assert_eq!(Value::UInt64(1500), columns["elapsed_ms"].get(0));
+        assert!(columns["plan"].is_null(0));
+    }
+
+    #[test]
+    fn test_slow_query_redacted() {
+        let process_manager = Arc::new(ProcessManager::default());
+        let handle = process_manager.register(
+            "CREATE USER alice IDENTIFIED BY 'secret'",
+            &QueryContext::new(),
+        );
+        let slow_query = SlowQuery::new(handle.process(), 0, None);
+        assert_eq!(handle.process().id(), slow_query.process_id);
+        assert!(!slow_query.query.contains("secret"));
+    }
+}
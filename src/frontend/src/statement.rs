@@ -16,11 +16,18 @@ mod backup;
 mod copy_table_from;
 mod copy_table_to;
 mod describe;
+mod materialized_view;
+mod privilege;
+mod process;
 mod show;
 mod tql;
+mod user;
+mod view;
+mod write_limit;
 
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use catalog::CatalogManagerRef;
 use common_error::prelude::BoxedError;
@@ -45,13 +52,19 @@ use crate::error::{
     CatalogSnafu, ExecLogicalPlanSnafu, ExecuteStatementSnafu, ExternalSnafu, PlanStatementSnafu,
     Result, SchemaNotFoundSnafu, TableNotFoundSnafu,
 };
+use crate::materialized_view::MaterializedViewManager;
+use crate::rbac::RbacUserProvider;
 use crate::statement::backup::{COPY_DATABASE_TIME_END_KEY, COPY_DATABASE_TIME_START_KEY};
+use crate::write_limit::WriteLimiterRef;
 
 #[derive(Clone)]
 pub struct StatementExecutor {
     catalog_manager: CatalogManagerRef,
     query_engine: QueryEngineRef,
     sql_stmt_executor: SqlStatementExecutorRef,
+    materialized_views: Arc<MaterializedViewManager>,
+    rbac: Option<Arc<RbacUserProvider>>,
+    write_limiter: Option<WriteLimiterRef>,
 }
 
 impl StatementExecutor {
@@ -59,14 +72,32 @@ impl StatementExecutor {
         catalog_manager: CatalogManagerRef,
         query_engine: QueryEngineRef,
         sql_stmt_executor: SqlStatementExecutorRef,
+        materialized_views: Arc<MaterializedViewManager>,
     ) -> Self {
         Self {
             catalog_manager,
             query_engine,
             sql_stmt_executor,
+            materialized_views,
+            rbac: None,
+            write_limiter: None,
         }
     }
 
+    /// Enables role-based access control: privileges are checked before executing
+    /// statements, and users and roles can be managed by SQL.
+    pub(crate) fn with_rbac(mut self, rbac: Arc<RbacUserProvider>) -> Self {
+        self.rbac = Some(rbac);
+        self
+    }
+
+    /// Enables limits of write rates and quotas of tables and columns, see
+    /// [crate::write_limit].
+    pub(crate) fn with_write_limiter(mut self, write_limiter: WriteLimiterRef) -> Self {
+        self.write_limiter = Some(write_limiter);
+        self
+    }
+
     pub async fn execute_stmt(
         &self,
         stmt: QueryStatement,
@@ -79,6 +110,9 @@ impl StatementExecutor {
     }
 
     pub async fn execute_sql(&self, stmt: Statement, query_ctx: QueryContextRef) -> Result<Output> {
+        self.check_statement_privileges(&stmt, &query_ctx).await?;
+        self.check_statement_write_limits(&stmt, &query_ctx).await?;
+
         match stmt {
             Statement::Query(_) | Statement::Explain(_) | Statement::Delete(_) => {
                 self.plan_exec(QueryStatement::Sql(stmt), query_ctx).await
@@ -93,8 +127,17 @@ impl StatementExecutor {
                         .await
                         .context(ExecuteStatementSnafu)
                 } else {
-                    self.plan_exec(QueryStatement::Sql(Statement::Insert(insert)), query_ctx)
-                        .await
+                    let (catalog, schema, table) =
+                        table_idents_to_full_name(insert.table_name(), query_ctx.clone())
+                            .map_err(BoxedError::new)
+                            .context(ExternalSnafu)?;
+                    let output = self
+                        .plan_exec(QueryStatement::Sql(Statement::Insert(insert)), query_ctx)
+                        .await?;
+                    if let Output::AffectedRows(rows) = output {
+                        self.record_writes(&catalog, &schema, &table, rows);
+                    }
+                    Ok(output)
                 }
             }
 
@@ -108,6 +151,10 @@ impl StatementExecutor {
 
             Statement::ShowTables(stmt) => self.show_tables(stmt, query_ctx).await,
 
+            Statement::ShowProcesslist(stmt) => self.show_processlist(stmt, query_ctx),
+
+            Statement::Kill(stmt) => self.kill(stmt, query_ctx),
+
             Statement::Copy(sql::statements::copy::Copy::CopyTable(stmt)) => {
                 let req = to_copy_table_request(stmt, query_ctx)?;
                 match req.direction {
@@ -125,11 +172,41 @@ impl StatementExecutor {
                     .await
             }
 
+            Statement::CreateUser(stmt) => self.create_user(stmt, query_ctx).await,
+
+            Statement::CreateRole(stmt) => self.create_role(stmt, query_ctx).await,
+
+            Statement::DropUser(stmt) => self.drop_user(stmt, query_ctx).await,
+
+            Statement::Grant(stmt) => self.grant(stmt, query_ctx).await,
+
+            Statement::Revoke(stmt) => self.revoke(stmt, query_ctx).await,
+
+            Statement::CreateView(stmt) => self.create_view(stmt, query_ctx).await,
+
+            Statement::DropView(stmt) => self.drop_view(stmt, query_ctx).await,
+
+            Statement::CreateMaterializedView(stmt) => {
+                self.create_materialized_view(stmt, query_ctx).await
+            }
+
+            Statement::DropMaterializedView(stmt) => {
+                self.drop_materialized_view(stmt, query_ctx).await
+            }
+
+            Statement::DropTable(stmt) => {
+                self.check_drop_table_not_view(stmt.table_name(), &query_ctx)
+                    .await?;
+                self.sql_stmt_executor
+                    .execute_sql(Statement::DropTable(stmt), query_ctx)
+                    .await
+                    .context(ExecuteStatementSnafu)
+            }
+
             Statement::CreateDatabase(_)
             | Statement::CreateTable(_)
             | Statement::CreateExternalTable(_)
             | Statement::Alter(_)
-            | Statement::DropTable(_)
             | Statement::ShowCreateTable(_) => self
                 .sql_stmt_executor
                 .execute_sql(stmt, query_ctx)
@@ -144,6 +221,7 @@ impl StatementExecutor {
             .plan(stmt, query_ctx.clone())
             .await
             .context(PlanStatementSnafu)?;
+        self.check_plan_privileges(&plan, &query_ctx).await?;
         self.query_engine
             .execute(plan, query_ctx)
             .await
@@ -16,6 +16,7 @@ use common_datasource::file_format::Format;
 use common_query::Output;
 use common_telemetry::info;
 use snafu::{ensure, ResultExt};
+use table::metadata::TableType;
 use table::requests::{CopyDatabaseRequest, CopyDirection, CopyTableRequest};
 
 use crate::error;
@@ -56,6 +57,15 @@ impl StatementExecutor {
             if table_name == "numbers" {
                 continue;
             }
+            // Views have no data of their own to export.
+            let table = self
+                .catalog_manager
+                .table(&req.catalog_name, &req.schema_name, &table_name)
+                .await
+                .context(CatalogSnafu)?;
+            if table.map(|t| t.table_type()) == Some(TableType::View) {
+                continue;
+            }
             let mut table_file = req.location.clone();
             table_file.push_str(&table_name);
             table_file.push_str(suffix);
@@ -339,6 +339,12 @@ impl StatementExecutor {
             }
         }
 
+        self.record_writes(
+            &req.catalog_name,
+            &req.schema_name,
+            &req.table_name,
+            rows_inserted,
+        );
         Ok(rows_inserted)
     }
 }
@@ -15,6 +15,7 @@
 use common_base::readable_size::ReadableSize;
 use common_datasource::file_format::csv::stream_to_csv;
 use common_datasource::file_format::json::stream_to_json;
+use common_datasource::file_format::orc::stream_to_orc;
 use common_datasource::file_format::Format;
 use common_datasource::object_store::{build_backend, parse_url};
 use common_query::physical_plan::SessionContext;
@@ -68,7 +69,13 @@ impl StatementExecutor {
 
                 Ok(rows_copied)
             }
-            _ => error::UnsupportedFormatSnafu { format: *format }.fail(),
+            Format::Orc(_) => stream_to_orc(
+                Box::pin(DfRecordBatchStreamAdapter::new(stream)),
+                object_store,
+                path,
+            )
+            .await
+            .context(error::WriteStreamToFileSnafu { path }),
         }
     }
 
@@ -0,0 +1,403 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_catalog::consts::MITO_ENGINE;
+use common_catalog::format_full_table_name;
+use common_error::prelude::BoxedError;
+use common_query::Output;
+use common_telemetry::{info, warn};
+use common_time::util;
+use datafusion::sql::sqlparser::ast::{Query as SpQuery, SelectItem, SetExpr};
+use datanode::instance::sql::table_idents_to_full_name;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::Schema;
+use query::parser::QueryStatement;
+use query::plan::LogicalPlan;
+use session::context::{QueryContext, QueryContextRef};
+use snafu::{ensure, OptionExt, ResultExt};
+use sql::ast::{
+    ColumnDef, ColumnOption, ColumnOptionDef, Expr, ObjectName, TableConstraint, Value,
+};
+use sql::statements::concrete_data_type_to_sql_data_type;
+use sql::statements::create::{CreateMaterializedView, CreateTable, TIME_INDEX};
+use sql::statements::drop::{DropMaterializedView, DropTable};
+use sql::statements::statement::Statement;
+
+use crate::error::{
+    CatalogSnafu, ConvertSqlTypeSnafu, ExecuteStatementSnafu, ExternalSnafu,
+    InvalidRefreshIntervalSnafu, InvalidSqlSnafu, MaterializedViewNotFoundSnafu,
+    PlanStatementSnafu, Result, TableAlreadyExistSnafu,
+};
+use crate::materialized_view::{MaterializedView, DEFAULT_REFRESH_INTERVAL};
+use crate::statement::StatementExecutor;
+
+const REFRESH_INTERVAL_KEY: &str = "refresh_interval";
+const DATE_BIN: &str = "date_bin";
+
+impl StatementExecutor {
+    pub(super) async fn create_materialized_view(
+        &self,
+        stmt: CreateMaterializedView,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let (catalog, schema, view_name) = table_idents_to_full_name(&stmt.name, query_ctx.clone())
+            .map_err(BoxedError::new)
+            .context(ExternalSnafu)?;
+        let full_name = format_full_table_name(&catalog, &schema, &view_name);
+        ensure!(
+            self.catalog_manager
+                .table(&catalog, &schema, &view_name)
+                .await
+                .context(CatalogSnafu)?
+                .is_none(),
+            TableAlreadyExistSnafu { table: &full_name }
+        );
+
+        let mut refresh_interval = DEFAULT_REFRESH_INTERVAL;
+        for (key, value) in &stmt.options {
+            ensure!(
+                key == REFRESH_INTERVAL_KEY,
+                InvalidSqlSnafu {
+                    err_msg: format!("Unrecognized materialized view option: {key}"),
+                }
+            );
+            refresh_interval =
+                humantime::parse_duration(value).context(InvalidRefreshIntervalSnafu { value })?;
+        }
+
+        let (time_index, primary_keys) = find_group_keys(&stmt.query.inner)?;
+
+        // Like views, the definition is planned with tables resolved against the
+        // view's catalog and schema, which is also how it's planned when refreshed.
+        let definition = stmt.query.inner.to_string();
+        let view_ctx = Arc::new(QueryContext::with(&catalog, &schema));
+        view_ctx.set_current_user(query_ctx.current_user().as_ref().clone());
+        let plan = self
+            .query_engine
+            .planner()
+            .plan(
+                QueryStatement::Sql(Statement::Query(stmt.query)),
+                view_ctx.clone(),
+            )
+            .await
+            .context(PlanStatementSnafu)?;
+        self.check_plan_privileges(&plan, &view_ctx).await?;
+        let view_schema = plan.schema().context(PlanStatementSnafu)?;
+        let LogicalPlan::DfPlan(df_plan) = &plan;
+        let _ = self
+            .materialized_views
+            .source_table(df_plan, &catalog, &schema)
+            .await?;
+
+        let create_table = build_create_table(
+            ObjectName(vec![
+                catalog.as_str().into(),
+                schema.as_str().into(),
+                view_name.as_str().into(),
+            ]),
+            &view_schema,
+            time_index,
+            &primary_keys,
+        )?;
+        let _ = self
+            .sql_stmt_executor
+            .execute_sql(Statement::CreateTable(create_table), query_ctx)
+            .await
+            .context(ExecuteStatementSnafu)?;
+
+        let view = MaterializedView {
+            catalog_name: catalog,
+            schema_name: schema,
+            view_name,
+            definition,
+            refresh_interval,
+            watermarks: Default::default(),
+            gmt_created: util::current_time_millis(),
+        };
+        self.materialized_views.insert(&view).await?;
+        info!("Created materialized view: {}", full_name);
+
+        // The view is populated right away, and refreshed in the background later.
+        if let Err(e) = self.materialized_views.refresh(&view).await {
+            warn!(
+                "Failed to populate materialized view {}, error: {}",
+                full_name, e
+            );
+        }
+        Ok(Output::AffectedRows(0))
+    }
+
+    pub(super) async fn drop_materialized_view(
+        &self,
+        stmt: DropMaterializedView,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let (catalog, schema, view_name) =
+            table_idents_to_full_name(stmt.view_name(), query_ctx.clone())
+                .map_err(BoxedError::new)
+                .context(ExternalSnafu)?;
+
+        if self
+            .materialized_views
+            .find(&catalog, &schema, &view_name)
+            .await?
+            .is_none()
+        {
+            ensure!(
+                stmt.if_exists(),
+                MaterializedViewNotFoundSnafu {
+                    name: stmt.view_name().to_string(),
+                }
+            );
+            return Ok(Output::AffectedRows(0));
+        }
+
+        self.materialized_views
+            .delete(&catalog, &schema, &view_name)
+            .await?;
+        let drop_table = DropTable::new(ObjectName(vec![
+            catalog.as_str().into(),
+            schema.as_str().into(),
+            view_name.as_str().into(),
+        ]));
+        let _ = self
+            .sql_stmt_executor
+            .execute_sql(Statement::DropTable(drop_table), query_ctx)
+            .await
+            .context(ExecuteStatementSnafu)?;
+        info!(
+            "Dropped materialized view: {}",
+            format_full_table_name(&catalog, &schema, &view_name)
+        );
+        Ok(Output::AffectedRows(0))
+    }
+}
+
+/// Finds the positions of the time index and the other group keys in the
+/// projection of a materialized view's query. The time index is the only group
+/// key computed by `date_bin`.
+fn find_group_keys(query: &SpQuery) -> Result<(usize, Vec<usize>)> {
+    let SetExpr::Select(select) = query.body.as_ref() else {
+        return InvalidSqlSnafu {
+            err_msg: "A materialized view must be defined by a SELECT query",
+        }
+        .fail();
+    };
+
+    let mut time_index = None;
+    let mut primary_keys = vec![];
+    for (i, item) in select.projection.iter().enumerate() {
+        let (expr, alias) = match item {
+            SelectItem::UnnamedExpr(expr) => (expr, None),
+            SelectItem::ExprWithAlias { expr, alias } => (expr, Some(alias)),
+            _ => {
+                return InvalidSqlSnafu {
+                    err_msg: "Wildcards are not allowed in materialized views",
+                }
+                .fail()
+            }
+        };
+        // Group keys may be referenced by expressions, aliases or positions.
+        let position = (i + 1).to_string();
+        let is_group_key = select.group_by.iter().any(|key| {
+            key == expr
+                || matches!(key, Expr::Value(Value::Number(n, _)) if *n == position)
+                || matches!((key, alias), (Expr::Identifier(key), Some(alias)) if key.value == alias.value)
+        });
+        if !is_group_key {
+            continue;
+        }
+
+        if matches!(expr, Expr::Function(f) if f.name.to_string().eq_ignore_ascii_case(DATE_BIN)) {
+            ensure!(
+                time_index.is_none(),
+                InvalidSqlSnafu {
+                    err_msg: "A materialized view must be grouped by only one date_bin time bucket",
+                }
+            );
+            time_index = Some(i);
+        } else {
+            primary_keys.push(i);
+        }
+    }
+
+    let time_index = time_index.context(InvalidSqlSnafu {
+        err_msg: "A materialized view must be grouped by a date_bin time bucket",
+    })?;
+    Ok((time_index, primary_keys))
+}
+
+/// Builds the statement that creates the table of a materialized view, whose
+/// columns are the output of the view's query.
+fn build_create_table(
+    name: ObjectName,
+    schema: &Schema,
+    time_index: usize,
+    primary_keys: &[usize],
+) -> Result<CreateTable> {
+    let column_schemas = schema.column_schemas();
+    let columns = column_schemas
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            ensure!(
+                !matches!(
+                    column.data_type,
+                    ConcreteDataType::Null(_)
+                        | ConcreteDataType::List(_)
+                        | ConcreteDataType::Dictionary(_)
+                ),
+                InvalidSqlSnafu {
+                    err_msg: format!(
+                        "Unsupported data type {:?} of column {} in materialized views",
+                        column.data_type, column.name
+                    ),
+                }
+            );
+            let data_type = concrete_data_type_to_sql_data_type(&column.data_type).context(
+                ConvertSqlTypeSnafu {
+                    datatype: column.data_type.clone(),
+                },
+            )?;
+            let option = if i == time_index {
+                ColumnOption::NotNull
+            } else {
+                ColumnOption::Null
+            };
+            Ok(ColumnDef {
+                name: column.name[..].into(),
+                data_type,
+                collation: None,
+                options: vec![ColumnOptionDef { name: None, option }],
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut constraints = vec![TableConstraint::Unique {
+        name: Some(TIME_INDEX.into()),
+        columns: vec![column_schemas[time_index].name[..].into()],
+        is_primary: false,
+    }];
+    if !primary_keys.is_empty() {
+        constraints.push(TableConstraint::Unique {
+            name: None,
+            columns: primary_keys
+                .iter()
+                .map(|i| column_schemas[*i].name[..].into())
+                .collect(),
+            is_primary: true,
+        });
+    }
+
+    Ok(CreateTable {
+        if_not_exists: false,
+        table_id: 0,
+        name,
+        columns,
+        engine: MITO_ENGINE.to_string(),
+        constraints,
+        options: vec![],
+        partitions: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::schema::ColumnSchema;
+    use sql::dialect::GreptimeDbDialect;
+    use sql::parser::ParserContext;
+
+    use super::*;
+
+    fn parse_query(sql: &str) -> SpQuery {
+        let mut stmts = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
+        let Statement::Query(query) = stmts.remove(0) else { unreachable!() };
+        query.inner
+    }
+
+    #[test]
+    fn test_find_group_keys() {
+        let query = parse_query(
+            "SELECT date_bin(INTERVAL '5 minutes', ts) AS ts, host, avg(cpu) FROM monitor GROUP BY 1, host",
+        );
+        assert_eq!((0, vec![1]), find_group_keys(&query).unwrap());
+
+        let query = parse_query(
+            "SELECT host, idc, date_bin(INTERVAL '1 hour', ts) AS bucket, max(cpu) FROM monitor GROUP BY bucket, idc, host",
+        );
+        assert_eq!((2, vec![0, 1]), find_group_keys(&query).unwrap());
+
+        let query = parse_query(
+            "SELECT date_bin(INTERVAL '1 hour', ts), count(*) FROM monitor GROUP BY date_bin(INTERVAL '1 hour', ts)",
+        );
+        assert_eq!((0, vec![]), find_group_keys(&query).unwrap());
+
+        let query = parse_query("SELECT host, avg(cpu) FROM monitor GROUP BY host");
+        assert!(find_group_keys(&query).is_err());
+
+        let query = parse_query("SELECT * FROM monitor");
+        assert!(find_group_keys(&query).is_err());
+
+        let query = parse_query(
+            "SELECT date_bin(INTERVAL '1 hour', ts), date_bin(INTERVAL '1 day', ts), count(*) FROM monitor GROUP BY 1, 2",
+        );
+        assert!(find_group_keys(&query).is_err());
+    }
+
+    #[test]
+    fn test_build_create_table() {
+        let schema = Schema::new(vec![
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_nanosecond_datatype(),
+                true,
+            ),
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+        ]);
+        let create_table =
+            build_create_table(ObjectName(vec!["cpu_5m".into()]), &schema, 0, &[1]).unwrap();
+        let columns = create_table
+            .columns
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "ts TIMESTAMP(9) NOT NULL",
+                "host STRING NULL",
+                "cpu DOUBLE NULL"
+            ],
+            columns
+        );
+        assert_eq!(
+            vec![
+                TableConstraint::Unique {
+                    name: Some(TIME_INDEX.into()),
+                    columns: vec!["ts".into()],
+                    is_primary: false,
+                },
+                TableConstraint::Unique {
+                    name: None,
+                    columns: vec!["host".into()],
+                    is_primary: true,
+                },
+            ],
+            create_table.constraints
+        );
+        assert_eq!(MITO_ENGINE, create_table.engine);
+    }
+}
@@ -0,0 +1,334 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_catalog::consts::INTERNAL_SCHEMA_NAME;
+use common_error::prelude::BoxedError;
+use datafusion_common::tree_node::{TreeNode, TreeNodeVisitor, VisitRecursion};
+use datafusion_common::{OwnedTableReference, Result as DfResult};
+use datafusion_expr::{Expr, LogicalPlan as DfLogicalPlan};
+use datanode::instance::sql::{idents_to_full_database_name, table_idents_to_full_name};
+use promql_parser::parser::EvalStmt;
+use query::parser::QueryStatement;
+use query::plan::LogicalPlan;
+use servers::auth::Privilege;
+use session::context::QueryContextRef;
+use snafu::ResultExt;
+use sql::ast::ObjectName;
+use sql::statements::copy::{Copy, CopyTable};
+use sql::statements::statement::Statement;
+
+use crate::error::{AuthSnafu, ExternalSnafu, PlanStatementSnafu, Result};
+use crate::statement::StatementExecutor;
+
+impl StatementExecutor {
+    /// Checks the privileges required by statements that are not planned by
+    /// query engine. Those planned are checked by [Self::check_plan_privileges].
+    pub(super) async fn check_statement_privileges(
+        &self,
+        stmt: &Statement,
+        query_ctx: &QueryContextRef,
+    ) -> Result<()> {
+        if self.rbac.is_none() {
+            return Ok(());
+        }
+
+        match stmt {
+            Statement::Insert(insert) if insert.can_extract_values() => {
+                self.check_table_privilege(Privilege::Write, insert.table_name(), query_ctx)
+                    .await
+            }
+            Statement::CreateTable(stmt) => {
+                self.check_table_privilege(Privilege::Ddl, &stmt.name, query_ctx)
+                    .await
+            }
+            Statement::CreateExternalTable(stmt) => {
+                self.check_table_privilege(Privilege::Ddl, &stmt.name, query_ctx)
+                    .await
+            }
+            Statement::Alter(stmt) => {
+                self.check_table_privilege(Privilege::Ddl, stmt.table_name(), query_ctx)
+                    .await
+            }
+            Statement::DropTable(stmt) => {
+                self.check_table_privilege(Privilege::Ddl, stmt.table_name(), query_ctx)
+                    .await
+            }
+            Statement::CreateView(stmt) => {
+                self.check_table_privilege(Privilege::Ddl, &stmt.name, query_ctx)
+                    .await
+            }
+            Statement::DropView(stmt) => {
+                self.check_table_privilege(Privilege::Ddl, stmt.view_name(), query_ctx)
+                    .await
+            }
+            // The query of a materialized view is checked on its plan when created.
+            Statement::CreateMaterializedView(stmt) => {
+                self.check_table_privilege(Privilege::Ddl, &stmt.name, query_ctx)
+                    .await
+            }
+            Statement::DropMaterializedView(stmt) => {
+                self.check_table_privilege(Privilege::Ddl, stmt.view_name(), query_ctx)
+                    .await
+            }
+            Statement::CreateDatabase(stmt) => {
+                self.check_database_privilege(Privilege::Ddl, &stmt.name, query_ctx)
+                    .await
+            }
+            Statement::Copy(Copy::CopyTable(CopyTable::To(arg))) => {
+                self.check_table_privilege(Privilege::Read, &arg.table_name, query_ctx)
+                    .await
+            }
+            Statement::Copy(Copy::CopyTable(CopyTable::From(arg))) => {
+                self.check_table_privilege(Privilege::Write, &arg.table_name, query_ctx)
+                    .await
+            }
+            Statement::Copy(Copy::CopyDatabase(arg)) => {
+                self.check_database_privilege(Privilege::Read, &arg.database_name, query_ctx)
+                    .await
+            }
+            // Describing and showing are not restricted, except for the tables in the
+            // internal schema, which are only accessible to superusers.
+            Statement::ShowTables(stmt) => {
+                let schema = stmt
+                    .database
+                    .clone()
+                    .unwrap_or_else(|| query_ctx.current_schema());
+                self.check_internal_schema(&query_ctx.current_catalog(), &schema, query_ctx)
+                    .await
+            }
+            Statement::DescribeTable(stmt) => {
+                self.check_internal_table(stmt.name(), query_ctx).await
+            }
+            Statement::ShowCreateTable(stmt) => {
+                self.check_internal_table(&stmt.table_name, query_ctx).await
+            }
+            // Queries, deletes and inserts with subqueries are checked on their plans.
+            // User managements are only allowed to superusers.
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks the privileges on all tables read or written by the plan.
+    pub(crate) async fn check_plan_privileges(
+        &self,
+        plan: &LogicalPlan,
+        query_ctx: &QueryContextRef,
+    ) -> Result<()> {
+        if self.rbac.is_none() {
+            return Ok(());
+        }
+
+        let LogicalPlan::DfPlan(plan) = plan;
+        let mut collector = TableAccessCollector::default();
+        collect_table_accesses(plan, &mut collector);
+
+        let catalog = query_ctx.current_catalog();
+        let schema = query_ctx.current_schema();
+        for (privilege, table_name) in collector.accesses {
+            let table_name = table_name.resolve(&catalog, &schema);
+            self.authorize(
+                privilege,
+                &table_name.catalog,
+                &table_name.schema,
+                Some(&table_name.table),
+                query_ctx,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Checks the privileges on all tables read by the PromQL query. The query
+    /// is planned just for the check, so it can be done before the results of
+    /// the query are served from cache.
+    pub(crate) async fn check_promql_privileges(
+        &self,
+        stmt: &EvalStmt,
+        query_ctx: &QueryContextRef,
+    ) -> Result<()> {
+        if self.rbac.is_none() {
+            return Ok(());
+        }
+
+        let plan = self
+            .query_engine
+            .planner()
+            .plan(QueryStatement::Promql(stmt.clone()), query_ctx.clone())
+            .await
+            .context(PlanStatementSnafu)?;
+        self.check_plan_privileges(&plan, query_ctx).await
+    }
+
+    pub(crate) async fn check_table_privilege(
+        &self,
+        privilege: Privilege,
+        table_name: &ObjectName,
+        query_ctx: &QueryContextRef,
+    ) -> Result<()> {
+        let (catalog, schema, table) = table_idents_to_full_name(table_name, query_ctx.clone())
+            .map_err(BoxedError::new)
+            .context(ExternalSnafu)?;
+        self.authorize(privilege, &catalog, &schema, Some(&table), query_ctx)
+            .await
+    }
+
+    async fn check_internal_table(
+        &self,
+        table_name: &ObjectName,
+        query_ctx: &QueryContextRef,
+    ) -> Result<()> {
+        let (catalog, schema, _) = table_idents_to_full_name(table_name, query_ctx.clone())
+            .map_err(BoxedError::new)
+            .context(ExternalSnafu)?;
+        self.check_internal_schema(&catalog, &schema, query_ctx)
+            .await
+    }
+
+    /// Checks the access to the internal schema, which is denied to all users
+    /// but superusers.
+    async fn check_internal_schema(
+        &self,
+        catalog: &str,
+        schema: &str,
+        query_ctx: &QueryContextRef,
+    ) -> Result<()> {
+        if schema != INTERNAL_SCHEMA_NAME {
+            return Ok(());
+        }
+        self.authorize(Privilege::Read, catalog, schema, None, query_ctx)
+            .await
+    }
+
+    async fn check_database_privilege(
+        &self,
+        privilege: Privilege,
+        database_name: &ObjectName,
+        query_ctx: &QueryContextRef,
+    ) -> Result<()> {
+        let (catalog, schema) = idents_to_full_database_name(database_name, query_ctx)
+            .map_err(BoxedError::new)
+            .context(ExternalSnafu)?;
+        self.authorize(privilege, &catalog, &schema, None, query_ctx)
+            .await
+    }
+
+    /// Checks the privilege of current user in `query_ctx`, on a table or, if
+    /// `table` is `None`, on a database. Always passes if RBAC is not enabled.
+    pub(crate) async fn authorize(
+        &self,
+        privilege: Privilege,
+        catalog: &str,
+        schema: &str,
+        table: Option<&str>,
+        query_ctx: &QueryContextRef,
+    ) -> Result<()> {
+        let Some(rbac) = &self.rbac else { return Ok(()) };
+        rbac.authorize_privilege(&query_ctx.current_user(), privilege, catalog, schema, table)
+            .await
+            .context(AuthSnafu)
+    }
+}
+
+/// Collects tables scanned or written in a plan, including its subqueries.
+#[derive(Default)]
+struct TableAccessCollector {
+    accesses: Vec<(Privilege, OwnedTableReference)>,
+}
+
+impl TreeNodeVisitor for TableAccessCollector {
+    type N = DfLogicalPlan;
+
+    fn pre_visit(&mut self, node: &Self::N) -> DfResult<VisitRecursion> {
+        match node {
+            DfLogicalPlan::TableScan(scan) => {
+                self.accesses
+                    .push((Privilege::Read, scan.table_name.clone()));
+            }
+            DfLogicalPlan::Dml(dml) => {
+                self.accesses
+                    .push((Privilege::Write, dml.table_name.clone()));
+            }
+            _ => {}
+        }
+
+        for expr in node.expressions() {
+            let _ = expr.apply(&mut |expr| {
+                match expr {
+                    Expr::ScalarSubquery(subquery)
+                    | Expr::Exists { subquery, .. }
+                    | Expr::InSubquery { subquery, .. } => {
+                        collect_table_accesses(&subquery.subquery, self)
+                    }
+                    _ => {}
+                }
+                Ok(VisitRecursion::Continue)
+            });
+        }
+        Ok(VisitRecursion::Continue)
+    }
+}
+
+fn collect_table_accesses(plan: &DfLogicalPlan, collector: &mut TableAccessCollector) {
+    // Safety: the visitor never returns an error.
+    let _ = plan.visit(collector).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::datasource::DefaultTableSource;
+    use datafusion_expr::{col, exists, LogicalPlanBuilder};
+    use table::table::adapter::DfTableProviderAdapter;
+    use table::table::numbers::NumbersTable;
+
+    use super::*;
+
+    #[test]
+    fn test_collect_table_accesses() {
+        let table_source = || {
+            let numbers_table = Arc::new(NumbersTable::new(0)) as _;
+            let adapter = Arc::new(DfTableProviderAdapter::new(numbers_table));
+            Arc::new(DefaultTableSource::new(adapter))
+        };
+
+        let subquery = LogicalPlanBuilder::scan("public.other", table_source(), None)
+            .unwrap()
+            .build()
+            .unwrap();
+        let plan = LogicalPlanBuilder::scan("numbers", table_source(), None)
+            .unwrap()
+            .filter(exists(Arc::new(subquery)))
+            .unwrap()
+            .project(vec![col("number")])
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let mut collector = TableAccessCollector::default();
+        collect_table_accesses(&plan, &mut collector);
+        let accesses = collector
+            .accesses
+            .into_iter()
+            .map(|(privilege, table)| (privilege, table.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (Privilege::Read, "public.other".to_string()),
+                (Privilege::Read, "numbers".to_string()),
+            ],
+            accesses
+        );
+    }
+}
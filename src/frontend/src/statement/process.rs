@@ -0,0 +1,73 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use catalog::process_manager::ProcessManagerRef;
+use common_query::Output;
+use servers::auth::PermissionDeniedSnafu;
+use session::context::QueryContextRef;
+use snafu::{OptionExt, ResultExt};
+use sql::statements::kill::Kill;
+use sql::statements::show::ShowProcesslist;
+
+use crate::error::{
+    AuthSnafu, ExecuteStatementSnafu, NotSupportedSnafu, ProcessNotFoundSnafu, Result,
+};
+use crate::statement::StatementExecutor;
+
+impl StatementExecutor {
+    /// Lists the running statements. Users other than superusers only see their
+    /// own statements.
+    pub(super) fn show_processlist(
+        &self,
+        stmt: ShowProcesslist,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let processes = self
+            .process_manager()?
+            .list_visible_to(&query_ctx.current_user());
+        query::sql::show_processlist(stmt, &processes).context(ExecuteStatementSnafu)
+    }
+
+    /// Kills a running statement. Users other than superusers can only kill their
+    /// own statements. Statements changing data or metadata are not stopped
+    /// halfway, only their remaining output is dropped.
+    pub(super) fn kill(&self, stmt: Kill, query_ctx: QueryContextRef) -> Result<Output> {
+        let process_manager = self.process_manager()?;
+        let process = process_manager
+            .get(stmt.id)
+            .context(ProcessNotFoundSnafu { id: stmt.id })?;
+
+        let user = query_ctx.current_user();
+        if !process_manager.is_visible_to(&process, &user) {
+            return PermissionDeniedSnafu {
+                username: user.username(),
+                privilege: "SUPERUSER",
+                object: format!("process {}", stmt.id),
+            }
+            .fail()
+            .context(AuthSnafu);
+        }
+
+        let _ = process_manager.kill(stmt.id);
+        Ok(Output::AffectedRows(0))
+    }
+
+    fn process_manager(&self) -> Result<ProcessManagerRef> {
+        self.catalog_manager
+            .process_manager()
+            .context(NotSupportedSnafu {
+                feat: "process list without a process manager",
+            })
+    }
+}
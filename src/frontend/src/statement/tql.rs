@@ -66,6 +66,7 @@ impl StatementExecutor {
             .plan(stmt, query_ctx.clone())
             .await
             .context(PlanStatementSnafu)?;
+        self.check_plan_privileges(&plan, &query_ctx).await?;
         self.query_engine
             .execute(plan, query_ctx)
             .await
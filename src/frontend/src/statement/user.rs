@@ -0,0 +1,140 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_error::prelude::BoxedError;
+use common_query::Output;
+use datanode::instance::sql::{idents_to_full_database_name, table_idents_to_full_name};
+use servers::auth::Privilege;
+use session::context::QueryContextRef;
+use snafu::{OptionExt, ResultExt};
+use sql::statements::user::{
+    CreateRole, CreateUser, DropUser, Grant, GrantObject, GrantTarget, Privilege as SqlPrivilege,
+    Revoke,
+};
+
+use crate::error::{AuthSnafu, ExternalSnafu, NotSupportedSnafu, Result};
+use crate::rbac::{privilege_object, RbacUserProvider};
+use crate::statement::StatementExecutor;
+
+impl StatementExecutor {
+    pub(super) async fn create_user(
+        &self,
+        stmt: CreateUser,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let rbac = self.rbac_for_superuser(&query_ctx)?;
+        let created = rbac
+            .create_principal(&stmt.name, Some(&stmt.password), stmt.if_not_exists)
+            .await?;
+        Ok(Output::AffectedRows(created as usize))
+    }
+
+    pub(super) async fn create_role(
+        &self,
+        stmt: CreateRole,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let rbac = self.rbac_for_superuser(&query_ctx)?;
+        let created = rbac
+            .create_principal(&stmt.name, None, stmt.if_not_exists)
+            .await?;
+        Ok(Output::AffectedRows(created as usize))
+    }
+
+    pub(super) async fn drop_user(
+        &self,
+        stmt: DropUser,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let rbac = self.rbac_for_superuser(&query_ctx)?;
+        let dropped = rbac
+            .drop_principal(&stmt.name, stmt.is_role, stmt.if_exists)
+            .await?;
+        Ok(Output::AffectedRows(dropped as usize))
+    }
+
+    pub(super) async fn grant(&self, stmt: Grant, query_ctx: QueryContextRef) -> Result<Output> {
+        let rbac = self.rbac_for_superuser(&query_ctx)?;
+        match stmt.target {
+            GrantTarget::Privileges { privileges, object } => {
+                let object = to_privilege_object(&object, &query_ctx)?;
+                rbac.grant(&stmt.grantee, &expand_privileges(&privileges), &object)
+                    .await?;
+            }
+            GrantTarget::Role(role) => rbac.grant_role(&stmt.grantee, &role).await?,
+        }
+        Ok(Output::AffectedRows(0))
+    }
+
+    pub(super) async fn revoke(&self, stmt: Revoke, query_ctx: QueryContextRef) -> Result<Output> {
+        let rbac = self.rbac_for_superuser(&query_ctx)?;
+        match stmt.target {
+            GrantTarget::Privileges { privileges, object } => {
+                let object = to_privilege_object(&object, &query_ctx)?;
+                rbac.revoke(&stmt.grantee, &expand_privileges(&privileges), &object)
+                    .await?;
+            }
+            GrantTarget::Role(role) => rbac.revoke_role(&stmt.grantee, &role).await?,
+        }
+        Ok(Output::AffectedRows(0))
+    }
+
+    /// Users and roles can only be managed by superusers, with RBAC enabled.
+    fn rbac_for_superuser(&self, query_ctx: &QueryContextRef) -> Result<&Arc<RbacUserProvider>> {
+        let rbac = self.rbac.as_ref().context(NotSupportedSnafu {
+            feat: "managing users without RBAC enabled",
+        })?;
+        rbac.ensure_superuser(&query_ctx.current_user())
+            .context(AuthSnafu)?;
+        Ok(rbac)
+    }
+}
+
+fn expand_privileges(privileges: &[SqlPrivilege]) -> Vec<Privilege> {
+    let mut expanded = Vec::with_capacity(privileges.len());
+    for privilege in privileges {
+        let privileges: &[Privilege] = match privilege {
+            SqlPrivilege::Read => &[Privilege::Read],
+            SqlPrivilege::Write => &[Privilege::Write],
+            SqlPrivilege::Ddl => &[Privilege::Ddl],
+            SqlPrivilege::All => &[Privilege::Read, Privilege::Write, Privilege::Ddl],
+        };
+        for privilege in privileges {
+            if !expanded.contains(privilege) {
+                expanded.push(*privilege);
+            }
+        }
+    }
+    expanded
+}
+
+fn to_privilege_object(object: &GrantObject, query_ctx: &QueryContextRef) -> Result<String> {
+    match object {
+        GrantObject::All => Ok("*".to_string()),
+        GrantObject::Database(name) => {
+            let (catalog, schema) = idents_to_full_database_name(name, query_ctx)
+                .map_err(BoxedError::new)
+                .context(ExternalSnafu)?;
+            Ok(privilege_object(&catalog, &schema, None))
+        }
+        GrantObject::Table(name) => {
+            let (catalog, schema, table) = table_idents_to_full_name(name, query_ctx.clone())
+                .map_err(BoxedError::new)
+                .context(ExternalSnafu)?;
+            Ok(privilege_object(&catalog, &schema, Some(&table)))
+        }
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use catalog::{DeregisterTableRequest, RegisterTableRequest};
+use common_catalog::format_full_table_name;
+use common_error::prelude::BoxedError;
+use common_query::Output;
+use common_telemetry::info;
+use datanode::instance::sql::table_idents_to_full_name;
+use query::parser::QueryStatement;
+use session::context::{QueryContext, QueryContextRef};
+use snafu::{ensure, ResultExt};
+use sql::ast::ObjectName;
+use sql::statements::create::CreateView;
+use sql::statements::drop::DropView;
+use sql::statements::statement::Statement;
+use table::metadata::TableType;
+use table::table::view::ViewTable;
+
+use crate::error::{
+    CatalogSnafu, ExternalSnafu, InvalidSqlSnafu, PlanStatementSnafu, Result,
+    TableAlreadyExistSnafu, TableNotFoundSnafu,
+};
+use crate::statement::StatementExecutor;
+
+impl StatementExecutor {
+    pub(super) async fn create_view(
+        &self,
+        stmt: CreateView,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let (catalog, schema, view_name) = table_idents_to_full_name(&stmt.name, query_ctx.clone())
+            .map_err(BoxedError::new)
+            .context(ExternalSnafu)?;
+
+        let existing = self
+            .catalog_manager
+            .table(&catalog, &schema, &view_name)
+            .await
+            .context(CatalogSnafu)?;
+        if let Some(existing) = &existing {
+            ensure!(
+                stmt.or_replace && existing.table_type() == TableType::View,
+                TableAlreadyExistSnafu {
+                    table: format_full_table_name(&catalog, &schema, &view_name),
+                }
+            );
+        }
+
+        // The definition is planned the same way as it's expanded when the view is
+        // queried: with tables resolved against the view's catalog and schema. It
+        // also checks that the definition is valid and readable by current user.
+        let definition = stmt.query.inner.to_string();
+        let view_ctx = Arc::new(QueryContext::with(&catalog, &schema));
+        view_ctx.set_current_user(query_ctx.current_user().as_ref().clone());
+        let plan = self
+            .query_engine
+            .planner()
+            .plan(
+                QueryStatement::Sql(Statement::Query(stmt.query)),
+                view_ctx.clone(),
+            )
+            .await
+            .context(PlanStatementSnafu)?;
+        self.check_plan_privileges(&plan, &view_ctx).await?;
+        let view_schema = plan.schema().context(PlanStatementSnafu)?;
+
+        let table_info = ViewTable::build_table_info(
+            &catalog,
+            &schema,
+            &view_name,
+            definition,
+            Arc::new(view_schema),
+        );
+
+        // The catalog manager allocates the table id of a new view, and keeps the
+        // id of a replaced one.
+        let request = RegisterTableRequest {
+            catalog: catalog.clone(),
+            schema: schema.clone(),
+            table_name: view_name.clone(),
+            table_id: table_info.ident.table_id,
+            table: Arc::new(ViewTable::new(Arc::new(table_info))),
+        };
+        if existing.is_some() {
+            self.catalog_manager
+                .replace_view(request)
+                .await
+                .context(CatalogSnafu)?;
+            info!(
+                "Replaced view: {}",
+                format_full_table_name(&catalog, &schema, &view_name)
+            );
+        } else {
+            let registered = self
+                .catalog_manager
+                .register_table(request)
+                .await
+                .context(CatalogSnafu)?;
+            ensure!(
+                registered,
+                TableAlreadyExistSnafu {
+                    table: format_full_table_name(&catalog, &schema, &view_name),
+                }
+            );
+            info!(
+                "Created view: {}",
+                format_full_table_name(&catalog, &schema, &view_name)
+            );
+        }
+
+        Ok(Output::AffectedRows(0))
+    }
+
+    pub(super) async fn drop_view(
+        &self,
+        stmt: DropView,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let (catalog, schema, view_name) = table_idents_to_full_name(stmt.view_name(), query_ctx)
+            .map_err(BoxedError::new)
+            .context(ExternalSnafu)?;
+
+        let view = self
+            .catalog_manager
+            .table(&catalog, &schema, &view_name)
+            .await
+            .context(CatalogSnafu)?;
+        let Some(view) = view else {
+            ensure!(
+                stmt.if_exists(),
+                TableNotFoundSnafu {
+                    table_name: stmt.view_name().to_string(),
+                }
+            );
+            return Ok(Output::AffectedRows(0));
+        };
+        ensure!(
+            view.table_type() == TableType::View,
+            InvalidSqlSnafu {
+                err_msg: format!("{} is not a view", stmt.view_name()),
+            }
+        );
+
+        self.catalog_manager
+            .deregister_table(DeregisterTableRequest {
+                catalog,
+                schema,
+                table_name: view_name,
+            })
+            .await
+            .context(CatalogSnafu)?;
+        Ok(Output::AffectedRows(0))
+    }
+
+    /// Rejects `DROP TABLE` on views and tables of materialized views, which are
+    /// dropped by `DROP VIEW` and `DROP MATERIALIZED VIEW`.
+    pub(super) async fn check_drop_table_not_view(
+        &self,
+        table_name: &ObjectName,
+        query_ctx: &QueryContextRef,
+    ) -> Result<()> {
+        let (catalog, schema, table) = table_idents_to_full_name(table_name, query_ctx.clone())
+            .map_err(BoxedError::new)
+            .context(ExternalSnafu)?;
+        let table_type = self
+            .catalog_manager
+            .table(&catalog, &schema, &table)
+            .await
+            .context(CatalogSnafu)?
+            .map(|t| t.table_type());
+        ensure!(
+            table_type != Some(TableType::View),
+            InvalidSqlSnafu {
+                err_msg: format!("{table_name} is a view, use DROP VIEW instead"),
+            }
+        );
+        ensure!(
+            self.materialized_views
+                .find(&catalog, &schema, &table)
+                .await?
+                .is_none(),
+            InvalidSqlSnafu {
+                err_msg: format!(
+                    "{table_name} is a materialized view, use DROP MATERIALIZED VIEW instead"
+                ),
+            }
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_error::prelude::BoxedError;
+use datanode::instance::sql::table_idents_to_full_name;
+use session::context::QueryContextRef;
+use snafu::ResultExt;
+use sql::statements::alter::AlterTableOperation;
+use sql::statements::statement::Statement;
+use table::engine::TableReference;
+use table::table::numbers::NUMBERS_TABLE_NAME;
+
+use crate::error::{CatalogSnafu, ExternalSnafu, Result};
+use crate::materialized_view::MATERIALIZED_VIEWS_TABLE_NAME;
+use crate::rbac::{PRIVILEGES_TABLE_NAME, USERS_TABLE_NAME};
+use crate::slow_query::SLOW_QUERIES_TABLE_NAME;
+use crate::statement::StatementExecutor;
+use crate::write_limit::TableWrites;
+
+/// Names of the system tables in the default database, which are not counted
+/// in the quota of tables.
+const SYSTEM_TABLE_NAMES: &[&str] = &[
+    NUMBERS_TABLE_NAME,
+    MATERIALIZED_VIEWS_TABLE_NAME,
+    USERS_TABLE_NAME,
+    PRIVILEGES_TABLE_NAME,
+    SLOW_QUERIES_TABLE_NAME,
+    // Tables of scripts, which are created with the `python` feature.
+    "scripts",
+    "script_schedules",
+    "script_runs",
+];
+
+impl StatementExecutor {
+    /// Checks the write rates of inserts and the quotas of tables and columns
+    /// of DDLs, if write limits are enabled.
+    pub(super) async fn check_statement_write_limits(
+        &self,
+        stmt: &Statement,
+        query_ctx: &QueryContextRef,
+    ) -> Result<()> {
+        if self.write_limiter.is_none() {
+            return Ok(());
+        }
+
+        match stmt {
+            // Inserts with subqueries are recorded after execution, when their
+            // rows are known.
+            Statement::Insert(insert) if insert.can_extract_values() => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(insert.table_name(), query_ctx.clone())
+                        .map_err(BoxedError::new)
+                        .context(ExternalSnafu)?;
+                // The size of SQL text approximates the bytes of values.
+                let rows = insert.values_rows().unwrap_or_default();
+                let bytes = insert.inner.to_string().len();
+                self.acquire_writes(&[TableWrites {
+                    table: TableReference::full(&catalog, &schema, &table),
+                    rows: rows as u64,
+                    bytes: bytes as u64,
+                }])
+            }
+            Statement::CreateTable(stmt) => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(&stmt.name, query_ctx.clone())
+                        .map_err(BoxedError::new)
+                        .context(ExternalSnafu)?;
+                self.check_new_table(&catalog, &schema, &table, stmt.columns.len())
+                    .await
+            }
+            // Columns of external tables may be inferred from files, which are
+            // not checked.
+            Statement::CreateExternalTable(stmt) => {
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(&stmt.name, query_ctx.clone())
+                        .map_err(BoxedError::new)
+                        .context(ExternalSnafu)?;
+                self.check_new_table(&catalog, &schema, &table, stmt.columns.len())
+                    .await
+            }
+            Statement::Alter(stmt) => {
+                let AlterTableOperation::AddColumn { .. } = stmt.alter_operation() else {
+                    return Ok(());
+                };
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(stmt.table_name(), query_ctx.clone())
+                        .map_err(BoxedError::new)
+                        .context(ExternalSnafu)?;
+                self.check_new_columns(&catalog, &schema, &table, 1).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Acquires all writes of a request, see
+    /// [WriteLimiter::acquire](crate::write_limit::WriteLimiter::acquire).
+    pub(crate) fn acquire_writes(&self, writes: &[TableWrites<'_>]) -> Result<()> {
+        let Some(write_limiter) = &self.write_limiter else { return Ok(()) };
+        write_limiter.acquire(writes)
+    }
+
+    /// Records the rows written to a table without acquiring them first.
+    pub(crate) fn record_writes(&self, catalog: &str, schema: &str, table: &str, rows: usize) {
+        if let Some(write_limiter) = &self.write_limiter {
+            write_limiter.record(TableReference::full(catalog, schema, table), rows as u64, 0);
+        }
+    }
+
+    /// Checks the quotas before creating a table with `columns` columns.
+    /// Always passes if the table exists, whose creation fails or is skipped.
+    ///
+    /// Tables are counted before they are created, so concurrent creations may
+    /// exceed the quota slightly.
+    pub(crate) async fn check_new_table(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table: &str,
+        columns: usize,
+    ) -> Result<()> {
+        let Some(write_limiter) = &self.write_limiter else { return Ok(()) };
+        if self
+            .catalog_manager
+            .table_exist(catalog, schema, table)
+            .await
+            .context(CatalogSnafu)?
+        {
+            return Ok(());
+        }
+
+        let names = self
+            .catalog_manager
+            .table_names(catalog, schema)
+            .await
+            .context(CatalogSnafu)?;
+        let tables = if catalog == DEFAULT_CATALOG_NAME && schema == DEFAULT_SCHEMA_NAME {
+            names
+                .iter()
+                .filter(|name| !SYSTEM_TABLE_NAMES.contains(&name.as_str()))
+                .count()
+        } else {
+            names.len()
+        };
+        write_limiter.check_new_table(catalog, schema, table, tables, columns)
+    }
+
+    /// Checks the quota of columns before adding `new_columns` columns to a
+    /// table. Always passes if the table doesn't exist.
+    pub(crate) async fn check_new_columns(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table: &str,
+        new_columns: usize,
+    ) -> Result<()> {
+        let Some(write_limiter) = &self.write_limiter else { return Ok(()) };
+        let Some(table_ref) = self
+            .catalog_manager
+            .table(catalog, schema, table)
+            .await
+            .context(CatalogSnafu)? else { return Ok(()) };
+
+        let columns = table_ref.schema().num_columns() + new_columns;
+        write_limiter.check_columns(catalog, schema, table, columns)
+    }
+}
@@ -13,16 +13,16 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::iter;
 use std::pin::Pin;
 use std::sync::Arc;
 
-use api::v1::AlterExpr;
 use async_trait::async_trait;
 use catalog::helper::{TableGlobalKey, TableGlobalValue};
 use client::Database;
 use common_error::prelude::BoxedError;
-use common_meta::key::TableRouteKey;
+use common_meta::peer::Peer;
 use common_meta::table_name::TableName;
 use common_query::error::Result as QueryResult;
 use common_query::logical_plan::Expr;
@@ -35,7 +35,6 @@ use common_recordbatch::error::{
 use common_recordbatch::{
     RecordBatch, RecordBatchStreamAdaptor, RecordBatches, SendableRecordBatchStream,
 };
-use common_telemetry::debug;
 use datafusion::execution::context::TaskContext;
 use datafusion::physical_plan::{
     Partitioning, SendableRecordBatchStream as DfSendableRecordBatchStream,
@@ -43,15 +42,14 @@ use datafusion::physical_plan::{
 use datafusion_common::DataFusionError;
 use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
 use futures_util::{Stream, StreamExt};
-use partition::manager::TableRouteCacheInvalidator;
 use partition::splitter::WriteSplitter;
+use session::context::ReadPreference;
 use snafu::prelude::*;
 use store_api::storage::{RegionNumber, ScanRequest};
 use table::error::TableOperationSnafu;
-use table::metadata::{FilterPushDownType, TableInfo, TableInfoRef};
-use table::requests::{AlterKind, AlterTableRequest, DeleteRequest, InsertRequest};
-use table::table::AlterContext;
-use table::Table;
+use table::metadata::{FilterPushDownType, TableInfoRef};
+use table::requests::{DeleteRequest, InsertRequest};
+use table::{Table, TableRef};
 use tokio::sync::RwLock;
 
 use crate::catalog::FrontendCatalogManager;
@@ -69,6 +67,7 @@ pub struct DistTable {
     table_name: TableName,
     table_info: TableInfoRef,
     catalog_manager: Arc<FrontendCatalogManager>,
+    read_preference: ReadPreference,
 }
 
 #[async_trait]
@@ -118,8 +117,8 @@ impl Table for DistTable {
             .find_regions_by_filters(partition_rule, filters)
             .map_err(BoxedError::new)
             .context(TableOperationSnafu)?;
-        let datanodes = partition_manager
-            .find_region_datanodes(&self.table_name, regions)
+        let datanodes = self
+            .find_scan_datanodes(regions)
             .await
             .map_err(BoxedError::new)
             .context(TableOperationSnafu)?;
@@ -166,8 +165,8 @@ impl Table for DistTable {
             .find_regions_by_filters(partition_rule, &request.filters)
             .map_err(BoxedError::new)
             .context(TableOperationSnafu)?;
-        let datanodes = partition_manager
-            .find_region_datanodes(&self.table_name, regions)
+        let datanodes = self
+            .find_scan_datanodes(regions)
             .await
             .map_err(BoxedError::new)
             .context(TableOperationSnafu)?;
@@ -223,13 +222,6 @@ impl Table for DistTable {
         Ok(vec![FilterPushDownType::Inexact; filters.len()])
     }
 
-    async fn alter(&self, context: AlterContext, request: &AlterTableRequest) -> table::Result<()> {
-        self.handle_alter(context, request)
-            .await
-            .map_err(BoxedError::new)
-            .context(TableOperationSnafu)
-    }
-
     async fn delete(&self, request: DeleteRequest) -> table::Result<usize> {
         let partition_manager = self.catalog_manager.partition_manager();
 
@@ -281,6 +273,13 @@ impl Table for DistTable {
         let Output::AffectedRows(rows) = output else { unreachable!() };
         Ok(rows)
     }
+
+    fn with_follower_reads(&self) -> Option<TableRef> {
+        Some(Arc::new(Self {
+            read_preference: ReadPreference::Follower,
+            ..self.clone()
+        }))
+    }
 }
 
 impl DistTable {
@@ -293,7 +292,51 @@ impl DistTable {
             table_name,
             table_info,
             catalog_manager,
+            read_preference: ReadPreference::default(),
+        }
+    }
+
+    /// Finds the datanodes to scan the regions. For follower reads, followers take the
+    /// place of their leaders if every region of the leader has a matched follower.
+    async fn find_scan_datanodes(
+        &self,
+        regions: Vec<RegionNumber>,
+    ) -> Result<HashMap<Peer, Vec<RegionNumber>>> {
+        let partition_manager = self.catalog_manager.partition_manager();
+        let datanodes = partition_manager
+            .find_region_datanodes(&self.table_name, regions)
+            .await
+            .with_context(|_| FindTableRouteSnafu {
+                table_name: self.table_name.to_string(),
+            })?;
+        if self.read_preference == ReadPreference::Leader {
+            return Ok(datanodes);
+        }
+
+        let route = partition_manager
+            .find_table_route(&self.table_name)
+            .await
+            .with_context(|_| FindTableRouteSnafu {
+                table_name: self.table_name.to_string(),
+            })?;
+        let mut scan_datanodes = HashMap::with_capacity(datanodes.len());
+        for (leader, regions) in datanodes {
+            let Some(followers) = route.find_followers_of_leader(&leader) else {
+                let _ = scan_datanodes.insert(leader, regions);
+                continue;
+            };
+            for (follower, follower_regions) in followers {
+                let regions = regions
+                    .iter()
+                    .filter(|region| follower_regions.contains(region))
+                    .copied()
+                    .collect::<Vec<_>>();
+                if !regions.is_empty() {
+                    let _ = scan_datanodes.insert(follower, regions);
+                }
+            }
         }
+        Ok(scan_datanodes)
     }
 
     pub async fn table_global_value(
@@ -313,169 +356,6 @@ impl DistTable {
         })
     }
 
-    async fn set_table_global_value(
-        &self,
-        key: TableGlobalKey,
-        value: TableGlobalValue,
-    ) -> Result<()> {
-        let value = value.as_bytes().context(error::CatalogEntrySerdeSnafu)?;
-        self.catalog_manager
-            .backend()
-            .set(key.to_string().as_bytes(), &value)
-            .await
-            .context(error::CatalogSnafu)
-    }
-
-    async fn delete_table_global_value(&self, key: TableGlobalKey) -> Result<()> {
-        self.catalog_manager
-            .backend()
-            .delete(key.to_string().as_bytes())
-            .await
-            .context(error::CatalogSnafu)
-    }
-
-    async fn move_table_route_value(
-        &self,
-        catalog_name: &str,
-        schema_name: &str,
-        table_id: u32,
-        old_table_name: &str,
-        new_table_name: &str,
-    ) -> Result<()> {
-        let old_key = TableRouteKey {
-            table_id: table_id.into(),
-            catalog_name,
-            schema_name,
-            table_name: old_table_name,
-        }
-        .key();
-
-        let new_key = TableRouteKey {
-            table_id: table_id.into(),
-            catalog_name,
-            schema_name,
-            table_name: new_table_name,
-        }
-        .key();
-
-        self.catalog_manager
-            .backend()
-            .move_value(old_key.as_bytes(), new_key.as_bytes())
-            .await
-            .context(error::CatalogSnafu)?;
-
-        self.catalog_manager
-            .partition_manager()
-            .invalidate_table_route(&TableName {
-                catalog_name: catalog_name.to_string(),
-                schema_name: schema_name.to_string(),
-                table_name: old_table_name.to_string(),
-            })
-            .await;
-
-        Ok(())
-    }
-
-    async fn handle_alter(&self, context: AlterContext, request: &AlterTableRequest) -> Result<()> {
-        let AlterTableRequest {
-            catalog_name,
-            schema_name,
-            table_name,
-            alter_kind,
-            table_id: _table_id,
-        } = request;
-
-        let alter_expr = context
-            .get::<AlterExpr>()
-            .context(error::ContextValueNotFoundSnafu { key: "AlterExpr" })?;
-
-        self.alter_by_expr(alter_expr).await?;
-
-        let table_info = self.table_info();
-        let new_meta = table_info
-            .meta
-            .builder_with_alter_kind(table_name, &request.alter_kind)
-            .context(error::TableSnafu)?
-            .build()
-            .context(error::BuildTableMetaSnafu {
-                table_name: table_name.clone(),
-            })?;
-
-        let mut new_info = TableInfo::clone(&*table_info);
-        new_info.ident.version = table_info.ident.version + 1;
-        new_info.meta = new_meta;
-
-        let key = TableGlobalKey {
-            catalog_name: catalog_name.clone(),
-            schema_name: schema_name.clone(),
-            table_name: table_name.clone(),
-        };
-        let mut value = self
-            .table_global_value(&key)
-            .await?
-            .context(error::TableNotFoundSnafu { table_name })?;
-
-        value.table_info = new_info.into();
-
-        if let AlterKind::RenameTable { new_table_name } = alter_kind {
-            let new_key = TableGlobalKey {
-                catalog_name: catalog_name.clone(),
-                schema_name: schema_name.clone(),
-                table_name: new_table_name.clone(),
-            };
-            self.set_table_global_value(new_key, value).await?;
-            self.delete_table_global_value(key).await?;
-            self.move_table_route_value(
-                catalog_name,
-                schema_name,
-                table_info.ident.table_id,
-                table_name,
-                new_table_name,
-            )
-            .await?;
-            Ok(())
-        } else {
-            self.set_table_global_value(key, value).await
-        }
-    }
-
-    /// Define a `alter_by_expr` instead of impl [`Table::alter`] to avoid redundant conversion between
-    /// [`table::requests::AlterTableRequest`] and [`AlterExpr`].
-    async fn alter_by_expr(&self, expr: &AlterExpr) -> Result<()> {
-        let table_routes = self
-            .catalog_manager
-            .partition_manager()
-            .find_table_route(&self.table_name)
-            .await
-            .with_context(|_| error::FindTableRouteSnafu {
-                table_name: self.table_name.to_string(),
-            })?;
-        let leaders = table_routes.find_leaders();
-        ensure!(
-            !leaders.is_empty(),
-            error::LeaderNotFoundSnafu {
-                table: format!(
-                    "{:?}.{:?}.{}",
-                    expr.catalog_name, expr.schema_name, expr.table_name
-                )
-            }
-        );
-
-        let datanode_clients = self.catalog_manager.datanode_clients();
-        for datanode in leaders {
-            let client = datanode_clients.get_client(&datanode).await;
-            let db = Database::new(&expr.catalog_name, &expr.schema_name, client);
-            debug!("Sending {:?} to {:?}", expr, db);
-            let result = db
-                .alter(expr.clone())
-                .await
-                .context(error::RequestDatanodeSnafu)?;
-            debug!("Alter table result: {:?}", result);
-            // TODO(hl): We should further check and track alter result in some global DDL task tracker
-        }
-        Ok(())
-    }
-
     async fn find_datanode_instances(
         &self,
         regions: &[RegionNumber],
@@ -0,0 +1,572 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Limits of writes to databases and tables.
+//!
+//! [WriteLimiter] throttles the rows and bytes written per second to each
+//! database and table by token buckets, which hold the writes of one second at
+//! most, so short bursts are allowed. Writes beyond the limits are rejected
+//! with [StatusCode::RateLimited](common_error::status_code::StatusCode), which
+//! clients may retry later, regardless of the protocol they are written by.
+//!
+//! The limits are enforced by each frontend independently, so a cluster with
+//! `N` frontends admits up to `N` times the configured rates in total. Sharing
+//! the buckets through the meta store, so that each database and table has the
+//! same limits across the cluster, is left as a follow-up.
+//!
+//! It also enforces the quotas of tables per database and columns per table,
+//! when tables are created or altered, either by DDL or automatically on
+//! insertion. Views are counted as tables, system tables are not. The quotas
+//! are checked before creating or altering tables, so concurrent DDLs may
+//! exceed them slightly.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use common_base::readable_size::ReadableSize;
+use common_catalog::build_db_string;
+use metrics::increment_counter;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use table::engine::TableReference;
+
+use crate::error::{ColumnQuotaExceededSnafu, RateLimitedSnafu, Result, TableQuotaExceededSnafu};
+use crate::metrics::METRIC_WRITE_RATE_LIMITED;
+
+/// Interval to evict the idle buckets.
+const EVICT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Rates of writes of each frontend, 0 means unlimited.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct WriteLimits {
+    pub rows_per_second: u64,
+    pub bytes_per_second: ReadableSize,
+}
+
+impl Default for WriteLimits {
+    fn default() -> Self {
+        Self {
+            rows_per_second: 0,
+            bytes_per_second: ReadableSize(0),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct WriteLimitOptions {
+    pub enable: bool,
+    /// Limits of each database, unless overridden in `databases`.
+    pub database: WriteLimits,
+    /// Limits of each table, unless overridden in `tables`.
+    pub table: WriteLimits,
+    /// Limits of specific databases, keyed by database names, e.g. `public`.
+    pub databases: HashMap<String, WriteLimits>,
+    /// Limits of specific tables, keyed by `<database>.<table>`, e.g. `public.cpu`.
+    pub tables: HashMap<String, WriteLimits>,
+    /// Max number of tables in a database including views, 0 means unlimited.
+    pub max_tables_per_database: usize,
+    /// Max number of columns in a table, 0 means unlimited.
+    pub max_columns_per_table: usize,
+}
+
+/// A token bucket refilled at `rate` tokens per second, up to `rate` tokens.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u64, now: Instant) -> Self {
+        Self {
+            rate: rate as f64,
+            tokens: rate as f64,
+            refilled_at: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.rate);
+        self.refilled_at = now;
+    }
+
+    /// A full bucket admits any amount, so writes larger than the limit of one
+    /// second are delayed instead of being rejected forever.
+    fn admits(&self, amount: u64) -> bool {
+        self.tokens >= amount as f64 || self.tokens >= self.rate
+    }
+
+    /// Tokens may go negative, which makes later writes wait longer.
+    fn take(&mut self, amount: u64) {
+        self.tokens -= amount as f64;
+    }
+
+    fn is_full(&self) -> bool {
+        self.tokens >= self.rate
+    }
+}
+
+/// Buckets of rows and bytes of a database or table, `None` if unlimited.
+#[derive(Debug)]
+struct Buckets {
+    rows: Option<TokenBucket>,
+    bytes: Option<TokenBucket>,
+}
+
+impl Buckets {
+    fn new(limits: &WriteLimits, now: Instant) -> Self {
+        let bucket = |rate| (rate > 0).then(|| TokenBucket::new(rate, now));
+        Self {
+            rows: bucket(limits.rows_per_second),
+            bytes: bucket(limits.bytes_per_second.0),
+        }
+    }
+
+    /// Returns the unit and rate of the first exceeded limit, if any.
+    fn exceeded(&mut self, rows: u64, bytes: u64, now: Instant) -> Option<(&'static str, u64)> {
+        [
+            ("rows", &mut self.rows, rows),
+            ("bytes", &mut self.bytes, bytes),
+        ]
+        .into_iter()
+        .find_map(|(unit, bucket, amount)| {
+            let bucket = bucket.as_mut()?;
+            bucket.refill(now);
+            (!bucket.admits(amount)).then_some((unit, bucket.rate as u64))
+        })
+    }
+
+    fn take(&mut self, rows: u64, bytes: u64, now: Instant) {
+        for (bucket, amount) in [(&mut self.rows, rows), (&mut self.bytes, bytes)] {
+            if let Some(bucket) = bucket {
+                bucket.refill(now);
+                bucket.take(amount);
+            }
+        }
+    }
+
+    /// Whether all buckets are full, in which case they are the same as new
+    /// buckets and can be dropped.
+    fn is_idle(&mut self, now: Instant) -> bool {
+        [&mut self.rows, &mut self.bytes]
+            .into_iter()
+            .flatten()
+            .all(|bucket| {
+                bucket.refill(now);
+                bucket.is_full()
+            })
+    }
+}
+
+/// Writes of a request to a table.
+#[derive(Debug)]
+pub struct TableWrites<'a> {
+    pub table: TableReference<'a>,
+    pub rows: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    databases: HashMap<String, Buckets>,
+    tables: HashMap<String, Buckets>,
+    evicted_at: Instant,
+}
+
+impl LimiterState {
+    /// Drops the idle buckets every [EVICT_INTERVAL], so the buckets of tables
+    /// not written recently, e.g. dropped ones, don't pile up.
+    fn evict_idle(&mut self, now: Instant) {
+        if now.saturating_duration_since(self.evicted_at) < EVICT_INTERVAL {
+            return;
+        }
+        self.databases.retain(|_, buckets| !buckets.is_idle(now));
+        self.tables.retain(|_, buckets| !buckets.is_idle(now));
+        self.evicted_at = now;
+    }
+}
+
+#[derive(Debug)]
+pub struct WriteLimiter {
+    opts: WriteLimitOptions,
+    state: Mutex<LimiterState>,
+}
+
+pub type WriteLimiterRef = Arc<WriteLimiter>;
+
+impl WriteLimiter {
+    pub fn new(opts: &WriteLimitOptions) -> Self {
+        Self {
+            opts: opts.clone(),
+            state: Mutex::new(LimiterState {
+                databases: HashMap::new(),
+                tables: HashMap::new(),
+                evicted_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Acquires all `writes` of a request, or fails if any of them exceeds
+    /// the limits of its table or database, in which case nothing is acquired.
+    pub fn acquire(&self, writes: &[TableWrites]) -> Result<()> {
+        let now = Instant::now();
+        // Writes of the same database or table in a request are summed up.
+        let mut database_writes = HashMap::new();
+        let mut table_writes = HashMap::new();
+        for writes in writes {
+            let database = build_db_string(writes.table.catalog, writes.table.schema);
+            let table = format!("{database}.{}", writes.table.table);
+            let (rows, bytes) = database_writes.entry(database.clone()).or_insert((0, 0));
+            *rows += writes.rows;
+            *bytes += writes.bytes;
+            let (rows, bytes) = table_writes.entry((database, table)).or_insert((0, 0));
+            *rows += writes.rows;
+            *bytes += writes.bytes;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.evict_idle(now);
+        let LimiterState {
+            databases, tables, ..
+        } = &mut *state;
+        for (database, (rows, bytes)) in &database_writes {
+            let buckets = Self::buckets(databases, database, self.database_limits(database), now);
+            if let Some((unit, limit)) = buckets.exceeded(*rows, *bytes, now) {
+                increment_counter!(METRIC_WRITE_RATE_LIMITED, "db" => database.clone());
+                return RateLimitedSnafu {
+                    target: format!("database {database}"),
+                    limit,
+                    unit,
+                }
+                .fail();
+            }
+        }
+        for ((database, table), (rows, bytes)) in &table_writes {
+            let buckets = Self::buckets(tables, table, self.table_limits(table), now);
+            if let Some((unit, limit)) = buckets.exceeded(*rows, *bytes, now) {
+                increment_counter!(METRIC_WRITE_RATE_LIMITED, "db" => database.clone());
+                return RateLimitedSnafu {
+                    target: format!("table {table}"),
+                    limit,
+                    unit,
+                }
+                .fail();
+            }
+        }
+
+        for (database, (rows, bytes)) in database_writes {
+            if let Some(buckets) = databases.get_mut(&database) {
+                buckets.take(rows, bytes, now);
+            }
+        }
+        for ((_, table), (rows, bytes)) in table_writes {
+            if let Some(buckets) = tables.get_mut(&table) {
+                buckets.take(rows, bytes, now);
+            }
+        }
+        Ok(())
+    }
+
+    /// Records writes that have been done without acquiring them first, like
+    /// those of `INSERT INTO ... SELECT` whose rows are unknown in advance.
+    /// Later writes are delayed until the limits catch up with them.
+    pub fn record(&self, table: TableReference, rows: u64, bytes: u64) {
+        let database = build_db_string(table.catalog, table.schema);
+        let table = format!("{database}.{}", table.table);
+        let now = Instant::now();
+
+        let mut state = self.state.lock().unwrap();
+        state.evict_idle(now);
+        let limits = self.database_limits(&database);
+        Self::buckets(&mut state.databases, &database, limits, now).take(rows, bytes, now);
+        let limits = self.table_limits(&table);
+        Self::buckets(&mut state.tables, &table, limits, now).take(rows, bytes, now);
+    }
+
+    fn buckets<'a>(
+        buckets: &'a mut HashMap<String, Buckets>,
+        key: &str,
+        limits: &WriteLimits,
+        now: Instant,
+    ) -> &'a mut Buckets {
+        buckets
+            .entry(key.to_string())
+            .or_insert_with(|| Buckets::new(limits, now))
+    }
+
+    /// Checks whether a new table with `columns` columns can be created in a
+    /// database which has `tables` tables.
+    pub fn check_new_table(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table: &str,
+        tables: usize,
+        columns: usize,
+    ) -> Result<()> {
+        let max_tables = self.opts.max_tables_per_database;
+        ensure!(
+            max_tables == 0 || tables < max_tables,
+            TableQuotaExceededSnafu {
+                database: build_db_string(catalog, schema),
+                max_tables,
+            }
+        );
+        self.check_columns(catalog, schema, table, columns)
+    }
+
+    /// Checks whether a table can have `columns` columns.
+    pub fn check_columns(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table: &str,
+        columns: usize,
+    ) -> Result<()> {
+        let max_columns = self.opts.max_columns_per_table;
+        ensure!(
+            max_columns == 0 || columns <= max_columns,
+            ColumnQuotaExceededSnafu {
+                table: format!("{}.{table}", build_db_string(catalog, schema)),
+                max_columns,
+            }
+        );
+        Ok(())
+    }
+
+    fn database_limits(&self, database: &str) -> &WriteLimits {
+        self.opts
+            .databases
+            .get(database)
+            .unwrap_or(&self.opts.database)
+    }
+
+    fn table_limits(&self, table: &str) -> &WriteLimits {
+        self.opts.tables.get(table).unwrap_or(&self.opts.table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use common_error::prelude::{ErrorExt, StatusCode};
+
+    use super::*;
+
+    fn writes<'a>(
+        catalog: &'a str,
+        schema: &'a str,
+        table: &'a str,
+        rows: u64,
+        bytes: u64,
+    ) -> TableWrites<'a> {
+        TableWrites {
+            table: TableReference::full(catalog, schema, table),
+            rows,
+            bytes,
+        }
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(10, now);
+        assert!(bucket.admits(10));
+        bucket.take(8);
+        assert!(!bucket.admits(3));
+
+        bucket.refill(now + Duration::from_millis(100));
+        assert!(bucket.admits(3));
+        bucket.take(3);
+
+        // A full bucket admits writes larger than its rate.
+        bucket.refill(now + Duration::from_secs(10));
+        assert!(bucket.admits(100));
+        bucket.take(100);
+        bucket.refill(now + Duration::from_secs(15));
+        assert!(!bucket.admits(1));
+        bucket.refill(now + Duration::from_secs(20));
+        assert!(bucket.admits(10));
+    }
+
+    #[test]
+    fn test_write_limiter() {
+        let opts = WriteLimitOptions {
+            enable: true,
+            database: WriteLimits {
+                rows_per_second: 100,
+                bytes_per_second: ReadableSize(0),
+            },
+            table: WriteLimits {
+                rows_per_second: 10,
+                bytes_per_second: ReadableSize(1000),
+            },
+            tables: HashMap::from([(
+                "public.big".to_string(),
+                WriteLimits {
+                    rows_per_second: 0,
+                    bytes_per_second: ReadableSize(0),
+                },
+            )]),
+            ..Default::default()
+        };
+        let limiter = WriteLimiter::new(&opts);
+
+        limiter
+            .acquire(&[writes("greptime", "public", "cpu", 10, 100)])
+            .unwrap();
+        let err = limiter
+            .acquire(&[writes("greptime", "public", "cpu", 1, 100)])
+            .unwrap_err();
+        assert_eq!(StatusCode::RateLimited, err.status_code());
+        assert_eq!(
+            "Writes to table public.cpu exceed the limit of 10 rows per second",
+            err.to_string()
+        );
+        // Other tables are not affected.
+        limiter
+            .acquire(&[writes("greptime", "public", "mem", 5, 2000)])
+            .unwrap();
+        let err = limiter
+            .acquire(&[writes("greptime", "public", "mem", 1, 1)])
+            .unwrap_err();
+        assert!(err.to_string().contains("limit of 1000 bytes"), "{err}");
+
+        // Tables without their own limits are still limited by the database.
+        limiter
+            .acquire(&[writes("greptime", "public", "big", 85, 0)])
+            .unwrap();
+        let err = limiter
+            .acquire(&[writes("greptime", "public", "big", 1, 0)])
+            .unwrap_err();
+        assert!(err.to_string().contains("database public"), "{err}");
+        limiter
+            .acquire(&[writes("greptime", "other", "cpu", 10, 0)])
+            .unwrap();
+
+        limiter.record(TableReference::full("greptime", "other", "disk"), 100, 0);
+        assert!(limiter
+            .acquire(&[writes("greptime", "other", "disk", 1, 0)])
+            .is_err());
+
+        // A request is rejected as a whole, writes to the same table are
+        // summed up.
+        limiter
+            .acquire(&[writes("greptime", "third", "cpu", 1, 0)])
+            .unwrap();
+        let err = limiter
+            .acquire(&[
+                writes("greptime", "third", "cpu", 4, 0),
+                writes("greptime", "third", "mem", 5, 0),
+                writes("greptime", "third", "cpu", 6, 0),
+            ])
+            .unwrap_err();
+        assert!(err.to_string().contains("table third.cpu"), "{err}");
+        limiter
+            .acquire(&[
+                writes("greptime", "third", "cpu", 9, 0),
+                writes("greptime", "third", "mem", 10, 0),
+            ])
+            .unwrap();
+    }
+
+    #[test]
+    fn test_evict_idle() {
+        let opts = WriteLimitOptions {
+            enable: true,
+            table: WriteLimits {
+                rows_per_second: 10,
+                bytes_per_second: ReadableSize(0),
+            },
+            ..Default::default()
+        };
+        let limiter = WriteLimiter::new(&opts);
+        limiter
+            .acquire(&[
+                writes("greptime", "public", "cpu", 10, 0),
+                writes("greptime", "public", "mem", 1, 0),
+            ])
+            .unwrap();
+        limiter.record(TableReference::full("greptime", "public", "disk"), 1000, 0);
+
+        let mut state = limiter.state.lock().unwrap();
+        assert_eq!(3, state.tables.len());
+        // Too early to evict.
+        let now = Instant::now();
+        state.evict_idle(now);
+        assert_eq!(3, state.tables.len());
+        // Buckets refilled are evicted, the overdrawn one is kept.
+        state.evict_idle(now + EVICT_INTERVAL);
+        assert_eq!(1, state.databases.len() + state.tables.len());
+        assert!(state.tables.contains_key("public.disk"));
+    }
+
+    #[test]
+    fn test_quotas() {
+        let opts = WriteLimitOptions {
+            enable: true,
+            max_tables_per_database: 2,
+            max_columns_per_table: 3,
+            ..Default::default()
+        };
+        let limiter = WriteLimiter::new(&opts);
+
+        limiter
+            .check_new_table("greptime", "public", "cpu", 1, 3)
+            .unwrap();
+        let err = limiter
+            .check_new_table("greptime", "public", "cpu", 2, 3)
+            .unwrap_err();
+        assert_eq!(StatusCode::QuotaExceeded, err.status_code());
+        assert_eq!(
+            "Database public exceeds the quota of 2 tables",
+            err.to_string()
+        );
+        let err = limiter
+            .check_new_table("tenant", "public", "cpu", 1, 4)
+            .unwrap_err();
+        assert_eq!(
+            "Table tenant-public.cpu exceeds the quota of 3 columns",
+            err.to_string()
+        );
+
+        assert!(limiter
+            .check_columns("greptime", "public", "cpu", 3)
+            .is_ok());
+        assert!(limiter
+            .check_columns("greptime", "public", "cpu", 4)
+            .is_err());
+
+        // Unlimited by default.
+        let limiter = WriteLimiter::new(&WriteLimitOptions::default());
+        assert!(limiter
+            .check_new_table("greptime", "public", "cpu", 10000, 10000)
+            .is_ok());
+        limiter
+            .acquire(&[writes(
+                "greptime",
+                "public",
+                "cpu",
+                u32::MAX as u64,
+                u32::MAX as u64,
+            )])
+            .unwrap();
+    }
+}
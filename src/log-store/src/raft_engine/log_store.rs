@@ -73,6 +73,21 @@ impl TaskFunction<Error> for PurgeExpiredFilesFunction {
 
 impl RaftEngineLogStore {
     pub async fn try_new(config: LogConfig) -> Result<Self, Error> {
+        let log_store = Self::open(config)?;
+        log_store.start()?;
+        Ok(log_store)
+    }
+
+    /// Opens the log store without the task purging expired files, e.g. to read
+    /// entries of a log directory for inspection.
+    ///
+    /// Opening the log store may still truncate the corrupted tail of log files,
+    /// so the directory shouldn't be used by a running datanode.
+    pub async fn try_new_without_gc(config: LogConfig) -> Result<Self, Error> {
+        Self::open(config)
+    }
+
+    fn open(config: LogConfig) -> Result<Self, Error> {
         // TODO(hl): set according to available disk space
         let raft_engine_config = Config {
             dir: config.log_file_dir.clone(),
@@ -90,13 +105,11 @@ impl RaftEngineLogStore {
             }),
         );
 
-        let log_store = Self {
+        Ok(Self {
             config,
             engine,
             gc_task,
-        };
-        log_store.start()?;
-        Ok(log_store)
+        })
     }
 
     pub fn started(&self) -> bool {
@@ -399,6 +412,18 @@ mod tests {
         assert_eq!(0, namespaces.len());
     }
 
+    #[tokio::test]
+    async fn test_open_logstore_without_gc() {
+        let dir = create_temp_dir("raft-engine-logstore-test");
+        let logstore = RaftEngineLogStore::try_new_without_gc(LogConfig {
+            log_file_dir: dir.path().to_str().unwrap().to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        assert!(!logstore.started());
+    }
+
     #[tokio::test]
     async fn test_manage_namespace() {
         let dir = create_temp_dir("raft-engine-logstore-test");
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod ddl;
 mod heartbeat;
 mod load_balance;
 mod lock;
@@ -20,6 +21,7 @@ mod store;
 
 use api::v1::meta::Role;
 use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
+use common_meta::rpc::ddl::{AllocateTableIdResponse, AlterTableRequest, AlterTableResponse};
 use common_meta::rpc::lock::{LockRequest, LockResponse, UnlockRequest};
 use common_meta::rpc::router::{CreateRequest, DeleteRequest, RouteRequest, RouteResponse};
 use common_meta::rpc::store::{
@@ -29,6 +31,7 @@ use common_meta::rpc::store::{
     RangeRequest, RangeResponse,
 };
 use common_telemetry::info;
+use ddl::Client as DdlClient;
 use heartbeat::Client as HeartbeatClient;
 use lock::Client as LockClient;
 use router::Client as RouterClient;
@@ -49,6 +52,7 @@ pub struct MetaClientBuilder {
     enable_router: bool,
     enable_store: bool,
     enable_lock: bool,
+    enable_ddl: bool,
     channel_manager: Option<ChannelManager>,
 }
 
@@ -89,6 +93,13 @@ impl MetaClientBuilder {
         }
     }
 
+    pub fn enable_ddl(self) -> Self {
+        Self {
+            enable_ddl: true,
+            ..self
+        }
+    }
+
     pub fn channel_manager(self, channel_manager: ChannelManager) -> Self {
         Self {
             channel_manager: Some(channel_manager),
@@ -103,7 +114,12 @@ impl MetaClientBuilder {
             MetaClient::new(self.id)
         };
 
-        if !(self.enable_heartbeat || self.enable_router || self.enable_store || self.enable_lock) {
+        if !(self.enable_heartbeat
+            || self.enable_router
+            || self.enable_store
+            || self.enable_lock
+            || self.enable_ddl)
+        {
             panic!("At least one client needs to be enabled.")
         }
 
@@ -119,7 +135,10 @@ impl MetaClientBuilder {
             client.store = Some(StoreClient::new(self.id, self.role, mgr.clone()));
         }
         if self.enable_lock {
-            client.lock = Some(LockClient::new(self.id, self.role, mgr));
+            client.lock = Some(LockClient::new(self.id, self.role, mgr.clone()));
+        }
+        if self.enable_ddl {
+            client.ddl = Some(DdlClient::new(self.id, self.role, mgr));
         }
 
         client
@@ -134,6 +153,7 @@ pub struct MetaClient {
     router: Option<RouterClient>,
     store: Option<StoreClient>,
     lock: Option<LockClient>,
+    ddl: Option<DdlClient>,
 }
 
 impl MetaClient {
@@ -173,9 +193,13 @@ impl MetaClient {
         }
 
         if let Some(client) = &mut self.lock {
-            client.start(urls).await?;
+            client.start(urls.clone()).await?;
             info!("Lock client started");
         }
+        if let Some(client) = &mut self.ddl {
+            client.start(urls).await?;
+            info!("DDL client started");
+        }
 
         Ok(())
     }
@@ -249,6 +273,27 @@ impl MetaClient {
             .context(ConvertMetaResponseSnafu)
     }
 
+    /// Alters the table in a procedure of `metasrv`, which alters the table
+    /// on `datanode`s and updates its metadata. Returns after the procedure
+    /// is done.
+    pub async fn alter_table(&self, req: AlterTableRequest) -> Result<AlterTableResponse> {
+        self.ddl_client()?
+            .alter_table(req.into())
+            .await?
+            .try_into()
+            .context(ConvertMetaResponseSnafu)
+    }
+
+    /// Allocates a table id from the table id sequence of `metasrv`, which
+    /// the ids of tables created by `metasrv` come from as well.
+    pub async fn allocate_table_id(&self) -> Result<AllocateTableIdResponse> {
+        self.ddl_client()?
+            .allocate_table_id()
+            .await?
+            .try_into()
+            .context(ConvertMetaResponseSnafu)
+    }
+
     /// Range gets the keys in the range from the key-value store.
     pub async fn range(&self, req: RangeRequest) -> Result<RangeResponse> {
         self.store_client()?
@@ -362,6 +407,13 @@ impl MetaClient {
         })
     }
 
+    #[inline]
+    pub fn ddl_client(&self) -> Result<DdlClient> {
+        self.ddl
+            .clone()
+            .context(error::NotStartedSnafu { name: "ddl_client" })
+    }
+
     #[inline]
     pub fn channel_config(&self) -> &ChannelConfig {
         self.channel_manager.config()
@@ -640,16 +692,18 @@ mod tests {
             .add_partition(p1)
             .add_partition(p2);
 
-        let res = client.create_route(req).await.unwrap();
-        assert_eq!(1, res.table_routes.len());
+        // The selected peers are not real Datanodes, so the table can't be created on them.
+        let res = client.create_route(req).await;
+        assert!(res.is_err());
 
+        // The route is only saved after the table is created on Datanodes.
         let req = RouteRequest::new().add_table_name(table_name.clone());
         let res = client.route(req).await.unwrap();
-        assert!(!res.table_routes.is_empty());
+        assert!(res.table_routes.is_empty());
 
         let req = DeleteRequest::new(table_name.clone());
         let res = client.delete_route(req).await;
-        let _ = res.unwrap();
+        assert!(res.is_err());
     }
 
     #[tokio::test]
@@ -18,6 +18,7 @@ use std::sync::Arc;
 use api::v1::meta::lock_client::LockClient;
 use api::v1::meta::{LockRequest, LockResponse, Role, UnlockRequest, UnlockResponse};
 use common_grpc::channel_manager::ChannelManager;
+use common_grpc::tracing_context::traced_request;
 use snafu::{ensure, OptionExt, ResultExt};
 use tokio::sync::RwLock;
 use tonic::transport::Channel;
@@ -128,7 +129,10 @@ impl Inner {
     async fn lock(&self, mut req: LockRequest) -> Result<LockResponse> {
         let mut client = self.random_client()?;
         req.set_header(self.id, self.role);
-        let res = client.lock(req).await.context(error::TonicStatusSnafu)?;
+        let res = client
+            .lock(traced_request(req))
+            .await
+            .context(error::TonicStatusSnafu)?;
 
         Ok(res.into_inner())
     }
@@ -136,7 +140,10 @@ impl Inner {
     async fn unlock(&self, mut req: UnlockRequest) -> Result<UnlockResponse> {
         let mut client = self.random_client()?;
         req.set_header(self.id, self.role);
-        let res = client.unlock(req).await.context(error::TonicStatusSnafu)?;
+        let res = client
+            .unlock(traced_request(req))
+            .await
+            .context(error::TonicStatusSnafu)?;
 
         Ok(res.into_inner())
     }
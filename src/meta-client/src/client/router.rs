@@ -18,6 +18,7 @@ use std::sync::Arc;
 use api::v1::meta::router_client::RouterClient;
 use api::v1::meta::{CreateRequest, DeleteRequest, Role, RouteRequest, RouteResponse};
 use common_grpc::channel_manager::ChannelManager;
+use common_grpc::tracing_context::traced_request;
 use snafu::{ensure, OptionExt, ResultExt};
 use tokio::sync::RwLock;
 use tonic::transport::Channel;
@@ -108,7 +109,10 @@ impl Inner {
     async fn create(&self, mut req: CreateRequest) -> Result<RouteResponse> {
         let mut client = self.random_client()?;
         req.set_header(self.id, self.role);
-        let res = client.create(req).await.context(error::TonicStatusSnafu)?;
+        let res = client
+            .create(traced_request(req))
+            .await
+            .context(error::TonicStatusSnafu)?;
 
         Ok(res.into_inner())
     }
@@ -116,7 +120,10 @@ impl Inner {
     async fn route(&self, mut req: RouteRequest) -> Result<RouteResponse> {
         let mut client = self.random_client()?;
         req.set_header(self.id, self.role);
-        let res = client.route(req).await.context(error::TonicStatusSnafu)?;
+        let res = client
+            .route(traced_request(req))
+            .await
+            .context(error::TonicStatusSnafu)?;
 
         Ok(res.into_inner())
     }
@@ -124,7 +131,10 @@ impl Inner {
     async fn delete(&self, mut req: DeleteRequest) -> Result<RouteResponse> {
         let mut client = self.random_client()?;
         req.set_header(self.id, self.role);
-        let res = client.delete(req).await.context(error::TonicStatusSnafu)?;
+        let res = client
+            .delete(traced_request(req))
+            .await
+            .context(error::TonicStatusSnafu)?;
 
         Ok(res.into_inner())
     }
@@ -23,6 +23,7 @@ use api::v1::meta::{
     RangeRequest, RangeResponse, Role,
 };
 use common_grpc::channel_manager::ChannelManager;
+use common_grpc::tracing_context::traced_request;
 use snafu::{ensure, OptionExt, ResultExt};
 use tokio::sync::RwLock;
 use tonic::transport::Channel;
@@ -141,7 +142,10 @@ impl Inner {
     async fn range(&self, mut req: RangeRequest) -> Result<RangeResponse> {
         let mut client = self.random_client()?;
         req.set_header(self.id, self.role);
-        let res = client.range(req).await.context(error::TonicStatusSnafu)?;
+        let res = client
+            .range(traced_request(req))
+            .await
+            .context(error::TonicStatusSnafu)?;
 
         Ok(res.into_inner())
     }
@@ -149,7 +153,10 @@ impl Inner {
     async fn put(&self, mut req: PutRequest) -> Result<PutResponse> {
         let mut client = self.random_client()?;
         req.set_header(self.id, self.role);
-        let res = client.put(req).await.context(error::TonicStatusSnafu)?;
+        let res = client
+            .put(traced_request(req))
+            .await
+            .context(error::TonicStatusSnafu)?;
 
         Ok(res.into_inner())
     }
@@ -159,7 +166,7 @@ impl Inner {
         req.set_header(self.id, self.role);
 
         let res = client
-            .batch_get(req)
+            .batch_get(traced_request(req))
             .await
             .context(error::TonicStatusSnafu)?;
 
@@ -170,7 +177,7 @@ impl Inner {
         let mut client = self.random_client()?;
         req.set_header(self.id, self.role);
         let res = client
-            .batch_put(req)
+            .batch_put(traced_request(req))
             .await
             .context(error::TonicStatusSnafu)?;
 
@@ -181,7 +188,7 @@ impl Inner {
         let mut client = self.random_client()?;
         req.set_header(self.id, self.role);
         let res = client
-            .batch_delete(req)
+            .batch_delete(traced_request(req))
             .await
             .context(error::TonicStatusSnafu)?;
 
@@ -195,7 +202,7 @@ impl Inner {
         let mut client = self.random_client()?;
         req.set_header(self.id, self.role);
         let res = client
-            .compare_and_put(req)
+            .compare_and_put(traced_request(req))
             .await
             .context(error::TonicStatusSnafu)?;
 
@@ -206,7 +213,7 @@ impl Inner {
         let mut client = self.random_client()?;
         req.set_header(self.id, self.role);
         let res = client
-            .delete_range(req)
+            .delete_range(traced_request(req))
             .await
             .context(error::TonicStatusSnafu)?;
 
@@ -217,7 +224,7 @@ impl Inner {
         let mut client = self.random_client()?;
         req.set_header(self.id, self.role);
         let res = client
-            .move_value(req)
+            .move_value(traced_request(req))
             .await
             .context(error::TonicStatusSnafu)?;
 
@@ -0,0 +1,559 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Region balancer that periodically moves regions from overloaded Datanodes to
+//! underloaded ones, according to the stats reported in Datanode heartbeats.
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use api::v1::meta::{PutRequest, RangeRequest};
+use common_catalog::consts::MITO_ENGINE;
+use common_meta::ident::TableIdent;
+use common_meta::peer::Peer;
+use common_meta::RegionIdent;
+use common_telemetry::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use table::engine::table_id;
+
+use crate::cluster::MetaPeerClientRef;
+use crate::error::Result;
+use crate::handler::node_stat::Stat;
+use crate::keys::StatKey;
+use crate::lease;
+use crate::metasrv::ElectionRef;
+use crate::procedure::region_failover::{RegionFailoverKey, RegionFailoverManager};
+use crate::service::store::kv::KvStoreRef;
+
+/// Key of the switch of the balancer in the kv store, so that the switch
+/// survives restarts and leader changes of metasrv.
+const REGION_BALANCER_ENABLED_KEY: &str = "__meta_region_balancer_enabled";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegionBalancerOptions {
+    /// Whether to move regions automatically. Could be switched at runtime through
+    /// the admin HTTP service, which overrides this option.
+    pub enable: bool,
+    /// Interval between two balance rounds, in seconds.
+    pub interval_secs: u64,
+    /// Max number of region moves emitted in a balance round.
+    pub max_moves_per_round: usize,
+    /// A Datanode is overloaded if its region number or disk usage exceeds the
+    /// average of the cluster by this percentage.
+    pub imbalance_threshold_percent: u64,
+}
+
+impl Default for RegionBalancerOptions {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            interval_secs: 300,
+            max_moves_per_round: 1,
+            imbalance_threshold_percent: 20,
+        }
+    }
+}
+
+/// Load of a region, from the latest heartbeat of its Datanode.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegionLoad {
+    pub region: RegionIdent,
+    pub approximate_bytes: i64,
+}
+
+/// Load of a Datanode, from its latest heartbeat.
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeLoad {
+    pub peer: Peer,
+    pub regions: Vec<RegionLoad>,
+}
+
+impl NodeLoad {
+    fn region_num(&self) -> usize {
+        self.regions.len()
+    }
+
+    fn approximate_bytes(&self) -> i64 {
+        self.regions
+            .iter()
+            .map(|r| r.approximate_bytes.max(0))
+            .sum()
+    }
+
+    fn contains_table(&self, table_ident: &TableIdent) -> bool {
+        self.regions
+            .iter()
+            .any(|r| &r.region.table_ident == table_ident)
+    }
+}
+
+/// A planned move of a region.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegionMove {
+    pub region: RegionIdent,
+    pub from: Peer,
+    pub to: Peer,
+    pub approximate_bytes: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NodeReport {
+    pub cluster_id: u64,
+    pub node_id: u64,
+    pub addr: String,
+    pub region_num: usize,
+    pub approximate_bytes: i64,
+    /// Load relative to the cluster average, the greater of region number and disk usage.
+    pub load: f64,
+}
+
+/// Report of a balance round, moves are only planned but not executed.
+#[derive(Debug, Serialize)]
+pub struct BalanceReport {
+    pub enabled: bool,
+    pub nodes: Vec<NodeReport>,
+    pub moves: Vec<RegionMove>,
+}
+
+/// Average region number and disk usage of Datanodes in a cluster.
+#[derive(Debug, Clone, Copy)]
+struct Average {
+    region_num: f64,
+    approximate_bytes: f64,
+}
+
+impl Average {
+    fn new(nodes: &[NodeLoad]) -> Self {
+        let n = nodes.len().max(1) as f64;
+        Self {
+            region_num: nodes.iter().map(|n| n.region_num()).sum::<usize>() as f64 / n,
+            approximate_bytes: nodes.iter().map(|n| n.approximate_bytes()).sum::<i64>() as f64 / n,
+        }
+    }
+
+    fn load(&self, region_num: usize, approximate_bytes: i64) -> f64 {
+        let region_load = if self.region_num > 0.0 {
+            region_num as f64 / self.region_num
+        } else {
+            0.0
+        };
+        let bytes_load = if self.approximate_bytes > 0.0 {
+            approximate_bytes as f64 / self.approximate_bytes
+        } else {
+            0.0
+        };
+        region_load.max(bytes_load)
+    }
+
+    fn node_load(&self, node: &NodeLoad) -> f64 {
+        self.load(node.region_num(), node.approximate_bytes())
+    }
+}
+
+/// Plans at most `max_moves` region moves between `nodes` of a cluster. Each move goes
+/// from the most loaded Datanode to the least loaded one, and must lower the peak load of
+/// the two. `nodes` are updated as if the moves were done.
+pub(crate) fn plan_moves(
+    nodes: &mut [NodeLoad],
+    max_moves: usize,
+    imbalance_threshold_percent: u64,
+) -> Vec<RegionMove> {
+    let mut moves = Vec::new();
+    if nodes.len() < 2 {
+        return moves;
+    }
+
+    let average = Average::new(nodes);
+    let threshold = 1.0 + imbalance_threshold_percent as f64 / 100.0;
+    let mut moved = HashSet::new();
+
+    while moves.len() < max_moves {
+        let loads = nodes
+            .iter()
+            .map(|n| average.node_load(n))
+            .collect::<Vec<_>>();
+        let (mut from, mut to) = (0, 0);
+        for (i, load) in loads.iter().enumerate() {
+            if *load > loads[from] {
+                from = i;
+            }
+            if *load < loads[to] {
+                to = i;
+            }
+        }
+        if from == to || loads[from] <= threshold {
+            break;
+        }
+
+        let (source, target) = (&nodes[from], &nodes[to]);
+        let mut best: Option<(usize, f64)> = None;
+        for (i, region) in source.regions.iter().enumerate() {
+            // The regions of a table should be distributed on different Datanodes.
+            if target.contains_table(&region.region.table_ident)
+                || moved.contains(&RegionFailoverKey::from(region.region.clone()))
+            {
+                continue;
+            }
+            let bytes = region.approximate_bytes.max(0);
+            let peak = average
+                .load(source.region_num() - 1, source.approximate_bytes() - bytes)
+                .max(average.load(target.region_num() + 1, target.approximate_bytes() + bytes));
+            if peak < loads[from] && best.map(|(_, p)| peak < p).unwrap_or(true) {
+                best = Some((i, peak));
+            }
+        }
+        let Some((i, _)) = best else { break };
+
+        let mut region = nodes[from].regions.remove(i);
+        let _ = moved.insert(RegionFailoverKey::from(region.region.clone()));
+        moves.push(RegionMove {
+            region: region.region.clone(),
+            from: nodes[from].peer.clone(),
+            to: nodes[to].peer.clone(),
+            approximate_bytes: region.approximate_bytes,
+        });
+        region.region.datanode_id = nodes[to].peer.id;
+        nodes[to].regions.push(region);
+    }
+    moves
+}
+
+pub type RegionBalancerRef = Arc<RegionBalancer>;
+
+pub struct RegionBalancer {
+    options: RegionBalancerOptions,
+    kv_store: KvStoreRef,
+    datanode_lease_secs: i64,
+    meta_peer_client: MetaPeerClientRef,
+    election: Option<ElectionRef>,
+    region_failover_manager: Option<Arc<RegionFailoverManager>>,
+}
+
+impl RegionBalancer {
+    pub(crate) fn new(
+        options: RegionBalancerOptions,
+        kv_store: KvStoreRef,
+        datanode_lease_secs: i64,
+        meta_peer_client: MetaPeerClientRef,
+        election: Option<ElectionRef>,
+        region_failover_manager: Option<Arc<RegionFailoverManager>>,
+    ) -> Self {
+        Self {
+            options,
+            kv_store,
+            datanode_lease_secs,
+            meta_peer_client,
+            election,
+            region_failover_manager,
+        }
+    }
+
+    /// Returns whether the balancer is switched on, or the `enable` option if
+    /// it has never been switched.
+    pub async fn is_enabled(&self) -> Result<bool> {
+        let resp = self
+            .kv_store
+            .range(RangeRequest {
+                key: REGION_BALANCER_ENABLED_KEY.as_bytes().to_vec(),
+                ..Default::default()
+            })
+            .await?;
+        Ok(resp
+            .kvs
+            .first()
+            .map(|kv| kv.value == b"true")
+            .unwrap_or(self.options.enable))
+    }
+
+    pub async fn set_enabled(&self, enabled: bool) -> Result<()> {
+        let _ = self
+            .kv_store
+            .put(PutRequest {
+                key: REGION_BALANCER_ENABLED_KEY.as_bytes().to_vec(),
+                value: enabled.to_string().into_bytes(),
+                ..Default::default()
+            })
+            .await?;
+        info!(
+            "Region balancer is {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+        Ok(())
+    }
+
+    /// Starts balancing regions periodically on the leader.
+    pub(crate) fn start(self: &Arc<Self>) {
+        let balancer = self.clone();
+        let _handle = common_runtime::spawn_bg(async move {
+            let interval = Duration::from_secs(balancer.options.interval_secs.max(1));
+            loop {
+                tokio::time::sleep(interval).await;
+
+                let is_leader = balancer
+                    .election
+                    .as_ref()
+                    .map(|x| x.is_leader())
+                    .unwrap_or(true);
+                if !is_leader {
+                    continue;
+                }
+                match balancer.is_enabled().await {
+                    Ok(true) => (),
+                    Ok(false) => continue,
+                    Err(e) => {
+                        error!(e; "Failed to check whether region balancer is enabled");
+                        continue;
+                    }
+                }
+                if let Err(e) = balancer.balance().await {
+                    error!(e; "Failed to balance regions");
+                }
+            }
+        });
+    }
+
+    /// Plans region moves without executing them.
+    pub async fn dry_run(&self) -> Result<BalanceReport> {
+        let clusters = self.collect_loads().await?;
+
+        let mut nodes = Vec::new();
+        let mut moves = Vec::new();
+        for (cluster_id, mut loads) in clusters {
+            let average = Average::new(&loads);
+            nodes.extend(loads.iter().map(|n| NodeReport {
+                cluster_id,
+                node_id: n.peer.id,
+                addr: n.peer.addr.clone(),
+                region_num: n.region_num(),
+                approximate_bytes: n.approximate_bytes(),
+                load: average.node_load(n),
+            }));
+            moves.extend(plan_moves(
+                &mut loads,
+                self.options.max_moves_per_round,
+                self.options.imbalance_threshold_percent,
+            ));
+        }
+
+        Ok(BalanceReport {
+            enabled: self.is_enabled().await?,
+            nodes,
+            moves,
+        })
+    }
+
+    /// Runs a balance round, moving regions by the region failover procedure. A
+    /// failed move doesn't stop the others, it may be planned again next round.
+    async fn balance(&self) -> Result<()> {
+        let Some(region_failover_manager) = &self.region_failover_manager else {
+            warn!("Region balancer is enabled but region failover is disabled, skip moving regions");
+            return Ok(());
+        };
+
+        let report = self.dry_run().await?;
+        for RegionMove { region, to, .. } in report.moves {
+            let key = RegionFailoverKey::from(region.clone());
+            if region_failover_manager.is_region_failover_running(&key) {
+                continue;
+            }
+            info!("Moving region {region} to Datanode {to:?} for balance");
+            if let Err(e) = region_failover_manager
+                .do_region_migration(&region, to.clone())
+                .await
+            {
+                error!(e; "Failed to move region {region} to Datanode {to:?}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Collects loads of alive Datanodes, grouped by cluster.
+    async fn collect_loads(&self) -> Result<BTreeMap<u64, Vec<NodeLoad>>> {
+        let stat_kvs = self.meta_peer_client.get_all_dn_stat_kvs().await?;
+        let cluster_ids = stat_kvs
+            .keys()
+            .map(|k| k.cluster_id)
+            .collect::<HashSet<_>>();
+
+        let mut clusters = BTreeMap::new();
+        for cluster_id in cluster_ids {
+            let lease_kvs = lease::alive_datanodes(
+                cluster_id,
+                &self.meta_peer_client,
+                self.datanode_lease_secs,
+            )
+            .await?;
+
+            let mut loads = lease_kvs
+                .into_iter()
+                .map(|(lease_key, lease_value)| {
+                    let stat_key: StatKey = (&lease_key).into();
+                    let regions = stat_kvs
+                        .get(&stat_key)
+                        .and_then(|stat_value| stat_value.stats.last())
+                        .map(region_loads)
+                        .unwrap_or_default();
+                    NodeLoad {
+                        peer: Peer {
+                            id: lease_key.node_id,
+                            addr: lease_value.node_addr,
+                        },
+                        regions,
+                    }
+                })
+                .collect::<Vec<_>>();
+            loads.sort_by_key(|n| n.peer.id);
+            let _ = clusters.insert(cluster_id, loads);
+        }
+        Ok(clusters)
+    }
+}
+
+fn region_loads(stat: &Stat) -> Vec<RegionLoad> {
+    stat.region_stats
+        .iter()
+        .map(|x| RegionLoad {
+            region: RegionIdent {
+                cluster_id: stat.cluster_id,
+                datanode_id: stat.id,
+                table_ident: TableIdent {
+                    catalog: x.catalog.clone(),
+                    schema: x.schema.clone(),
+                    table: x.table.clone(),
+                    table_id: table_id(x.id),
+                    // TODO(#1583): Use the actual table engine.
+                    engine: MITO_ENGINE.to_string(),
+                },
+                region_number: x.id as u32,
+            },
+            approximate_bytes: x.approximate_bytes,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cluster::MetaPeerClientBuilder;
+    use crate::service::store::memory::MemStore;
+
+    fn new_node_load(id: u64, regions: &[(&str, u32)]) -> NodeLoad {
+        NodeLoad {
+            peer: Peer {
+                id,
+                addr: format!("127.0.0.1:{}", 4100 + id),
+            },
+            regions: regions
+                .iter()
+                .map(|(table, region_number)| RegionLoad {
+                    region: RegionIdent {
+                        cluster_id: 0,
+                        datanode_id: id,
+                        table_ident: TableIdent {
+                            catalog: "greptime".to_string(),
+                            schema: "public".to_string(),
+                            table: table.to_string(),
+                            table_id: 1024,
+                            engine: MITO_ENGINE.to_string(),
+                        },
+                        region_number: *region_number,
+                    },
+                    approximate_bytes: 0,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_plan_moves() {
+        let mut nodes = vec![
+            new_node_load(1, &[("a", 0), ("b", 0), ("c", 0), ("d", 0)]),
+            new_node_load(2, &[]),
+            new_node_load(3, &[]),
+        ];
+        let moves = plan_moves(&mut nodes, 10, 20);
+        assert_eq!(2, moves.len());
+        assert!(moves.iter().all(|m| m.from.id == 1));
+        let targets = moves.iter().map(|m| m.to.id).collect::<HashSet<_>>();
+        assert_eq!(HashSet::from([2, 3]), targets);
+        assert_eq!(
+            vec![2, 1, 1],
+            nodes.iter().map(|n| n.region_num()).collect::<Vec<_>>()
+        );
+        assert!(nodes[1].regions.iter().all(|r| r.region.datanode_id == 2));
+
+        // Rate limited by max moves.
+        let mut nodes = vec![
+            new_node_load(1, &[("a", 0), ("b", 0), ("c", 0), ("d", 0)]),
+            new_node_load(2, &[]),
+            new_node_load(3, &[]),
+        ];
+        assert_eq!(1, plan_moves(&mut nodes, 1, 20).len());
+    }
+
+    #[tokio::test]
+    async fn test_switch_balancer() {
+        let kv_store = Arc::new(MemStore::new());
+        let meta_peer_client = MetaPeerClientBuilder::default()
+            .election(None)
+            .in_memory(Arc::new(MemStore::new()))
+            .build()
+            .map(Arc::new)
+            .unwrap();
+        let new_balancer = || {
+            RegionBalancer::new(
+                RegionBalancerOptions::default(),
+                kv_store.clone(),
+                10,
+                meta_peer_client.clone(),
+                None,
+                None,
+            )
+        };
+
+        let balancer = new_balancer();
+        assert!(!balancer.is_enabled().await.unwrap());
+        balancer.set_enabled(true).await.unwrap();
+        assert!(balancer.is_enabled().await.unwrap());
+
+        // The switch is persisted, e.g. for a new leader.
+        let balancer = new_balancer();
+        assert!(balancer.is_enabled().await.unwrap());
+        balancer.set_enabled(false).await.unwrap();
+        assert!(!new_balancer().is_enabled().await.unwrap());
+    }
+
+    #[test]
+    fn test_plan_no_moves() {
+        // Balanced.
+        let mut nodes = vec![
+            new_node_load(1, &[("a", 0), ("b", 0)]),
+            new_node_load(2, &[("c", 0), ("d", 0)]),
+        ];
+        assert!(plan_moves(&mut nodes, 10, 20).is_empty());
+
+        // Regions of a table should not be placed on the same Datanode.
+        let mut nodes = vec![
+            new_node_load(1, &[("a", 0), ("a", 1), ("a", 2)]),
+            new_node_load(2, &[("a", 3)]),
+        ];
+        assert!(plan_moves(&mut nodes, 10, 20).is_empty());
+
+        // Single Datanode.
+        let mut nodes = vec![new_node_load(1, &[("a", 0), ("b", 0)])];
+        assert!(plan_moves(&mut nodes, 10, 20).is_empty());
+    }
+}
@@ -15,10 +15,12 @@
 use std::sync::Arc;
 
 use api::v1::meta::cluster_server::ClusterServer;
+use api::v1::meta::ddl::ddl_task_server::DdlTaskServer;
 use api::v1::meta::heartbeat_server::HeartbeatServer;
 use api::v1::meta::lock_server::LockServer;
 use api::v1::meta::router_server::RouterServer;
 use api::v1::meta::store_server::StoreServer;
+use common_grpc::tracing_context::TracingContextLayer;
 use etcd_client::Client;
 use servers::http::{HttpServer, HttpServerBuilder};
 use servers::metrics_handler::MetricsHandler;
@@ -29,6 +31,7 @@ use tokio::select;
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio_stream::wrappers::TcpListenerStream;
 use tonic::transport::server::Router;
+use tower::layer::util::{Identity, Stack};
 
 use crate::election::etcd::EtcdElection;
 use crate::lock::etcd::EtcdLock;
@@ -121,7 +124,7 @@ impl MetaSrvInstance {
 
 pub async fn bootstrap_meta_srv_with_router(
     bind_addr: &str,
-    router: Router,
+    router: MetaSrvRouter,
     signal: &mut Receiver<()>,
 ) -> Result<()> {
     let listener = TcpListener::bind(bind_addr)
@@ -139,14 +142,20 @@ pub async fn bootstrap_meta_srv_with_router(
     Ok(())
 }
 
-pub fn router(meta_srv: MetaSrv) -> Router {
+/// Router of the metasrv gRPC services, which handles each request in a span continuing
+/// the tracing context of the caller.
+pub type MetaSrvRouter = Router<Stack<TracingContextLayer, Identity>>;
+
+pub fn router(meta_srv: MetaSrv) -> MetaSrvRouter {
     tonic::transport::Server::builder()
         .accept_http1(true) // for admin services
+        .layer(TracingContextLayer)
         .add_service(HeartbeatServer::new(meta_srv.clone()))
         .add_service(RouterServer::new(meta_srv.clone()))
         .add_service(StoreServer::new(meta_srv.clone()))
         .add_service(ClusterServer::new(meta_srv.clone()))
         .add_service(LockServer::new(meta_srv.clone()))
+        .add_service(DdlTaskServer::new(meta_srv.clone()))
         .add_service(admin::make_admin_service(meta_srv))
 }
 
@@ -0,0 +1,202 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use api::v1::meta::{MailboxMessage, TableRouteValue};
+use api::v1::AlterExpr;
+use catalog::helper::TableGlobalValue;
+use client::client_manager::DatanodeClients;
+use common_meta::ident::TableIdent;
+use common_meta::instruction::Instruction;
+use common_meta::table_name::TableName;
+use common_meta::ClusterId;
+use common_procedure::{watcher, ProcedureManagerRef, ProcedureWithId};
+use common_telemetry::info;
+use snafu::ResultExt;
+
+use crate::error::{
+    self, RegisterProcedureLoaderSnafu, Result, SubmitProcedureSnafu, WaitProcedureSnafu,
+};
+use crate::procedure::alter_table::AlterTableProcedure;
+use crate::procedure::create_table::CreateTableProcedure;
+use crate::procedure::drop_table::DropTableProcedure;
+use crate::service::mailbox::{BroadcastChannel, MailboxRef};
+use crate::service::store::kv::KvStoreRef;
+
+pub type DdlManagerRef = Arc<DdlManager>;
+
+/// Runs CREATE TABLE, ALTER TABLE and DROP TABLE of distributed tables as
+/// procedures on the leader, so that they are resumed by the new leader after
+/// failover instead of leaving the metadata and the regions on Datanodes
+/// inconsistent.
+pub struct DdlManager {
+    procedure_manager: ProcedureManagerRef,
+    context: DdlContext,
+}
+
+/// The context shared by the DDL procedures.
+#[derive(Clone)]
+pub struct DdlContext {
+    pub kv_store: KvStoreRef,
+    pub datanode_clients: Arc<DatanodeClients>,
+    pub mailbox: MailboxRef,
+    pub server_addr: String,
+}
+
+impl DdlContext {
+    /// Broadcasts the invalidate table cache message to Frontends, so that they
+    /// don't serve the stale table after the DDL.
+    pub(crate) async fn invalidate_table_cache(&self, table_ident: TableIdent) -> Result<()> {
+        let instruction = Instruction::InvalidateTableCache(table_ident);
+
+        let msg = &MailboxMessage::json_message(
+            "Invalidate Table Cache",
+            &format!("Metasrv@{}", self.server_addr),
+            "Frontend broadcast",
+            common_time::util::current_time_millis(),
+            &instruction,
+        )
+        .with_context(|_| error::SerializeToJsonSnafu {
+            input: instruction.to_string(),
+        })?;
+
+        self.mailbox
+            .broadcast(&BroadcastChannel::Frontend, msg)
+            .await
+    }
+}
+
+impl DdlManager {
+    pub(crate) fn new(procedure_manager: ProcedureManagerRef, context: DdlContext) -> Self {
+        Self {
+            procedure_manager,
+            context,
+        }
+    }
+
+    /// Registers the loaders of the DDL procedures, it must be called before the
+    /// procedures are recovered.
+    pub(crate) fn try_start(&self) -> Result<()> {
+        self.context.datanode_clients.start();
+
+        let context = self.context.clone();
+        self.procedure_manager
+            .register_loader(
+                CreateTableProcedure::TYPE_NAME,
+                Box::new(move |json| {
+                    let context = context.clone();
+                    CreateTableProcedure::from_json(json, context).map(|p| Box::new(p) as _)
+                }),
+            )
+            .context(RegisterProcedureLoaderSnafu {
+                type_name: CreateTableProcedure::TYPE_NAME,
+            })?;
+
+        let context = self.context.clone();
+        self.procedure_manager
+            .register_loader(
+                DropTableProcedure::TYPE_NAME,
+                Box::new(move |json| {
+                    let context = context.clone();
+                    DropTableProcedure::from_json(json, context).map(|p| Box::new(p) as _)
+                }),
+            )
+            .context(RegisterProcedureLoaderSnafu {
+                type_name: DropTableProcedure::TYPE_NAME,
+            })?;
+
+        let context = self.context.clone();
+        self.procedure_manager
+            .register_loader(
+                AlterTableProcedure::TYPE_NAME,
+                Box::new(move |json| {
+                    let context = context.clone();
+                    AlterTableProcedure::from_json(json, context).map(|p| Box::new(p) as _)
+                }),
+            )
+            .context(RegisterProcedureLoaderSnafu {
+                type_name: AlterTableProcedure::TYPE_NAME,
+            })
+    }
+
+    /// Creates the table on the Datanodes of its route, then saves its metadata.
+    /// Returns after the procedure is done.
+    pub(crate) async fn submit_create_table_task(
+        &self,
+        cluster_id: ClusterId,
+        table_route_value: TableRouteValue,
+        table_global_value: TableGlobalValue,
+    ) -> Result<()> {
+        let procedure = CreateTableProcedure::new(
+            cluster_id,
+            table_route_value,
+            table_global_value,
+            self.context.clone(),
+        );
+        self.submit_procedure(ProcedureWithId::with_random_id(Box::new(procedure)))
+            .await
+    }
+
+    /// Alters the table on the Datanodes of its route, then updates its metadata.
+    /// Returns after the procedure is done.
+    pub(crate) async fn submit_alter_table_task(
+        &self,
+        cluster_id: ClusterId,
+        alter_expr: AlterExpr,
+    ) -> Result<()> {
+        let procedure = AlterTableProcedure::new(cluster_id, alter_expr, self.context.clone());
+        self.submit_procedure(ProcedureWithId::with_random_id(Box::new(procedure)))
+            .await
+    }
+
+    /// Removes the metadata of the table, then drops it on the Datanodes of its
+    /// route. Returns after the procedure is done.
+    pub(crate) async fn submit_drop_table_task(
+        &self,
+        cluster_id: ClusterId,
+        table_name: TableName,
+        table_route_value: TableRouteValue,
+        table_global_value: TableGlobalValue,
+    ) -> Result<()> {
+        let procedure = DropTableProcedure::new(
+            cluster_id,
+            table_name,
+            table_route_value,
+            table_global_value,
+            self.context.clone(),
+        );
+        self.submit_procedure(ProcedureWithId::with_random_id(Box::new(procedure)))
+            .await
+    }
+
+    async fn submit_procedure(&self, procedure_with_id: ProcedureWithId) -> Result<()> {
+        let procedure_id = procedure_with_id.id;
+        let type_name = procedure_with_id.procedure.type_name().to_string();
+        info!("Submitting DDL procedure {type_name} {procedure_id}");
+
+        let mut watcher = self
+            .procedure_manager
+            .submit(procedure_with_id)
+            .await
+            .context(SubmitProcedureSnafu)?;
+
+        watcher::wait(&mut watcher)
+            .await
+            .context(WaitProcedureSnafu)?;
+
+        info!("DDL procedure {type_name} {procedure_id} is finished successfully");
+        Ok(())
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use common_error::prelude::*;
+use common_meta::peer::Peer;
 use snafu::Location;
 use tokio::sync::mpsc::error::SendError;
 use tonic::codegen::http;
@@ -322,6 +323,73 @@ pub enum Error {
         source: common_procedure::error::Error,
     },
 
+    #[snafu(display("Failed to submit procedure, source: {source}"))]
+    SubmitProcedure {
+        location: Location,
+        source: common_procedure::Error,
+    },
+
+    #[snafu(display("Failed to wait procedure done, source: {source}"))]
+    WaitProcedure {
+        location: Location,
+        source: common_procedure::Error,
+    },
+
+    #[snafu(display("Failed to request Datanode {}, source: {}", peer, source))]
+    RequestDatanode {
+        peer: Peer,
+        location: Location,
+        source: client::Error,
+    },
+
+    #[snafu(display(
+        "Failed to build create table expr for table: {}, source: {}",
+        table_name,
+        source
+    ))]
+    BuildCreateTableExpr {
+        table_name: String,
+        location: Location,
+        source: api::error::Error,
+    },
+
+    #[snafu(display("Failed to decode {} of the DDL request, source: {}", field, source))]
+    DecodeDdlRequest {
+        field: String,
+        location: Location,
+        source: prost::DecodeError,
+    },
+
+    #[snafu(display("Failed to convert alter expr to request, source: {}", source))]
+    ConvertAlterExpr {
+        location: Location,
+        source: common_grpc_expr::error::Error,
+    },
+
+    #[snafu(display("Failed to convert raw table info, source: {}", source))]
+    ConvertRawTableInfo {
+        location: Location,
+        source: table::metadata::ConvertError,
+    },
+
+    #[snafu(display("Failed to alter table info of {}, source: {}", table_name, source))]
+    AlterTableInfo {
+        table_name: String,
+        location: Location,
+        source: table::error::Error,
+    },
+
+    #[snafu(display(
+        "Failed to build table meta for table: {}, source: {}",
+        table_name,
+        source
+    ))]
+    BuildTableMeta {
+        table_name: String,
+        location: Location,
+        source: table::metadata::TableMetaBuilderError,
+    },
+
     #[snafu(display("Failed to find failover candidates for region: {}", failed_region))]
     RegionFailoverCandidatesNotFound {
         failed_region: String,
@@ -447,7 +515,17 @@ impl ErrorExt for Error {
 
             Error::RegionFailoverCandidatesNotFound { .. } => StatusCode::RuntimeResourcesExhausted,
 
-            Error::RegisterProcedureLoader { source, .. } => source.status_code(),
+            Error::RegisterProcedureLoader { source, .. }
+            | Error::SubmitProcedure { source, .. }
+            | Error::WaitProcedure { source, .. } => source.status_code(),
+            Error::RequestDatanode { source, .. } => source.status_code(),
+            Error::BuildCreateTableExpr { source, .. } => source.status_code(),
+            Error::DecodeDdlRequest { .. } => StatusCode::InvalidArguments,
+            Error::ConvertAlterExpr { source, .. } => source.status_code(),
+            Error::ConvertRawTableInfo { .. } | Error::BuildTableMeta { .. } => {
+                StatusCode::Unexpected
+            }
+            Error::AlterTableInfo { source, .. } => source.status_code(),
             Error::TableRouteConversion { source, .. } | Error::ConvertProtoData { source, .. } => {
                 source.status_code()
             }
@@ -49,6 +49,7 @@ use crate::service::mailbox::{
 mod check_leader_handler;
 mod collect_stats_handler;
 pub(crate) mod failure_handler;
+pub(crate) mod follower_region_handler;
 mod keep_lease_handler;
 pub mod mailbox_handler;
 pub mod node_stat;
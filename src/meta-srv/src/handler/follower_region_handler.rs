@@ -0,0 +1,277 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use api::v1::meta::{HeartbeatRequest, MailboxMessage, Peer, Role, TableRouteValue};
+use async_trait::async_trait;
+use common_catalog::consts::MITO_ENGINE;
+use common_meta::ident::TableIdent;
+use common_meta::instruction::{Instruction, InstructionReply, SimpleReply};
+use common_meta::key::TableRouteKey;
+use common_meta::RegionIdent;
+use common_telemetry::{error, info, warn};
+use dashmap::DashMap;
+use snafu::ResultExt;
+use store_api::storage::{RegionId, RegionNumber};
+use table::engine::{region_id, region_number, table_id};
+
+use crate::error::{Result, SerializeToJsonSnafu};
+use crate::handler::{HeartbeatAccumulator, HeartbeatHandler, HeartbeatMailbox};
+use crate::metasrv::Context;
+use crate::service::mailbox::Channel;
+use crate::table_routes;
+
+/// Timeout of opening a follower region, the region will be opened again after it.
+const OPEN_FOLLOWER_REGION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Opens the followers of the regions led by the datanode in the heartbeat, if the
+/// followers in the table route haven't reported them yet.
+#[derive(Default)]
+pub(crate) struct FollowerRegionHandler {
+    /// Follower regions reported by each datanode.
+    followed_regions: DashMap<u64, HashSet<RegionId>>,
+    /// Follower regions being opened, keyed by the datanode id and the region id.
+    opening_regions: DashMap<(u64, RegionId), Instant>,
+}
+
+#[async_trait]
+impl HeartbeatHandler for FollowerRegionHandler {
+    fn is_acceptable(&self, role: Role) -> bool {
+        role == Role::Datanode
+    }
+
+    async fn handle(
+        &self,
+        _: &HeartbeatRequest,
+        ctx: &mut Context,
+        acc: &mut HeartbeatAccumulator,
+    ) -> Result<()> {
+        let Some(stat) = acc.stat.as_ref() else { return Ok(()) };
+
+        let followed = stat
+            .follower_region_stats
+            .iter()
+            .map(|x| x.id)
+            .collect::<HashSet<_>>();
+        self.opening_regions.retain(|(datanode_id, region_id), _| {
+            *datanode_id != stat.id || !followed.contains(region_id)
+        });
+        let _ = self.followed_regions.insert(stat.id, followed);
+
+        let mut leader_regions: HashMap<TableIdent, HashSet<RegionNumber>> = HashMap::new();
+        for x in &stat.region_stats {
+            let table_ident = TableIdent {
+                catalog: x.catalog.clone(),
+                schema: x.schema.clone(),
+                table: x.table.clone(),
+                table_id: table_id(x.id),
+                // TODO(#1583): Use the actual table engine.
+                engine: MITO_ENGINE.to_string(),
+            };
+            let _ = leader_regions
+                .entry(table_ident)
+                .or_default()
+                .insert(region_number(x.id));
+        }
+
+        for (table_ident, regions) in leader_regions {
+            let key = TableRouteKey {
+                table_id: table_ident.table_id as u64,
+                catalog_name: &table_ident.catalog,
+                schema_name: &table_ident.schema,
+                table_name: &table_ident.table,
+            };
+            let table_route_value =
+                match table_routes::get_table_route_value(&ctx.kv_store, &key).await {
+                    Ok(value) => value,
+                    Err(e) => {
+                        warn!("Failed to get table route of {}, error: {}", table_ident, e);
+                        continue;
+                    }
+                };
+
+            for (follower, region_number) in
+                self.missing_followers(stat.id, &table_ident, &regions, &table_route_value)
+            {
+                let region_ident = RegionIdent {
+                    cluster_id: stat.cluster_id,
+                    datanode_id: follower.id,
+                    table_ident: table_ident.clone(),
+                    region_number,
+                };
+                self.open_follower_region(ctx, &follower, region_ident)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FollowerRegionHandler {
+    /// Returns the followers in the table route that neither follow nor are opening the
+    /// `regions` led by the datanode.
+    fn missing_followers(
+        &self,
+        datanode_id: u64,
+        table_ident: &TableIdent,
+        regions: &HashSet<RegionNumber>,
+        table_route_value: &TableRouteValue,
+    ) -> Vec<(Peer, RegionNumber)> {
+        let Some(table_route) = &table_route_value.table_route else { return vec![] };
+        let peers = &table_route_value.peers;
+
+        let mut missing = Vec::new();
+        for region_route in &table_route.region_routes {
+            let Some(region) = &region_route.region else { continue };
+            let region_number = region.id as RegionNumber;
+            let leader = peers.get(region_route.leader_peer_index as usize);
+            // Skips the regions whose routes are out of date.
+            if !regions.contains(&region_number) || leader.map(|x| x.id) != Some(datanode_id) {
+                continue;
+            }
+
+            let region_id = region_id(table_ident.table_id, region_number);
+            for index in &region_route.follower_peer_indexes {
+                let Some(follower) = peers.get(*index as usize) else { continue };
+                let followed = self
+                    .followed_regions
+                    .get(&follower.id)
+                    .map(|x| x.contains(&region_id))
+                    .unwrap_or(false);
+                let opening = self
+                    .opening_regions
+                    .get(&(follower.id, region_id))
+                    .map(|x| x.elapsed() < OPEN_FOLLOWER_REGION_TIMEOUT)
+                    .unwrap_or(false);
+                if !followed && !opening {
+                    missing.push((follower.clone(), region_number));
+                }
+            }
+        }
+        missing
+    }
+
+    async fn open_follower_region(
+        &self,
+        ctx: &Context,
+        follower: &Peer,
+        region_ident: RegionIdent,
+    ) -> Result<()> {
+        let region_id = region_id(
+            region_ident.table_ident.table_id,
+            region_ident.region_number,
+        );
+        let _ = self
+            .opening_regions
+            .insert((follower.id, region_id), Instant::now());
+
+        let instruction = Instruction::OpenFollowerRegion(region_ident.clone());
+        let msg = MailboxMessage::json_message(
+            "Open Follower Region",
+            &format!("Metasrv@{}", ctx.server_addr),
+            &format!("Datanode-(id={}, addr={})", follower.id, follower.addr),
+            common_time::util::current_time_millis(),
+            &instruction,
+        )
+        .with_context(|_| SerializeToJsonSnafu {
+            input: instruction.to_string(),
+        })?;
+        let receiver = ctx
+            .mailbox
+            .send(
+                &Channel::Datanode(follower.id),
+                msg,
+                OPEN_FOLLOWER_REGION_TIMEOUT,
+            )
+            .await?;
+
+        let _handle = common_runtime::spawn_bg(async move {
+            let reply = match receiver.await {
+                Ok(Ok(msg)) => HeartbeatMailbox::json_reply(&msg),
+                Ok(Err(e)) | Err(e) => Err(e),
+            };
+            match reply {
+                Ok(InstructionReply::OpenFollowerRegion(SimpleReply { result: true, .. })) => {
+                    info!("Opened follower region {}", region_ident);
+                }
+                Ok(reply) => {
+                    warn!(
+                        "Failed to open follower region {}, reply: {}",
+                        region_ident, reply
+                    );
+                }
+                Err(e) => {
+                    error!(e; "Failed to open follower region {}", region_ident);
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+
+    use super::*;
+    use crate::service::store::kv::KvStoreRef;
+    use crate::service::store::memory::MemStore;
+    use crate::table_routes::tests::prepare_table_route_value;
+
+    #[tokio::test]
+    async fn test_missing_followers() {
+        let kv_store = std::sync::Arc::new(MemStore::new()) as KvStoreRef;
+        let (_, mut table_route_value) = prepare_table_route_value(&kv_store, "my_table").await;
+        // region routes:
+        // region number => leader node + [follower nodes]
+        // 1 => 1 + [2, 3]
+        // 2 => 1 + [2]
+        // 3 => 2
+        // 4 => 3
+        let table_route = table_route_value.table_route.as_mut().unwrap();
+        table_route.region_routes[0].follower_peer_indexes = vec![1, 2];
+        table_route.region_routes[1].follower_peer_indexes = vec![1];
+
+        let table_ident = TableIdent {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: DEFAULT_SCHEMA_NAME.to_string(),
+            table: "my_table".to_string(),
+            table_id: 1,
+            engine: MITO_ENGINE.to_string(),
+        };
+        let handler = FollowerRegionHandler::default();
+        let _ = handler
+            .followed_regions
+            .insert(3, HashSet::from([region_id(1, 1)]));
+        let _ = handler
+            .opening_regions
+            .insert((2, region_id(1, 2)), Instant::now());
+
+        let regions = HashSet::from([1, 2]);
+        let missing = handler
+            .missing_followers(1, &table_ident, &regions, &table_route_value)
+            .into_iter()
+            .map(|(peer, region_number)| (peer.id, region_number))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(2, 1)], missing);
+
+        // The routes say regions 1 and 2 are led by datanode 1.
+        assert!(handler
+            .missing_followers(2, &table_ident, &regions, &table_route_value)
+            .is_empty());
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use api::v1::meta::HeartbeatRequest;
+use common_meta::heartbeat::{FOLLOWER_REGION_ROLE, REGION_ROLE_ATTR};
 use common_time::util as time_util;
 use serde::{Deserialize, Serialize};
 
@@ -42,6 +43,10 @@ pub struct Stat {
     pub write_io_rate: f64,
     /// Region stats on this node
     pub region_stats: Vec<RegionStat>,
+    /// Stats of the read-only follower regions on this node, which are not counted in
+    /// `region_stats`.
+    #[serde(default)]
+    pub follower_region_stats: Vec<RegionStat>,
     // The node epoch is used to check whether the node has restarted or redeployed.
     pub node_epoch: u64,
 }
@@ -87,6 +92,11 @@ impl TryFrom<HeartbeatRequest> for Stat {
 
         match (header, peer, node_stat) {
             (Some(header), Some(peer), Some(node_stat)) => {
+                let (follower_region_stats, region_stats): (Vec<_>, Vec<_>) =
+                    region_stats.into_iter().partition(|stat| {
+                        stat.attrs.get(REGION_ROLE_ATTR).map(String::as_str)
+                            == Some(FOLLOWER_REGION_ROLE)
+                    });
                 let region_num = if node_stat.region_num >= 0 {
                     Some(node_stat.region_num as u64)
                 } else {
@@ -107,6 +117,10 @@ impl TryFrom<HeartbeatRequest> for Stat {
                     read_io_rate: node_stat.read_io_rate,
                     write_io_rate: node_stat.write_io_rate,
                     region_stats: region_stats.into_iter().map(RegionStat::from).collect(),
+                    follower_region_stats: follower_region_stats
+                        .into_iter()
+                        .map(RegionStat::from)
+                        .collect(),
                     node_epoch,
                 })
             }
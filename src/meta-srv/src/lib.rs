@@ -15,8 +15,10 @@
 #![feature(async_closure)]
 #![feature(btree_drain_filter)]
 
+pub mod balancer;
 pub mod bootstrap;
 pub mod cluster;
+pub mod ddl;
 pub mod election;
 pub mod error;
 mod failure_detector;
@@ -27,7 +27,9 @@ use servers::http::HttpOptions;
 use snafu::ResultExt;
 use tokio::sync::broadcast::error::RecvError;
 
+use crate::balancer::{RegionBalancerOptions, RegionBalancerRef};
 use crate::cluster::MetaPeerClientRef;
+use crate::ddl::DdlManagerRef;
 use crate::election::{Election, LeaderChangeMessage};
 use crate::error::{RecoverProcedureSnafu, Result};
 use crate::handler::HeartbeatHandlerGroup;
@@ -50,6 +52,9 @@ pub struct MetaSrvOptions {
     pub selector: SelectorType,
     pub use_memory_store: bool,
     pub disable_region_failover: bool,
+    pub region_balancer: RegionBalancerOptions,
+    /// Number of read-only followers of each region, followers are disabled if it's 0.
+    pub region_follower_num: usize,
     pub http_opts: HttpOptions,
     pub logging: LoggingOptions,
 }
@@ -64,6 +69,8 @@ impl Default for MetaSrvOptions {
             selector: SelectorType::default(),
             use_memory_store: false,
             disable_region_failover: false,
+            region_balancer: RegionBalancerOptions::default(),
+            region_follower_num: 0,
             http_opts: HttpOptions::default(),
             logging: LoggingOptions::default(),
         }
@@ -129,6 +136,8 @@ pub struct MetaSrv {
     procedure_manager: ProcedureManagerRef,
     metadata_service: MetadataServiceRef,
     mailbox: MailboxRef,
+    region_balancer: RegionBalancerRef,
+    ddl_manager: DdlManagerRef,
 }
 
 impl MetaSrv {
@@ -190,6 +199,8 @@ impl MetaSrv {
                 .context(RecoverProcedureSnafu)?;
         }
 
+        self.region_balancer.start();
+
         info!("MetaSrv started");
         Ok(())
     }
@@ -254,6 +265,16 @@ impl MetaSrv {
         self.mailbox.clone()
     }
 
+    #[inline]
+    pub fn region_balancer(&self) -> RegionBalancerRef {
+        self.region_balancer.clone()
+    }
+
+    #[inline]
+    pub fn ddl_manager(&self) -> &DdlManagerRef {
+        &self.ddl_manager
+    }
+
     pub fn procedure_manager(&self) -> &ProcedureManagerRef {
         &self.procedure_manager
     }
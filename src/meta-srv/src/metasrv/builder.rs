@@ -15,10 +15,14 @@
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
+use client::client_manager::DatanodeClients;
 use common_procedure::local::{LocalManager, ManagerConfig};
 
+use crate::balancer::RegionBalancer;
 use crate::cluster::{MetaPeerClientBuilder, MetaPeerClientRef};
+use crate::ddl::{DdlContext, DdlManager};
 use crate::error::Result;
+use crate::handler::follower_region_handler::FollowerRegionHandler;
 use crate::handler::mailbox_handler::MailboxHandler;
 use crate::handler::region_lease_handler::RegionLeaseHandler;
 use crate::handler::{
@@ -50,6 +54,7 @@ pub struct MetaSrvBuilder {
     meta_peer_client: Option<MetaPeerClientRef>,
     lock: Option<DistLockRef>,
     metadata_service: Option<MetadataServiceRef>,
+    datanode_clients: Option<Arc<DatanodeClients>>,
 }
 
 impl MetaSrvBuilder {
@@ -64,6 +69,7 @@ impl MetaSrvBuilder {
             options: None,
             lock: None,
             metadata_service: None,
+            datanode_clients: None,
         }
     }
 
@@ -112,6 +118,11 @@ impl MetaSrvBuilder {
         self
     }
 
+    pub fn datanode_clients(mut self, datanode_clients: Arc<DatanodeClients>) -> Self {
+        self.datanode_clients = Some(datanode_clients);
+        self
+    }
+
     pub async fn build(self) -> Result<MetaSrv> {
         let started = Arc::new(AtomicBool::new(false));
 
@@ -125,6 +136,7 @@ impl MetaSrvBuilder {
             handler_group,
             lock,
             metadata_service,
+            datanode_clients,
         } = self;
 
         let options = options.unwrap_or_default();
@@ -148,32 +160,50 @@ impl MetaSrvBuilder {
         let procedure_manager = Arc::new(LocalManager::new(ManagerConfig::default(), state_store));
         let lock = lock.unwrap_or_else(|| Arc::new(MemLock::default()));
 
+        let ddl_manager = Arc::new(DdlManager::new(
+            procedure_manager.clone(),
+            DdlContext {
+                kv_store: kv_store.clone(),
+                datanode_clients: datanode_clients
+                    .unwrap_or_else(|| Arc::new(DatanodeClients::default())),
+                mailbox: mailbox.clone(),
+                server_addr: options.server_addr.clone(),
+            },
+        ));
+        ddl_manager.try_start()?;
+
+        let region_failover_manager = if options.disable_region_failover {
+            None
+        } else {
+            Some(Arc::new(RegionFailoverManager::new(
+                mailbox.clone(),
+                procedure_manager.clone(),
+                selector.clone(),
+                SelectorContext {
+                    server_addr: options.server_addr.clone(),
+                    datanode_lease_secs: options.datanode_lease_secs,
+                    kv_store: kv_store.clone(),
+                    meta_peer_client: meta_peer_client.clone(),
+                    catalog: None,
+                    schema: None,
+                    table: None,
+                },
+                lock.clone(),
+            )))
+        };
+
         let handler_group = match handler_group {
             Some(handler_group) => handler_group,
             None => {
-                let region_failover_handler = if options.disable_region_failover {
-                    None
-                } else {
-                    let region_failover_manager = Arc::new(RegionFailoverManager::new(
-                        mailbox.clone(),
-                        procedure_manager.clone(),
-                        selector.clone(),
-                        SelectorContext {
-                            server_addr: options.server_addr.clone(),
-                            datanode_lease_secs: options.datanode_lease_secs,
-                            kv_store: kv_store.clone(),
-                            meta_peer_client: meta_peer_client.clone(),
-                            catalog: None,
-                            schema: None,
-                            table: None,
-                        },
-                        lock.clone(),
-                    ));
-
-                    Some(
-                        RegionFailureHandler::try_new(election.clone(), region_failover_manager)
-                            .await?,
-                    )
+                let region_failover_handler = match &region_failover_manager {
+                    Some(region_failover_manager) => Some(
+                        RegionFailureHandler::try_new(
+                            election.clone(),
+                            region_failover_manager.clone(),
+                        )
+                        .await?,
+                    ),
+                    None => None,
                 };
 
                 let region_lease_handler = RegionLeaseHandler::new(
@@ -197,6 +227,9 @@ impl MetaSrvBuilder {
                     group.add_handler(region_failover_handler).await;
                 }
                 group.add_handler(region_lease_handler).await;
+                if options.region_follower_num > 0 {
+                    group.add_handler(FollowerRegionHandler::default()).await;
+                }
                 group.add_handler(PersistStatsHandler::default()).await;
                 group
             }
@@ -207,6 +240,15 @@ impl MetaSrvBuilder {
         let metadata_service = metadata_service
             .unwrap_or_else(|| Arc::new(DefaultMetadataService::new(kv_store.clone())));
 
+        let region_balancer = Arc::new(RegionBalancer::new(
+            options.region_balancer.clone(),
+            kv_store.clone(),
+            options.datanode_lease_secs,
+            meta_peer_client.clone(),
+            election.clone(),
+            region_failover_manager,
+        ));
+
         Ok(MetaSrv {
             started,
             options,
@@ -221,6 +263,8 @@ impl MetaSrvBuilder {
             procedure_manager,
             metadata_service,
             mailbox,
+            region_balancer,
+            ddl_manager,
         })
     }
 }
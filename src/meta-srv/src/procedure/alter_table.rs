@@ -0,0 +1,359 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::alter_expr::Kind;
+use api::v1::meta::{MoveValueRequest, PutRequest};
+use api::v1::AlterExpr;
+use async_trait::async_trait;
+use catalog::helper::{TableGlobalKey, TableGlobalValue};
+use client::Database;
+use common_error::prelude::ErrorExt;
+use common_error::status_code::StatusCode;
+use common_meta::ident::TableIdent;
+use common_meta::key::TableRouteKey;
+use common_meta::table_name::TableName;
+use common_meta::ClusterId;
+use common_procedure::error::{
+    Error as ProcedureError, FromJsonSnafu, Result as ProcedureResult, ToJsonSnafu,
+};
+use common_procedure::{Context as ProcedureContext, LockKey, Procedure, Status};
+use common_telemetry::{debug, info, warn};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt, ResultExt};
+use table::metadata::TableInfo;
+use table::requests::AlterKind;
+
+use super::create_table::decode_table_route;
+use crate::ddl::DdlContext;
+use crate::error::{self, Result};
+use crate::service::store::ext::KvStoreExt;
+use crate::table_routes::{get_table_global_value, get_table_route_value};
+
+/// Procedure to alter a distributed table. The table is altered on the Datanodes
+/// before its metadata is updated, so Frontends never see columns the regions
+/// don't have.
+pub struct AlterTableProcedure {
+    context: DdlContext,
+    data: AlterTableData,
+}
+
+impl AlterTableProcedure {
+    pub(crate) const TYPE_NAME: &'static str = "metasrv-procedure::AlterTable";
+
+    pub(crate) fn new(cluster_id: ClusterId, alter_expr: AlterExpr, context: DdlContext) -> Self {
+        let table_name = TableName::new(
+            &alter_expr.catalog_name,
+            &alter_expr.schema_name,
+            &alter_expr.table_name,
+        );
+        Self {
+            context,
+            data: AlterTableData {
+                state: AlterTableState::Prepare,
+                cluster_id,
+                table_name,
+                alter_expr: alter_expr.encode_to_vec(),
+                table_route_value: vec![],
+                table_global_value: None,
+            },
+        }
+    }
+
+    pub(crate) fn from_json(json: &str, context: DdlContext) -> ProcedureResult<Self> {
+        let data = serde_json::from_str(json).context(FromJsonSnafu)?;
+        Ok(Self { context, data })
+    }
+
+    /// Loads the metadata of the table and applies the alteration to it. It runs
+    /// under the lock of the table, so the alteration is applied to the latest
+    /// metadata rather than the one a concurrent ALTER TABLE replaces.
+    async fn on_prepare(&mut self) -> Result<Status> {
+        let table_name = &self.data.table_name;
+        let alter_expr = self.data.alter_expr()?;
+
+        if let Some(new_table_name) = self.data.new_table_name()? {
+            let new_key = TableGlobalKey {
+                catalog_name: table_name.catalog_name.clone(),
+                schema_name: table_name.schema_name.clone(),
+                table_name: new_table_name.clone(),
+            };
+            ensure!(
+                self.context
+                    .kv_store
+                    .get(new_key.to_raw_key())
+                    .await?
+                    .is_none(),
+                error::TableAlreadyExistsSnafu {
+                    table_name: new_key.to_string(),
+                }
+            );
+        }
+
+        let key = self.data.table_global_key();
+        let mut value = get_table_global_value(&self.context.kv_store, &key)
+            .await?
+            .with_context(|| error::TableNotFoundSnafu {
+                name: key.to_string(),
+            })?;
+
+        let route_key = TableRouteKey::with_table_name(value.table_id() as u64, table_name);
+        let table_route_value = get_table_route_value(&self.context.kv_store, &route_key).await?;
+
+        let table_info: TableInfo = value
+            .table_info
+            .clone()
+            .try_into()
+            .context(error::ConvertRawTableInfoSnafu)?;
+        let request =
+            common_grpc_expr::alter_expr_to_request(table_info.ident.table_id, alter_expr)
+                .context(error::ConvertAlterExprSnafu)?;
+        let new_meta = table_info
+            .meta
+            .builder_with_alter_kind(&table_name.table_name, &request.alter_kind)
+            .with_context(|_| error::AlterTableInfoSnafu {
+                table_name: table_name.to_string(),
+            })?
+            .build()
+            .with_context(|_| error::BuildTableMetaSnafu {
+                table_name: table_name.to_string(),
+            })?;
+
+        let mut new_info = table_info.clone();
+        new_info.ident.version = table_info.ident.version + 1;
+        new_info.meta = new_meta;
+        if let AlterKind::RenameTable { new_table_name } = &request.alter_kind {
+            new_info.name = new_table_name.clone();
+        }
+        value.table_info = new_info.into();
+
+        self.data.table_route_value = table_route_value.into();
+        self.data.table_global_value = Some(value);
+        self.data.state = AlterTableState::DatanodeAlterTable;
+        Ok(Status::executing(true))
+    }
+
+    /// Alters the table on the Datanodes of its route. As the alteration has been
+    /// checked against the metadata, a Datanode rejecting it because of the missing
+    /// or existing column or table has applied it in a previous attempt.
+    async fn on_datanode_alter_table(&mut self) -> Result<Status> {
+        let table_name = &self.data.table_name;
+        let alter_expr = self.data.alter_expr()?;
+        let table_route = decode_table_route(&self.data.table_route_value)?;
+        let leaders = table_route.find_leaders();
+        ensure!(
+            !leaders.is_empty(),
+            error::UnexpectedSnafu {
+                violated: format!("table {table_name} should have leaders"),
+            }
+        );
+
+        for datanode in leaders {
+            debug!("Altering table {table_name} on Datanode {datanode}");
+
+            let client = self.context.datanode_clients.get_client(&datanode).await;
+            let client = Database::new(&alter_expr.catalog_name, &alter_expr.schema_name, client);
+            match client.alter(alter_expr.clone()).await {
+                Ok(_) => {}
+                Err(e)
+                    if matches!(
+                        e.status_code(),
+                        StatusCode::TableColumnExists
+                            | StatusCode::TableColumnNotFound
+                            | StatusCode::TableNotFound
+                    ) =>
+                {
+                    warn!("Table {table_name} may have been altered on Datanode {datanode}: {e}");
+                }
+                Err(e) => {
+                    return Err(e).context(error::RequestDatanodeSnafu { peer: datanode });
+                }
+            }
+        }
+
+        self.data.state = AlterTableState::UpdateMetadata;
+        Ok(Status::executing(true))
+    }
+
+    /// Saves the altered table global value. Renaming a table moves its values
+    /// to the keys of the new name. The step is idempotent, as it's retried if
+    /// the procedure is resumed.
+    async fn on_update_metadata(&mut self) -> Result<Status> {
+        let table_name = &self.data.table_name;
+        let key = self.data.table_global_key();
+        let value = self.data.table_global_value()?;
+        let new_table_name = self.data.new_table_name()?.map(|new_table_name| {
+            TableName::new(
+                &table_name.catalog_name,
+                &table_name.schema_name,
+                new_table_name,
+            )
+        });
+
+        let new_key = match &new_table_name {
+            Some(new_table_name) => TableGlobalKey {
+                catalog_name: new_table_name.catalog_name.clone(),
+                schema_name: new_table_name.schema_name.clone(),
+                table_name: new_table_name.table_name.clone(),
+            },
+            None => key.clone(),
+        };
+        let req = PutRequest {
+            key: new_key.to_raw_key(),
+            value: value.as_bytes().context(error::InvalidCatalogValueSnafu)?,
+            ..Default::default()
+        };
+        let _ = self.context.kv_store.put(req).await?;
+
+        if let Some(new_table_name) = &new_table_name {
+            let _ = self
+                .context
+                .kv_store
+                .delete(key.to_raw_key(), false)
+                .await?;
+
+            let table_id = value.table_id() as u64;
+            let from_key = TableRouteKey::with_table_name(table_id, table_name).key();
+            let to_key = TableRouteKey::with_table_name(table_id, new_table_name).key();
+            let req = MoveValueRequest {
+                from_key: from_key.clone().into_bytes(),
+                to_key: to_key.into_bytes(),
+                ..Default::default()
+            };
+            if self.context.kv_store.move_value(req).await?.kv.is_none() {
+                warn!("Value of key '{from_key}' is absent, it may have been moved");
+            }
+        }
+
+        self.data.state = AlterTableState::InvalidateCache;
+        Ok(Status::executing(true))
+    }
+
+    async fn on_invalidate_cache(&mut self) -> Result<Status> {
+        let value = self.data.table_global_value()?;
+        // Frontends cache the table by its old name.
+        let table_ident = TableIdent {
+            catalog: self.data.table_name.catalog_name.clone(),
+            schema: self.data.table_name.schema_name.clone(),
+            table: self.data.table_name.table_name.clone(),
+            table_id: value.table_id(),
+            engine: value.table_info.meta.engine.clone(),
+        };
+        self.context.invalidate_table_cache(table_ident).await?;
+
+        info!(
+            "Table {} is altered to version {}",
+            self.data.table_name, value.table_info.ident.version
+        );
+        Ok(Status::Done)
+    }
+}
+
+#[async_trait]
+impl Procedure for AlterTableProcedure {
+    fn type_name(&self) -> &str {
+        Self::TYPE_NAME
+    }
+
+    async fn execute(&mut self, _ctx: &ProcedureContext) -> ProcedureResult<Status> {
+        let result = match self.data.state {
+            AlterTableState::Prepare => self.on_prepare().await,
+            AlterTableState::DatanodeAlterTable => self.on_datanode_alter_table().await,
+            AlterTableState::UpdateMetadata => self.on_update_metadata().await,
+            AlterTableState::InvalidateCache => self.on_invalidate_cache().await,
+        };
+        result.map_err(ProcedureError::from_error_ext)
+    }
+
+    fn dump(&self) -> ProcedureResult<String> {
+        serde_json::to_string(&self.data).context(ToJsonSnafu)
+    }
+
+    /// Locks the table, and the new name of the table if it's renamed, so the
+    /// rename doesn't race with creating a table of the new name.
+    fn lock_key(&self) -> LockKey {
+        let table_name = &self.data.table_name;
+        let mut keys = vec![table_name.to_string()];
+        if let Ok(Some(new_table_name)) = self.data.new_table_name() {
+            keys.push(
+                TableName::new(
+                    &table_name.catalog_name,
+                    &table_name.schema_name,
+                    new_table_name,
+                )
+                .to_string(),
+            );
+        }
+        LockKey::new(keys)
+    }
+}
+
+/// Represents each step while altering a distributed table.
+#[derive(Debug, Serialize, Deserialize)]
+enum AlterTableState {
+    /// Loads the metadata of the table and applies the alteration to it.
+    Prepare,
+    /// Alters the regions on Datanodes.
+    DatanodeAlterTable,
+    /// Saves the altered table global value, and moves the values of a renamed
+    /// table.
+    UpdateMetadata,
+    /// Invalidates the table cache of Frontends.
+    InvalidateCache,
+}
+
+/// Serializable data of [AlterTableProcedure].
+#[derive(Debug, Serialize, Deserialize)]
+struct AlterTableData {
+    state: AlterTableState,
+    cluster_id: ClusterId,
+    table_name: TableName,
+    /// The encoded [AlterExpr].
+    alter_expr: Vec<u8>,
+    /// The encoded `TableRouteValue` of the table, loaded on preparing.
+    table_route_value: Vec<u8>,
+    /// The altered table global value, set on preparing.
+    table_global_value: Option<TableGlobalValue>,
+}
+
+impl AlterTableData {
+    fn alter_expr(&self) -> Result<AlterExpr> {
+        AlterExpr::decode(self.alter_expr.as_slice()).context(error::DecodeDdlRequestSnafu {
+            field: "alter_expr",
+        })
+    }
+
+    fn new_table_name(&self) -> Result<Option<String>> {
+        Ok(match self.alter_expr()?.kind {
+            Some(Kind::RenameTable(rename)) => Some(rename.new_table_name),
+            _ => None,
+        })
+    }
+
+    fn table_global_key(&self) -> TableGlobalKey {
+        TableGlobalKey {
+            catalog_name: self.table_name.catalog_name.clone(),
+            schema_name: self.table_name.schema_name.clone(),
+            table_name: self.table_name.table_name.clone(),
+        }
+    }
+
+    fn table_global_value(&self) -> Result<&TableGlobalValue> {
+        self.table_global_value
+            .as_ref()
+            .context(error::UnexpectedSnafu {
+                violated: "table global value should have been set",
+            })
+    }
+}
@@ -0,0 +1,406 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use api::v1::meta::{BatchPutRequest, KeyValue, TableRouteValue};
+use api::v1::{column_def, CreateTableExpr, TableId};
+use async_trait::async_trait;
+use catalog::helper::{TableGlobalKey, TableGlobalValue};
+use client::Database;
+use common_catalog::format_full_table_name;
+use common_error::prelude::ErrorExt;
+use common_meta::ident::TableIdent;
+use common_meta::key::TableRouteKey;
+use common_meta::rpc::router::TableRoute;
+use common_meta::table_name::TableName;
+use common_meta::ClusterId;
+use common_procedure::error::{
+    Error as ProcedureError, FromJsonSnafu, Result as ProcedureResult, ToJsonSnafu,
+};
+use common_procedure::{Context as ProcedureContext, LockKey, Procedure, Status};
+use common_telemetry::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+use table::metadata::RawTableInfo;
+
+use super::drop_table::drop_table_on_datanodes;
+use crate::ddl::DdlContext;
+use crate::error::{self, Result};
+use crate::service::store::ext::KvStoreExt;
+
+/// Procedure to create a distributed table. The regions are created on the
+/// Datanodes before the metadata is saved, so a table visible to Frontends
+/// always has its regions.
+pub struct CreateTableProcedure {
+    context: DdlContext,
+    data: CreateTableData,
+}
+
+impl CreateTableProcedure {
+    pub(crate) const TYPE_NAME: &'static str = "metasrv-procedure::CreateTable";
+
+    pub(crate) fn new(
+        cluster_id: ClusterId,
+        table_route_value: TableRouteValue,
+        table_global_value: TableGlobalValue,
+        context: DdlContext,
+    ) -> Self {
+        Self {
+            context,
+            data: CreateTableData {
+                state: CreateTableState::Prepare,
+                cluster_id,
+                table_route_value: table_route_value.into(),
+                table_global_value,
+            },
+        }
+    }
+
+    pub(crate) fn from_json(json: &str, context: DdlContext) -> ProcedureResult<Self> {
+        let data = serde_json::from_str(json).context(FromJsonSnafu)?;
+        Ok(Self { context, data })
+    }
+
+    /// Checks the table doesn't exist, and cleans up the regions left on the
+    /// Datanodes by a previous attempt that failed before saving the metadata,
+    /// so they aren't taken as the regions of this table.
+    async fn on_prepare(&mut self) -> Result<Status> {
+        let key = self.data.table_global_key();
+        ensure!(
+            self.context.kv_store.get(key.to_raw_key()).await?.is_none(),
+            error::TableAlreadyExistsSnafu {
+                table_name: key.to_string(),
+            }
+        );
+
+        let table_route = self.data.table_route()?;
+        drop_table_on_datanodes(
+            &self.context,
+            &self.data.table_name(),
+            table_route.find_leaders(),
+        )
+        .await?;
+
+        self.data.state = CreateTableState::DatanodeCreateTable;
+        Ok(Status::executing(true))
+    }
+
+    async fn on_datanode_create_table(&mut self) -> Result<Status> {
+        let table_route = self.data.table_route()?;
+        if let Err(e) = self.create_table_on_datanodes(&table_route).await {
+            // Retryable errors are retried by the procedure framework, others
+            // fail the procedure, so the regions created are rolled back.
+            if !e.status_code().is_retryable() {
+                let table_name = self.data.table_name();
+                if let Err(rollback_err) =
+                    drop_table_on_datanodes(&self.context, &table_name, table_route.find_leaders())
+                        .await
+                {
+                    error!(rollback_err; "Failed to roll back the regions of table {table_name}");
+                }
+            }
+            return Err(e);
+        }
+
+        self.data.state = CreateTableState::CreateMetadata;
+        Ok(Status::executing(true))
+    }
+
+    async fn create_table_on_datanodes(&self, table_route: &TableRoute) -> Result<()> {
+        let table_info = &self.data.table_global_value.table_info;
+        let expr = create_table_expr(table_info)?;
+
+        for datanode in table_route.find_leaders() {
+            let client = self.context.datanode_clients.get_client(&datanode).await;
+            let client = Database::new(&expr.catalog_name, &expr.schema_name, client);
+
+            let mut expr = expr.clone();
+            expr.region_numbers = table_route.find_leader_regions(&datanode);
+            debug!(
+                "Creating table {} on Datanode {datanode} with regions {:?}",
+                expr.table_name, expr.region_numbers
+            );
+
+            let _ = client
+                .create(expr)
+                .await
+                .context(error::RequestDatanodeSnafu { peer: datanode })?;
+        }
+        Ok(())
+    }
+
+    async fn on_create_metadata(&mut self) -> Result<Status> {
+        let table_global_key = self.data.table_global_key();
+        let table_route_key = TableRouteKey {
+            table_id: self.data.table_global_value.table_id() as u64,
+            catalog_name: &table_global_key.catalog_name,
+            schema_name: &table_global_key.schema_name,
+            table_name: &table_global_key.table_name,
+        };
+
+        let req = BatchPutRequest {
+            kvs: vec![
+                KeyValue {
+                    key: table_global_key.to_raw_key(),
+                    value: self
+                        .data
+                        .table_global_value
+                        .as_bytes()
+                        .context(error::InvalidCatalogValueSnafu)?,
+                },
+                KeyValue {
+                    key: table_route_key.key().into_bytes(),
+                    value: self.data.table_route_value.clone(),
+                },
+            ],
+            prev_kv: true,
+            ..Default::default()
+        };
+        let resp = self.context.kv_store.batch_put(req).await?;
+        if !resp.prev_kvs.is_empty() {
+            warn!("Caution: table meta values of {table_global_key} are replaced!");
+        }
+
+        self.data.state = CreateTableState::InvalidateCache;
+        Ok(Status::executing(true))
+    }
+
+    async fn on_invalidate_cache(&mut self) -> Result<Status> {
+        self.context
+            .invalidate_table_cache(self.data.table_ident())
+            .await?;
+
+        info!(
+            "Table {} is created with id {}",
+            self.data.table_global_key(),
+            self.data.table_global_value.table_id()
+        );
+        Ok(Status::Done)
+    }
+}
+
+#[async_trait]
+impl Procedure for CreateTableProcedure {
+    fn type_name(&self) -> &str {
+        Self::TYPE_NAME
+    }
+
+    async fn execute(&mut self, _ctx: &ProcedureContext) -> ProcedureResult<Status> {
+        // There's no need to retry if the table already exists, as its status
+        // code isn't retryable.
+        let result = match self.data.state {
+            CreateTableState::Prepare => self.on_prepare().await,
+            CreateTableState::DatanodeCreateTable => self.on_datanode_create_table().await,
+            CreateTableState::CreateMetadata => self.on_create_metadata().await,
+            CreateTableState::InvalidateCache => self.on_invalidate_cache().await,
+        };
+        result.map_err(ProcedureError::from_error_ext)
+    }
+
+    fn dump(&self) -> ProcedureResult<String> {
+        serde_json::to_string(&self.data).context(ToJsonSnafu)
+    }
+
+    fn lock_key(&self) -> LockKey {
+        let table_info = &self.data.table_global_value.table_info;
+        LockKey::single(format_full_table_name(
+            &table_info.catalog_name,
+            &table_info.schema_name,
+            &table_info.name,
+        ))
+    }
+}
+
+/// Represents each step while creating a distributed table.
+#[derive(Debug, Serialize, Deserialize)]
+enum CreateTableState {
+    /// Checks whether the table exists, and cleans up the regions left by
+    /// previous attempts.
+    Prepare,
+    /// Creates the regions on Datanodes.
+    DatanodeCreateTable,
+    /// Saves the table global value and the table route.
+    CreateMetadata,
+    /// Invalidates the table cache of Frontends.
+    InvalidateCache,
+}
+
+/// Serializable data of [CreateTableProcedure].
+#[derive(Debug, Serialize, Deserialize)]
+struct CreateTableData {
+    state: CreateTableState,
+    cluster_id: ClusterId,
+    /// The encoded [TableRouteValue], the route is saved as is.
+    table_route_value: Vec<u8>,
+    table_global_value: TableGlobalValue,
+}
+
+impl CreateTableData {
+    fn table_global_key(&self) -> TableGlobalKey {
+        let table_info = &self.table_global_value.table_info;
+        TableGlobalKey {
+            catalog_name: table_info.catalog_name.clone(),
+            schema_name: table_info.schema_name.clone(),
+            table_name: table_info.name.clone(),
+        }
+    }
+
+    fn table_name(&self) -> TableName {
+        let table_info = &self.table_global_value.table_info;
+        TableName::new(
+            &table_info.catalog_name,
+            &table_info.schema_name,
+            &table_info.name,
+        )
+    }
+
+    fn table_ident(&self) -> TableIdent {
+        let table_info = &self.table_global_value.table_info;
+        TableIdent {
+            catalog: table_info.catalog_name.clone(),
+            schema: table_info.schema_name.clone(),
+            table: table_info.name.clone(),
+            table_id: table_info.ident.table_id,
+            engine: table_info.meta.engine.clone(),
+        }
+    }
+
+    fn table_route(&self) -> Result<TableRoute> {
+        decode_table_route(&self.table_route_value)
+    }
+}
+
+pub(crate) fn decode_table_route(table_route_value: &[u8]) -> Result<TableRoute> {
+    let TableRouteValue { peers, table_route } = table_route_value
+        .try_into()
+        .context(error::DecodeTableRouteSnafu)?;
+    let table_route = table_route.context(error::UnexpectedSnafu {
+        violated: "table route should have been set",
+    })?;
+    TableRoute::try_from_raw(&peers, table_route).context(error::TableRouteConversionSnafu)
+}
+
+/// Builds the request to create the table on Datanodes from its info, the
+/// regions are left to be filled per Datanode.
+fn create_table_expr(table_info: &RawTableInfo) -> Result<CreateTableExpr> {
+    let schema = &table_info.meta.schema;
+    let column_defs = schema
+        .column_schemas
+        .iter()
+        .map(column_def::try_as_column_def)
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .with_context(|_| error::BuildCreateTableExprSnafu {
+            table_name: format_full_table_name(
+                &table_info.catalog_name,
+                &table_info.schema_name,
+                &table_info.name,
+            ),
+        })?;
+    let time_index = schema
+        .timestamp_index
+        .map(|index| schema.column_schemas[index].name.clone())
+        .unwrap_or_default();
+    let primary_keys = table_info
+        .meta
+        .primary_key_indices
+        .iter()
+        .map(|index| schema.column_schemas[*index].name.clone())
+        .collect();
+
+    Ok(CreateTableExpr {
+        catalog_name: table_info.catalog_name.clone(),
+        schema_name: table_info.schema_name.clone(),
+        table_name: table_info.name.clone(),
+        desc: table_info.desc.clone().unwrap_or_default(),
+        column_defs,
+        time_index,
+        primary_keys,
+        // Makes the request idempotent, as it's retried if the procedure is
+        // resumed.
+        create_if_not_exists: true,
+        table_options: HashMap::from(&table_info.meta.options),
+        table_id: Some(TableId {
+            id: table_info.ident.table_id,
+        }),
+        region_numbers: vec![],
+        engine: table_info.meta.engine.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, RawSchema};
+    use table::metadata::{RawTableMeta, TableIdent as RawTableIdent, TableType};
+    use table::requests::TableOptions;
+
+    use super::*;
+
+    fn new_table_info() -> RawTableInfo {
+        let column_schemas = vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            )
+            .with_time_index(true),
+        ];
+        RawTableInfo {
+            ident: RawTableIdent {
+                table_id: 1024,
+                version: 0,
+            },
+            name: "monitor".to_string(),
+            desc: None,
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            meta: RawTableMeta {
+                schema: RawSchema::new(column_schemas),
+                primary_key_indices: vec![0],
+                value_indices: vec![1],
+                engine: "mito".to_string(),
+                next_column_id: 3,
+                region_numbers: vec![],
+                engine_options: HashMap::new(),
+                options: TableOptions::default(),
+                created_on: Default::default(),
+            },
+            table_type: TableType::Base,
+        }
+    }
+
+    #[test]
+    fn test_create_table_expr() {
+        let expr = create_table_expr(&new_table_info()).unwrap();
+        assert_eq!("greptime", expr.catalog_name);
+        assert_eq!("public", expr.schema_name);
+        assert_eq!("monitor", expr.table_name);
+        assert_eq!(
+            vec!["host", "cpu", "ts"],
+            expr.column_defs
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert!(expr.column_defs[1].is_nullable);
+        assert_eq!("ts", expr.time_index);
+        assert_eq!(vec!["host".to_string()], expr.primary_keys);
+        assert!(expr.create_if_not_exists);
+        assert_eq!(Some(TableId { id: 1024 }), expr.table_id);
+        assert_eq!("mito", expr.engine);
+    }
+}
@@ -0,0 +1,218 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::meta::{MoveValueRequest, TableRouteValue};
+use api::v1::DropTableExpr;
+use async_trait::async_trait;
+use catalog::helper::{TableGlobalKey, TableGlobalValue};
+use client::Database;
+use common_error::prelude::ErrorExt;
+use common_error::status_code::StatusCode;
+use common_meta::ident::TableIdent;
+use common_meta::key::TableRouteKey;
+use common_meta::peer::Peer;
+use common_meta::table_name::TableName;
+use common_meta::ClusterId;
+use common_procedure::error::{
+    Error as ProcedureError, FromJsonSnafu, Result as ProcedureResult, ToJsonSnafu,
+};
+use common_procedure::{Context as ProcedureContext, LockKey, Procedure, Status};
+use common_telemetry::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+use super::create_table::decode_table_route;
+use crate::ddl::DdlContext;
+use crate::error::{self, Result};
+
+/// Procedure to drop a distributed table. The metadata is removed before the
+/// regions are dropped on the Datanodes, so Frontends stop routing requests to
+/// the regions first.
+pub struct DropTableProcedure {
+    context: DdlContext,
+    data: DropTableData,
+}
+
+impl DropTableProcedure {
+    pub(crate) const TYPE_NAME: &'static str = "metasrv-procedure::DropTable";
+
+    pub(crate) fn new(
+        cluster_id: ClusterId,
+        table_name: TableName,
+        table_route_value: TableRouteValue,
+        table_global_value: TableGlobalValue,
+        context: DdlContext,
+    ) -> Self {
+        Self {
+            context,
+            data: DropTableData {
+                state: DropTableState::RemoveMetadata,
+                cluster_id,
+                table_name,
+                table_route_value: table_route_value.into(),
+                table_global_value,
+            },
+        }
+    }
+
+    pub(crate) fn from_json(json: &str, context: DdlContext) -> ProcedureResult<Self> {
+        let data = serde_json::from_str(json).context(FromJsonSnafu)?;
+        Ok(Self { context, data })
+    }
+
+    /// Moves the table global value and the table route to their removed keys.
+    /// Values already moved are skipped, as the step is retried if the procedure
+    /// is resumed.
+    async fn on_remove_metadata(&mut self) -> Result<Status> {
+        let table_name = &self.data.table_name;
+        let table_global_key = TableGlobalKey {
+            catalog_name: table_name.catalog_name.clone(),
+            schema_name: table_name.schema_name.clone(),
+            table_name: table_name.table_name.clone(),
+        }
+        .to_string();
+        let table_route_key = TableRouteKey {
+            table_id: self.data.table_global_value.table_id() as u64,
+            catalog_name: &table_name.catalog_name,
+            schema_name: &table_name.schema_name,
+            table_name: &table_name.table_name,
+        };
+
+        for (from_key, to_key) in [
+            (
+                table_global_key.clone(),
+                crate::keys::to_removed_key(&table_global_key),
+            ),
+            (table_route_key.key(), table_route_key.removed_key()),
+        ] {
+            let req = MoveValueRequest {
+                from_key: from_key.clone().into_bytes(),
+                to_key: to_key.into_bytes(),
+                ..Default::default()
+            };
+            if self.context.kv_store.move_value(req).await?.kv.is_none() {
+                warn!("Value of key '{from_key}' is absent, it may have been removed");
+            }
+        }
+
+        self.data.state = DropTableState::InvalidateCache;
+        Ok(Status::executing(true))
+    }
+
+    async fn on_invalidate_cache(&mut self) -> Result<Status> {
+        self.context
+            .invalidate_table_cache(self.data.table_ident())
+            .await?;
+
+        self.data.state = DropTableState::DatanodeDropTable;
+        Ok(Status::executing(true))
+    }
+
+    async fn on_datanode_drop_table(&mut self) -> Result<Status> {
+        let table_name = &self.data.table_name;
+        let table_route = decode_table_route(&self.data.table_route_value)?;
+        drop_table_on_datanodes(&self.context, table_name, table_route.find_leaders()).await?;
+
+        info!("Table {table_name} is dropped");
+        Ok(Status::Done)
+    }
+}
+
+/// Drops the table on the Datanodes. Datanodes without the table are skipped,
+/// so it can be retried.
+pub(crate) async fn drop_table_on_datanodes(
+    context: &DdlContext,
+    table_name: &TableName,
+    datanodes: impl IntoIterator<Item = Peer>,
+) -> Result<()> {
+    let expr = DropTableExpr {
+        catalog_name: table_name.catalog_name.clone(),
+        schema_name: table_name.schema_name.clone(),
+        table_name: table_name.table_name.clone(),
+    };
+
+    for datanode in datanodes {
+        debug!("Dropping table {table_name} on Datanode {datanode}");
+
+        let client = context.datanode_clients.get_client(&datanode).await;
+        let client = Database::new(&expr.catalog_name, &expr.schema_name, client);
+        match client.drop_table(expr.clone()).await {
+            Ok(_) => {}
+            // The table has been dropped on this Datanode before.
+            Err(e) if e.status_code() == StatusCode::TableNotFound => {}
+            Err(e) => {
+                return Err(e).context(error::RequestDatanodeSnafu { peer: datanode });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl Procedure for DropTableProcedure {
+    fn type_name(&self) -> &str {
+        Self::TYPE_NAME
+    }
+
+    async fn execute(&mut self, _ctx: &ProcedureContext) -> ProcedureResult<Status> {
+        let result = match self.data.state {
+            DropTableState::RemoveMetadata => self.on_remove_metadata().await,
+            DropTableState::InvalidateCache => self.on_invalidate_cache().await,
+            DropTableState::DatanodeDropTable => self.on_datanode_drop_table().await,
+        };
+        result.map_err(ProcedureError::from_error_ext)
+    }
+
+    fn dump(&self) -> ProcedureResult<String> {
+        serde_json::to_string(&self.data).context(ToJsonSnafu)
+    }
+
+    fn lock_key(&self) -> LockKey {
+        LockKey::single(self.data.table_name.to_string())
+    }
+}
+
+/// Represents each step while dropping a distributed table.
+#[derive(Debug, Serialize, Deserialize)]
+enum DropTableState {
+    /// Removes the table global value and the table route.
+    RemoveMetadata,
+    /// Invalidates the table cache of Frontends.
+    InvalidateCache,
+    /// Drops the regions on Datanodes.
+    DatanodeDropTable,
+}
+
+/// Serializable data of [DropTableProcedure].
+#[derive(Debug, Serialize, Deserialize)]
+struct DropTableData {
+    state: DropTableState,
+    cluster_id: ClusterId,
+    table_name: TableName,
+    /// The encoded [TableRouteValue] of the table.
+    table_route_value: Vec<u8>,
+    table_global_value: TableGlobalValue,
+}
+
+impl DropTableData {
+    fn table_ident(&self) -> TableIdent {
+        TableIdent {
+            catalog: self.table_name.catalog_name.clone(),
+            schema: self.table_name.schema_name.clone(),
+            table: self.table_name.table_name.clone(),
+            table_id: self.table_global_value.table_id(),
+            engine: self.table_global_value.table_info.meta.engine.clone(),
+        }
+    }
+}
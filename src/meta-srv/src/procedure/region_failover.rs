@@ -27,6 +27,7 @@ use std::time::Duration;
 use async_trait::async_trait;
 use catalog::helper::TableGlobalKey;
 use common_meta::ident::TableIdent;
+use common_meta::peer::Peer;
 use common_meta::{ClusterId, RegionIdent};
 use common_procedure::error::{
     Error as ProcedureError, FromJsonSnafu, Result as ProcedureResult, ToJsonSnafu,
@@ -156,6 +157,24 @@ impl RegionFailoverManager {
     }
 
     pub(crate) async fn do_region_failover(&self, failed_region: &RegionIdent) -> Result<()> {
+        self.submit_procedure(failed_region, None).await
+    }
+
+    /// Moves the region to the `target` Datanode, by running the region failover procedure
+    /// with `target` as the chosen candidate.
+    pub(crate) async fn do_region_migration(
+        &self,
+        region: &RegionIdent,
+        target: Peer,
+    ) -> Result<()> {
+        self.submit_procedure(region, Some(target)).await
+    }
+
+    async fn submit_procedure(
+        &self,
+        failed_region: &RegionIdent,
+        candidate: Option<Peer>,
+    ) -> Result<()> {
         let Some(guard) = self.insert_running_procedures(failed_region) else {
             warn!("Region failover procedure for region {failed_region} is already running!");
             return Ok(());
@@ -170,7 +189,12 @@ impl RegionFailoverManager {
         }
 
         let context = self.create_context();
-        let procedure = RegionFailoverProcedure::new(failed_region.clone(), context);
+        let procedure = match candidate {
+            Some(candidate) => {
+                RegionFailoverProcedure::with_candidate(failed_region.clone(), candidate, context)
+            }
+            None => RegionFailoverProcedure::new(failed_region.clone(), context),
+        };
         let procedure_with_id = ProcedureWithId::with_random_id(Box::new(procedure));
         let procedure_id = procedure_with_id.id;
         info!("Starting region failover procedure {procedure_id} for region {failed_region:?}");
@@ -312,6 +336,20 @@ impl RegionFailoverProcedure {
         Self { node, context }
     }
 
+    /// Creates a procedure that moves the region to the `candidate` instead of selecting one.
+    pub fn with_candidate(
+        failed_region: RegionIdent,
+        candidate: Peer,
+        context: RegionFailoverContext,
+    ) -> Self {
+        let state = RegionFailoverStart::with_candidate(candidate);
+        let node = Node {
+            failed_region,
+            state: Some(Box::new(state)),
+        };
+        Self { node, context }
+    }
+
     fn from_json(json: &str, context: RegionFailoverContext) -> ProcedureResult<Self> {
         let node: Node = serde_json::from_str(json).context(FromJsonSnafu)?;
         Ok(Self { node, context })
@@ -375,6 +413,7 @@ mod tests {
     use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, MITO_ENGINE};
     use common_meta::ident::TableIdent;
     use common_meta::instruction::{Instruction, InstructionReply, SimpleReply};
+    use common_meta::peer::Peer;
     use common_meta::DatanodeId;
     use common_procedure::BoxedProcedure;
     use rand::prelude::SliceRandom;
@@ -37,6 +37,12 @@ impl RegionFailoverStart {
         }
     }
 
+    pub(super) fn with_candidate(candidate: Peer) -> Self {
+        Self {
+            failover_candidate: Some(candidate),
+        }
+    }
+
     async fn choose_candidate(
         &mut self,
         ctx: &RegionFailoverContext,
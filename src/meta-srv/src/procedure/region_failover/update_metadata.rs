@@ -130,6 +130,10 @@ impl UpdateRegionMetadata {
         for region_route in table_route.region_routes.iter_mut() {
             if region_route.region.id == failed_region.region_number as u64 {
                 region_route.leader_peer = Some(self.candidate.clone());
+                // A follower chosen as the candidate has been promoted to the leader.
+                region_route
+                    .follower_peers
+                    .retain(|peer| peer.id != self.candidate.id);
                 break;
             }
         }
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod balancer;
 mod health;
 mod heartbeat;
 mod leader;
@@ -89,6 +90,20 @@ pub fn make_admin_service(meta_srv: MetaSrv) -> Admin {
         },
     );
 
+    let router = router.route(
+        "/balancer",
+        balancer::BalancerHandler {
+            region_balancer: meta_srv.region_balancer(),
+        },
+    );
+
+    let router = router.route(
+        "/balancer/switch",
+        balancer::SwitchBalancerHandler {
+            region_balancer: meta_srv.region_balancer(),
+        },
+    );
+
     let router = Router::nest("/admin", router);
 
     Admin::new(router)
@@ -101,6 +116,12 @@ pub trait HttpHandler: Send + Sync {
         path: &str,
         params: &HashMap<String, String>,
     ) -> crate::Result<http::Response<String>>;
+
+    /// Returns whether the handler accepts requests of the `method`. Handlers
+    /// changing states should only accept methods like `POST` and `PUT`.
+    fn accepts(&self, _method: &http::Method) -> bool {
+        true
+    }
 }
 
 #[derive(Clone)]
@@ -147,7 +168,8 @@ where
             })
             .unwrap_or_else(HashMap::new);
         let path = req.uri().path().to_owned();
-        Box::pin(async move { router.call(&path, query_params).await })
+        let method = req.method().clone();
+        Box::pin(async move { router.call(&path, &method, query_params).await })
     }
 }
 
@@ -186,6 +208,7 @@ impl Router {
     pub async fn call(
         &self,
         path: &str,
+        method: &http::Method,
         params: HashMap<String, String>,
     ) -> Result<http::Response<BoxBody>, Infallible> {
         let handler = match self.handlers.get(path) {
@@ -197,6 +220,12 @@ impl Router {
                     .unwrap())
             }
         };
+        if !handler.accepts(method) {
+            return Ok(http::Response::builder()
+                .status(http::StatusCode::METHOD_NOT_ALLOWED)
+                .body(empty_body())
+                .unwrap());
+        }
 
         let res = match handler.handle(path, &params).await {
             Ok(res) => res.map(boxed),
@@ -284,7 +313,11 @@ mod tests {
         let router = Router::nest("/test_root", router);
 
         let res = router
-            .call("/test_root/test_node", HashMap::default())
+            .call(
+                "/test_root/test_node",
+                &http::Method::GET,
+                HashMap::default(),
+            )
             .await
             .unwrap();
 
@@ -296,7 +329,11 @@ mod tests {
         let router = Router::new();
 
         let res = router
-            .call("/test_root/test_node", HashMap::default())
+            .call(
+                "/test_root/test_node",
+                &http::Method::GET,
+                HashMap::default(),
+            )
             .await
             .unwrap();
 
@@ -310,7 +347,11 @@ mod tests {
         let router = Router::nest("/test_root", router);
 
         let res = router
-            .call("/test_root/test_node", HashMap::default())
+            .call(
+                "/test_root/test_node",
+                &http::Method::GET,
+                HashMap::default(),
+            )
             .await
             .unwrap();
 
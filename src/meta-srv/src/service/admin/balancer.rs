@@ -0,0 +1,77 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashMap;
+
+use snafu::{OptionExt, ResultExt};
+use tonic::codegen::http;
+
+use crate::balancer::RegionBalancerRef;
+use crate::error::{self, Result};
+use crate::service::admin::HttpHandler;
+
+/// Returns a dry-run report of the region balancer.
+pub struct BalancerHandler {
+    pub region_balancer: RegionBalancerRef,
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for BalancerHandler {
+    async fn handle(&self, _: &str, _: &HashMap<String, String>) -> Result<http::Response<String>> {
+        report(&self.region_balancer).await
+    }
+}
+
+/// Switches the region balancer by the `enable` parameter, then returns a
+/// dry-run report of it.
+pub struct SwitchBalancerHandler {
+    pub region_balancer: RegionBalancerRef,
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for SwitchBalancerHandler {
+    async fn handle(
+        &self,
+        _: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<http::Response<String>> {
+        let enable = params
+            .get("enable")
+            .context(error::MissingRequiredParameterSnafu { param: "enable" })?;
+        let enable = enable.parse::<bool>().map_err(|_| {
+            error::InvalidArgumentsSnafu {
+                err_msg: format!("invalid value of enable: {enable}, expect true or false"),
+            }
+            .build()
+        })?;
+        self.region_balancer.set_enabled(enable).await?;
+
+        report(&self.region_balancer).await
+    }
+
+    fn accepts(&self, method: &http::Method) -> bool {
+        [http::Method::POST, http::Method::PUT].contains(method)
+    }
+}
+
+async fn report(region_balancer: &RegionBalancerRef) -> Result<http::Response<String>> {
+    let report = region_balancer.dry_run().await?;
+    let result = serde_json::to_string(&report).context(error::SerializeToJsonSnafu {
+        input: format!("{report:?}"),
+    })?;
+
+    http::Response::builder()
+        .status(http::StatusCode::OK)
+        .body(result)
+        .context(error::InvalidHttpBodySnafu)
+}
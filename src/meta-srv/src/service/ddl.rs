@@ -0,0 +1,142 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::meta::ddl::{
+    ddl_task_server, AllocateTableIdRequest, AllocateTableIdResponse, AlterTableRequest,
+    AlterTableResponse,
+};
+use api::v1::meta::{RequestHeader, ResponseHeader};
+use api::v1::AlterExpr;
+use common_telemetry::timer;
+use prost::Message;
+use snafu::ResultExt;
+use tonic::{Request, Response};
+
+use crate::error;
+use crate::metasrv::MetaSrv;
+use crate::metrics::METRIC_META_ROUTE_REQUEST;
+use crate::service::GrpcResult;
+
+#[async_trait::async_trait]
+impl ddl_task_server::DdlTask for MetaSrv {
+    async fn alter_table(&self, req: Request<AlterTableRequest>) -> GrpcResult<AlterTableResponse> {
+        let AlterTableRequest { header, alter_expr } = req.into_inner();
+        let header = RequestHeader::decode(header.as_slice())
+            .context(error::DecodeDdlRequestSnafu { field: "header" })?;
+        let alter_expr =
+            AlterExpr::decode(alter_expr.as_slice()).context(error::DecodeDdlRequestSnafu {
+                field: "alter_expr",
+            })?;
+        let cluster_id = header.cluster_id;
+
+        let _timer = timer!(
+            METRIC_META_ROUTE_REQUEST,
+            &[
+                ("op", "alter".to_string()),
+                ("cluster_id", cluster_id.to_string())
+            ]
+        );
+
+        // Alters the table on Datanodes and updates its metadata in a procedure.
+        self.ddl_manager()
+            .submit_alter_table_task(cluster_id, alter_expr)
+            .await?;
+
+        let header = ResponseHeader::success(cluster_id).encode_to_vec();
+        Ok(Response::new(AlterTableResponse { header }))
+    }
+
+    async fn allocate_table_id(
+        &self,
+        req: Request<AllocateTableIdRequest>,
+    ) -> GrpcResult<AllocateTableIdResponse> {
+        let AllocateTableIdRequest { header } = req.into_inner();
+        let header = RequestHeader::decode(header.as_slice())
+            .context(error::DecodeDdlRequestSnafu { field: "header" })?;
+        let cluster_id = header.cluster_id;
+
+        let table_id = self.table_id_sequence().next().await? as u32;
+
+        let header = ResponseHeader::success(cluster_id).encode_to_vec();
+        Ok(Response::new(AllocateTableIdResponse { header, table_id }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use api::v1::alter_expr::Kind;
+    use api::v1::meta::ddl::ddl_task_server::DdlTask;
+    use api::v1::meta::Role;
+    use api::v1::DropColumns;
+    use tonic::IntoRequest;
+
+    use super::*;
+    use crate::metasrv::builder::MetaSrvBuilder;
+    use crate::service::store::memory::MemStore;
+
+    #[tokio::test]
+    async fn test_alter_table() {
+        let meta_srv = MetaSrvBuilder::new()
+            .kv_store(Arc::new(MemStore::new()))
+            .build()
+            .await
+            .unwrap();
+
+        let alter_expr = AlterExpr {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "absent".to_string(),
+            kind: Some(Kind::DropColumns(DropColumns {
+                drop_columns: vec![],
+            })),
+        };
+        let req = AlterTableRequest {
+            header: RequestHeader::new((1, 1), Role::Frontend).encode_to_vec(),
+            alter_expr: alter_expr.encode_to_vec(),
+        };
+        let res = meta_srv.alter_table(req.into_request()).await;
+        assert!(res.unwrap_err().message().contains("not found"));
+
+        let req = AlterTableRequest {
+            header: vec![],
+            alter_expr: vec![0xff],
+        };
+        let res = meta_srv.alter_table(req.into_request()).await;
+        assert!(res.unwrap_err().message().contains("alter_expr"));
+    }
+
+    #[tokio::test]
+    async fn test_allocate_table_id() {
+        let meta_srv = MetaSrvBuilder::new()
+            .kv_store(Arc::new(MemStore::new()))
+            .build()
+            .await
+            .unwrap();
+
+        let header = RequestHeader::new((1, 1), Role::Frontend).encode_to_vec();
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            let req = AllocateTableIdRequest {
+                header: header.clone(),
+            };
+            let res = meta_srv.allocate_table_id(req.into_request()).await;
+            ids.push(res.unwrap().into_inner().table_id);
+        }
+        let id = meta_srv.table_id_sequence().next().await.unwrap() as u32;
+        assert_eq!(vec![1024, 1025], ids);
+        assert_eq!(1026, id);
+    }
+}
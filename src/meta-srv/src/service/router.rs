@@ -15,9 +15,8 @@
 use std::collections::HashMap;
 
 use api::v1::meta::{
-    router_server, BatchPutRequest, CreateRequest, DeleteRequest, Error, KeyValue,
-    MoveValueRequest, Peer, PeerDict, Region, RegionRoute, ResponseHeader, RouteRequest,
-    RouteResponse, Table, TableRoute, TableRouteValue,
+    router_server, CreateRequest, DeleteRequest, Error, Peer, PeerDict, Region, RegionRoute,
+    ResponseHeader, RouteRequest, RouteResponse, Table, TableRoute, TableRouteValue,
 };
 use catalog::helper::{TableGlobalKey, TableGlobalValue};
 use common_meta::key::TableRouteKey;
@@ -27,9 +26,9 @@ use snafu::{ensure, OptionExt, ResultExt};
 use table::metadata::RawTableInfo;
 use tonic::{Request, Response};
 
+use crate::ddl::DdlManagerRef;
 use crate::error;
 use crate::error::Result;
-use crate::lock::{keys, DistLockGuard};
 use crate::metasrv::{Context, MetaSrv, SelectorContext, SelectorRef};
 use crate::metrics::METRIC_META_ROUTE_REQUEST;
 use crate::sequence::SequenceRef;
@@ -52,10 +51,6 @@ impl router_server::Router for MetaSrv {
             .clone()
             .into();
 
-        // TODO(LFC): Use procedure to create table, and get rid of locks here.
-        let mut guard = DistLockGuard::new(self.lock(), keys::table_creation_lock_key(&table_name));
-        guard.lock().await?;
-
         let table_global_key = TableGlobalKey {
             catalog_name: table_name.catalog_name.clone(),
             schema_name: table_name.schema_name.clone(),
@@ -93,7 +88,17 @@ impl router_server::Router for MetaSrv {
         let selector = self.selector();
         let table_id_sequence = self.table_id_sequence();
 
-        let res = handle_create(req, ctx, selector, table_id_sequence).await?;
+        let follower_num = self.options().region_follower_num;
+
+        let res = handle_create(
+            req,
+            ctx,
+            selector,
+            table_id_sequence,
+            follower_num,
+            self.ddl_manager(),
+        )
+        .await?;
 
         Ok(Response::new(res))
     }
@@ -129,7 +134,7 @@ impl router_server::Router for MetaSrv {
         );
 
         let ctx = self.new_ctx();
-        let res = handle_delete(req, ctx).await?;
+        let res = handle_delete(req, ctx, self.ddl_manager()).await?;
 
         Ok(Response::new(res))
     }
@@ -140,6 +145,8 @@ async fn handle_create(
     ctx: SelectorContext,
     selector: SelectorRef,
     table_id_sequence: SequenceRef,
+    follower_num: usize,
+    ddl_manager: &DdlManagerRef,
 ) -> Result<RouteResponse> {
     let CreateRequest {
         header,
@@ -171,17 +178,24 @@ async fn handle_create(
         });
     }
 
-    // We don't need to keep all peers, just truncate it to the number of partitions.
-    // If the peers are not enough, some peers will be used for multiple partitions.
-    peers.truncate(partitions.len());
+    // Each leader gets its own followers, so that a follower can serve reads of all the
+    // regions of its leader.
+    let leader_num = partitions.len();
+    let follower_num = if peers.len() >= leader_num * (follower_num + 1) {
+        follower_num
+    } else {
+        if follower_num > 0 {
+            warn!("Create table without followers due to no enough available datanodes, table: {table_name:?}, partition number: {}, follower number: {}, datanode number: {}", leader_num, follower_num, peers.len());
+        }
+        0
+    };
+
+    // We don't need to keep all peers, just truncate it to the number of leaders and followers.
+    peers.truncate(leader_num * (follower_num + 1));
 
     let id = table_id_sequence.next().await?;
     table_info.ident.table_id = id as u32;
 
-    let table_route_key = TableRouteKey::with_table_name(id, &table_name)
-        .key()
-        .into_bytes();
-
     let table = Table {
         id,
         table_name: Some(table_name.clone()),
@@ -194,10 +208,14 @@ async fn handle_create(
             partition: Some(partition),
             ..Default::default()
         };
+        // Peers after the leaders are followers, grouped by the leaders they follow.
+        let followers_start = leader_num + i * follower_num;
         let region_route = RegionRoute {
             region: Some(region),
-            leader_peer_index: (i % peers.len()) as u64,
-            follower_peer_indexes: vec![], // follower_peers is not supported at the moment
+            leader_peer_index: i as u64,
+            follower_peer_indexes: (followers_start..followers_start + follower_num)
+                .map(|index| index as u64)
+                .collect(),
         };
         region_routes.push(region_route);
     }
@@ -212,40 +230,12 @@ async fn handle_create(
         table_route: Some(table_route.clone()),
     };
 
-    let table_global_key = TableGlobalKey {
-        catalog_name: table_name.catalog_name.clone(),
-        schema_name: table_name.schema_name.clone(),
-        table_name: table_name.table_name.clone(),
-    }
-    .to_string()
-    .into_bytes();
-
-    let table_global_value = create_table_global_value(&table_route_value, table_info)?
-        .as_bytes()
-        .context(error::InvalidCatalogValueSnafu)?;
-
-    let req = BatchPutRequest {
-        kvs: vec![
-            KeyValue {
-                key: table_global_key,
-                value: table_global_value,
-            },
-            KeyValue {
-                key: table_route_key,
-                value: table_route_value.into(),
-            },
-        ],
-        prev_kv: true,
-        ..Default::default()
-    };
+    let table_global_value = create_table_global_value(&table_route_value, table_info)?;
 
-    let resp = ctx.kv_store.batch_put(req).await?;
-    if !resp.prev_kvs.is_empty() {
-        warn!(
-            "Caution: table meta values are replaced! \
-            Maybe last creation procedure for table {table_name:?} was aborted?"
-        );
-    }
+    // Creates the table on Datanodes and saves its metadata in a procedure.
+    ddl_manager
+        .submit_create_table_task(cluster_id, table_route_value, table_global_value)
+        .await?;
 
     let header = Some(ResponseHeader::success(cluster_id));
     Ok(RouteResponse {
@@ -315,7 +305,11 @@ async fn handle_route(req: RouteRequest, ctx: Context) -> Result<RouteResponse>
     })
 }
 
-async fn handle_delete(req: DeleteRequest, ctx: Context) -> Result<RouteResponse> {
+async fn handle_delete(
+    req: DeleteRequest,
+    ctx: Context,
+    ddl_manager: &DdlManagerRef,
+) -> Result<RouteResponse> {
     let DeleteRequest { header, table_name } = req;
     let cluster_id = header.as_ref().map_or(0, |h| h.cluster_id);
     let tgk = table_name
@@ -332,10 +326,15 @@ async fn handle_delete(req: DeleteRequest, ctx: Context) -> Result<RouteResponse
             name: format!("{tgk}"),
         })?;
 
-    let _ = remove_table_global_value(&ctx.kv_store, &tgk).await?;
-
     let trk = table_route_key(tgv.table_id() as u64, &tgk);
-    let (_, trv) = remove_table_route_value(&ctx.kv_store, &trk).await?;
+    let trv = get_table_route_value(&ctx.kv_store, &trk).await?;
+
+    // Removes the metadata of the table and drops it on Datanodes in a procedure.
+    let table_name = TableName::new(&tgk.catalog_name, &tgk.schema_name, &tgk.table_name);
+    ddl_manager
+        .submit_drop_table_task(cluster_id, table_name, trv.clone(), tgv.clone())
+        .await?;
+
     let (peers, table_routes) = fill_table_routes(vec![(tgv, trv)])?;
 
     let header = Some(ResponseHeader::success(cluster_id));
@@ -412,51 +411,3 @@ fn table_route_key(table_id: u64, t: &TableGlobalKey) -> TableRouteKey<'_> {
         table_name: &t.table_name,
     }
 }
-
-async fn remove_table_route_value(
-    kv_store: &KvStoreRef,
-    key: &TableRouteKey<'_>,
-) -> Result<(Vec<u8>, TableRouteValue)> {
-    let from_key = key.key().into_bytes();
-    let to_key = key.removed_key().into_bytes();
-    let v = move_value(kv_store, from_key, to_key)
-        .await?
-        .context(error::TableRouteNotFoundSnafu { key: key.key() })?;
-    let trv: TableRouteValue =
-        v.1.as_slice()
-            .try_into()
-            .context(error::DecodeTableRouteSnafu)?;
-
-    Ok((v.0, trv))
-}
-
-async fn remove_table_global_value(
-    kv_store: &KvStoreRef,
-    key: &TableGlobalKey,
-) -> Result<(Vec<u8>, TableGlobalValue)> {
-    let key = key.to_string();
-    let removed_key = crate::keys::to_removed_key(&key);
-    let kv = move_value(kv_store, key.as_bytes(), removed_key)
-        .await?
-        .context(error::TableNotFoundSnafu { name: key })?;
-    let value: TableGlobalValue =
-        TableGlobalValue::from_bytes(&kv.1).context(error::InvalidCatalogValueSnafu)?;
-    Ok((kv.0, value))
-}
-
-async fn move_value(
-    kv_store: &KvStoreRef,
-    from_key: impl Into<Vec<u8>>,
-    to_key: impl Into<Vec<u8>>,
-) -> Result<Option<(Vec<u8>, Vec<u8>)>> {
-    let from_key = from_key.into();
-    let to_key = to_key.into();
-    let move_req = MoveValueRequest {
-        from_key,
-        to_key,
-        ..Default::default()
-    };
-    let res = kv_store.move_value(move_req).await?;
-
-    Ok(res.kv.map(|kv| (kv.key, kv.value)))
-}
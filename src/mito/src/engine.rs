@@ -425,6 +425,8 @@ impl<S: StorageEngine> MitoEngineInner<S> {
                 .write_buffer_size
                 .map(|s| s.0 as usize),
             ttl: table_info.meta.options.ttl,
+            cold_after: table_info.meta.options.cold_after,
+            read_only: request.read_only,
         };
 
         debug!(
@@ -487,6 +489,7 @@ impl<S: StorageEngine> MitoEngineInner<S> {
         _ctx: &EngineContext,
         table: Arc<MitoTable<S::Region>>,
         region_numbers: &[RegionNumber],
+        read_only: bool,
     ) -> TableResult<()> {
         let table_info = table.table_info();
         let catalog = &table_info.catalog_name;
@@ -509,6 +512,8 @@ impl<S: StorageEngine> MitoEngineInner<S> {
                 .write_buffer_size
                 .map(|s| s.0 as usize),
             ttl: table_info.meta.options.ttl,
+            cold_after: table_info.meta.options.cold_after,
+            read_only,
         };
 
         // TODO(weny): Returns an error earlier if the target region does not exist in the meta.
@@ -529,14 +534,52 @@ impl<S: StorageEngine> MitoEngineInner<S> {
         Ok(())
     }
 
+    /// Closes the read-only regions in `region_numbers`, so they can be reopened as
+    /// writable regions when a follower is promoted to the leader.
+    async fn close_read_only_regions(
+        &self,
+        table: &MitoTable<S::Region>,
+        region_numbers: &[RegionNumber],
+    ) -> TableResult<()> {
+        let read_only_regions = table.read_only_regions(region_numbers);
+        if read_only_regions.is_empty() {
+            return Ok(());
+        }
+
+        let table_id = table.table_info().ident.table_id;
+        let _ = table.remove_regions(&read_only_regions).await?;
+        let ctx = StorageEngineContext::default();
+        let opts = CloseOptions::default();
+        for region_number in read_only_regions {
+            self.storage_engine
+                .close_region(&ctx, &region_name(table_id, region_number), &opts)
+                .await
+                .map_err(BoxedError::new)
+                .context(table_error::TableOperationSnafu)?;
+
+            logging::info!(
+                "Mito engine closed read-only region {} of table {} to reopen it as writable",
+                region_number,
+                table_id
+            );
+        }
+
+        Ok(())
+    }
+
     async fn open_table(
         &self,
         ctx: &EngineContext,
         request: OpenTableRequest,
     ) -> TableResult<Option<TableRef>> {
-        if let Some(table) = self.get_table(request.table_id) {
-            if let Some(table) = self.check_regions(table, &request.region_numbers)? {
-                return Ok(Some(table));
+        if let Some(table) = self.get_mito_table(request.table_id) {
+            // Promoting read-only regions requires the table lock.
+            let need_promotion =
+                !request.read_only && !table.read_only_regions(&request.region_numbers).is_empty();
+            if !need_promotion {
+                if let Some(table) = self.check_regions(table, &request.region_numbers)? {
+                    return Ok(Some(table));
+                }
             }
         }
 
@@ -546,13 +589,23 @@ impl<S: StorageEngine> MitoEngineInner<S> {
 
             // Checks again, read lock should be enough since we are guarded by the mutex.
             if let Some(table) = self.get_mito_table(request.table_id) {
+                if !request.read_only {
+                    self.close_read_only_regions(&table, &request.region_numbers)
+                        .await?;
+                }
+
                 // Contains all regions or target region
                 if let Some(table) = self.check_regions(table.clone(), &request.region_numbers)? {
                     Some(table)
                 } else {
                     // Loads missing regions
-                    self.load_missing_regions(ctx, table.clone(), &request.region_numbers)
-                        .await?;
+                    self.load_missing_regions(
+                        ctx,
+                        table.clone(),
+                        &request.region_numbers,
+                        request.read_only,
+                    )
+                    .await?;
 
                     Some(table as _)
                 }
@@ -419,6 +419,7 @@ async fn test_open_table_with_region_number() {
         // the test table id is 1
         table_id: 1,
         region_numbers: vec![0],
+        read_only: false,
     };
 
     let invalid_open_req = OpenTableRequest {
@@ -428,6 +429,7 @@ async fn test_open_table_with_region_number() {
         // the test table id is 1
         table_id: 1,
         region_numbers: vec![1],
+        read_only: false,
     };
 
     let (_engine, storage_engine, table, object_store, _dir) = {
@@ -483,6 +485,7 @@ async fn test_open_table() {
         // the test table id is 1
         table_id: 1,
         region_numbers: vec![0],
+        read_only: false,
     };
 
     let (_engine, storage_engine, table, object_store, _dir) = {
@@ -528,6 +531,86 @@ async fn test_open_table() {
     assert_eq!(reopened.manifest().last_version(), 1);
 }
 
+#[tokio::test]
+async fn test_open_table_read_only() {
+    common_telemetry::init_default_ut_logging();
+
+    let ctx = EngineContext::default();
+    let open_req = |read_only| OpenTableRequest {
+        catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+        schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+        table_name: test_util::TABLE_NAME.to_string(),
+        // the test table id is 1
+        table_id: 1,
+        region_numbers: vec![0],
+        read_only,
+    };
+    let columns_values = || {
+        let hosts: VectorRef = Arc::new(StringVector::from(vec!["host1", "host2"]));
+        let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 2.0]));
+        let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 2.0]));
+        let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![1, 2]));
+        HashMap::from([
+            ("host".to_string(), hosts),
+            ("cpu".to_string(), cpus),
+            ("memory".to_string(), memories),
+            ("ts".to_string(), tss),
+        ])
+    };
+
+    let TestEngineComponents {
+        table_engine,
+        storage_engine,
+        table_ref: table,
+        object_store,
+        dir: _dir,
+        ..
+    } = test_util::setup_test_engine_and_table().await;
+    let insert_req = new_insert_request("demo".to_string(), columns_values());
+    assert_eq!(2, table.insert(insert_req).await.unwrap());
+    // Closes the table with flush so the data is visible to followers.
+    let _ = table_engine
+        .close_table(
+            &ctx,
+            CloseTableRequest {
+                catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+                schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name: test_util::TABLE_NAME.to_string(),
+                table_id: 1,
+                region_numbers: vec![0],
+                flush: true,
+            },
+        )
+        .await
+        .unwrap();
+
+    let table_engine = MitoEngine::new(EngineConfig::default(), storage_engine, object_store);
+    let follower = table_engine
+        .open_table(&ctx, open_req(true))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(follower.region_stats().unwrap()[0].read_only);
+    let insert_req = new_insert_request("demo".to_string(), columns_values());
+    assert!(follower.insert(insert_req).await.is_err());
+
+    let session_ctx = SessionContext::new();
+    let stream = follower.scan(None, &[], None).await.unwrap();
+    let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+    let batches = util::collect_batches(stream).await.unwrap();
+    assert_eq!(2, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+
+    // Opening the table as writable promotes the read-only region.
+    let leader = table_engine
+        .open_table(&ctx, open_req(false))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!leader.region_stats().unwrap()[0].read_only);
+    let insert_req = new_insert_request("demo".to_string(), columns_values());
+    assert_eq!(2, leader.insert(insert_req).await.unwrap());
+}
+
 #[test]
 fn test_region_id() {
     assert_eq!(1, region_id(0, 1));
@@ -745,6 +828,7 @@ async fn test_alter_rename_table() {
         table_name: new_table_name.to_string(),
         table_id,
         region_numbers: vec![0],
+        read_only: false,
     };
 
     // test reopen table
@@ -148,7 +148,7 @@ impl<R: Region> Table for MitoTable<R> {
         _limit: Option<usize>,
     ) -> TableResult<PhysicalPlanRef> {
         let read_ctx = ReadContext::default();
-        let regions = self.regions.load();
+        let regions = self.scannable_regions();
 
         let mut readers = Vec::with_capacity(regions.len());
         let mut first_schema: Option<Arc<Schema>> = None;
@@ -157,7 +157,7 @@ impl<R: Region> Table for MitoTable<R> {
         // TODO(hl): Currently the API between frontend and datanode is under refactoring in
         // https://github.com/GreptimeTeam/greptimedb/issues/597 . Once it's finished, query plan
         // can carry filtered region info to avoid scanning all regions on datanode.
-        for region in regions.values() {
+        for region in &regions {
             let snapshot = region
                 .snapshot(&read_ctx)
                 .map_err(BoxedError::new)
@@ -225,7 +225,7 @@ impl<R: Region> Table for MitoTable<R> {
 
     async fn scan_to_stream(&self, request: ScanRequest) -> TableResult<SendableRecordBatchStream> {
         let read_ctx = ReadContext::default();
-        let regions = self.regions.load();
+        let regions = self.scannable_regions();
         let mut readers = Vec::with_capacity(regions.len());
         let mut first_schema: Option<Arc<Schema>> = None;
 
@@ -233,7 +233,7 @@ impl<R: Region> Table for MitoTable<R> {
         // TODO(hl): Currently the API between frontend and datanode is under refactoring in
         // https://github.com/GreptimeTeam/greptimedb/issues/597 . Once it's finished, query plan
         // can carry filtered region info to avoid scanning all regions on datanode.
-        for region in regions.values() {
+        for region in &regions {
             let snapshot = region
                 .snapshot(&read_ctx)
                 .map_err(BoxedError::new)
@@ -356,7 +356,7 @@ impl<R: Region> Table for MitoTable<R> {
         let mut rows_deleted = 0;
         // TODO(hl): Should be tracked by procedure.
         // TODO(hl): Parse delete request into region->keys instead of delete in each region
-        for region in regions.values() {
+        for region in regions.values().filter(|region| !region.is_read_only()) {
             let mut write_request = region.write_request();
             let key_column_values = request.key_column_values.clone();
             // Safety: key_column_values isn't empty.
@@ -421,10 +421,7 @@ impl<R: Region> Table for MitoTable<R> {
 
         Ok(regions
             .values()
-            .map(|region| RegionStat {
-                region_id: region.id(),
-                disk_usage_bytes: region.disk_usage_bytes(),
-            })
+            .map(|region| region.region_stat())
             .collect())
     }
 
@@ -621,10 +618,16 @@ impl<R: Region> MitoTable<R> {
     pub async fn drop_regions(&self, region_number: &[RegionNumber]) -> TableResult<()> {
         let regions = self.remove_regions(region_number).await?;
 
-        let _ = futures::future::try_join_all(regions.values().map(|region| region.drop_region()))
-            .await
-            .map_err(BoxedError::new)
-            .context(table_error::TableOperationSnafu)?;
+        // Files of read-only regions are dropped by their leaders.
+        let _ = futures::future::try_join_all(
+            regions
+                .values()
+                .filter(|region| !region.is_read_only())
+                .map(|region| region.drop_region()),
+        )
+        .await
+        .map_err(BoxedError::new)
+        .context(table_error::TableOperationSnafu)?;
         Ok(())
     }
 
@@ -640,6 +643,33 @@ impl<R: Region> MitoTable<R> {
         regions.iter().map(|(k, _)| *k).collect()
     }
 
+    /// Returns the numbers of the read-only regions in `region_numbers`.
+    pub(crate) fn read_only_regions(&self, region_numbers: &[RegionNumber]) -> Vec<RegionNumber> {
+        let regions = self.regions.load();
+        region_numbers
+            .iter()
+            .filter(|number| {
+                regions
+                    .get(number)
+                    .map(|region| region.is_read_only())
+                    .unwrap_or(false)
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Returns the regions to scan. Read-only regions are only scanned if there is no
+    /// writable region of the table, in which case the datanode serves follower reads.
+    fn scannable_regions(&self) -> Vec<R> {
+        let regions = self.regions.load();
+        let has_writable = regions.values().any(|region| !region.is_read_only());
+        regions
+            .values()
+            .filter(|region| !has_writable || !region.is_read_only())
+            .cloned()
+            .collect()
+    }
+
     pub fn set_table_info(&self, table_info: TableInfo) {
         let _ = self.table_info.swap(Arc::new(table_info));
     }
@@ -662,7 +692,8 @@ impl<R: Region> MitoTable<R> {
         alter_op: &AlterOperation,
     ) -> TableResult<()> {
         let regions = self.regions.load();
-        for region in regions.values() {
+        // Read-only regions catch up with the metadata from the manifest.
+        for region in regions.values().filter(|region| !region.is_read_only()) {
             let region_meta = region.in_memory_metadata();
             if u64::from(region_meta.version()) > table_version {
                 // Region is already altered.
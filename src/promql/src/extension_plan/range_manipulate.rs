@@ -89,6 +89,27 @@ impl RangeManipulate {
         })
     }
 
+    /// Creates a plan that evaluates each time series at the timestamps aligned to
+    /// `interval` (since the unix epoch) whose ranges cover some points of the
+    /// series, instead of the timestamps of a requested range.
+    pub fn new_aligned(
+        interval: Millisecond,
+        range: Millisecond,
+        time_index: String,
+        field_columns: Vec<String>,
+        input: LogicalPlan,
+    ) -> DataFusionResult<Self> {
+        Self::new(
+            Millisecond::MIN,
+            Millisecond::MAX,
+            interval,
+            range,
+            time_index,
+            field_columns,
+            input,
+        )
+    }
+
     pub const fn name() -> &'static str {
         "RangeManipulate"
     }
@@ -340,15 +361,20 @@ impl ExecutionPlan for RangeManipulateExec {
     fn statistics(&self) -> Statistics {
         let input_stats = self.input.statistics();
 
-        let estimated_row_num = (self.end - self.start) as f64 / self.interval as f64;
+        let estimated_row_num = if is_aligned_to_data(self.start, self.end) {
+            input_stats.num_rows.map(|rows| rows as f64)
+        } else {
+            Some((self.end - self.start) as f64 / self.interval as f64)
+        };
         let estimated_total_bytes = input_stats
             .total_byte_size
             .zip(input_stats.num_rows)
-            .map(|(size, rows)| (size as f64 / rows as f64) * estimated_row_num)
+            .zip(estimated_row_num)
+            .map(|((size, rows), row_num)| (size as f64 / rows as f64) * row_num)
             .map(|size| size.floor() as _);
 
         Statistics {
-            num_rows: Some(estimated_row_num.floor() as _),
+            num_rows: estimated_row_num.map(|rows| rows.floor() as _),
             total_byte_size: estimated_total_bytes,
             // TODO(ruihang): support this column statistics
             column_statistics: None,
@@ -456,7 +482,8 @@ impl RangeManipulateStream {
         let mut ranges = vec![];
 
         // calculate for every aligned timestamp (`curr_ts`), assume the ts column is ordered.
-        for curr_ts in (self.start..=self.end).step_by(self.interval as _) {
+        let (start, end) = self.eval_range(ts_column);
+        for curr_ts in (start..=end).step_by(self.interval as _) {
             aligned_ts.push(curr_ts);
             let mut range_start = ts_column.len();
             let mut range_end = 0;
@@ -481,6 +508,29 @@ impl RangeManipulateStream {
 
         (aligned_ts_array, ranges)
     }
+
+    /// Returns the first and the last timestamps to evaluate. For plans created by
+    /// [RangeManipulate::new_aligned], they are the aligned timestamps whose ranges
+    /// cover the points of this series.
+    fn eval_range(&self, ts_column: &TimestampMillisecondArray) -> (Millisecond, Millisecond) {
+        if !is_aligned_to_data(self.start, self.end) {
+            return (self.start, self.end);
+        }
+        let values = ts_column.values();
+        let (Some(first), Some(last)) = (values.first(), values.last()) else {
+            return (0, -1);
+        };
+
+        let start =
+            first.saturating_add((self.interval - first.rem_euclid(self.interval)) % self.interval);
+        let end = last.saturating_add(self.range);
+        (start, end - end.rem_euclid(self.interval))
+    }
+}
+
+/// Whether the plan is created by [RangeManipulate::new_aligned].
+fn is_aligned_to_data(start: Millisecond, end: Millisecond) -> bool {
+    start == Millisecond::MIN && end == Millisecond::MAX
 }
 
 #[cfg(test)]
@@ -641,4 +691,30 @@ mod test {
         }");
         do_normalize_test(1, 10_001, 3_000, 1_000, expected).await;
     }
+
+    #[tokio::test]
+    async fn aligned_to_data() {
+        let expected = String::from(
+            "PrimitiveArray<Timestamp(Millisecond, None)>\n[\n  \
+                1970-01-01T00:00:00,\n  \
+                1970-01-01T00:01:00,\n  \
+                1970-01-01T00:02:00,\n  \
+                1970-01-01T00:03:00,\n  \
+                1970-01-01T00:04:00,\n  \
+                1970-01-01T00:05:00,\n  \
+                1970-01-01T00:06:00,\n\
+            ]\nRangeArray { \
+                base array: PrimitiveArray<Float64>\n[\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n], \
+                ranges: [Some(0..1), Some(0..3), Some(1..5), Some(3..6), Some(5..7), Some(6..10), Some(8..10)] \
+            }\nRangeArray { \
+                base array: PrimitiveArray<Float64>\n[\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n  1.0,\n], \
+                ranges: [Some(0..1), Some(0..3), Some(1..5), Some(3..6), Some(5..7), Some(6..10), Some(8..10)] \
+            }\nStringArray\n[\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n  \"foo\",\n]\n\
+            RangeArray { \
+                base array: PrimitiveArray<Timestamp(Millisecond, None)>\n[\n  1970-01-01T00:00:00,\n  1970-01-01T00:00:30,\n  1970-01-01T00:01:00,\n  1970-01-01T00:01:30,\n  1970-01-01T00:02:00,\n  1970-01-01T00:03:00,\n  1970-01-01T00:04:00,\n  1970-01-01T00:04:01,\n  1970-01-01T00:04:31,\n  1970-01-01T00:04:51,\n], \
+                ranges: [Some(0..1), Some(0..3), Some(1..5), Some(3..6), Some(5..7), Some(6..10), Some(8..10)] \
+            }",
+        );
+        do_normalize_test(Millisecond::MIN, Millisecond::MAX, 60_000, 90_000, expected).await;
+    }
 }
@@ -0,0 +1,440 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use datafusion::arrow::array::{Array, Float64Array, TimestampMillisecondArray};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::{DFSchemaRef, Result as DataFusionResult, Statistics};
+use datafusion::error::DataFusionError;
+use datafusion::execution::context::TaskContext;
+use datafusion::logical_expr::{Expr, LogicalPlan, UserDefinedLogicalNodeCore};
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::{
+    DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream, SendableRecordBatchStream,
+};
+use futures::{Stream, StreamExt};
+
+/// How to fill the null values of a column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fill {
+    /// Leaves the null values as is.
+    Null,
+    /// Fills with the previous non-null value.
+    Prev,
+    /// Fills with the value linearly interpolated between the previous and the
+    /// next non-null values.
+    Linear,
+    /// Fills with a constant.
+    Const(f64),
+}
+
+impl Eq for Fill {}
+
+impl Hash for Fill {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        if let Fill::Const(value) = self {
+            value.to_bits().hash(state);
+        }
+    }
+}
+
+impl Display for Fill {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Fill::Null => write!(f, "NULL"),
+            Fill::Prev => write!(f, "PREV"),
+            Fill::Linear => write!(f, "LINEAR"),
+            Fill::Const(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl Fill {
+    /// Fills the null values of one time series, `timestamps` are used to
+    /// interpolate.
+    pub fn fill(
+        &self,
+        values: &Float64Array,
+        timestamps: &TimestampMillisecondArray,
+    ) -> Float64Array {
+        match self {
+            Fill::Null => values.clone(),
+            Fill::Const(constant) => values.iter().map(|v| v.or(Some(*constant))).collect(),
+            Fill::Prev => {
+                let mut prev = None;
+                values
+                    .iter()
+                    .map(|v| {
+                        if v.is_some() {
+                            prev = v;
+                        }
+                        prev
+                    })
+                    .collect()
+            }
+            Fill::Linear => {
+                let mut result = values.iter().collect::<Vec<_>>();
+                let known = values
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, v)| v.map(|v| (i, v)))
+                    .collect::<Vec<_>>();
+                for pair in known.windows(2) {
+                    let ((start, start_value), (end, end_value)) = (pair[0], pair[1]);
+                    let start_ts = timestamps.value(start) as f64;
+                    let end_ts = timestamps.value(end) as f64;
+                    let slope = (end_value - start_value) / (end_ts - start_ts);
+                    for (i, filled) in result.iter_mut().enumerate().take(end).skip(start + 1) {
+                        *filled =
+                            Some(start_value + slope * (timestamps.value(i) as f64 - start_ts));
+                    }
+                }
+                Float64Array::from(result)
+            }
+        }
+    }
+}
+
+/// Fills the null values of the float columns in each time series. Notice that
+/// for simplicity, this plan assumes the input batch only contains points from
+/// one time series ordered by timestamp, like the output of [RangeManipulate].
+///
+/// [RangeManipulate]: crate::extension_plan::RangeManipulate
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SeriesFill {
+    time_index: String,
+    fill_columns: Vec<(String, Fill)>,
+    input: LogicalPlan,
+}
+
+impl UserDefinedLogicalNodeCore for SeriesFill {
+    fn name(&self) -> &str {
+        Self::name()
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![&self.input]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        self.input.schema()
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        vec![]
+    }
+
+    fn fmt_for_explain(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "PromSeriesFill: time index=[{}], fill=[{}]",
+            self.time_index,
+            format_fill_columns(&self.fill_columns)
+        )
+    }
+
+    fn from_template(&self, _exprs: &[Expr], inputs: &[LogicalPlan]) -> Self {
+        assert!(!inputs.is_empty());
+
+        Self {
+            time_index: self.time_index.clone(),
+            fill_columns: self.fill_columns.clone(),
+            input: inputs[0].clone(),
+        }
+    }
+}
+
+impl SeriesFill {
+    pub fn new(time_index: String, fill_columns: Vec<(String, Fill)>, input: LogicalPlan) -> Self {
+        Self {
+            time_index,
+            fill_columns,
+            input,
+        }
+    }
+
+    pub const fn name() -> &'static str {
+        "SeriesFill"
+    }
+
+    pub fn to_execution_plan(&self, exec_input: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        Arc::new(SeriesFillExec {
+            time_index: self.time_index.clone(),
+            fill_columns: self.fill_columns.clone(),
+            input: exec_input,
+            metric: ExecutionPlanMetricsSet::new(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct SeriesFillExec {
+    time_index: String,
+    fill_columns: Vec<(String, Fill)>,
+    input: Arc<dyn ExecutionPlan>,
+    metric: ExecutionPlanMetricsSet,
+}
+
+impl ExecutionPlan for SeriesFillExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true; self.children().len()]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        assert!(!children.is_empty());
+        Ok(Arc::new(Self {
+            time_index: self.time_index.clone(),
+            fill_columns: self.fill_columns.clone(),
+            input: children[0].clone(),
+            metric: self.metric.clone(),
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        let baseline_metric = BaselineMetrics::new(&self.metric, partition);
+
+        let input = self.input.execute(partition, context)?;
+        let schema = input.schema();
+        let time_index = schema
+            .column_with_name(&self.time_index)
+            .unwrap_or_else(|| panic!("time index column {} not found", self.time_index))
+            .0;
+        let fill_columns = self
+            .fill_columns
+            .iter()
+            .map(|(name, fill)| {
+                let index = schema
+                    .column_with_name(name)
+                    .unwrap_or_else(|| panic!("fill column {name} not found"))
+                    .0;
+                (index, *fill)
+            })
+            .collect();
+        Ok(Box::pin(SeriesFillStream {
+            time_index,
+            fill_columns,
+            schema,
+            input,
+            metric: baseline_metric,
+        }))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(
+                    f,
+                    "PromSeriesFillExec: time index=[{}], fill=[{}]",
+                    self.time_index,
+                    format_fill_columns(&self.fill_columns)
+                )
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metric.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.input.statistics()
+    }
+}
+
+pub struct SeriesFillStream {
+    time_index: usize,
+    fill_columns: Vec<(usize, Fill)>,
+
+    schema: SchemaRef,
+    input: SendableRecordBatchStream,
+    metric: BaselineMetrics,
+}
+
+impl SeriesFillStream {
+    pub fn fill(&self, input: RecordBatch) -> DataFusionResult<RecordBatch> {
+        let timestamps = input
+            .column(self.time_index)
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .unwrap();
+
+        let mut columns = input.columns().to_vec();
+        for (index, fill) in &self.fill_columns {
+            let values = columns[*index]
+                .as_any()
+                .downcast_ref::<Float64Array>()
+                .ok_or_else(|| {
+                    DataFusionError::Execution(format!(
+                        "Expect Float64 column to fill, found {}",
+                        columns[*index].data_type()
+                    ))
+                })?;
+            columns[*index] = Arc::new(fill.fill(values, timestamps));
+        }
+
+        RecordBatch::try_new(self.schema.clone(), columns).map_err(DataFusionError::ArrowError)
+    }
+}
+
+impl RecordBatchStream for SeriesFillStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl Stream for SeriesFillStream {
+    type Item = DataFusionResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = match self.input.poll_next_unpin(cx) {
+            Poll::Ready(batch) => {
+                let _timer = self.metric.elapsed_compute().timer();
+                Poll::Ready(batch.map(|batch| batch.and_then(|batch| self.fill(batch))))
+            }
+            Poll::Pending => Poll::Pending,
+        };
+        self.metric.record_poll(poll)
+    }
+}
+
+fn format_fill_columns(fill_columns: &[(String, Fill)]) -> String {
+    fill_columns
+        .iter()
+        .map(|(name, fill)| format!("{name}: {fill}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use datafusion::arrow::datatypes::{
+        ArrowPrimitiveType, DataType, Field, Schema, TimestampMillisecondType,
+    };
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::prelude::SessionContext;
+
+    use super::*;
+
+    const TIME_INDEX_COLUMN: &str = "timestamp";
+
+    fn prepare_test_data() -> MemoryExec {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(TIME_INDEX_COLUMN, TimestampMillisecondType::DATA_TYPE, true),
+            Field::new("prev", DataType::Float64, true),
+            Field::new("linear", DataType::Float64, true),
+            Field::new("constant", DataType::Float64, true),
+            Field::new("null", DataType::Float64, true),
+        ]));
+        let timestamp_column = Arc::new(TimestampMillisecondArray::from(vec![
+            0, 10_000, 20_000, 30_000, 60_000, 70_000,
+        ])) as _;
+        let values = vec![None, Some(1.0), None, Some(3.0), None, Some(10.0)];
+        let field_column = Arc::new(Float64Array::from(values)) as Arc<dyn Array>;
+        let data = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                timestamp_column,
+                field_column.clone(),
+                field_column.clone(),
+                field_column.clone(),
+                field_column,
+            ],
+        )
+        .unwrap();
+
+        MemoryExec::try_new(&[vec![data]], schema, None).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fill_series() {
+        let memory_exec = Arc::new(prepare_test_data());
+        let fill_exec = Arc::new(SeriesFillExec {
+            time_index: TIME_INDEX_COLUMN.to_string(),
+            fill_columns: vec![
+                ("prev".to_string(), Fill::Prev),
+                ("linear".to_string(), Fill::Linear),
+                ("constant".to_string(), Fill::Const(-1.0)),
+                ("null".to_string(), Fill::Null),
+            ],
+            input: memory_exec,
+            metric: ExecutionPlanMetricsSet::new(),
+        });
+        let session_context = SessionContext::default();
+        let result = datafusion::physical_plan::collect(fill_exec, session_context.task_ctx())
+            .await
+            .unwrap();
+        let result_literal = datatypes::arrow::util::pretty::pretty_format_batches(&result)
+            .unwrap()
+            .to_string();
+
+        let expected = String::from(
+            "+---------------------+------+--------+----------+------+\
+            \n| timestamp           | prev | linear | constant | null |\
+            \n+---------------------+------+--------+----------+------+\
+            \n| 1970-01-01T00:00:00 |      |        | -1.0     |      |\
+            \n| 1970-01-01T00:00:10 | 1.0  | 1.0    | 1.0      | 1.0  |\
+            \n| 1970-01-01T00:00:20 | 1.0  | 2.0    | -1.0     |      |\
+            \n| 1970-01-01T00:00:30 | 3.0  | 3.0    | 3.0      | 3.0  |\
+            \n| 1970-01-01T00:01:00 | 3.0  | 8.25   | -1.0     |      |\
+            \n| 1970-01-01T00:01:10 | 10.0 | 10.0   | 10.0     | 10.0 |\
+            \n+---------------------+------+--------+----------+------+",
+        );
+
+        assert_eq!(result_literal, expected);
+    }
+
+    #[test]
+    fn test_fill_display() {
+        assert_eq!("NULL", Fill::Null.to_string());
+        assert_eq!("PREV", Fill::Prev.to_string());
+        assert_eq!("LINEAR", Fill::Linear.to_string());
+        assert_eq!("1.5", Fill::Const(1.5).to_string());
+    }
+}
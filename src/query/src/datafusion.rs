@@ -31,7 +31,9 @@ use common_query::physical_plan::{DfPhysicalPlanAdapter, PhysicalPlan, PhysicalP
 use common_query::prelude::ScalarUdf;
 use common_query::Output;
 use common_recordbatch::adapter::RecordBatchStreamAdapter;
-use common_recordbatch::{EmptyRecordBatchStream, SendableRecordBatchStream};
+use common_recordbatch::{
+    EmptyRecordBatchStream, RecordBatchStreamWithPlan, SendableRecordBatchStream,
+};
 use common_telemetry::timer;
 use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
 use datafusion::physical_plan::ExecutionPlan;
@@ -75,7 +77,8 @@ impl DatafusionQueryEngine {
         plan: LogicalPlan,
         query_ctx: QueryContextRef,
     ) -> Result<Output> {
-        let mut ctx = QueryEngineContext::new(self.state.session_state());
+        let mut ctx = QueryEngineContext::new(self.state.session_state())
+            .with_memory_limit(self.state.query_memory_limit());
 
         // `create_physical_plan` will optimize logical plan internally
         let physical_plan = self.create_physical_plan(&mut ctx, &plan).await?;
@@ -87,7 +90,16 @@ impl DatafusionQueryEngine {
             physical_plan
         };
 
-        Ok(Output::Stream(self.execute_stream(&ctx, &physical_plan)?))
+        let stream = self.execute_stream(&ctx, &physical_plan)?;
+        // Keeps the plan along with the stream, so that its metrics can be
+        // inspected once the stream is consumed, e.g. by the slow query log.
+        let df_plan = match physical_plan.as_any().downcast_ref::<PhysicalPlanAdapter>() {
+            Some(adapter) => adapter.df_plan(),
+            None => Arc::new(DfPhysicalPlanAdapter(physical_plan.clone())),
+        };
+        Ok(Output::Stream(Box::pin(RecordBatchStreamWithPlan::new(
+            df_plan, stream,
+        ))))
     }
 
     async fn exec_dml_statement(
@@ -359,7 +371,7 @@ impl QueryExecutor for DatafusionQueryEngine {
         match plan.output_partitioning().partition_count() {
             0 => Ok(Box::pin(EmptyRecordBatchStream::new(plan.schema()))),
             1 => Ok(plan
-                .execute(0, ctx.state().task_ctx())
+                .execute(0, ctx.task_ctx())
                 .context(error::ExecutePhysicalPlanSnafu)
                 .map_err(BoxedError::new)
                 .context(QueryExecutionSnafu))?,
@@ -370,7 +382,7 @@ impl QueryExecutor for DatafusionQueryEngine {
                 // CoalescePartitionsExec must produce a single partition
                 assert_eq!(1, plan.output_partitioning().partition_count());
                 let df_stream = plan
-                    .execute(0, ctx.state().task_ctx())
+                    .execute(0, ctx.task_ctx())
                     .context(error::DatafusionSnafu {
                         msg: "Failed to execute DataFusion merge exec",
                     })
@@ -15,6 +15,7 @@
 use std::any::Any;
 
 use common_error::prelude::*;
+use common_recordbatch::error::datafusion_status_code;
 use datafusion::error::DataFusionError;
 use snafu::Location;
 
@@ -60,7 +61,9 @@ impl ErrorExt for InnerError {
 
         match self {
             // TODO(yingwen): Further categorize datafusion error.
-            Datafusion { .. } => StatusCode::EngineExecuteQuery,
+            Datafusion { source, .. } => {
+                datafusion_status_code(source, StatusCode::EngineExecuteQuery)
+            }
             PhysicalPlanDowncast { .. } | ConvertSchema { .. } => StatusCode::Unexpected,
             ConvertDfRecordBatchStream { source, .. } => source.status_code(),
             ExecutePhysicalPlan { source, .. } => source.status_code(),
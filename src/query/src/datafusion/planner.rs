@@ -17,6 +17,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use arrow_schema::DataType;
+use catalog::error::Error as CatalogError;
 use catalog::table_source::DfTableSourceProvider;
 use common_query::logical_plan::create_aggregate_function;
 use datafusion::catalog::TableReference;
@@ -30,7 +31,6 @@ use datafusion_common::{DataFusionError, OwnedTableReference};
 use datafusion_expr::TableSource;
 use datafusion_physical_expr::var_provider::{is_system_variables, VarType};
 use datafusion_sql::parser::Statement as DfStatement;
-use session::context::QueryContextRef;
 use snafu::ResultExt;
 
 use crate::error::{CatalogSnafu, DataFusionSnafu, Result};
@@ -48,18 +48,12 @@ impl DfContextProviderAdapter {
         engine_state: Arc<QueryEngineState>,
         session_state: SessionState,
         df_stmt: &DfStatement,
-        query_ctx: QueryContextRef,
+        mut table_provider: DfTableSourceProvider,
     ) -> Result<Self> {
         let table_names = session_state
             .resolve_table_references(df_stmt)
             .context(DataFusionSnafu)?;
 
-        let mut table_provider = DfTableSourceProvider::new(
-            engine_state.catalog_manager().clone(),
-            engine_state.disallow_cross_schema_query(),
-            query_ctx.as_ref(),
-        );
-
         let tables = resolve_tables(table_names, &mut table_provider).await?;
 
         Ok(Self {
@@ -85,9 +79,15 @@ async fn resolve_tables(
         if let Entry::Vacant(v) = tables.entry(resolved_name.to_string()) {
             // Try our best to resolve the tables here, but we don't return an error if table is not found,
             // because the table name may be a temporary name of CTE or view, they can't be found until plan
-            // execution.
-            if let Ok(table) = table_provider.resolve_table(table_name).await {
-                let _ = v.insert(table);
+            // execution. But failures in expanding views are reported.
+            match table_provider.resolve_table(table_name).await {
+                Ok(table) => {
+                    let _ = v.insert(table);
+                }
+                Err(e @ (CatalogError::PlanView { .. } | CatalogError::CyclicView { .. })) => {
+                    return Err(e).context(CatalogSnafu);
+                }
+                Err(_) => {}
             }
         }
     }
@@ -30,6 +30,7 @@ use common_recordbatch::error::ExternalSnafu;
 use common_recordbatch::{
     DfSendableRecordBatchStream, RecordBatchStreamAdaptor, SendableRecordBatchStream,
 };
+use common_telemetry::tracing::{info_span, Instrument, Span};
 use datafusion::physical_plan::{DisplayFormatType, ExecutionPlan, Partitioning};
 use datafusion_common::{DataFusionError, Result, Statistics};
 use datafusion_expr::{Extension, LogicalPlan, UserDefinedLogicalNodeCore};
@@ -137,6 +138,9 @@ impl MergeScanExec {
         let peers = self.peers.clone();
         let clients = self.clients.clone();
         let table = self.table.clone();
+        // The stream may be polled outside the span of the query, so sub-requests are
+        // parented to the span creating the stream.
+        let parent_span = Span::current();
 
         let stream = try_stream! {
             for peer in peers {
@@ -144,6 +148,11 @@ impl MergeScanExec {
                 let database = Database::new(&table.catalog_name, &table.schema_name, client);
                 let output: Output = database
                     .logical_plan(substrait_plan.clone())
+                    .instrument(info_span!(
+                        parent: &parent_span,
+                        "merge_scan_request",
+                        peer = %peer
+                    ))
                     .await
                     .context(RemoteRequestSnafu)
                     .map_err(BoxedError::new)
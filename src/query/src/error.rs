@@ -16,6 +16,7 @@ use std::any::Any;
 
 use common_error::prelude::*;
 use common_meta::table_name::TableName;
+use common_recordbatch::error::datafusion_status_code;
 use datafusion::error::DataFusionError;
 use datatypes::prelude::ConcreteDataType;
 use datatypes::value::Value;
@@ -218,6 +219,13 @@ pub enum Error {
         source: datatypes::error::Error,
         location: Location,
     },
+
+    #[snafu(display("Invalid partition column {}: {}", column, reason))]
+    InvalidPartitionColumn {
+        column: String,
+        reason: String,
+        location: Location,
+    },
 }
 
 impl ErrorExt for Error {
@@ -235,7 +243,8 @@ impl ErrorExt for Error {
             | ParseFloat { .. }
             | MissingRequiredField { .. }
             | BuildRegex { .. }
-            | ConvertSchema { .. } => StatusCode::InvalidArguments,
+            | ConvertSchema { .. }
+            | InvalidPartitionColumn { .. } => StatusCode::InvalidArguments,
 
             BuildBackend { .. } | ListObjects { .. } => StatusCode::StorageUnavailable,
             EncodeSubstraitLogicalPlan { source, .. } => source.status_code(),
@@ -250,9 +259,8 @@ impl ErrorExt for Error {
             ParseSql { source, .. } => source.status_code(),
             CreateRecordBatch { source, .. } => source.status_code(),
             QueryExecution { source, .. } | QueryPlan { source, .. } => source.status_code(),
-            DataFusion { .. } | MissingTimestampColumn { .. } | RoutePartition { .. } => {
-                StatusCode::Internal
-            }
+            DataFusion { source, .. } => datafusion_status_code(source, StatusCode::Internal),
+            MissingTimestampColumn { .. } | RoutePartition { .. } => StatusCode::Internal,
             Sql { source, .. } => source.status_code(),
             PlanSql { .. } => StatusCode::PlanQuery,
             ConvertSqlType { source, .. } | ConvertSqlValue { source, .. } => source.status_code(),
@@ -0,0 +1,447 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Logical and physical plans to generate the missing time buckets of a
+//! `GROUP BY time_bucket_gapfill(...)` query, see
+//! [GapFillRule](crate::optimizer::gap_fill::GapFillRule).
+
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use common_function::scalars::gapfill::time_bucket;
+use datafusion::arrow::array::{
+    Array, ArrayRef, Float64Array, TimestampMillisecondArray, UInt32Array,
+};
+use datafusion::arrow::compute;
+use datafusion::arrow::datatypes::{DataType, SchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::{DFSchemaRef, Result as DfResult, ScalarValue, Statistics};
+use datafusion::error::DataFusionError;
+use datafusion::execution::context::{SessionState, TaskContext};
+use datafusion::logical_expr::{
+    Expr, LogicalPlan, UserDefinedLogicalNode, UserDefinedLogicalNodeCore,
+};
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::planner::ExtensionPlanner;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning, PhysicalPlanner,
+    SendableRecordBatchStream,
+};
+use futures::{stream, TryStreamExt};
+use promql::extension_plan::Fill;
+
+/// Generates a row for each empty bucket in `[start, end)` of every series,
+/// the series are identified by the `group_columns`. Columns in `fill_columns`
+/// are filled in the generated rows, others are left null.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct GapFill {
+    time_bucket: String,
+    interval: i64,
+    start: i64,
+    end: i64,
+    group_columns: Vec<String>,
+    fill_columns: Vec<(String, Fill)>,
+    input: LogicalPlan,
+}
+
+impl UserDefinedLogicalNodeCore for GapFill {
+    fn name(&self) -> &str {
+        Self::name()
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![&self.input]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        self.input.schema()
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        vec![]
+    }
+
+    fn fmt_for_explain(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "GapFill: time bucket=[{}], interval=[{}], range=[{}..{}], group by=[{}], fill=[{}]",
+            self.time_bucket,
+            self.interval,
+            self.start,
+            self.end,
+            self.group_columns.join(", "),
+            format_fill_columns(&self.fill_columns)
+        )
+    }
+
+    fn from_template(&self, _exprs: &[Expr], inputs: &[LogicalPlan]) -> Self {
+        assert!(!inputs.is_empty());
+
+        Self {
+            time_bucket: self.time_bucket.clone(),
+            interval: self.interval,
+            start: self.start,
+            end: self.end,
+            group_columns: self.group_columns.clone(),
+            fill_columns: self.fill_columns.clone(),
+            input: inputs[0].clone(),
+        }
+    }
+}
+
+impl GapFill {
+    /// Creates a new [GapFill], `interval`, `start` and `end` are in
+    /// milliseconds.
+    pub fn new(
+        time_bucket: String,
+        interval: i64,
+        start: i64,
+        end: i64,
+        group_columns: Vec<String>,
+        fill_columns: Vec<(String, Fill)>,
+        input: LogicalPlan,
+    ) -> Self {
+        Self {
+            time_bucket,
+            interval,
+            start,
+            end,
+            group_columns,
+            fill_columns,
+            input,
+        }
+    }
+
+    pub const fn name() -> &'static str {
+        "GapFill"
+    }
+
+    pub fn to_execution_plan(&self, exec_input: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        Arc::new(GapFillExec {
+            time_bucket: self.time_bucket.clone(),
+            interval: self.interval,
+            start: self.start,
+            end: self.end,
+            group_columns: self.group_columns.clone(),
+            fill_columns: self.fill_columns.clone(),
+            input: exec_input,
+            metric: ExecutionPlanMetricsSet::new(),
+        })
+    }
+}
+
+fn format_fill_columns(fill_columns: &[(String, Fill)]) -> String {
+    fill_columns
+        .iter()
+        .map(|(name, fill)| format!("{name}: {fill}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug)]
+pub struct GapFillExec {
+    time_bucket: String,
+    interval: i64,
+    start: i64,
+    end: i64,
+    group_columns: Vec<String>,
+    fill_columns: Vec<(String, Fill)>,
+    input: Arc<dyn ExecutionPlan>,
+    metric: ExecutionPlanMetricsSet,
+}
+
+impl ExecutionPlan for GapFillExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::SinglePartition]
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![false; self.children().len()]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        assert!(!children.is_empty());
+        Ok(Arc::new(Self {
+            time_bucket: self.time_bucket.clone(),
+            interval: self.interval,
+            start: self.start,
+            end: self.end,
+            group_columns: self.group_columns.clone(),
+            fill_columns: self.fill_columns.clone(),
+            input: children[0].clone(),
+            metric: self.metric.clone(),
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DfResult<SendableRecordBatchStream> {
+        let baseline_metric = BaselineMetrics::new(&self.metric, partition);
+
+        let input = self.input.execute(partition, context)?;
+        let schema = input.schema();
+        let column_index = |name: &str| {
+            schema
+                .column_with_name(name)
+                .map(|(index, _)| index)
+                .ok_or_else(|| {
+                    DataFusionError::Internal(format!("Column {name} not found in gap fill"))
+                })
+        };
+        let filler = GapFiller {
+            time_bucket: column_index(&self.time_bucket)?,
+            interval: self.interval,
+            start: self.start,
+            end: self.end,
+            group_columns: self
+                .group_columns
+                .iter()
+                .map(|name| column_index(name))
+                .collect::<DfResult<_>>()?,
+            fill_columns: self
+                .fill_columns
+                .iter()
+                .map(|(name, fill)| Ok((column_index(name)?, *fill)))
+                .collect::<DfResult<_>>()?,
+            schema: schema.clone(),
+        };
+
+        // The whole input is required to find out the missing buckets of each
+        // series, it's the output of an aggregation and is usually small. The
+        // series are then filled and output one by one.
+        let output = stream::once(async move {
+            let batches = input.try_collect::<Vec<_>>().await?;
+            let (series, null_buckets) = {
+                let _timer = baseline_metric.elapsed_compute().timer();
+                filler.group_series(&batches)?
+            };
+            let outputs = series
+                .into_iter()
+                .map(Some)
+                .chain((!null_buckets.is_empty()).then_some(None))
+                .map(move |rows| {
+                    let _timer = baseline_metric.elapsed_compute().timer();
+                    let batch = match rows {
+                        Some(rows) => filler.fill_series(&batches, rows)?,
+                        None => filler.take_rows(&batches, &null_buckets)?,
+                    };
+                    baseline_metric.record_output(batch.num_rows());
+                    Ok(batch)
+                });
+            Ok::<_, DataFusionError>(stream::iter(outputs))
+        })
+        .try_flatten();
+        Ok(Box::pin(RecordBatchStreamAdapter::new(schema, output)))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(
+                    f,
+                    "GapFillExec: time bucket=[{}], interval=[{}], range=[{}..{}], \
+                    group by=[{}], fill=[{}]",
+                    self.time_bucket,
+                    self.interval,
+                    self.start,
+                    self.end,
+                    self.group_columns.join(", "),
+                    format_fill_columns(&self.fill_columns)
+                )
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metric.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// Rows of a series keyed by their time buckets, each row is located by the
+/// index of the batch and the index in the batch.
+type SeriesRows = BTreeMap<i64, (usize, usize)>;
+
+struct GapFiller {
+    time_bucket: usize,
+    interval: i64,
+    start: i64,
+    end: i64,
+    group_columns: Vec<usize>,
+    fill_columns: Vec<(usize, Fill)>,
+    schema: SchemaRef,
+}
+
+impl GapFiller {
+    /// Groups the rows in `batches` by series, the series are ordered by their
+    /// first appearance. Rows with null bucket are returned separately.
+    fn group_series(
+        &self,
+        batches: &[RecordBatch],
+    ) -> DfResult<(Vec<SeriesRows>, Vec<(usize, usize)>)> {
+        let mut series_index = HashMap::new();
+        let mut series: Vec<SeriesRows> = vec![];
+        let mut null_buckets = vec![];
+        for (batch_index, batch) in batches.iter().enumerate() {
+            let buckets = compute::cast(
+                batch.column(self.time_bucket),
+                &DataType::Timestamp(TimeUnit::Millisecond, None),
+            )?;
+            let buckets = buckets
+                .as_any()
+                .downcast_ref::<TimestampMillisecondArray>()
+                .unwrap();
+            for row in 0..batch.num_rows() {
+                if buckets.is_null(row) {
+                    null_buckets.push((batch_index, row));
+                    continue;
+                }
+                let key = self
+                    .group_columns
+                    .iter()
+                    .map(|i| ScalarValue::try_from_array(batch.column(*i), row))
+                    .collect::<DfResult<Vec<_>>>()?;
+                let index = *series_index.entry(key).or_insert_with(|| {
+                    series.push(BTreeMap::new());
+                    series.len() - 1
+                });
+                let _ = series[index].insert(buckets.value(row), (batch_index, row));
+            }
+        }
+        Ok((series, null_buckets))
+    }
+
+    /// Fills the gaps of a series, the output rows are ordered by the time
+    /// bucket.
+    fn fill_series(&self, batches: &[RecordBatch], rows: SeriesRows) -> DfResult<RecordBatch> {
+        // Indices of the rows in `series` to take, `None` for the generated rows.
+        let mut indices: BTreeMap<_, _> = rows
+            .keys()
+            .enumerate()
+            .map(|(index, ts)| (*ts, Some(index as u32)))
+            .collect();
+        self.generate_buckets(&mut indices);
+        let series = self.take_rows(batches, &rows.into_values().collect::<Vec<_>>())?;
+
+        let (timestamps, indices): (Vec<_>, Vec<_>) = indices.into_iter().unzip();
+        let timestamps = TimestampMillisecondArray::from(timestamps);
+        let indices = UInt32Array::from(indices);
+        // All rows of the series share the group columns of the first row.
+        let group_indices = UInt32Array::from(vec![0; timestamps.len()]);
+        let columns = series
+            .columns()
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                if i == self.time_bucket {
+                    Ok(compute::cast(&timestamps, column.data_type())?)
+                } else if self.group_columns.contains(&i) {
+                    Ok(compute::take(column, &group_indices, None)?)
+                } else {
+                    let column = compute::take(column, &indices, None)?;
+                    match self.fill_columns.iter().find(|(index, _)| *index == i) {
+                        Some((_, fill)) => Self::fill_column(&column, fill, &timestamps),
+                        None => Ok(column),
+                    }
+                }
+            })
+            .collect::<DfResult<Vec<_>>>()?;
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+
+    /// Takes `rows` from `batches` into a new batch.
+    fn take_rows(&self, batches: &[RecordBatch], rows: &[(usize, usize)]) -> DfResult<RecordBatch> {
+        let columns = (0..self.schema.fields().len())
+            .map(|i| {
+                let values = batches
+                    .iter()
+                    .map(|batch| batch.column(i).as_ref())
+                    .collect::<Vec<_>>();
+                Ok(compute::interleave(&values, rows)?)
+            })
+            .collect::<DfResult<Vec<_>>>()?;
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+
+    /// Adds the missing buckets in `[start, end)` into `rows`.
+    fn generate_buckets(&self, rows: &mut BTreeMap<i64, Option<u32>>) {
+        let mut bucket = time_bucket(self.start, self.interval);
+        while bucket < self.end {
+            let _ = rows.entry(bucket).or_insert(None);
+            bucket += self.interval;
+        }
+    }
+
+    fn fill_column(
+        column: &ArrayRef,
+        fill: &Fill,
+        timestamps: &TimestampMillisecondArray,
+    ) -> DfResult<ArrayRef> {
+        let values = compute::cast(column, &DataType::Float64)?;
+        let values = values.as_any().downcast_ref::<Float64Array>().unwrap();
+        let filled: ArrayRef = Arc::new(fill.fill(values, timestamps));
+        Ok(compute::cast(&filled, column.data_type())?)
+    }
+}
+
+pub struct GapFillExtensionPlanner;
+
+#[async_trait]
+impl ExtensionPlanner for GapFillExtensionPlanner {
+    async fn plan_extension(
+        &self,
+        _planner: &dyn PhysicalPlanner,
+        node: &dyn UserDefinedLogicalNode,
+        _logical_inputs: &[&LogicalPlan],
+        physical_inputs: &[Arc<dyn ExecutionPlan>],
+        _session_state: &SessionState,
+    ) -> DfResult<Option<Arc<dyn ExecutionPlan>>> {
+        Ok(node
+            .as_any()
+            .downcast_ref::<GapFill>()
+            .map(|node| node.to_execution_plan(physical_inputs[0].clone())))
+    }
+}
@@ -20,6 +20,7 @@ pub mod dist_plan;
 pub mod error;
 pub mod executor;
 pub mod extension_serializer;
+mod gap_fill;
 pub mod logical_optimizer;
 mod metrics;
 mod optimizer;
@@ -30,6 +31,8 @@ pub mod physical_wrapper;
 pub mod plan;
 pub mod planner;
 pub mod query_engine;
+mod range_select;
+mod spill;
 pub mod sql;
 
 pub use crate::datafusion::DfContextProviderAdapter;
@@ -12,5 +12,6 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod gap_fill;
 pub mod order_hint;
 pub mod type_conversion;
@@ -0,0 +1,340 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use common_function::scalars::gapfill::{
+    parse_bucket_interval, time_bucket, INTERPOLATE, LOCF, TIME_BUCKET_GAPFILL,
+};
+use common_time::timestamp::{TimeUnit, Timestamp};
+use datafusion::config::ConfigOptions;
+use datafusion_common::tree_node::{Transformed, TreeNode, VisitRecursion};
+use datafusion_common::{DataFusionError, Result, ScalarValue};
+use datafusion_expr::expr::ScalarUDF;
+use datafusion_expr::{Aggregate, Expr, ExprSchemable, Extension, Filter, LogicalPlan};
+use datafusion_optimizer::analyzer::AnalyzerRule;
+use datatypes::arrow::datatypes::{DataType, TimeUnit as ArrowTimeUnit};
+use promql::extension_plan::Fill;
+
+use crate::gap_fill::GapFill;
+
+/// Max number of buckets a `time_bucket_gapfill` may generate for each series.
+const MAX_BUCKETS: i64 = 100_000;
+
+/// GapFillRule adds a [GapFill] plan above the aggregation grouped by
+/// `time_bucket_gapfill(interval, ts, start, end)`, which generates the empty
+/// buckets in `[start, end)` for each series. Rows out of these buckets are
+/// filtered before the aggregation.
+///
+/// Aggregated values wrapped by `locf()` or `interpolate()` are filled with the
+/// previous value or the linear interpolation in the generated buckets, and the
+/// wrappers are removed from the plan. Other values are left null.
+pub struct GapFillRule;
+
+impl AnalyzerRule for GapFillRule {
+    fn analyze(&self, plan: LogicalPlan, _config: &ConfigOptions) -> Result<LogicalPlan> {
+        let mut fills = HashMap::new();
+        let mut value_columns = HashSet::new();
+        let mut has_gap_fill = false;
+        let _ = plan.apply(&mut |node| {
+            if let LogicalPlan::Aggregate(aggregate) = node
+                && find_time_bucket_gapfill(aggregate)?.is_some()
+            {
+                has_gap_fill = true;
+                let fields = aggregate.schema.fields();
+                value_columns.extend(
+                    fields[aggregate.group_expr.len()..]
+                        .iter()
+                        .map(|field| field.name().clone()),
+                );
+            }
+            for expr in node.expressions() {
+                collect_fills(&expr, &mut fills)?;
+            }
+            Ok(VisitRecursion::Continue)
+        })?;
+
+        if let Some(column) = fills.keys().find(|c| !value_columns.contains(*c)) {
+            return Err(plan_error(format!(
+                "locf() and interpolate() can only be applied to the aggregated values of \
+                a query grouped by time_bucket_gapfill(), found: {column}"
+            )));
+        }
+        if !has_gap_fill {
+            return Ok(plan);
+        }
+
+        plan.transform_up(&|plan| Self::rewrite_plan(plan, &fills))
+    }
+
+    fn name(&self) -> &str {
+        "GapFillRule"
+    }
+}
+
+impl GapFillRule {
+    fn rewrite_plan(
+        plan: LogicalPlan,
+        fills: &HashMap<String, Fill>,
+    ) -> Result<Transformed<LogicalPlan>> {
+        if let LogicalPlan::Aggregate(aggregate) = &plan
+            && let Some(time_bucket) = find_time_bucket_gapfill(aggregate)?
+        {
+            let gap_fill = Self::build_gap_fill(aggregate, time_bucket, fills)?;
+            return Ok(Transformed::Yes(LogicalPlan::Extension(Extension {
+                node: Arc::new(gap_fill),
+            })));
+        }
+        if fills.is_empty() {
+            return Ok(Transformed::No(plan));
+        }
+
+        // Removes the fill wrappers, whose values are filled by the GapFill
+        // plan below. The names of projected columns are kept unchanged.
+        let is_projection = matches!(plan, LogicalPlan::Projection(_));
+        let mut transformed = false;
+        let exprs = plan
+            .expressions()
+            .into_iter()
+            .map(|expr| {
+                let rewritten = expr.clone().transform_up(&|expr| match expr {
+                    Expr::ScalarUDF(ScalarUDF { fun, mut args })
+                        if fun.name == LOCF || fun.name == INTERPOLATE =>
+                    {
+                        Ok(Transformed::Yes(args.remove(0)))
+                    }
+                    expr => Ok(Transformed::No(expr)),
+                })?;
+                if rewritten == expr {
+                    return Ok(expr);
+                }
+                transformed = true;
+                if is_projection && !matches!(expr, Expr::Alias(..)) {
+                    Ok(rewritten.alias(expr.display_name()?))
+                } else {
+                    Ok(rewritten)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if !transformed {
+            return Ok(Transformed::No(plan));
+        }
+        let inputs = plan.inputs().into_iter().cloned().collect::<Vec<_>>();
+        datafusion_expr::utils::from_plan(&plan, &exprs, &inputs).map(Transformed::Yes)
+    }
+
+    fn build_gap_fill(
+        aggregate: &Aggregate,
+        (position, args): (usize, &[Expr]),
+        fills: &HashMap<String, Fill>,
+    ) -> Result<GapFill> {
+        let interval = match &args[0] {
+            Expr::Literal(ScalarValue::Utf8(Some(interval))) => {
+                parse_bucket_interval(interval).map_err(|e| plan_error(e.to_string()))?
+            }
+            _ => {
+                return Err(plan_error(
+                    "The interval of time_bucket_gapfill() must be a string literal like '1m'",
+                ))
+            }
+        };
+        let start = literal_timestamp(&args[2]).ok_or_else(|| {
+            plan_error("The start of time_bucket_gapfill() must be a timestamp literal")
+        })?;
+        let end = literal_timestamp(&args[3]).ok_or_else(|| {
+            plan_error("The end of time_bucket_gapfill() must be a timestamp literal")
+        })?;
+        if start >= end {
+            return Err(plan_error(
+                "The start of time_bucket_gapfill() must be before the end",
+            ));
+        }
+        if (end - time_bucket(start, interval)) / interval > MAX_BUCKETS {
+            return Err(plan_error(format!(
+                "time_bucket_gapfill() generates more than {MAX_BUCKETS} buckets"
+            )));
+        }
+
+        let fields = aggregate.schema.fields();
+        let group_len = aggregate.group_expr.len();
+        let group_columns = fields[..group_len]
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != position)
+            .map(|(_, field)| field.name().clone())
+            .collect();
+        let mut fill_columns = vec![];
+        for field in &fields[group_len..] {
+            if let Some(fill) = fills.get(field.name()) {
+                if !DataType::is_numeric(field.data_type()) {
+                    return Err(plan_error(format!(
+                        "locf() and interpolate() only support numeric values, found: {}",
+                        field.name()
+                    )));
+                }
+                fill_columns.push((field.name().clone(), *fill));
+            }
+        }
+
+        // Rows out of the buckets in `[start, end)` are filtered before the
+        // aggregation.
+        let start_bucket = time_bucket(start, interval);
+        let end_bucket = time_bucket(end - 1, interval)
+            .checked_add(interval)
+            .unwrap_or(i64::MAX);
+        let ts_type = args[1].get_type(aggregate.input.schema())?;
+        let input = match time_range_filter(&args[1], &ts_type, start_bucket, end_bucket) {
+            Some(predicate) => LogicalPlan::Aggregate(Aggregate::try_new(
+                Arc::new(LogicalPlan::Filter(Filter::try_new(
+                    predicate,
+                    aggregate.input.clone(),
+                )?)),
+                aggregate.group_expr.clone(),
+                aggregate.aggr_expr.clone(),
+            )?),
+            None => LogicalPlan::Aggregate(aggregate.clone()),
+        };
+
+        Ok(GapFill::new(
+            fields[position].name().clone(),
+            interval,
+            start,
+            end,
+            group_columns,
+            fill_columns,
+            input,
+        ))
+    }
+}
+
+/// Finds the position and the arguments of `time_bucket_gapfill` in the group
+/// by expressions.
+fn find_time_bucket_gapfill(aggregate: &Aggregate) -> Result<Option<(usize, &[Expr])>> {
+    let mut found = aggregate
+        .group_expr
+        .iter()
+        .enumerate()
+        .filter_map(|(i, expr)| match expr {
+            Expr::ScalarUDF(ScalarUDF { fun, args }) if fun.name == TIME_BUCKET_GAPFILL => {
+                Some((i, args.as_slice()))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if found.len() > 1 {
+        return Err(plan_error(
+            "Only one time_bucket_gapfill() is allowed in GROUP BY",
+        ));
+    }
+    Ok(found.pop())
+}
+
+/// Collects the aggregated columns wrapped by `locf()` or `interpolate()`.
+fn collect_fills(expr: &Expr, fills: &mut HashMap<String, Fill>) -> Result<()> {
+    let _ = expr.apply(&mut |expr| {
+        if let Expr::ScalarUDF(ScalarUDF { fun, args }) = expr
+            && (fun.name == LOCF || fun.name == INTERPOLATE)
+        {
+            let fill = if fun.name == LOCF {
+                Fill::Prev
+            } else {
+                Fill::Linear
+            };
+            let Some(Expr::Column(column)) = args.first() else {
+                return Err(plan_error(format!(
+                    "The argument of {}() must be an aggregated value",
+                    fun.name
+                )));
+            };
+            if let Some(existing) = fills.insert(column.name.clone(), fill)
+                && existing != fill
+            {
+                return Err(plan_error(format!(
+                    "Column {} is filled by both locf() and interpolate()",
+                    column.name
+                )));
+            }
+        }
+        Ok(VisitRecursion::Continue)
+    })?;
+    Ok(())
+}
+
+/// Builds the predicate `start <= ts < end` of the time column `ts`, `start`
+/// and `end` are in milliseconds. Returns `None` if the type of `ts` isn't
+/// supported or the range overflows.
+fn time_range_filter(ts: &Expr, ts_type: &DataType, start: i64, end: i64) -> Option<Expr> {
+    let (start, end) = match ts_type {
+        DataType::Int64 => (
+            ScalarValue::Int64(Some(start)),
+            ScalarValue::Int64(Some(end)),
+        ),
+        DataType::Timestamp(ArrowTimeUnit::Second, tz) => (
+            ScalarValue::TimestampSecond(Some(div_ceil(start, 1000)), tz.clone()),
+            ScalarValue::TimestampSecond(Some(div_ceil(end, 1000)), tz.clone()),
+        ),
+        DataType::Timestamp(ArrowTimeUnit::Millisecond, tz) => (
+            ScalarValue::TimestampMillisecond(Some(start), tz.clone()),
+            ScalarValue::TimestampMillisecond(Some(end), tz.clone()),
+        ),
+        DataType::Timestamp(ArrowTimeUnit::Microsecond, tz) => (
+            ScalarValue::TimestampMicrosecond(Some(start.checked_mul(1000)?), tz.clone()),
+            ScalarValue::TimestampMicrosecond(Some(end.checked_mul(1000)?), tz.clone()),
+        ),
+        DataType::Timestamp(ArrowTimeUnit::Nanosecond, tz) => (
+            ScalarValue::TimestampNanosecond(Some(start.checked_mul(1_000_000)?), tz.clone()),
+            ScalarValue::TimestampNanosecond(Some(end.checked_mul(1_000_000)?), tz.clone()),
+        ),
+        _ => return None,
+    };
+    Some(
+        ts.clone()
+            .gt_eq(Expr::Literal(start))
+            .and(ts.clone().lt(Expr::Literal(end))),
+    )
+}
+
+fn div_ceil(value: i64, divisor: i64) -> i64 {
+    let quotient = value.div_euclid(divisor);
+    if value.rem_euclid(divisor) == 0 {
+        quotient
+    } else {
+        quotient + 1
+    }
+}
+
+/// Returns the milliseconds of a timestamp literal, strings are parsed as
+/// timestamps and integers are treated as milliseconds.
+fn literal_timestamp(expr: &Expr) -> Option<i64> {
+    let Expr::Literal(value) = expr else {
+        return None;
+    };
+    match value {
+        ScalarValue::Utf8(Some(s)) => Timestamp::from_str(s)
+            .ok()?
+            .convert_to(TimeUnit::Millisecond)
+            .map(|ts| ts.value()),
+        ScalarValue::Int64(Some(v)) | ScalarValue::TimestampMillisecond(Some(v), _) => Some(*v),
+        ScalarValue::TimestampSecond(Some(v), _) => v.checked_mul(1000),
+        ScalarValue::TimestampMicrosecond(Some(v), _) => Some(v.div_euclid(1000)),
+        ScalarValue::TimestampNanosecond(Some(v), _) => Some(v.div_euclid(1_000_000)),
+        _ => None,
+    }
+}
+
+fn plan_error(message: impl Into<String>) -> DataFusionError {
+    DataFusionError::Plan(message.into())
+}
@@ -15,9 +15,11 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use catalog::table_source::DfTableSourceProvider;
+use catalog::table_source::{DfTableSourceProvider, ViewPlanner};
 use common_error::prelude::BoxedError;
+use common_telemetry::tracing::{info_span, Instrument};
 use datafusion::execution::context::SessionState;
+use datafusion_expr::LogicalPlan as DfLogicalPlan;
 use datafusion_sql::planner::{ParserOptions, SqlToRel};
 use promql::planner::PromPlanner;
 use promql_parser::parser::EvalStmt;
@@ -26,10 +28,10 @@ use snafu::ResultExt;
 use sql::statements::statement::Statement;
 
 use crate::error::{PlanSqlSnafu, QueryPlanSnafu, Result, SqlSnafu};
-use crate::parser::QueryStatement;
+use crate::parser::{QueryLanguageParser, QueryStatement};
 use crate::plan::LogicalPlan;
 use crate::query_engine::QueryEngineState;
-use crate::DfContextProviderAdapter;
+use crate::{range_select, DfContextProviderAdapter};
 
 #[async_trait]
 pub trait LogicalPlanner: Send + Sync {
@@ -51,13 +53,27 @@ impl DfLogicalPlanner {
     }
 
     async fn plan_sql(&self, stmt: Statement, query_ctx: QueryContextRef) -> Result<LogicalPlan> {
+        let table_provider = DfTableSourceProvider::new(
+            self.engine_state.catalog_manager().clone(),
+            self.engine_state.disallow_cross_schema_query(),
+            query_ctx.as_ref(),
+        )
+        .with_view_planner(Arc::new(DfLogicalPlanner::new(self.engine_state.clone())));
+        self.plan_sql_with(stmt, table_provider).await
+    }
+
+    async fn plan_sql_with(
+        &self,
+        stmt: Statement,
+        table_provider: DfTableSourceProvider,
+    ) -> Result<LogicalPlan> {
         let df_stmt = (&stmt).try_into().context(SqlSnafu)?;
 
         let context_provider = DfContextProviderAdapter::try_new(
             self.engine_state.clone(),
             self.session_state.clone(),
             &df_stmt,
-            query_ctx,
+            table_provider,
         )
         .await?;
 
@@ -69,7 +85,13 @@ impl DfLogicalPlanner {
 
         let sql_to_rel = SqlToRel::new_with_options(&context_provider, parser_options);
 
-        let result = sql_to_rel.statement_to_plan(df_stmt).with_context(|_| {
+        let result = match &stmt {
+            Statement::Query(query) if range_select::is_range_query(&query.inner) => {
+                range_select::plan_range_query(&sql_to_rel, &query.inner)
+            }
+            _ => sql_to_rel.statement_to_plan(df_stmt),
+        };
+        let result = result.with_context(|_| {
             let sql = if let Statement::Query(query) = stmt {
                 query.inner.to_string()
             } else {
@@ -95,12 +117,37 @@ impl DfLogicalPlanner {
     }
 }
 
+#[async_trait]
+impl ViewPlanner for DfLogicalPlanner {
+    async fn plan_view(
+        &self,
+        definition: &str,
+        table_provider: DfTableSourceProvider,
+    ) -> std::result::Result<DfLogicalPlan, BoxedError> {
+        let stmt = QueryLanguageParser::parse_sql(definition).map_err(BoxedError::new)?;
+        let plan = match stmt {
+            QueryStatement::Sql(stmt) => self.plan_sql_with(stmt, table_provider).await,
+            QueryStatement::Promql(_) => unreachable!("views are defined by sql queries"),
+        };
+        let LogicalPlan::DfPlan(plan) = plan.map_err(BoxedError::new)?;
+        Ok(plan)
+    }
+}
+
 #[async_trait]
 impl LogicalPlanner for DfLogicalPlanner {
     async fn plan(&self, stmt: QueryStatement, query_ctx: QueryContextRef) -> Result<LogicalPlan> {
         match stmt {
-            QueryStatement::Sql(stmt) => self.plan_sql(stmt, query_ctx).await,
-            QueryStatement::Promql(stmt) => self.plan_pql(stmt, query_ctx).await,
+            QueryStatement::Sql(stmt) => {
+                self.plan_sql(stmt, query_ctx)
+                    .instrument(info_span!("plan_sql"))
+                    .await
+            }
+            QueryStatement::Promql(stmt) => {
+                self.plan_pql(stmt, query_ctx)
+                    .instrument(info_span!("plan_promql"))
+                    .await
+            }
         }
     }
 }
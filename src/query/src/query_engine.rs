@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod context;
+mod memory_pool;
 pub mod options;
 mod state;
 
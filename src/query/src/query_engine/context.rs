@@ -12,20 +12,60 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use datafusion::execution::context::SessionState;
+use std::sync::Arc;
+
+use datafusion::execution::context::{SessionState, TaskContext};
+use datafusion::execution::runtime_env::RuntimeEnv;
+
+use crate::query_engine::memory_pool::QueryMemoryPool;
 
 #[derive(Debug)]
 pub struct QueryEngineContext {
     state: SessionState,
+    /// Memory limit of the query in bytes, unlimited if not set.
+    memory_limit: Option<usize>,
 }
 
 impl QueryEngineContext {
     pub fn new(state: SessionState) -> Self {
-        Self { state }
+        Self {
+            state,
+            memory_limit: None,
+        }
+    }
+
+    pub fn with_memory_limit(mut self, memory_limit: Option<usize>) -> Self {
+        self.memory_limit = memory_limit;
+        self
     }
 
     #[inline]
     pub fn state(&self) -> &SessionState {
         &self.state
     }
+
+    /// Builds the task context to execute the query, whose memory pool is
+    /// limited by the query's memory limit.
+    pub fn task_ctx(&self) -> Arc<TaskContext> {
+        let task_ctx = self.state.task_ctx();
+        let Some(memory_limit) = self.memory_limit else { return task_ctx };
+
+        let runtime = task_ctx.runtime_env();
+        let runtime = Arc::new(RuntimeEnv {
+            memory_pool: Arc::new(QueryMemoryPool::new(
+                memory_limit,
+                runtime.memory_pool.clone(),
+            )),
+            disk_manager: runtime.disk_manager.clone(),
+            object_store_registry: runtime.object_store_registry.clone(),
+        });
+        Arc::new(TaskContext::new(
+            task_ctx.task_id(),
+            task_ctx.session_id(),
+            task_ctx.session_config().clone(),
+            self.state.scalar_functions().clone(),
+            self.state.aggregate_functions().clone(),
+            runtime,
+        ))
+    }
 }
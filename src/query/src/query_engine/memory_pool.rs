@@ -0,0 +1,140 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use datafusion::error::{DataFusionError, Result as DfResult};
+use datafusion::execution::disk_manager::DiskManagerConfig;
+use datafusion::execution::memory_pool::{
+    FairSpillPool, MemoryConsumer, MemoryPool, MemoryReservation, UnboundedMemoryPool,
+};
+use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+
+use crate::query_engine::options::QueryMemoryOptions;
+
+/// Builds the runtime shared by all queries, whose memory pool is limited by
+/// the global limit. Sort, and hash aggregates and joins wrapped by
+/// [SpillRule](crate::spill::SpillRule), spill to the spill directories.
+pub(crate) fn build_runtime_env(opts: &QueryMemoryOptions) -> RuntimeEnv {
+    let memory_pool: Arc<dyn MemoryPool> = match opts.global_limit {
+        Some(limit) => Arc::new(FairSpillPool::new(limit.0 as usize)),
+        None => Arc::new(UnboundedMemoryPool::default()),
+    };
+    let disk_manager = match &opts.spill_dir {
+        Some(dir) => DiskManagerConfig::NewSpecified(vec![PathBuf::from(dir)]),
+        None => DiskManagerConfig::NewOs,
+    };
+    let config = RuntimeConfig::new()
+        .with_memory_pool(memory_pool)
+        .with_disk_manager(disk_manager);
+    // Same as `RuntimeEnv::default()`, creating the runtime with a given memory
+    // pool and spill directories never fails.
+    RuntimeEnv::new(config).expect("Failed to create query runtime")
+}
+
+/// Memory pool of a single query, which fails allocations beyond the query's
+/// limit and delegates others to the pool shared by all queries.
+#[derive(Debug)]
+pub(crate) struct QueryMemoryPool {
+    limit: usize,
+    used: AtomicUsize,
+    pool: Arc<dyn MemoryPool>,
+}
+
+impl QueryMemoryPool {
+    pub(crate) fn new(limit: usize, pool: Arc<dyn MemoryPool>) -> Self {
+        Self {
+            limit,
+            used: AtomicUsize::new(0),
+            pool,
+        }
+    }
+}
+
+impl MemoryPool for QueryMemoryPool {
+    fn register(&self, consumer: &MemoryConsumer) {
+        self.pool.register(consumer)
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        self.pool.unregister(consumer)
+    }
+
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        let _ = self.used.fetch_add(additional, Ordering::Relaxed);
+        self.pool.grow(reservation, additional)
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        let _ = self.used.fetch_sub(shrink, Ordering::Relaxed);
+        self.pool.shrink(reservation, shrink)
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> DfResult<()> {
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                let new_used = used + additional;
+                (new_used <= self.limit).then_some(new_used)
+            })
+            .map_err(|used| {
+                DataFusionError::ResourcesExhausted(format!(
+                    "Failed to allocate additional {} bytes with {} bytes already allocated, exceeds query memory limit {} bytes",
+                    additional, used, self.limit
+                ))
+            })?;
+
+        self.pool.try_grow(reservation, additional).map_err(|e| {
+            let _ = self.used.fetch_sub(additional, Ordering::Relaxed);
+            e
+        })
+    }
+
+    fn reserved(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_memory_pool() {
+        let global: Arc<dyn MemoryPool> = Arc::new(FairSpillPool::new(100));
+        let first: Arc<dyn MemoryPool> = Arc::new(QueryMemoryPool::new(60, global.clone()));
+        let second: Arc<dyn MemoryPool> = Arc::new(QueryMemoryPool::new(60, global.clone()));
+
+        let mut r1 = MemoryConsumer::new("r1").register(&first);
+        r1.try_grow(50).unwrap();
+        // Exceeds the query limit.
+        let err = r1.try_grow(20).unwrap_err();
+        assert!(matches!(err, DataFusionError::ResourcesExhausted(_)));
+        assert_eq!(50, first.reserved());
+
+        let mut r2 = MemoryConsumer::new("r2").register(&second);
+        r2.try_grow(40).unwrap();
+        // Within the query limit but exceeds the global limit.
+        let err = r2.try_grow(20).unwrap_err();
+        assert!(matches!(err, DataFusionError::ResourcesExhausted(_)));
+        assert_eq!(40, second.reserved());
+        assert_eq!(90, global.reserved());
+
+        r1.free();
+        assert_eq!(0, first.reserved());
+        r2.try_grow(20).unwrap();
+        assert_eq!(60, global.reserved());
+    }
+}
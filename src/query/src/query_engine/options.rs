@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common_base::readable_size::ReadableSize;
 use common_catalog::consts::INFORMATION_SCHEMA_NAME;
+use serde::{Deserialize, Serialize};
 use session::context::QueryContextRef;
 use snafu::ensure;
 
@@ -23,6 +25,20 @@ pub struct QueryOptions {
     pub disallow_cross_schema_query: bool,
 }
 
+/// Memory limits of query execution. Sort, hash aggregate and hash join spill
+/// their state to disk when they reach the limits, and fail the query with
+/// `ResourcesExhausted` if it still doesn't fit after spilling.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct QueryMemoryOptions {
+    /// Memory shared by all queries, unlimited if not set.
+    pub global_limit: Option<ReadableSize>,
+    /// Memory of a single query, unlimited if not set.
+    pub query_limit: Option<ReadableSize>,
+    /// Directory to spill to, the OS temp directory if not set.
+    pub spill_dir: Option<String>,
+}
+
 // TODO(shuiyisong): remove one method after #559 is done
 pub fn validate_catalog_and_schema(
     catalog: &str,
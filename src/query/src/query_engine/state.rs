@@ -27,7 +27,6 @@ use datafusion::catalog::catalog::MemoryCatalogList;
 use datafusion::dataframe::DataFrame;
 use datafusion::error::Result as DfResult;
 use datafusion::execution::context::{QueryPlanner, SessionConfig, SessionState};
-use datafusion::execution::runtime_env::RuntimeEnv;
 use datafusion::physical_optimizer::dist_enforcement::EnforceDistribution;
 use datafusion::physical_optimizer::repartition::Repartition;
 use datafusion::physical_optimizer::sort_enforcement::EnforceSorting;
@@ -44,9 +43,13 @@ use table::TableRef;
 
 use crate::dist_plan::{DistExtensionPlanner, DistPlannerAnalyzer};
 use crate::extension_serializer::ExtensionSerializer;
+use crate::gap_fill::GapFillExtensionPlanner;
+use crate::optimizer::gap_fill::GapFillRule;
 use crate::optimizer::order_hint::OrderHintRule;
 use crate::optimizer::type_conversion::TypeConversionRule;
-use crate::query_engine::options::QueryOptions;
+use crate::query_engine::memory_pool::build_runtime_env;
+use crate::query_engine::options::{QueryMemoryOptions, QueryOptions};
+use crate::spill::SpillRule;
 
 /// Query engine global state
 // TODO(yingwen): This QueryEngineState still relies on datafusion, maybe we can define a trait for it,
@@ -58,6 +61,8 @@ pub struct QueryEngineState {
     catalog_manager: CatalogManagerRef,
     aggregate_functions: Arc<RwLock<HashMap<String, AggregateFunctionMetaRef>>>,
     plugins: Arc<Plugins>,
+    /// Memory limit of a single query in bytes.
+    query_memory_limit: Option<usize>,
 }
 
 impl fmt::Debug for QueryEngineState {
@@ -76,13 +81,16 @@ impl QueryEngineState {
         datanode_clients: Option<Arc<DatanodeClients>>,
         plugins: Arc<Plugins>,
     ) -> Self {
-        let runtime_env = Arc::new(RuntimeEnv::default());
+        let memory_options = plugins.get::<QueryMemoryOptions>().unwrap_or_default();
+        let runtime_env = Arc::new(build_runtime_env(&memory_options));
         let session_config = SessionConfig::new().with_create_default_catalog_and_schema(false);
         // Apply the type conversion rule first.
         let mut analyzer = Analyzer::new();
         if with_dist_planner {
             analyzer.rules.insert(0, Arc::new(DistPlannerAnalyzer));
         }
+        // Gap fill should be planned before the aggregation is pushed down.
+        analyzer.rules.insert(0, Arc::new(GapFillRule));
         analyzer.rules.insert(0, Arc::new(TypeConversionRule));
         let mut optimizer = Optimizer::new();
         optimizer.rules.push(Arc::new(OrderHintRule));
@@ -102,6 +110,11 @@ impl QueryEngineState {
         physical_optimizers.insert(0, Arc::new(EnforceSorting {}));
         physical_optimizers.insert(0, Arc::new(EnforceDistribution {}));
         physical_optimizers.insert(0, Arc::new(Repartition {}));
+        // Spill hash aggregates and joins when they reach the memory limits, after
+        // other rules have chosen the plans to run.
+        if memory_options.global_limit.is_some() || memory_options.query_limit.is_some() {
+            physical_optimizers.push(Arc::new(SpillRule));
+        }
 
         let session_state = SessionState::with_config_rt_and_catalog_list(
             session_config,
@@ -124,6 +137,7 @@ impl QueryEngineState {
             catalog_manager: catalog_list,
             aggregate_functions: Arc::new(RwLock::new(HashMap::new())),
             plugins,
+            query_memory_limit: memory_options.query_limit.map(|limit| limit.0 as usize),
         }
     }
 
@@ -189,6 +203,10 @@ impl QueryEngineState {
             .unwrap_or(false)
     }
 
+    pub(crate) fn query_memory_limit(&self) -> Option<usize> {
+        self.query_memory_limit
+    }
+
     pub(crate) fn session_state(&self) -> SessionState {
         self.df_context.state()
     }
@@ -222,8 +240,10 @@ impl DfQueryPlanner {
         partition_manager: Option<Arc<PartitionRuleManager>>,
         datanode_clients: Option<Arc<DatanodeClients>>,
     ) -> Self {
-        let mut planners: Vec<Arc<dyn ExtensionPlanner + Send + Sync>> =
-            vec![Arc::new(PromExtensionPlanner)];
+        let mut planners: Vec<Arc<dyn ExtensionPlanner + Send + Sync>> = vec![
+            Arc::new(PromExtensionPlanner),
+            Arc::new(GapFillExtensionPlanner),
+        ];
         if let Some(partition_manager) = partition_manager
          && let Some(datanode_clients) = datanode_clients {
             planners.push(Arc::new(DistExtensionPlanner::new(partition_manager, datanode_clients)));
@@ -0,0 +1,552 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+use datafusion::datasource::DefaultTableSource;
+use datafusion::sql::sqlparser::ast::{
+    visit_expressions_mut, Expr, FunctionArg, FunctionArgExpr, Ident, Query as SpQuery, SelectItem,
+    SetExpr, Statement as SpStatement, Value, WildcardAdditionalOptions,
+};
+use datafusion_common::{Column, DataFusionError, Result as DfResult};
+use datafusion_expr::{
+    cast, Expr as DfExpr, Extension, LogicalPlan as DfLogicalPlan, LogicalPlanBuilder, ScalarUDF,
+};
+use datafusion_sql::parser::Statement as DfStatement;
+use datafusion_sql::planner::{ContextProvider, PlannerContext, SqlToRel};
+use datatypes::arrow::datatypes::{DataType, TimeUnit};
+use promql::extension_plan::{Fill, RangeManipulate, SeriesDivide, SeriesFill};
+use promql::functions::{
+    AvgOverTime, CountOverTime, LastOverTime, MaxOverTime, MinOverTime, StddevOverTime,
+    StdvarOverTime, SumOverTime,
+};
+use sql::parsers::range_parser::{RANGE_ALIGN, RANGE_FN};
+use table::table::adapter::DfTableProviderAdapter;
+use table::TableRef;
+
+const VALUE_COLUMN_PREFIX: &str = "__range_value_";
+const SERIES_KEY_COLUMN_PREFIX: &str = "__range_series_key_";
+
+/// Whether the query is a range query, i.e. it's aligned by `ALIGN`.
+pub(crate) fn is_range_query(query: &SpQuery) -> bool {
+    matches!(
+        query.body.as_ref(),
+        SetExpr::Select(select) if select.group_by.iter().any(|expr| is_function(expr, RANGE_ALIGN))
+    )
+}
+
+/// Plans the range query rewritten by the sql parser, e.g.
+///
+/// ```sql
+/// SELECT ts, host, avg(cpu) RANGE '5m' FILL PREV FROM monitor ALIGN '1m' BY (host)
+/// ```
+///
+/// The rows of the source table are divided into series by the `BY` columns (the
+/// primary keys by default), then folded into the ranges ending at the aligned
+/// timestamps by [RangeManipulate], like the range vectors of PromQL. The
+/// aggregates are evaluated on the ranges by the PromQL range functions.
+///
+/// All the aggregates in a query must have the same `RANGE`.
+pub(crate) fn plan_range_query<S: ContextProvider>(
+    sql_to_rel: &SqlToRel<S>,
+    query: &SpQuery,
+) -> DfResult<DfLogicalPlan> {
+    let SetExpr::Select(select) = query.body.as_ref() else {
+        return Err(plan_error("Range query must be a SELECT"));
+    };
+    if select.having.is_some() {
+        return Err(plan_error("HAVING is not supported in range query"));
+    }
+    let align = RangeAlign::try_new(&select.group_by)?;
+
+    let mut range_exprs = vec![];
+    let mut items = Vec::with_capacity(select.projection.len());
+    for item in &select.projection {
+        let (mut expr, alias) = match item {
+            SelectItem::UnnamedExpr(expr) => (expr.clone(), None),
+            SelectItem::ExprWithAlias { expr, alias } => (expr.clone(), Some(alias.clone())),
+            _ => return Err(plan_error("Wildcard is not supported in range query")),
+        };
+        replace_range_fns(&mut expr, &mut range_exprs)?;
+        items.push((expr, alias));
+    }
+    let mut order_by = query.order_by.clone();
+    for order_by_expr in &mut order_by {
+        replace_range_fns(&mut order_by_expr.expr, &mut range_exprs)?;
+    }
+
+    let Some(first) = range_exprs.first() else {
+        return Err(plan_error("Range query requires an aggregate with RANGE"));
+    };
+    let range = first.range;
+    if let Some(other) = range_exprs.iter().find(|e| e.range != range) {
+        return Err(plan_error(format!(
+            "All aggregates in a range query must have the same RANGE, found '{}' and '{}'",
+            first.name, other.name
+        )));
+    }
+
+    let table = find_table(&plan_source(
+        sql_to_rel,
+        query,
+        vec![SelectItem::Wildcard(WildcardAdditionalOptions::default())],
+    )?)?;
+    let time_index = table
+        .schema()
+        .timestamp_column()
+        .ok_or_else(|| plan_error("Range query requires a table with time index"))?
+        .name
+        .clone();
+    let by = match align.by {
+        Some(by) => by,
+        None => table
+            .table_info()
+            .meta
+            .row_key_column_names()
+            .map(|name| Expr::Identifier(Ident::with_quote('"', name)))
+            .collect(),
+    };
+    let by_aliases = by
+        .iter()
+        .map(|expr| match expr {
+            Expr::Identifier(ident) => ident.clone(),
+            _ => Ident::with_quote('"', expr.to_string()),
+        })
+        .collect::<Vec<_>>();
+    for (expr, _) in &mut items {
+        let _ = visit_expressions_mut(expr, |expr| {
+            if let Some(index) = by.iter().position(|by_expr| by_expr == expr) {
+                *expr = Expr::Identifier(by_aliases[index].clone());
+            }
+            ControlFlow::<()>::Continue(())
+        });
+    }
+
+    // Selects the time index, the `BY` columns and the arguments of the aggregates.
+    let mut projection = vec![SelectItem::UnnamedExpr(Expr::Identifier(
+        Ident::with_quote('"', &time_index),
+    ))];
+    projection.extend(
+        by.iter()
+            .zip(&by_aliases)
+            .map(|(expr, alias)| SelectItem::ExprWithAlias {
+                expr: expr.clone(),
+                alias: alias.clone(),
+            }),
+    );
+    projection.extend(range_exprs.iter().enumerate().map(|(i, range_expr)| {
+        SelectItem::ExprWithAlias {
+            // `count(*)` counts the rows.
+            expr: range_expr
+                .arg
+                .clone()
+                .unwrap_or_else(|| Expr::Value(Value::Number("1".to_string(), false))),
+            alias: Ident::new(format!("{VALUE_COLUMN_PREFIX}{i}")),
+        }
+    }));
+    let source = plan_source(sql_to_rel, query, projection)?;
+
+    let fields = source.schema().fields().clone();
+    let ts_name = fields[0].name().clone();
+    let by_fields = &fields[1..=by.len()];
+    let value_fields = &fields[by.len() + 1..];
+
+    let mut exprs = vec![cast(
+        DfExpr::Column(fields[0].qualified_column()),
+        DataType::Timestamp(TimeUnit::Millisecond, None),
+    )
+    .alias(&ts_name)];
+    let mut tags = Vec::with_capacity(by_fields.len());
+    for (i, field) in by_fields.iter().enumerate() {
+        exprs.push(DfExpr::Column(field.qualified_column()));
+        // Series are divided by string columns.
+        if field.data_type() == &DataType::Utf8 {
+            tags.push(field.name().clone());
+        } else {
+            let key = format!("{SERIES_KEY_COLUMN_PREFIX}{i}");
+            exprs.push(cast(DfExpr::Column(field.qualified_column()), DataType::Utf8).alias(&key));
+            tags.push(key);
+        }
+    }
+    for field in value_fields {
+        exprs.push(
+            cast(DfExpr::Column(field.qualified_column()), DataType::Float64).alias(field.name()),
+        );
+    }
+    let mut sort_exprs = by_fields
+        .iter()
+        .map(|field| DfExpr::Column(Column::from_name(field.name())).sort(true, true))
+        .collect::<Vec<_>>();
+    sort_exprs.push(DfExpr::Column(Column::from_name(&ts_name)).sort(true, true));
+    let plan = LogicalPlanBuilder::from(source)
+        .project(exprs)?
+        .sort(sort_exprs)?
+        .build()?;
+
+    let plan = extension(SeriesDivide::new(tags, plan));
+    let plan = extension(RangeManipulate::new_aligned(
+        align.align,
+        range,
+        ts_name.clone(),
+        value_fields.iter().map(|f| f.name().clone()).collect(),
+        plan,
+    )?);
+
+    let ts_range = DfExpr::Column(Column::from_name(
+        RangeManipulate::build_timestamp_range_name(&ts_name),
+    ));
+    let mut exprs = vec![DfExpr::Column(Column::from_name(&ts_name))];
+    exprs.extend(
+        by_fields
+            .iter()
+            .map(|field| DfExpr::Column(Column::from_name(field.name()))),
+    );
+    exprs.extend(
+        range_exprs
+            .iter()
+            .zip(value_fields)
+            .map(|(range_expr, field)| {
+                range_expr
+                    .udf
+                    .call(vec![
+                        ts_range.clone(),
+                        DfExpr::Column(Column::from_name(field.name())),
+                    ])
+                    .alias(&range_expr.name)
+            }),
+    );
+    let mut builder = LogicalPlanBuilder::from(plan).project(exprs)?;
+
+    let fill_columns = range_exprs
+        .iter()
+        .filter_map(|e| {
+            e.fill
+                .or(align.fill)
+                .filter(|fill| *fill != Fill::Null)
+                .map(|fill| (e.name.clone(), fill))
+        })
+        .collect::<Vec<_>>();
+    if !fill_columns.is_empty() {
+        builder = LogicalPlanBuilder::from(extension(SeriesFill::new(
+            ts_name,
+            fill_columns,
+            builder.build()?,
+        )));
+    }
+    // The timestamps whose ranges are empty are skipped, unless they are filled.
+    if align.fill.is_none() && range_exprs.iter().all(|e| e.fill.is_none()) {
+        let not_empty = range_exprs
+            .iter()
+            .map(|e| DfExpr::Column(Column::from_name(&e.name)).is_not_null())
+            .reduce(|lhs, rhs| lhs.or(rhs))
+            .unwrap();
+        builder = builder.filter(not_empty)?;
+    }
+
+    let mut planner_context = PlannerContext::new();
+    let schema = builder.schema().clone();
+    let exprs = items
+        .into_iter()
+        .map(|(expr, alias)| {
+            let expr = sql_to_rel.sql_to_expr(expr, &schema, &mut planner_context)?;
+            Ok(match alias {
+                Some(alias) => expr.alias(normalize_ident(alias)),
+                None => expr,
+            })
+        })
+        .collect::<DfResult<Vec<_>>>()?;
+    builder = builder.project(exprs)?;
+
+    if !order_by.is_empty() {
+        let schema = builder.schema().clone();
+        let sort_exprs = order_by
+            .into_iter()
+            .map(|order_by_expr| {
+                let expr =
+                    sql_to_rel.sql_to_expr(order_by_expr.expr, &schema, &mut planner_context)?;
+                let asc = order_by_expr.asc.unwrap_or(true);
+                Ok(expr.sort(asc, order_by_expr.nulls_first.unwrap_or(!asc)))
+            })
+            .collect::<DfResult<Vec<_>>>()?;
+        builder = builder.sort(sort_exprs)?;
+    }
+
+    let skip = query
+        .offset
+        .as_ref()
+        .map(|offset| parse_limit(&offset.value))
+        .transpose()?
+        .unwrap_or(0);
+    let fetch = query.limit.as_ref().map(parse_limit).transpose()?;
+    if skip > 0 || fetch.is_some() {
+        builder = builder.limit(skip, fetch)?;
+    }
+
+    builder.build()
+}
+
+/// The options of `ALIGN`, which is rewritten into
+/// `GROUP BY range_align('<align>', '<fill>', <by>), <exprs>`.
+struct RangeAlign {
+    align: i64,
+    fill: Option<Fill>,
+    by: Option<Vec<Expr>>,
+}
+
+impl RangeAlign {
+    fn try_new(group_by: &[Expr]) -> DfResult<Self> {
+        let Some((Expr::Function(function), by)) = group_by.split_first() else {
+            return Err(plan_error("GROUP BY is not supported in range query"));
+        };
+        let invalid = || plan_error(format!("Invalid ALIGN: {function}"));
+        if !is_function(&group_by[0], RANGE_ALIGN) || function.args.len() != 3 {
+            return Err(invalid());
+        }
+
+        let align = string_arg(&function.args[0]).ok_or_else(invalid)?;
+        let fill = string_arg(&function.args[1]).ok_or_else(invalid)?;
+        let by_given = match &function.args[2] {
+            FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Value(Value::Boolean(b)))) => *b,
+            _ => return Err(invalid()),
+        };
+        if !by_given && !by.is_empty() {
+            return Err(plan_error("GROUP BY is not supported in range query"));
+        }
+
+        Ok(Self {
+            align: parse_duration(align)?,
+            fill: parse_fill(fill)?,
+            by: by_given.then(|| by.to_vec()),
+        })
+    }
+}
+
+/// An aggregate with `RANGE`, which is rewritten into
+/// `range_fn(<aggregate>, '<range>', '<fill>')`.
+struct RangeExpr {
+    /// Name of the output column, e.g. `avg(cpu) RANGE 5m FILL PREV`.
+    name: String,
+    udf: ScalarUDF,
+    /// The argument of the aggregate, `None` for `count(*)`.
+    arg: Option<Expr>,
+    range: i64,
+    fill: Option<Fill>,
+}
+
+impl RangeExpr {
+    fn try_new(expr: &Expr) -> DfResult<Self> {
+        let Expr::Function(function) = expr else {
+            unreachable!("range_fn is checked")
+        };
+        let invalid = || plan_error(format!("Invalid RANGE: {function}"));
+        let [aggregate, range, fill] = function.args.as_slice() else {
+            return Err(invalid());
+        };
+        let FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Function(aggregate))) = aggregate else {
+            return Err(plan_error(format!(
+                "RANGE must follow an aggregate function: {function}"
+            )));
+        };
+        let range = string_arg(range).ok_or_else(invalid)?;
+        let fill = string_arg(fill).ok_or_else(invalid)?;
+
+        if aggregate.distinct || aggregate.over.is_some() {
+            return Err(plan_error(format!(
+                "Unsupported aggregate in range query: {aggregate}"
+            )));
+        }
+        let arg = match aggregate.args.as_slice() {
+            [FunctionArg::Unnamed(FunctionArgExpr::Expr(arg))] => Some(arg.clone()),
+            [FunctionArg::Unnamed(FunctionArgExpr::Wildcard)] => None,
+            _ => {
+                return Err(plan_error(format!(
+                    "Aggregate in range query must have one argument: {aggregate}"
+                )))
+            }
+        };
+        let aggregate_name = aggregate.name.to_string().to_lowercase();
+        let udf = match aggregate_name.as_str() {
+            "avg" => AvgOverTime::scalar_udf(),
+            "min" => MinOverTime::scalar_udf(),
+            "max" => MaxOverTime::scalar_udf(),
+            "sum" => SumOverTime::scalar_udf(),
+            "count" => CountOverTime::scalar_udf(),
+            "last_value" => LastOverTime::scalar_udf(),
+            "stddev_pop" => StddevOverTime::scalar_udf(),
+            "var_pop" => StdvarOverTime::scalar_udf(),
+            _ => {
+                return Err(plan_error(format!(
+                    "Unsupported aggregate in range query: {aggregate_name}"
+                )))
+            }
+        };
+        if arg.is_none() && aggregate_name != "count" {
+            return Err(plan_error(format!(
+                "Aggregate in range query must have one argument: {aggregate}"
+            )));
+        }
+
+        let mut name = format!("{aggregate} RANGE {range}");
+        if !fill.is_empty() {
+            name.push_str(&format!(" FILL {fill}"));
+        }
+        Ok(Self {
+            name,
+            udf,
+            arg,
+            range: parse_duration(range)?,
+            fill: parse_fill(fill)?,
+        })
+    }
+}
+
+/// Replaces the `range_fn`s in the expression with the columns of their results.
+fn replace_range_fns(expr: &mut Expr, range_exprs: &mut Vec<RangeExpr>) -> DfResult<()> {
+    let result = visit_expressions_mut(expr, |expr| {
+        if !is_function(expr, RANGE_FN) {
+            return ControlFlow::Continue(());
+        }
+        match RangeExpr::try_new(expr) {
+            Ok(range_expr) => {
+                *expr = Expr::Identifier(Ident::with_quote('"', &range_expr.name));
+                if range_exprs.iter().all(|e| e.name != range_expr.name) {
+                    range_exprs.push(range_expr);
+                }
+                ControlFlow::Continue(())
+            }
+            Err(e) => ControlFlow::Break(e),
+        }
+    });
+    match result {
+        ControlFlow::Continue(()) => Ok(()),
+        ControlFlow::Break(e) => Err(e),
+    }
+}
+
+/// Plans the query with the projection, without grouping, ordering and limit.
+fn plan_source<S: ContextProvider>(
+    sql_to_rel: &SqlToRel<S>,
+    query: &SpQuery,
+    projection: Vec<SelectItem>,
+) -> DfResult<DfLogicalPlan> {
+    let mut source = query.clone();
+    source.order_by = vec![];
+    source.limit = None;
+    source.offset = None;
+    source.fetch = None;
+    if let SetExpr::Select(select) = source.body.as_mut() {
+        select.projection = projection;
+        select.group_by = vec![];
+    }
+    sql_to_rel.statement_to_plan(DfStatement::Statement(Box::new(SpStatement::Query(
+        Box::new(source),
+    ))))
+}
+
+fn find_table(plan: &DfLogicalPlan) -> DfResult<TableRef> {
+    fn collect_tables(plan: &DfLogicalPlan, tables: &mut Vec<TableRef>) {
+        if let DfLogicalPlan::TableScan(scan) = plan
+            && let Some(adapter) = scan
+                .source
+                .as_any()
+                .downcast_ref::<DefaultTableSource>()
+                .and_then(|source| {
+                    source
+                        .table_provider
+                        .as_any()
+                        .downcast_ref::<DfTableProviderAdapter>()
+                })
+        {
+            tables.push(adapter.table());
+        }
+        for input in plan.inputs() {
+            collect_tables(input, tables);
+        }
+    }
+
+    let mut tables = vec![];
+    collect_tables(plan, &mut tables);
+    match tables.len() {
+        1 => Ok(tables.remove(0)),
+        _ => Err(plan_error("Range query must select from exactly one table")),
+    }
+}
+
+fn extension<T: datafusion_expr::UserDefinedLogicalNode + 'static>(node: T) -> DfLogicalPlan {
+    DfLogicalPlan::Extension(Extension {
+        node: Arc::new(node),
+    })
+}
+
+fn is_function(expr: &Expr, name: &str) -> bool {
+    matches!(expr, Expr::Function(function) if function.name.to_string().eq_ignore_ascii_case(name))
+}
+
+fn string_arg(arg: &FunctionArg) -> Option<&str> {
+    match arg {
+        FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Value(Value::SingleQuotedString(s)))) => {
+            Some(s)
+        }
+        _ => None,
+    }
+}
+
+fn parse_duration(duration: &str) -> DfResult<i64> {
+    let millis = promql_parser::util::parse_duration(duration)
+        .map_err(|e| plan_error(format!("Invalid duration '{duration}': {e}")))?
+        .as_millis() as i64;
+    if millis <= 0 {
+        return Err(plan_error(format!(
+            "Duration must be positive, found '{duration}'"
+        )));
+    }
+    Ok(millis)
+}
+
+fn parse_fill(fill: &str) -> DfResult<Option<Fill>> {
+    let fill = match fill.to_uppercase().as_str() {
+        "" => return Ok(None),
+        "NULL" => Fill::Null,
+        "PREV" => Fill::Prev,
+        "LINEAR" => Fill::Linear,
+        _ => Fill::Const(
+            fill.parse()
+                .map_err(|_| plan_error(format!("Invalid FILL: {fill}")))?,
+        ),
+    };
+    Ok(Some(fill))
+}
+
+fn parse_limit(expr: &Expr) -> DfResult<usize> {
+    match expr {
+        Expr::Value(Value::Number(n, _)) => n
+            .parse()
+            .map_err(|_| plan_error(format!("Invalid LIMIT or OFFSET: {n}"))),
+        _ => Err(plan_error(format!(
+            "LIMIT and OFFSET of range query must be numbers, found: {expr}"
+        ))),
+    }
+}
+
+fn normalize_ident(ident: Ident) -> String {
+    match ident.quote_style {
+        Some(_) => ident.value,
+        None => ident.value.to_lowercase(),
+    }
+}
+
+fn plan_error(msg: impl Into<String>) -> DataFusionError {
+    DataFusionError::Plan(msg.into())
+}
@@ -0,0 +1,641 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Spilling of hash aggregates and hash joins, which fail the query with
+//! `ResourcesExhausted` in DataFusion when they reach the memory limits. See
+//! [SpillRule].
+
+mod aggregate;
+mod join;
+
+use std::any::Any;
+use std::fs::File;
+use std::io::BufReader;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use ahash::RandomState;
+use datafusion::arrow::array::UInt32Array;
+use datafusion::arrow::compute;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::ipc::reader::FileReader;
+use datafusion::arrow::ipc::writer::FileWriter;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::{Result as DfResult, Statistics};
+use datafusion::config::ConfigOptions;
+use datafusion::error::DataFusionError;
+use datafusion::execution::context::TaskContext;
+use datafusion::execution::memory_pool::MemoryReservation;
+use datafusion::physical_expr::hash_utils::create_hashes;
+use datafusion::physical_expr::{PhysicalExpr, PhysicalSortExpr};
+use datafusion::physical_optimizer::PhysicalOptimizerRule;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::metrics::{Count, ExecutionPlanMetricsSet, MetricBuilder};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream, SendableRecordBatchStream,
+};
+use futures::{stream, Stream, StreamExt};
+use tempfile::NamedTempFile;
+
+pub(crate) use self::aggregate::SpillableAggregateExec;
+pub(crate) use self::join::SpillableHashJoinExec;
+
+/// Number of files the rows are partitioned to when spilling.
+const SPILL_PARTITIONS: usize = 16;
+/// Times a spilled file can be partitioned again if it still doesn't fit in
+/// memory, before failing the query.
+const MAX_SPILL_DEPTH: usize = 3;
+/// Seed of the hashes partitioning the spilled rows, it differs from the seed of
+/// `RepartitionExec` so the rows of a partition don't all go to the same file.
+const SPILL_HASH_SEED: u64 = 0x5350_494c_4c;
+
+/// Physical optimizer rule wrapping hash aggregates and hash joins, so they
+/// spill their input to disk instead of failing the query when they reach the
+/// memory limits. It runs after the other rules, as the wrapped plans don't keep
+/// the order of their outputs.
+#[derive(Debug, Default)]
+pub struct SpillRule;
+
+impl PhysicalOptimizerRule for SpillRule {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        Self::wrap(plan)
+    }
+
+    fn name(&self) -> &str {
+        "SpillRule"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+impl SpillRule {
+    fn wrap(plan: Arc<dyn ExecutionPlan>) -> DfResult<Arc<dyn ExecutionPlan>> {
+        let children = plan
+            .children()
+            .into_iter()
+            .map(Self::wrap)
+            .collect::<DfResult<Vec<_>>>()?;
+        let plan = if children.is_empty() {
+            plan
+        } else {
+            plan.with_new_children(children)?
+        };
+
+        if let Some(wrapped) = SpillableAggregateExec::try_wrap(&plan) {
+            return Ok(wrapped);
+        }
+        if let Some(wrapped) = SpillableHashJoinExec::try_wrap(&plan) {
+            return Ok(wrapped);
+        }
+        Ok(plan)
+    }
+}
+
+/// Returns whether the error is caused by reaching the memory limits.
+pub(crate) fn is_resources_exhausted(error: &DataFusionError) -> bool {
+    match error {
+        DataFusionError::ResourcesExhausted(_) => true,
+        DataFusionError::Context(_, source) => is_resources_exhausted(source),
+        DataFusionError::ArrowError(ArrowError::ExternalError(source))
+        | DataFusionError::External(source) => {
+            if let Some(source) = source.downcast_ref::<DataFusionError>() {
+                is_resources_exhausted(source)
+            } else if let Some(source) = source.downcast_ref::<Arc<DataFusionError>>() {
+                is_resources_exhausted(source)
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Error of a spilled file that still doesn't fit in memory.
+fn spill_exhausted(operator: &str) -> DataFusionError {
+    DataFusionError::ResourcesExhausted(format!(
+        "{operator} doesn't fit in the memory limits after partitioning its spilled input \
+        {MAX_SPILL_DEPTH} times"
+    ))
+}
+
+/// Metrics of the spilled files of a partition.
+#[derive(Clone)]
+struct SpillMetrics {
+    spill_count: Count,
+    spilled_bytes: Count,
+}
+
+impl SpillMetrics {
+    fn new(metrics: &ExecutionPlanMetricsSet, partition: usize) -> Self {
+        Self {
+            spill_count: MetricBuilder::new(metrics).spill_count(partition),
+            spilled_bytes: MetricBuilder::new(metrics).spilled_bytes(partition),
+        }
+    }
+}
+
+/// A file of spilled rows.
+struct SpillFile {
+    file: NamedTempFile,
+    schema: SchemaRef,
+    rows: usize,
+}
+
+impl SpillFile {
+    fn reader(&self) -> DfResult<impl Iterator<Item = DfResult<RecordBatch>> + Send> {
+        let reader = FileReader::try_new(BufReader::new(self.file.reopen()?), None)?;
+        Ok(reader.map(|batch| batch.map_err(DataFusionError::from)))
+    }
+
+    fn stream(&self) -> DfResult<SendableRecordBatchStream> {
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            stream::iter(self.reader()?),
+        )))
+    }
+
+    /// Reads the rows of the file into memory, returns `None` if they don't fit
+    /// in the reservation.
+    fn read_to_memory(
+        &self,
+        reservation: &mut MemoryReservation,
+    ) -> DfResult<Option<Vec<RecordBatch>>> {
+        let mut batches = Vec::new();
+        for batch in self.reader()? {
+            let batch = batch?;
+            if reservation.try_grow(batch.get_array_memory_size()).is_err() {
+                let _ = reservation.free();
+                return Ok(None);
+            }
+            batches.push(batch);
+        }
+        Ok(Some(batches))
+    }
+}
+
+/// Writes rows to [SPILL_PARTITIONS] files by the hashes of their keys, so rows
+/// of the same keys are in the same file.
+struct SpillPartitioner {
+    keys: Vec<Arc<dyn PhysicalExpr>>,
+    random_state: RandomState,
+    schema: SchemaRef,
+    writers: Vec<(NamedTempFile, FileWriter<File>, usize)>,
+    metrics: SpillMetrics,
+}
+
+impl SpillPartitioner {
+    /// Creates a partitioner for rows that have been partitioned `depth` times.
+    fn try_new(
+        keys: Vec<Arc<dyn PhysicalExpr>>,
+        schema: SchemaRef,
+        depth: usize,
+        context: &TaskContext,
+        metrics: SpillMetrics,
+    ) -> DfResult<Self> {
+        let writers = (0..SPILL_PARTITIONS)
+            .map(|_| {
+                let file = context
+                    .runtime_env()
+                    .disk_manager
+                    .create_tmp_file("Spill")?;
+                let writer = FileWriter::try_new(file.reopen()?, &schema)?;
+                Ok((file, writer, 0))
+            })
+            .collect::<DfResult<_>>()?;
+        metrics.spill_count.add(1);
+
+        Ok(Self {
+            keys,
+            random_state: RandomState::with_seeds(SPILL_HASH_SEED, depth as u64, 0, 0),
+            schema,
+            writers,
+            metrics,
+        })
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> DfResult<()> {
+        let num_rows = batch.num_rows();
+        if num_rows == 0 {
+            return Ok(());
+        }
+        let keys = self
+            .keys
+            .iter()
+            .map(|key| key.evaluate(batch).map(|value| value.into_array(num_rows)))
+            .collect::<DfResult<Vec<_>>>()?;
+        let mut hashes = vec![0; num_rows];
+        let _ = create_hashes(&keys, &self.random_state, &mut hashes)?;
+
+        let mut indices: Vec<Vec<u32>> = vec![Vec::new(); SPILL_PARTITIONS];
+        for (row, hash) in hashes.into_iter().enumerate() {
+            indices[hash as usize % SPILL_PARTITIONS].push(row as u32);
+        }
+        for ((_, writer, rows), indices) in self.writers.iter_mut().zip(indices) {
+            if indices.is_empty() {
+                continue;
+            }
+            let indices = UInt32Array::from(indices);
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|column| compute::take(column, &indices, None))
+                .collect::<Result<Vec<_>, _>>()?;
+            *rows += indices.len();
+            writer.write(&RecordBatch::try_new(batch.schema(), columns)?)?;
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> DfResult<Vec<SpillFile>> {
+        self.writers
+            .into_iter()
+            .map(|(file, mut writer, rows)| {
+                writer.finish()?;
+                self.metrics
+                    .spilled_bytes
+                    .add(file.as_file().metadata()?.len() as usize);
+                Ok(SpillFile {
+                    file,
+                    schema: self.schema.clone(),
+                    rows,
+                })
+            })
+            .collect()
+    }
+
+    /// Partitions the rows of a spilled file again, with the hashes of the next
+    /// depth.
+    fn repartition(
+        file: &SpillFile,
+        keys: Vec<Arc<dyn PhysicalExpr>>,
+        depth: usize,
+        context: &TaskContext,
+        metrics: SpillMetrics,
+    ) -> DfResult<Vec<SpillFile>> {
+        let mut partitioner =
+            SpillPartitioner::try_new(keys, file.schema.clone(), depth, context, metrics)?;
+        for batch in file.reader()? {
+            partitioner.write(&batch?)?;
+        }
+        partitioner.finish()
+    }
+}
+
+/// Outcome of executing a plan that may run out of memory.
+enum Execution {
+    /// The plan produces its output.
+    Stream(SendableRecordBatchStream),
+    /// The plan ran out of memory before producing any output, so it can be
+    /// executed again with less input.
+    Exhausted(DataFusionError),
+}
+
+/// Executes the only partition of the plan, polling its first batch to find out
+/// whether the plan runs out of memory. Hash aggregates and hash joins reserve
+/// their memory before producing any output.
+async fn try_execute(
+    plan: Arc<dyn ExecutionPlan>,
+    context: Arc<TaskContext>,
+) -> DfResult<Execution> {
+    let schema = plan.schema();
+    let mut stream = match plan.execute(0, context) {
+        Ok(stream) => stream,
+        Err(e) if is_resources_exhausted(&e) => return Ok(Execution::Exhausted(e)),
+        Err(e) => return Err(e),
+    };
+    let first = match stream.next().await {
+        Some(Ok(first)) => first,
+        Some(Err(e)) if is_resources_exhausted(&e) => return Ok(Execution::Exhausted(e)),
+        Some(Err(e)) => return Err(e),
+        None => {
+            return Ok(Execution::Stream(Box::pin(RecordBatchStreamAdapter::new(
+                schema,
+                stream::empty(),
+            ))))
+        }
+    };
+    Ok(Execution::Stream(Box::pin(RecordBatchStreamAdapter::new(
+        schema,
+        stream::once(async { Ok(first) }).chain(stream),
+    ))))
+}
+
+fn memory_exec(batches: Vec<RecordBatch>, schema: SchemaRef) -> DfResult<Arc<dyn ExecutionPlan>> {
+    Ok(Arc::new(MemoryExec::try_new(&[batches], schema, None)?))
+}
+
+/// Splits the batches into two halves of about the same number of rows.
+fn split_batches(batches: Vec<RecordBatch>) -> (Vec<RecordBatch>, Vec<RecordBatch>) {
+    let rows = batches.iter().map(RecordBatch::num_rows).sum::<usize>();
+    let mut remaining = rows / 2;
+    let (mut first, mut second) = (Vec::new(), Vec::new());
+    for batch in batches {
+        if remaining == 0 {
+            second.push(batch);
+        } else if batch.num_rows() <= remaining {
+            remaining -= batch.num_rows();
+            first.push(batch);
+        } else {
+            first.push(batch.slice(0, remaining));
+            second.push(batch.slice(remaining, batch.num_rows() - remaining));
+            remaining = 0;
+        }
+    }
+    (first, second)
+}
+
+struct SharedStreamState {
+    stream: Option<SendableRecordBatchStream>,
+    polled: bool,
+}
+
+/// A stream passed to a plan that can be taken back if the plan doesn't poll
+/// it, e.g. the probe side of a hash join whose build side runs out of memory.
+#[derive(Clone)]
+struct SharedStream {
+    schema: SchemaRef,
+    state: Arc<Mutex<SharedStreamState>>,
+}
+
+impl SharedStream {
+    fn new(stream: SendableRecordBatchStream) -> Self {
+        Self {
+            schema: stream.schema(),
+            state: Arc::new(Mutex::new(SharedStreamState {
+                stream: Some(stream),
+                polled: false,
+            })),
+        }
+    }
+
+    /// Takes the stream back if it has never been polled.
+    fn take_unpolled(&self) -> Option<SendableRecordBatchStream> {
+        let mut state = self.state.lock().unwrap();
+        if state.polled {
+            None
+        } else {
+            state.stream.take()
+        }
+    }
+}
+
+impl Stream for SharedStream {
+    type Item = DfResult<RecordBatch>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.state.lock().unwrap();
+        state.polled = true;
+        match state.stream.as_mut() {
+            Some(stream) => stream.poll_next_unpin(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl RecordBatchStream for SharedStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Leaf plan producing a stream created beforehand, so a plan can be executed
+/// over a stream instead of a child plan.
+struct OnceExec {
+    schema: SchemaRef,
+    stream: Mutex<Option<SendableRecordBatchStream>>,
+}
+
+impl OnceExec {
+    fn new(stream: SendableRecordBatchStream) -> Self {
+        Self {
+            schema: stream.schema(),
+            stream: Mutex::new(Some(stream)),
+        }
+    }
+}
+
+impl std::fmt::Debug for OnceExec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OnceExec")
+            .field("schema", &self.schema)
+            .finish()
+    }
+}
+
+impl ExecutionPlan for OnceExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        _partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> DfResult<SendableRecordBatchStream> {
+        self.stream.lock().unwrap().take().ok_or_else(|| {
+            DataFusionError::Internal("OnceExec is executed more than once".to_string())
+        })
+    }
+
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "OnceExec")
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::{Array, Int64Array};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::datasource::MemTable;
+    use datafusion::execution::context::{SessionConfig, SessionContext, SessionState};
+    use datafusion::execution::memory_pool::FairSpillPool;
+    use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+
+    use super::*;
+
+    const ROWS: i64 = 100_000;
+
+    fn new_table(name: &str, keys: i64) -> (String, Arc<MemTable>) {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(format!("{name}_key"), DataType::Int64, false),
+            Field::new(format!("{name}_value"), DataType::Int64, false),
+        ]));
+        let batches = (0..ROWS)
+            .step_by(1000)
+            .map(|start| {
+                let rows = start..start + 1000;
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(Int64Array::from_iter_values(rows.clone().map(|i| i % keys))),
+                        Arc::new(Int64Array::from_iter_values(rows)),
+                    ],
+                )
+                .unwrap()
+            })
+            .collect();
+        let table = MemTable::try_new(schema, vec![batches]).unwrap();
+        (name.to_string(), Arc::new(table))
+    }
+
+    fn new_context(spill: bool) -> SessionContext {
+        let runtime = RuntimeEnv::new(
+            RuntimeConfig::new().with_memory_pool(Arc::new(FairSpillPool::new(1 << 20))),
+        )
+        .unwrap();
+        let config = SessionConfig::new()
+            .with_target_partitions(1)
+            .with_batch_size(1024);
+        let mut state = SessionState::with_config_rt(config, Arc::new(runtime));
+        if spill {
+            state = state.add_physical_optimizer_rule(Arc::new(SpillRule));
+        }
+        let context = SessionContext::with_state(state);
+        for (name, table) in [new_table("l", ROWS / 2), new_table("r", ROWS)] {
+            let _ = context.register_table(name.as_str(), table).unwrap();
+        }
+        context
+    }
+
+    fn sum(batches: &[RecordBatch], column: usize) -> i64 {
+        batches
+            .iter()
+            .map(|batch| {
+                let array = batch.column(column);
+                let array = array.as_any().downcast_ref::<Int64Array>().unwrap();
+                array.iter().map(|v| v.unwrap()).sum::<i64>()
+            })
+            .sum()
+    }
+
+    #[tokio::test]
+    async fn test_spill_aggregate() {
+        let sql = "SELECT l_key, count(*) FROM l GROUP BY l_key";
+        let err = new_context(false).sql(sql).await.unwrap().collect().await;
+        assert!(is_resources_exhausted(&err.unwrap_err()));
+
+        let batches = new_context(true)
+            .sql(sql)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let rows = batches.iter().map(RecordBatch::num_rows).sum::<usize>();
+        assert_eq!(ROWS / 2, rows as i64);
+        assert_eq!((0..ROWS / 2).sum::<i64>(), sum(&batches, 0));
+        assert_eq!(ROWS, sum(&batches, 1));
+    }
+
+    #[tokio::test]
+    async fn test_spill_join() {
+        let sql = "SELECT l_value, r_value FROM l JOIN r ON l_key = r_key";
+        let err = new_context(false).sql(sql).await.unwrap().collect().await;
+        assert!(is_resources_exhausted(&err.unwrap_err()));
+
+        let batches = new_context(true)
+            .sql(sql)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let rows = batches.iter().map(RecordBatch::num_rows).sum::<usize>();
+        // Each key of `r` matches two rows of `l` if it's less than `ROWS / 2`.
+        assert_eq!(ROWS, rows as i64);
+        assert_eq!(2 * (0..ROWS / 2).sum::<i64>(), sum(&batches, 1));
+    }
+
+    #[test]
+    fn test_is_resources_exhausted() {
+        let exhausted = DataFusionError::ResourcesExhausted("exhausted".to_string());
+        assert!(is_resources_exhausted(&DataFusionError::Context(
+            "context".to_string(),
+            Box::new(exhausted)
+        )));
+        let external = DataFusionError::External(Box::new(DataFusionError::ResourcesExhausted(
+            "exhausted".to_string(),
+        )));
+        assert!(is_resources_exhausted(&DataFusionError::ArrowError(
+            ArrowError::ExternalError(Box::new(external))
+        )));
+        assert!(!is_resources_exhausted(&DataFusionError::Internal(
+            "internal".to_string()
+        )));
+    }
+
+    #[test]
+    fn test_split_batches() {
+        let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Int64, false)]));
+        let batches = [3, 4]
+            .into_iter()
+            .map(|rows| {
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(Int64Array::from_iter_values(0..rows))],
+                )
+                .unwrap()
+            })
+            .collect();
+        let (first, second) = split_batches(batches);
+        let rows = |batches: &[RecordBatch]| {
+            batches
+                .iter()
+                .map(RecordBatch::num_rows)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![3], rows(&first));
+        assert_eq!(vec![4], rows(&second));
+
+        let (first, second) = split_batches(second);
+        assert_eq!(vec![2], rows(&first));
+        assert_eq!(vec![2], rows(&second));
+    }
+}
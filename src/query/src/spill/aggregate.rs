@@ -0,0 +1,323 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use async_stream::try_stream;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::common::{Result as DfResult, Statistics};
+use datafusion::execution::context::TaskContext;
+use datafusion::execution::memory_pool::MemoryConsumer;
+use datafusion::physical_expr::{PhysicalExpr, PhysicalSortExpr};
+use datafusion::physical_plan::aggregates::{AggregateExec, AggregateMode};
+use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+};
+use futures::StreamExt;
+
+use crate::spill::{
+    memory_exec, spill_exhausted, split_batches, try_execute, Execution, SpillMetrics,
+    SpillPartitioner, MAX_SPILL_DEPTH,
+};
+
+/// Batches of input a partial aggregate runs over at most at a time.
+const PARTIAL_CHUNK_BATCHES: usize = 16;
+
+/// Wraps a hash aggregate to spill its input when it reaches the memory limits.
+///
+/// A partial aggregate runs over chunks of its input, and a chunk is split into
+/// halves if it still doesn't fit, as the final aggregate merges the states of a
+/// group from different chunks. Other aggregates buffer their input, and write
+/// it to files partitioned by the group keys once it doesn't fit, then aggregate
+/// the files one by one.
+#[derive(Debug)]
+pub(crate) struct SpillableAggregateExec {
+    aggregate: Arc<dyn ExecutionPlan>,
+    keys: Vec<Arc<dyn PhysicalExpr>>,
+    partial: bool,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl SpillableAggregateExec {
+    /// Wraps the plan if it's a hash aggregate whose output is not ordered.
+    /// Aggregates with grouping sets are not wrapped, as a group doesn't only
+    /// depend on its keys.
+    pub(crate) fn try_wrap(plan: &Arc<dyn ExecutionPlan>) -> Option<Arc<dyn ExecutionPlan>> {
+        let aggregate = plan.as_any().downcast_ref::<AggregateExec>()?;
+        let group_by = aggregate.group_expr();
+        if group_by.expr().is_empty()
+            || group_by.groups().len() != 1
+            || plan.output_ordering().is_some()
+            || plan.required_input_ordering().iter().any(Option::is_some)
+        {
+            return None;
+        }
+
+        Some(Arc::new(Self {
+            aggregate: plan.clone(),
+            keys: group_by
+                .expr()
+                .iter()
+                .map(|(expr, _)| expr.clone())
+                .collect(),
+            partial: matches!(aggregate.mode(), AggregateMode::Partial),
+            metrics: ExecutionPlanMetricsSet::new(),
+        }))
+    }
+
+    fn execute_partial(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DfResult<SendableRecordBatchStream> {
+        let aggregate = self.aggregate.clone();
+        let input_schema = aggregate.children()[0].schema();
+        let mut input = aggregate.children()[0].execute(partition, context.clone())?;
+        let chunk_rows = context.session_config().batch_size() * PARTIAL_CHUNK_BATCHES;
+
+        let output = try_stream! {
+            let mut reservation =
+                MemoryConsumer::new(format!("SpillableAggregateExec[{partition}]"))
+                    .with_can_spill(true)
+                    .register(context.memory_pool());
+            let mut next = input.next().await.transpose()?;
+            while next.is_some() {
+                let (mut chunk, mut rows) = (Vec::new(), 0);
+                while let Some(batch) = next.take() {
+                    let size = batch.get_array_memory_size();
+                    // Always takes a batch so the chunk is not empty.
+                    if !chunk.is_empty()
+                        && (rows >= chunk_rows || reservation.try_grow(size).is_err())
+                    {
+                        next = Some(batch);
+                        break;
+                    }
+                    rows += batch.num_rows();
+                    chunk.push(batch);
+                    next = input.next().await.transpose()?;
+                }
+
+                let mut chunks = vec![chunk];
+                while let Some(chunk) = chunks.pop() {
+                    let memory_input = memory_exec(chunk.clone(), input_schema.clone())?;
+                    let plan = aggregate.clone().with_new_children(vec![memory_input])?;
+                    match try_execute(plan, context.clone()).await? {
+                        Execution::Stream(mut stream) => {
+                            while let Some(batch) = stream.next().await {
+                                yield batch?;
+                            }
+                        }
+                        Execution::Exhausted(e) => {
+                            if chunk.iter().map(|batch| batch.num_rows()).sum::<usize>() <= 1 {
+                                Err::<(), _>(e)?;
+                            }
+                            let (first, second) = split_batches(chunk);
+                            chunks.push(second);
+                            chunks.push(first);
+                        }
+                    }
+                }
+                let _ = reservation.free();
+            }
+        };
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            output,
+        )))
+    }
+
+    fn execute_partitioned(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DfResult<SendableRecordBatchStream> {
+        let aggregate = self.aggregate.clone();
+        let keys = self.keys.clone();
+        let input_schema = aggregate.children()[0].schema();
+        let mut input = aggregate.children()[0].execute(partition, context.clone())?;
+        let metrics = SpillMetrics::new(&self.metrics, partition);
+
+        let output = try_stream! {
+            let mut reservation =
+                MemoryConsumer::new(format!("SpillableAggregateExec[{partition}]"))
+                    .with_can_spill(true)
+                    .register(context.memory_pool());
+            let mut buffer = Vec::new();
+            let mut partitioner: Option<SpillPartitioner> = None;
+            while let Some(batch) = input.next().await {
+                let batch = batch?;
+                if let Some(partitioner) = &mut partitioner {
+                    partitioner.write(&batch)?;
+                    continue;
+                }
+                if reservation.try_grow(batch.get_array_memory_size()).is_ok() {
+                    buffer.push(batch);
+                    continue;
+                }
+                let mut spilled = SpillPartitioner::try_new(
+                    keys.clone(),
+                    input_schema.clone(),
+                    0,
+                    &context,
+                    metrics.clone(),
+                )?;
+                for batch in buffer.drain(..).chain(Some(batch)) {
+                    spilled.write(&batch)?;
+                }
+                let _ = reservation.free();
+                partitioner = Some(spilled);
+            }
+
+            if partitioner.is_none() {
+                let memory_input = memory_exec(buffer.clone(), input_schema.clone())?;
+                let plan = aggregate.clone().with_new_children(vec![memory_input])?;
+                match try_execute(plan, context.clone()).await? {
+                    Execution::Stream(mut stream) => {
+                        while let Some(batch) = stream.next().await {
+                            yield batch?;
+                        }
+                    }
+                    Execution::Exhausted(_) => {
+                        let mut spilled = SpillPartitioner::try_new(
+                            keys.clone(),
+                            input_schema.clone(),
+                            0,
+                            &context,
+                            metrics.clone(),
+                        )?;
+                        for batch in buffer.drain(..) {
+                            spilled.write(&batch)?;
+                        }
+                        partitioner = Some(spilled);
+                    }
+                }
+                buffer.clear();
+                let _ = reservation.free();
+            }
+
+            let mut pending = match partitioner {
+                Some(partitioner) => partitioner
+                    .finish()?
+                    .into_iter()
+                    .map(|file| (file, 0))
+                    .collect::<Vec<_>>(),
+                None => Vec::new(),
+            };
+            while let Some((file, depth)) = pending.pop() {
+                if file.rows == 0 {
+                    continue;
+                }
+                if let Some(batches) = file.read_to_memory(&mut reservation)? {
+                    let memory_input = memory_exec(batches, input_schema.clone())?;
+                    let plan = aggregate.clone().with_new_children(vec![memory_input])?;
+                    let execution = try_execute(plan, context.clone()).await?;
+                    if let Execution::Stream(mut stream) = execution {
+                        while let Some(batch) = stream.next().await {
+                            yield batch?;
+                        }
+                        let _ = reservation.free();
+                        continue;
+                    }
+                    let _ = reservation.free();
+                }
+
+                if depth >= MAX_SPILL_DEPTH {
+                    Err::<(), _>(spill_exhausted("SpillableAggregateExec"))?;
+                }
+                let files = SpillPartitioner::repartition(
+                    &file,
+                    keys.clone(),
+                    depth + 1,
+                    &context,
+                    metrics.clone(),
+                )?;
+                pending.extend(files.into_iter().map(|file| (file, depth + 1)));
+            }
+        };
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            output,
+        )))
+    }
+}
+
+impl ExecutionPlan for SpillableAggregateExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.aggregate.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.aggregate.output_partitioning()
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        self.aggregate.required_input_distribution()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![false; self.children().len()]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        self.aggregate.children()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(Self {
+            aggregate: self.aggregate.clone().with_new_children(children)?,
+            keys: self.keys.clone(),
+            partial: self.partial,
+            metrics: self.metrics.clone(),
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DfResult<SendableRecordBatchStream> {
+        if self.partial {
+            self.execute_partial(partition, context)
+        } else {
+            self.execute_partitioned(partition, context)
+        }
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Spillable")?;
+        self.aggregate.fmt_as(t, f)
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.aggregate.statistics()
+    }
+}
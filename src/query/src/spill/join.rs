@@ -0,0 +1,303 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use async_stream::try_stream;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::common::{Result as DfResult, Statistics};
+use datafusion::execution::context::TaskContext;
+use datafusion::execution::memory_pool::MemoryConsumer;
+use datafusion::logical_expr::JoinType;
+use datafusion::physical_expr::{PhysicalExpr, PhysicalSortExpr};
+use datafusion::physical_plan::joins::{HashJoinExec, PartitionMode};
+use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+};
+use futures::StreamExt;
+
+use crate::spill::{
+    memory_exec, spill_exhausted, try_execute, Execution, OnceExec, SharedStream, SpillMetrics,
+    SpillPartitioner, MAX_SPILL_DEPTH,
+};
+
+/// Wraps a hash join to spill both sides when its build side reaches the
+/// memory limits.
+///
+/// The build side is buffered, and the probe side is streamed through the join
+/// as long as the buffer fits. Otherwise both sides are written to files
+/// partitioned by the join keys, and each pair of files is joined alone.
+#[derive(Debug)]
+pub(crate) struct SpillableHashJoinExec {
+    join: Arc<dyn ExecutionPlan>,
+    left_keys: Vec<Arc<dyn PhysicalExpr>>,
+    right_keys: Vec<Arc<dyn PhysicalExpr>>,
+    join_type: JoinType,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl SpillableHashJoinExec {
+    /// Wraps the plan if it's a hash join whose output is not ordered, and each
+    /// of its partitions only joins the same partition of both sides.
+    pub(crate) fn try_wrap(plan: &Arc<dyn ExecutionPlan>) -> Option<Arc<dyn ExecutionPlan>> {
+        let join = plan.as_any().downcast_ref::<HashJoinExec>()?;
+        let single_partition = join
+            .children()
+            .iter()
+            .all(|child| child.output_partitioning().partition_count() == 1);
+        if !(matches!(join.partition_mode(), PartitionMode::Partitioned) || single_partition)
+            || plan.output_ordering().is_some()
+        {
+            return None;
+        }
+
+        let (left_keys, right_keys) = join
+            .on()
+            .iter()
+            .map(|(left, right)| {
+                (
+                    Arc::new(left.clone()) as Arc<dyn PhysicalExpr>,
+                    Arc::new(right.clone()) as Arc<dyn PhysicalExpr>,
+                )
+            })
+            .unzip();
+        Some(Arc::new(Self {
+            join: plan.clone(),
+            left_keys,
+            right_keys,
+            join_type: *join.join_type(),
+            metrics: ExecutionPlanMetricsSet::new(),
+        }))
+    }
+}
+
+/// Returns whether the join outputs nothing for sides of these numbers of rows.
+fn is_empty_output(join_type: JoinType, left_rows: usize, right_rows: usize) -> bool {
+    match join_type {
+        JoinType::Inner | JoinType::LeftSemi | JoinType::RightSemi => {
+            left_rows == 0 || right_rows == 0
+        }
+        JoinType::Left | JoinType::LeftAnti => left_rows == 0,
+        JoinType::Right | JoinType::RightAnti => right_rows == 0,
+        JoinType::Full => left_rows == 0 && right_rows == 0,
+    }
+}
+
+impl ExecutionPlan for SpillableHashJoinExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.join.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.join.output_partitioning()
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        self.join.required_input_distribution()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![false; self.children().len()]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        self.join.children()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(Self {
+            join: self.join.clone().with_new_children(children)?,
+            left_keys: self.left_keys.clone(),
+            right_keys: self.right_keys.clone(),
+            join_type: self.join_type,
+            metrics: self.metrics.clone(),
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DfResult<SendableRecordBatchStream> {
+        let join = self.join.clone();
+        let (left_keys, right_keys) = (self.left_keys.clone(), self.right_keys.clone());
+        let join_type = self.join_type;
+        let children = join.children();
+        let (left_schema, right_schema) = (children[0].schema(), children[1].schema());
+        let mut left = children[0].execute(partition, context.clone())?;
+        let right = children[1].execute(partition, context.clone())?;
+        let metrics = SpillMetrics::new(&self.metrics, partition);
+
+        let output = try_stream! {
+            let mut reservation =
+                MemoryConsumer::new(format!("SpillableHashJoinExec[{partition}]"))
+                    .with_can_spill(true)
+                    .register(context.memory_pool());
+            let mut buffer = Vec::new();
+            let mut left_partitioner: Option<SpillPartitioner> = None;
+            while let Some(batch) = left.next().await {
+                let batch = batch?;
+                if let Some(partitioner) = &mut left_partitioner {
+                    partitioner.write(&batch)?;
+                    continue;
+                }
+                if reservation.try_grow(batch.get_array_memory_size()).is_ok() {
+                    buffer.push(batch);
+                    continue;
+                }
+                let mut partitioner = SpillPartitioner::try_new(
+                    left_keys.clone(),
+                    left_schema.clone(),
+                    0,
+                    &context,
+                    metrics.clone(),
+                )?;
+                for batch in buffer.drain(..).chain(Some(batch)) {
+                    partitioner.write(&batch)?;
+                }
+                let _ = reservation.free();
+                left_partitioner = Some(partitioner);
+            }
+
+            let mut right = Some(right);
+            if left_partitioner.is_none() {
+                let shared = SharedStream::new(right.take().unwrap());
+                let memory_left = memory_exec(buffer.clone(), left_schema.clone())?;
+                let once_right: Arc<dyn ExecutionPlan> =
+                    Arc::new(OnceExec::new(Box::pin(shared.clone())));
+                let plan = join.clone().with_new_children(vec![memory_left, once_right])?;
+                match try_execute(plan, context.clone()).await? {
+                    Execution::Stream(mut stream) => {
+                        while let Some(batch) = stream.next().await {
+                            yield batch?;
+                        }
+                    }
+                    Execution::Exhausted(e) => {
+                        // The join has consumed some rows of the probe side if
+                        // it has polled it, they can't be joined again.
+                        right = shared.take_unpolled();
+                        if right.is_none() {
+                            Err::<(), _>(e)?;
+                        }
+                        let mut partitioner = SpillPartitioner::try_new(
+                            left_keys.clone(),
+                            left_schema.clone(),
+                            0,
+                            &context,
+                            metrics.clone(),
+                        )?;
+                        for batch in buffer.drain(..) {
+                            partitioner.write(&batch)?;
+                        }
+                        left_partitioner = Some(partitioner);
+                    }
+                }
+                buffer.clear();
+                let _ = reservation.free();
+            }
+
+            let mut pending = Vec::new();
+            if let (Some(left_partitioner), Some(mut right)) = (left_partitioner, right) {
+                let mut right_partitioner = SpillPartitioner::try_new(
+                    right_keys.clone(),
+                    right_schema.clone(),
+                    0,
+                    &context,
+                    metrics.clone(),
+                )?;
+                while let Some(batch) = right.next().await {
+                    right_partitioner.write(&batch?)?;
+                }
+                let lefts = left_partitioner.finish()?;
+                let rights = right_partitioner.finish()?;
+                pending.extend(lefts.into_iter().zip(rights).map(|(l, r)| (l, r, 0)));
+            }
+            while let Some((left_file, right_file, depth)) = pending.pop() {
+                if is_empty_output(join_type, left_file.rows, right_file.rows) {
+                    continue;
+                }
+                if let Some(batches) = left_file.read_to_memory(&mut reservation)? {
+                    let memory_left = memory_exec(batches, left_schema.clone())?;
+                    let once_right: Arc<dyn ExecutionPlan> =
+                        Arc::new(OnceExec::new(right_file.stream()?));
+                    let plan = join.clone().with_new_children(vec![memory_left, once_right])?;
+                    let execution = try_execute(plan, context.clone()).await?;
+                    if let Execution::Stream(mut stream) = execution {
+                        while let Some(batch) = stream.next().await {
+                            yield batch?;
+                        }
+                        let _ = reservation.free();
+                        continue;
+                    }
+                    let _ = reservation.free();
+                }
+
+                if depth >= MAX_SPILL_DEPTH {
+                    Err::<(), _>(spill_exhausted("SpillableHashJoinExec"))?;
+                }
+                let lefts = SpillPartitioner::repartition(
+                    &left_file,
+                    left_keys.clone(),
+                    depth + 1,
+                    &context,
+                    metrics.clone(),
+                )?;
+                let rights = SpillPartitioner::repartition(
+                    &right_file,
+                    right_keys.clone(),
+                    depth + 1,
+                    &context,
+                    metrics.clone(),
+                )?;
+                pending.extend(
+                    lefts
+                        .into_iter()
+                        .zip(rights)
+                        .map(|(l, r)| (l, r, depth + 1)),
+                );
+            }
+        };
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            output,
+        )))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Spillable")?;
+        self.join.fmt_as(t, f)
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.join.statistics()
+    }
+}
@@ -17,19 +17,20 @@ mod show;
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use catalog::process_manager::Process;
 use catalog::CatalogManagerRef;
 use common_catalog::consts::{
-    SEMANTIC_TYPE_FIELD, SEMANTIC_TYPE_PRIMARY_KEY, SEMANTIC_TYPE_TIME_INDEX,
+    INTERNAL_SCHEMA_NAME, SEMANTIC_TYPE_FIELD, SEMANTIC_TYPE_PRIMARY_KEY, SEMANTIC_TYPE_TIME_INDEX,
 };
 use common_datasource::file_format::{infer_schemas, FileFormat, Format};
 use common_datasource::lister::{Lister, Source};
 use common_datasource::object_store::build_backend;
-use common_datasource::util::find_dir_and_filename;
+use common_datasource::util::{find_dir_and_filename, parse_partition_values};
 use common_query::Output;
 use common_recordbatch::RecordBatches;
 use datatypes::prelude::*;
 use datatypes::schema::{ColumnSchema, RawSchema, Schema};
-use datatypes::vectors::{Helper, StringVector};
+use datatypes::vectors::{Helper, StringVector, UInt64Vector};
 use object_store::ObjectStore;
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -38,8 +39,10 @@ use snafu::{ensure, OptionExt, ResultExt};
 use sql::ast::ColumnDef;
 use sql::statements::column_def_to_schema;
 use sql::statements::create::Partitions;
-use sql::statements::show::{ShowDatabases, ShowKind, ShowTables};
+use sql::statements::show::{ShowDatabases, ShowKind, ShowProcesslist, ShowTables};
+use table::metadata::TableType;
 use table::requests::{IMMUTABLE_TABLE_LOCATION_KEY, IMMUTABLE_TABLE_PATTERN_KEY};
+use table::table::view::VIEW_DEFINITION_KEY;
 use table::TableRef;
 
 use crate::error::{self, Result};
@@ -109,6 +112,7 @@ pub async fn show_databases(
         .await
         .context(error::CatalogSnafu)?;
 
+    databases.retain(|db| db != INTERNAL_SCHEMA_NAME);
     // TODO(dennis): Specify the order of the results in catalog manager API
     databases.sort();
 
@@ -173,9 +177,20 @@ pub async fn show_tables(
 pub fn show_create_table(table: TableRef, partitions: Option<Partitions>) -> Result<Output> {
     let table_info = table.table_info();
     let table_name = &table_info.name;
-    let mut stmt = show::create_table_stmt(&table_info)?;
-    stmt.partitions = partitions;
-    let sql = format!("{}", stmt);
+    let sql = if table_info.table_type == TableType::View {
+        let definition = table_info
+            .meta
+            .options
+            .extra_options
+            .get(VIEW_DEFINITION_KEY)
+            .map(|s| s.as_str())
+            .unwrap_or_default();
+        format!("CREATE VIEW {table_name} AS {definition}")
+    } else {
+        let mut stmt = show::create_table_stmt(&table_info)?;
+        stmt.partitions = partitions;
+        format!("{}", stmt)
+    };
     let columns = vec![
         Arc::new(StringVector::from(vec![table_name.clone()])) as _,
         Arc::new(StringVector::from(vec![sql])) as _,
@@ -186,6 +201,83 @@ pub fn show_create_table(table: TableRef, partitions: Option<Partitions>) -> Res
     Ok(Output::RecordBatches(records))
 }
 
+/// Statements longer than this are truncated by `SHOW PROCESSLIST` without `FULL`.
+const PROCESSLIST_INFO_LENGTH: usize = 100;
+
+static SHOW_PROCESSLIST_OUTPUT_SCHEMA: Lazy<Arc<Schema>> = Lazy::new(|| {
+    Arc::new(Schema::new(vec![
+        ColumnSchema::new("Id", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("User", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("Host", ConcreteDataType::string_datatype(), true),
+        ColumnSchema::new("Channel", ConcreteDataType::string_datatype(), true),
+        ColumnSchema::new("db", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("Time", ConcreteDataType::uint64_datatype(), false),
+        ColumnSchema::new("State", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("Info", ConcreteDataType::string_datatype(), false),
+    ]))
+});
+
+pub fn show_processlist(stmt: ShowProcesslist, processes: &[Arc<Process>]) -> Result<Output> {
+    let infos = processes
+        .iter()
+        .map(|process| {
+            let query = process.query();
+            if stmt.full {
+                query.to_string()
+            } else {
+                query.chars().take(PROCESSLIST_INFO_LENGTH).collect()
+            }
+        })
+        .collect::<Vec<_>>();
+    let columns = vec![
+        Arc::new(UInt64Vector::from_values(
+            processes.iter().map(|process| process.id()),
+        )) as _,
+        Arc::new(StringVector::from(
+            processes
+                .iter()
+                .map(|process| process.user())
+                .collect::<Vec<_>>(),
+        )) as _,
+        Arc::new(StringVector::from(
+            processes
+                .iter()
+                .map(|process| {
+                    process
+                        .conn_info()
+                        .and_then(|info| info.client_addr)
+                        .map(|addr| addr.to_string())
+                })
+                .collect::<Vec<_>>(),
+        )) as _,
+        Arc::new(StringVector::from(
+            processes
+                .iter()
+                .map(|process| process.conn_info().map(|info| info.channel.to_string()))
+                .collect::<Vec<_>>(),
+        )) as _,
+        Arc::new(StringVector::from(
+            processes
+                .iter()
+                .map(|process| process.schema())
+                .collect::<Vec<_>>(),
+        )) as _,
+        Arc::new(UInt64Vector::from_values(
+            processes.iter().map(|process| process.elapsed().as_secs()),
+        )) as _,
+        Arc::new(StringVector::from(
+            processes
+                .iter()
+                .map(|process| process.stage().to_string())
+                .collect::<Vec<_>>(),
+        )) as _,
+        Arc::new(StringVector::from(infos)) as _,
+    ];
+    let records = RecordBatches::try_from_columns(SHOW_PROCESSLIST_OUTPUT_SCHEMA.clone(), columns)
+        .context(error::CreateRecordBatchSnafu)?;
+    Ok(Output::RecordBatches(records))
+}
+
 pub fn describe_table(table: TableRef) -> Result<Output> {
     let table_info = table.table_info();
     let columns_schemas = table_info.meta.schema.column_schemas();
@@ -258,12 +350,17 @@ fn describe_column_semantic_types(
     ))
 }
 
+/// Lists the files of an external table and builds its schema, either from
+/// `columns` or inferred from the files. Partition columns, if any, are appended
+/// to the schema as string columns, and files outside of partition directories
+/// are ignored.
 pub async fn prepare_immutable_file_table_files_and_schema(
     options: &HashMap<String, String>,
     columns: &Vec<ColumnDef>,
+    partition_columns: &[String],
 ) -> Result<(Vec<String>, RawSchema)> {
-    let (object_store, files) = prepare_immutable_file_table(options).await?;
-    let schema = if !columns.is_empty() {
+    let (object_store, files) = prepare_immutable_file_table(options, partition_columns).await?;
+    let mut schema = if !columns.is_empty() {
         let columns_schemas: Vec<_> = columns
             .iter()
             .map(|column| column_def_to_schema(column, false).context(error::ParseSqlSnafu))
@@ -274,12 +371,36 @@ pub async fn prepare_immutable_file_table_files_and_schema(
         infer_immutable_file_table_schema(&object_store, &*format, &files).await?
     };
 
+    for (i, column) in partition_columns.iter().enumerate() {
+        ensure!(
+            !partition_columns[..i].contains(column),
+            error::InvalidPartitionColumnSnafu {
+                column,
+                reason: "duplicated",
+            }
+        );
+        ensure!(
+            schema.column_schemas.iter().all(|c| &c.name != column),
+            error::InvalidPartitionColumnSnafu {
+                column,
+                reason: "conflicts with a column in files",
+            }
+        );
+        // Every listed file has values for all partition columns.
+        schema.column_schemas.push(ColumnSchema::new(
+            column,
+            ConcreteDataType::string_datatype(),
+            false,
+        ));
+    }
+
     Ok((files, schema))
 }
 
 // lists files in the frontend to reduce unnecessary scan requests repeated in each datanode.
 async fn prepare_immutable_file_table(
     options: &HashMap<String, String>,
+    partition_columns: &[String],
 ) -> Result<(ObjectStore, Vec<String>)> {
     let url =
         options
@@ -300,18 +421,24 @@ async fn prepare_immutable_file_table(
         .transpose()
         .context(error::BuildRegexSnafu)?;
     let object_store = build_backend(url, options).context(error::BuildBackendSnafu)?;
-    let lister = Lister::new(object_store.clone(), source, dir, regex);
+    // Files of partitioned tables are in partition directories.
+    let lister = Lister::new(object_store.clone(), source, dir, regex)
+        .with_recursive(!partition_columns.is_empty());
     // If we scan files in a directory every time the database restarts,
     // then it might lead to a potential undefined behavior:
     // If a user adds a file with an incompatible schema to that directory,
     // it will make the external table unavailable.
+    // Partitioned tables are the exception: their files are listed again on each
+    // scan, and these files are only used to infer the schema.
     let files = lister
         .list()
         .await
         .context(error::ListObjectsSnafu)?
         .into_iter()
         .filter_map(|entry| {
-            if entry.path().ends_with('/') {
+            if entry.path().ends_with('/')
+                || parse_partition_values(entry.path(), partition_columns).is_none()
+            {
                 None
             } else {
                 Some(entry.path().to_string())
@@ -351,20 +478,23 @@ async fn infer_immutable_file_table_schema(
 mod test {
     use std::sync::Arc;
 
+    use catalog::process_manager::ProcessManager;
     use common_query::Output;
     use common_recordbatch::{RecordBatch, RecordBatches};
     use common_time::timestamp::TimeUnit;
     use datatypes::prelude::ConcreteDataType;
     use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema, Schema, SchemaRef};
     use datatypes::vectors::{StringVector, TimestampMillisecondVector, UInt32Vector, VectorRef};
+    use session::context::QueryContext;
     use snafu::ResultExt;
+    use sql::statements::show::ShowProcesslist;
     use table::test_util::MemTable;
     use table::TableRef;
 
     use crate::error;
     use crate::error::Result;
     use crate::sql::{
-        describe_table, DESCRIBE_TABLE_OUTPUT_SCHEMA, NULLABLE_NO, NULLABLE_YES,
+        describe_table, show_processlist, DESCRIBE_TABLE_OUTPUT_SCHEMA, NULLABLE_NO, NULLABLE_YES,
         SEMANTIC_TYPE_FIELD, SEMANTIC_TYPE_TIME_INDEX,
     };
 
@@ -432,4 +562,25 @@ mod test {
         let record_batch = RecordBatch::new(table_schema, data).unwrap();
         Arc::new(MemTable::new(table_name, record_batch))
     }
+
+    #[test]
+    fn test_show_processlist() {
+        let process_manager = Arc::new(ProcessManager::default());
+        let query = format!("SELECT '{}'", "a".repeat(200));
+        let _handle = process_manager.register(&query, &QueryContext::new());
+
+        for (full, expected_len) in [(false, 100), (true, query.len())] {
+            let Output::RecordBatches(batches) =
+                show_processlist(ShowProcesslist { full }, &process_manager.list()).unwrap() else {
+                panic!("show processlist must return record batches");
+            };
+            let batches = batches.take();
+            assert_eq!(1, batches[0].num_rows());
+            let info = batches[0].column_by_name("Info").unwrap();
+            assert_eq!(
+                expected_len,
+                info.get_ref(0).as_string().unwrap().unwrap().len()
+            );
+        }
+    }
 }
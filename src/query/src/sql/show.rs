@@ -49,7 +49,7 @@ fn sql_option(name: &str, value: SqlValue) -> SqlOption {
 
 fn create_sql_options(table_meta: &TableMeta) -> Vec<SqlOption> {
     let table_opts = &table_meta.options;
-    let mut options = Vec::with_capacity(4 + table_opts.extra_options.len());
+    let mut options = Vec::with_capacity(5 + table_opts.extra_options.len());
 
     if !table_meta.region_numbers.is_empty() {
         options.push(sql_option(
@@ -70,6 +70,12 @@ fn create_sql_options(table_meta: &TableMeta) -> Vec<SqlOption> {
             string_value(format_duration(ttl).to_string()),
         ));
     }
+    if let Some(cold_after) = table_opts.cold_after {
+        options.push(sql_option(
+            "cold_after",
+            string_value(format_duration(cold_after).to_string()),
+        ));
+    }
 
     for (k, v) in table_opts
         .extra_options
@@ -25,14 +25,17 @@ use crate::{QueryEngineFactory, QueryEngineRef};
 
 mod argmax_test;
 mod argmin_test;
+mod gap_fill_test;
 mod mean_test;
 mod my_sum_udaf_example;
 mod percentile_test;
 mod polyval_test;
 mod query_engine_test;
+mod range_select_test;
 mod scipy_stats_norm_cdf_test;
 mod scipy_stats_norm_pdf;
 mod time_range_filter_test;
+mod time_series_aggregate_test;
 
 mod function;
 mod pow;
@@ -0,0 +1,156 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_recordbatch::{RecordBatch, RecordBatches};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector};
+use session::context::QueryContext;
+use table::test_util::MemTable;
+
+use crate::parser::QueryLanguageParser;
+use crate::tests::{exec_selection, new_query_engine_with_table};
+use crate::QueryEngineRef;
+
+fn create_test_engine() -> QueryEngineRef {
+    let schema = Arc::new(
+        Schema::try_new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            )
+            .with_time_index(true),
+        ])
+        .unwrap(),
+    );
+    let recordbatch = RecordBatch::new(
+        schema,
+        vec![
+            Arc::new(StringVector::from(vec!["a", "a", "a", "b", "b"])) as _,
+            Arc::new(Float64Vector::from_slice([1.0, 2.0, 4.0, 10.0, 20.0])) as _,
+            Arc::new(TimestampMillisecondVector::from_slice([
+                0, 10_000, 60_000, 0, 20_000,
+            ])) as _,
+        ],
+    )
+    .unwrap();
+
+    new_query_engine_with_table(MemTable::new("monitor", recordbatch))
+}
+
+async fn query(engine: QueryEngineRef, sql: &str) -> String {
+    let batches = exec_selection(engine, sql).await;
+    RecordBatches::try_new(batches.first().unwrap().schema.clone(), batches)
+        .unwrap()
+        .pretty_print()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_time_bucket_gapfill() {
+    let engine = create_test_engine();
+
+    let sql =
+        "SELECT time_bucket_gapfill('20s', ts, '1970-01-01T00:00:00Z', '1970-01-01T00:01:20Z') \
+        AS bucket, host, avg(cpu) AS v, locf(avg(cpu)) AS l, interpolate(max(cpu)) AS i \
+        FROM monitor GROUP BY bucket, host ORDER BY host, bucket";
+    let expected = "\
++---------------------+------+------+------+--------------------+
+| bucket              | host | v    | l    | i                  |
++---------------------+------+------+------+--------------------+
+| 1970-01-01T00:00:00 | a    | 1.5  | 1.5  | 2.0                |
+| 1970-01-01T00:00:20 | a    |      | 1.5  | 2.666666666666667  |
+| 1970-01-01T00:00:40 | a    |      | 1.5  | 3.3333333333333335 |
+| 1970-01-01T00:01:00 | a    | 4.0  | 4.0  | 4.0                |
+| 1970-01-01T00:00:00 | b    | 10.0 | 10.0 | 10.0               |
+| 1970-01-01T00:00:20 | b    | 20.0 | 20.0 | 20.0               |
+| 1970-01-01T00:00:40 | b    |      | 20.0 |                    |
+| 1970-01-01T00:01:00 | b    |      | 20.0 |                    |
++---------------------+------+------+------+--------------------+";
+    assert_eq!(expected, query(engine.clone(), sql).await);
+
+    // Integers are treated as milliseconds, empty buckets are left null
+    // without fill.
+    let sql = "SELECT time_bucket_gapfill('20s', ts, 0, 60000) AS bucket, count(*) AS c \
+        FROM monitor WHERE host = 'b' GROUP BY bucket ORDER BY bucket";
+    let expected = "\
++---------------------+---+
+| bucket              | c |
++---------------------+---+
+| 1970-01-01T00:00:00 | 1 |
+| 1970-01-01T00:00:20 | 1 |
+| 1970-01-01T00:00:40 |   |
++---------------------+---+";
+    assert_eq!(expected, query(engine.clone(), sql).await);
+
+    // Rows out of the range are filtered, series without any row in the range
+    // are not generated.
+    let sql = "SELECT time_bucket_gapfill('20s', ts, 20000, 60000) AS bucket, host, \
+        avg(cpu) AS v FROM monitor GROUP BY bucket, host ORDER BY host, bucket";
+    let expected = "\
++---------------------+------+------+
+| bucket              | host | v    |
++---------------------+------+------+
+| 1970-01-01T00:00:20 | b    | 20.0 |
+| 1970-01-01T00:00:40 | b    |      |
++---------------------+------+------+";
+    assert_eq!(expected, query(engine, sql).await);
+}
+
+#[tokio::test]
+async fn test_invalid_time_bucket_gapfill() {
+    let engine = create_test_engine();
+
+    for (sql, error) in [
+        (
+            "SELECT locf(avg(cpu)) FROM monitor GROUP BY host",
+            "can only be applied to the aggregated values",
+        ),
+        (
+            "SELECT time_bucket_gapfill('1m', ts, 0, 60000) AS b, locf(cpu) FROM monitor \
+            GROUP BY b, cpu",
+            "can only be applied to the aggregated values",
+        ),
+        (
+            "SELECT time_bucket_gapfill(host, ts, 0, 60000) AS b, avg(cpu) FROM monitor \
+            GROUP BY b",
+            "must be a string literal",
+        ),
+        (
+            "SELECT time_bucket_gapfill('1m', ts, 60000, 0) AS b, avg(cpu) FROM monitor \
+            GROUP BY b",
+            "must be before the end",
+        ),
+        (
+            "SELECT time_bucket_gapfill('1ms', ts, 0, 3600000) AS b, avg(cpu) FROM monitor \
+            GROUP BY b",
+            "generates more than 100000 buckets",
+        ),
+    ] {
+        let stmt = QueryLanguageParser::parse_sql(sql).unwrap();
+        let plan = engine
+            .planner()
+            .plan(stmt, QueryContext::arc())
+            .await
+            .unwrap();
+        let result = engine.execute(plan, QueryContext::arc()).await;
+        let message = result.err().unwrap().to_string();
+        assert!(message.contains(error), "{sql}: {message}");
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_recordbatch::{RecordBatch, RecordBatches};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector};
+use session::context::QueryContext;
+use table::test_util::MemTable;
+
+use crate::parser::QueryLanguageParser;
+use crate::tests::{exec_selection, new_query_engine_with_table};
+use crate::QueryEngineRef;
+
+fn create_test_engine() -> QueryEngineRef {
+    let schema = Arc::new(
+        Schema::try_new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            )
+            .with_time_index(true),
+        ])
+        .unwrap(),
+    );
+    let recordbatch = RecordBatch::new(
+        schema,
+        vec![
+            Arc::new(StringVector::from(vec!["a", "a", "a", "b", "b"])) as _,
+            Arc::new(Float64Vector::from_slice([1.0, 2.0, 4.0, 10.0, 20.0])) as _,
+            Arc::new(TimestampMillisecondVector::from_slice([
+                0, 10_000, 60_000, 0, 20_000,
+            ])) as _,
+        ],
+    )
+    .unwrap();
+
+    new_query_engine_with_table(MemTable::new("monitor", recordbatch))
+}
+
+async fn query(engine: QueryEngineRef, sql: &str) -> String {
+    let batches = exec_selection(engine, sql).await;
+    RecordBatches::try_new(batches.first().unwrap().schema.clone(), batches)
+        .unwrap()
+        .pretty_print()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_range_query() {
+    let engine = create_test_engine();
+
+    let sql = "SELECT ts, host, avg(cpu) RANGE '20s' FILL PREV AS a, max(cpu) RANGE '20s' \
+        FROM monitor ALIGN '10s' BY (host) ORDER BY host, ts";
+    let expected = "\
++---------------------+------+------+--------------------+
+| ts                  | host | a    | max(cpu) RANGE 20s |
++---------------------+------+------+--------------------+
+| 1970-01-01T00:00:00 | a    | 1.0  | 1.0                |
+| 1970-01-01T00:00:10 | a    | 1.5  | 2.0                |
+| 1970-01-01T00:00:20 | a    | 1.5  | 2.0                |
+| 1970-01-01T00:00:30 | a    | 2.0  | 2.0                |
+| 1970-01-01T00:00:40 | a    | 2.0  |                    |
+| 1970-01-01T00:00:50 | a    | 2.0  |                    |
+| 1970-01-01T00:01:00 | a    | 4.0  | 4.0                |
+| 1970-01-01T00:01:10 | a    | 4.0  | 4.0                |
+| 1970-01-01T00:01:20 | a    | 4.0  | 4.0                |
+| 1970-01-01T00:00:00 | b    | 10.0 | 10.0               |
+| 1970-01-01T00:00:10 | b    | 10.0 | 10.0               |
+| 1970-01-01T00:00:20 | b    | 15.0 | 20.0               |
+| 1970-01-01T00:00:30 | b    | 20.0 | 20.0               |
+| 1970-01-01T00:00:40 | b    | 20.0 | 20.0               |
++---------------------+------+------+--------------------+";
+    assert_eq!(expected, query(engine.clone(), sql).await);
+
+    // The empty ranges are skipped without FILL.
+    let sql = "SELECT ts, count(*) RANGE '10s' AS c FROM monitor WHERE host = 'a' \
+        ALIGN '10s' BY () ORDER BY ts LIMIT 5";
+    let expected = "\
++---------------------+-----+
+| ts                  | c   |
++---------------------+-----+
+| 1970-01-01T00:00:00 | 1.0 |
+| 1970-01-01T00:00:10 | 2.0 |
+| 1970-01-01T00:00:20 | 1.0 |
+| 1970-01-01T00:01:00 | 1.0 |
+| 1970-01-01T00:01:10 | 1.0 |
++---------------------+-----+";
+    assert_eq!(expected, query(engine.clone(), sql).await);
+
+    let sql =
+        "SELECT ts, host, min(cpu) RANGE '10s' FROM monitor ALIGN '10s' BY (host) FILL LINEAR \
+        ORDER BY host, ts LIMIT 7";
+    let expected = "\
++---------------------+------+--------------------+
+| ts                  | host | min(cpu) RANGE 10s |
++---------------------+------+--------------------+
+| 1970-01-01T00:00:00 | a    | 1.0                |
+| 1970-01-01T00:00:10 | a    | 1.0                |
+| 1970-01-01T00:00:20 | a    | 2.0                |
+| 1970-01-01T00:00:30 | a    | 2.5                |
+| 1970-01-01T00:00:40 | a    | 3.0                |
+| 1970-01-01T00:00:50 | a    | 3.5                |
+| 1970-01-01T00:01:00 | a    | 4.0                |
++---------------------+------+--------------------+";
+    assert_eq!(expected, query(engine, sql).await);
+}
+
+#[tokio::test]
+async fn test_invalid_range_query() {
+    let engine = create_test_engine();
+
+    for (sql, error) in [
+        (
+            "SELECT avg(cpu) RANGE '5m', max(cpu) RANGE '10m' FROM monitor ALIGN '1m'",
+            "must have the same RANGE",
+        ),
+        (
+            "SELECT median(cpu) RANGE '5m' FROM monitor ALIGN '1m'",
+            "Unsupported aggregate in range query: median",
+        ),
+        (
+            "SELECT host FROM monitor ALIGN '1m' BY (host)",
+            "requires an aggregate with RANGE",
+        ),
+        (
+            "SELECT avg(cpu) RANGE '5x' FROM monitor ALIGN '1m'",
+            "Invalid duration '5x'",
+        ),
+    ] {
+        let stmt = QueryLanguageParser::parse_sql(sql).unwrap();
+        let result = engine.planner().plan(stmt, QueryContext::arc()).await;
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains(error), "{sql}: {message}");
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_recordbatch::{RecordBatch, RecordBatches};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector};
+use table::test_util::MemTable;
+
+use crate::tests::{exec_selection, new_query_engine_with_table};
+use crate::QueryEngineRef;
+
+fn create_test_engine() -> QueryEngineRef {
+    let schema = Arc::new(
+        Schema::try_new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("counter", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            )
+            .with_time_index(true),
+        ])
+        .unwrap(),
+    );
+    // The counter of host a resets at 20s.
+    let recordbatch = RecordBatch::new(
+        schema,
+        vec![
+            Arc::new(StringVector::from(vec!["a", "b", "a", "a", "b", "a"])) as _,
+            Arc::new(Float64Vector::from_slice([5.0, 40.0, 1.0, 4.0, 10.0, 2.0])) as _,
+            Arc::new(TimestampMillisecondVector::from_slice([
+                10_000, 30_000, 0, 30_000, 0, 20_000,
+            ])) as _,
+        ],
+    )
+    .unwrap();
+
+    new_query_engine_with_table(MemTable::new("metrics", recordbatch))
+}
+
+async fn query(engine: QueryEngineRef, sql: &str) -> String {
+    let batches = exec_selection(engine, sql).await;
+    RecordBatches::try_new(batches.first().unwrap().schema.clone(), batches)
+        .unwrap()
+        .pretty_print()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_time_series_aggregates() {
+    let engine = create_test_engine();
+
+    let sql = "SELECT host, first(counter, ts) AS f, last(counter, ts) AS l, \
+        increase(counter, ts) AS i, rate(counter, ts) AS r, \
+        round(approx_percentile(counter, 50.0)) AS p \
+        FROM metrics GROUP BY host ORDER BY host";
+    let expected = "\
++------+------+------+------+---------------------+------+
+| host | f    | l    | i    | r                   | p    |
++------+------+------+------+---------------------+------+
+| a    | 1.0  | 4.0  | 8.0  | 0.26666666666666666 | 2.0  |
+| b    | 10.0 | 40.0 | 30.0 | 1.0                 | 10.0 |
++------+------+------+------+---------------------+------+";
+    assert_eq!(expected, query(engine.clone(), sql).await);
+
+    let sql = "SELECT histogram(counter, 0, 10, 2) AS h FROM metrics WHERE host = 'a'";
+    let expected = "\
++--------------+
+| h            |
++--------------+
+| [0, 3, 1, 0] |
++--------------+";
+    assert_eq!(expected, query(engine, sql).await);
+}
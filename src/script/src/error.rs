@@ -82,6 +82,46 @@ pub enum Error {
 
     #[snafu(display("Failed to cast type, msg: {}", msg))]
     CastType { msg: String, location: Location },
+
+    #[snafu(display("Invalid schedule {}: {}", schedule, reason))]
+    InvalidSchedule {
+        schedule: String,
+        reason: String,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to register system table {}, source: {}", table_name, source))]
+    RegisterSystemTable {
+        table_name: String,
+        location: Location,
+        source: catalog::error::Error,
+    },
+
+    #[snafu(display("Failed to find table {}, source: {}", table_name, source))]
+    FindTable {
+        table_name: String,
+        location: Location,
+        source: catalog::error::Error,
+    },
+
+    #[snafu(display("Table not found: {}", table_name))]
+    TableNotFound {
+        table_name: String,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to acquire lease of running scripts, source: {}", source))]
+    AcquireLease {
+        location: Location,
+        source: catalog::error::Error,
+    },
+
+    #[snafu(display("Failed to access table {}, source: {}", table_name, source))]
+    AccessTable {
+        table_name: String,
+        location: Location,
+        source: table::error::Error,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -91,15 +131,17 @@ impl ErrorExt for Error {
         use Error::*;
         match self {
             FindColumnInScriptsTable { .. } | CastType { .. } => StatusCode::Unexpected,
-            ScriptsTableNotFound { .. } => StatusCode::TableNotFound,
-            RegisterScriptsTable { source, .. } | FindScriptsTable { source, .. } => {
-                source.status_code()
-            }
-            InsertScript { source, .. } => source.status_code(),
+            ScriptsTableNotFound { .. } | TableNotFound { .. } => StatusCode::TableNotFound,
+            RegisterScriptsTable { source, .. }
+            | FindScriptsTable { source, .. }
+            | RegisterSystemTable { source, .. }
+            | FindTable { source, .. }
+            | AcquireLease { source, .. } => source.status_code(),
+            InsertScript { source, .. } | AccessTable { source, .. } => source.status_code(),
             CompilePython { source, .. } | ExecutePython { source, .. } => source.status_code(),
             FindScript { source, .. } => source.status_code(),
             CollectRecords { source, .. } => source.status_code(),
-            ScriptNotFound { .. } => StatusCode::InvalidArguments,
+            ScriptNotFound { .. } | InvalidSchedule { .. } => StatusCode::InvalidArguments,
         }
     }
 
@@ -21,4 +21,6 @@ pub mod error;
 pub mod manager;
 #[cfg(feature = "python")]
 pub mod python;
+#[cfg(feature = "python")]
+pub mod scheduler;
 pub mod table;
@@ -0,0 +1,838 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Scheduled scripts.
+//!
+//! A script can be scheduled to run periodically, either at a fixed interval
+//! such as `5m`, or by a cron expression such as `0 */5 * * * *`. Each run
+//! executes the coprocessor and writes the returned record batches into the
+//! target table. Schedules and the time of their last runs are persisted in the
+//! `script_schedules` system table and the history of runs is recorded in the
+//! `script_runs` system table.
+//!
+//! When several frontends share the schedules, only the one holding the run
+//! lease runs the scripts.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use catalog::remote::lease::Lease;
+use catalog::{CatalogManagerRef, RegisterSystemTableRequest};
+use chrono::{TimeZone, Utc};
+use common_catalog::consts::{
+    DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, MITO_ENGINE, SCRIPT_RUNS_TABLE_ID,
+    SCRIPT_SCHEDULES_TABLE_ID,
+};
+use common_catalog::format_full_table_name;
+use common_query::Output;
+use common_recordbatch::{util as record_util, RecordBatch};
+use common_telemetry::logging;
+use common_time::util;
+use datatypes::prelude::{ConcreteDataType, ScalarVector};
+use datatypes::schema::{ColumnSchema, RawSchema};
+use datatypes::vectors::{Int64Vector, StringVector, TimestampMillisecondVector, VectorRef};
+use snafu::{ensure, OptionExt, ResultExt};
+use store_api::storage::{RegionNumber, ScanRequest};
+use table::requests::{CreateTableRequest, DeleteRequest, InsertRequest, TableOptions};
+use table::TableRef;
+
+use crate::error::{
+    AccessTableSnafu, AcquireLeaseSnafu, CastTypeSnafu, CollectRecordsSnafu,
+    FindColumnInScriptsTableSnafu, FindTableSnafu, InvalidScheduleSnafu, RegisterSystemTableSnafu,
+    Result, TableNotFoundSnafu,
+};
+use crate::manager::ScriptManager;
+
+pub const SCRIPT_SCHEDULES_TABLE_NAME: &str = "script_schedules";
+pub const SCRIPT_RUNS_TABLE_NAME: &str = "script_runs";
+
+/// How often the schedules are checked whether their scripts are due.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// When a script runs.
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    /// Runs at a fixed interval, e.g. `30s` or `1h`.
+    Interval(Duration),
+    /// Runs at the times of a cron expression with seconds, e.g. `0 */5 * * * *`.
+    Cron(Box<cron::Schedule>),
+}
+
+impl Schedule {
+    /// Returns the time of the next run after `last`, both in milliseconds.
+    pub fn next_run(&self, last: i64) -> Option<i64> {
+        match self {
+            Schedule::Interval(interval) => Some(last + interval.as_millis() as i64),
+            Schedule::Cron(schedule) => {
+                let last = Utc.timestamp_millis_opt(last).single()?;
+                schedule
+                    .after(&last)
+                    .next()
+                    .map(|next| next.timestamp_millis())
+            }
+        }
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Ok(interval) = humantime::parse_duration(s) {
+            ensure!(
+                !interval.is_zero(),
+                InvalidScheduleSnafu {
+                    schedule: s,
+                    reason: "interval must be positive",
+                }
+            );
+            return Ok(Schedule::Interval(interval));
+        }
+
+        cron::Schedule::from_str(s)
+            .map(|schedule| Schedule::Cron(Box::new(schedule)))
+            .map_err(|e| {
+                InvalidScheduleSnafu {
+                    schedule: s,
+                    reason: format!("neither an interval nor a cron expression, {e}"),
+                }
+                .build()
+            })
+    }
+}
+
+/// A script scheduled to write its outputs into `target_table` periodically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledScript {
+    pub schema: String,
+    pub name: String,
+    pub schedule: String,
+    /// Table in `schema` which the outputs of the script are written to.
+    pub target_table: String,
+    /// Time when the script was scheduled, in milliseconds.
+    pub gmt_modified: i64,
+    /// Time of the last run since the script was scheduled, in milliseconds.
+    pub last_run: Option<i64>,
+}
+
+impl ScheduledScript {
+    /// Returns whether the script is due at `now`. Scripts never run are
+    /// scheduled from the time they were scheduled.
+    fn is_due(&self, schedule: &Schedule, now: i64) -> bool {
+        let last = self.last_run.unwrap_or(self.gmt_modified);
+        schedule.next_run(last).map_or(false, |next| next <= now)
+    }
+}
+
+/// A finished run of a scheduled script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptRun {
+    pub schema: String,
+    pub name: String,
+    /// Start time in milliseconds.
+    pub start: i64,
+    pub duration: Duration,
+    /// Rows written to the target table.
+    pub rows: usize,
+    pub error: Option<String>,
+}
+
+/// Runs scheduled scripts in the background.
+pub struct ScriptScheduler {
+    catalog_manager: CatalogManagerRef,
+    script_manager: Arc<ScriptManager>,
+    /// Elects the frontend to run scripts, `None` in the standalone mode.
+    run_lease: Option<Lease>,
+}
+
+impl ScriptScheduler {
+    pub async fn try_new(
+        catalog_manager: CatalogManagerRef,
+        script_manager: Arc<ScriptManager>,
+        run_lease: Option<Lease>,
+    ) -> Result<Self> {
+        // Like the scripts table, these tables are put into default catalog and schema.
+        for (id, table_name, desc, schema) in [
+            (
+                SCRIPT_SCHEDULES_TABLE_ID,
+                SCRIPT_SCHEDULES_TABLE_NAME,
+                "Script schedules table",
+                build_script_schedules_schema(),
+            ),
+            (
+                SCRIPT_RUNS_TABLE_ID,
+                SCRIPT_RUNS_TABLE_NAME,
+                "Script runs table",
+                build_script_runs_schema(),
+            ),
+        ] {
+            let request = CreateTableRequest {
+                id,
+                catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+                schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name: table_name.to_string(),
+                desc: Some(desc.to_string()),
+                schema,
+                region_numbers: vec![0],
+                // schema and name as primary key
+                primary_key_indices: vec![0, 1],
+                create_if_not_exists: true,
+                table_options: TableOptions::default(),
+                engine: MITO_ENGINE.to_string(),
+            };
+            catalog_manager
+                .register_system_table(RegisterSystemTableRequest {
+                    create_table_request: request,
+                    open_hook: None,
+                })
+                .await
+                .context(RegisterSystemTableSnafu { table_name })?;
+        }
+
+        Ok(Self {
+            catalog_manager,
+            script_manager,
+            run_lease,
+        })
+    }
+
+    /// Starts running scheduled scripts in the background.
+    pub fn start(self: &Arc<Self>) {
+        let scheduler = self.clone();
+        let _handle = common_runtime::spawn_bg(async move {
+            let mut interval = tokio::time::interval(CHECK_INTERVAL);
+            loop {
+                let _ = interval.tick().await;
+                if let Err(e) = scheduler.run_due(util::current_time_millis()).await {
+                    logging::error!(e; "Failed to run scheduled scripts");
+                }
+            }
+        });
+    }
+
+    /// Schedules the script, or updates its schedule if it's already scheduled.
+    pub async fn schedule(
+        &self,
+        schema: &str,
+        name: &str,
+        schedule: &str,
+        target_table: &str,
+    ) -> Result<()> {
+        let _ = Schedule::from_str(schedule)?;
+        let table_name = format_full_table_name(DEFAULT_CATALOG_NAME, schema, target_table);
+        let _ = self
+            .catalog_manager
+            .table(DEFAULT_CATALOG_NAME, schema, target_table)
+            .await
+            .context(FindTableSnafu {
+                table_name: &table_name,
+            })?
+            .context(TableNotFoundSnafu { table_name })?;
+
+        // The script starts to run at its new schedule.
+        self.write_schedule(&ScheduledScript {
+            schema: schema.to_string(),
+            name: name.to_string(),
+            schedule: schedule.to_string(),
+            target_table: target_table.to_string(),
+            gmt_modified: util::current_time_millis(),
+            last_run: None,
+        })
+        .await?;
+        logging::info!(
+            "Scheduled script: name={} at {} into table {}",
+            name,
+            schedule,
+            target_table
+        );
+        Ok(())
+    }
+
+    pub async fn unschedule(&self, schema: &str, name: &str) -> Result<()> {
+        let _ = self
+            .table(SCRIPT_SCHEDULES_TABLE_NAME)
+            .await?
+            .delete(DeleteRequest {
+                key_column_values: script_key(schema, name),
+            })
+            .await
+            .context(AccessTableSnafu {
+                table_name: SCRIPT_SCHEDULES_TABLE_NAME,
+            })?;
+        logging::info!("Unscheduled script: name={}", name);
+        Ok(())
+    }
+
+    pub async fn list(&self) -> Result<Vec<ScheduledScript>> {
+        let mut scripts = vec![];
+        for batch in self.scan(SCRIPT_SCHEDULES_TABLE_NAME).await? {
+            let schemas = string_column(&batch, "schema")?;
+            let names = string_column(&batch, "name")?;
+            let schedules = string_column(&batch, "schedule")?;
+            let target_tables = string_column(&batch, "target_table")?;
+            let gmt_modifieds = timestamp_column(&batch, "gmt_modified")?;
+            let last_runs = timestamp_column(&batch, "last_run")?;
+            for i in 0..batch.num_rows() {
+                let (Some(schema), Some(name), Some(schedule), Some(target_table)) = (
+                    schemas.get_data(i),
+                    names.get_data(i),
+                    schedules.get_data(i),
+                    target_tables.get_data(i),
+                ) else { continue };
+                let Some(gmt_modified) = gmt_modifieds.get_data(i) else { continue };
+                scripts.push(ScheduledScript {
+                    schema: schema.to_string(),
+                    name: name.to_string(),
+                    schedule: schedule.to_string(),
+                    target_table: target_table.to_string(),
+                    gmt_modified: gmt_modified.0.value(),
+                    last_run: last_runs.get_data(i).map(|t| t.0.value()),
+                });
+            }
+        }
+        Ok(scripts)
+    }
+
+    /// Runs the scheduled scripts whose next runs are due at `now`, if this
+    /// frontend holds the run lease.
+    async fn run_due(&self, now: i64) -> Result<()> {
+        if !self.hold_run_lease().await? {
+            return Ok(());
+        }
+
+        for mut script in self.list().await? {
+            let schedule = match Schedule::from_str(&script.schedule) {
+                Ok(schedule) => schedule,
+                Err(e) => {
+                    logging::error!(e; "Invalid schedule of script {}", script.name);
+                    continue;
+                }
+            };
+
+            if !script.is_due(&schedule, now) {
+                continue;
+            }
+
+            // Running the previous scripts may outlast the lease, and the next holder
+            // may have run this one already.
+            if !self.renew_run_lease().await? {
+                return Ok(());
+            }
+            // The last run is persisted before running, so that the script isn't
+            // run again by the next holder of the lease if this frontend is down.
+            script.last_run = Some(now);
+            if let Err(e) = self.write_schedule(&script).await {
+                logging::error!(e; "Failed to update last run of script {}", script.name);
+                continue;
+            }
+
+            let run = self.run(&script).await;
+            if let Err(e) = self.record_run(&run).await {
+                logging::error!(e; "Failed to record run of script {}", script.name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether this frontend holds the lease of running scripts.
+    async fn hold_run_lease(&self) -> Result<bool> {
+        match &self.run_lease {
+            Some(lease) => lease.acquire().await.context(AcquireLeaseSnafu),
+            None => Ok(true),
+        }
+    }
+
+    /// Returns whether this frontend still holds the lease of running scripts,
+    /// renewing it for the writes that follow.
+    async fn renew_run_lease(&self) -> Result<bool> {
+        match &self.run_lease {
+            Some(lease) => lease.renew().await.context(AcquireLeaseSnafu),
+            None => Ok(true),
+        }
+    }
+
+    async fn write_schedule(&self, script: &ScheduledScript) -> Result<()> {
+        let mut columns_values = script_key(&script.schema, &script.name);
+        columns_values.extend([
+            (
+                "schedule".to_string(),
+                Arc::new(StringVector::from(vec![script.schedule.as_str()])) as VectorRef,
+            ),
+            (
+                "target_table".to_string(),
+                Arc::new(StringVector::from(vec![script.target_table.as_str()])) as VectorRef,
+            ),
+            (
+                "gmt_modified".to_string(),
+                Arc::new(TimestampMillisecondVector::from_slice(
+                    [script.gmt_modified],
+                )) as VectorRef,
+            ),
+            (
+                "last_run".to_string(),
+                Arc::new(TimestampMillisecondVector::from(vec![script.last_run])) as VectorRef,
+            ),
+        ]);
+        self.insert_into(SCRIPT_SCHEDULES_TABLE_NAME, columns_values)
+            .await
+    }
+
+    /// Runs the script and writes its outputs into the target table.
+    pub async fn run(&self, script: &ScheduledScript) -> ScriptRun {
+        let start = util::current_time_millis();
+        let timer = Instant::now();
+        let result = self.run_inner(script).await;
+
+        let (rows, error) = match result {
+            Ok(rows) => (rows, None),
+            Err(e) => {
+                logging::error!(e; "Failed to run scheduled script {}", script.name);
+                (0, Some(e.to_string()))
+            }
+        };
+        ScriptRun {
+            schema: script.schema.clone(),
+            name: script.name.clone(),
+            start,
+            duration: timer.elapsed(),
+            rows,
+            error,
+        }
+    }
+
+    async fn run_inner(&self, script: &ScheduledScript) -> Result<usize> {
+        let output = self
+            .script_manager
+            .execute(&script.schema, &script.name, HashMap::new())
+            .await?;
+        let batches = match output {
+            Output::Stream(stream) => record_util::collect(stream)
+                .await
+                .context(CollectRecordsSnafu)?,
+            Output::RecordBatches(batches) => batches.take(),
+            Output::AffectedRows(_) => vec![],
+        };
+
+        let table_name =
+            format_full_table_name(DEFAULT_CATALOG_NAME, &script.schema, &script.target_table);
+        let target = self
+            .catalog_manager
+            .table(DEFAULT_CATALOG_NAME, &script.schema, &script.target_table)
+            .await
+            .context(FindTableSnafu {
+                table_name: &table_name,
+            })?
+            .context(TableNotFoundSnafu {
+                table_name: &table_name,
+            })?;
+
+        let mut rows = 0;
+        for batch in batches {
+            if batch.num_rows() == 0 {
+                continue;
+            }
+            let columns_values = batch
+                .schema
+                .column_schemas()
+                .iter()
+                .zip(batch.columns())
+                .map(|(column, vector)| (column.name.clone(), vector.clone()))
+                .collect();
+            rows += target
+                .insert(InsertRequest {
+                    catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+                    schema_name: script.schema.clone(),
+                    table_name: script.target_table.clone(),
+                    columns_values,
+                    region_number: insert_region(&target),
+                })
+                .await
+                .context(AccessTableSnafu {
+                    table_name: &table_name,
+                })?;
+        }
+        Ok(rows)
+    }
+
+    async fn record_run(&self, run: &ScriptRun) -> Result<()> {
+        let columns_values = HashMap::from([
+            (
+                "schema".to_string(),
+                Arc::new(StringVector::from(vec![run.schema.as_str()])) as VectorRef,
+            ),
+            (
+                "name".to_string(),
+                Arc::new(StringVector::from(vec![run.name.as_str()])) as VectorRef,
+            ),
+            (
+                "start_time".to_string(),
+                Arc::new(TimestampMillisecondVector::from_slice([run.start])) as VectorRef,
+            ),
+            (
+                "duration_ms".to_string(),
+                Arc::new(Int64Vector::from_slice([run.duration.as_millis() as i64])) as VectorRef,
+            ),
+            (
+                "rows".to_string(),
+                Arc::new(Int64Vector::from_slice([run.rows as i64])) as VectorRef,
+            ),
+            (
+                "error".to_string(),
+                Arc::new(StringVector::from(vec![run.error.as_deref()])) as VectorRef,
+            ),
+        ]);
+        self.insert_into(SCRIPT_RUNS_TABLE_NAME, columns_values)
+            .await
+    }
+
+    async fn insert_into(
+        &self,
+        table_name: &str,
+        columns_values: HashMap<String, VectorRef>,
+    ) -> Result<()> {
+        let table = self.table(table_name).await?;
+        let _ = table
+            .insert(InsertRequest {
+                catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+                schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name: table_name.to_string(),
+                columns_values,
+                region_number: insert_region(&table),
+            })
+            .await
+            .context(AccessTableSnafu { table_name })?;
+        Ok(())
+    }
+
+    async fn scan(&self, table_name: &str) -> Result<Vec<RecordBatch>> {
+        let stream = self
+            .table(table_name)
+            .await?
+            .scan_to_stream(ScanRequest::default())
+            .await
+            .context(AccessTableSnafu { table_name })?;
+        record_util::collect(stream)
+            .await
+            .context(CollectRecordsSnafu)
+    }
+
+    async fn table(&self, table_name: &str) -> Result<TableRef> {
+        self.catalog_manager
+            .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, table_name)
+            .await
+            .context(FindTableSnafu { table_name })?
+            .context(TableNotFoundSnafu { table_name })
+    }
+}
+
+/// Returns the region to insert into `table`. Tables of the frontend catalog in
+/// the distributed mode route the rows to regions by their partition rules and
+/// ignore the region, while tables in the standalone mode have a single region.
+fn insert_region(table: &TableRef) -> RegionNumber {
+    table
+        .table_info()
+        .meta
+        .region_numbers
+        .first()
+        .copied()
+        .unwrap_or_default()
+}
+
+fn script_key(schema: &str, name: &str) -> HashMap<String, VectorRef> {
+    HashMap::from([
+        (
+            "schema".to_string(),
+            Arc::new(StringVector::from(vec![schema])) as VectorRef,
+        ),
+        (
+            "name".to_string(),
+            Arc::new(StringVector::from(vec![name])) as VectorRef,
+        ),
+        (
+            "timestamp".to_string(),
+            // Timestamp in key part is intentionally left to 0
+            Arc::new(TimestampMillisecondVector::from_slice([0])) as VectorRef,
+        ),
+    ])
+}
+
+fn string_column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a StringVector> {
+    let column = batch
+        .column_by_name(name)
+        .with_context(|| FindColumnInScriptsTableSnafu { name })?;
+    column
+        .as_any()
+        .downcast_ref::<StringVector>()
+        .with_context(|| CastTypeSnafu {
+            msg: format!(
+                "can't downcast {:?} array into string vector",
+                column.data_type()
+            ),
+        })
+}
+
+fn timestamp_column<'a>(
+    batch: &'a RecordBatch,
+    name: &str,
+) -> Result<&'a TimestampMillisecondVector> {
+    let column = batch
+        .column_by_name(name)
+        .with_context(|| FindColumnInScriptsTableSnafu { name })?;
+    column
+        .as_any()
+        .downcast_ref::<TimestampMillisecondVector>()
+        .with_context(|| CastTypeSnafu {
+            msg: format!(
+                "can't downcast {:?} array into timestamp vector",
+                column.data_type()
+            ),
+        })
+}
+
+fn build_script_schedules_schema() -> RawSchema {
+    RawSchema::new(vec![
+        ColumnSchema::new("schema", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("name", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new(
+            "timestamp",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )
+        .with_time_index(true),
+        ColumnSchema::new("schedule", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("target_table", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new(
+            "gmt_modified",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "last_run",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            true,
+        ),
+    ])
+}
+
+fn build_script_runs_schema() -> RawSchema {
+    RawSchema::new(vec![
+        ColumnSchema::new("schema", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new("name", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new(
+            "start_time",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )
+        .with_time_index(true),
+        ColumnSchema::new("duration_ms", ConcreteDataType::int64_datatype(), false),
+        ColumnSchema::new("rows", ConcreteDataType::int64_datatype(), false),
+        ColumnSchema::new("error", ConcreteDataType::string_datatype(), true),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use catalog::remote::mock::MockKvBackend;
+    use catalog::remote::KvBackendRef;
+    use catalog::CatalogManager;
+    use common_test_util::temp_dir::{create_temp_dir, TempDir};
+    use log_store::raft_engine::log_store::RaftEngineLogStore;
+    use log_store::LogConfig;
+    use mito::config::EngineConfig as TableEngineConfig;
+    use mito::engine::MitoEngine;
+    use mito::table::test_util::new_test_object_store;
+    use query::QueryEngineFactory;
+    use storage::compaction::noop::NoopCompactionScheduler;
+    use storage::config::EngineConfig as StorageEngineConfig;
+    use storage::EngineImpl;
+    use table::engine::manager::MemoryTableEngineManager;
+
+    use super::*;
+
+    type DefaultEngine = MitoEngine<EngineImpl<RaftEngineLogStore>>;
+
+    struct TestEnv {
+        catalog_manager: CatalogManagerRef,
+        script_manager: Arc<ScriptManager>,
+        _dirs: (TempDir, TempDir),
+    }
+
+    impl TestEnv {
+        async fn new(prefix: &str) -> Self {
+            let wal_dir = create_temp_dir(&format!("{prefix}_wal"));
+            let (data_dir, object_store) = new_test_object_store(prefix).await;
+            let log_store = RaftEngineLogStore::try_new(LogConfig {
+                log_file_dir: wal_dir.path().to_string_lossy().to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+            let engine = Arc::new(DefaultEngine::new(
+                TableEngineConfig::default(),
+                EngineImpl::new(
+                    StorageEngineConfig::default(),
+                    Arc::new(log_store),
+                    object_store.clone(),
+                    Arc::new(NoopCompactionScheduler::default()),
+                )
+                .unwrap(),
+                object_store,
+            ));
+            let engine_manager = Arc::new(MemoryTableEngineManager::new(engine));
+            let catalog_manager = Arc::new(
+                catalog::local::LocalCatalogManager::try_new(engine_manager)
+                    .await
+                    .unwrap(),
+            );
+            catalog_manager.start().await.unwrap();
+
+            let query_engine =
+                QueryEngineFactory::new(catalog_manager.clone(), false).query_engine();
+            let script_manager = Arc::new(
+                ScriptManager::new(catalog_manager.clone(), query_engine)
+                    .await
+                    .unwrap(),
+            );
+            Self {
+                catalog_manager,
+                script_manager,
+                _dirs: (wal_dir, data_dir),
+            }
+        }
+
+        async fn scheduler(&self, run_lease: Option<Lease>) -> ScriptScheduler {
+            let scheduler = ScriptScheduler::try_new(
+                self.catalog_manager.clone(),
+                self.script_manager.clone(),
+                run_lease,
+            )
+            .await
+            .unwrap();
+            self.catalog_manager.start().await.unwrap();
+            scheduler
+        }
+    }
+
+    async fn count_runs(scheduler: &ScriptScheduler) -> usize {
+        scheduler
+            .scan(SCRIPT_RUNS_TABLE_NAME)
+            .await
+            .unwrap()
+            .iter()
+            .map(|batch| batch.num_rows())
+            .sum()
+    }
+
+    #[tokio::test]
+    async fn test_run_due_scripts() {
+        common_telemetry::init_default_ut_logging();
+        let env = TestEnv::new("test_run_due_scripts").await;
+        let scheduler = env.scheduler(None).await;
+
+        // The script doesn't exist, its runs are recorded with errors.
+        scheduler
+            .schedule(
+                DEFAULT_SCHEMA_NAME,
+                "missing",
+                "10s",
+                SCRIPT_RUNS_TABLE_NAME,
+            )
+            .await
+            .unwrap();
+        let scheduled = scheduler.list().await.unwrap().remove(0);
+        assert_eq!(None, scheduled.last_run);
+        let start = scheduled.gmt_modified;
+
+        scheduler.run_due(start + 5_000).await.unwrap();
+        assert_eq!(0, count_runs(&scheduler).await);
+
+        scheduler.run_due(start + 10_000).await.unwrap();
+        assert_eq!(1, count_runs(&scheduler).await);
+        let scheduled = scheduler.list().await.unwrap().remove(0);
+        assert_eq!(Some(start + 10_000), scheduled.last_run);
+
+        // The last run is persisted, a restarted scheduler doesn't run it again.
+        let scheduler = env.scheduler(None).await;
+        scheduler.run_due(start + 15_000).await.unwrap();
+        assert_eq!(1, count_runs(&scheduler).await);
+        scheduler.run_due(start + 20_000).await.unwrap();
+        assert_eq!(2, count_runs(&scheduler).await);
+
+        // Rescheduling starts over from now on.
+        scheduler
+            .schedule(DEFAULT_SCHEMA_NAME, "missing", "1h", SCRIPT_RUNS_TABLE_NAME)
+            .await
+            .unwrap();
+        let scheduled = scheduler.list().await.unwrap().remove(0);
+        assert_eq!(None, scheduled.last_run);
+        scheduler.run_due(start + 30_000).await.unwrap();
+        assert_eq!(2, count_runs(&scheduler).await);
+
+        scheduler
+            .unschedule(DEFAULT_SCHEMA_NAME, "missing")
+            .await
+            .unwrap();
+        assert!(scheduler.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_due_scripts_on_lease_holder() {
+        common_telemetry::init_default_ut_logging();
+        let env = TestEnv::new("test_run_due_scripts_on_lease_holder").await;
+        let backend: KvBackendRef = Arc::new(MockKvBackend::default());
+        let new_lease = |holder: &str| {
+            Lease::new(
+                backend.clone(),
+                "script_schedule_run",
+                holder.to_string(),
+                Duration::from_secs(30),
+            )
+        };
+        let scheduler_a = env.scheduler(Some(new_lease("a"))).await;
+        let scheduler_b = env.scheduler(Some(new_lease("b"))).await;
+
+        scheduler_a
+            .schedule(DEFAULT_SCHEMA_NAME, "missing", "1s", SCRIPT_RUNS_TABLE_NAME)
+            .await
+            .unwrap();
+        let start = scheduler_a.list().await.unwrap()[0].gmt_modified;
+
+        // a holds the lease from now on, b never runs the script.
+        scheduler_a.run_due(start + 1_000).await.unwrap();
+        scheduler_b.run_due(start + 1_000).await.unwrap();
+        assert_eq!(1, count_runs(&scheduler_a).await);
+        scheduler_b.run_due(start + 2_000).await.unwrap();
+        assert_eq!(1, count_runs(&scheduler_a).await);
+        scheduler_a.run_due(start + 2_000).await.unwrap();
+        assert_eq!(2, count_runs(&scheduler_a).await);
+    }
+
+    #[test]
+    fn test_parse_schedule() {
+        let schedule = Schedule::from_str("5m").unwrap();
+        assert!(matches!(schedule, Schedule::Interval(d) if d == Duration::from_secs(300)));
+        assert_eq!(Some(300_000), schedule.next_run(0));
+
+        // every 10 seconds
+        let schedule = Schedule::from_str("*/10 * * * * *").unwrap();
+        assert!(matches!(schedule, Schedule::Cron(_)));
+        assert_eq!(Some(10_000), schedule.next_run(0));
+        assert_eq!(Some(20_000), schedule.next_run(12_345));
+
+        assert!(Schedule::from_str("0s").is_err());
+        assert!(Schedule::from_str("every day").is_err());
+    }
+}
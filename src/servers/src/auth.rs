@@ -37,6 +37,21 @@ pub trait UserProvider: Send + Sync {
     /// This method should be called after [`authenticate`].
     async fn authorize(&self, catalog: &str, schema: &str, user_info: &UserInfo) -> Result<()>;
 
+    /// [`authorize_privilege`] checks whether a user has `privilege` on the table
+    /// `catalog.schema.table`, or on the whole schema if `table` is `None`.
+    /// This method should be called after [`authenticate`]. Providers without
+    /// fine-grained privileges allow everything.
+    async fn authorize_privilege(
+        &self,
+        _user_info: &UserInfo,
+        _privilege: Privilege,
+        _catalog: &str,
+        _schema: &str,
+        _table: Option<&str>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     /// [`auth`] is a combination of [`authenticate`] and [`authorize`].
     /// In most cases it's preferred for both convenience and performance.
     async fn auth(
@@ -54,6 +69,33 @@ pub trait UserProvider: Send + Sync {
 
 pub type UserProviderRef = Arc<dyn UserProvider>;
 
+/// Table-level privileges checked before executing a statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Privilege {
+    /// Query a table.
+    Read,
+    /// Insert into or delete from a table.
+    Write,
+    /// Create, alter or drop a table.
+    Ddl,
+}
+
+impl Privilege {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Privilege::Read => "READ",
+            Privilege::Write => "WRITE",
+            Privilege::Ddl => "DDL",
+        }
+    }
+}
+
+impl std::fmt::Display for Privilege {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 type Username<'a> = &'a str;
 type HostOrIp<'a> = &'a str;
 
@@ -135,6 +177,18 @@ pub enum Error {
         schema: String,
         username: String,
     },
+
+    #[snafu(display(
+        "Permission denied for user '{}', {} privilege is required on {}",
+        username,
+        privilege,
+        object
+    ))]
+    PermissionDenied {
+        username: String,
+        privilege: String,
+        object: String,
+    },
 }
 
 impl ErrorExt for Error {
@@ -149,7 +203,7 @@ impl ErrorExt for Error {
             Error::UserNotFound { .. } => StatusCode::UserNotFound,
             Error::UnsupportedPasswordType { .. } => StatusCode::UnsupportedPasswordType,
             Error::UserPasswordMismatch { .. } => StatusCode::UserPasswordMismatch,
-            Error::AccessDenied { .. } => StatusCode::AccessDenied,
+            Error::AccessDenied { .. } | Error::PermissionDenied { .. } => StatusCode::AccessDenied,
         }
     }
 
@@ -95,6 +95,12 @@ pub struct StaticUserProvider {
     users: HashMap<String, Vec<u8>>,
 }
 
+impl StaticUserProvider {
+    pub fn has_user(&self, username: &str) -> bool {
+        self.users.contains_key(username)
+    }
+}
+
 #[async_trait]
 impl UserProvider for StaticUserProvider {
     fn name(&self) -> &str {
@@ -165,10 +171,59 @@ pub fn auth_mysql(
     salt: Salt,
     username: &str,
     save_pwd: &[u8],
+) -> Result<()> {
+    auth_mysql_with_hash(auth_data, salt, username, &double_sha1(save_pwd))
+}
+
+/// Authenticates a password sent by the client against the stored password hash,
+/// see [`hash_password`].
+pub fn auth_with_password_hash(
+    username: &str,
+    password: Password<'_>,
+    password_hash: &[u8],
+) -> Result<()> {
+    match password {
+        Password::PlainText(pwd) => {
+            ensure!(
+                !pwd.expose_secret().is_empty(),
+                IllegalParamSnafu {
+                    msg: "blank password"
+                }
+            );
+            ensure!(
+                hash_password(pwd.expose_secret().as_bytes()) == password_hash,
+                UserPasswordMismatchSnafu {
+                    username: username.to_string(),
+                }
+            );
+            Ok(())
+        }
+        Password::MysqlNativePassword(auth_data, salt) => {
+            ensure!(
+                auth_data.len() == 20,
+                IllegalParamSnafu {
+                    msg: "Illegal MySQL native password format, length != 20"
+                }
+            );
+            auth_mysql_with_hash(auth_data, salt, username, password_hash)
+        }
+        Password::PgMD5(_, _) => UnsupportedPasswordTypeSnafu {
+            password_type: "pg_md5",
+        }
+        .fail(),
+    }
+}
+
+/// Authenticates a MySQL native password against the stored password hash,
+/// see [`hash_password`].
+pub fn auth_mysql_with_hash(
+    auth_data: HashedPassword,
+    salt: Salt,
+    username: &str,
+    hash_stage_2: &[u8],
 ) -> Result<()> {
     // ref: https://github.com/mysql/mysql-server/blob/a246bad76b9271cb4333634e954040a970222e0a/sql/auth/password.cc#L62
-    let hash_stage_2 = double_sha1(save_pwd);
-    let tmp = sha1_two(salt, &hash_stage_2);
+    let tmp = sha1_two(salt, hash_stage_2);
     // xor auth_data and tmp
     let mut xor_result = [0u8; 20];
     for i in 0..20 {
@@ -202,6 +257,12 @@ fn double_sha1(data: &[u8]) -> Vec<u8> {
     sha1_one(&sha1_one(data))
 }
 
+/// Hashes a password as `SHA1(SHA1(password))`, the same as MySQL stores it, so
+/// that the hash can authenticate both plain text and MySQL native passwords.
+pub fn hash_password(password: &[u8]) -> Vec<u8> {
+    double_sha1(password)
+}
+
 #[cfg(test)]
 pub mod test {
     use std::fs::File;
@@ -210,7 +271,9 @@ pub mod test {
     use common_test_util::temp_dir::create_temp_dir;
     use session::context::UserInfo;
 
-    use crate::auth::user_provider::{double_sha1, sha1_one, sha1_two, StaticUserProvider};
+    use crate::auth::user_provider::{
+        auth_with_password_hash, double_sha1, hash_password, sha1_one, sha1_two, StaticUserProvider,
+    };
     use crate::auth::{Identity, Password, UserProvider};
 
     #[test]
@@ -286,4 +349,35 @@ admin=654321",
         test_authenticate(&provider, "root", "123456").await;
         test_authenticate(&provider, "admin", "654321").await;
     }
+
+    #[test]
+    fn test_auth_with_password_hash() {
+        let hash = hash_password(b"123456");
+        auth_with_password_hash(
+            "root",
+            Password::PlainText("123456".to_string().into()),
+            &hash,
+        )
+        .unwrap();
+        assert!(auth_with_password_hash(
+            "root",
+            Password::PlainText("654321".to_string().into()),
+            &hash
+        )
+        .is_err());
+
+        // mysql native password: SHA1(password) XOR SHA1(salt + SHA1(SHA1(password)))
+        let salt = b"01234567890123456789";
+        let auth_data = sha1_one(b"123456")
+            .iter()
+            .zip(sha1_two(salt, &hash))
+            .map(|(a, b)| a ^ b)
+            .collect::<Vec<_>>();
+        auth_with_password_hash(
+            "root",
+            Password::MysqlNativePassword(&auth_data, salt),
+            &hash,
+        )
+        .unwrap();
+    }
 }
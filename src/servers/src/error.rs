@@ -110,6 +110,13 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to schedule script with name: {}, source: {}", name, source))]
+    ScheduleScript {
+        name: String,
+        location: Location,
+        source: BoxedError,
+    },
+
     #[snafu(display("Not supported: {}", feat))]
     NotSupported { feat: String },
 
@@ -122,6 +129,13 @@ pub enum Error {
         source: influxdb_line_protocol::Error,
     },
 
+    #[snafu(display("Failed to parse InfluxQL: {}, reason: {}", query, reason))]
+    ParseInfluxql {
+        query: String,
+        reason: String,
+        location: Location,
+    },
+
     #[snafu(display("Failed to write InfluxDB line protocol, source: {}", source))]
     InfluxdbLinesWrite {
         location: Location,
@@ -155,6 +169,16 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Invalid OpenTSDB query, reason: {}", reason))]
+    InvalidOpentsdbQuery { reason: String, location: Location },
+
+    #[snafu(display("Invalid Graphite template: {}, reason: {}", template, reason))]
+    InvalidGraphiteTemplate {
+        template: String,
+        reason: String,
+        location: Location,
+    },
+
     #[snafu(display("Failed to decode prometheus remote request, source: {}", source))]
     DecodePromRemoteRequest {
         location: Location,
@@ -330,7 +354,6 @@ impl ErrorExt for Error {
             Internal { .. }
             | InternalIo { .. }
             | TokioIo { .. }
-            | CollectRecordbatch { .. }
             | StartHttp { .. }
             | StartGrpc { .. }
             | AlreadyStarted { .. }
@@ -340,8 +363,11 @@ impl ErrorExt for Error {
             | GrpcReflectionService { .. }
             | BuildHttpResponse { .. } => StatusCode::Internal,
 
+            CollectRecordbatch { source, .. } => source.status_code(),
+
             InsertScript { source, .. }
             | ExecuteScript { source, .. }
+            | ScheduleScript { source, .. }
             | ExecuteQuery { source, .. }
             | ExecutePlan { source, .. }
             | ExecuteGrpcQuery { source, .. }
@@ -350,9 +376,12 @@ impl ErrorExt for Error {
             NotSupported { .. }
             | InvalidQuery { .. }
             | InfluxdbLineProtocol { .. }
+            | ParseInfluxql { .. }
             | ConnResetByPeer { .. }
             | InvalidOpentsdbLine { .. }
             | InvalidOpentsdbJsonRequest { .. }
+            | InvalidOpentsdbQuery { .. }
+            | InvalidGraphiteTemplate { .. }
             | DecodePromRemoteRequest { .. }
             | DecompressPromRemoteRequest { .. }
             | InvalidPromRemoteRequest { .. }
@@ -432,7 +461,10 @@ fn status_to_tonic_code(status_code: StatusCode) -> Code {
         | StatusCode::DatabaseNotFound
         | StatusCode::UserNotFound => Code::NotFound,
         StatusCode::StorageUnavailable => Code::Unavailable,
-        StatusCode::RuntimeResourcesExhausted => Code::ResourceExhausted,
+        StatusCode::RuntimeResourcesExhausted
+        | StatusCode::ResourcesExhausted
+        | StatusCode::RateLimited
+        | StatusCode::QuotaExceeded => Code::ResourceExhausted,
         StatusCode::UnsupportedPasswordType
         | StatusCode::UserPasswordMismatch
         | StatusCode::AuthHeaderNotFound
@@ -471,15 +503,24 @@ impl IntoResponse for Error {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
             Error::InfluxdbLineProtocol { .. }
+            | Error::ParseInfluxql { .. }
             | Error::InfluxdbLinesWrite { .. }
             | Error::PromSeriesWrite { .. }
             | Error::InvalidOpentsdbLine { .. }
             | Error::InvalidOpentsdbJsonRequest { .. }
+            | Error::InvalidOpentsdbQuery { .. }
             | Error::DecodePromRemoteRequest { .. }
             | Error::DecompressPromRemoteRequest { .. }
             | Error::InvalidPromRemoteRequest { .. }
             | Error::InvalidQuery { .. }
             | Error::TimePrecision { .. } => (HttpStatusCode::BAD_REQUEST, self.to_string()),
+            // Clients like Prometheus and Telegraf retry writes on 429.
+            _ if self.status_code() == StatusCode::RateLimited => {
+                (HttpStatusCode::TOO_MANY_REQUESTS, self.to_string())
+            }
+            _ if self.status_code() == StatusCode::QuotaExceeded => {
+                (HttpStatusCode::BAD_REQUEST, self.to_string())
+            }
             _ => {
                 logging::error!(self; "Failed to handle HTTP request");
 
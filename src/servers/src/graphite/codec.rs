@@ -0,0 +1,288 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::column::SemanticType;
+use api::v1::{column, Column, ColumnDataType, InsertRequest as GrpcInsertRequest};
+use snafu::OptionExt;
+
+use crate::error::{self, Result};
+use crate::graphite::template::Templates;
+
+pub const GRAPHITE_TIMESTAMP_COLUMN_NAME: &str = "greptime_timestamp";
+pub const GRAPHITE_DEFAULT_FIELD_NAME: &str = "value";
+
+/// A row of a measurement, written by the Graphite or StatsD server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metric {
+    measurement: String,
+    ts_millis: i64,
+    fields: Vec<(String, f64)>,
+    tags: Vec<(String, String)>,
+}
+
+impl Metric {
+    pub fn new(
+        measurement: String,
+        ts_millis: i64,
+        fields: Vec<(String, f64)>,
+        tags: Vec<(String, String)>,
+    ) -> Self {
+        Self {
+            measurement,
+            ts_millis,
+            fields,
+            tags,
+        }
+    }
+
+    /// Creates a metric from a Graphite path and value, extracting the measurement,
+    /// field and tags by `templates`. Tags of Graphite's tagged series format
+    /// (`path;tag1=value1;tag2=value2`) are kept as well.
+    pub fn from_path(
+        path: &str,
+        value: f64,
+        ts_millis: i64,
+        templates: &Templates,
+    ) -> Result<Self> {
+        let mut segments = path.split(';');
+        // Safety: split always returns at least one segment.
+        let path = segments.next().unwrap();
+        if path.is_empty() {
+            return error::InvalidQuerySnafu {
+                reason: "empty metric path",
+            }
+            .fail();
+        }
+
+        let parsed = templates.apply(path);
+        let mut tags = parsed.tags;
+        for segment in segments {
+            match segment.split_once('=') {
+                Some((k, v)) if !k.is_empty() && !v.is_empty() => {
+                    tags.retain(|(name, _)| name != k);
+                    tags.push((k.to_string(), v.to_string()));
+                }
+                _ => {
+                    return error::InvalidQuerySnafu {
+                        reason: format!("invalid tag: {segment}"),
+                    }
+                    .fail()
+                }
+            }
+        }
+
+        let field = parsed
+            .field
+            .unwrap_or_else(|| GRAPHITE_DEFAULT_FIELD_NAME.to_string());
+        Ok(Metric {
+            measurement: parsed.measurement,
+            ts_millis,
+            fields: vec![(field, value)],
+            tags,
+        })
+    }
+
+    /// Parses a line of Graphite plaintext protocol: `<path> <value> [timestamp]`,
+    /// where timestamp is in seconds. A missing or negative timestamp means now.
+    pub fn try_create(line: &str, templates: &Templates, now_millis: i64) -> Result<Self> {
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        if tokens.len() < 2 || tokens.len() > 3 {
+            return error::InvalidQuerySnafu {
+                reason: format!("expect '<path> <value> [timestamp]', got: {line}"),
+            }
+            .fail();
+        }
+
+        let value = tokens[1]
+            .parse::<f64>()
+            .ok()
+            .with_context(|| error::InvalidQuerySnafu {
+                reason: format!("invalid value: {}", tokens[1]),
+            })?;
+        let ts_millis = match tokens.get(2) {
+            Some(ts) => {
+                let ts = ts
+                    .parse::<f64>()
+                    .ok()
+                    .with_context(|| error::InvalidQuerySnafu {
+                        reason: format!("invalid timestamp: {ts}"),
+                    })?;
+                if ts < 0.0 {
+                    now_millis
+                } else {
+                    (ts * 1000.0) as i64
+                }
+            }
+            None => now_millis,
+        };
+
+        Self::from_path(tokens[0], value, ts_millis, templates)
+    }
+
+    pub fn measurement(&self) -> &str {
+        &self.measurement
+    }
+
+    pub fn ts_millis(&self) -> i64 {
+        self.ts_millis
+    }
+
+    pub fn fields(&self) -> &Vec<(String, f64)> {
+        &self.fields
+    }
+
+    pub fn tags(&self) -> &Vec<(String, String)> {
+        &self.tags
+    }
+
+    pub fn as_grpc_insert(&self) -> GrpcInsertRequest {
+        let mut columns = Vec::with_capacity(1 + self.fields.len() + self.tags.len());
+
+        columns.push(Column {
+            column_name: GRAPHITE_TIMESTAMP_COLUMN_NAME.to_string(),
+            values: Some(column::Values {
+                ts_millisecond_values: vec![self.ts_millis],
+                ..Default::default()
+            }),
+            semantic_type: SemanticType::Timestamp as i32,
+            datatype: ColumnDataType::TimestampMillisecond as i32,
+            ..Default::default()
+        });
+
+        for (name, value) in self.fields.iter() {
+            columns.push(Column {
+                column_name: name.to_string(),
+                values: Some(column::Values {
+                    f64_values: vec![*value],
+                    ..Default::default()
+                }),
+                semantic_type: SemanticType::Field as i32,
+                datatype: ColumnDataType::Float64 as i32,
+                ..Default::default()
+            });
+        }
+
+        for (tagk, tagv) in self.tags.iter() {
+            columns.push(Column {
+                column_name: tagk.to_string(),
+                values: Some(column::Values {
+                    string_values: vec![tagv.to_string()],
+                    ..Default::default()
+                }),
+                semantic_type: SemanticType::Tag as i32,
+                datatype: ColumnDataType::String as i32,
+                ..Default::default()
+            });
+        }
+
+        GrpcInsertRequest {
+            table_name: self.measurement.clone(),
+            region_number: 0,
+            columns,
+            row_count: 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_create() {
+        let templates = Templates::try_new(&[".host.measurement.field".to_string()], ".").unwrap();
+
+        let metric =
+            Metric::try_create("servers.web01.cpu.idle 98.5 1346846400", &templates, 0).unwrap();
+        assert_eq!(
+            Metric::new(
+                "cpu".to_string(),
+                1346846400000,
+                vec![("idle".to_string(), 98.5)],
+                vec![("host".to_string(), "web01".to_string())],
+            ),
+            metric
+        );
+
+        let metric = Metric::try_create(
+            "servers.web01.cpu;dc=lga;host=web02 1",
+            &Templates::default(),
+            1000,
+        )
+        .unwrap();
+        assert_eq!(
+            Metric::new(
+                "servers.web01.cpu".to_string(),
+                1000,
+                vec![("value".to_string(), 1.0)],
+                vec![
+                    ("dc".to_string(), "lga".to_string()),
+                    ("host".to_string(), "web02".to_string())
+                ],
+            ),
+            metric
+        );
+        assert_eq!(
+            1000,
+            Metric::try_create("cpu 1 -1", &templates, 1000)
+                .unwrap()
+                .ts_millis()
+        );
+
+        for line in [
+            "",
+            "cpu",
+            "cpu 1 2 3",
+            "cpu x 1",
+            "cpu 1 x",
+            "cpu;dc 1",
+            ";dc=lga 1",
+        ] {
+            assert!(Metric::try_create(line, &templates, 0).is_err(), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_as_grpc_insert() {
+        let metric = Metric::new(
+            "cpu".to_string(),
+            1000,
+            vec![("idle".to_string(), 98.5)],
+            vec![("host".to_string(), "web01".to_string())],
+        );
+        let insert = metric.as_grpc_insert();
+        assert_eq!("cpu", insert.table_name);
+        assert_eq!(1, insert.row_count);
+        assert_eq!(
+            vec!["greptime_timestamp", "idle", "host"],
+            insert
+                .columns
+                .iter()
+                .map(|c| c.column_name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![1000],
+            insert.columns[0]
+                .values
+                .as_ref()
+                .unwrap()
+                .ts_millisecond_values
+        );
+        assert_eq!(
+            vec![98.5],
+            insert.columns[1].values.as_ref().unwrap().f64_values
+        );
+    }
+}
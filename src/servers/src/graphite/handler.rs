@@ -0,0 +1,135 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_telemetry::logging::warn;
+use common_telemetry::timer;
+use common_time::util::current_time_millis;
+use session::context::QueryContext;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, BufReader};
+
+use crate::error::Result;
+use crate::graphite::codec::Metric;
+use crate::graphite::template::Templates;
+use crate::graphite::{pickle, GraphiteProtocol};
+use crate::opentsdb::connection::Connection;
+use crate::query_handler::GraphiteProtocolHandlerRef;
+use crate::shutdown::Shutdown;
+
+/// The maximum size of a pickle payload, same as carbon's.
+const MAX_PICKLE_PAYLOAD_SIZE: usize = 1024 * 1024;
+
+/// Per-connection handler. Reads metrics in plaintext or pickle protocol and applies
+/// them to [GraphiteProtocolHandler](crate::query_handler::GraphiteProtocolHandler).
+///
+/// Graphite protocols have no response, invalid metrics are logged and skipped.
+pub(crate) struct Handler {
+    query_handler: GraphiteProtocolHandlerRef,
+    templates: Arc<Templates>,
+    protocol: GraphiteProtocol,
+
+    /// Listen for shutdown notifications, see [OpentsdbServer](crate::opentsdb::OpentsdbServer).
+    shutdown: Shutdown,
+}
+
+impl Handler {
+    pub(crate) fn new(
+        query_handler: GraphiteProtocolHandlerRef,
+        templates: Arc<Templates>,
+        protocol: GraphiteProtocol,
+        shutdown: Shutdown,
+    ) -> Self {
+        Self {
+            query_handler,
+            templates,
+            protocol,
+            shutdown,
+        }
+    }
+
+    pub(crate) async fn run<S: AsyncWrite + AsyncRead + Unpin>(&mut self, stream: S) -> Result<()> {
+        match self.protocol {
+            GraphiteProtocol::Plaintext => self.run_plaintext(Connection::new(stream)).await,
+            GraphiteProtocol::Pickle => self.run_pickle(BufReader::new(stream)).await,
+        }
+    }
+
+    async fn run_plaintext<S: AsyncWrite + AsyncRead + Unpin>(
+        &mut self,
+        mut connection: Connection<S>,
+    ) -> Result<()> {
+        while !self.shutdown.is_shutdown() {
+            let maybe_line = tokio::select! {
+                line = connection.read_line() => line?,
+                _ = self.shutdown.recv() => return Ok(()),
+            };
+            // The peer closed the socket.
+            let Some(line) = maybe_line else { return Ok(()) };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match Metric::try_create(&line, &self.templates, current_time_millis()) {
+                Ok(metric) => self.write(vec![metric]).await,
+                Err(e) => warn!("Invalid Graphite line: {}, error: {}", line, e),
+            }
+        }
+        Ok(())
+    }
+
+    async fn run_pickle<S: AsyncRead + Unpin>(&mut self, mut reader: BufReader<S>) -> Result<()> {
+        while !self.shutdown.is_shutdown() {
+            let mut header = [0u8; 4];
+            let result = tokio::select! {
+                result = reader.read_exact(&mut header) => result,
+                _ = self.shutdown.recv() => return Ok(()),
+            };
+            if let Err(e) = result {
+                // The peer closed the socket.
+                if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    return Ok(());
+                }
+                return Err(e.into());
+            }
+
+            let len = u32::from_be_bytes(header) as usize;
+            if len > MAX_PICKLE_PAYLOAD_SIZE {
+                warn!(
+                    "Graphite pickle payload of {} bytes exceeds the limit, closing connection",
+                    len
+                );
+                return Ok(());
+            }
+            let mut payload = vec![0u8; len];
+            let _ = reader.read_exact(&mut payload).await?;
+
+            match pickle::decode(&payload, &self.templates, current_time_millis()) {
+                Ok(metrics) => self.write(metrics).await,
+                Err(e) => warn!("Invalid Graphite pickle payload, error: {}", e),
+            }
+        }
+        Ok(())
+    }
+
+    async fn write(&self, metrics: Vec<Metric>) {
+        if metrics.is_empty() {
+            return;
+        }
+        let _timer = timer!(crate::metrics::METRIC_TCP_GRAPHITE_WRITE_ELAPSED);
+        if let Err(e) = self.query_handler.exec(&metrics, QueryContext::arc()).await {
+            warn!("Failed to write Graphite metrics, error: {}", e);
+        }
+    }
+}
@@ -0,0 +1,514 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A minimal unpickler for the Graphite pickle protocol.
+//!
+//! A pickle payload is a list of `(path, (timestamp, value))` tuples, framed by
+//! a 4 bytes big-endian length header. Only the opcodes that Python emits for
+//! such lists of tuples, strings and numbers are supported.
+//!
+//! Memoized values are copied on every use, so a small payload referencing
+//! them repeatedly could decode into a huge value. The values decoded are
+//! bounded by the size of the payload and their nesting depth is bounded too.
+
+use std::collections::HashMap;
+
+use crate::error::{self, Result};
+use crate::graphite::codec::Metric;
+use crate::graphite::template::Templates;
+
+/// Max number of values decoded, including the copies of memoized values, per
+/// byte of payload. A Graphite payload decodes to far fewer values.
+const MAX_VALUES_PER_BYTE: usize = 4;
+
+/// Max nesting depth of values, a Graphite payload is a list of nested tuples.
+const MAX_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+enum PickleValue {
+    None,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    List(Vec<PickleValue>),
+    Tuple(Vec<PickleValue>),
+}
+
+impl PickleValue {
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            PickleValue::Int(v) => Some(*v as f64),
+            PickleValue::Float(v) => Some(*v),
+            PickleValue::String(v) => v.parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn children(&self) -> &[PickleValue] {
+        match self {
+            PickleValue::List(values) | PickleValue::Tuple(values) => values,
+            _ => &[],
+        }
+    }
+
+    /// Returns the number of values, including the nested ones.
+    fn size(&self) -> usize {
+        1 + self.children().iter().map(|v| v.size()).sum::<usize>()
+    }
+
+    fn depth(&self) -> usize {
+        1 + self
+            .children()
+            .iter()
+            .map(|v| v.depth())
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+enum StackItem {
+    Mark,
+    Value(PickleValue),
+}
+
+struct Unpickler<'a> {
+    data: &'a [u8],
+    pos: usize,
+    stack: Vec<StackItem>,
+    memo: HashMap<u32, PickleValue>,
+    /// Number of values decoded so far, see [MAX_VALUES_PER_BYTE].
+    decoded: usize,
+}
+
+fn invalid<T>(reason: impl Into<String>) -> Result<T> {
+    error::InvalidQuerySnafu {
+        reason: format!("invalid Graphite pickle data, {}", reason.into()),
+    }
+    .fail()
+}
+
+impl<'a> Unpickler<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            stack: vec![],
+            memo: HashMap::new(),
+            decoded: 0,
+        }
+    }
+
+    /// Counts `size` values as decoded, fails if too many values are decoded.
+    fn charge(&mut self, size: usize) -> Result<()> {
+        self.decoded += size;
+        if self.decoded > self.data.len() * MAX_VALUES_PER_BYTE {
+            return invalid("too many values");
+        }
+        Ok(())
+    }
+
+    fn read(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.pos + n > self.data.len() {
+            return invalid("unexpected end of data");
+        }
+        let bytes = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read(1)?[0])
+    }
+
+    fn read_u32_le(&mut self) -> Result<u32> {
+        let bytes = self.read(4)?;
+        // Safety: read returns exactly 4 bytes.
+        Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn read_line(&mut self) -> Result<&'a str> {
+        let rest = &self.data[self.pos..];
+        let Some(end) = rest.iter().position(|b| *b == b'\n') else {
+            return invalid("unterminated line");
+        };
+        self.pos += end + 1;
+        std::str::from_utf8(&rest[..end]).or_else(|_| invalid("line is not valid UTF-8"))
+    }
+
+    fn read_string(&mut self, len: usize) -> Result<PickleValue> {
+        let bytes = self.read(len)?;
+        String::from_utf8(bytes.to_vec())
+            .map(PickleValue::String)
+            .or_else(|_| invalid("string is not valid UTF-8"))
+    }
+
+    fn push(&mut self, value: PickleValue) -> Result<()> {
+        if value.depth() > MAX_DEPTH {
+            return invalid("values are nested too deep");
+        }
+        self.charge(value.size())?;
+        self.stack.push(StackItem::Value(value));
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<PickleValue> {
+        match self.stack.pop() {
+            Some(StackItem::Value(value)) => Ok(value),
+            _ => invalid("stack underflow"),
+        }
+    }
+
+    fn top(&mut self) -> Result<&mut PickleValue> {
+        match self.stack.last_mut() {
+            Some(StackItem::Value(value)) => Ok(value),
+            _ => invalid("stack underflow"),
+        }
+    }
+
+    /// Pops all values above the topmost mark, and the mark itself.
+    fn pop_mark(&mut self) -> Result<Vec<PickleValue>> {
+        let Some(idx) = self
+            .stack
+            .iter()
+            .rposition(|item| matches!(item, StackItem::Mark))
+        else {
+            return invalid("mark not found");
+        };
+        let values = self
+            .stack
+            .split_off(idx + 1)
+            .into_iter()
+            .filter_map(|item| match item {
+                StackItem::Value(value) => Some(value),
+                StackItem::Mark => None,
+            })
+            .collect();
+        let _ = self.stack.pop();
+        Ok(values)
+    }
+
+    fn append(&mut self, mut values: Vec<PickleValue>) -> Result<()> {
+        if values.iter().any(|v| v.depth() >= MAX_DEPTH) {
+            return invalid("values are nested too deep");
+        }
+        match self.top()? {
+            PickleValue::List(list) => {
+                list.append(&mut values);
+                Ok(())
+            }
+            _ => invalid("append to non-list"),
+        }
+    }
+
+    fn memoize(&mut self, key: u32) -> Result<()> {
+        let value = self.top()?.clone();
+        self.charge(value.size())?;
+        let _ = self.memo.insert(key, value);
+        Ok(())
+    }
+
+    fn get(&mut self, key: u32) -> Result<()> {
+        match self.memo.get(&key) {
+            Some(value) => {
+                let value = value.clone();
+                self.push(value)
+            }
+            None => invalid(format!("memo key {key} not found")),
+        }
+    }
+
+    fn load(mut self) -> Result<PickleValue> {
+        loop {
+            let opcode = self.read_u8()?;
+            match opcode {
+                // PROTO
+                0x80 => {
+                    let _ = self.read_u8()?;
+                }
+                // FRAME
+                0x95 => {
+                    let _ = self.read(8)?;
+                }
+                // STOP
+                b'.' => return self.pop(),
+                b'(' => self.stack.push(StackItem::Mark),
+                b'N' => self.push(PickleValue::None)?,
+                0x88 => self.push(PickleValue::Bool(true))?,
+                0x89 => self.push(PickleValue::Bool(false))?,
+                // BININT, BININT1, BININT2
+                b'J' => {
+                    let v = self.read_u32_le()? as i32;
+                    self.push(PickleValue::Int(v as i64))?;
+                }
+                b'K' => {
+                    let v = self.read_u8()?;
+                    self.push(PickleValue::Int(v as i64))?;
+                }
+                b'M' => {
+                    let bytes = self.read(2)?;
+                    self.push(PickleValue::Int(
+                        u16::from_le_bytes([bytes[0], bytes[1]]) as i64
+                    ))?;
+                }
+                // LONG1
+                0x8a => {
+                    let len = self.read_u8()? as usize;
+                    if len > 8 {
+                        return invalid("long integer overflow");
+                    }
+                    let bytes = self.read(len)?;
+                    let mut v: i64 = 0;
+                    for (i, b) in bytes.iter().enumerate() {
+                        v |= (*b as i64) << (8 * i);
+                    }
+                    // Sign extends negative numbers.
+                    if len > 0 && len < 8 && bytes[len - 1] & 0x80 != 0 {
+                        v -= 1 << (8 * len);
+                    }
+                    self.push(PickleValue::Int(v))?;
+                }
+                // INT, LONG
+                b'I' | b'L' => {
+                    let line = self.read_line()?.trim_end_matches('L');
+                    let value = match line {
+                        "00" => PickleValue::Bool(false),
+                        "01" => PickleValue::Bool(true),
+                        _ => match line.parse() {
+                            Ok(v) => PickleValue::Int(v),
+                            Err(_) => return invalid(format!("invalid integer: {line}")),
+                        },
+                    };
+                    self.push(value)?;
+                }
+                // FLOAT
+                b'F' => {
+                    let line = self.read_line()?;
+                    match line.parse() {
+                        Ok(v) => self.push(PickleValue::Float(v))?,
+                        Err(_) => return invalid(format!("invalid float: {line}")),
+                    }
+                }
+                // BINFLOAT
+                b'G' => {
+                    let bytes = self.read(8)?;
+                    // Safety: read returns exactly 8 bytes.
+                    self.push(PickleValue::Float(f64::from_be_bytes(
+                        bytes.try_into().unwrap(),
+                    )))?;
+                }
+                // SHORT_BINUNICODE, SHORT_BINSTRING, SHORT_BINBYTES
+                0x8c | b'U' | b'C' => {
+                    let len = self.read_u8()? as usize;
+                    let value = self.read_string(len)?;
+                    self.push(value)?;
+                }
+                // BINUNICODE, BINSTRING, BINBYTES
+                b'X' | b'T' | b'B' => {
+                    let len = self.read_u32_le()? as usize;
+                    let value = self.read_string(len)?;
+                    self.push(value)?;
+                }
+                // STRING
+                b'S' => {
+                    let line = self.read_line()?;
+                    let value = line
+                        .strip_prefix('\'')
+                        .and_then(|s| s.strip_suffix('\''))
+                        .or_else(|| line.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+                    match value {
+                        Some(v) => self.push(PickleValue::String(v.to_string()))?,
+                        None => return invalid(format!("invalid string: {line}")),
+                    }
+                }
+                // UNICODE
+                b'V' => {
+                    let line = self.read_line()?;
+                    self.push(PickleValue::String(line.to_string()))?;
+                }
+                b']' => self.push(PickleValue::List(vec![]))?,
+                b'l' => {
+                    let values = self.pop_mark()?;
+                    self.push(PickleValue::List(values))?;
+                }
+                b'a' => {
+                    let value = self.pop()?;
+                    self.append(vec![value])?;
+                }
+                b'e' => {
+                    let values = self.pop_mark()?;
+                    self.append(values)?;
+                }
+                b')' => self.push(PickleValue::Tuple(vec![]))?,
+                b't' => {
+                    let values = self.pop_mark()?;
+                    self.push(PickleValue::Tuple(values))?;
+                }
+                // TUPLE1, TUPLE2, TUPLE3
+                0x85..=0x87 => {
+                    let n = (opcode - 0x84) as usize;
+                    let mut values = Vec::with_capacity(n);
+                    for _ in 0..n {
+                        values.push(self.pop()?);
+                    }
+                    values.reverse();
+                    self.push(PickleValue::Tuple(values))?;
+                }
+                // MEMOIZE
+                0x94 => {
+                    let key = self.memo.len() as u32;
+                    self.memoize(key)?;
+                }
+                // BINPUT, LONG_BINPUT, PUT
+                b'q' => {
+                    let key = self.read_u8()? as u32;
+                    self.memoize(key)?;
+                }
+                b'r' => {
+                    let key = self.read_u32_le()?;
+                    self.memoize(key)?;
+                }
+                b'p' => {
+                    let line = self.read_line()?;
+                    match line.parse() {
+                        Ok(key) => self.memoize(key)?,
+                        Err(_) => return invalid(format!("invalid memo key: {line}")),
+                    }
+                }
+                // BINGET, LONG_BINGET, GET
+                b'h' => {
+                    let key = self.read_u8()? as u32;
+                    self.get(key)?;
+                }
+                b'j' => {
+                    let key = self.read_u32_le()?;
+                    self.get(key)?;
+                }
+                b'g' => {
+                    let line = self.read_line()?;
+                    match line.parse() {
+                        Ok(key) => self.get(key)?,
+                        Err(_) => return invalid(format!("invalid memo key: {line}")),
+                    }
+                }
+                _ => return invalid(format!("unsupported opcode: {opcode:#x}")),
+            }
+        }
+    }
+}
+
+/// Decodes a pickle payload (without the length header) into metrics.
+pub fn decode(data: &[u8], templates: &Templates, now_millis: i64) -> Result<Vec<Metric>> {
+    let PickleValue::List(items) = Unpickler::new(data).load()? else {
+        return invalid("expect a list of metrics");
+    };
+
+    items
+        .into_iter()
+        .map(|item| {
+            let (path, ts, value) = match item {
+                PickleValue::Tuple(mut metric) if metric.len() == 2 => {
+                    let datapoint = metric.pop();
+                    let path = metric.pop();
+                    match (path, datapoint) {
+                        (Some(PickleValue::String(path)), Some(PickleValue::Tuple(dp)))
+                            if dp.len() == 2 =>
+                        {
+                            (path, dp[0].as_f64(), dp[1].as_f64())
+                        }
+                        _ => return invalid("expect (path, (timestamp, value))"),
+                    }
+                }
+                _ => return invalid("expect (path, (timestamp, value))"),
+            };
+            let (Some(ts), Some(value)) = (ts, value) else {
+                return invalid(format!("invalid data point of {path}"));
+            };
+            let ts_millis = if ts < 0.0 {
+                now_millis
+            } else {
+                (ts * 1000.0) as i64
+            };
+            Metric::from_path(&path, value, ts_millis, templates)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        // pickle.dumps([("servers.web01.cpu", (1346846400, 98.5)), ("servers.web02.cpu", (1346846400.5, 1))], protocol=2)
+        let data: &[u8] = b"\x80\x02]q\x00(X\x11\x00\x00\x00servers.web01.cpuq\x01J\xc0>GPG@X\
+            \xa0\x00\x00\x00\x00\x00\x86q\x02\x86q\x03X\x11\x00\x00\x00servers.web02.cpuq\x04G\
+            A\xd4\x11\xcf\xb0 \x00\x00K\x01\x86q\x05\x86q\x06e.";
+        let metrics = decode(data, &Templates::default(), 0).unwrap();
+        assert_eq!(
+            vec![
+                Metric::new(
+                    "servers.web01.cpu".to_string(),
+                    1346846400000,
+                    vec![("value".to_string(), 98.5)],
+                    vec![],
+                ),
+                Metric::new(
+                    "servers.web02.cpu".to_string(),
+                    1346846400500,
+                    vec![("value".to_string(), 1.0)],
+                    vec![],
+                ),
+            ],
+            metrics
+        );
+
+        // pickle.dumps([("cpu", (-1, 1))], protocol=0) and protocol=4
+        let expected = vec![Metric::new(
+            "cpu".to_string(),
+            1000,
+            vec![("value".to_string(), 1.0)],
+            vec![],
+        )];
+        let data: &[u8] = b"(lp0\n(Vcpu\np1\n(I-1\nI1\ntp2\ntp3\na.";
+        assert_eq!(expected, decode(data, &Templates::default(), 1000).unwrap());
+        let data: &[u8] = b"\x80\x04\x95\x15\x00\x00\x00\x00\x00\x00\x00]\x94\x8c\x03cpu\x94J\xff\xff\xff\xffK\x01\x86\x94\x86\x94a.";
+        assert_eq!(expected, decode(data, &Templates::default(), 1000).unwrap());
+
+        assert!(decode(b"", &Templates::default(), 0).is_err());
+        assert!(decode(b"\x80\x02K\x01.", &Templates::default(), 0).is_err());
+        assert!(decode(b"\x80\x02]q\x00K\x01a.", &Templates::default(), 0).is_err());
+    }
+
+    #[test]
+    fn test_decode_self_referencing_memo() {
+        // Each step builds a list of two copies of the memoized value and
+        // memoizes it again, which doubles the decoded size:
+        // `]q0` + `(h0h0lq0` * 20.
+        let mut data = b"\x80\x02]q\x00".to_vec();
+        for _ in 0..20 {
+            data.extend_from_slice(b"(h\x00h\x00lq\x00");
+        }
+        data.push(b'.');
+        let err = decode(&data, &Templates::default(), 0).unwrap_err();
+        assert!(err.to_string().contains("too many values"), "{err}");
+
+        // Wraps `None` in 20 nested tuples.
+        let mut data = b"\x80\x02N".to_vec();
+        data.extend_from_slice(&[0x85; 20]);
+        data.push(b'.');
+        let err = decode(&data, &Templates::default(), 0).unwrap_err();
+        assert!(err.to_string().contains("nested too deep"), "{err}");
+    }
+}
@@ -0,0 +1,338 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Templates that extract the measurement, field and tags from a dot separated
+//! Graphite (or StatsD) metric path, in the same syntax as InfluxDB:
+//!
+//! ```text
+//! [filter] <template> [tag1=value1,tag2=value2]
+//! ```
+//!
+//! For example, `servers.*.cpu.* .host.measurement.field` turns the path
+//! `servers.web01.cpu.idle` into measurement `cpu`, field `idle` and tag `host=web01`.
+//!
+//! Each part of a template is one of `measurement`, `field`, a tag name or
+//! empty to skip the part; `measurement*` and `field*` consume all remaining
+//! parts. Templates are tried in the configured order, the first one whose
+//! filter matches the path is applied. A template without filter matches any
+//! path. If nothing matches, the whole path becomes the measurement.
+
+use snafu::ensure;
+
+use crate::error::{self, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplatePart {
+    Skip,
+    Measurement,
+    MeasurementGreedy,
+    Field,
+    FieldGreedy,
+    Tag(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Template {
+    /// Glob patterns that match the leading parts of a path.
+    filter: Option<Vec<String>>,
+    parts: Vec<TemplatePart>,
+    default_tags: Vec<(String, String)>,
+}
+
+impl Template {
+    fn try_new(spec: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            error::InvalidGraphiteTemplateSnafu {
+                template: spec,
+                reason,
+            }
+            .build()
+        };
+
+        let tokens = spec.split_whitespace().collect::<Vec<_>>();
+        let (filter, template, tags) = match tokens.as_slice() {
+            [template] => (None, *template, None),
+            [template, tags] if tags.contains('=') => (None, *template, Some(*tags)),
+            [filter, template] => (Some(*filter), *template, None),
+            [filter, template, tags] => (Some(*filter), *template, Some(*tags)),
+            _ => return Err(invalid("expect '[filter] <template> [tags]'")),
+        };
+
+        let parts = template
+            .split('.')
+            .map(|part| match part {
+                "" => TemplatePart::Skip,
+                "measurement" => TemplatePart::Measurement,
+                "measurement*" => TemplatePart::MeasurementGreedy,
+                "field" => TemplatePart::Field,
+                "field*" => TemplatePart::FieldGreedy,
+                tag => TemplatePart::Tag(tag.to_string()),
+            })
+            .collect::<Vec<_>>();
+        ensure!(
+            parts.iter().any(|p| matches!(
+                p,
+                TemplatePart::Measurement | TemplatePart::MeasurementGreedy
+            )),
+            error::InvalidGraphiteTemplateSnafu {
+                template: spec,
+                reason: "no measurement in template",
+            }
+        );
+        ensure!(
+            !(parts.contains(&TemplatePart::MeasurementGreedy)
+                && parts.contains(&TemplatePart::FieldGreedy)),
+            error::InvalidGraphiteTemplateSnafu {
+                template: spec,
+                reason: "either 'measurement*' or 'field*' can be used in a template",
+            }
+        );
+
+        let default_tags = match tags {
+            Some(tags) => tags
+                .split(',')
+                .map(|kv| {
+                    kv.split_once('=')
+                        .filter(|(k, v)| !k.is_empty() && !v.is_empty())
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .ok_or_else(|| invalid("invalid default tags"))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => vec![],
+        };
+
+        Ok(Template {
+            filter: filter.map(|f| f.split('.').map(|s| s.to_string()).collect()),
+            parts,
+            default_tags,
+        })
+    }
+
+    fn matches(&self, path: &[&str]) -> bool {
+        match &self.filter {
+            Some(filter) => {
+                filter.len() <= path.len()
+                    && filter
+                        .iter()
+                        .zip(path.iter())
+                        .all(|(pattern, part)| glob_match(pattern, part))
+            }
+            None => true,
+        }
+    }
+
+    fn apply(&self, path: &[&str], separator: &str) -> ParsedPath {
+        let mut measurement = vec![];
+        let mut field = vec![];
+        let mut tags: Vec<(String, Vec<&str>)> = vec![];
+
+        for (i, part) in self.parts.iter().enumerate() {
+            let Some(value) = path.get(i) else { break };
+            match part {
+                TemplatePart::Skip => {}
+                TemplatePart::Measurement => measurement.push(*value),
+                TemplatePart::MeasurementGreedy => {
+                    measurement.extend_from_slice(&path[i..]);
+                    break;
+                }
+                TemplatePart::Field => field.push(*value),
+                TemplatePart::FieldGreedy => {
+                    field.extend_from_slice(&path[i..]);
+                    break;
+                }
+                TemplatePart::Tag(name) => match tags.iter_mut().find(|(k, _)| k == name) {
+                    Some((_, values)) => values.push(value),
+                    None => tags.push((name.clone(), vec![value])),
+                },
+            }
+        }
+
+        let mut tags = tags
+            .into_iter()
+            .map(|(k, v)| (k, v.join(separator)))
+            .collect::<Vec<_>>();
+        for (k, v) in &self.default_tags {
+            if !tags.iter().any(|(name, _)| name == k) {
+                tags.push((k.clone(), v.clone()));
+            }
+        }
+
+        ParsedPath {
+            measurement: if measurement.is_empty() {
+                path.join(separator)
+            } else {
+                measurement.join(separator)
+            },
+            field: (!field.is_empty()).then(|| field.join(separator)),
+            tags,
+        }
+    }
+}
+
+/// Matches `text` against a pattern where `*` matches any characters.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut segments = pattern.split('*');
+    // Safety: split always returns at least one segment.
+    let first = segments.next().unwrap();
+    let Some(mut rest) = text.strip_prefix(first) else { return false };
+
+    let segments = segments.collect::<Vec<_>>();
+    let Some((last, middle)) = segments.split_last() else {
+        return rest.is_empty();
+    };
+    for segment in middle {
+        match rest.find(segment) {
+            Some(idx) => rest = &rest[idx + segment.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// The measurement, field and tags extracted from a metric path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedPath {
+    pub measurement: String,
+    /// The field name if the template has, otherwise the default field should be used.
+    pub field: Option<String>,
+    pub tags: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Templates {
+    separator: String,
+    templates: Vec<Template>,
+}
+
+impl Templates {
+    /// Creates templates from configurations. `separator` joins multiple parts
+    /// of a measurement, field or tag.
+    pub fn try_new(templates: &[String], separator: &str) -> Result<Self> {
+        let templates = templates
+            .iter()
+            .map(|t| Template::try_new(t))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            separator: separator.to_string(),
+            templates,
+        })
+    }
+
+    pub fn apply(&self, path: &str) -> ParsedPath {
+        let parts = path.split('.').collect::<Vec<_>>();
+        match self.templates.iter().find(|t| t.matches(&parts)) {
+            Some(template) => template.apply(&parts, &self.separator),
+            None => ParsedPath {
+                measurement: path.to_string(),
+                field: None,
+                tags: vec![],
+            },
+        }
+    }
+}
+
+impl Default for Templates {
+    fn default() -> Self {
+        Self {
+            separator: ".".to_string(),
+            templates: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn templates(specs: &[&str], separator: &str) -> Templates {
+        let specs = specs.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        Templates::try_new(&specs, separator).unwrap()
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*", "cpu"));
+        assert!(glob_match("cpu", "cpu"));
+        assert!(glob_match("cp*", "cpu"));
+        assert!(glob_match("*u", "cpu"));
+        assert!(glob_match("c*u", "cpu"));
+        assert!(glob_match("c*p*", "cpu"));
+        assert!(!glob_match("cpu", "cpu0"));
+        assert!(!glob_match("m*", "cpu"));
+        assert!(!glob_match("c*x", "cpu"));
+    }
+
+    #[test]
+    fn test_apply_templates() {
+        let templates = templates(
+            &[
+                "servers.*.cpu.* .host.measurement.field",
+                "stats.* .measurement.field* region=us-west",
+                "host.measurement*",
+            ],
+            "_",
+        );
+
+        assert_eq!(
+            ParsedPath {
+                measurement: "cpu".to_string(),
+                field: Some("idle".to_string()),
+                tags: vec![("host".to_string(), "web01".to_string())],
+            },
+            templates.apply("servers.web01.cpu.idle")
+        );
+        assert_eq!(
+            ParsedPath {
+                measurement: "mem".to_string(),
+                field: Some("used_bytes".to_string()),
+                tags: vec![("region".to_string(), "us-west".to_string())],
+            },
+            templates.apply("stats.mem.used.bytes")
+        );
+        assert_eq!(
+            ParsedPath {
+                measurement: "disk_sda_reads".to_string(),
+                field: None,
+                tags: vec![("host".to_string(), "web02".to_string())],
+            },
+            templates.apply("web02.disk.sda.reads")
+        );
+
+        let templates = Templates::default();
+        assert_eq!(
+            ParsedPath {
+                measurement: "servers.web01.cpu".to_string(),
+                field: None,
+                tags: vec![],
+            },
+            templates.apply("servers.web01.cpu")
+        );
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        for spec in [
+            "host.field",
+            "measurement*.field*",
+            "a b c d",
+            "measurement.field region",
+            "*.cpu measurement tag=",
+        ] {
+            assert!(
+                Templates::try_new(&[spec.to_string()], ".").is_err(),
+                "{spec}"
+            );
+        }
+    }
+}
@@ -26,6 +26,7 @@ use api::v1::prometheus_gateway_server::{PrometheusGateway, PrometheusGatewaySer
 use api::v1::{HealthCheckRequest, HealthCheckResponse};
 use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
 use async_trait::async_trait;
+use common_grpc::tracing_context::TracingContextLayer;
 use common_runtime::Runtime;
 use common_telemetry::logging::info;
 use common_telemetry::{error, warn};
@@ -175,6 +176,7 @@ impl Server for GrpcServer {
             .context(GrpcReflectionServiceSnafu)?;
 
         let mut builder = tonic::transport::Server::builder()
+            .layer(TracingContextLayer)
             .add_service(self.create_flight_service())
             .add_service(self.create_database_service())
             .add_service(self.create_healthcheck_service());
@@ -41,8 +41,9 @@ impl GreptimeDatabase for DatabaseService {
         &self,
         request: Request<GreptimeRequest>,
     ) -> TonicResult<Response<GreptimeResponse>> {
+        let client_addr = request.remote_addr();
         let request = request.into_inner();
-        let output = self.handler.handle_request(request).await?;
+        let output = self.handler.handle_request(request, client_addr).await?;
         let response = match output {
             Output::AffectedRows(rows) => GreptimeResponse {
                 header: None,
@@ -61,10 +62,11 @@ impl GreptimeDatabase for DatabaseService {
     ) -> Result<Response<GreptimeResponse>, Status> {
         let mut affected_rows = 0;
 
+        let client_addr = request.remote_addr();
         let mut stream = request.into_inner();
         while let Some(request) = stream.next().await {
             let request = request?;
-            let output = self.handler.handle_request(request).await?;
+            let output = self.handler.handle_request(request, client_addr).await?;
             match output {
                 Output::AffectedRows(rows) => affected_rows += rows,
                 Output::Stream(_) | Output::RecordBatches(_) => {
@@ -85,11 +85,12 @@ impl FlightService for FlightHandler {
     type DoGetStream = TonicStream<FlightData>;
 
     async fn do_get(&self, request: Request<Ticket>) -> TonicResult<Response<Self::DoGetStream>> {
+        let client_addr = request.remote_addr();
         let ticket = request.into_inner().ticket;
         let request =
             GreptimeRequest::decode(ticket.as_ref()).context(error::InvalidFlightTicketSnafu)?;
 
-        let output = self.handler.handle_request(request).await?;
+        let output = self.handler.handle_request(request, client_addr).await?;
 
         let stream = to_flight_data_stream(output);
         Ok(Response::new(stream))
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -24,7 +25,7 @@ use common_query::Output;
 use common_runtime::Runtime;
 use common_telemetry::logging;
 use metrics::{histogram, increment_counter};
-use session::context::{QueryContext, QueryContextRef};
+use session::context::{Channel, ConnInfo, QueryContext, QueryContextRef};
 use snafu::{OptionExt, ResultExt};
 use tonic::Status;
 
@@ -57,13 +58,18 @@ impl GreptimeRequestHandler {
         }
     }
 
-    pub(crate) async fn handle_request(&self, request: GreptimeRequest) -> TonicResult<Output> {
+    pub(crate) async fn handle_request(
+        &self,
+        request: GreptimeRequest,
+        client_addr: Option<SocketAddr>,
+    ) -> TonicResult<Output> {
         let query = request.request.context(InvalidQuerySnafu {
             reason: "Expecting non-empty GreptimeRequest.",
         })?;
 
         let header = request.header.as_ref();
         let query_ctx = create_query_context(header);
+        query_ctx.set_conn_info(ConnInfo::new(client_addr, Channel::Grpc));
 
         self.auth(header, &query_ctx).await?;
 
@@ -114,7 +120,7 @@ impl GreptimeRequestHandler {
             })
             .context(NotFoundAuthHeaderSnafu)?;
 
-        let _ = match auth_scheme {
+        let user_info = match auth_scheme {
             AuthScheme::Basic(Basic { username, password }) => user_provider
                 .auth(
                     Identity::UserId(&username, None),
@@ -135,6 +141,7 @@ impl GreptimeRequestHandler {
             );
             Status::unauthenticated(e.to_string())
         })?;
+        query_ctx.set_current_user(user_info);
         Ok(())
     }
 }
@@ -41,6 +41,7 @@ use axum::response::{Html, IntoResponse, Json};
 use axum::{routing, BoxError, Extension, Router};
 use common_error::prelude::ErrorExt;
 use common_error::status_code::StatusCode;
+use common_grpc::tracing_context::TracingContextLayer;
 use common_query::Output;
 use common_recordbatch::{util, RecordBatch};
 use common_telemetry::logging::{self, info};
@@ -64,7 +65,9 @@ use warp::filters::body::BodyDeserializeError;
 use warp::http::StatusCode;
 
 use self::authorize::HttpAuth;
-use self::influxdb::{influxdb_health, influxdb_ping, influxdb_write_v1, influxdb_write_v2};
+use self::influxdb::{
+    influxdb_health, influxdb_ping, influxdb_query, influxdb_write_v1, influxdb_write_v2,
+};
 use crate::auth::UserProviderRef;
 use crate::configurator::ConfiguratorRef;
 use crate::error::{AlreadyStartedSnafu, Result, StartHttpSnafu};
@@ -554,6 +557,7 @@ impl HttpServer {
             .layer(
                 ServiceBuilder::new()
                     .layer(HandleErrorLayer::new(handle_error))
+                    .layer(TracingContextLayer)
                     .layer(TraceLayer::new_for_http())
                     .layer(TimeoutLayer::new(self.options.timeout))
                     // custom layer
@@ -612,6 +616,7 @@ impl HttpServer {
         Router::new()
             .route("/write", routing::post(influxdb_write_v1))
             .route("/api/v2/write", routing::post(influxdb_write_v2))
+            .route("/query", routing::get(influxdb_query).post(influxdb_query))
             .route("/ping", routing::get(influxdb_ping))
             .route("/health", routing::get(influxdb_health))
             .with_state(influxdb_handler)
@@ -620,6 +625,8 @@ impl HttpServer {
     fn route_opentsdb<S>(&self, opentsdb_handler: OpentsdbProtocolHandlerRef) -> Router<S> {
         Router::new()
             .route("/api/put", routing::post(opentsdb::put))
+            .route("/api/query", routing::get(opentsdb::query).post(opentsdb::query))
+            .route("/api/suggest", routing::get(opentsdb::suggest).post(opentsdb::suggest))
             .with_state(opentsdb_handler)
     }
 
@@ -689,7 +696,8 @@ impl Server for HttpServer {
                 app = configurator.config_http(app);
             }
             let app = self.build(app);
-            let server = axum::Server::bind(&listening).serve(app.into_make_service());
+            let server = axum::Server::bind(&listening)
+                .serve(app.into_make_service_with_connect_info::<SocketAddr>());
 
             *shutdown_tx = Some(tx);
 
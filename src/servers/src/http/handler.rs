@@ -14,26 +14,30 @@
 
 use std::collections::HashMap;
 use std::env;
+use std::net::SocketAddr;
 use std::time::Instant;
 
 use aide::transform::TransformOperation;
-use axum::extract::{Json, Query, State};
+use axum::extract::{ConnectInfo, Json, Query, State};
 use axum::{Extension, Form};
 use common_error::status_code::StatusCode;
 use common_telemetry::{error, timer};
 use query::parser::PromQuery;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use session::context::UserInfo;
+use session::context::{Channel, ConnInfo, QueryContextRef, ReadPreference, UserInfo};
 
 use crate::http::{ApiState, JsonResponse};
 use crate::metrics::{JEMALLOC_COLLECTOR, PROCESS_COLLECTOR};
 use crate::metrics_handler::MetricsHandler;
+use crate::query_handler::sql::ServerSqlQueryHandlerRef;
 
 #[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
 pub struct SqlQuery {
     pub db: Option<String>,
     pub sql: Option<String>,
+    /// Replicas to read from, either `leader` or `follower`.
+    pub read_preference: Option<String>,
 }
 
 /// Handler to execute sql
@@ -41,8 +45,8 @@ pub struct SqlQuery {
 pub async fn sql(
     State(state): State<ApiState>,
     Query(query_params): Query<SqlQuery>,
-    // TODO(fys): pass _user_info into query context
-    _user_info: Extension<UserInfo>,
+    Extension(user_info): Extension<UserInfo>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
     Form(form_params): Form<SqlQuery>,
 ) -> Json<JsonResponse> {
     let sql_handler = &state.sql_handler;
@@ -50,6 +54,7 @@ pub async fn sql(
     let start = Instant::now();
     let sql = query_params.sql.or(form_params.sql);
     let db = query_params.db.or(form_params.db);
+    let read_preference = query_params.read_preference.or(form_params.read_preference);
     let _timer = timer!(
         crate::metrics::METRIC_HTTP_SQL_ELAPSED,
         &[(
@@ -59,8 +64,9 @@ pub async fn sql(
     );
 
     let resp = if let Some(sql) = &sql {
-        match crate::http::query_context_from_db(sql_handler.clone(), db).await {
+        match query_context(sql_handler, db, read_preference.as_deref()).await {
             Ok(query_ctx) => {
+                set_connection(&query_ctx, user_info, connect_info);
                 JsonResponse::from_output(sql_handler.do_query(sql, query_ctx).await).await
             }
             Err(resp) => resp,
@@ -82,6 +88,8 @@ pub struct PromqlQuery {
     pub end: String,
     pub step: String,
     pub db: Option<String>,
+    /// Replicas to read from, either `leader` or `follower`.
+    pub read_preference: Option<String>,
 }
 
 impl From<PromqlQuery> for PromQuery {
@@ -100,12 +108,13 @@ impl From<PromqlQuery> for PromQuery {
 pub async fn promql(
     State(state): State<ApiState>,
     Query(params): Query<PromqlQuery>,
-    // TODO(fys): pass _user_info into query context
-    _user_info: Extension<UserInfo>,
+    Extension(user_info): Extension<UserInfo>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
 ) -> Json<JsonResponse> {
     let sql_handler = &state.sql_handler;
     let exec_start = Instant::now();
     let db = params.db.clone();
+    let read_preference = params.read_preference.clone();
     let _timer = timer!(
         crate::metrics::METRIC_HTTP_PROMQL_ELAPSED,
         &[(
@@ -115,8 +124,9 @@ pub async fn promql(
     );
 
     let prom_query = params.into();
-    let resp = match super::query_context_from_db(sql_handler.clone(), db).await {
+    let resp = match query_context(sql_handler, db, read_preference.as_deref()).await {
         Ok(query_ctx) => {
+            set_connection(&query_ctx, user_info, connect_info);
             JsonResponse::from_output(sql_handler.do_promql_query(&prom_query, query_ctx).await)
                 .await
         }
@@ -126,6 +136,34 @@ pub async fn promql(
     Json(resp.with_execution_time(exec_start.elapsed().as_millis()))
 }
 
+/// Creates the query context of the database, with the read preference hint of the request.
+async fn query_context(
+    sql_handler: &ServerSqlQueryHandlerRef,
+    db: Option<String>,
+    read_preference: Option<&str>,
+) -> std::result::Result<QueryContextRef, JsonResponse> {
+    let read_preference = match read_preference {
+        Some(read_preference) => read_preference
+            .parse::<ReadPreference>()
+            .map_err(|e| JsonResponse::with_error(e, StatusCode::InvalidArguments))?,
+        None => ReadPreference::default(),
+    };
+    let query_ctx = crate::http::query_context_from_db(sql_handler.clone(), db).await?;
+    query_ctx.set_read_preference(read_preference);
+    Ok(query_ctx)
+}
+
+/// Sets the user and client of the HTTP request to the query context.
+fn set_connection(
+    query_ctx: &QueryContextRef,
+    user_info: UserInfo,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+) {
+    query_ctx.set_current_user(user_info);
+    let client_addr = connect_info.map(|ConnectInfo(addr)| addr);
+    query_ctx.set_conn_info(ConnInfo::new(client_addr, Channel::Http));
+}
+
 pub(crate) fn sql_docs(op: TransformOperation) -> TransformOperation {
     op.response::<200, Json<JsonResponse>>()
 }
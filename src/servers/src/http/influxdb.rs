@@ -18,12 +18,15 @@ use std::sync::Arc;
 use axum::extract::{Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
+use axum::{Extension, Form, Json};
 use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use common_grpc::writer::Precision;
 use common_telemetry::timer;
-use session::context::QueryContext;
+use session::context::{QueryContext, UserInfo};
+use snafu::OptionExt;
 
-use crate::error::{Result, TimePrecisionSnafu};
+use crate::error::{InvalidQuerySnafu, Result, TimePrecisionSnafu};
+use crate::influxdb::influxql::{self, InfluxqlResponse, InfluxqlStatementResult};
 use crate::influxdb::InfluxdbRequest;
 use crate::parse_catalog_and_schema_from_client_database_name;
 use crate::query_handler::InfluxdbLineProtocolHandlerRef;
@@ -44,6 +47,7 @@ pub async fn influxdb_health() -> Result<impl IntoResponse> {
 pub async fn influxdb_write_v1(
     State(handler): State<InfluxdbLineProtocolHandlerRef>,
     Query(mut params): Query<HashMap<String, String>>,
+    Extension(user_info): Extension<UserInfo>,
     lines: String,
 ) -> Result<impl IntoResponse> {
     let db = params
@@ -55,13 +59,14 @@ pub async fn influxdb_write_v1(
         .map(|val| parse_time_precision(val))
         .transpose()?;
 
-    influxdb_write(&db, precision, lines, handler).await
+    influxdb_write(&db, precision, lines, handler, user_info).await
 }
 
 #[axum_macros::debug_handler]
 pub async fn influxdb_write_v2(
     State(handler): State<InfluxdbLineProtocolHandlerRef>,
     Query(mut params): Query<HashMap<String, String>>,
+    Extension(user_info): Extension<UserInfo>,
     lines: String,
 ) -> Result<impl IntoResponse> {
     let db = params
@@ -73,7 +78,67 @@ pub async fn influxdb_write_v2(
         .map(|val| parse_time_precision(val))
         .transpose()?;
 
-    influxdb_write(&db, precision, lines, handler).await
+    influxdb_write(&db, precision, lines, handler, user_info).await
+}
+
+// https://docs.influxdata.com/influxdb/v1.8/tools/api/#query-http-endpoint
+#[axum_macros::debug_handler]
+pub async fn influxdb_query(
+    State(handler): State<InfluxdbLineProtocolHandlerRef>,
+    Query(mut params): Query<HashMap<String, String>>,
+    Extension(user_info): Extension<UserInfo>,
+    form: Option<Form<HashMap<String, String>>>,
+) -> Result<impl IntoResponse> {
+    // Parameters may also be sent as an url-encoded form body by POST requests.
+    if let Some(Form(form)) = form {
+        params.extend(form);
+    }
+
+    let query = params.remove("q").context(InvalidQuerySnafu {
+        reason: "missing query parameter 'q'",
+    })?;
+    let db = params
+        .remove("db")
+        .unwrap_or_else(|| DEFAULT_SCHEMA_NAME.to_string());
+    let epoch = params
+        .get("epoch")
+        .map(|val| parse_time_precision(val))
+        .transpose()?;
+
+    let _timer = timer!(
+        crate::metrics::METRIC_HTTP_INFLUXDB_QUERY_ELAPSED,
+        &[(crate::metrics::METRIC_DB_LABEL, db.clone())]
+    );
+
+    let (catalog, schema) = parse_catalog_and_schema_from_client_database_name(&db);
+    let ctx = Arc::new(QueryContext::with(catalog, schema));
+    ctx.set_current_user(user_info);
+
+    let statements = influxql::parse(&query)?;
+    let mut results = Vec::with_capacity(statements.len());
+    for (statement_id, stmt) in statements.iter().enumerate() {
+        match handler.query(stmt, ctx.clone()).await {
+            Ok(mut series) => {
+                series.iter_mut().for_each(|s| s.format_time(epoch));
+                results.push(InfluxqlStatementResult {
+                    statement_id,
+                    series,
+                    error: None,
+                });
+            }
+            Err(e) => {
+                // Like InfluxDB, stop executing the remaining statements on error.
+                results.push(InfluxqlStatementResult {
+                    statement_id,
+                    series: vec![],
+                    error: Some(e.to_string()),
+                });
+                break;
+            }
+        }
+    }
+
+    Ok(Json(InfluxqlResponse { results }))
 }
 
 pub async fn influxdb_write(
@@ -81,6 +146,7 @@ pub async fn influxdb_write(
     precision: Option<Precision>,
     lines: String,
     handler: InfluxdbLineProtocolHandlerRef,
+    user_info: UserInfo,
 ) -> Result<impl IntoResponse> {
     let _timer = timer!(
         crate::metrics::METRIC_HTTP_INFLUXDB_WRITE_ELAPSED,
@@ -89,6 +155,7 @@ pub async fn influxdb_write(
 
     let (catalog, schema) = parse_catalog_and_schema_from_client_database_name(db);
     let ctx = Arc::new(QueryContext::with(catalog, schema));
+    ctx.set_current_user(user_info);
 
     let request = InfluxdbRequest { precision, lines };
 
@@ -17,15 +17,19 @@ use std::sync::Arc;
 
 use axum::extract::{Query, RawBody, State};
 use axum::http::StatusCode as HttpStatusCode;
-use axum::Json;
+use axum::{Extension, Json};
 use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use hyper::Body;
 use serde::{Deserialize, Serialize};
-use session::context::QueryContext;
-use snafu::ResultExt;
+use session::context::{QueryContext, UserInfo};
+use snafu::{OptionExt, ResultExt};
 
 use crate::error::{self, Error, Result};
 use crate::opentsdb::codec::DataPoint;
+use crate::opentsdb::query::{
+    parse_metric_query, OpentsdbQueryResult, QueryRequest, SuggestRequest, SuggestType, TimeValue,
+    DEFAULT_SUGGEST_MAX,
+};
 use crate::parse_catalog_and_schema_from_client_database_name;
 use crate::query_handler::OpentsdbProtocolHandlerRef;
 
@@ -80,6 +84,7 @@ pub enum OpentsdbPutResponse {
 pub async fn put(
     State(opentsdb_handler): State<OpentsdbProtocolHandlerRef>,
     Query(params): Query<HashMap<String, String>>,
+    Extension(user_info): Extension<UserInfo>,
     RawBody(body): RawBody,
 ) -> Result<(HttpStatusCode, Json<OpentsdbPutResponse>)> {
     let summary = params.contains_key("summary");
@@ -92,6 +97,7 @@ pub async fn put(
 
     let (catalog, schema) = parse_catalog_and_schema_from_client_database_name(db);
     let ctx = Arc::new(QueryContext::with(catalog, schema));
+    ctx.set_current_user(user_info);
 
     let data_points = parse_data_points(body).await?;
 
@@ -136,6 +142,124 @@ pub async fn put(
     Ok(response)
 }
 
+// Please refer to the OpenTSDB documents of ["api/query"](http://opentsdb.net/docs/build/html/api_http/query/index.html)
+// for more details. Both the JSON body of POST and the `m` parameters of GET are supported.
+#[axum_macros::debug_handler]
+pub async fn query(
+    State(opentsdb_handler): State<OpentsdbProtocolHandlerRef>,
+    Query(params): Query<Vec<(String, String)>>,
+    Extension(user_info): Extension<UserInfo>,
+    RawBody(body): RawBody,
+) -> Result<Json<Vec<OpentsdbQueryResult>>> {
+    let db = params
+        .iter()
+        .find(|(k, _)| k == "db")
+        .map(|(_, v)| v.as_str())
+        .unwrap_or(DEFAULT_SCHEMA_NAME);
+    let (catalog, schema) = parse_catalog_and_schema_from_client_database_name(db);
+    let ctx = Arc::new(QueryContext::with(catalog, schema));
+    ctx.set_current_user(user_info);
+
+    let body = hyper::body::to_bytes(body)
+        .await
+        .context(error::HyperSnafu)?;
+    let request = if body.is_empty() {
+        parse_query_params(&params)?
+    } else {
+        serde_json::from_slice::<QueryRequest>(&body[..])
+            .context(error::InvalidOpentsdbJsonRequestSnafu)?
+    };
+
+    let ms_resolution = request.ms_resolution;
+    let queries = request.into_queries(common_time::util::current_time_millis())?;
+    let mut results = vec![];
+    for q in &queries {
+        results.extend(opentsdb_handler.query(q, ctx.clone()).await?);
+    }
+    if !ms_resolution {
+        results = results
+            .into_iter()
+            .map(OpentsdbQueryResult::into_second_resolution)
+            .collect();
+    }
+    Ok(Json(results))
+}
+
+fn parse_query_params(params: &[(String, String)]) -> Result<QueryRequest> {
+    let mut start = None;
+    let mut end = None;
+    let mut queries = vec![];
+    let mut ms_resolution = false;
+    for (k, v) in params {
+        match k.as_str() {
+            "start" => start = Some(TimeValue::String(v.clone())),
+            "end" => end = Some(TimeValue::String(v.clone())),
+            "m" => queries.push(parse_metric_query(v)?),
+            "ms" | "msResolution" => ms_resolution = v.is_empty() || v == "true",
+            _ => {}
+        }
+    }
+    Ok(QueryRequest {
+        start: start.context(error::InvalidOpentsdbQuerySnafu {
+            reason: "missing start time",
+        })?,
+        end,
+        queries,
+        ms_resolution,
+    })
+}
+
+// Please refer to the OpenTSDB documents of ["api/suggest"](http://opentsdb.net/docs/build/html/api_http/suggest.html)
+// for more details.
+#[axum_macros::debug_handler]
+pub async fn suggest(
+    State(opentsdb_handler): State<OpentsdbProtocolHandlerRef>,
+    Query(params): Query<HashMap<String, String>>,
+    Extension(user_info): Extension<UserInfo>,
+    RawBody(body): RawBody,
+) -> Result<Json<Vec<String>>> {
+    let db = params
+        .get("db")
+        .map(|v| v.as_str())
+        .unwrap_or(DEFAULT_SCHEMA_NAME);
+    let (catalog, schema) = parse_catalog_and_schema_from_client_database_name(db);
+    let ctx = Arc::new(QueryContext::with(catalog, schema));
+    ctx.set_current_user(user_info);
+
+    let body = hyper::body::to_bytes(body)
+        .await
+        .context(error::HyperSnafu)?;
+    let request = if body.is_empty() {
+        let suggest_type = match params.get("type").map(|v| v.as_str()) {
+            Some("metrics") => SuggestType::Metrics,
+            Some("tagk") => SuggestType::Tagk,
+            Some("tagv") => SuggestType::Tagv,
+            other => {
+                return error::InvalidOpentsdbQuerySnafu {
+                    reason: format!("invalid suggest type: {other:?}"),
+                }
+                .fail()
+            }
+        };
+        let max = match params.get("max") {
+            Some(max) => max.parse().ok().context(error::InvalidOpentsdbQuerySnafu {
+                reason: format!("invalid max: {max}"),
+            })?,
+            None => DEFAULT_SUGGEST_MAX,
+        };
+        SuggestRequest {
+            suggest_type,
+            q: params.get("q").cloned().unwrap_or_default(),
+            max,
+        }
+    } else {
+        serde_json::from_slice::<SuggestRequest>(&body[..])
+            .context(error::InvalidOpentsdbJsonRequestSnafu)?
+    };
+
+    Ok(Json(opentsdb_handler.suggest(&request, ctx).await?))
+}
+
 async fn parse_data_points(body: Body) -> Result<Vec<DataPointRequest>> {
     let body = hyper::body::to_bytes(body)
         .await
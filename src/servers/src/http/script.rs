@@ -75,6 +75,22 @@ pub async fn scripts(
             Err(e) => json_err!(format!("Insert script error: {e}"), e.status_code()),
         };
 
+        // An empty schedule unschedules the script.
+        if let Some(schedule) = &params.schedule {
+            let schedule = Some(schedule.as_str()).filter(|s| !s.is_empty());
+            if let Err(e) = script_handler
+                .schedule_script(
+                    schema.unwrap(),
+                    name.unwrap(),
+                    schedule,
+                    params.target_table.as_deref(),
+                )
+                .await
+            {
+                json_err!(format!("Schedule script error: {e}"), e.status_code())
+            }
+        }
+
         Json(body)
     } else {
         json_err!("Script execution not supported, missing script handler");
@@ -85,6 +101,10 @@ pub async fn scripts(
 pub struct ScriptQuery {
     pub db: Option<String>,
     pub name: Option<String>,
+    /// Interval or cron expression to run the script periodically.
+    pub schedule: Option<String>,
+    /// Table to write the outputs of the scheduled script into.
+    pub target_table: Option<String>,
     #[serde(flatten)]
     pub params: HashMap<String, String>,
 }
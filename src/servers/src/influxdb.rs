@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod influxql;
+
 use std::collections::HashMap;
 
 use api::v1::{InsertRequest as GrpcInsertRequest, InsertRequests};
@@ -0,0 +1,1609 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A subset of InfluxQL, enough for the InfluxDB v1 `/query` endpoint used by
+//! Grafana and Telegraf-era tooling.
+//!
+//! Supported statements:
+//! - `SELECT <fields> FROM <measurement> [WHERE <cond>] [GROUP BY time(<interval>), <tag>...]
+//!   [fill(null|none)] [ORDER BY time [ASC|DESC]] [LIMIT <n>]`, where empty
+//!   intervals within the time range are filled with nulls unless `fill(none)`
+//! - `SHOW MEASUREMENTS [LIMIT <n>]`
+//! - `SHOW TAG KEYS [FROM <measurement>]`
+//! - `SHOW FIELD KEYS [FROM <measurement>]`
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{SecondsFormat, TimeZone, Utc};
+use common_grpc::writer::Precision;
+use common_query::Output;
+use common_recordbatch::RecordBatches;
+use common_time::Timestamp;
+use datafusion::logical_expr::expr::{AggregateFunction, ScalarFunction};
+use datafusion::logical_expr::{
+    AggregateFunction as AggregateFunctionEnum, BuiltinScalarFunction, Cast,
+};
+use datafusion::prelude::{lit, regexp_match, Column, Expr};
+use datafusion::scalar::ScalarValue;
+use datatypes::arrow::datatypes::{
+    DataType as ArrowDataType, IntervalMonthDayNanoType, TimeUnit as ArrowTimeUnit,
+};
+use datatypes::prelude::{ConcreteDataType, Value};
+use query::dataframe::DataFrame;
+use query::plan::LogicalPlan;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::error::{self, Result};
+
+/// Name of the time column in InfluxQL results.
+pub const INFLUXQL_TIME_COLUMN_NAME: &str = "time";
+
+const NANOS_PER_MICRO: i64 = 1_000;
+const NANOS_PER_MILLI: i64 = 1_000_000;
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+const NANOS_PER_MINUTE: i64 = 60 * NANOS_PER_SECOND;
+const NANOS_PER_HOUR: i64 = 60 * NANOS_PER_MINUTE;
+const NANOS_PER_DAY: i64 = 24 * NANOS_PER_HOUR;
+const NANOS_PER_WEEK: i64 = 7 * NANOS_PER_DAY;
+
+/// Max number of `GROUP BY time(...)` intervals of a series filled with nulls,
+/// like the `max-select-buckets` of InfluxDB.
+const MAX_FILL_BUCKETS: i64 = 100_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InfluxqlStatement {
+    Select(SelectStatement),
+    ShowMeasurements { limit: Option<usize> },
+    ShowTagKeys { measurement: Option<String> },
+    ShowFieldKeys { measurement: Option<String> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectStatement {
+    pub fields: Vec<Field>,
+    pub measurement: String,
+    pub condition: Option<Condition>,
+    /// Interval of `GROUP BY time(...)`, in nanoseconds.
+    pub group_by_time: Option<i64>,
+    pub group_by_tags: Vec<String>,
+    pub fill: Fill,
+    pub order_desc: bool,
+    pub limit: Option<usize>,
+}
+
+impl SelectStatement {
+    fn is_aggregate(&self) -> bool {
+        self.fields
+            .iter()
+            .any(|f| matches!(f.expr, FieldExpr::Call { .. }))
+    }
+
+    /// Returns the first and last `GROUP BY time(...)` intervals to fill with
+    /// nulls, or `None` if empty intervals are not filled, i.e. the query is not
+    /// grouped by time, uses `fill(none)`, or has no lower bound of time.
+    pub fn fill_range(&self, now: i64) -> Result<Option<(i64, i64)>> {
+        let (Some(interval), Fill::Null, Some(condition)) =
+            (self.group_by_time, self.fill, &self.condition) else { return Ok(None) };
+        let Some(start) = time_lower_bound(condition, now) else { return Ok(None) };
+        if !self.is_aggregate() {
+            return Ok(None);
+        }
+
+        let end = time_upper_bound(condition, now).unwrap_or(now);
+        let first = start.div_euclid(interval) * interval;
+        let last = end.div_euclid(interval) * interval;
+        if first > last {
+            return Ok(None);
+        }
+        ensure!(
+            (last - first) / interval < MAX_FILL_BUCKETS,
+            error::InvalidQuerySnafu {
+                reason: format!(
+                    "fill(null) of more than {MAX_FILL_BUCKETS} intervals, \
+                    narrow the time range or use fill(none)"
+                ),
+            }
+        );
+        Ok(Some((first, last)))
+    }
+}
+
+/// How empty intervals of `GROUP BY time(...)` are reported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Fill {
+    /// Reported as rows of nulls, the default of InfluxDB.
+    #[default]
+    Null,
+    /// Omitted.
+    None,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub expr: FieldExpr,
+    pub alias: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldExpr {
+    Wildcard,
+    Column(String),
+    Call { name: String, arg: Box<FieldExpr> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Compare {
+        lhs: Operand,
+        op: CompareOp,
+        rhs: Operand,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    RegexMatch,
+    RegexNotMatch,
+}
+
+impl CompareOp {
+    /// Returns the operator with both sides swapped, e.g. `a < b` to `b > a`.
+    fn swap(self) -> Self {
+        match self {
+            CompareOp::Lt => CompareOp::Gt,
+            CompareOp::LtEq => CompareOp::GtEq,
+            CompareOp::Gt => CompareOp::Lt,
+            CompareOp::GtEq => CompareOp::LtEq,
+            other => other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Column(String),
+    String(String),
+    Number(f64),
+    Boolean(bool),
+    Regex(String),
+    /// A duration literal in nanoseconds, e.g. `1h`. Compared with `time` it is
+    /// treated as an offset from unix epoch.
+    Duration(i64),
+    /// `now()` plus an offset in nanoseconds.
+    Now(i64),
+}
+
+impl Operand {
+    fn is_time(&self) -> bool {
+        matches!(self, Operand::Column(c) if c == INFLUXQL_TIME_COLUMN_NAME)
+    }
+}
+
+/// Parses one or more `;` separated InfluxQL statements.
+pub fn parse(query: &str) -> Result<Vec<InfluxqlStatement>> {
+    let tokens = tokenize(query).map_err(|reason| {
+        error::ParseInfluxqlSnafu {
+            query: query.to_string(),
+            reason,
+        }
+        .build()
+    })?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let mut statements = vec![];
+    loop {
+        while parser.consume(&Token::Semicolon) {}
+        if parser.peek().is_none() {
+            break;
+        }
+        let statement = parser.parse_statement().map_err(|reason| {
+            error::ParseInfluxqlSnafu {
+                query: query.to_string(),
+                reason,
+            }
+            .build()
+        })?;
+        statements.push(statement);
+    }
+    ensure!(
+        !statements.is_empty(),
+        error::ParseInfluxqlSnafu {
+            query,
+            reason: "empty query",
+        }
+    );
+    Ok(statements)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident { value: String, quoted: bool },
+    Str(String),
+    Number(String),
+    Duration(i64),
+    Regex(String),
+    Comma,
+    Dot,
+    LParen,
+    RParen,
+    Star,
+    Plus,
+    Minus,
+    Semicolon,
+    Op(CompareOp),
+}
+
+fn tokenize(input: &str) -> std::result::Result<Vec<Token>, String> {
+    let chars = input.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '.' if !chars.get(i + 1).map_or(false, |c| c.is_ascii_digit()) => {
+                tokens.push(Token::Dot);
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '*' => {
+                tokens.push(Token::Star);
+                i += 1;
+            }
+            '+' => {
+                tokens.push(Token::Plus);
+                i += 1;
+            }
+            '-' => {
+                tokens.push(Token::Minus);
+                i += 1;
+            }
+            ';' => {
+                tokens.push(Token::Semicolon);
+                i += 1;
+            }
+            '=' if chars.get(i + 1) == Some(&'~') => {
+                tokens.push(Token::Op(CompareOp::RegexMatch));
+                i += 2;
+            }
+            '=' => {
+                tokens.push(Token::Op(CompareOp::Eq));
+                i += 1;
+            }
+            '!' if chars.get(i + 1) == Some(&'~') => {
+                tokens.push(Token::Op(CompareOp::RegexNotMatch));
+                i += 2;
+            }
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op(CompareOp::NotEq));
+                i += 2;
+            }
+            '<' if chars.get(i + 1) == Some(&'>') => {
+                tokens.push(Token::Op(CompareOp::NotEq));
+                i += 2;
+            }
+            '<' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op(CompareOp::LtEq));
+                i += 2;
+            }
+            '<' => {
+                tokens.push(Token::Op(CompareOp::Lt));
+                i += 1;
+            }
+            '>' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op(CompareOp::GtEq));
+                i += 2;
+            }
+            '>' => {
+                tokens.push(Token::Op(CompareOp::Gt));
+                i += 1;
+            }
+            '/' if matches!(
+                tokens.last(),
+                Some(Token::Op(CompareOp::RegexMatch | CompareOp::RegexNotMatch))
+            ) =>
+            {
+                let (regex, next) = read_quoted(&chars, i, '/')?;
+                tokens.push(Token::Regex(regex));
+                i = next;
+            }
+            '"' => {
+                let (value, next) = read_quoted(&chars, i, '"')?;
+                tokens.push(Token::Ident {
+                    value,
+                    quoted: true,
+                });
+                i = next;
+            }
+            '\'' => {
+                let (value, next) = read_quoted(&chars, i, '\'')?;
+                tokens.push(Token::Str(value));
+                i = next;
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let number = chars[start..i].iter().collect::<String>();
+                let unit_start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphabetic() || chars[i] == 'µ') {
+                    i += 1;
+                }
+                if unit_start == i {
+                    tokens.push(Token::Number(number));
+                } else {
+                    let unit = chars[unit_start..i].iter().collect::<String>();
+                    tokens.push(Token::Duration(parse_duration(&number, &unit)?));
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident {
+                    value: chars[start..i].iter().collect(),
+                    quoted: false,
+                });
+            }
+            c => return Err(format!("unexpected character '{c}' at position {i}")),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Reads a string enclosed by `quote` starting at `start`, returns the unescaped
+/// content and the position after the closing quote.
+fn read_quoted(
+    chars: &[char],
+    start: usize,
+    quote: char,
+) -> std::result::Result<(String, usize), String> {
+    let mut value = String::new();
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if chars.get(i + 1) == Some(&quote) => {
+                value.push(quote);
+                i += 2;
+            }
+            c if c == quote => return Ok((value, i + 1)),
+            c => {
+                value.push(c);
+                i += 1;
+            }
+        }
+    }
+    Err(format!("unterminated {quote} at position {start}"))
+}
+
+fn parse_duration(number: &str, unit: &str) -> std::result::Result<i64, String> {
+    let value = number
+        .parse::<i64>()
+        .map_err(|e| format!("invalid duration {number}{unit}: {e}"))?;
+    let factor = match unit {
+        "ns" => 1,
+        "u" | "µ" | "us" => NANOS_PER_MICRO,
+        "ms" => NANOS_PER_MILLI,
+        "s" => NANOS_PER_SECOND,
+        "m" => NANOS_PER_MINUTE,
+        "h" => NANOS_PER_HOUR,
+        "d" => NANOS_PER_DAY,
+        "w" => NANOS_PER_WEEK,
+        _ => return Err(format!("invalid duration unit: {unit}")),
+    };
+    value
+        .checked_mul(factor)
+        .ok_or_else(|| format!("duration overflow: {number}{unit}"))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+type ParseResult<T> = std::result::Result<T, String>;
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next_token(&mut self) -> ParseResult<Token> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| "unexpected end of query".to_string())?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn consume(&mut self, expected: &Token) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: &Token) -> ParseResult<()> {
+        let token = self.next_token()?;
+        if &token == expected {
+            Ok(())
+        } else {
+            Err(format!("expected {expected:?}, found {token:?}"))
+        }
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(
+            self.peek(),
+            Some(Token::Ident { value, quoted: false }) if value.eq_ignore_ascii_case(keyword)
+        )
+    }
+
+    fn consume_keyword(&mut self, keyword: &str) -> bool {
+        if self.peek_keyword(keyword) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> ParseResult<()> {
+        if self.consume_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(format!("expected {keyword}, found {:?}", self.peek()))
+        }
+    }
+
+    fn parse_identifier(&mut self) -> ParseResult<String> {
+        match self.next_token()? {
+            Token::Ident { value, .. } => Ok(value),
+            token => Err(format!("expected identifier, found {token:?}")),
+        }
+    }
+
+    fn parse_usize(&mut self) -> ParseResult<usize> {
+        match self.next_token()? {
+            Token::Number(n) => n
+                .parse::<usize>()
+                .map_err(|e| format!("invalid integer {n}: {e}")),
+            token => Err(format!("expected integer, found {token:?}")),
+        }
+    }
+
+    fn parse_statement(&mut self) -> ParseResult<InfluxqlStatement> {
+        if self.consume_keyword("SELECT") {
+            self.parse_select().map(InfluxqlStatement::Select)
+        } else if self.consume_keyword("SHOW") {
+            self.parse_show()
+        } else {
+            Err(format!("expected SELECT or SHOW, found {:?}", self.peek()))
+        }
+    }
+
+    fn parse_show(&mut self) -> ParseResult<InfluxqlStatement> {
+        if self.consume_keyword("MEASUREMENTS") {
+            let limit = if self.consume_keyword("LIMIT") {
+                Some(self.parse_usize()?)
+            } else {
+                None
+            };
+            Ok(InfluxqlStatement::ShowMeasurements { limit })
+        } else if self.consume_keyword("TAG") {
+            self.expect_keyword("KEYS")?;
+            let measurement = self.parse_optional_from()?;
+            Ok(InfluxqlStatement::ShowTagKeys { measurement })
+        } else if self.consume_keyword("FIELD") {
+            self.expect_keyword("KEYS")?;
+            let measurement = self.parse_optional_from()?;
+            Ok(InfluxqlStatement::ShowFieldKeys { measurement })
+        } else {
+            Err(format!(
+                "expected MEASUREMENTS, TAG KEYS or FIELD KEYS after SHOW, found {:?}",
+                self.peek()
+            ))
+        }
+    }
+
+    fn parse_optional_from(&mut self) -> ParseResult<Option<String>> {
+        if self.consume_keyword("FROM") {
+            self.parse_measurement().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Parses `[<db>.][<rp>.]<measurement>`, only the measurement part is kept.
+    fn parse_measurement(&mut self) -> ParseResult<String> {
+        let mut name = self.parse_identifier()?;
+        while self.consume(&Token::Dot) {
+            name = self.parse_identifier()?;
+        }
+        Ok(name)
+    }
+
+    fn parse_select(&mut self) -> ParseResult<SelectStatement> {
+        let mut fields = vec![self.parse_field()?];
+        while self.consume(&Token::Comma) {
+            fields.push(self.parse_field()?);
+        }
+
+        self.expect_keyword("FROM")?;
+        let measurement = self.parse_measurement()?;
+
+        let condition = if self.consume_keyword("WHERE") {
+            Some(self.parse_condition()?)
+        } else {
+            None
+        };
+
+        let mut group_by_time = None;
+        let mut group_by_tags = vec![];
+        if self.consume_keyword("GROUP") {
+            self.expect_keyword("BY")?;
+            loop {
+                if self.peek_keyword(INFLUXQL_TIME_COLUMN_NAME)
+                    && self.tokens.get(self.pos + 1) == Some(&Token::LParen)
+                {
+                    self.pos += 2;
+                    match self.next_token()? {
+                        Token::Duration(interval) if interval > 0 => group_by_time = Some(interval),
+                        token => return Err(format!("expected time interval, found {token:?}")),
+                    }
+                    self.expect(&Token::RParen)?;
+                } else {
+                    group_by_tags.push(self.parse_identifier()?);
+                }
+                if !self.consume(&Token::Comma) {
+                    break;
+                }
+            }
+        }
+
+        let mut fill = Fill::default();
+        if self.consume_keyword("fill") {
+            self.expect(&Token::LParen)?;
+            let option = self.parse_identifier()?;
+            fill = if option.eq_ignore_ascii_case("null") {
+                Fill::Null
+            } else if option.eq_ignore_ascii_case("none") {
+                Fill::None
+            } else {
+                return Err(format!("unsupported fill option: {option}"));
+            };
+            self.expect(&Token::RParen)?;
+        }
+
+        let mut order_desc = false;
+        if self.consume_keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            self.expect_keyword(INFLUXQL_TIME_COLUMN_NAME)?;
+            if self.consume_keyword("DESC") {
+                order_desc = true;
+            } else {
+                let _ = self.consume_keyword("ASC");
+            }
+        }
+
+        let limit = if self.consume_keyword("LIMIT") {
+            Some(self.parse_usize()?)
+        } else {
+            None
+        };
+
+        match self.peek() {
+            None | Some(Token::Semicolon) => {}
+            Some(token) => return Err(format!("unexpected token {token:?}")),
+        }
+
+        Ok(SelectStatement {
+            fields,
+            measurement,
+            condition,
+            group_by_time,
+            group_by_tags,
+            fill,
+            order_desc,
+            limit,
+        })
+    }
+
+    fn parse_field(&mut self) -> ParseResult<Field> {
+        let expr = self.parse_field_expr()?;
+        let alias = if self.consume_keyword("AS") {
+            Some(self.parse_identifier()?)
+        } else {
+            None
+        };
+        Ok(Field { expr, alias })
+    }
+
+    fn parse_field_expr(&mut self) -> ParseResult<FieldExpr> {
+        if self.consume(&Token::Star) {
+            return Ok(FieldExpr::Wildcard);
+        }
+        let name = self.parse_identifier()?;
+        if self.consume(&Token::LParen) {
+            let arg = self.parse_field_expr()?;
+            self.expect(&Token::RParen)?;
+            Ok(FieldExpr::Call {
+                name: name.to_lowercase(),
+                arg: Box::new(arg),
+            })
+        } else {
+            Ok(FieldExpr::Column(name))
+        }
+    }
+
+    fn parse_condition(&mut self) -> ParseResult<Condition> {
+        let mut condition = self.parse_and_condition()?;
+        while self.consume_keyword("OR") {
+            let rhs = self.parse_and_condition()?;
+            condition = Condition::Or(Box::new(condition), Box::new(rhs));
+        }
+        Ok(condition)
+    }
+
+    fn parse_and_condition(&mut self) -> ParseResult<Condition> {
+        let mut condition = self.parse_primary_condition()?;
+        while self.consume_keyword("AND") {
+            let rhs = self.parse_primary_condition()?;
+            condition = Condition::And(Box::new(condition), Box::new(rhs));
+        }
+        Ok(condition)
+    }
+
+    fn parse_primary_condition(&mut self) -> ParseResult<Condition> {
+        if self.consume(&Token::LParen) {
+            let condition = self.parse_condition()?;
+            self.expect(&Token::RParen)?;
+            return Ok(condition);
+        }
+        let lhs = self.parse_operand()?;
+        let op = match self.next_token()? {
+            Token::Op(op) => op,
+            token => return Err(format!("expected comparison operator, found {token:?}")),
+        };
+        let rhs = self.parse_operand()?;
+        Ok(Condition::Compare { lhs, op, rhs })
+    }
+
+    fn parse_operand(&mut self) -> ParseResult<Operand> {
+        let mut operand = match self.next_token()? {
+            Token::Ident {
+                value,
+                quoted: false,
+            } if value.eq_ignore_ascii_case("now") && self.peek() == Some(&Token::LParen) => {
+                self.pos += 1;
+                self.expect(&Token::RParen)?;
+                Operand::Now(0)
+            }
+            Token::Ident {
+                value,
+                quoted: false,
+            } if value.eq_ignore_ascii_case("true") => Operand::Boolean(true),
+            Token::Ident {
+                value,
+                quoted: false,
+            } if value.eq_ignore_ascii_case("false") => Operand::Boolean(false),
+            Token::Ident { value, .. } => Operand::Column(value),
+            Token::Str(s) => Operand::String(s),
+            Token::Number(n) => Operand::Number(
+                n.parse::<f64>()
+                    .map_err(|e| format!("invalid number {n}: {e}"))?,
+            ),
+            Token::Minus => match self.next_token()? {
+                Token::Number(n) => Operand::Number(
+                    -n.parse::<f64>()
+                        .map_err(|e| format!("invalid number {n}: {e}"))?,
+                ),
+                Token::Duration(d) => Operand::Duration(-d),
+                token => return Err(format!("expected number, found {token:?}")),
+            },
+            Token::Duration(d) => Operand::Duration(d),
+            Token::Regex(r) => Operand::Regex(r),
+            token => return Err(format!("expected operand, found {token:?}")),
+        };
+
+        loop {
+            let sign = if self.consume(&Token::Plus) {
+                1
+            } else if self.consume(&Token::Minus) {
+                -1
+            } else {
+                break;
+            };
+            let offset = match self.next_token()? {
+                Token::Duration(d) => d * sign,
+                token => return Err(format!("expected duration, found {token:?}")),
+            };
+            operand = match operand {
+                Operand::Now(o) => Operand::Now(o + offset),
+                Operand::Duration(d) => Operand::Duration(d + offset),
+                other => return Err(format!("cannot add duration to {other:?}")),
+            };
+        }
+        Ok(operand)
+    }
+}
+
+/// Creates a logical plan for a SELECT statement against the measurement's
+/// table. `time_index` is the name of the table's time index column, and `now`
+/// is the nanosecond timestamp that `now()` evaluates to.
+pub fn select_to_plan(
+    dataframe: DataFrame,
+    time_index: &str,
+    stmt: &SelectStatement,
+    now: i64,
+) -> Result<LogicalPlan> {
+    let DataFrame::DataFusion(mut dataframe) = dataframe;
+
+    let time_unit = match dataframe
+        .schema()
+        .field_with_unqualified_name(time_index)
+        .context(error::DataFrameSnafu)?
+        .data_type()
+    {
+        ArrowDataType::Timestamp(unit, _) => unit.clone(),
+        other => {
+            return error::InvalidQuerySnafu {
+                reason: format!("time index column {time_index} has unexpected type {other:?}"),
+            }
+            .fail()
+        }
+    };
+    let planner = ExprPlanner {
+        time_index,
+        time_unit,
+        now,
+    };
+
+    if let Some(condition) = &stmt.condition {
+        let predicate = planner.condition_to_expr(condition)?;
+        dataframe = dataframe.filter(predicate).context(error::DataFrameSnafu)?;
+    }
+
+    let mut sort_exprs = stmt
+        .group_by_tags
+        .iter()
+        .map(|tag| column(tag).sort(true, false))
+        .collect::<Vec<_>>();
+    sort_exprs.push(column(INFLUXQL_TIME_COLUMN_NAME).sort(!stmt.order_desc, false));
+
+    dataframe = if stmt.is_aggregate() {
+        let mut group_exprs = Vec::with_capacity(stmt.group_by_tags.len() + 1);
+        let mut projection = Vec::with_capacity(stmt.fields.len() + stmt.group_by_tags.len() + 1);
+        if let Some(interval) = stmt.group_by_time {
+            group_exprs.push(planner.date_bin(interval).alias(INFLUXQL_TIME_COLUMN_NAME));
+            projection.push(column(INFLUXQL_TIME_COLUMN_NAME));
+        } else {
+            // Like InfluxDB, an aggregation without time buckets reports the lower
+            // bound of the queried time range (or epoch) as its time.
+            let start = stmt
+                .condition
+                .as_ref()
+                .and_then(|c| time_lower_bound(c, now))
+                .unwrap_or(0);
+            projection.push(
+                lit(ScalarValue::TimestampNanosecond(Some(start), None))
+                    .alias(INFLUXQL_TIME_COLUMN_NAME),
+            );
+        }
+        for tag in &stmt.group_by_tags {
+            group_exprs.push(column(tag));
+            projection.push(column(tag));
+        }
+
+        let mut aggr_exprs = Vec::with_capacity(stmt.fields.len());
+        for field in &stmt.fields {
+            let FieldExpr::Call { name, arg } = &field.expr else {
+                return error::InvalidQuerySnafu {
+                    reason: "mixing aggregate and non-aggregate queries is not supported",
+                }
+                .fail();
+            };
+            let alias = field.alias.clone().unwrap_or_else(|| name.clone());
+            aggr_exprs.push(aggregate_expr(name, arg)?.alias(&alias));
+            projection.push(column(&alias));
+        }
+
+        dataframe
+            .aggregate(group_exprs, aggr_exprs)
+            .and_then(|df| df.select(projection))
+            .context(error::DataFrameSnafu)?
+    } else {
+        let mut projection = vec![column(time_index).alias(INFLUXQL_TIME_COLUMN_NAME)];
+        for tag in &stmt.group_by_tags {
+            projection.push(column(tag));
+        }
+        for field in &stmt.fields {
+            match &field.expr {
+                FieldExpr::Wildcard => {
+                    for f in dataframe.schema().fields() {
+                        let name = f.name();
+                        if name != time_index && !stmt.group_by_tags.contains(name) {
+                            projection.push(column(name));
+                        }
+                    }
+                }
+                FieldExpr::Column(name) => {
+                    let expr = column(name);
+                    projection.push(match &field.alias {
+                        Some(alias) => expr.alias(alias),
+                        None => expr,
+                    });
+                }
+                FieldExpr::Call { .. } => unreachable!(),
+            }
+        }
+        dataframe
+            .select(projection)
+            .context(error::DataFrameSnafu)?
+    };
+
+    dataframe = dataframe.sort(sort_exprs).context(error::DataFrameSnafu)?;
+    // Filled series are limited one by one after filling, see `output_to_series`.
+    if let (Some(limit), None) = (stmt.limit, stmt.fill_range(now)?) {
+        // Note the limit applies to the whole result instead of each series.
+        dataframe = dataframe
+            .limit(0, Some(limit))
+            .context(error::DataFrameSnafu)?;
+    }
+
+    Ok(LogicalPlan::DfPlan(dataframe.into_parts().1))
+}
+
+fn column(name: &str) -> Expr {
+    Expr::Column(Column::from_name(name))
+}
+
+fn aggregate_expr(name: &str, arg: &FieldExpr) -> Result<Expr> {
+    let arg = match arg {
+        FieldExpr::Column(c) => column(c),
+        FieldExpr::Wildcard if name == "count" => lit(1),
+        _ => {
+            return error::InvalidQuerySnafu {
+                reason: format!("unsupported argument of function {name}: {arg:?}"),
+            }
+            .fail()
+        }
+    };
+    let fun = match name {
+        "mean" => AggregateFunctionEnum::Avg,
+        "median" => AggregateFunctionEnum::Median,
+        "count" => AggregateFunctionEnum::Count,
+        "sum" => AggregateFunctionEnum::Sum,
+        "min" => AggregateFunctionEnum::Min,
+        "max" => AggregateFunctionEnum::Max,
+        "stddev" => AggregateFunctionEnum::Stddev,
+        _ => {
+            return error::NotSupportedSnafu {
+                feat: format!("InfluxQL function {name}"),
+            }
+            .fail()
+        }
+    };
+    Ok(Expr::AggregateFunction(AggregateFunction {
+        fun,
+        args: vec![arg],
+        distinct: false,
+        filter: None,
+        order_by: None,
+    }))
+}
+
+struct ExprPlanner<'a> {
+    time_index: &'a str,
+    time_unit: ArrowTimeUnit,
+    now: i64,
+}
+
+impl<'a> ExprPlanner<'a> {
+    fn date_bin(&self, interval: i64) -> Expr {
+        let interval = lit(ScalarValue::IntervalMonthDayNano(Some(
+            IntervalMonthDayNanoType::make_value(0, 0, interval),
+        )));
+        let source = Expr::Cast(Cast::new(
+            Box::new(column(self.time_index)),
+            ArrowDataType::Timestamp(ArrowTimeUnit::Nanosecond, None),
+        ));
+        let origin = lit(ScalarValue::TimestampNanosecond(Some(0), None));
+        Expr::ScalarFunction(ScalarFunction {
+            fun: BuiltinScalarFunction::DateBin,
+            args: vec![interval, source, origin],
+        })
+    }
+
+    fn time_literal(&self, nanos: i64) -> Expr {
+        let value = match self.time_unit {
+            ArrowTimeUnit::Second => {
+                ScalarValue::TimestampSecond(Some(nanos.div_euclid(NANOS_PER_SECOND)), None)
+            }
+            ArrowTimeUnit::Millisecond => {
+                ScalarValue::TimestampMillisecond(Some(nanos.div_euclid(NANOS_PER_MILLI)), None)
+            }
+            ArrowTimeUnit::Microsecond => {
+                ScalarValue::TimestampMicrosecond(Some(nanos.div_euclid(NANOS_PER_MICRO)), None)
+            }
+            ArrowTimeUnit::Nanosecond => ScalarValue::TimestampNanosecond(Some(nanos), None),
+        };
+        lit(value)
+    }
+
+    fn condition_to_expr(&self, condition: &Condition) -> Result<Expr> {
+        match condition {
+            Condition::And(lhs, rhs) => Ok(self
+                .condition_to_expr(lhs)?
+                .and(self.condition_to_expr(rhs)?)),
+            Condition::Or(lhs, rhs) => Ok(self
+                .condition_to_expr(lhs)?
+                .or(self.condition_to_expr(rhs)?)),
+            Condition::Compare { lhs, op, rhs } => {
+                if lhs.is_time() {
+                    let value = self.time_literal(time_operand(rhs, self.now)?);
+                    compare(column(self.time_index), *op, value)
+                } else if rhs.is_time() {
+                    let value = self.time_literal(time_operand(lhs, self.now)?);
+                    compare(column(self.time_index), op.swap(), value)
+                } else {
+                    let (column_name, op, operand) = match (lhs, rhs) {
+                        (Operand::Column(c), operand) => (c, *op, operand),
+                        (operand, Operand::Column(c)) => (c, op.swap(), operand),
+                        _ => {
+                            return error::InvalidQuerySnafu {
+                                reason: format!("unsupported condition: {condition:?}"),
+                            }
+                            .fail()
+                        }
+                    };
+                    let value = match operand {
+                        Operand::String(s) | Operand::Regex(s) => lit(s.clone()),
+                        Operand::Number(n) => lit(*n),
+                        Operand::Boolean(b) => lit(*b),
+                        Operand::Column(c) => column(c),
+                        other => {
+                            return error::InvalidQuerySnafu {
+                                reason: format!(
+                                    "cannot compare column {column_name} with {other:?}"
+                                ),
+                            }
+                            .fail()
+                        }
+                    };
+                    compare(column(column_name), op, value)
+                }
+            }
+        }
+    }
+}
+
+/// Evaluates an operand compared with `time` into a nanosecond timestamp, `now`
+/// is the nanosecond timestamp that `now()` evaluates to.
+fn time_operand(operand: &Operand, now: i64) -> Result<i64> {
+    match operand {
+        Operand::Now(offset) => Ok(now + offset),
+        Operand::Duration(d) => Ok(*d),
+        Operand::Number(n) => Ok(*n as i64),
+        Operand::String(s) => {
+            let ts = s.parse::<Timestamp>().map_err(|e| {
+                error::InvalidQuerySnafu {
+                    reason: format!("invalid time literal '{s}': {e}"),
+                }
+                .build()
+            })?;
+            ts.convert_to(common_time::timestamp::TimeUnit::Nanosecond)
+                .map(|ts| ts.value())
+                .with_context(|| error::InvalidQuerySnafu {
+                    reason: format!("time literal '{s}' out of range"),
+                })
+        }
+        other => error::InvalidQuerySnafu {
+            reason: format!("cannot compare time with {other:?}"),
+        }
+        .fail(),
+    }
+}
+
+/// Finds the largest lower bound of `time` in the top level conjunctions.
+fn time_lower_bound(condition: &Condition, now: i64) -> Option<i64> {
+    time_bound(condition, now, &|op, value| match op {
+        CompareOp::Gt | CompareOp::GtEq => Some(value),
+        _ => None,
+    })
+    .reduce(i64::max)
+}
+
+/// Finds the smallest inclusive upper bound of `time` in the top level
+/// conjunctions.
+fn time_upper_bound(condition: &Condition, now: i64) -> Option<i64> {
+    time_bound(condition, now, &|op, value| match op {
+        CompareOp::Lt => Some(value - 1),
+        CompareOp::LtEq => Some(value),
+        _ => None,
+    })
+    .reduce(i64::min)
+}
+
+/// Collects the bounds of `time` in the top level conjunctions, which are
+/// picked by `bound` from comparisons of `time` with values.
+fn time_bound<'a>(
+    condition: &'a Condition,
+    now: i64,
+    bound: &'a dyn Fn(CompareOp, i64) -> Option<i64>,
+) -> Box<dyn Iterator<Item = i64> + 'a> {
+    match condition {
+        Condition::And(lhs, rhs) => {
+            Box::new(time_bound(lhs, now, bound).chain(time_bound(rhs, now, bound)))
+        }
+        Condition::Compare { lhs, op, rhs } => {
+            let (op, operand) = if lhs.is_time() {
+                (*op, rhs)
+            } else if rhs.is_time() {
+                (op.swap(), lhs)
+            } else {
+                return Box::new(std::iter::empty());
+            };
+            let value = time_operand(operand, now).ok();
+            Box::new(value.and_then(|value| bound(op, value)).into_iter())
+        }
+        Condition::Or(_, _) => Box::new(std::iter::empty()),
+    }
+}
+
+fn compare(lhs: Expr, op: CompareOp, rhs: Expr) -> Result<Expr> {
+    Ok(match op {
+        CompareOp::Eq => lhs.eq(rhs),
+        CompareOp::NotEq => lhs.not_eq(rhs),
+        CompareOp::Lt => lhs.lt(rhs),
+        CompareOp::LtEq => lhs.lt_eq(rhs),
+        CompareOp::Gt => lhs.gt(rhs),
+        CompareOp::GtEq => lhs.gt_eq(rhs),
+        // Case sensitive regexp match
+        CompareOp::RegexMatch => regexp_match(vec![lhs, rhs]).is_not_null(),
+        // Case sensitive regexp not match
+        CompareOp::RegexNotMatch => regexp_match(vec![lhs, rhs]).is_null(),
+    })
+}
+
+/// A series in the InfluxDB JSON response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InfluxqlSeries {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<BTreeMap<String, String>>,
+    pub columns: Vec<String>,
+    pub values: Vec<Vec<serde_json::Value>>,
+}
+
+impl InfluxqlSeries {
+    pub fn new(name: impl Into<String>, columns: Vec<String>) -> Self {
+        Self {
+            name: name.into(),
+            tags: None,
+            columns,
+            values: vec![],
+        }
+    }
+
+    /// Formats the nanosecond values in `time` column as RFC3339 strings, or as
+    /// integers in the given `epoch` precision.
+    pub fn format_time(&mut self, epoch: Option<Precision>) {
+        let Some(idx) = self
+            .columns
+            .iter()
+            .position(|c| c == INFLUXQL_TIME_COLUMN_NAME) else { return };
+
+        for row in &mut self.values {
+            let Some(nanos) = row.get(idx).and_then(|v| v.as_i64()) else { continue };
+            row[idx] = match epoch {
+                None => serde_json::Value::String(
+                    Utc.timestamp_nanos(nanos)
+                        .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+                ),
+                Some(precision) => {
+                    let divisor = match precision {
+                        Precision::Nanosecond => 1,
+                        Precision::Microsecond => NANOS_PER_MICRO,
+                        Precision::Millisecond => NANOS_PER_MILLI,
+                        Precision::Second => NANOS_PER_SECOND,
+                        Precision::Minute => NANOS_PER_MINUTE,
+                        Precision::Hour => NANOS_PER_HOUR,
+                    };
+                    serde_json::Value::from(nanos.div_euclid(divisor))
+                }
+            };
+        }
+    }
+}
+
+impl InfluxqlSeries {
+    /// Fills the `GROUP BY time(...)` intervals from `first` to `last` that have
+    /// no rows with nulls. Rows must be sorted by time, in descending order if
+    /// `order_desc`.
+    fn fill_nulls(&mut self, (first, last): (i64, i64), interval: i64, order_desc: bool) {
+        let Some(time_idx) = self
+            .columns
+            .iter()
+            .position(|c| c == INFLUXQL_TIME_COLUMN_NAME) else { return };
+
+        let mut rows = BTreeMap::new();
+        for row in self.values.drain(..) {
+            let time = row
+                .get(time_idx)
+                .and_then(|v| v.as_i64())
+                .unwrap_or_default();
+            let _ = rows.insert(time, row);
+        }
+        let mut time = first;
+        while time <= last {
+            let _ = rows.entry(time).or_insert_with(|| {
+                let mut row = vec![serde_json::Value::Null; self.columns.len()];
+                row[time_idx] = serde_json::Value::from(time);
+                row
+            });
+            time += interval;
+        }
+
+        self.values = if order_desc {
+            rows.into_values().rev().collect()
+        } else {
+            rows.into_values().collect()
+        };
+    }
+}
+
+/// The result of one statement in the InfluxDB JSON response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InfluxqlStatementResult {
+    pub statement_id: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub series: Vec<InfluxqlSeries>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InfluxqlResponse {
+    pub results: Vec<InfluxqlStatementResult>,
+}
+
+/// Collects the output of a SELECT statement into series, one for each distinct
+/// combination of the `GROUP BY` tags. `now` must be the same as the one the
+/// statement is planned with by [select_to_plan].
+pub async fn output_to_series(
+    stmt: &SelectStatement,
+    output: Output,
+    now: i64,
+) -> Result<Vec<InfluxqlSeries>> {
+    let recordbatches = match output {
+        Output::Stream(stream) => RecordBatches::try_collect(stream)
+            .await
+            .context(error::CollectRecordbatchSnafu)?,
+        Output::RecordBatches(recordbatches) => recordbatches,
+        Output::AffectedRows(_) => {
+            return error::UnexpectedResultSnafu {
+                reason: "expect records for InfluxQL SELECT statement",
+            }
+            .fail()
+        }
+    };
+    let mut series =
+        recordbatches_to_series(&stmt.measurement, &stmt.group_by_tags, recordbatches)?;
+
+    if let (Some(range), Some(interval)) = (stmt.fill_range(now)?, stmt.group_by_time) {
+        for s in &mut series {
+            s.fill_nulls(range, interval, stmt.order_desc);
+            if let Some(limit) = stmt.limit {
+                s.values.truncate(limit);
+            }
+        }
+    }
+    Ok(series)
+}
+
+fn recordbatches_to_series(
+    measurement: &str,
+    group_by_tags: &[String],
+    recordbatches: RecordBatches,
+) -> Result<Vec<InfluxqlSeries>> {
+    let schema = recordbatches.schema();
+    let column_schemas = schema.column_schemas();
+    let tag_indices = column_schemas
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| group_by_tags.contains(&c.name))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let columns = column_schemas
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !tag_indices.contains(i))
+        .map(|(_, c)| c.name.clone())
+        .collect::<Vec<_>>();
+
+    let mut series: Vec<InfluxqlSeries> = vec![];
+    let mut series_index: HashMap<Vec<String>, usize> = HashMap::new();
+    for recordbatch in recordbatches.take() {
+        for row in recordbatch.rows() {
+            let mut tags = BTreeMap::new();
+            let mut tag_values = Vec::with_capacity(tag_indices.len());
+            let mut values = Vec::with_capacity(columns.len());
+            for (i, value) in row.into_iter().enumerate() {
+                if tag_indices.contains(&i) {
+                    let tag_value = if value.is_null() {
+                        String::new()
+                    } else {
+                        value.to_string()
+                    };
+                    tag_values.push(tag_value.clone());
+                    let _ = tags.insert(column_schemas[i].name.clone(), tag_value);
+                } else {
+                    values.push(value_to_json(value)?);
+                }
+            }
+
+            let idx = *series_index.entry(tag_values).or_insert_with(|| {
+                let mut s = InfluxqlSeries::new(measurement, columns.clone());
+                if !group_by_tags.is_empty() {
+                    s.tags = Some(tags);
+                }
+                series.push(s);
+                series.len() - 1
+            });
+            series[idx].values.push(values);
+        }
+    }
+    Ok(series)
+}
+
+fn value_to_json(value: Value) -> Result<serde_json::Value> {
+    match value {
+        // Timestamps are kept in nanoseconds until formatted by `InfluxqlSeries::format_time`.
+        Value::Timestamp(ts) => Ok(ts
+            .convert_to(common_time::timestamp::TimeUnit::Nanosecond)
+            .map(|ts| serde_json::Value::from(ts.value()))
+            .unwrap_or(serde_json::Value::Null)),
+        value => serde_json::Value::try_from(value).map_err(|e| {
+            error::InternalSnafu {
+                err_msg: format!("failed to convert value to json: {e}"),
+            }
+            .build()
+        }),
+    }
+}
+
+/// Builds the result of `SHOW MEASUREMENTS`.
+pub fn measurements_to_series(mut names: Vec<String>, limit: Option<usize>) -> Vec<InfluxqlSeries> {
+    if names.is_empty() {
+        return vec![];
+    }
+    names.sort();
+    if let Some(limit) = limit {
+        names.truncate(limit);
+    }
+    let mut series = InfluxqlSeries::new("measurements", vec!["name".to_string()]);
+    series.values = names
+        .into_iter()
+        .map(|n| vec![serde_json::Value::String(n)])
+        .collect();
+    vec![series]
+}
+
+/// Builds one series of `SHOW TAG KEYS` for a measurement.
+pub fn tag_keys_to_series(measurement: &str, tag_keys: Vec<String>) -> InfluxqlSeries {
+    let mut series = InfluxqlSeries::new(measurement, vec!["tagKey".to_string()]);
+    series.values = tag_keys
+        .into_iter()
+        .map(|k| vec![serde_json::Value::String(k)])
+        .collect();
+    series
+}
+
+/// Builds one series of `SHOW FIELD KEYS` for a measurement.
+pub fn field_keys_to_series(
+    measurement: &str,
+    fields: Vec<(String, ConcreteDataType)>,
+) -> InfluxqlSeries {
+    let mut series = InfluxqlSeries::new(
+        measurement,
+        vec!["fieldKey".to_string(), "fieldType".to_string()],
+    );
+    series.values = fields
+        .into_iter()
+        .map(|(name, data_type)| {
+            vec![
+                serde_json::Value::String(name),
+                serde_json::Value::String(field_type_name(&data_type).to_string()),
+            ]
+        })
+        .collect();
+    series
+}
+
+fn field_type_name(data_type: &ConcreteDataType) -> &'static str {
+    match data_type {
+        ConcreteDataType::Float32(_) | ConcreteDataType::Float64(_) => "float",
+        ConcreteDataType::Boolean(_) => "boolean",
+        ConcreteDataType::Int8(_)
+        | ConcreteDataType::Int16(_)
+        | ConcreteDataType::Int32(_)
+        | ConcreteDataType::Int64(_) => "integer",
+        ConcreteDataType::UInt8(_)
+        | ConcreteDataType::UInt16(_)
+        | ConcreteDataType::UInt32(_)
+        | ConcreteDataType::UInt64(_) => "unsigned",
+        _ => "string",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector};
+
+    use super::*;
+
+    fn parse_one(query: &str) -> InfluxqlStatement {
+        let mut statements = parse(query).unwrap();
+        assert_eq!(1, statements.len());
+        statements.pop().unwrap()
+    }
+
+    #[test]
+    fn test_parse_select() {
+        let stmt = parse_one(
+            r#"SELECT mean("value") AS "avg", max(value) FROM "autogen"."cpu" WHERE "host" =~ /^a\/b$/ AND time > now() - 1h GROUP BY time(1m), "host" fill(null) ORDER BY time DESC LIMIT 10"#,
+        );
+        let InfluxqlStatement::Select(select) = stmt else { unreachable!() };
+        assert_eq!("cpu", select.measurement);
+        assert_eq!(
+            vec![
+                Field {
+                    expr: FieldExpr::Call {
+                        name: "mean".to_string(),
+                        arg: Box::new(FieldExpr::Column("value".to_string())),
+                    },
+                    alias: Some("avg".to_string()),
+                },
+                Field {
+                    expr: FieldExpr::Call {
+                        name: "max".to_string(),
+                        arg: Box::new(FieldExpr::Column("value".to_string())),
+                    },
+                    alias: None,
+                },
+            ],
+            select.fields
+        );
+        assert_eq!(
+            Some(Condition::And(
+                Box::new(Condition::Compare {
+                    lhs: Operand::Column("host".to_string()),
+                    op: CompareOp::RegexMatch,
+                    rhs: Operand::Regex("^a/b$".to_string()),
+                }),
+                Box::new(Condition::Compare {
+                    lhs: Operand::Column("time".to_string()),
+                    op: CompareOp::Gt,
+                    rhs: Operand::Now(-NANOS_PER_HOUR),
+                }),
+            )),
+            select.condition
+        );
+        assert_eq!(Some(NANOS_PER_MINUTE), select.group_by_time);
+        assert_eq!(vec!["host".to_string()], select.group_by_tags);
+        assert_eq!(Fill::Null, select.fill);
+        assert!(select.order_desc);
+        assert_eq!(Some(10), select.limit);
+    }
+
+    #[test]
+    fn test_fill_range() {
+        let select = |query| {
+            let InfluxqlStatement::Select(select) = parse_one(query) else { unreachable!() };
+            select
+        };
+        let now = 10 * NANOS_PER_MINUTE;
+
+        let stmt = select("SELECT mean(value) FROM cpu WHERE time > now() - 5m GROUP BY time(1m)");
+        assert_eq!(
+            Some((5 * NANOS_PER_MINUTE, 10 * NANOS_PER_MINUTE)),
+            stmt.fill_range(now).unwrap()
+        );
+        let stmt = select(
+            "SELECT mean(value) FROM cpu WHERE time >= 90s AND time < 3m GROUP BY time(1m) fill(null)",
+        );
+        assert_eq!(
+            Some((NANOS_PER_MINUTE, 2 * NANOS_PER_MINUTE)),
+            stmt.fill_range(now).unwrap()
+        );
+
+        for query in [
+            "SELECT mean(value) FROM cpu WHERE time > now() - 5m GROUP BY time(1m) fill(none)",
+            "SELECT mean(value) FROM cpu GROUP BY time(1m)",
+            "SELECT mean(value) FROM cpu WHERE time > now() - 5m",
+        ] {
+            assert_eq!(None, select(query).fill_range(now).unwrap(), "{query}");
+        }
+        assert!(
+            select("SELECT mean(value) FROM cpu WHERE time > 0 GROUP BY time(1ms)")
+                .fill_range(now)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_fill_nulls() {
+        let mut series = InfluxqlSeries::new("cpu", vec!["time".to_string(), "mean".to_string()]);
+        series.values = vec![
+            vec![serde_json::json!(30), serde_json::json!(3.0)],
+            vec![serde_json::json!(10), serde_json::json!(1.0)],
+        ];
+        series.fill_nulls((0, 40), 10, true);
+        assert_eq!(
+            serde_json::json!([[40, null], [30, 3.0], [20, null], [10, 1.0], [0, null]]),
+            serde_json::to_value(&series.values).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_time_range() {
+        let stmt = parse_one(
+            "select * from cpu where time >= 1690000000000ms and time <= '2023-07-22T04:26:40Z'",
+        );
+        let InfluxqlStatement::Select(select) = stmt else { unreachable!() };
+        assert_eq!(
+            vec![Field {
+                expr: FieldExpr::Wildcard,
+                alias: None
+            }],
+            select.fields
+        );
+        let condition = select.condition.as_ref().unwrap();
+        assert_eq!(
+            Some(1_690_000_000_000 * NANOS_PER_MILLI),
+            time_lower_bound(condition, 0)
+        );
+        assert_eq!(
+            Some(1_690_000_000_000 * NANOS_PER_MILLI),
+            time_upper_bound(condition, 0)
+        );
+    }
+
+    #[test]
+    fn test_parse_show() {
+        assert_eq!(
+            InfluxqlStatement::ShowMeasurements { limit: Some(1) },
+            parse_one("SHOW MEASUREMENTS LIMIT 1")
+        );
+        assert_eq!(
+            InfluxqlStatement::ShowTagKeys {
+                measurement: Some("cpu".to_string())
+            },
+            parse_one("show tag keys from \"cpu\"")
+        );
+        assert_eq!(
+            InfluxqlStatement::ShowFieldKeys { measurement: None },
+            parse_one("SHOW FIELD KEYS;")
+        );
+
+        let statements = parse("SHOW MEASUREMENTS; SHOW TAG KEYS").unwrap();
+        assert_eq!(2, statements.len());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(parse("").is_err());
+        assert!(parse("DELETE FROM cpu").is_err());
+        assert!(parse("SELECT value FROM cpu WHERE host = 'a").is_err());
+        assert!(parse("SELECT mean(value) FROM cpu GROUP BY time(1x)").is_err());
+        assert!(parse("SELECT mean(value) FROM cpu fill(linear)").is_err());
+        assert!(parse("SHOW RETENTION POLICIES").is_err());
+    }
+
+    #[test]
+    fn test_recordbatches_to_series() {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new(
+                "time",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("mean", ConcreteDataType::float64_datatype(), true),
+        ]));
+        let recordbatch = common_recordbatch::RecordBatch::new(
+            schema.clone(),
+            vec![
+                Arc::new(TimestampMillisecondVector::from_slice([0, 60_000, 0])) as _,
+                Arc::new(StringVector::from(vec!["a", "a", "b"])) as _,
+                Arc::new(Float64Vector::from_slice([1.0, 2.0, 3.0])) as _,
+            ],
+        )
+        .unwrap();
+        let recordbatches = RecordBatches::try_new(schema, vec![recordbatch]).unwrap();
+
+        let mut series =
+            recordbatches_to_series("cpu", &["host".to_string()], recordbatches).unwrap();
+        assert_eq!(2, series.len());
+        assert_eq!(
+            vec!["time".to_string(), "mean".to_string()],
+            series[0].columns
+        );
+        assert_eq!(
+            Some(&"a".to_string()),
+            series[0].tags.as_ref().unwrap().get("host")
+        );
+        assert_eq!(2, series[0].values.len());
+
+        series[0].format_time(None);
+        assert_eq!(
+            serde_json::json!(["1970-01-01T00:01:00Z", 2.0]),
+            serde_json::Value::from(series[0].values[1].clone())
+        );
+        series[1].format_time(Some(Precision::Millisecond));
+        assert_eq!(
+            serde_json::json!([0, 3.0]),
+            serde_json::Value::from(series[1].values[0].clone())
+        );
+    }
+
+    #[test]
+    fn test_show_series() {
+        assert!(measurements_to_series(vec![], None).is_empty());
+
+        let series = measurements_to_series(vec!["mem".to_string(), "cpu".to_string()], Some(1));
+        assert_eq!(
+            serde_json::json!({"name": "measurements", "columns": ["name"], "values": [["cpu"]]}),
+            serde_json::to_value(&series[0]).unwrap()
+        );
+
+        let series = field_keys_to_series(
+            "cpu",
+            vec![("usage".to_string(), ConcreteDataType::float64_datatype())],
+        );
+        assert_eq!(
+            serde_json::json!({"name": "cpu", "columns": ["fieldKey", "fieldType"], "values": [["usage", "float"]]}),
+            serde_json::to_value(&series).unwrap()
+        );
+    }
+}
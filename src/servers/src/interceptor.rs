@@ -14,14 +14,33 @@
 
 use std::borrow::Cow;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use api::v1::greptime_request::Request;
 use common_error::prelude::ErrorExt;
+use common_error::status_code::StatusCode;
 use common_query::Output;
+use common_recordbatch::{RecordBatchStreamAdaptor, SendableRecordBatchStream};
+use futures::StreamExt;
 use query::plan::LogicalPlan;
 use session::context::QueryContextRef;
 use sql::statements::statement::Statement;
 
+/// A finished execution of sql query, see [SqlQueryInterceptor::on_execute_finished].
+#[derive(Debug, Clone)]
+pub struct QueryExecution {
+    /// The text of the executed statement, with credentials redacted. It's the
+    /// query string for PromQL, or the plan for prepared statements.
+    pub query: String,
+    /// Time elapsed since the execution started. For stream outputs, it includes
+    /// the time the stream is consumed.
+    pub elapsed: Duration,
+    /// Rows affected, or returned to client.
+    pub rows: usize,
+    /// Status code and message of the error, if the execution failed.
+    pub error: Option<(StatusCode, String)>,
+}
+
 /// SqlQueryInterceptor can track life cycle of a sql query and customize or
 /// abort its execution at given point.
 pub trait SqlQueryInterceptor {
@@ -48,7 +67,8 @@ pub trait SqlQueryInterceptor {
         Ok(statements)
     }
 
-    /// Called before sql is actually executed. This hook is not called at the moment.
+    /// Called before each statement is actually executed. The implementation can
+    /// abort execution by raising an error. `plan` is not provided at the moment.
     fn pre_execute(
         &self,
         _statement: &Statement,
@@ -67,6 +87,12 @@ pub trait SqlQueryInterceptor {
     ) -> Result<Output, Self::Error> {
         Ok(output)
     }
+
+    /// Called after execution of each statement finished, whether it succeeded
+    /// or not. For stream outputs, it's called after the stream is exhausted or
+    /// dropped. Unlike [SqlQueryInterceptor::post_execute], the implementation
+    /// can only observe the execution, e.g. for auditing.
+    fn on_execute_finished(&self, _execution: &QueryExecution, _query_ctx: QueryContextRef) {}
 }
 
 pub type SqlQueryInterceptorRef<E> =
@@ -126,6 +152,93 @@ where
             Ok(output)
         }
     }
+
+    fn on_execute_finished(&self, execution: &QueryExecution, query_ctx: QueryContextRef) {
+        if let Some(this) = self {
+            this.on_execute_finished(execution, query_ctx)
+        }
+    }
+}
+
+/// Reports the execution of `query` started at `start` to
+/// [SqlQueryInterceptor::on_execute_finished]. Stream outputs are wrapped to be
+/// reported once they are exhausted or dropped.
+pub fn report_execution<I, E>(
+    interceptor: Option<&SqlQueryInterceptorRef<I>>,
+    query: &str,
+    start: Instant,
+    result: Result<Output, E>,
+    query_ctx: QueryContextRef,
+) -> Result<Output, E>
+where
+    I: ErrorExt + 'static,
+    E: ErrorExt,
+{
+    let Some(interceptor) = interceptor else { return result };
+
+    let mut reporter = ExecutionReporter {
+        interceptor: interceptor.clone(),
+        execution: QueryExecution {
+            query: query.to_string(),
+            elapsed: Duration::ZERO,
+            rows: 0,
+            error: None,
+        },
+        start,
+        query_ctx,
+    };
+    match result {
+        Ok(Output::Stream(stream)) => Ok(Output::Stream(reporter.wrap_stream(stream))),
+        Ok(output) => {
+            reporter.execution.rows = match &output {
+                Output::AffectedRows(rows) => *rows,
+                Output::RecordBatches(batches) => {
+                    batches.iter().map(|batch| batch.num_rows()).sum()
+                }
+                Output::Stream(_) => unreachable!(),
+            };
+            Ok(output)
+        }
+        Err(e) => {
+            reporter.execution.error = Some((e.status_code(), e.to_string()));
+            Err(e)
+        }
+    }
+}
+
+/// Reports the execution on drop.
+struct ExecutionReporter<E: ErrorExt> {
+    interceptor: SqlQueryInterceptorRef<E>,
+    execution: QueryExecution,
+    start: Instant,
+    query_ctx: QueryContextRef,
+}
+
+impl<E: ErrorExt + 'static> ExecutionReporter<E> {
+    fn wrap_stream(mut self, stream: SendableRecordBatchStream) -> SendableRecordBatchStream {
+        let schema = stream.schema();
+        let output_ordering = stream.output_ordering().map(|ordering| ordering.to_vec());
+        let stream = stream.map(move |batch| {
+            match &batch {
+                Ok(batch) => self.execution.rows += batch.num_rows(),
+                Err(e) => self.execution.error = Some((e.status_code(), e.to_string())),
+            }
+            batch
+        });
+        Box::pin(RecordBatchStreamAdaptor {
+            schema,
+            stream: Box::pin(stream),
+            output_ordering,
+        })
+    }
+}
+
+impl<E: ErrorExt> Drop for ExecutionReporter<E> {
+    fn drop(&mut self) {
+        self.execution.elapsed = self.start.elapsed();
+        self.interceptor
+            .on_execute_finished(&self.execution, self.query_ctx.clone());
+    }
 }
 
 /// GrpcQueryInterceptor can track life cycle of a grpc request and customize or
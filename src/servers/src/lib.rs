@@ -21,6 +21,7 @@ use serde::{Deserialize, Serialize};
 pub mod auth;
 pub mod configurator;
 pub mod error;
+pub mod graphite;
 pub mod grpc;
 pub mod http;
 pub mod influxdb;
@@ -36,6 +37,7 @@ pub mod prometheus;
 pub mod query_handler;
 pub mod server;
 mod shutdown;
+pub mod statsd;
 pub mod tls;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
@@ -58,6 +58,10 @@ static SHOW_SQL_MODE_PATTERN: Lazy<Regex> =
 static SET_TIME_ZONE_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?i)^SET TIME_ZONE\s*=\s*'(\S+)'").unwrap());
 
+// Read preference settings
+static SET_READ_PREFERENCE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^SET READ_PREFERENCE\s*=\s*'(\S+)'").unwrap());
+
 static OTHER_NOT_SUPPORTED_STMT: Lazy<RegexSet> = Lazy::new(|| {
     RegexSet::new([
         // Txn.
@@ -201,6 +205,7 @@ fn select_variable(query: &str, query_context: QueryContextRef) -> Option<Output
                 .map(|tz| tz.to_string())
                 .unwrap_or_else(|| "".to_owned()),
             "system_time_zone" => system_time_zone_name(),
+            "read_preference" => query_context.read_preference().to_string(),
             _ => VAR_VALUES
                 .get(var_as[0])
                 .map(|v| v.to_string())
@@ -273,6 +278,15 @@ fn check_set_variables(query: &str, query_ctx: QueryContextRef) -> Option<Output
         }
     }
 
+    if let Some(captures) = SET_READ_PREFERENCE_PATTERN.captures(query) {
+        // get the capture
+        let read_preference = captures.get(1).unwrap();
+        if let Ok(read_preference) = read_preference.as_str().parse() {
+            query_ctx.set_read_preference(read_preference);
+            return Some(Output::AffectedRows(0));
+        }
+    }
+
     None
 }
 
@@ -326,7 +340,7 @@ fn get_version() -> String {
 #[cfg(test)]
 mod test {
 
-    use session::context::QueryContext;
+    use session::context::{QueryContext, ReadPreference};
 
     use super::*;
 
@@ -452,4 +466,31 @@ mod test {
             _ => unreachable!(),
         }
     }
+
+    #[test]
+    fn test_set_read_preference() {
+        let query_context = Arc::new(QueryContext::new());
+        let output = check("set read_preference = 'follower'", query_context.clone());
+        match output.unwrap() {
+            Output::AffectedRows(rows) => {
+                assert_eq!(rows, 0)
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(ReadPreference::Follower, query_context.read_preference());
+
+        let output = check("select @@read_preference", query_context);
+        match output.unwrap() {
+            Output::RecordBatches(r) => {
+                let expected = "\
++-------------------+
+| @@read_preference |
++-------------------+
+| follower          |
++-------------------+";
+                assert_eq!(r.pretty_print().unwrap(), expected);
+            }
+            _ => unreachable!(),
+        }
+    }
 }
@@ -21,6 +21,7 @@ use async_trait::async_trait;
 use chrono::{NaiveDate, NaiveDateTime};
 use common_error::prelude::ErrorExt;
 use common_query::Output;
+use common_telemetry::tracing::{info_span, Instrument};
 use common_telemetry::{error, logging, timer, trace, warn};
 use datatypes::prelude::ConcreteDataType;
 use metrics::increment_counter;
@@ -105,6 +106,7 @@ impl MysqlInstanceShim {
             } else {
                 self.query_handler
                     .do_query(query, self.session.context())
+                    .instrument(info_span!("mysql_query", query))
                     .await
             };
 
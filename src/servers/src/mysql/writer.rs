@@ -14,6 +14,7 @@
 
 use std::ops::Deref;
 
+use common_error::prelude::{ErrorExt, StatusCode};
 use common_query::Output;
 use common_recordbatch::{util, RecordBatch};
 use common_telemetry::error;
@@ -82,9 +83,16 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
             Ok(output) => match output {
                 Output::Stream(stream) => {
                     let schema = stream.schema().clone();
-                    let recordbatches = util::collect(stream)
+                    let recordbatches = match util::collect(stream)
                         .await
-                        .context(error::CollectRecordbatchSnafu)?;
+                        .context(error::CollectRecordbatchSnafu)
+                    {
+                        Ok(recordbatches) => recordbatches,
+                        Err(error) => {
+                            Self::write_query_error(query, error, self.writer).await?;
+                            return Ok(None);
+                        }
+                    };
                     let query_result = QueryResult {
                         recordbatches,
                         schema,
@@ -202,7 +210,10 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
     ) -> Result<()> {
         error!(error; "Failed to execute query '{}'", query);
 
-        let kind = ErrorKind::ER_INTERNAL_ERROR;
+        let kind = match error.status_code() {
+            StatusCode::ResourcesExhausted => ErrorKind::ER_OUT_OF_RESOURCES,
+            _ => ErrorKind::ER_INTERNAL_ERROR,
+        };
         w.error(kind, error.to_string().as_bytes()).await?;
         Ok(())
     }
@@ -119,6 +119,7 @@ mod tests {
 
     use super::*;
     use crate::error;
+    use crate::opentsdb::query::{OpentsdbQuery, OpentsdbQueryResult, SuggestRequest};
     use crate::query_handler::OpentsdbProtocolHandler;
 
     struct DummyQueryHandler {
@@ -138,6 +139,22 @@ mod tests {
             self.tx.send(metric.to_string()).await.unwrap();
             Ok(())
         }
+
+        async fn query(
+            &self,
+            _query: &OpentsdbQuery,
+            _ctx: QueryContextRef,
+        ) -> Result<Vec<OpentsdbQueryResult>> {
+            unimplemented!()
+        }
+
+        async fn suggest(
+            &self,
+            _request: &SuggestRequest,
+            _ctx: QueryContextRef,
+        ) -> Result<Vec<String>> {
+            unimplemented!()
+        }
     }
 
     #[tokio::test]
@@ -0,0 +1,1032 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! OpenTSDB read path, handles `/api/query` and `/api/suggest` logic.
+//!
+//! Data points of a metric are stored by [DataPoint::as_grpc_insert] in a table
+//! named after the metric, with a `greptime_timestamp` time index, a
+//! `greptime_value` field and one string column per tag.
+//!
+//! The tag filters, time range and downsampling of a query are pushed down into
+//! a DataFusion plan, while `rate` and the cross-series aggregation are applied on
+//! the downsampled series afterward, following the order OpenTSDB evaluates them.
+//! Unlike OpenTSDB, series are not interpolated when aggregated, values are only
+//! merged on equal timestamps.
+//!
+//! [DataPoint::as_grpc_insert]: crate::opentsdb::codec::DataPoint::as_grpc_insert
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use common_query::Output;
+use common_recordbatch::RecordBatches;
+use datafusion::logical_expr::expr::{AggregateFunction, ScalarFunction};
+use datafusion::logical_expr::{
+    AggregateFunction as AggregateFunctionEnum, BuiltinScalarFunction, Cast,
+};
+use datafusion::prelude::{lit, regexp_match, Column, Expr};
+use datafusion::scalar::ScalarValue;
+use datatypes::arrow::datatypes::{
+    DataType as ArrowDataType, IntervalMonthDayNanoType, TimeUnit as ArrowTimeUnit,
+};
+use datatypes::prelude::Value;
+use query::dataframe::DataFrame;
+use query::plan::LogicalPlan;
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt};
+
+use crate::error::{self, Result};
+use crate::opentsdb::codec::{
+    DataPoint, OPENTSDB_FIELD_COLUMN_NAME, OPENTSDB_TIMESTAMP_COLUMN_NAME,
+};
+
+const MILLIS_PER_SECOND: i64 = 1_000;
+const MILLIS_PER_MINUTE: i64 = 60 * MILLIS_PER_SECOND;
+const MILLIS_PER_HOUR: i64 = 60 * MILLIS_PER_MINUTE;
+const MILLIS_PER_DAY: i64 = 24 * MILLIS_PER_HOUR;
+
+/// Default maximum number of results returned by `/api/suggest`.
+pub const DEFAULT_SUGGEST_MAX: usize = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregator {
+    Sum,
+    Avg,
+    Min,
+    Max,
+    Count,
+    Dev,
+    /// Skips the cross-series aggregation, returns every series as is.
+    None,
+}
+
+impl Aggregator {
+    fn parse(name: &str) -> Result<Self> {
+        Ok(match name {
+            "sum" | "zimsum" => Aggregator::Sum,
+            "avg" => Aggregator::Avg,
+            "min" | "mimmin" => Aggregator::Min,
+            "max" | "mimmax" => Aggregator::Max,
+            "count" => Aggregator::Count,
+            "dev" => Aggregator::Dev,
+            "none" => Aggregator::None,
+            _ => {
+                return error::InvalidOpentsdbQuerySnafu {
+                    reason: format!("unsupported aggregator: {name}"),
+                }
+                .fail()
+            }
+        })
+    }
+
+    fn as_df_aggregate(&self) -> Result<AggregateFunctionEnum> {
+        Ok(match self {
+            Aggregator::Sum => AggregateFunctionEnum::Sum,
+            Aggregator::Avg => AggregateFunctionEnum::Avg,
+            Aggregator::Min => AggregateFunctionEnum::Min,
+            Aggregator::Max => AggregateFunctionEnum::Max,
+            Aggregator::Count => AggregateFunctionEnum::Count,
+            Aggregator::Dev => AggregateFunctionEnum::StddevPop,
+            Aggregator::None => {
+                return error::InvalidOpentsdbQuerySnafu {
+                    reason: "aggregator 'none' is not allowed in downsample",
+                }
+                .fail()
+            }
+        })
+    }
+
+    /// Aggregates values of the same timestamp from different series.
+    fn aggregate(&self, values: &[f64]) -> f64 {
+        let count = values.len() as f64;
+        match self {
+            Aggregator::Sum => values.iter().sum(),
+            Aggregator::Avg => values.iter().sum::<f64>() / count,
+            Aggregator::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            Aggregator::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Aggregator::Count => count,
+            Aggregator::Dev => {
+                let mean = values.iter().sum::<f64>() / count;
+                (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count).sqrt()
+            }
+            // Never aggregated, each series is returned individually.
+            Aggregator::None => unreachable!(),
+        }
+    }
+}
+
+/// Downsampling like `1m-avg`, buckets data points of each series by `interval_ms`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Downsample {
+    pub interval_ms: i64,
+    pub aggregator: Aggregator,
+}
+
+impl Downsample {
+    fn parse(spec: &str) -> Result<Self> {
+        let parts = spec.split('-').collect::<Vec<_>>();
+        let invalid = || {
+            error::InvalidOpentsdbQuerySnafu {
+                reason: format!("invalid downsample: {spec}"),
+            }
+            .build()
+        };
+        if parts.len() < 2 || parts.len() > 3 {
+            return Err(invalid());
+        }
+        // Only the default "none" fill policy is supported.
+        if parts.len() == 3 && parts[2] != "none" {
+            return error::InvalidOpentsdbQuerySnafu {
+                reason: format!("unsupported downsample fill policy: {}", parts[2]),
+            }
+            .fail();
+        }
+        let interval_ms = parse_duration_millis(parts[0]).ok_or_else(invalid)?;
+        let aggregator = Aggregator::parse(parts[1])?;
+        let _ = aggregator.as_df_aggregate()?;
+        Ok(Downsample {
+            interval_ms,
+            aggregator,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterKind {
+    LiteralOr(Vec<String>),
+    NotLiteralOr(Vec<String>),
+    /// Glob like pattern where `*` matches any characters.
+    Wildcard(String),
+    Regexp(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagFilter {
+    pub tagk: String,
+    pub kind: FilterKind,
+    pub group_by: bool,
+}
+
+impl TagFilter {
+    fn new(tagk: &str, filter_type: &str, filter: &str, group_by: bool) -> Result<Self> {
+        let literals = || filter.split('|').map(|s| s.to_string()).collect();
+        let kind = match filter_type {
+            "literal_or" => FilterKind::LiteralOr(literals()),
+            "not_literal_or" => FilterKind::NotLiteralOr(literals()),
+            "wildcard" => FilterKind::Wildcard(filter.to_string()),
+            "regexp" => FilterKind::Regexp(filter.to_string()),
+            _ => {
+                return error::InvalidOpentsdbQuerySnafu {
+                    reason: format!("unsupported filter type: {filter_type}"),
+                }
+                .fail()
+            }
+        };
+        Ok(TagFilter {
+            tagk: tagk.to_string(),
+            kind,
+            group_by,
+        })
+    }
+
+    /// Parses a filter in the `tags` map or the `{tagk=filter}` of a metric query,
+    /// e.g. `*`, `web01|web02`, `wildcard(web*)` or `regexp(web[0-9]+)`.
+    fn parse_tag(tagk: &str, value: &str, group_by: bool) -> Result<Self> {
+        if let Some((filter_type, rest)) = value.split_once('(') {
+            if let Some(filter) = rest.strip_suffix(')') {
+                return Self::new(tagk, filter_type, filter, group_by);
+            }
+        }
+        if value.contains('*') {
+            Self::new(tagk, "wildcard", value, group_by)
+        } else {
+            Self::new(tagk, "literal_or", value, group_by)
+        }
+    }
+
+    fn to_expr(&self) -> Expr {
+        let column = column(&self.tagk);
+        match &self.kind {
+            FilterKind::LiteralOr(values) => {
+                column.in_list(values.iter().map(|v| lit(v.as_str())).collect(), false)
+            }
+            FilterKind::NotLiteralOr(values) => {
+                column.in_list(values.iter().map(|v| lit(v.as_str())).collect(), true)
+            }
+            FilterKind::Wildcard(pattern) if pattern == "*" => column.is_not_null(),
+            FilterKind::Wildcard(pattern) => {
+                let regex = pattern
+                    .split('*')
+                    .map(regex::escape)
+                    .collect::<Vec<_>>()
+                    .join(".*");
+                regexp_match(vec![column, lit(format!("^{regex}$"))]).is_not_null()
+            }
+            FilterKind::Regexp(regex) => {
+                regexp_match(vec![column, lit(regex.clone())]).is_not_null()
+            }
+        }
+    }
+}
+
+/// A validated OpenTSDB sub query with resolved time range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpentsdbQuery {
+    pub start: i64,
+    pub end: i64,
+    pub metric: String,
+    pub aggregator: Aggregator,
+    pub downsample: Option<Downsample>,
+    pub rate: bool,
+    pub filters: Vec<TagFilter>,
+}
+
+/// The JSON body of `/api/query` requests.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRequest {
+    pub start: TimeValue,
+    #[serde(default)]
+    pub end: Option<TimeValue>,
+    pub queries: Vec<SubQueryRequest>,
+    #[serde(default)]
+    pub ms_resolution: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum TimeValue {
+    Integer(i64),
+    String(String),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubQueryRequest {
+    pub aggregator: String,
+    pub metric: String,
+    #[serde(default)]
+    pub rate: bool,
+    #[serde(default)]
+    pub downsample: Option<String>,
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+    #[serde(default)]
+    pub filters: Vec<FilterRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FilterRequest {
+    #[serde(rename = "type")]
+    pub filter_type: String,
+    pub tagk: String,
+    pub filter: String,
+    #[serde(default, rename = "groupBy")]
+    pub group_by: bool,
+}
+
+impl QueryRequest {
+    /// Validates the request and resolves its time range against `now`, in milliseconds.
+    pub fn into_queries(self, now: i64) -> Result<Vec<OpentsdbQuery>> {
+        let start = parse_time(&self.start, now)?;
+        let end = match &self.end {
+            Some(end) => parse_time(end, now)?,
+            None => now,
+        };
+
+        self.queries
+            .into_iter()
+            .map(|q| {
+                let mut filters = q
+                    .tags
+                    .iter()
+                    .map(|(k, v)| TagFilter::parse_tag(k, v, true))
+                    .collect::<Result<Vec<_>>>()?;
+                for f in &q.filters {
+                    filters.push(TagFilter::new(
+                        &f.tagk,
+                        &f.filter_type,
+                        &f.filter,
+                        f.group_by,
+                    )?);
+                }
+                Ok(OpentsdbQuery {
+                    start,
+                    end,
+                    metric: q.metric,
+                    aggregator: Aggregator::parse(&q.aggregator)?,
+                    downsample: q.downsample.as_deref().map(Downsample::parse).transpose()?,
+                    rate: q.rate,
+                    filters,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parses the `m` parameter of GET `/api/query`, formatted as
+/// `<aggregator>:[<downsample>:][rate:]<metric>[{<tagk>=<filter>,...}][{<tagk>=<filter>,...}]`.
+/// Filters in the first braces group by their tag, those in the second don't.
+pub fn parse_metric_query(m: &str) -> Result<SubQueryRequest> {
+    let invalid = || {
+        error::InvalidOpentsdbQuerySnafu {
+            reason: format!("invalid metric query: {m}"),
+        }
+        .build()
+    };
+
+    let (head, braces) = match m.find('{') {
+        Some(idx) => m.split_at(idx),
+        None => (m, ""),
+    };
+    let parts = head.split(':').collect::<Vec<_>>();
+    if parts.len() < 2 {
+        return Err(invalid());
+    }
+
+    let mut request = SubQueryRequest {
+        aggregator: parts[0].to_string(),
+        metric: parts[parts.len() - 1].to_string(),
+        rate: false,
+        downsample: None,
+        tags: HashMap::new(),
+        filters: vec![],
+    };
+    for part in &parts[1..parts.len() - 1] {
+        if *part == "rate" || part.starts_with("rate{") {
+            request.rate = true;
+        } else if part.contains('-') {
+            request.downsample = Some(part.to_string());
+        } else {
+            return Err(invalid());
+        }
+    }
+
+    let mut rest = braces;
+    let mut group_by = true;
+    while !rest.is_empty() {
+        let inner = rest.strip_prefix('{').ok_or_else(invalid)?;
+        let close = inner.find('}').ok_or_else(invalid)?;
+        for pair in inner[..close].split(',').filter(|p| !p.is_empty()) {
+            let (tagk, value) = pair.split_once('=').ok_or_else(invalid)?;
+            let filter = TagFilter::parse_tag(tagk, value, group_by)?;
+            request.filters.push(FilterRequest {
+                filter_type: match filter.kind {
+                    FilterKind::LiteralOr(_) => "literal_or",
+                    FilterKind::NotLiteralOr(_) => "not_literal_or",
+                    FilterKind::Wildcard(_) => "wildcard",
+                    FilterKind::Regexp(_) => "regexp",
+                }
+                .to_string(),
+                tagk: tagk.to_string(),
+                filter: match value.split_once('(') {
+                    Some((_, f)) if f.ends_with(')') => f[..f.len() - 1].to_string(),
+                    _ => value.to_string(),
+                },
+                group_by,
+            });
+        }
+        rest = &inner[close + 1..];
+        group_by = false;
+    }
+    Ok(request)
+}
+
+/// Parses an absolute or relative (like `1h-ago`) OpenTSDB time into milliseconds.
+fn parse_time(value: &TimeValue, now: i64) -> Result<i64> {
+    let s = match value {
+        TimeValue::Integer(t) => return Ok(DataPoint::timestamp_to_millis(*t)),
+        TimeValue::String(s) => s.trim(),
+    };
+    let invalid = || {
+        error::InvalidOpentsdbQuerySnafu {
+            reason: format!("invalid time: {s}"),
+        }
+        .build()
+    };
+
+    if let Some(relative) = s.strip_suffix("-ago") {
+        return parse_duration_millis(relative)
+            .map(|d| now - d)
+            .ok_or_else(invalid);
+    }
+    if let Ok(t) = s.parse::<i64>() {
+        return Ok(DataPoint::timestamp_to_millis(t));
+    }
+    for format in [
+        "%Y/%m/%d-%H:%M:%S",
+        "%Y/%m/%d %H:%M:%S",
+        "%Y/%m/%d-%H:%M",
+        "%Y/%m/%d %H:%M",
+    ] {
+        if let Ok(t) = NaiveDateTime::parse_from_str(s, format) {
+            return Ok(t.timestamp_millis());
+        }
+    }
+    NaiveDate::parse_from_str(s, "%Y/%m/%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|t| t.timestamp_millis())
+        .ok_or_else(invalid)
+}
+
+/// Parses durations like `30s`, `1m` or `2h` into milliseconds.
+fn parse_duration_millis(s: &str) -> Option<i64> {
+    let idx = s.find(|c: char| !c.is_ascii_digit())?;
+    let (value, unit) = s.split_at(idx);
+    let value = value.parse::<i64>().ok()?;
+    let factor = match unit {
+        "ms" => 1,
+        "s" => MILLIS_PER_SECOND,
+        "m" => MILLIS_PER_MINUTE,
+        "h" => MILLIS_PER_HOUR,
+        "d" => MILLIS_PER_DAY,
+        "w" => 7 * MILLIS_PER_DAY,
+        "n" => 30 * MILLIS_PER_DAY,
+        "y" => 365 * MILLIS_PER_DAY,
+        _ => return None,
+    };
+    value.checked_mul(factor).filter(|d| *d > 0)
+}
+
+fn column(name: &str) -> Expr {
+    Expr::Column(Column::from_name(name))
+}
+
+/// Creates a logical plan that filters and downsamples every series of the
+/// metric. `tag_columns` are all tag columns of the metric's table.
+pub fn query_to_plan(
+    dataframe: DataFrame,
+    tag_columns: &[String],
+    q: &OpentsdbQuery,
+) -> Result<LogicalPlan> {
+    let DataFrame::DataFusion(dataframe) = dataframe;
+
+    let mut conditions = Vec::with_capacity(q.filters.len() + 2);
+    conditions.push(
+        column(OPENTSDB_TIMESTAMP_COLUMN_NAME)
+            .gt_eq(lit(ScalarValue::TimestampMillisecond(Some(q.start), None))),
+    );
+    conditions.push(
+        column(OPENTSDB_TIMESTAMP_COLUMN_NAME)
+            .lt_eq(lit(ScalarValue::TimestampMillisecond(Some(q.end), None))),
+    );
+    for filter in &q.filters {
+        if !tag_columns.contains(&filter.tagk) {
+            return error::InvalidOpentsdbQuerySnafu {
+                reason: format!("no such tag '{}' in metric {}", filter.tagk, q.metric),
+            }
+            .fail();
+        }
+        conditions.push(filter.to_expr());
+    }
+    // Safety: conditions MUST not be empty, reduce always return Some(expr).
+    let conditions = conditions.into_iter().reduce(Expr::and).unwrap();
+
+    let mut dataframe = dataframe
+        .filter(conditions)
+        .context(error::DataFrameSnafu)?;
+
+    if let Some(downsample) = &q.downsample {
+        let interval = lit(ScalarValue::IntervalMonthDayNano(Some(
+            IntervalMonthDayNanoType::make_value(0, 0, downsample.interval_ms * 1_000_000),
+        )));
+        let source = Expr::Cast(Cast::new(
+            Box::new(column(OPENTSDB_TIMESTAMP_COLUMN_NAME)),
+            ArrowDataType::Timestamp(ArrowTimeUnit::Nanosecond, None),
+        ));
+        let origin = lit(ScalarValue::TimestampNanosecond(Some(0), None));
+        let bucket = Expr::ScalarFunction(ScalarFunction {
+            fun: BuiltinScalarFunction::DateBin,
+            args: vec![interval, source, origin],
+        });
+
+        let mut group_exprs = vec![bucket.alias(OPENTSDB_TIMESTAMP_COLUMN_NAME)];
+        group_exprs.extend(tag_columns.iter().map(|c| column(c)));
+        let aggr_expr = Expr::AggregateFunction(AggregateFunction {
+            fun: downsample.aggregator.as_df_aggregate()?,
+            args: vec![column(OPENTSDB_FIELD_COLUMN_NAME)],
+            distinct: false,
+            filter: None,
+            order_by: None,
+        })
+        .alias(OPENTSDB_FIELD_COLUMN_NAME);
+
+        dataframe = dataframe
+            .aggregate(group_exprs, vec![aggr_expr])
+            .context(error::DataFrameSnafu)?;
+    }
+
+    let mut projection = vec![
+        column(OPENTSDB_TIMESTAMP_COLUMN_NAME),
+        column(OPENTSDB_FIELD_COLUMN_NAME),
+    ];
+    projection.extend(tag_columns.iter().map(|c| column(c)));
+    let mut sort_exprs = tag_columns
+        .iter()
+        .map(|c| column(c).sort(true, true))
+        .collect::<Vec<_>>();
+    sort_exprs.push(column(OPENTSDB_TIMESTAMP_COLUMN_NAME).sort(true, false));
+
+    let dataframe = dataframe
+        .select(projection)
+        .and_then(|df| df.sort(sort_exprs))
+        .context(error::DataFrameSnafu)?;
+
+    Ok(LogicalPlan::DfPlan(dataframe.into_parts().1))
+}
+
+/// A result of `/api/query`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpentsdbQueryResult {
+    pub metric: String,
+    pub tags: BTreeMap<String, String>,
+    pub aggregate_tags: Vec<String>,
+    /// Data points keyed by timestamp in milliseconds.
+    pub dps: BTreeMap<i64, f64>,
+}
+
+impl OpentsdbQueryResult {
+    /// Converts the timestamps of data points into seconds, which is the default
+    /// resolution of OpenTSDB responses.
+    pub fn into_second_resolution(mut self) -> Self {
+        self.dps = self
+            .dps
+            .into_iter()
+            .map(|(ts, v)| (ts.div_euclid(MILLIS_PER_SECOND), v))
+            .collect();
+        self
+    }
+}
+
+/// A series of one tag combination.
+struct Series {
+    tags: BTreeMap<String, String>,
+    points: Vec<(i64, f64)>,
+}
+
+impl Series {
+    fn rate(&mut self) {
+        self.points = self
+            .points
+            .windows(2)
+            .filter(|w| w[1].0 > w[0].0)
+            .map(|w| {
+                let (t0, v0) = w[0];
+                let (t1, v1) = w[1];
+                (t1, (v1 - v0) * MILLIS_PER_SECOND as f64 / (t1 - t0) as f64)
+            })
+            .collect();
+    }
+}
+
+/// Collects the output of the plan from [query_to_plan], then applies rate and
+/// the aggregator of the query.
+pub async fn output_to_results(
+    q: &OpentsdbQuery,
+    tag_columns: &[String],
+    output: Output,
+) -> Result<Vec<OpentsdbQueryResult>> {
+    let recordbatches = match output {
+        Output::Stream(stream) => RecordBatches::try_collect(stream)
+            .await
+            .context(error::CollectRecordbatchSnafu)?,
+        Output::RecordBatches(recordbatches) => recordbatches,
+        Output::AffectedRows(_) => {
+            return error::UnexpectedResultSnafu {
+                reason: "expect records for OpenTSDB query",
+            }
+            .fail()
+        }
+    };
+
+    let mut series = recordbatches_to_series(tag_columns, recordbatches)?;
+    if q.rate {
+        series.iter_mut().for_each(Series::rate);
+    }
+    Ok(aggregate_series(q, series))
+}
+
+fn recordbatches_to_series(
+    tag_columns: &[String],
+    recordbatches: RecordBatches,
+) -> Result<Vec<Series>> {
+    let schema = recordbatches.schema();
+    let ts_index = schema
+        .column_index_by_name(OPENTSDB_TIMESTAMP_COLUMN_NAME)
+        .context(error::UnexpectedResultSnafu {
+            reason: "missing greptime_timestamp column in query result",
+        })?;
+    let value_index = schema
+        .column_index_by_name(OPENTSDB_FIELD_COLUMN_NAME)
+        .context(error::UnexpectedResultSnafu {
+            reason: "missing greptime_value column in query result",
+        })?;
+    let tag_indices = tag_columns
+        .iter()
+        .filter_map(|c| schema.column_index_by_name(c).map(|i| (c, i)))
+        .collect::<Vec<_>>();
+
+    let mut series: Vec<Series> = vec![];
+    let mut series_index: HashMap<Vec<Option<String>>, usize> = HashMap::new();
+    for recordbatch in recordbatches.take() {
+        for row in recordbatch.rows() {
+            let Some(ts) = row[ts_index]
+                .as_timestamp()
+                .and_then(|ts| ts.convert_to(common_time::timestamp::TimeUnit::Millisecond))
+            else {
+                continue;
+            };
+            let Some(value) = value_as_f64(&row[value_index]) else { continue };
+
+            let key = tag_indices
+                .iter()
+                .map(|(_, i)| (!row[*i].is_null()).then(|| row[*i].to_string()))
+                .collect::<Vec<_>>();
+            let idx = *series_index.entry(key).or_insert_with_key(|key| {
+                let tags = tag_indices
+                    .iter()
+                    .zip(key.iter())
+                    .filter_map(|((name, _), value)| {
+                        value.as_ref().map(|v| (name.to_string(), v.clone()))
+                    })
+                    .collect();
+                series.push(Series {
+                    tags,
+                    points: vec![],
+                });
+                series.len() - 1
+            });
+            series[idx].points.push((ts.value(), value));
+        }
+    }
+    Ok(series)
+}
+
+fn value_as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float64(v) => Some(v.0),
+        Value::Float32(v) => Some(v.0 as f64),
+        Value::Int8(v) => Some(*v as f64),
+        Value::Int16(v) => Some(*v as f64),
+        Value::Int32(v) => Some(*v as f64),
+        Value::Int64(v) => Some(*v as f64),
+        Value::UInt8(v) => Some(*v as f64),
+        Value::UInt16(v) => Some(*v as f64),
+        Value::UInt32(v) => Some(*v as f64),
+        Value::UInt64(v) => Some(*v as f64),
+        _ => None,
+    }
+}
+
+/// Groups series by the group-by tags of the query and aggregates each group.
+fn aggregate_series(q: &OpentsdbQuery, series: Vec<Series>) -> Vec<OpentsdbQueryResult> {
+    if q.aggregator == Aggregator::None {
+        return series
+            .into_iter()
+            .map(|s| OpentsdbQueryResult {
+                metric: q.metric.clone(),
+                tags: s.tags,
+                aggregate_tags: vec![],
+                dps: s.points.into_iter().collect(),
+            })
+            .collect();
+    }
+
+    let group_by_tags = q
+        .filters
+        .iter()
+        .filter(|f| f.group_by)
+        .map(|f| f.tagk.as_str())
+        .collect::<Vec<_>>();
+    let mut groups: BTreeMap<Vec<Option<&String>>, Vec<&Series>> = BTreeMap::new();
+    for s in &series {
+        let key = group_by_tags.iter().map(|t| s.tags.get(*t)).collect();
+        groups.entry(key).or_default().push(s);
+    }
+
+    groups
+        .into_values()
+        .map(|group| {
+            // Tags having the same value in all series are kept, others are aggregated.
+            let mut tags = group[0].tags.clone();
+            let mut aggregate_tags = group
+                .iter()
+                .flat_map(|s| s.tags.keys())
+                .filter(|k| group.iter().any(|s| s.tags.get(*k) != tags.get(*k)))
+                .cloned()
+                .collect::<Vec<_>>();
+            aggregate_tags.sort();
+            aggregate_tags.dedup();
+            tags.retain(|k, _| !aggregate_tags.contains(k));
+
+            let mut points: BTreeMap<i64, Vec<f64>> = BTreeMap::new();
+            for s in &group {
+                for (ts, v) in &s.points {
+                    points.entry(*ts).or_default().push(*v);
+                }
+            }
+            OpentsdbQueryResult {
+                metric: q.metric.clone(),
+                tags,
+                aggregate_tags,
+                dps: points
+                    .into_iter()
+                    .map(|(ts, values)| (ts, q.aggregator.aggregate(&values)))
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuggestType {
+    Metrics,
+    Tagk,
+    Tagv,
+}
+
+/// A request of `/api/suggest`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SuggestRequest {
+    #[serde(rename = "type")]
+    pub suggest_type: SuggestType,
+    #[serde(default)]
+    pub q: String,
+    #[serde(default = "default_suggest_max")]
+    pub max: usize,
+}
+
+fn default_suggest_max() -> usize {
+    DEFAULT_SUGGEST_MAX
+}
+
+/// Sorts and deduplicates suggestions starting with the requested prefix.
+pub fn collect_suggestions(
+    request: &SuggestRequest,
+    candidates: impl IntoIterator<Item = String>,
+) -> Vec<String> {
+    let mut suggestions = candidates
+        .into_iter()
+        .filter(|c| c.starts_with(&request.q))
+        .collect::<Vec<_>>();
+    suggestions.sort();
+    suggestions.dedup();
+    suggestions.truncate(request.max);
+    suggestions
+}
+
+/// Name of the column of tag values selected by [tag_values_to_plan].
+const TAG_VALUE_COLUMN_NAME: &str = "tagv";
+
+/// Creates a logical plan selecting the first `limit` distinct values, in
+/// order, of all the tag columns that start with `prefix`. Values of all tag
+/// columns are collected by one plan, instead of one for each column.
+pub fn tag_values_to_plan(
+    dataframe: DataFrame,
+    tag_columns: &[String],
+    prefix: &str,
+    limit: usize,
+) -> Result<LogicalPlan> {
+    let DataFrame::DataFusion(dataframe) = dataframe;
+
+    let tag_value = column(TAG_VALUE_COLUMN_NAME);
+    let mut condition = tag_value.clone().is_not_null();
+    if !prefix.is_empty() {
+        let regex = format!("^{}", regex::escape(prefix));
+        condition = condition.and(regexp_match(vec![tag_value.clone(), lit(regex)]).is_not_null());
+    }
+
+    let mut union = None;
+    for tagk in tag_columns {
+        let values = dataframe
+            .clone()
+            .select(vec![Expr::Cast(Cast::new(
+                Box::new(column(tagk)),
+                ArrowDataType::Utf8,
+            ))
+            .alias(TAG_VALUE_COLUMN_NAME)])
+            .and_then(|df| df.filter(condition.clone()))
+            .context(error::DataFrameSnafu)?;
+        union = Some(match union {
+            None => values,
+            Some(union) => union.union(values).context(error::DataFrameSnafu)?,
+        });
+    }
+    let Some(union) = union else {
+        return error::InvalidQuerySnafu {
+            reason: "no tag columns to suggest values from",
+        }
+        .fail();
+    };
+
+    let dataframe = union
+        .distinct()
+        .and_then(|df| df.sort(vec![tag_value.sort(true, false)]))
+        .and_then(|df| df.limit(0, Some(limit)))
+        .context(error::DataFrameSnafu)?;
+    Ok(LogicalPlan::DfPlan(dataframe.into_parts().1))
+}
+
+/// Collects the string values of the first column in output.
+pub async fn output_to_strings(output: Output) -> Result<Vec<String>> {
+    let recordbatches = match output {
+        Output::Stream(stream) => RecordBatches::try_collect(stream)
+            .await
+            .context(error::CollectRecordbatchSnafu)?,
+        Output::RecordBatches(recordbatches) => recordbatches,
+        Output::AffectedRows(_) => return Ok(vec![]),
+    };
+    let mut values = vec![];
+    for recordbatch in recordbatches.take() {
+        for row in recordbatch.rows() {
+            if let Some(Value::String(s)) = row.into_iter().next() {
+                values.push(s.as_utf8().to_string());
+            }
+        }
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_time() {
+        let now = 1_690_000_000_000;
+        assert_eq!(
+            1_346_846_400_000,
+            parse_time(&TimeValue::Integer(1_346_846_400), now).unwrap()
+        );
+        assert_eq!(
+            now - MILLIS_PER_HOUR,
+            parse_time(&TimeValue::String("1h-ago".to_string()), now).unwrap()
+        );
+        assert_eq!(
+            1_346_846_400_000,
+            parse_time(&TimeValue::String("2012/09/05-12:00:00".to_string()), now).unwrap()
+        );
+        assert_eq!(
+            1_346_803_200_000,
+            parse_time(&TimeValue::String("2012/09/05".to_string()), now).unwrap()
+        );
+        assert!(parse_time(&TimeValue::String("1x-ago".to_string()), now).is_err());
+        assert!(parse_time(&TimeValue::String("yesterday".to_string()), now).is_err());
+    }
+
+    #[test]
+    fn test_parse_downsample() {
+        assert_eq!(
+            Downsample {
+                interval_ms: MILLIS_PER_MINUTE,
+                aggregator: Aggregator::Avg,
+            },
+            Downsample::parse("1m-avg").unwrap()
+        );
+        assert_eq!(
+            Downsample {
+                interval_ms: 30 * MILLIS_PER_SECOND,
+                aggregator: Aggregator::Sum,
+            },
+            Downsample::parse("30s-sum-none").unwrap()
+        );
+        assert!(Downsample::parse("1m").is_err());
+        assert!(Downsample::parse("1m-none").is_err());
+        assert!(Downsample::parse("1m-avg-zero").is_err());
+    }
+
+    #[test]
+    fn test_parse_metric_query() {
+        let request =
+            parse_metric_query("sum:1m-avg:rate:sys.cpu.user{host=web*,dc=lga|sjc}{env=prod}")
+                .unwrap();
+        assert_eq!("sum", request.aggregator);
+        assert_eq!("sys.cpu.user", request.metric);
+        assert!(request.rate);
+        assert_eq!(Some("1m-avg".to_string()), request.downsample);
+
+        let filters = request
+            .filters
+            .iter()
+            .map(|f| TagFilter::new(&f.tagk, &f.filter_type, &f.filter, f.group_by).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                TagFilter {
+                    tagk: "host".to_string(),
+                    kind: FilterKind::Wildcard("web*".to_string()),
+                    group_by: true,
+                },
+                TagFilter {
+                    tagk: "dc".to_string(),
+                    kind: FilterKind::LiteralOr(vec!["lga".to_string(), "sjc".to_string()]),
+                    group_by: true,
+                },
+                TagFilter {
+                    tagk: "env".to_string(),
+                    kind: FilterKind::LiteralOr(vec!["prod".to_string()]),
+                    group_by: false,
+                },
+            ],
+            filters
+        );
+
+        let request = parse_metric_query("avg:sys.cpu.user{host=regexp(web[0-9])}").unwrap();
+        assert_eq!("web[0-9]", request.filters[0].filter);
+        assert_eq!("regexp", request.filters[0].filter_type);
+
+        assert!(parse_metric_query("sys.cpu.user").is_err());
+        assert!(parse_metric_query("sum:foo:sys.cpu.user").is_err());
+        assert!(parse_metric_query("sum:sys.cpu.user{host=a").is_err());
+    }
+
+    #[test]
+    fn test_aggregate_series() {
+        let query = OpentsdbQuery {
+            start: 0,
+            end: 10_000,
+            metric: "sys.cpu".to_string(),
+            aggregator: Aggregator::Sum,
+            downsample: None,
+            rate: false,
+            filters: vec![TagFilter::parse_tag("dc", "*", true).unwrap()],
+        };
+        let new_series = |host: &str, dc: &str, points: Vec<(i64, f64)>| Series {
+            tags: BTreeMap::from([
+                ("host".to_string(), host.to_string()),
+                ("dc".to_string(), dc.to_string()),
+            ]),
+            points,
+        };
+        let series = vec![
+            new_series("a", "lga", vec![(1000, 1.0), (2000, 2.0)]),
+            new_series("b", "lga", vec![(1000, 3.0)]),
+            new_series("c", "sjc", vec![(1000, 5.0)]),
+        ];
+
+        let results = aggregate_series(&query, series);
+        assert_eq!(
+            vec![
+                OpentsdbQueryResult {
+                    metric: "sys.cpu".to_string(),
+                    tags: BTreeMap::from([("dc".to_string(), "lga".to_string())]),
+                    aggregate_tags: vec!["host".to_string()],
+                    dps: BTreeMap::from([(1000, 4.0), (2000, 2.0)]),
+                },
+                OpentsdbQueryResult {
+                    metric: "sys.cpu".to_string(),
+                    tags: BTreeMap::from([
+                        ("dc".to_string(), "sjc".to_string()),
+                        ("host".to_string(), "c".to_string())
+                    ]),
+                    aggregate_tags: vec![],
+                    dps: BTreeMap::from([(1000, 5.0)]),
+                },
+            ],
+            results
+        );
+        assert_eq!(
+            r#"{"metric":"sys.cpu","tags":{"dc":"sjc","host":"c"},"aggregateTags":[],"dps":{"1":5.0}}"#,
+            serde_json::to_string(&results[1].clone().into_second_resolution()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rate() {
+        let mut series = Series {
+            tags: BTreeMap::new(),
+            points: vec![(0, 10.0), (2000, 30.0), (4000, 30.0)],
+        };
+        series.rate();
+        assert_eq!(vec![(2000, 10.0), (4000, 0.0)], series.points);
+    }
+
+    #[test]
+    fn test_collect_suggestions() {
+        let request = SuggestRequest {
+            suggest_type: SuggestType::Metrics,
+            q: "sys".to_string(),
+            max: 2,
+        };
+        let candidates = ["sys.mem", "sys.cpu", "net.in", "sys.cpu", "sys.disk"]
+            .into_iter()
+            .map(|s| s.to_string());
+        assert_eq!(
+            vec!["sys.cpu".to_string(), "sys.disk".to_string()],
+            collect_suggestions(&request, candidates)
+        );
+    }
+}
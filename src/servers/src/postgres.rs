@@ -22,6 +22,8 @@ pub(crate) const METADATA_DATABASE: &str = "database";
 pub(crate) const METADATA_CATALOG: &str = "catalog";
 /// key to store our parsed schema
 pub(crate) const METADATA_SCHEMA: &str = "schema";
+/// key of the command-line options sent at startup, e.g. `-c read_preference=follower`
+pub(crate) const METADATA_OPTIONS: &str = "options";
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -24,7 +24,7 @@ use pgwire::error::{ErrorInfo, PgWireError, PgWireResult};
 use pgwire::messages::response::ErrorResponse;
 use pgwire::messages::startup::Authentication;
 use pgwire::messages::{PgWireBackendMessage, PgWireFrontendMessage};
-use session::context::UserInfo;
+use session::context::{ReadPreference, UserInfo};
 use session::Session;
 use snafu::IntoError;
 
@@ -128,6 +128,29 @@ where
     if let Some(username) = client.metadata().get(super::METADATA_USER) {
         session.set_user_info(UserInfo::new(username));
     }
+    if let Some(Ok(read_preference)) = read_preference_hint(client).map(str::parse) {
+        ctx.set_read_preference(read_preference);
+    }
+}
+
+/// Finds the read preference set by `-c read_preference=<value>` in the startup options.
+fn read_preference_hint<C>(client: &C) -> Option<&str>
+where
+    C: ClientInfo,
+{
+    let options = client.metadata().get(super::METADATA_OPTIONS)?;
+    let mut args = options.split_whitespace();
+    let mut read_preference = None;
+    while let Some(arg) = args.next() {
+        let setting = match arg {
+            "-c" => args.next(),
+            _ => arg.strip_prefix("-c").or_else(|| arg.strip_prefix("--")),
+        };
+        if let Some(value) = setting.and_then(|x| x.strip_prefix("read_preference=")) {
+            read_preference = Some(value);
+        }
+    }
+    read_preference
 }
 
 #[async_trait]
@@ -165,6 +188,14 @@ impl StartupHandler for PostgresServerHandler {
                     }
                 }
 
+                // check if the read preference hint is valid
+                let read_preference =
+                    read_preference_hint(client).map(str::parse::<ReadPreference>);
+                if let Some(Err(msg)) = read_preference {
+                    send_error(client, "FATAL", "22023", msg).await?;
+                    return Ok(());
+                }
+
                 if self.login_verifier.user_provider.is_some() {
                     client.set_state(PgWireConnectionState::AuthenticationInProgress);
                     client
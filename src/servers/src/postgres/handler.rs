@@ -16,10 +16,12 @@ use std::ops::Deref;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use common_error::prelude::{ErrorExt, StatusCode};
 use common_query::Output;
 use common_recordbatch::error::Result as RecordBatchResult;
 use common_recordbatch::RecordBatch;
 use common_telemetry::timer;
+use common_telemetry::tracing::{info_span, Instrument};
 use datatypes::prelude::{ConcreteDataType, Value};
 use datatypes::schema::{Schema, SchemaRef};
 use futures::{future, stream, Stream, StreamExt};
@@ -63,6 +65,7 @@ impl SimpleQueryHandler for PostgresServerHandler {
         let outputs = self
             .query_handler
             .do_query(query, self.session.context())
+            .instrument(info_span!("postgres_query", query))
             .await;
 
         let mut results = Vec::with_capacity(outputs.len());
@@ -93,14 +96,20 @@ fn output_to_query_response<'a>(
             let schema = recordbatches.schema();
             recordbatches_to_query_response(recordbatches.as_stream(), schema, field_format)
         }
-        Err(e) => Ok(Response::Error(Box::new(ErrorInfo::new(
-            "ERROR".to_string(),
-            "XX000".to_string(),
-            e.to_string(),
-        )))),
+        Err(e) => Ok(Response::Error(Box::new(error_info(&e)))),
     }
 }
 
+/// Builds the error response with SQLSTATE `53200` (out of memory) if the query
+/// exceeds its memory limit, or `XX000` (internal error) otherwise.
+fn error_info(e: &dyn ErrorExt) -> ErrorInfo {
+    let code = match e.status_code() {
+        StatusCode::ResourcesExhausted => "53200",
+        _ => "XX000",
+    };
+    ErrorInfo::new("ERROR".to_string(), code.to_string(), e.to_string())
+}
+
 fn recordbatches_to_query_response<'a, S>(
     recordbatches_stream: S,
     schema: SchemaRef,
@@ -122,7 +131,10 @@ where
                 rb.rows().map(Ok).collect::<Vec<_>>().into_iter(),
             )
             .boxed(),
-            Err(e) => stream::once(future::err(PgWireError::ApiError(Box::new(e)))).boxed(),
+            Err(e) => {
+                let error = PgWireError::UserError(Box::new(error_info(&e)));
+                stream::once(future::err(error)).boxed()
+            }
         })
         .flatten() // flatten into stream<result<row>>
         .map(move |row| {
@@ -378,6 +390,7 @@ impl ExtendedQueryHandler for PostgresServerHandler {
         let output = self
             .query_handler
             .do_query(&sql, self.session.context())
+            .instrument(info_span!("postgres_query", query = %sql))
             .await
             .remove(0);
 
@@ -73,14 +73,14 @@ impl PostgresServer {
         accepting_stream.for_each(move |tcp_stream| {
             let io_runtime = io_runtime.clone();
             let tls_acceptor = tls_acceptor.clone();
-            let mut handler = handler.make();
+            let handler = handler.make();
             async move {
                 match tcp_stream {
                     Err(error) => error!("Broken pipe: {}", error), // IoError doesn't impl ErrorExt.
                     Ok(io_stream) => {
                         match io_stream.peer_addr() {
                             Ok(addr) => {
-                                handler.session.mut_conn_info().client_addr = Some(addr);
+                                handler.session.set_client_addr(addr);
                                 debug!("PostgreSQL client coming from {}", addr)
                             }
                             Err(e) => warn!("Failed to get PostgreSQL client addr, err: {}", e),
@@ -20,10 +20,11 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use axum::body::BoxBody;
 use axum::extract::{Path, Query, State};
-use axum::{middleware, routing, Form, Json, Router};
+use axum::{middleware, routing, Extension, Form, Json, Router};
 use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use common_error::prelude::ErrorExt;
 use common_error::status_code::StatusCode;
+use common_grpc::tracing_context::TracingContextLayer;
 use common_query::Output;
 use common_recordbatch::RecordBatches;
 use common_telemetry::info;
@@ -41,7 +42,7 @@ use query::parser::{PromQuery, DEFAULT_LOOKBACK_STRING};
 use schemars::JsonSchema;
 use serde::de::{self, MapAccess, Visitor};
 use serde::{Deserialize, Serialize};
-use session::context::{QueryContext, QueryContextRef};
+use session::context::{QueryContext, QueryContextRef, UserInfo};
 use snafu::{ensure, Location, OptionExt, ResultExt};
 use tokio::sync::oneshot::Sender;
 use tokio::sync::{oneshot, Mutex};
@@ -109,6 +110,7 @@ impl PromServer {
             // middlewares
             .layer(
                 ServiceBuilder::new()
+                    .layer(TracingContextLayer)
                     .layer(TraceLayer::new_for_http())
                     .layer(CompressionLayer::new())
                     // custom layer
@@ -426,6 +428,7 @@ pub struct InstantQuery {
 pub async fn instant_query(
     State(handler): State<PromHandlerRef>,
     Query(params): Query<InstantQuery>,
+    Extension(user_info): Extension<UserInfo>,
     Form(form_params): Form<InstantQuery>,
 ) -> Json<PromJsonResponse> {
     // Extract time from query string, or use current server time if not specified.
@@ -444,6 +447,7 @@ pub async fn instant_query(
     let (catalog, schema) = crate::parse_catalog_and_schema_from_client_database_name(db);
 
     let query_ctx = QueryContext::with(catalog, schema);
+    query_ctx.set_current_user(user_info);
 
     let result = handler.do_query(&prom_query, Arc::new(query_ctx)).await;
     let (metric_name, result_type) = match retrieve_metric_name_and_result_type(&prom_query.query) {
@@ -467,6 +471,7 @@ pub struct RangeQuery {
 pub async fn range_query(
     State(handler): State<PromHandlerRef>,
     Query(params): Query<RangeQuery>,
+    Extension(user_info): Extension<UserInfo>,
     Form(form_params): Form<RangeQuery>,
 ) -> Json<PromJsonResponse> {
     let prom_query = PromQuery {
@@ -480,6 +485,7 @@ pub async fn range_query(
     let (catalog, schema) = crate::parse_catalog_and_schema_from_client_database_name(db);
 
     let query_ctx = QueryContext::with(catalog, schema);
+    query_ctx.set_current_user(user_info);
 
     let result = handler.do_query(&prom_query, Arc::new(query_ctx)).await;
     let metric_name = match retrieve_metric_name_and_result_type(&prom_query.query) {
@@ -537,6 +543,7 @@ impl<'de> Deserialize<'de> for Matches {
 pub async fn labels_query(
     State(handler): State<PromHandlerRef>,
     Query(params): Query<LabelsQuery>,
+    Extension(user_info): Extension<UserInfo>,
     Form(form_params): Form<LabelsQuery>,
 ) -> Json<PromJsonResponse> {
     let mut queries = params.matches.0;
@@ -559,6 +566,7 @@ pub async fn labels_query(
     let db = &params.db.unwrap_or(DEFAULT_SCHEMA_NAME.to_string());
     let (catalog, schema) = crate::parse_catalog_and_schema_from_client_database_name(db);
     let query_ctx = Arc::new(QueryContext::with(catalog, schema));
+    query_ctx.set_current_user(user_info);
 
     let mut labels = HashSet::new();
     let _ = labels.insert(METRIC_NAME.to_string());
@@ -771,6 +779,7 @@ pub async fn label_values_query(
     State(handler): State<PromHandlerRef>,
     Path(label_name): Path<String>,
     Query(params): Query<LabelValueQuery>,
+    Extension(user_info): Extension<UserInfo>,
 ) -> Json<PromJsonResponse> {
     let queries = params.matches.0;
     if queries.is_empty() {
@@ -782,6 +791,7 @@ pub async fn label_values_query(
     let db = &params.db.unwrap_or(DEFAULT_SCHEMA_NAME.to_string());
     let (catalog, schema) = crate::parse_catalog_and_schema_from_client_database_name(db);
     let query_ctx = Arc::new(QueryContext::with(catalog, schema));
+    query_ctx.set_current_user(user_info);
 
     let mut label_values = HashSet::new();
 
@@ -879,6 +889,7 @@ pub struct SeriesQuery {
 pub async fn series_query(
     State(handler): State<PromHandlerRef>,
     Query(params): Query<SeriesQuery>,
+    Extension(user_info): Extension<UserInfo>,
     Form(form_params): Form<SeriesQuery>,
 ) -> Json<PromJsonResponse> {
     let mut queries: Vec<String> = params.matches.0;
@@ -900,6 +911,7 @@ pub async fn series_query(
     let db = &params.db.unwrap_or(DEFAULT_SCHEMA_NAME.to_string());
     let (catalog, schema) = super::parse_catalog_and_schema_from_client_database_name(db);
     let query_ctx = Arc::new(QueryContext::with(catalog, schema));
+    query_ctx.set_current_user(user_info);
 
     let mut series = Vec::new();
     for query in queries {
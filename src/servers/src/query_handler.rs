@@ -34,11 +34,15 @@ use common_query::Output;
 use session::context::QueryContextRef;
 
 use crate::error::Result;
+use crate::graphite::codec::Metric;
+use crate::influxdb::influxql::{InfluxqlSeries, InfluxqlStatement};
 use crate::influxdb::InfluxdbRequest;
 use crate::opentsdb::codec::DataPoint;
+use crate::opentsdb::query::{OpentsdbQuery, OpentsdbQueryResult, SuggestRequest};
 use crate::prometheus::Metrics;
 
 pub type OpentsdbProtocolHandlerRef = Arc<dyn OpentsdbProtocolHandler + Send + Sync>;
+pub type GraphiteProtocolHandlerRef = Arc<dyn GraphiteProtocolHandler + Send + Sync>;
 pub type InfluxdbLineProtocolHandlerRef = Arc<dyn InfluxdbLineProtocolHandler + Send + Sync>;
 pub type PrometheusProtocolHandlerRef = Arc<dyn PrometheusProtocolHandler + Send + Sync>;
 pub type ScriptHandlerRef = Arc<dyn ScriptHandler + Send + Sync>;
@@ -52,6 +56,15 @@ pub trait ScriptHandler {
         name: &str,
         params: HashMap<String, String>,
     ) -> Result<Output>;
+    /// Schedules the script to run at `schedule` and write its outputs into
+    /// `target_table`, or unschedules it if `schedule` is `None`.
+    async fn schedule_script(
+        &self,
+        schema: &str,
+        name: &str,
+        schedule: Option<&str>,
+        target_table: Option<&str>,
+    ) -> Result<()>;
 }
 
 #[async_trait]
@@ -59,6 +72,13 @@ pub trait InfluxdbLineProtocolHandler {
     /// A successful request will not return a response.
     /// Only on error will the socket return a line of data.
     async fn exec(&self, request: &InfluxdbRequest, ctx: QueryContextRef) -> Result<()>;
+
+    /// Executes an InfluxQL statement and returns the series of its result.
+    async fn query(
+        &self,
+        stmt: &InfluxqlStatement,
+        ctx: QueryContextRef,
+    ) -> Result<Vec<InfluxqlSeries>>;
 }
 
 #[async_trait]
@@ -66,6 +86,22 @@ pub trait OpentsdbProtocolHandler {
     /// A successful request will not return a response.
     /// Only on error will the socket return a line of data.
     async fn exec(&self, data_point: &DataPoint, ctx: QueryContextRef) -> Result<()>;
+
+    /// Handles a sub query of `/api/query`.
+    async fn query(
+        &self,
+        query: &OpentsdbQuery,
+        ctx: QueryContextRef,
+    ) -> Result<Vec<OpentsdbQueryResult>>;
+
+    /// Suggests metric names, tag keys or tag values for `/api/suggest`.
+    async fn suggest(&self, request: &SuggestRequest, ctx: QueryContextRef) -> Result<Vec<String>>;
+}
+
+#[async_trait]
+pub trait GraphiteProtocolHandler {
+    /// Writes metrics received by the Graphite server, or flushed by the StatsD server.
+    async fn exec(&self, metrics: &[Metric], ctx: QueryContextRef) -> Result<()>;
 }
 
 pub struct PrometheusResponse {
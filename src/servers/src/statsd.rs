@@ -0,0 +1,232 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod codec;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use common_runtime::Runtime;
+use common_telemetry::logging::{error, info, warn};
+use common_telemetry::timer;
+use common_time::util::current_time_millis;
+use session::context::QueryContext;
+use snafu::{ensure, ResultExt};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, oneshot, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::error::{self, Result};
+use crate::graphite::template::Templates;
+use crate::query_handler::GraphiteProtocolHandlerRef;
+use crate::server::Server;
+use crate::statsd::codec::{Aggregator, StatsdLine};
+
+pub const STATSD_SERVER: &str = "STATSD_SERVER";
+
+/// The maximum size of a UDP datagram.
+const MAX_DATAGRAM_SIZE: usize = 65535;
+
+/// Receives StatsD metrics over UDP, aggregates them and flushes the aggregates
+/// every `flush_interval` through the [GraphiteProtocolHandler](crate::query_handler::GraphiteProtocolHandler).
+pub struct StatsdServer {
+    query_handler: GraphiteProtocolHandlerRef,
+    templates: Arc<Templates>,
+    flush_interval: Duration,
+    percentiles: Vec<f64>,
+    io_runtime: Arc<Runtime>,
+    shutdown_tx: Mutex<Option<oneshot::Sender<()>>>,
+    join_handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl StatsdServer {
+    pub fn create_server(
+        query_handler: GraphiteProtocolHandlerRef,
+        templates: Arc<Templates>,
+        flush_interval: Duration,
+        percentiles: Vec<f64>,
+        io_runtime: Arc<Runtime>,
+    ) -> Box<dyn Server> {
+        Box::new(StatsdServer {
+            query_handler,
+            templates,
+            flush_interval,
+            percentiles,
+            io_runtime,
+            shutdown_tx: Mutex::new(None),
+            join_handle: Mutex::new(None),
+        })
+    }
+}
+
+/// The maximum number of aggregations waiting to be written. When the flusher
+/// falls behind, the receiver keeps aggregating into the current interval
+/// instead of blocking on the write.
+const FLUSH_QUEUE_SIZE: usize = 4;
+
+/// Receives and aggregates datagrams. It never waits for the writes, so no
+/// datagram is dropped by the socket while the aggregates are being flushed.
+struct Receiver {
+    socket: UdpSocket,
+    aggregator: Aggregator,
+    flush_tx: mpsc::Sender<(Aggregator, i64)>,
+}
+
+impl Receiver {
+    async fn run(mut self, flush_interval: Duration, mut shutdown_rx: oneshot::Receiver<()>) {
+        let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+        let mut ticker = tokio::time::interval(flush_interval);
+        // The first tick completes immediately.
+        let _ = ticker.tick().await;
+
+        loop {
+            tokio::select! {
+                result = self.socket.recv_from(&mut buf) => match result {
+                    Ok((len, _)) => self.receive(&buf[..len]),
+                    Err(e) => error!("Failed to receive StatsD datagram, error: {}", e),
+                },
+                _ = ticker.tick() => self.try_flush(),
+                _ = &mut shutdown_rx => {
+                    if !self.aggregator.is_empty() {
+                        let aggregator = std::mem::take(&mut self.aggregator);
+                        let _ = self.flush_tx.send((aggregator, current_time_millis())).await;
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    fn receive(&mut self, datagram: &[u8]) {
+        let datagram = String::from_utf8_lossy(datagram);
+        for line in datagram.lines().filter(|l| !l.trim().is_empty()) {
+            match StatsdLine::try_create(line.trim()) {
+                Ok(line) => self.aggregator.add(line),
+                Err(e) => warn!("{}", e),
+            }
+        }
+    }
+
+    /// Hands the current aggregation over to the flusher.
+    fn try_flush(&mut self) {
+        if self.aggregator.is_empty() {
+            return;
+        }
+        let aggregator = std::mem::take(&mut self.aggregator);
+        match self.flush_tx.try_send((aggregator, current_time_millis())) {
+            Ok(()) => {}
+            Err(TrySendError::Full((aggregator, _))) => {
+                warn!("StatsD flush is falling behind, delaying the flush to the next interval");
+                self.aggregator = aggregator;
+            }
+            Err(TrySendError::Closed(_)) => error!("StatsD flusher is closed unexpectedly"),
+        }
+    }
+}
+
+/// Writes the aggregations handed over by the [Receiver].
+struct Flusher {
+    query_handler: GraphiteProtocolHandlerRef,
+    templates: Arc<Templates>,
+    percentiles: Vec<f64>,
+}
+
+impl Flusher {
+    async fn run(self, mut flush_rx: mpsc::Receiver<(Aggregator, i64)>) {
+        while let Some((mut aggregator, ts_millis)) = flush_rx.recv().await {
+            let _timer = timer!(crate::metrics::METRIC_UDP_STATSD_FLUSH_ELAPSED);
+            let metrics = aggregator.flush(&self.templates, &self.percentiles, ts_millis);
+            if let Err(e) = self.query_handler.exec(&metrics, QueryContext::arc()).await {
+                error!(e; "Failed to flush StatsD metrics");
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Server for StatsdServer {
+    async fn shutdown(&self) -> Result<()> {
+        if let Some(tx) = self.shutdown_tx.lock().await.take() {
+            let _ = tx.send(());
+        }
+        match self.join_handle.lock().await.take() {
+            Some(join_handle) => {
+                if let Err(e) = join_handle.await {
+                    error!(
+                        "Unexpected error during shutdown StatsD server, error: {}",
+                        e
+                    );
+                } else {
+                    info!("StatsD server is shutdown.");
+                }
+                Ok(())
+            }
+            None => error::InternalSnafu {
+                err_msg: "StatsD server is not started.",
+            }
+            .fail(),
+        }
+    }
+
+    async fn start(&self, listening: SocketAddr) -> Result<SocketAddr> {
+        let mut join_handle = self.join_handle.lock().await;
+        ensure!(
+            join_handle.is_none(),
+            error::InternalSnafu {
+                err_msg: "StatsD server has been started.",
+            }
+        );
+
+        let socket = UdpSocket::bind(listening)
+            .await
+            .context(error::TokioIoSnafu {
+                err_msg: format!("StatsD failed to bind addr {listening}"),
+            })?;
+        let addr = socket.local_addr()?;
+        info!("StatsD server started at {addr}");
+
+        let (flush_tx, flush_rx) = mpsc::channel(FLUSH_QUEUE_SIZE);
+        let receiver = Receiver {
+            socket,
+            aggregator: Aggregator::default(),
+            flush_tx,
+        };
+        let flusher = Flusher {
+            query_handler: self.query_handler.clone(),
+            templates: self.templates.clone(),
+            percentiles: self.percentiles.clone(),
+        };
+        let (tx, rx) = oneshot::channel();
+        *self.shutdown_tx.lock().await = Some(tx);
+
+        let flush_interval = self.flush_interval;
+        let flush_handle = self.io_runtime.spawn(flusher.run(flush_rx));
+        *join_handle = Some(self.io_runtime.spawn(async move {
+            receiver.run(flush_interval, rx).await;
+            // The receiver has dropped its sender, so the flusher exits after
+            // writing the remaining aggregations.
+            if let Err(e) = flush_handle.await {
+                error!("StatsD flusher exited unexpectedly, error: {}", e);
+            }
+        }));
+        Ok(addr)
+    }
+
+    fn name(&self) -> &str {
+        STATSD_SERVER
+    }
+}
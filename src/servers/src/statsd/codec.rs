@@ -0,0 +1,399 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use snafu::{ensure, OptionExt};
+
+use crate::error::{self, Result};
+use crate::graphite::codec::{Metric, GRAPHITE_DEFAULT_FIELD_NAME};
+use crate::graphite::template::Templates;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatsdValue {
+    Counter(f64),
+    /// A gauge value, and whether it's a delta (`+1` or `-1`) to the current value.
+    Gauge(f64, bool),
+    Timer(f64),
+    Set(String),
+}
+
+/// A StatsD line: `<name>:<value>|<type>[|@<sample rate>][|#<tag>:<value>,...]`.
+/// The optional tags are in DogStatsD format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsdLine {
+    pub name: String,
+    pub value: StatsdValue,
+    pub sample_rate: f64,
+    pub tags: Vec<(String, String)>,
+}
+
+impl StatsdLine {
+    pub fn try_create(line: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            error::InvalidQuerySnafu {
+                reason: format!("invalid StatsD line: {line}, {reason}"),
+            }
+            .build()
+        };
+
+        let (name, rest) = line
+            .split_once(':')
+            .filter(|(name, _)| !name.is_empty())
+            .ok_or_else(|| invalid("missing metric name"))?;
+        let mut sections = rest.split('|');
+        // Safety: split always returns at least one segment.
+        let value = sections.next().unwrap();
+        let metric_type = sections.next().ok_or_else(|| invalid("missing type"))?;
+
+        let parse_number = |v: &str| v.parse::<f64>().map_err(|_| invalid("invalid value"));
+        let value = match metric_type {
+            "c" => StatsdValue::Counter(parse_number(value)?),
+            "g" => StatsdValue::Gauge(
+                parse_number(value)?,
+                value.starts_with('+') || value.starts_with('-'),
+            ),
+            "ms" | "h" | "d" => StatsdValue::Timer(parse_number(value)?),
+            "s" => StatsdValue::Set(value.to_string()),
+            _ => return Err(invalid("unknown type")),
+        };
+
+        let mut sample_rate = 1.0;
+        let mut tags = vec![];
+        for section in sections {
+            if let Some(rate) = section.strip_prefix('@') {
+                sample_rate = rate
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|r| *r > 0.0 && *r <= 1.0)
+                    .with_context(|| error::InvalidQuerySnafu {
+                        reason: format!("invalid StatsD line: {line}, invalid sample rate"),
+                    })?;
+            } else if let Some(tag_list) = section.strip_prefix('#') {
+                for tag in tag_list.split(',').filter(|t| !t.is_empty()) {
+                    let (k, v) = tag.split_once(':').unwrap_or((tag, "true"));
+                    ensure!(
+                        !k.is_empty(),
+                        error::InvalidQuerySnafu {
+                            reason: format!("invalid StatsD line: {line}, invalid tag"),
+                        }
+                    );
+                    tags.push((k.to_string(), v.to_string()));
+                }
+            } else {
+                return Err(invalid("unknown section"));
+            }
+        }
+        tags.sort();
+
+        Ok(StatsdLine {
+            name: name.to_string(),
+            value,
+            sample_rate,
+            tags,
+        })
+    }
+}
+
+#[derive(Debug)]
+enum Aggregate {
+    Counter(f64),
+    Gauge(f64),
+    Timer {
+        values: Vec<f64>,
+        /// Counts scaled by the sample rates.
+        count: f64,
+    },
+    Set(HashSet<String>),
+}
+
+/// Aggregates StatsD lines received during a flush interval.
+#[derive(Debug, Default)]
+pub struct Aggregator {
+    metrics: HashMap<(String, Vec<(String, String)>), Aggregate>,
+}
+
+impl Aggregator {
+    pub fn add(&mut self, line: StatsdLine) {
+        let key = (line.name, line.tags);
+        match line.value {
+            StatsdValue::Counter(v) => {
+                let v = v / line.sample_rate;
+                match self.metrics.get_mut(&key) {
+                    Some(Aggregate::Counter(sum)) => *sum += v,
+                    _ => {
+                        let _ = self.metrics.insert(key, Aggregate::Counter(v));
+                    }
+                }
+            }
+            StatsdValue::Gauge(v, is_delta) => match self.metrics.get_mut(&key) {
+                Some(Aggregate::Gauge(current)) if is_delta => *current += v,
+                _ => {
+                    let _ = self.metrics.insert(key, Aggregate::Gauge(v));
+                }
+            },
+            StatsdValue::Timer(v) => {
+                let count = 1.0 / line.sample_rate;
+                match self.metrics.get_mut(&key) {
+                    Some(Aggregate::Timer { values, count: c }) => {
+                        values.push(v);
+                        *c += count;
+                    }
+                    _ => {
+                        let _ = self.metrics.insert(
+                            key,
+                            Aggregate::Timer {
+                                values: vec![v],
+                                count,
+                            },
+                        );
+                    }
+                }
+            }
+            StatsdValue::Set(v) => match self.metrics.get_mut(&key) {
+                Some(Aggregate::Set(set)) => {
+                    let _ = set.insert(v);
+                }
+                _ => {
+                    let _ = self.metrics.insert(key, Aggregate::Set(HashSet::from([v])));
+                }
+            },
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.metrics.is_empty()
+    }
+
+    /// Takes all aggregated metrics and resets the aggregator. Counters, sets and
+    /// timers are written as their statistics during the interval, gauges as
+    /// their last values.
+    pub fn flush(
+        &mut self,
+        templates: &Templates,
+        percentiles: &[f64],
+        ts_millis: i64,
+    ) -> Vec<Metric> {
+        std::mem::take(&mut self.metrics)
+            .into_iter()
+            .map(|((name, tags), aggregate)| {
+                let parsed = templates.apply(&name);
+                let mut all_tags = parsed.tags;
+                for (k, v) in tags {
+                    all_tags.retain(|(name, _)| *name != k);
+                    all_tags.push((k, v));
+                }
+
+                let fields = match aggregate {
+                    Aggregate::Counter(v) | Aggregate::Gauge(v) => vec![(
+                        parsed
+                            .field
+                            .unwrap_or_else(|| GRAPHITE_DEFAULT_FIELD_NAME.to_string()),
+                        v,
+                    )],
+                    Aggregate::Set(set) => vec![(
+                        parsed
+                            .field
+                            .unwrap_or_else(|| GRAPHITE_DEFAULT_FIELD_NAME.to_string()),
+                        set.len() as f64,
+                    )],
+                    Aggregate::Timer { values, count } => {
+                        let prefix = parsed.field.map(|f| format!("{f}_")).unwrap_or_default();
+                        timer_stats(values, count, percentiles)
+                            .into_iter()
+                            .map(|(stat, v)| (format!("{prefix}{stat}"), v))
+                            .collect()
+                    }
+                };
+                Metric::new(parsed.measurement, ts_millis, fields, all_tags)
+            })
+            .collect()
+    }
+}
+
+fn timer_stats(mut values: Vec<f64>, count: f64, percentiles: &[f64]) -> BTreeMap<String, f64> {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let n = values.len() as f64;
+    let sum = values.iter().sum::<f64>();
+    let mean = sum / n;
+    let stddev = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+
+    let mut stats = BTreeMap::from([
+        ("count".to_string(), count),
+        ("sum".to_string(), sum),
+        ("mean".to_string(), mean),
+        ("lower".to_string(), values[0]),
+        ("upper".to_string(), values[values.len() - 1]),
+        ("stddev".to_string(), stddev),
+    ]);
+    for p in percentiles {
+        // Nearest-rank percentile.
+        let rank = ((p / 100.0) * n).ceil().max(1.0) as usize;
+        let value = values[rank.min(values.len()) - 1];
+        let name = format!("p{p}").replace('.', "_");
+        let _ = stats.insert(name, value);
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_create() {
+        assert_eq!(
+            StatsdLine {
+                name: "api.requests".to_string(),
+                value: StatsdValue::Counter(2.0),
+                sample_rate: 0.5,
+                tags: vec![
+                    ("host".to_string(), "web01".to_string()),
+                    ("ssl".to_string(), "true".to_string())
+                ],
+            },
+            StatsdLine::try_create("api.requests:2|c|@0.5|#ssl,host:web01").unwrap()
+        );
+        assert_eq!(
+            StatsdValue::Gauge(-3.0, true),
+            StatsdLine::try_create("queue:-3|g").unwrap().value
+        );
+        assert_eq!(
+            StatsdValue::Timer(320.0),
+            StatsdLine::try_create("latency:320|ms").unwrap().value
+        );
+        assert_eq!(
+            StatsdValue::Set("user1".to_string()),
+            StatsdLine::try_create("users:user1|s").unwrap().value
+        );
+
+        for line in [
+            "",
+            "foo",
+            ":1|c",
+            "foo:1",
+            "foo:x|c",
+            "foo:1|x",
+            "foo:1|c|@2",
+            "foo:1|c|#:v",
+            "foo:1|c|x",
+        ] {
+            assert!(StatsdLine::try_create(line).is_err(), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_flush() {
+        let mut aggregator = Aggregator::default();
+        for line in [
+            "requests:1|c",
+            "requests:2|c|@0.5",
+            "queue:10|g",
+            "queue:-3|g",
+            "users:a|s",
+            "users:b|s",
+            "users:a|s",
+            "latency:10|ms",
+            "latency:30|ms",
+            "latency:20|ms",
+        ] {
+            aggregator.add(StatsdLine::try_create(line).unwrap());
+        }
+
+        let templates =
+            Templates::try_new(&["latency measurement.field".to_string()], ".").unwrap();
+        let mut metrics = aggregator.flush(&templates, &[50.0, 99.9], 1000);
+        metrics.sort_by(|a, b| a.measurement().cmp(b.measurement()));
+        assert!(aggregator.is_empty());
+
+        assert_eq!(
+            vec![
+                Metric::new(
+                    "latency".to_string(),
+                    1000,
+                    vec![
+                        ("count".to_string(), 3.0),
+                        ("lower".to_string(), 10.0),
+                        ("mean".to_string(), 20.0),
+                        ("p50".to_string(), 20.0),
+                        ("p99_9".to_string(), 30.0),
+                        ("stddev".to_string(), (200.0f64 / 3.0).sqrt()),
+                        ("sum".to_string(), 60.0),
+                        ("upper".to_string(), 30.0),
+                    ],
+                    vec![],
+                ),
+                Metric::new(
+                    "queue".to_string(),
+                    1000,
+                    vec![("value".to_string(), 7.0)],
+                    vec![],
+                ),
+                Metric::new(
+                    "requests".to_string(),
+                    1000,
+                    vec![("value".to_string(), 5.0)],
+                    vec![],
+                ),
+                Metric::new(
+                    "users".to_string(),
+                    1000,
+                    vec![("value".to_string(), 2.0)],
+                    vec![],
+                ),
+            ],
+            metrics
+        );
+    }
+
+    #[test]
+    fn test_flush_with_tags() {
+        let mut aggregator = Aggregator::default();
+        for line in [
+            "servers.web01.requests:1|c|#host:web02,dc:us",
+            "servers.web01.requests:1|c|#dc:us,host:web02",
+            "servers.web01.requests:4|c",
+            "servers.web01.queue:5|g",
+            "servers.web01.queue:+2|g",
+            "servers.web01.queue:1|g|#dc:eu",
+        ] {
+            aggregator.add(StatsdLine::try_create(line).unwrap());
+        }
+
+        let templates = Templates::try_new(&[".host.measurement".to_string()], ".").unwrap();
+        let mut metrics = aggregator.flush(&templates, &[], 1000);
+        metrics.sort_by(|a, b| (a.measurement(), a.tags()).cmp(&(b.measurement(), b.tags())));
+
+        let metric = |measurement: &str, value: f64, tags: &[(&str, &str)]| {
+            Metric::new(
+                measurement.to_string(),
+                1000,
+                vec![("value".to_string(), value)],
+                tags.iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            )
+        };
+        assert_eq!(
+            vec![
+                metric("queue", 7.0, &[("host", "web01")]),
+                metric("queue", 1.0, &[("host", "web01"), ("dc", "eu")]),
+                // DogStatsD tags override the tags extracted by templates.
+                metric("requests", 2.0, &[("dc", "us"), ("host", "web02")]),
+                metric("requests", 4.0, &[("host", "web01")]),
+            ],
+            metrics
+        );
+    }
+}
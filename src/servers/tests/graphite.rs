@@ -0,0 +1,234 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use common_runtime::Builder as RuntimeBuilder;
+use servers::error::Result;
+use servers::graphite::codec::Metric;
+use servers::graphite::template::Templates;
+use servers::graphite::{GraphiteProtocol, GraphiteServer};
+use servers::query_handler::GraphiteProtocolHandler;
+use servers::server::Server;
+use servers::statsd::StatsdServer;
+use session::context::QueryContextRef;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::mpsc;
+
+struct DummyGraphiteInstance {
+    tx: mpsc::Sender<Metric>,
+}
+
+#[async_trait]
+impl GraphiteProtocolHandler for DummyGraphiteInstance {
+    async fn exec(&self, metrics: &[Metric], _ctx: QueryContextRef) -> Result<()> {
+        for metric in metrics {
+            let _ = self.tx.send(metric.clone()).await;
+        }
+        Ok(())
+    }
+}
+
+fn create_io_runtime() -> Arc<common_runtime::Runtime> {
+    Arc::new(
+        RuntimeBuilder::default()
+            .worker_threads(2)
+            .thread_name("graphite-io-handlers")
+            .build()
+            .unwrap(),
+    )
+}
+
+fn create_templates() -> Arc<Templates> {
+    Arc::new(Templates::try_new(&["servers.* .host.measurement.field".to_string()], ".").unwrap())
+}
+
+#[tokio::test]
+async fn test_graphite_plaintext() {
+    let (tx, mut rx) = mpsc::channel(100);
+    let server = GraphiteServer::create_server(
+        Arc::new(DummyGraphiteInstance { tx }),
+        create_templates(),
+        GraphiteProtocol::Plaintext,
+        create_io_runtime(),
+    );
+    let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
+    let addr = server.start(listening).await.unwrap();
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"servers.web01.cpu.idle 98.5 1346846400\nbad line\nmem.used 1024 1346846400\n")
+        .await
+        .unwrap();
+
+    let metric = rx.recv().await.unwrap();
+    assert_eq!(
+        Metric::new(
+            "cpu".to_string(),
+            1346846400000,
+            vec![("idle".to_string(), 98.5)],
+            vec![("host".to_string(), "web01".to_string())],
+        ),
+        metric
+    );
+    let metric = rx.recv().await.unwrap();
+    assert_eq!("mem.used", metric.measurement());
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_graphite_pickle() {
+    let (tx, mut rx) = mpsc::channel(100);
+    let server = GraphiteServer::create_server(
+        Arc::new(DummyGraphiteInstance { tx }),
+        create_templates(),
+        GraphiteProtocol::Pickle,
+        create_io_runtime(),
+    );
+    let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
+    let addr = server.start(listening).await.unwrap();
+    assert_eq!("GRAPHITE_PICKLE_SERVER", server.name());
+
+    // pickle.dumps([("servers.web01.cpu.idle", (1346846400, 98.5))], protocol=2)
+    let payload: &[u8] = b"\x80\x02]q\x00X\x16\x00\x00\x00servers.web01.cpu.idleq\x01J\xc0>GP\
+        G@X\xa0\x00\x00\x00\x00\x00\x86q\x02\x86q\x03a.";
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(&(payload.len() as u32).to_be_bytes())
+        .await
+        .unwrap();
+    stream.write_all(payload).await.unwrap();
+
+    let metric = rx.recv().await.unwrap();
+    assert_eq!(
+        Metric::new(
+            "cpu".to_string(),
+            1346846400000,
+            vec![("idle".to_string(), 98.5)],
+            vec![("host".to_string(), "web01".to_string())],
+        ),
+        metric
+    );
+
+    server.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_statsd() {
+    let (tx, mut rx) = mpsc::channel(100);
+    let server = StatsdServer::create_server(
+        Arc::new(DummyGraphiteInstance { tx }),
+        Arc::new(Templates::default()),
+        Duration::from_secs(3600),
+        vec![90.0],
+        create_io_runtime(),
+    );
+    let result = server.shutdown().await;
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("StatsD server is not started."));
+
+    let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
+    let addr = server.start(listening).await.unwrap();
+    assert!(server.start(listening).await.is_err());
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let _ = socket
+        .send_to(b"requests:1|c|#host:web01\nrequests:2|c|#host:web01", addr)
+        .await
+        .unwrap();
+    // Wait for the datagram to be received before flushing by shutdown.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    server.shutdown().await.unwrap();
+
+    let metric = rx.recv().await.unwrap();
+    assert_eq!("requests", metric.measurement());
+    assert_eq!(&vec![("value".to_string(), 3.0)], metric.fields());
+    assert_eq!(
+        &vec![("host".to_string(), "web01".to_string())],
+        metric.tags()
+    );
+}
+
+#[tokio::test]
+async fn test_statsd_flush_interval() {
+    let (tx, mut rx) = mpsc::channel(100);
+    let server = StatsdServer::create_server(
+        Arc::new(DummyGraphiteInstance { tx }),
+        create_templates(),
+        Duration::from_millis(100),
+        vec![50.0],
+        create_io_runtime(),
+    );
+    let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
+    let addr = server.start(listening).await.unwrap();
+    assert_eq!("STATSD_SERVER", server.name());
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let _ = socket
+        .send_to(
+            b"servers.web01.latency.p:10|ms\nbad line\nservers.web01.latency.p:30|ms|@0.5",
+            addr,
+        )
+        .await
+        .unwrap();
+
+    // Flushed by the ticker, without shutting down the server.
+    let metric = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        Metric::new(
+            "latency".to_string(),
+            metric.ts_millis(),
+            vec![
+                ("p_count".to_string(), 3.0),
+                ("p_lower".to_string(), 10.0),
+                ("p_mean".to_string(), 20.0),
+                ("p_p50".to_string(), 10.0),
+                ("p_stddev".to_string(), 10.0),
+                ("p_sum".to_string(), 40.0),
+                ("p_upper".to_string(), 30.0),
+            ],
+            vec![("host".to_string(), "web01".to_string())],
+        ),
+        metric
+    );
+
+    // Datagrams received after a flush go to the next interval.
+    let _ = socket
+        .send_to(b"servers.web02.queue.size:7|g", addr)
+        .await
+        .unwrap();
+    let metric = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!("queue", metric.measurement());
+    assert_eq!(&vec![("size".to_string(), 7.0)], metric.fields());
+    assert_eq!(
+        &vec![("host".to_string(), "web02".to_string())],
+        metric.tags()
+    );
+
+    server.shutdown().await.unwrap();
+    assert!(rx.try_recv().is_err());
+}
@@ -39,6 +39,7 @@ async fn test_sql_not_provided() {
         }),
         Query(http_handler::SqlQuery::default()),
         axum::Extension(UserInfo::default()),
+        None,
         Form(http_handler::SqlQuery::default()),
     )
     .await;
@@ -64,6 +65,7 @@ async fn test_sql_output_rows() {
         }),
         query,
         axum::Extension(UserInfo::default()),
+        None,
         Form(http_handler::SqlQuery::default()),
     )
     .await;
@@ -110,6 +112,7 @@ async fn test_sql_form() {
         }),
         Query(http_handler::SqlQuery::default()),
         axum::Extension(UserInfo::default()),
+        None,
         form,
     )
     .await;
@@ -142,6 +145,48 @@ async fn test_sql_form() {
     }
 }
 
+#[tokio::test]
+async fn test_sql_read_preference() {
+    let sql_handler = create_testing_sql_query_handler(MemTable::default_numbers_table());
+    let query = |read_preference: &str| {
+        Query(http_handler::SqlQuery {
+            sql: Some("select sum(uint32s) from numbers limit 20".to_string()),
+            db: None,
+            read_preference: Some(read_preference.to_string()),
+        })
+    };
+
+    let Json(json) = http_handler::sql(
+        State(ApiState {
+            sql_handler: sql_handler.clone(),
+            script_handler: None,
+        }),
+        query("follower"),
+        axum::Extension(UserInfo::default()),
+        None,
+        Form(http_handler::SqlQuery::default()),
+    )
+    .await;
+    assert!(json.success(), "{json:?}");
+
+    let Json(json) = http_handler::sql(
+        State(ApiState {
+            sql_handler,
+            script_handler: None,
+        }),
+        query("primary"),
+        axum::Extension(UserInfo::default()),
+        None,
+        Form(http_handler::SqlQuery::default()),
+    )
+    .await;
+    assert!(!json.success());
+    assert_eq!(
+        Some(&"Invalid read preference: primary".to_string()),
+        json.error()
+    );
+}
+
 #[tokio::test]
 async fn test_metrics() {
     metric::init_default_metrics_recorder();
@@ -341,6 +386,7 @@ fn create_query() -> Query<http_handler::SqlQuery> {
     Query(http_handler::SqlQuery {
         sql: Some("select sum(uint32s) from numbers limit 20".to_string()),
         db: None,
+        read_preference: None,
     })
 }
 
@@ -348,6 +394,7 @@ fn create_form() -> Form<http_handler::SqlQuery> {
     Form(http_handler::SqlQuery {
         sql: Some("select sum(uint32s) from numbers limit 20".to_string()),
         db: None,
+        read_preference: None,
     })
 }
 
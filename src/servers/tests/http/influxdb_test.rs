@@ -26,6 +26,7 @@ use query::plan::LogicalPlan;
 use query::query_engine::DescribeResult;
 use servers::error::{Error, Result};
 use servers::http::{HttpOptions, HttpServerBuilder};
+use servers::influxdb::influxql::{self, InfluxqlSeries, InfluxqlStatement};
 use servers::influxdb::InfluxdbRequest;
 use servers::query_handler::grpc::GrpcQueryHandler;
 use servers::query_handler::sql::SqlQueryHandler;
@@ -62,6 +63,20 @@ impl InfluxdbLineProtocolHandler for DummyInstance {
 
         Ok(())
     }
+
+    async fn query(
+        &self,
+        stmt: &InfluxqlStatement,
+        _ctx: QueryContextRef,
+    ) -> Result<Vec<InfluxqlSeries>> {
+        match stmt {
+            InfluxqlStatement::ShowMeasurements { limit } => Ok(influxql::measurements_to_series(
+                vec!["monitor".to_string()],
+                *limit,
+            )),
+            _ => unimplemented!(),
+        }
+    }
 }
 
 #[async_trait]
@@ -212,3 +227,34 @@ async fn test_influxdb_write() {
         ]
     );
 }
+
+#[tokio::test]
+async fn test_influxdb_query() {
+    let (tx, _rx) = mpsc::channel(100);
+    let app = make_test_app(Arc::new(tx), None);
+    let client = TestClient::new(app);
+
+    let result = client
+        .get("/v1/influxdb/query?db=public&q=SHOW%20MEASUREMENTS&u=greptime&p=greptime")
+        .send()
+        .await;
+    assert_eq!(result.status(), 200);
+    assert_eq!(
+        result.text().await,
+        r#"{"results":[{"statement_id":0,"series":[{"name":"measurements","columns":["name"],"values":[["monitor"]]}]}]}"#
+    );
+
+    // missing query
+    let result = client
+        .get("/v1/influxdb/query?db=public&u=greptime&p=greptime")
+        .send()
+        .await;
+    assert_eq!(result.status(), 400);
+
+    // invalid InfluxQL
+    let result = client
+        .get("/v1/influxdb/query?db=public&q=DROP%20SERIES&u=greptime&p=greptime")
+        .send()
+        .await;
+    assert_eq!(result.status(), 400);
+}
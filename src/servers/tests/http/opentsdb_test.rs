@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use api::v1::greptime_request::Request;
@@ -26,6 +27,9 @@ use query::query_engine::DescribeResult;
 use servers::error::{self, Result};
 use servers::http::{HttpOptions, HttpServerBuilder};
 use servers::opentsdb::codec::DataPoint;
+use servers::opentsdb::query::{
+    collect_suggestions, OpentsdbQuery, OpentsdbQueryResult, SuggestRequest,
+};
 use servers::query_handler::grpc::GrpcQueryHandler;
 use servers::query_handler::sql::SqlQueryHandler;
 use servers::query_handler::OpentsdbProtocolHandler;
@@ -61,6 +65,30 @@ impl OpentsdbProtocolHandler for DummyInstance {
         let _ = self.tx.send(data_point.metric().to_string()).await;
         Ok(())
     }
+
+    async fn query(
+        &self,
+        query: &OpentsdbQuery,
+        _ctx: QueryContextRef,
+    ) -> Result<Vec<OpentsdbQueryResult>> {
+        Ok(vec![OpentsdbQueryResult {
+            metric: query.metric.clone(),
+            tags: BTreeMap::from([("host".to_string(), "web01".to_string())]),
+            aggregate_tags: vec![],
+            dps: BTreeMap::from([(query.start, 1.0)]),
+        }])
+    }
+
+    async fn suggest(
+        &self,
+        request: &SuggestRequest,
+        _ctx: QueryContextRef,
+    ) -> Result<Vec<String>> {
+        let candidates = ["sys.cpu.user", "sys.cpu.nice", "net.bytes"]
+            .into_iter()
+            .map(|s| s.to_string());
+        Ok(collect_suggestions(request, candidates))
+    }
 }
 
 #[async_trait]
@@ -245,6 +273,90 @@ async fn test_opentsdb_debug_put() {
     );
 }
 
+#[tokio::test]
+async fn test_opentsdb_query() {
+    let (tx, _rx) = mpsc::channel(100);
+
+    let app = make_test_app(tx);
+    let client = TestClient::new(app);
+
+    let result = client
+        .post("/v1/opentsdb/api/query")
+        .body(
+            r#"{
+                "start": 1346846400,
+                "end": 1346846460,
+                "queries": [{
+                    "aggregator": "sum",
+                    "metric": "sys.cpu.nice",
+                    "downsample": "1m-avg",
+                    "tags": {"host": "*"}
+                }]
+            }"#,
+        )
+        .send()
+        .await;
+    assert_eq!(result.status(), 200);
+    assert_eq!(
+        result.text().await,
+        r#"[{"metric":"sys.cpu.nice","tags":{"host":"web01"},"aggregateTags":[],"dps":{"1346846400":1.0}}]"#
+    );
+
+    let result = client
+        .get("/v1/opentsdb/api/query?start=1346846400000&m=sum:rate:sys.cpu.nice%7Bhost=web01%7D&ms=true")
+        .send()
+        .await;
+    assert_eq!(result.status(), 200);
+    assert_eq!(
+        result.text().await,
+        r#"[{"metric":"sys.cpu.nice","tags":{"host":"web01"},"aggregateTags":[],"dps":{"1346846400000":1.0}}]"#
+    );
+
+    // missing start time
+    let result = client
+        .get("/v1/opentsdb/api/query?m=sum:sys.cpu.nice")
+        .send()
+        .await;
+    assert_eq!(result.status(), 400);
+
+    // unknown aggregator
+    let result = client
+        .get("/v1/opentsdb/api/query?start=1h-ago&m=foo:sys.cpu.nice")
+        .send()
+        .await;
+    assert_eq!(result.status(), 400);
+    assert_eq!(
+        result.text().await,
+        "{\"error\":\"Invalid OpenTSDB query, reason: unsupported aggregator: foo\"}"
+    );
+}
+
+#[tokio::test]
+async fn test_opentsdb_suggest() {
+    let (tx, _rx) = mpsc::channel(100);
+
+    let app = make_test_app(tx);
+    let client = TestClient::new(app);
+
+    let result = client
+        .get("/v1/opentsdb/api/suggest?type=metrics&q=sys&max=10")
+        .send()
+        .await;
+    assert_eq!(result.status(), 200);
+    assert_eq!(result.text().await, r#"["sys.cpu.nice","sys.cpu.user"]"#);
+
+    let result = client
+        .post("/v1/opentsdb/api/suggest")
+        .body(r#"{"type": "metrics", "max": 1}"#)
+        .send()
+        .await;
+    assert_eq!(result.status(), 200);
+    assert_eq!(result.text().await, r#"["net.bytes"]"#);
+
+    let result = client.get("/v1/opentsdb/api/suggest?type=foo").send().await;
+    assert_eq!(result.status(), 400);
+}
+
 fn create_data_point(metric: &str) -> String {
     format!(
         r#"{{
@@ -13,12 +13,18 @@
 // limitations under the License.
 
 use std::borrow::Cow;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 use api::v1::greptime_request::Request;
 use api::v1::{InsertRequest, InsertRequests};
+use common_error::status_code::StatusCode;
+use common_query::Output;
 use servers::error::{self, NotSupportedSnafu, Result};
-use servers::interceptor::{GrpcQueryInterceptor, SqlQueryInterceptor};
+use servers::interceptor::{
+    report_execution, GrpcQueryInterceptor, QueryExecution, SqlQueryInterceptor,
+    SqlQueryInterceptorRef,
+};
 use session::context::{QueryContext, QueryContextRef};
 use snafu::ensure;
 
@@ -85,3 +91,52 @@ fn test_grpc_interceptor() {
     let req = Request::Inserts(InsertRequests::default());
     GrpcQueryInterceptor::pre_execute(&di, &req, ctx).unwrap();
 }
+
+#[derive(Default)]
+struct RecordingInterceptor {
+    executions: Mutex<Vec<QueryExecution>>,
+}
+
+impl SqlQueryInterceptor for RecordingInterceptor {
+    type Error = error::Error;
+
+    fn on_execute_finished(&self, execution: &QueryExecution, _query_ctx: QueryContextRef) {
+        self.executions.lock().unwrap().push(execution.clone());
+    }
+}
+
+#[test]
+fn test_report_execution() {
+    let recorder = Arc::new(RecordingInterceptor::default());
+    let interceptor: SqlQueryInterceptorRef<error::Error> = recorder.clone();
+    let ctx = Arc::new(QueryContext::new());
+
+    let output = report_execution(
+        Some(&interceptor),
+        "INSERT INTO t VALUES (1)",
+        Instant::now(),
+        Ok::<_, error::Error>(Output::AffectedRows(1)),
+        ctx.clone(),
+    );
+    assert!(matches!(output, Ok(Output::AffectedRows(1))));
+
+    let result = report_execution(
+        Some(&interceptor),
+        "SELECT 1",
+        Instant::now(),
+        NotSupportedSnafu { feat: "test" }.fail(),
+        ctx,
+    );
+    assert!(result.is_err());
+
+    let executions = recorder.executions.lock().unwrap();
+    assert_eq!(2, executions.len());
+    assert_eq!("INSERT INTO t VALUES (1)", executions[0].query);
+    assert_eq!(1, executions[0].rows);
+    assert!(executions[0].error.is_none());
+    assert_eq!("SELECT 1", executions[1].query);
+    assert_eq!(
+        StatusCode::InvalidArguments,
+        executions[1].error.as_ref().unwrap().0
+    );
+}
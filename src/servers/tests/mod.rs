@@ -37,6 +37,7 @@ use sql::statements::statement::Statement;
 use table::test_util::MemTable;
 
 mod auth;
+mod graphite;
 mod grpc;
 mod http;
 mod interceptor;
@@ -148,6 +149,16 @@ impl ScriptHandler for DummyInstance {
             .await
             .unwrap())
     }
+
+    async fn schedule_script(
+        &self,
+        _schema: &str,
+        _name: &str,
+        _schedule: Option<&str>,
+        _target_table: Option<&str>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
 }
 
 #[async_trait]
@@ -24,6 +24,7 @@ use rand::Rng;
 use servers::error::{self as server_error, Error, Result};
 use servers::opentsdb::codec::DataPoint;
 use servers::opentsdb::connection::Connection;
+use servers::opentsdb::query::{OpentsdbQuery, OpentsdbQueryResult, SuggestRequest};
 use servers::opentsdb::OpentsdbServer;
 use servers::query_handler::OpentsdbProtocolHandler;
 use servers::server::Server;
@@ -49,6 +50,22 @@ impl OpentsdbProtocolHandler for DummyOpentsdbInstance {
         let _ = self.tx.send(i * i).await;
         Ok(())
     }
+
+    async fn query(
+        &self,
+        _query: &OpentsdbQuery,
+        _ctx: QueryContextRef,
+    ) -> Result<Vec<OpentsdbQueryResult>> {
+        unimplemented!()
+    }
+
+    async fn suggest(
+        &self,
+        _request: &SuggestRequest,
+        _ctx: QueryContextRef,
+    ) -> Result<Vec<String>> {
+        unimplemented!()
+    }
 }
 
 fn create_opentsdb_server(tx: mpsc::Sender<i32>) -> Result<Box<dyn Server>> {
@@ -323,6 +323,21 @@ async fn test_using_db() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_read_preference() -> Result<()> {
+    let server_port = start_test_server(TlsOption::default()).await?;
+
+    let client = create_connection_with_options(server_port, "-c read_preference=follower")
+        .await
+        .unwrap();
+    let result = client.simple_query("SELECT uint32s FROM numbers").await;
+    let _ = result.unwrap();
+
+    let client = create_connection_with_options(server_port, "-c read_preference=primary").await;
+    assert!(client.is_err());
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_extended_query() -> Result<()> {
     let server_port = start_test_server(TlsOption::default()).await?;
@@ -436,6 +451,18 @@ async fn create_connection_with_given_catalog_schema(
     Ok(client)
 }
 
+async fn create_connection_with_options(
+    port: u16,
+    options: &str,
+) -> std::result::Result<Client, PgError> {
+    let url = format!(
+        "host=127.0.0.1 port={port} connect_timeout=2 dbname={DEFAULT_SCHEMA_NAME} options='{options}'"
+    );
+    let (client, conn) = tokio_postgres::connect(&url, NoTls).await?;
+    let _handle = tokio::spawn(conn);
+    Ok(client)
+}
+
 async fn create_connection_without_db(port: u16) -> std::result::Result<Client, PgError> {
     let url = format!("host=127.0.0.1 port={port} connect_timeout=2");
     let (client, conn) = tokio_postgres::connect(&url, NoTls).await?;
@@ -14,9 +14,10 @@
 
 use std::fmt::{Display, Formatter};
 use std::net::SocketAddr;
+use std::str::FromStr;
 use std::sync::Arc;
 
-use arc_swap::ArcSwap;
+use arc_swap::{ArcSwap, ArcSwapOption};
 use common_catalog::build_db_string;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_telemetry::debug;
@@ -31,6 +32,9 @@ pub struct QueryContext {
     current_catalog: ArcSwap<String>,
     current_schema: ArcSwap<String>,
     time_zone: ArcSwap<Option<TimeZone>>,
+    current_user: ArcSwap<UserInfo>,
+    conn_info: ArcSwapOption<ConnInfo>,
+    read_preference: ArcSwap<ReadPreference>,
     sql_dialect: Box<dyn Dialect + Send + Sync>,
 }
 
@@ -61,6 +65,9 @@ impl QueryContext {
             current_catalog: ArcSwap::new(Arc::new(DEFAULT_CATALOG_NAME.to_string())),
             current_schema: ArcSwap::new(Arc::new(DEFAULT_SCHEMA_NAME.to_string())),
             time_zone: ArcSwap::new(Arc::new(None)),
+            current_user: ArcSwap::new(Arc::new(UserInfo::default())),
+            conn_info: ArcSwapOption::empty(),
+            read_preference: ArcSwap::new(Arc::new(ReadPreference::default())),
             sql_dialect: Box::new(GreptimeDbDialect {}),
         }
     }
@@ -78,6 +85,9 @@ impl QueryContext {
             current_catalog: ArcSwap::new(Arc::new(catalog.to_string())),
             current_schema: ArcSwap::new(Arc::new(schema.to_string())),
             time_zone: ArcSwap::new(Arc::new(None)),
+            current_user: ArcSwap::new(Arc::new(UserInfo::default())),
+            conn_info: ArcSwapOption::empty(),
+            read_preference: ArcSwap::new(Arc::new(ReadPreference::default())),
             sql_dialect,
         }
     }
@@ -132,19 +142,88 @@ impl QueryContext {
     pub fn set_time_zone(&self, tz: Option<TimeZone>) {
         let _ = self.time_zone.swap(Arc::new(tz));
     }
+
+    /// The authenticated user that issues queries in this context.
+    #[inline]
+    pub fn current_user(&self) -> Arc<UserInfo> {
+        self.current_user.load().clone()
+    }
+
+    #[inline]
+    pub fn set_current_user(&self, user_info: UserInfo) {
+        self.current_user.store(Arc::new(user_info));
+    }
+
+    /// The connection that issues queries in this context, `None` for internal queries.
+    #[inline]
+    pub fn conn_info(&self) -> Option<Arc<ConnInfo>> {
+        self.conn_info.load_full()
+    }
+
+    #[inline]
+    pub fn set_conn_info(&self, conn_info: ConnInfo) {
+        self.conn_info.store(Some(Arc::new(conn_info)));
+    }
+
+    /// Which replicas of regions the queries in this context read from.
+    #[inline]
+    pub fn read_preference(&self) -> ReadPreference {
+        **self.read_preference.load()
+    }
+
+    #[inline]
+    pub fn set_read_preference(&self, read_preference: ReadPreference) {
+        self.read_preference.store(Arc::new(read_preference));
+    }
+}
+
+/// Replicas of regions that queries read from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReadPreference {
+    /// Reads from the leaders, which always see the latest data.
+    #[default]
+    Leader,
+    /// Reads from the followers if possible, which only see flushed data.
+    Follower,
+}
+
+impl FromStr for ReadPreference {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "leader" => Ok(ReadPreference::Leader),
+            "follower" => Ok(ReadPreference::Follower),
+            _ => Err(format!("Invalid read preference: {s}")),
+        }
+    }
+}
+
+impl Display for ReadPreference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadPreference::Leader => write!(f, "leader"),
+            ReadPreference::Follower => write!(f, "follower"),
+        }
+    }
 }
 
 pub const DEFAULT_USERNAME: &str = "greptime";
 
+/// The user issuing queries. The default one is anonymous, which is used when
+/// the request is not authenticated, e.g. no user provider is configured or
+/// the protocol has no authentication at all.
 #[derive(Clone, Debug)]
 pub struct UserInfo {
     username: String,
+    anonymous: bool,
 }
 
 impl Default for UserInfo {
     fn default() -> Self {
         Self {
             username: DEFAULT_USERNAME.to_string(),
+            anonymous: true,
         }
     }
 }
@@ -154,14 +233,21 @@ impl UserInfo {
         self.username.as_str()
     }
 
+    /// Returns true if the user is not authenticated. Its username is always
+    /// [DEFAULT_USERNAME], which must not be taken as the user of that name.
+    pub fn is_anonymous(&self) -> bool {
+        self.anonymous
+    }
+
     pub fn new(username: impl Into<String>) -> Self {
         Self {
             username: username.into(),
+            anonymous: false,
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ConnInfo {
     pub client_addr: Option<SocketAddr>,
     pub channel: Channel,
@@ -190,10 +276,12 @@ impl ConnInfo {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Channel {
     Mysql,
     Postgres,
+    Http,
+    Grpc,
 }
 
 impl Channel {
@@ -201,6 +289,7 @@ impl Channel {
         match self {
             Channel::Mysql => Box::new(MySqlDialect {}),
             Channel::Postgres => Box::new(PostgreSqlDialect {}),
+            Channel::Http | Channel::Grpc => Box::new(GreptimeDbDialect {}),
         }
     }
 }
@@ -210,6 +299,8 @@ impl std::fmt::Display for Channel {
         match self {
             Channel::Mysql => write!(f, "mysql"),
             Channel::Postgres => write!(f, "postgres"),
+            Channel::Http => write!(f, "http"),
+            Channel::Grpc => write!(f, "grpc"),
         }
     }
 }
@@ -225,8 +316,10 @@ mod test {
         let session = Session::new(Some("127.0.0.1:9000".parse().unwrap()), Channel::Mysql);
         // test user_info
         assert_eq!(session.user_info().username(), "greptime");
+        assert!(session.user_info().is_anonymous());
         session.set_user_info(UserInfo::new("root"));
         assert_eq!(session.user_info().username(), "root");
+        assert!(!session.user_info().is_anonymous());
 
         // test channel
         assert_eq!(session.conn_info().channel, Channel::Mysql);
@@ -251,4 +344,17 @@ mod test {
 
         assert_eq!("test", context.get_db_string());
     }
+
+    #[test]
+    fn test_read_preference() {
+        let context = QueryContext::new();
+        assert_eq!(ReadPreference::Leader, context.read_preference());
+
+        let read_preference = "FOLLOWER".parse().unwrap();
+        context.set_read_preference(read_preference);
+        assert_eq!(ReadPreference::Follower, context.read_preference());
+        assert_eq!("follower", context.read_preference().to_string());
+
+        assert!("primary".parse::<ReadPreference>().is_err());
+    }
 }
@@ -17,7 +17,6 @@ pub mod context;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use arc_swap::ArcSwap;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 
 use crate::context::{Channel, ConnInfo, QueryContext, QueryContextRef, UserInfo};
@@ -26,22 +25,20 @@ use crate::context::{Channel, ConnInfo, QueryContext, QueryContextRef, UserInfo}
 #[derive(Debug)]
 pub struct Session {
     query_ctx: QueryContextRef,
-    user_info: ArcSwap<UserInfo>,
-    conn_info: ConnInfo,
 }
 
 pub type SessionRef = Arc<Session>;
 
 impl Session {
     pub fn new(addr: Option<SocketAddr>, channel: Channel) -> Self {
+        let query_ctx = QueryContext::with_sql_dialect(
+            DEFAULT_CATALOG_NAME,
+            DEFAULT_SCHEMA_NAME,
+            channel.dialect(),
+        );
+        query_ctx.set_conn_info(ConnInfo::new(addr, channel));
         Session {
-            query_ctx: Arc::new(QueryContext::with_sql_dialect(
-                DEFAULT_CATALOG_NAME,
-                DEFAULT_SCHEMA_NAME,
-                channel.dialect(),
-            )),
-            user_info: ArcSwap::new(Arc::new(UserInfo::default())),
-            conn_info: ConnInfo::new(addr, channel),
+            query_ctx: Arc::new(query_ctx),
         }
     }
 
@@ -51,22 +48,25 @@ impl Session {
     }
 
     #[inline]
-    pub fn conn_info(&self) -> &ConnInfo {
-        &self.conn_info
+    pub fn conn_info(&self) -> Arc<ConnInfo> {
+        // Safety: conn info is always set on creation.
+        self.query_ctx.conn_info().unwrap()
     }
 
     #[inline]
-    pub fn mut_conn_info(&mut self) -> &mut ConnInfo {
-        &mut self.conn_info
+    pub fn set_client_addr(&self, addr: SocketAddr) {
+        let channel = self.conn_info().channel;
+        self.query_ctx
+            .set_conn_info(ConnInfo::new(Some(addr), channel));
     }
 
     #[inline]
     pub fn user_info(&self) -> Arc<UserInfo> {
-        self.user_info.load().clone()
+        self.query_ctx.current_user()
     }
 
     #[inline]
     pub fn set_user_info(&self, user_info: UserInfo) {
-        self.user_info.store(Arc::new(user_info));
+        self.query_ctx.set_current_user(user_info);
     }
 }
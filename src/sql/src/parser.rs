@@ -16,15 +16,18 @@ use snafu::{ensure, ResultExt};
 use sqlparser::dialect::Dialect;
 use sqlparser::keywords::Keyword;
 use sqlparser::parser::{Parser, ParserError};
-use sqlparser::tokenizer::{Token, TokenWithLocation};
+use sqlparser::tokenizer::{Token, TokenWithLocation, Tokenizer};
 
 use crate::ast::{Expr, ObjectName};
 use crate::error::{self, InvalidDatabaseNameSnafu, InvalidTableNameSnafu, Result, SyntaxSnafu};
-use crate::parsers::tql_parser;
+use crate::parsers::{range_parser, tql_parser};
 use crate::statements::describe::DescribeTable;
-use crate::statements::drop::DropTable;
+use crate::statements::drop::{DropMaterializedView, DropTable, DropView};
 use crate::statements::explain::Explain;
-use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowKind, ShowTables};
+use crate::statements::kill::Kill;
+use crate::statements::show::{
+    ShowCreateTable, ShowDatabases, ShowKind, ShowProcesslist, ShowTables,
+};
 use crate::statements::statement::Statement;
 
 /// GrepTime SQL parser context, a simple wrapper for Datafusion SQL parser.
@@ -38,9 +41,12 @@ impl<'a> ParserContext<'a> {
     pub fn create_with_dialect(sql: &'a str, dialect: &dyn Dialect) -> Result<Vec<Statement>> {
         let mut stmts: Vec<Statement> = Vec::new();
 
-        let parser = Parser::new(dialect)
-            .try_with_sql(sql)
+        let tokens = Tokenizer::new(dialect, sql)
+            .tokenize_with_location()
+            .map_err(ParserError::from)
+            .and_then(range_parser::rewrite_range_query)
             .context(SyntaxSnafu { sql })?;
+        let parser = Parser::new(dialect).with_tokens_with_locations(tokens);
         let mut parser_ctx = ParserContext { sql, parser };
 
         let mut expecting_statement_delimiter = false;
@@ -127,6 +133,15 @@ impl<'a> ParserContext<'a> {
 
                     Keyword::COPY => self.parse_copy(),
 
+                    Keyword::GRANT => self.parse_grant(),
+
+                    Keyword::REVOKE => self.parse_revoke(),
+
+                    Keyword::KILL => {
+                        let _ = self.parser.next_token();
+                        self.parse_kill()
+                    }
+
                     Keyword::NoKeyword
                         if w.value.to_uppercase() == tql_parser::TQL && w.quote_style.is_none() =>
                     {
@@ -165,11 +180,33 @@ impl<'a> ParserContext<'a> {
             } else {
                 self.unsupported(self.peek_token_as_string())
             }
+        } else if self.consume_token("PROCESSLIST") {
+            Ok(Statement::ShowProcesslist(ShowProcesslist { full: false }))
+        } else if self.consume_token("FULL") {
+            if self.consume_token("PROCESSLIST") {
+                Ok(Statement::ShowProcesslist(ShowProcesslist { full: true }))
+            } else {
+                self.unsupported(self.peek_token_as_string())
+            }
         } else {
             self.unsupported(self.peek_token_as_string())
         }
     }
 
+    /// Parses `KILL [QUERY] <id>`, the `KILL` keyword has been consumed.
+    fn parse_kill(&mut self) -> Result<Statement> {
+        let _ = self.consume_token("QUERY");
+        let id = self
+            .parser
+            .parse_literal_uint()
+            .with_context(|_| error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a process id",
+                actual: self.peek_token_as_string(),
+            })?;
+        Ok(Statement::Kill(Kill { id }))
+    }
+
     /// Parse SHOW CREATE TABLE statement
     fn parse_show_create_table(&mut self) -> Result<Statement> {
         let table_name =
@@ -300,6 +337,12 @@ impl<'a> ParserContext<'a> {
 
     fn parse_drop(&mut self) -> Result<Statement> {
         let _ = self.parser.next_token();
+        if self.matches_keyword(Keyword::USER) || self.matches_keyword(Keyword::ROLE) {
+            return self.parse_drop_user();
+        }
+        if self.matches_keyword(Keyword::VIEW) || self.matches_keyword(Keyword::MATERIALIZED) {
+            return self.parse_drop_view();
+        }
         if !self.matches_keyword(Keyword::TABLE) {
             return self.unsupported(self.peek_token_as_string());
         }
@@ -323,6 +366,37 @@ impl<'a> ParserContext<'a> {
         Ok(Statement::DropTable(DropTable::new(table_ident)))
     }
 
+    fn parse_drop_view(&mut self) -> Result<Statement> {
+        let materialized = self.parser.parse_keyword(Keyword::MATERIALIZED);
+        self.parser
+            .expect_keyword(Keyword::VIEW)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+
+        let view_ident =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a view name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        ensure!(
+            !view_ident.0.is_empty(),
+            InvalidTableNameSnafu {
+                name: view_ident.to_string()
+            }
+        );
+
+        if materialized {
+            Ok(Statement::DropMaterializedView(DropMaterializedView::new(
+                view_ident, if_exists,
+            )))
+        } else {
+            Ok(Statement::DropView(DropView::new(view_ident, if_exists)))
+        }
+    }
+
     // Report unexpected token
     pub(crate) fn expected<T>(&self, expected: &str, found: TokenWithLocation) -> Result<T> {
         Err(ParserError::ParserError(format!(
@@ -621,6 +695,45 @@ mod tests {
         )
     }
 
+    #[test]
+    pub fn test_drop_view() {
+        let sql = "DROP VIEW foo";
+        let result = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {});
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::DropView(DropView::new(ObjectName(vec![Ident::new("foo")]), false))
+        );
+
+        let sql = "DROP VIEW IF EXISTS my_schema.foo";
+        let result = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {});
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::DropView(DropView::new(
+                ObjectName(vec![Ident::new("my_schema"), Ident::new("foo")]),
+                true
+            ))
+        );
+    }
+
+    #[test]
+    pub fn test_drop_materialized_view() {
+        let sql = "DROP MATERIALIZED VIEW IF EXISTS cpu_5m";
+        let result = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {});
+        let mut stmts = result.unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::DropMaterializedView(DropMaterializedView::new(
+                ObjectName(vec![Ident::new("cpu_5m")]),
+                true
+            ))
+        );
+
+        let sql = "DROP MATERIALIZED cpu_5m";
+        assert!(ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).is_err());
+    }
+
     fn test_timestamp_precision(sql: &str, expected_type: ConcreteDataType) {
         match ParserContext::create_with_dialect(sql, &GreptimeDbDialect {})
             .unwrap()
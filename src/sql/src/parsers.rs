@@ -18,4 +18,6 @@ pub(crate) mod create_parser;
 pub(crate) mod delete_parser;
 pub(crate) mod insert_parser;
 pub(crate) mod query_parser;
+pub mod range_parser;
 pub(crate) mod tql_parser;
+pub(crate) mod user_parser;
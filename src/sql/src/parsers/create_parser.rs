@@ -31,8 +31,10 @@ use crate::error::{
 };
 use crate::parser::ParserContext;
 use crate::statements::create::{
-    CreateDatabase, CreateExternalTable, CreateTable, PartitionEntry, Partitions, TIME_INDEX,
+    CreateDatabase, CreateExternalTable, CreateMaterializedView, CreateTable, CreateView,
+    PartitionEntry, Partitions, TIME_INDEX,
 };
+use crate::statements::query::Query;
 use crate::statements::statement::Statement;
 use crate::statements::{sql_data_type_to_concrete_data_type, sql_value_to_value};
 use crate::util::parse_option_string;
@@ -54,6 +56,14 @@ impl<'a> ParserContext<'a> {
 
                 Keyword::EXTERNAL => self.parse_create_external_table(),
 
+                Keyword::USER => self.parse_create_user(),
+
+                Keyword::ROLE => self.parse_create_role(),
+
+                Keyword::OR | Keyword::VIEW => self.parse_create_view(),
+
+                Keyword::MATERIALIZED => self.parse_create_materialized_view(),
+
                 _ => self.unsupported(w.to_string()),
             },
             unexpected => self.unsupported(unexpected.to_string()),
@@ -77,6 +87,7 @@ impl<'a> ParserContext<'a> {
                 actual: self.peek_token_as_string(),
             })?;
         let (columns, constraints) = self.parse_columns()?;
+        let partition_columns = self.parse_partition_columns()?;
         let engine = self.parse_table_engine(common_catalog::consts::IMMUTABLE_FILE_ENGINE)?;
         let options = self
             .parser
@@ -96,12 +107,99 @@ impl<'a> ParserContext<'a> {
             name: table_name,
             columns,
             constraints,
+            partition_columns,
             options,
             if_not_exists,
             engine,
         }))
     }
 
+    // "PARTITION COLUMNS (dt, host)" of external tables
+    fn parse_partition_columns(&mut self) -> Result<Vec<Ident>> {
+        if !self.parser.parse_keyword(Keyword::PARTITION) {
+            return Ok(vec![]);
+        }
+        self.parser
+            .expect_keyword(Keyword::COLUMNS)
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "COLUMNS",
+                actual: self.peek_token_as_string(),
+            })?;
+
+        self.parser
+            .parse_parenthesized_column_list(Mandatory, false)
+            .context(error::SyntaxSnafu { sql: self.sql })
+    }
+
+    fn parse_create_view(&mut self) -> Result<Statement> {
+        let or_replace = self.parser.parse_keywords(&[Keyword::OR, Keyword::REPLACE]);
+        self.parser
+            .expect_keyword(Keyword::VIEW)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+
+        let view_name = self
+            .parser
+            .parse_object_name()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a view name",
+                actual: self.peek_token_as_string(),
+            })?;
+
+        self.parser
+            .expect_keyword(Keyword::AS)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+        let query = self
+            .parser
+            .parse_query()
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+
+        Ok(Statement::CreateView(CreateView {
+            name: view_name,
+            query: Box::new(Query::try_from(query)?),
+            or_replace,
+        }))
+    }
+
+    fn parse_create_materialized_view(&mut self) -> Result<Statement> {
+        self.parser
+            .expect_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW])
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+
+        let view_name = self
+            .parser
+            .parse_object_name()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a view name",
+                actual: self.peek_token_as_string(),
+            })?;
+        let options = self
+            .parser
+            .parse_options(Keyword::WITH)
+            .context(error::SyntaxSnafu { sql: self.sql })?
+            .into_iter()
+            .filter_map(|option| {
+                parse_option_string(option.value).map(|v| (option.name.value.to_lowercase(), v))
+            })
+            .collect();
+
+        self.parser
+            .expect_keyword(Keyword::AS)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+        let query = self
+            .parser
+            .parse_query()
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+
+        Ok(Statement::CreateMaterializedView(CreateMaterializedView {
+            name: view_name,
+            query: Box::new(Query::try_from(query)?),
+            options,
+        }))
+    }
+
     fn parse_create_database(&mut self) -> Result<Statement> {
         let _ = self.parser.next_token();
 
@@ -788,6 +886,57 @@ mod tests {
     use super::*;
     use crate::dialect::GreptimeDbDialect;
 
+    #[test]
+    fn test_parse_create_view() {
+        let sql = "CREATE VIEW my_schema.v AS SELECT host, cpu FROM monitor WHERE cpu > 0.5";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        let Statement::CreateView(create_view) = stmts.pop().unwrap() else { unreachable!() };
+        assert_eq!("my_schema.v", create_view.name.to_string());
+        assert_eq!(
+            "SELECT host, cpu FROM monitor WHERE cpu > 0.5",
+            create_view.query.inner.to_string()
+        );
+        assert!(!create_view.or_replace);
+
+        let sql = "CREATE OR REPLACE VIEW v AS SELECT 1";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
+        let Statement::CreateView(create_view) = stmts.pop().unwrap() else { unreachable!() };
+        assert_eq!("v", create_view.name.to_string());
+        assert!(create_view.or_replace);
+
+        let sql = "CREATE VIEW v SELECT 1";
+        assert!(ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).is_err());
+
+        let sql = "CREATE OR VIEW v AS SELECT 1";
+        assert!(ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).is_err());
+    }
+
+    #[test]
+    fn test_parse_create_materialized_view() {
+        let sql = "CREATE MATERIALIZED VIEW cpu_5m WITH (refresh_interval = '1m') AS SELECT date_bin(INTERVAL '5 minutes', ts) AS ts, host, avg(cpu) AS cpu FROM monitor GROUP BY 1, host";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        let Statement::CreateMaterializedView(create) = stmts.pop().unwrap() else { unreachable!() };
+        assert_eq!("cpu_5m", create.name.to_string());
+        assert_eq!(
+            "SELECT date_bin(INTERVAL '5 minutes', ts) AS ts, host, avg(cpu) AS cpu FROM monitor GROUP BY 1, host",
+            create.query.inner.to_string()
+        );
+        assert_eq!(
+            HashMap::from([("refresh_interval".to_string(), "1m".to_string())]),
+            create.options
+        );
+
+        let sql = "CREATE MATERIALIZED VIEW cpu_5m AS SELECT 1";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
+        let Statement::CreateMaterializedView(create) = stmts.pop().unwrap() else { unreachable!() };
+        assert!(create.options.is_empty());
+
+        let sql = "CREATE MATERIALIZED cpu_5m AS SELECT 1";
+        assert!(ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).is_err());
+    }
+
     #[test]
     fn test_parse_create_external_table() {
         struct Test<'a> {
@@ -886,6 +1035,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_create_external_table_with_partition_columns() {
+        let sql = "CREATE EXTERNAL TABLE logs PARTITION COLUMNS (dt, host) with(location='/var/data/logs/',format='parquet');";
+        let stmts = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        let Statement::CreateExternalTable(c) = &stmts[0] else { unreachable!() };
+        assert_eq!("logs", c.name.to_string());
+        assert!(c.columns.is_empty());
+        assert_eq!(
+            vec![Ident::new("dt"), Ident::new("host")],
+            c.partition_columns
+        );
+        assert_eq!(IMMUTABLE_FILE_ENGINE, c.engine);
+
+        let sql = "CREATE EXTERNAL TABLE logs (msg string) PARTITION COLUMNS (dt) with(location='/var/data/logs/',format='csv');";
+        let stmts = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
+        let Statement::CreateExternalTable(c) = &stmts[0] else { unreachable!() };
+        assert_eq!(1, c.columns.len());
+        assert_eq!(vec![Ident::new("dt")], c.partition_columns);
+
+        let sql = "CREATE EXTERNAL TABLE logs PARTITION (dt) with(location='/var/data/logs/',format='csv');";
+        assert!(ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).is_err());
+    }
+
     #[test]
     fn test_parse_create_database() {
         let sql = "create database";
@@ -0,0 +1,372 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sqlparser::keywords::Keyword;
+use sqlparser::parser::ParserError;
+use sqlparser::tokenizer::{Location, Token, TokenWithLocation, Whitespace};
+
+/// The function wrapping an aggregate with its `RANGE` and `FILL` options,
+/// i.e. `range_fn(<aggregate>, '<range>', '<fill>')`.
+pub const RANGE_FN: &str = "range_fn";
+/// The function in the `GROUP BY` clause that holds the `ALIGN` options, i.e.
+/// `range_align('<align>', '<fill>', <by>)`, the `BY` expressions follow it.
+pub const RANGE_ALIGN: &str = "range_align";
+
+const RANGE: &str = "RANGE";
+const ALIGN: &str = "ALIGN";
+const FILL: &str = "FILL";
+const BY: &str = "BY";
+const PREV: &str = "PREV";
+const LINEAR: &str = "LINEAR";
+
+/// Rewrites the range query extension into plain SQL functions, so that the
+/// query is parsed by the sql parser as is:
+/// - `<aggregate>(<args>) RANGE '<range>' [FILL <fill>]` is rewritten into
+///   `range_fn(<aggregate>(<args>), '<range>', '<fill>')`
+/// - `ALIGN '<align>' [BY (<exprs>)] [FILL <fill>]` is rewritten into
+///   `GROUP BY range_align('<align>', '<fill>', <by>), <exprs>`
+///
+/// `<fill>` is one of `NULL`, `PREV`, `LINEAR` or a constant, it's left empty
+/// if `FILL` is absent. `<by>` tells whether `BY` is present.
+pub(crate) fn rewrite_range_query(
+    tokens: Vec<TokenWithLocation>,
+) -> Result<Vec<TokenWithLocation>, ParserError> {
+    let mut rewriter = RangeRewriter {
+        tokens,
+        pos: 0,
+        output: vec![],
+        open_parens: vec![],
+        closed_function: None,
+    };
+    rewriter.rewrite()?;
+    Ok(rewriter.output)
+}
+
+struct RangeRewriter {
+    tokens: Vec<TokenWithLocation>,
+    pos: usize,
+    output: Vec<TokenWithLocation>,
+    /// The output positions of the functions (or `None` for plain parentheses)
+    /// whose parentheses are open.
+    open_parens: Vec<Option<usize>>,
+    /// The output position of the function whose closing parenthesis is the
+    /// last non-whitespace output token.
+    closed_function: Option<usize>,
+}
+
+impl RangeRewriter {
+    fn rewrite(&mut self) -> Result<(), ParserError> {
+        while self.pos < self.tokens.len() {
+            let token = self.tokens[self.pos].clone();
+            if self.is_range_of_function(&token) {
+                self.rewrite_range()?;
+                continue;
+            }
+            if self.is_align(&token) {
+                self.rewrite_align()?;
+                continue;
+            }
+
+            self.pos += 1;
+            match &token.token {
+                Token::Whitespace(_) => {}
+                Token::LParen => {
+                    let function = self.function_start();
+                    self.open_parens.push(function);
+                    self.closed_function = None;
+                }
+                Token::RParen => {
+                    self.closed_function = self.open_parens.pop().flatten();
+                }
+                _ => self.closed_function = None,
+            }
+            self.output.push(token);
+        }
+        Ok(())
+    }
+
+    /// Whether the token is the `RANGE` following a function call.
+    fn is_range_of_function(&self, token: &TokenWithLocation) -> bool {
+        self.closed_function.is_some()
+            && is_word(&token.token, RANGE)
+            && matches!(
+                self.peek_significant(self.pos + 1),
+                Some((_, Token::SingleQuotedString(_)))
+            )
+    }
+
+    fn is_align(&self, token: &TokenWithLocation) -> bool {
+        is_word(&token.token, ALIGN)
+            && matches!(
+                self.peek_significant(self.pos + 1),
+                Some((_, Token::SingleQuotedString(_)))
+            )
+    }
+
+    fn rewrite_range(&mut self) -> Result<(), ParserError> {
+        let location = self.tokens[self.pos].location;
+        let function_start = self.closed_function.take().unwrap();
+        self.pos += 1;
+        let range = self.next_quoted_string()?;
+        let fill = self.next_fill()?;
+
+        let wrapped = [Token::make_word(RANGE_FN, None), Token::LParen]
+            .map(|token| TokenWithLocation { token, location });
+        let _ = self.output.splice(function_start..function_start, wrapped);
+        while matches!(
+            self.output.last(),
+            Some(TokenWithLocation {
+                token: Token::Whitespace(_),
+                ..
+            })
+        ) {
+            let _ = self.output.pop();
+        }
+        self.push_all(
+            [
+                Token::Comma,
+                Token::SingleQuotedString(range),
+                Token::Comma,
+                Token::SingleQuotedString(fill),
+                Token::RParen,
+            ],
+            location,
+        );
+        Ok(())
+    }
+
+    fn rewrite_align(&mut self) -> Result<(), ParserError> {
+        let location = self.tokens[self.pos].location;
+        self.pos += 1;
+        let align = self.next_quoted_string()?;
+
+        let by = match self.peek_significant(self.pos) {
+            Some((index, token)) if is_word(token, BY) => {
+                self.pos = index + 1;
+                Some(self.next_parenthesized()?)
+            }
+            _ => None,
+        };
+        let fill = self.next_fill()?;
+
+        self.push_all(
+            [
+                Token::Whitespace(Whitespace::Space),
+                Token::make_keyword("GROUP"),
+                Token::Whitespace(Whitespace::Space),
+                Token::make_keyword("BY"),
+                Token::Whitespace(Whitespace::Space),
+                Token::make_word(RANGE_ALIGN, None),
+                Token::LParen,
+                Token::SingleQuotedString(align),
+                Token::Comma,
+                Token::SingleQuotedString(fill),
+                Token::Comma,
+                Token::make_keyword(if by.is_some() { "TRUE" } else { "FALSE" }),
+                Token::RParen,
+            ],
+            location,
+        );
+        let exprs = by.unwrap_or_default();
+        if exprs
+            .iter()
+            .any(|t| !matches!(t.token, Token::Whitespace(_)))
+        {
+            self.push_all(
+                [Token::Comma, Token::Whitespace(Whitespace::Space)],
+                location,
+            );
+            self.output.extend(exprs);
+        }
+        self.push_all([Token::Whitespace(Whitespace::Space)], location);
+        self.closed_function = None;
+        Ok(())
+    }
+
+    /// Returns the output position of the function name, if the next `(` opens
+    /// the arguments of a function.
+    fn function_start(&self) -> Option<usize> {
+        let mut start = self.output.len().checked_sub(1)?;
+        if !matches!(self.output[start].token, Token::Word(_)) {
+            return None;
+        }
+        // Includes the qualifiers of the function name, e.g. `schema.func`.
+        while start >= 2
+            && self.output[start - 1].token == Token::Period
+            && matches!(self.output[start - 2].token, Token::Word(_))
+        {
+            start -= 2;
+        }
+        Some(start)
+    }
+
+    fn peek_significant(&self, from: usize) -> Option<(usize, &Token)> {
+        self.tokens[from.min(self.tokens.len())..]
+            .iter()
+            .enumerate()
+            .find(|(_, t)| !matches!(t.token, Token::Whitespace(_)))
+            .map(|(i, t)| (from + i, &t.token))
+    }
+
+    fn next_significant(&mut self) -> Option<Token> {
+        let (index, token) = self.peek_significant(self.pos)?;
+        let token = token.clone();
+        self.pos = index + 1;
+        Some(token)
+    }
+
+    fn next_quoted_string(&mut self) -> Result<String, ParserError> {
+        match self.next_significant() {
+            Some(Token::SingleQuotedString(s)) => Ok(s),
+            other => Err(expected("a quoted duration", other)),
+        }
+    }
+
+    /// Consumes `FILL <fill>` if present, returns the normalized `<fill>` or an
+    /// empty string.
+    fn next_fill(&mut self) -> Result<String, ParserError> {
+        match self.peek_significant(self.pos) {
+            Some((index, token)) if is_word(token, FILL) => self.pos = index + 1,
+            _ => return Ok(String::new()),
+        }
+
+        match self.next_significant() {
+            Some(Token::Word(w)) if w.quote_style.is_none() && w.keyword == Keyword::NULL => {
+                Ok("NULL".to_string())
+            }
+            Some(Token::Word(w))
+                if w.quote_style.is_none()
+                    && (w.value.eq_ignore_ascii_case(PREV)
+                        || w.value.eq_ignore_ascii_case(LINEAR)) =>
+            {
+                Ok(w.value.to_uppercase())
+            }
+            Some(Token::Number(n, _)) => Ok(n),
+            Some(Token::Minus) => match self.next_significant() {
+                Some(Token::Number(n, _)) => Ok(format!("-{n}")),
+                other => Err(expected("a number", other)),
+            },
+            other => Err(expected(
+                "NULL, PREV, LINEAR or a constant after FILL",
+                other,
+            )),
+        }
+    }
+
+    /// Consumes `( ... )`, returns the tokens inside the parentheses.
+    fn next_parenthesized(&mut self) -> Result<Vec<TokenWithLocation>, ParserError> {
+        match self.next_significant() {
+            Some(Token::LParen) => {}
+            other => return Err(expected("(", other)),
+        }
+
+        let start = self.pos;
+        let mut depth = 1;
+        while let Some(token) = self.tokens.get(self.pos) {
+            self.pos += 1;
+            match token.token {
+                Token::LParen => depth += 1,
+                Token::RParen => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(self.tokens[start..self.pos - 1].to_vec());
+                    }
+                }
+                _ => {}
+            }
+        }
+        Err(expected(")", None))
+    }
+
+    fn push_all<const N: usize>(&mut self, tokens: [Token; N], location: Location) {
+        self.output
+            .extend(tokens.map(|token| TokenWithLocation { token, location }));
+    }
+}
+
+fn is_word(token: &Token, value: &str) -> bool {
+    matches!(token, Token::Word(w) if w.quote_style.is_none() && w.value.eq_ignore_ascii_case(value))
+}
+
+fn expected(expected: &str, found: Option<Token>) -> ParserError {
+    ParserError::ParserError(format!(
+        "Expected {expected} in range query, found: {}",
+        found.unwrap_or(Token::EOF)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::tokenizer::Tokenizer;
+
+    use super::*;
+    use crate::dialect::GreptimeDbDialect;
+    use crate::parser::ParserContext;
+    use crate::statements::statement::Statement;
+
+    fn rewrite(sql: &str) -> Result<String, ParserError> {
+        let tokens = Tokenizer::new(&GreptimeDbDialect {}, sql)
+            .tokenize_with_location()
+            .unwrap();
+        let tokens = rewrite_range_query(tokens)?;
+        Ok(tokens
+            .into_iter()
+            .map(|t| t.token.to_string())
+            .collect::<String>())
+    }
+
+    #[test]
+    fn test_rewrite_range_query() {
+        assert_eq!(
+            "SELECT ts, host, range_fn(avg(cpu), '5m', 'PREV'), range_fn(max(cpu + 1), '10m', '') FROM monitor  GROUP BY range_align('1m', '', TRUE), host ",
+            rewrite("SELECT ts, host, avg(cpu) RANGE '5m' FILL prev, max(cpu + 1) RANGE '10m' FROM monitor ALIGN '1m' BY (host)").unwrap()
+        );
+
+        assert_eq!(
+            "SELECT range_fn(s.f(a), '1h', '-1.5') AS x FROM t WHERE a > 1  GROUP BY range_align('5m', 'LINEAR', FALSE)  ORDER BY x",
+            rewrite("SELECT s.f(a) RANGE '1h' FILL -1.5 AS x FROM t WHERE a > 1 ALIGN '5m' FILL LINEAR ORDER BY x").unwrap()
+        );
+
+        assert_eq!(
+            "SELECT range_fn(count(*), '1m', 'NULL') FROM t  GROUP BY range_align('1m', '', TRUE) ",
+            rewrite("SELECT count(*) RANGE '1m' FILL NULL FROM t ALIGN '1m' BY ()").unwrap()
+        );
+
+        // Window frames are left as is.
+        let sql =
+            "SELECT sum(a) OVER (ORDER BY f(b) RANGE BETWEEN 1 PRECEDING AND CURRENT ROW) FROM t";
+        assert_eq!(sql, rewrite(sql).unwrap());
+
+        let result = rewrite("SELECT avg(a) RANGE '1m' FILL foo FROM t ALIGN '1m'");
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Expected NULL, PREV, LINEAR or a constant after FILL"));
+        assert!(rewrite("SELECT avg(a) RANGE '1m' FROM t ALIGN '1m' BY (host").is_err());
+    }
+
+    #[test]
+    fn test_parse_range_query() {
+        let sql = "SELECT ts, host, avg(cpu) RANGE '5m' FILL PREV FROM monitor ALIGN '1m' BY (host) ORDER BY host LIMIT 10";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        let Statement::Query(query) = stmts.remove(0) else {
+            unreachable!()
+        };
+        assert_eq!(
+            "SELECT ts, host, range_fn(avg(cpu), '5m', 'PREV') FROM monitor GROUP BY range_align('1m', '', true), host ORDER BY host LIMIT 10",
+            query.inner.to_string()
+        );
+    }
+}
@@ -0,0 +1,360 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snafu::ResultExt;
+use sqlparser::ast::ObjectName;
+use sqlparser::keywords::Keyword;
+use sqlparser::tokenizer::Token;
+
+use crate::ast::Ident;
+use crate::error::{self, Result};
+use crate::parser::ParserContext;
+use crate::statements::statement::Statement;
+use crate::statements::user::{
+    CreateRole, CreateUser, DropUser, Grant, GrantObject, GrantTarget, Privilege, Revoke,
+};
+
+const IDENTIFIED: &str = "IDENTIFIED";
+
+/// Parses user and role management statements:
+///
+/// ```sql
+/// CREATE USER [IF NOT EXISTS] <name> IDENTIFIED BY '<password>';
+/// CREATE ROLE [IF NOT EXISTS] <name>;
+/// DROP {USER | ROLE} [IF EXISTS] <name>;
+/// GRANT {<privilege>[, ...] ON {*.* | <db>.* | [<db>.]<table>} | <role>} TO <name>;
+/// REVOKE {<privilege>[, ...] ON {*.* | <db>.* | [<db>.]<table>} | <role>} FROM <name>;
+/// ```
+///
+/// where `<privilege>` is one of `READ` (or `SELECT`), `WRITE` (or `INSERT`),
+/// `DDL` and `ALL [PRIVILEGES]`.
+impl<'a> ParserContext<'a> {
+    pub(crate) fn parse_create_user(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let name = self.parse_principal_name("a user name")?;
+
+        if !self.consume_token(IDENTIFIED) {
+            return self.expected("IDENTIFIED BY", self.parser.peek_token());
+        }
+        self.parser
+            .expect_keyword(Keyword::BY)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+        let password =
+            self.parser
+                .parse_literal_string()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a password string",
+                    actual: self.peek_token_as_string(),
+                })?;
+
+        Ok(Statement::CreateUser(CreateUser {
+            name,
+            password,
+            if_not_exists,
+        }))
+    }
+
+    pub(crate) fn parse_create_role(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let name = self.parse_principal_name("a role name")?;
+
+        Ok(Statement::CreateRole(CreateRole {
+            name,
+            if_not_exists,
+        }))
+    }
+
+    /// Parses `DROP USER` or `DROP ROLE`, the `DROP` keyword has been consumed.
+    pub(crate) fn parse_drop_user(&mut self) -> Result<Statement> {
+        let is_role = match self.parser.next_token().token {
+            Token::Word(w) if w.keyword == Keyword::USER => false,
+            Token::Word(w) if w.keyword == Keyword::ROLE => true,
+            unexpected => return self.unsupported(unexpected.to_string()),
+        };
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let name = self.parse_principal_name(if is_role {
+            "a role name"
+        } else {
+            "a user name"
+        })?;
+
+        Ok(Statement::DropUser(DropUser {
+            name,
+            if_exists,
+            is_role,
+        }))
+    }
+
+    pub(crate) fn parse_grant(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let target = self.parse_grant_target()?;
+        self.parser
+            .expect_keyword(Keyword::TO)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+        let grantee = self.parse_principal_name("a user or role name")?;
+
+        Ok(Statement::Grant(Grant { target, grantee }))
+    }
+
+    pub(crate) fn parse_revoke(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let target = self.parse_grant_target()?;
+        self.parser
+            .expect_keyword(Keyword::FROM)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+        let grantee = self.parse_principal_name("a user or role name")?;
+
+        Ok(Statement::Revoke(Revoke { target, grantee }))
+    }
+
+    fn parse_grant_target(&mut self) -> Result<GrantTarget> {
+        // A single name directly followed by `TO` or `FROM` is a role.
+        if let Token::Word(next) = self.parser.peek_nth_token(1).token {
+            if next.keyword == Keyword::TO || next.keyword == Keyword::FROM {
+                let role = self.parse_principal_name("a role name")?;
+                return Ok(GrantTarget::Role(role));
+            }
+        }
+
+        let mut privileges = vec![];
+        loop {
+            privileges.push(self.parse_privilege()?);
+            if !self.parser.consume_token(&Token::Comma) {
+                break;
+            }
+        }
+        self.parser
+            .expect_keyword(Keyword::ON)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+        let object = self.parse_grant_object()?;
+
+        Ok(GrantTarget::Privileges { privileges, object })
+    }
+
+    fn parse_privilege(&mut self) -> Result<Privilege> {
+        let token = self.parser.next_token();
+        let word = match &token.token {
+            Token::Word(w) => w.value.to_uppercase(),
+            _ => return self.expected("a privilege", token),
+        };
+        let privilege = match word.as_str() {
+            "READ" | "SELECT" => Privilege::Read,
+            "WRITE" | "INSERT" => Privilege::Write,
+            "DDL" => Privilege::Ddl,
+            "ALL" => {
+                let _ = self.parser.parse_keyword(Keyword::PRIVILEGES);
+                Privilege::All
+            }
+            _ => return self.expected("a privilege", token),
+        };
+        Ok(privilege)
+    }
+
+    fn parse_grant_object(&mut self) -> Result<GrantObject> {
+        if self.parser.consume_token(&Token::Mul) {
+            for expected in [Token::Period, Token::Mul] {
+                self.parser
+                    .expect_token(&expected)
+                    .context(error::SyntaxSnafu { sql: self.sql })?;
+            }
+            return Ok(GrantObject::All);
+        }
+
+        let mut idents = vec![self.parse_object_ident()?];
+        while self.parser.consume_token(&Token::Period) {
+            if self.parser.consume_token(&Token::Mul) {
+                return Ok(GrantObject::Database(ObjectName(idents)));
+            }
+            idents.push(self.parse_object_ident()?);
+        }
+        Ok(GrantObject::Table(ObjectName(idents)))
+    }
+
+    fn parse_object_ident(&mut self) -> Result<Ident> {
+        self.parser
+            .parse_identifier()
+            .with_context(|_| error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a database or table name",
+                actual: self.peek_token_as_string(),
+            })
+    }
+
+    /// Parses a user or role name, which is either an identifier or a string literal.
+    fn parse_principal_name(&mut self, expected: &str) -> Result<String> {
+        let token = self.parser.next_token();
+        match token.token {
+            Token::Word(w) => Ok(w.value),
+            Token::SingleQuotedString(s) | Token::DoubleQuotedString(s) => Ok(s),
+            _ => self.expected(expected, token),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use super::*;
+    use crate::dialect::GreptimeDbDialect;
+
+    fn parse(sql: &str) -> Result<Statement> {
+        let mut stmts = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {})?;
+        assert_eq!(1, stmts.len());
+        Ok(stmts.remove(0))
+    }
+
+    #[test]
+    fn test_parse_create_user() {
+        let stmt = parse("CREATE USER IF NOT EXISTS alice IDENTIFIED BY 'secret'").unwrap();
+        assert_eq!(
+            Statement::CreateUser(CreateUser {
+                name: "alice".to_string(),
+                password: "secret".to_string(),
+                if_not_exists: true,
+            }),
+            stmt
+        );
+
+        let stmt = parse("create user 'bob' identified by 'pwd'").unwrap();
+        assert_matches!(
+            stmt,
+            Statement::CreateUser(CreateUser {
+                if_not_exists: false,
+                ..
+            })
+        );
+
+        assert!(parse("CREATE USER alice").is_err());
+        assert!(parse("CREATE USER alice IDENTIFIED BY pwd").is_err());
+    }
+
+    #[test]
+    fn test_parse_create_and_drop_role() {
+        let stmt = parse("CREATE ROLE analyst").unwrap();
+        assert_eq!(
+            Statement::CreateRole(CreateRole {
+                name: "analyst".to_string(),
+                if_not_exists: false,
+            }),
+            stmt
+        );
+
+        let stmt = parse("DROP ROLE IF EXISTS analyst").unwrap();
+        assert_eq!(
+            Statement::DropUser(DropUser {
+                name: "analyst".to_string(),
+                if_exists: true,
+                is_role: true,
+            }),
+            stmt
+        );
+
+        let stmt = parse("DROP USER alice").unwrap();
+        assert_eq!(
+            Statement::DropUser(DropUser {
+                name: "alice".to_string(),
+                if_exists: false,
+                is_role: false,
+            }),
+            stmt
+        );
+    }
+
+    #[test]
+    fn test_parse_grant() {
+        let stmt = parse("GRANT READ, WRITE ON mydb.* TO alice").unwrap();
+        assert_eq!(
+            Statement::Grant(Grant {
+                target: GrantTarget::Privileges {
+                    privileges: vec![Privilege::Read, Privilege::Write],
+                    object: GrantObject::Database(ObjectName(vec![Ident::new("mydb")])),
+                },
+                grantee: "alice".to_string(),
+            }),
+            stmt
+        );
+
+        let stmt = parse("GRANT ALL PRIVILEGES ON *.* TO admin").unwrap();
+        assert_eq!(
+            Statement::Grant(Grant {
+                target: GrantTarget::Privileges {
+                    privileges: vec![Privilege::All],
+                    object: GrantObject::All,
+                },
+                grantee: "admin".to_string(),
+            }),
+            stmt
+        );
+
+        let stmt = parse("GRANT select, ddl ON monitor TO analyst").unwrap();
+        assert_eq!(
+            Statement::Grant(Grant {
+                target: GrantTarget::Privileges {
+                    privileges: vec![Privilege::Read, Privilege::Ddl],
+                    object: GrantObject::Table(ObjectName(vec![Ident::new("monitor")])),
+                },
+                grantee: "analyst".to_string(),
+            }),
+            stmt
+        );
+
+        let stmt = parse("GRANT analyst TO alice").unwrap();
+        assert_eq!(
+            Statement::Grant(Grant {
+                target: GrantTarget::Role("analyst".to_string()),
+                grantee: "alice".to_string(),
+            }),
+            stmt
+        );
+
+        assert!(parse("GRANT EXECUTE ON *.* TO alice").is_err());
+        assert!(parse("GRANT READ ON * TO alice").is_err());
+        assert!(parse("GRANT READ ON *.* alice").is_err());
+    }
+
+    #[test]
+    fn test_parse_revoke() {
+        let stmt = parse("REVOKE WRITE ON public.monitor FROM alice").unwrap();
+        assert_eq!(
+            Statement::Revoke(Revoke {
+                target: GrantTarget::Privileges {
+                    privileges: vec![Privilege::Write],
+                    object: GrantObject::Table(ObjectName(vec![
+                        Ident::new("public"),
+                        Ident::new("monitor")
+                    ])),
+                },
+                grantee: "alice".to_string(),
+            }),
+            stmt
+        );
+
+        let stmt = parse("REVOKE analyst FROM alice").unwrap();
+        assert_eq!(
+            Statement::Revoke(Revoke {
+                target: GrantTarget::Role("analyst".to_string()),
+                grantee: "alice".to_string(),
+            }),
+            stmt
+        );
+    }
+}
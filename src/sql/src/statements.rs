@@ -20,10 +20,12 @@ pub mod describe;
 pub mod drop;
 pub mod explain;
 pub mod insert;
+pub mod kill;
 pub mod query;
 pub mod show;
 pub mod statement;
 pub mod tql;
+pub mod user;
 
 use std::str::FromStr;
 
@@ -19,6 +19,7 @@ use common_catalog::consts::IMMUTABLE_FILE_ENGINE;
 use itertools::Itertools;
 
 use crate::ast::{ColumnDef, Ident, ObjectName, SqlOption, TableConstraint, Value as SqlValue};
+use crate::statements::query::Query;
 
 const LINE_SEP: &str = ",\n";
 const COMMA_SEP: &str = ", ";
@@ -191,12 +192,38 @@ pub struct CreateDatabase {
     pub if_not_exists: bool,
 }
 
+/// CREATE [OR REPLACE] VIEW statement.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CreateView {
+    /// View name
+    pub name: ObjectName,
+    /// The query that defines the view.
+    pub query: Box<Query>,
+    pub or_replace: bool,
+}
+
+/// CREATE MATERIALIZED VIEW statement.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CreateMaterializedView {
+    /// Name of the materialized view, which is also the name of the table that
+    /// stores its results.
+    pub name: ObjectName,
+    /// The aggregation query that defines the materialized view.
+    pub query: Box<Query>,
+    /// Options in `WITH`.
+    /// All keys are lowercase.
+    pub options: HashMap<String, String>,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct CreateExternalTable {
     /// Table name
     pub name: ObjectName,
     pub columns: Vec<ColumnDef>,
     pub constraints: Vec<TableConstraint>,
+    /// Hive-style partition columns in `PARTITION COLUMNS`, whose values are
+    /// parsed from directories like `dt=2023-06-01/`.
+    pub partition_columns: Vec<Ident>,
     /// Table options in `WITH`.
     /// All keys are lowercase.
     pub options: HashMap<String, String>,
@@ -30,3 +30,53 @@ impl DropTable {
         &self.table_name
     }
 }
+
+/// DROP MATERIALIZED VIEW statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropMaterializedView {
+    view_name: ObjectName,
+    if_exists: bool,
+}
+
+impl DropMaterializedView {
+    /// Creates a statement for `DROP MATERIALIZED VIEW`
+    pub fn new(view_name: ObjectName, if_exists: bool) -> Self {
+        Self {
+            view_name,
+            if_exists,
+        }
+    }
+
+    pub fn view_name(&self) -> &ObjectName {
+        &self.view_name
+    }
+
+    pub fn if_exists(&self) -> bool {
+        self.if_exists
+    }
+}
+
+/// DROP VIEW statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropView {
+    view_name: ObjectName,
+    if_exists: bool,
+}
+
+impl DropView {
+    /// Creates a statement for `DROP VIEW`
+    pub fn new(view_name: ObjectName, if_exists: bool) -> Self {
+        Self {
+            view_name,
+            if_exists,
+        }
+    }
+
+    pub fn view_name(&self) -> &ObjectName {
+        &self.view_name
+    }
+
+    pub fn if_exists(&self) -> bool {
+        self.if_exists
+    }
+}
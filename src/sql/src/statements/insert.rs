@@ -95,6 +95,22 @@ impl Insert {
         }
     }
 
+    /// Returns the number of rows in the `VALUES` body, or `None` if the insert
+    /// statement is not an `INSERT ... VALUES`.
+    pub fn values_rows(&self) -> Option<usize> {
+        match &self.inner {
+            Statement::Insert {
+                source:
+                    box Query {
+                        body: box SetExpr::Values(Values { rows, .. }),
+                        ..
+                    },
+                ..
+            } => Some(rows.len()),
+            _ => None,
+        }
+    }
+
     pub fn query_body(&self) -> Result<Option<GtQuery>> {
         Ok(match &self.inner {
             Statement::Insert {
@@ -295,8 +311,21 @@ mod tests {
                         ..
                     }
                 ));
+                assert_eq!(None, insert.values_rows());
             }
             _ => unreachable!(),
         }
     }
+
+    #[test]
+    fn test_insert_values_rows() {
+        let sql = "INSERT INTO my_table VALUES(1, 'a'), (2, 'b'), (3, 'c')";
+        let stmt = ParserContext::create_with_dialect(sql, &GreptimeDbDialect {})
+            .unwrap()
+            .remove(0);
+        match stmt {
+            Statement::Insert(insert) => assert_eq!(Some(3), insert.values_rows()),
+            _ => unreachable!(),
+        }
+    }
 }
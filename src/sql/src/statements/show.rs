@@ -60,6 +60,13 @@ pub struct ShowCreateTable {
     pub table_name: ObjectName,
 }
 
+/// SQL structure for `SHOW [FULL] PROCESSLIST`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowProcesslist {
+    /// Whether to show the full statements instead of their first 100 characters.
+    pub full: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
@@ -137,4 +144,26 @@ mod tests {
         let sql = "SHOW CREATE TABLE";
         assert!(ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}).is_err());
     }
+
+    #[test]
+    pub fn test_show_processlist() {
+        let stmts =
+            ParserContext::create_with_dialect("SHOW PROCESSLIST", &GreptimeDbDialect {}).unwrap();
+        assert_eq!(
+            vec![Statement::ShowProcesslist(ShowProcesslist { full: false })],
+            stmts
+        );
+
+        let stmts =
+            ParserContext::create_with_dialect("show full processlist", &GreptimeDbDialect {})
+                .unwrap();
+        assert_eq!(
+            vec![Statement::ShowProcesslist(ShowProcesslist { full: true })],
+            stmts
+        );
+
+        assert!(
+            ParserContext::create_with_dialect("SHOW FULL TABLES", &GreptimeDbDialect {}).is_err()
+        );
+    }
 }
@@ -17,15 +17,19 @@ use sqlparser::ast::Statement as SpStatement;
 
 use crate::error::{ConvertToDfStatementSnafu, Error};
 use crate::statements::alter::AlterTable;
-use crate::statements::create::{CreateDatabase, CreateExternalTable, CreateTable};
+use crate::statements::create::{
+    CreateDatabase, CreateExternalTable, CreateMaterializedView, CreateTable, CreateView,
+};
 use crate::statements::delete::Delete;
 use crate::statements::describe::DescribeTable;
-use crate::statements::drop::DropTable;
+use crate::statements::drop::{DropMaterializedView, DropTable, DropView};
 use crate::statements::explain::Explain;
 use crate::statements::insert::Insert;
+use crate::statements::kill::Kill;
 use crate::statements::query::Query;
-use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowTables};
+use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowProcesslist, ShowTables};
 use crate::statements::tql::Tql;
+use crate::statements::user::{CreateRole, CreateUser, DropUser, Grant, Revoke};
 
 /// Tokens parsed by `DFParser` are converted into these values.
 #[allow(clippy::large_enum_variant)]
@@ -43,6 +47,14 @@ pub enum Statement {
     CreateExternalTable(CreateExternalTable),
     // DROP TABLE
     DropTable(DropTable),
+    // CREATE [OR REPLACE] VIEW
+    CreateView(CreateView),
+    // DROP VIEW
+    DropView(DropView),
+    // CREATE MATERIALIZED VIEW
+    CreateMaterializedView(CreateMaterializedView),
+    // DROP MATERIALIZED VIEW
+    DropMaterializedView(DropMaterializedView),
     // CREATE DATABASE
     CreateDatabase(CreateDatabase),
     /// ALTER TABLE
@@ -53,6 +65,10 @@ pub enum Statement {
     ShowTables(ShowTables),
     // SHOW CREATE TABLE
     ShowCreateTable(ShowCreateTable),
+    // SHOW [FULL] PROCESSLIST
+    ShowProcesslist(ShowProcesslist),
+    // KILL [QUERY]
+    Kill(Kill),
     // DESCRIBE TABLE
     DescribeTable(DescribeTable),
     // EXPLAIN QUERY
@@ -61,6 +77,16 @@ pub enum Statement {
     // COPY
     Copy(crate::statements::copy::Copy),
     Tql(Tql),
+    // CREATE USER
+    CreateUser(CreateUser),
+    // CREATE ROLE
+    CreateRole(CreateRole),
+    // DROP USER / DROP ROLE
+    DropUser(DropUser),
+    // GRANT
+    Grant(Grant),
+    // REVOKE
+    Revoke(Revoke),
 }
 
 /// Comment hints from SQL.
@@ -0,0 +1,111 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use crate::ast::ObjectName;
+
+/// Privileges that can be granted on tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Privilege {
+    /// Query the table, by SQL or PromQL.
+    Read,
+    /// Insert into or delete from the table, by SQL or any write protocol.
+    Write,
+    /// Create, alter or drop the table.
+    Ddl,
+    /// All of the above.
+    All,
+}
+
+impl fmt::Display for Privilege {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Privilege::Read => write!(f, "READ"),
+            Privilege::Write => write!(f, "WRITE"),
+            Privilege::Ddl => write!(f, "DDL"),
+            Privilege::All => write!(f, "ALL"),
+        }
+    }
+}
+
+/// The object a privilege is granted on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrantObject {
+    /// `*.*`, all tables in all databases.
+    All,
+    /// `db.*`, all tables in a database.
+    Database(ObjectName),
+    /// `[db.]table`, a single table.
+    Table(ObjectName),
+}
+
+impl fmt::Display for GrantObject {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GrantObject::All => write!(f, "*.*"),
+            GrantObject::Database(db) => write!(f, "{db}.*"),
+            GrantObject::Table(table) => write!(f, "{table}"),
+        }
+    }
+}
+
+/// SQL structure for `CREATE USER [IF NOT EXISTS] <name> IDENTIFIED BY '<password>'`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateUser {
+    pub name: String,
+    pub password: String,
+    pub if_not_exists: bool,
+}
+
+/// SQL structure for `CREATE ROLE [IF NOT EXISTS] <name>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateRole {
+    pub name: String,
+    pub if_not_exists: bool,
+}
+
+/// SQL structure for `DROP USER [IF EXISTS] <name>` and `DROP ROLE [IF EXISTS] <name>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropUser {
+    pub name: String,
+    pub if_exists: bool,
+    pub is_role: bool,
+}
+
+/// What is granted to or revoked from a user or role.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrantTarget {
+    /// `<privilege>[, ...] ON <object>`
+    Privileges {
+        privileges: Vec<Privilege>,
+        object: GrantObject,
+    },
+    /// `<role>`
+    Role(String),
+}
+
+/// SQL structure for `GRANT <target> TO <grantee>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grant {
+    pub target: GrantTarget,
+    pub grantee: String,
+}
+
+/// SQL structure for `REVOKE <target> FROM <grantee>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Revoke {
+    pub target: GrantTarget,
+    pub grantee: String,
+}
@@ -15,6 +15,12 @@
 use std::collections::HashMap;
 
 use sqlparser::ast::{SqlOption, Value};
+use sqlparser::dialect::Dialect;
+use sqlparser::keywords::Keyword;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+/// Replaces credential literals in SQL texts that may be exposed, e.g. in logs.
+const REDACTED_LITERAL: &str = "******";
 
 pub fn parse_option_string(value: Value) -> Option<String> {
     match value {
@@ -36,3 +42,116 @@ pub fn to_lowercase_options_map(opts: &[SqlOption]) -> HashMap<String, String> {
     }
     map
 }
+
+/// Redacts credential literals, i.e. passwords in `IDENTIFIED BY '<password>'`,
+/// in the SQL text.
+pub fn redact_sql(sql: &str, dialect: &dyn Dialect) -> String {
+    match Tokenizer::new(dialect, sql).tokenize() {
+        Ok(tokens) => tokens_to_string(&redact_tokens(tokens)),
+        Err(_) => redact_untokenized(sql),
+    }
+}
+
+/// Splits the SQL text into the texts of its statements, with credential
+/// literals redacted as [redact_sql]. Empty statements are skipped, so the
+/// texts are in the same order as the statements parsed from the SQL.
+pub fn split_and_redact_sql(sql: &str, dialect: &dyn Dialect) -> Vec<String> {
+    let tokens = match Tokenizer::new(dialect, sql).tokenize() {
+        Ok(tokens) => redact_tokens(tokens),
+        Err(_) => return vec![redact_untokenized(sql)],
+    };
+    tokens
+        .split(|token| *token == Token::SemiColon)
+        .filter(|tokens| {
+            tokens
+                .iter()
+                .any(|token| !matches!(token, Token::Whitespace(_) | Token::EOF))
+        })
+        .map(|tokens| tokens_to_string(tokens).trim().to_string())
+        .collect()
+}
+
+fn redact_tokens(mut tokens: Vec<Token>) -> Vec<Token> {
+    // The number of keywords of `IDENTIFIED BY` matched before the current token.
+    let mut matched = 0;
+    for token in tokens.iter_mut() {
+        match token {
+            Token::Whitespace(_) => continue,
+            Token::Word(word) if matched == 0 && word.value.eq_ignore_ascii_case("IDENTIFIED") => {
+                matched = 1;
+            }
+            Token::Word(word) if matched == 1 && word.keyword == Keyword::BY => matched = 2,
+            _ if matched == 2 => {
+                *token = Token::SingleQuotedString(REDACTED_LITERAL.to_string());
+                matched = 0;
+            }
+            _ => matched = 0,
+        }
+    }
+    tokens
+}
+
+fn tokens_to_string(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .filter(|token| **token != Token::EOF)
+        .map(|token| token.to_string())
+        .collect()
+}
+
+/// SQL that can't be tokenized can't be redacted precisely, so it's entirely
+/// redacted if it may contain credentials.
+fn redact_untokenized(sql: &str) -> String {
+    if sql.to_ascii_uppercase().contains("IDENTIFIED") {
+        REDACTED_LITERAL.to_string()
+    } else {
+        sql.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GreptimeDbDialect;
+
+    #[test]
+    fn test_redact_sql() {
+        let dialect = GreptimeDbDialect {};
+        assert_eq!(
+            "CREATE USER alice IDENTIFIED BY '******'",
+            redact_sql("CREATE USER alice IDENTIFIED BY 'secret'", &dialect)
+        );
+        assert_eq!(
+            "create user bob identified\n by '******'; SELECT 'identified by'",
+            redact_sql(
+                "create user bob identified\n by 'it''s;secret'; SELECT 'identified by'",
+                &dialect
+            )
+        );
+        assert_eq!(
+            "SELECT * FROM numbers",
+            redact_sql("SELECT * FROM numbers", &dialect)
+        );
+        assert_eq!(
+            "******",
+            redact_sql("CREATE USER alice IDENTIFIED BY 'secret", &dialect)
+        );
+    }
+
+    #[test]
+    fn test_split_and_redact_sql() {
+        let dialect = GreptimeDbDialect {};
+        assert_eq!(
+            vec![
+                "SELECT 1".to_string(),
+                "CREATE USER alice IDENTIFIED BY '******'".to_string(),
+                "SELECT ';'".to_string(),
+            ],
+            split_and_redact_sql(
+                ";SELECT 1; CREATE USER alice IDENTIFIED BY 'a;b';;\n SELECT ';';\n",
+                &dialect
+            )
+        );
+        assert!(split_and_redact_sql(" ; ", &dialect).is_empty());
+    }
+}
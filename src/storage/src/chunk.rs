@@ -18,6 +18,7 @@ use async_trait::async_trait;
 use common_query::logical_plan::Expr;
 use common_recordbatch::OrderOption;
 use common_telemetry::debug;
+use common_telemetry::tracing::{Instrument, Span};
 use common_time::range::TimestampRange;
 use snafu::ResultExt;
 use store_api::storage::{Chunk, ChunkReader, SchemaRef, SequenceNumber};
@@ -39,6 +40,8 @@ pub struct ChunkReaderImpl {
     schema: ProjectedSchemaRef,
     batch_reader: BoxedBatchReader,
     output_ordering: Option<Vec<OrderOption>>,
+    /// Span that reading chunks is traced in.
+    span: Span,
 }
 
 #[async_trait]
@@ -50,7 +53,12 @@ impl ChunkReader for ChunkReaderImpl {
     }
 
     async fn next_chunk(&mut self) -> Result<Option<Chunk>> {
-        let batch = match self.batch_reader.next_batch().await? {
+        let batch = match self
+            .batch_reader
+            .next_batch()
+            .instrument(self.span.clone())
+            .await?
+        {
             Some(b) => b,
             None => return Ok(None),
         };
@@ -79,9 +87,16 @@ impl ChunkReaderImpl {
             schema,
             batch_reader,
             output_ordering,
+            span: Span::none(),
         }
     }
 
+    /// Traces reading chunks in the `span`.
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+
     #[inline]
     pub fn projected_schema(&self) -> &ProjectedSchemaRef {
         &self.schema
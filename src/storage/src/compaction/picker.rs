@@ -91,6 +91,24 @@ impl<S> SimplePicker<S> {
         }
         Ok(expired_ssts)
     }
+
+    fn get_cold_ssts(
+        &self,
+        levels: &LevelMetasRef,
+        cold_after: Option<Duration>,
+    ) -> crate::error::Result<Vec<FileHandle>> {
+        let Some(cold_after) = cold_after else { return Ok(vec![]); };
+
+        let cold_time = Timestamp::current_millis()
+            .sub_duration(cold_after)
+            .context(TtlCalculationSnafu)?;
+
+        let mut cold_ssts = vec![];
+        for level in 0..levels.level_num() {
+            cold_ssts.extend(levels.level(level as Level).get_cold_files(&cold_time));
+        }
+        Ok(cold_ssts)
+    }
 }
 
 impl<S: LogStore> Picker for SimplePicker<S> {
@@ -119,6 +137,20 @@ impl<S: LogStore> Picker for SimplePicker<S> {
             expired_ssts.iter().for_each(|f| f.mark_compacting(true));
         }
 
+        // Expired SSTs are marked as compacting, so they are never moved.
+        let cold_ssts = self
+            .get_cold_ssts(levels, req.cold_after)
+            .map_err(|e| {
+                error!(e;"Failed to get region cold SST files, region: {}, cold_after: {:?}", req.region_id, req.cold_after);
+                e
+            })
+            .unwrap_or_default();
+
+        if !cold_ssts.is_empty() {
+            info!("Cold SSTs in region {}: {:?}", req.region_id, cold_ssts);
+            cold_ssts.iter().for_each(|f| f.mark_compacting(true));
+        }
+
         let ctx = &PickerContext::with(req.compaction_time_window);
 
         for level_num in 0..levels.level_num() {
@@ -143,11 +175,28 @@ impl<S: LogStore> Picker for SimplePicker<S> {
                 wal: req.wal.clone(),
                 manifest: req.manifest.clone(),
                 expired_ssts,
+                cold_ssts,
                 sst_write_buffer_size: req.sst_write_buffer_size,
                 compaction_time_window,
             }));
         }
 
-        Ok(None)
+        if cold_ssts.is_empty() {
+            return Ok(None);
+        }
+        // Still builds a task to move the cold SSTs even if there is nothing to compact.
+        Ok(Some(CompactionTaskImpl {
+            schema: req.schema(),
+            sst_layer: req.sst_layer.clone(),
+            outputs: vec![],
+            writer: req.writer.clone(),
+            shared_data: req.shared.clone(),
+            wal: req.wal.clone(),
+            manifest: req.manifest.clone(),
+            expired_ssts,
+            cold_ssts,
+            sst_write_buffer_size: req.sst_write_buffer_size,
+            compaction_time_window: None,
+        }))
     }
 }
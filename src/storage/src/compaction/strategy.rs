@@ -180,7 +180,7 @@ mod tests {
 
     use super::*;
     use crate::file_purger::noop::new_noop_file_purger;
-    use crate::sst::{FileId, FileMeta};
+    use crate::sst::{FileId, FileMeta, FileTier};
 
     #[test]
     fn test_time_bucket_span() {
@@ -251,6 +251,7 @@ mod tests {
                 )),
                 level: 0,
                 file_size: 0,
+                tier: FileTier::Hot,
             },
             layer,
             file_purger,
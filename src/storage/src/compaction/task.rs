@@ -27,7 +27,7 @@ use crate::manifest::region::RegionManifest;
 use crate::region::{RegionWriterRef, SharedDataRef};
 use crate::schema::RegionSchemaRef;
 use crate::sst::{
-    AccessLayerRef, FileHandle, FileId, FileMeta, Level, Source, SstInfo, WriteOptions,
+    AccessLayerRef, FileHandle, FileId, FileMeta, FileTier, Level, Source, SstInfo, WriteOptions,
 };
 use crate::wal::Wal;
 
@@ -47,6 +47,8 @@ pub struct CompactionTaskImpl<S: LogStore> {
     pub wal: Wal<S>,
     pub manifest: RegionManifest,
     pub expired_ssts: Vec<FileHandle>,
+    /// Hot SSTs to move to the cold object store.
+    pub cold_ssts: Vec<FileHandle>,
     pub sst_write_buffer_size: ReadableSize,
     pub compaction_time_window: Option<i64>,
 }
@@ -101,6 +103,36 @@ impl<S: LogStore> CompactionTaskImpl<S> {
         Ok((outputs, inputs))
     }
 
+    /// Copies cold SSTs to the cold object store, returns `(cold file, moved hot file)`.
+    ///
+    /// Files failed to copy stay in the hot object store and will be picked again by
+    /// the next compaction.
+    async fn move_ssts_to_cold(&self) -> (HashSet<FileMeta>, HashSet<FileMeta>) {
+        let mut outputs = HashSet::with_capacity(self.cold_ssts.len());
+        let mut inputs = HashSet::with_capacity(self.cold_ssts.len());
+        for file in &self.cold_ssts {
+            let cold_file_id = FileId::random();
+            if let Err(e) = self
+                .sst_layer
+                .copy_sst_to_cold(file.file_id(), cold_file_id)
+                .await
+            {
+                error!(e; "Failed to move SST file {} to cold store, region: {}",
+                    file.file_id(), self.shared_data.name());
+                continue;
+            }
+
+            let meta = file.meta();
+            let _ = outputs.insert(FileMeta {
+                file_id: cold_file_id,
+                tier: FileTier::Cold,
+                ..meta.clone()
+            });
+            let _ = inputs.insert(meta);
+        }
+        (outputs, inputs)
+    }
+
     /// Writes updated SST info into manifest.
     async fn write_manifest_and_apply(
         &self,
@@ -134,6 +166,9 @@ impl<S: LogStore> CompactionTaskImpl<S> {
                 input.mark_compacting(compacting);
             }
         }
+        for file in &self.cold_ssts {
+            file.mark_compacting(compacting);
+        }
     }
 }
 
@@ -143,12 +178,23 @@ impl<S: LogStore> CompactionTask for CompactionTaskImpl<S> {
         let _timer = timer!(crate::metrics::COMPACT_ELAPSED);
         self.mark_files_compacting(true);
 
-        let (output, mut compacted) = self.merge_ssts().await.map_err(|e| {
+        let (mut output, mut compacted) = self.merge_ssts().await.map_err(|e| {
             error!(e; "Failed to compact region: {}", self.shared_data.name());
             e
         })?;
         compacted.extend(self.expired_ssts.iter().map(FileHandle::meta));
 
+        let (cold_output, moved) = self.move_ssts_to_cold().await;
+        if !moved.is_empty() {
+            info!(
+                "Moving SST files to cold store, region: {}, files: {:?}",
+                self.shared_data.name(),
+                moved.iter().map(|f| f.file_id).collect::<Vec<_>>()
+            );
+        }
+        output.extend(cold_output);
+        compacted.extend(moved);
+
         let input_ids = compacted.iter().map(|f| f.file_id).collect::<Vec<_>>();
         let output_ids = output.iter().map(|f| f.file_id).collect::<Vec<_>>();
         info!(
@@ -214,6 +260,7 @@ impl CompactionOutput {
                     time_range,
                     level: self.output_level,
                     file_size,
+                    tier: FileTier::Hot,
                 },
             ))
     }
@@ -136,7 +136,9 @@ mod tests {
     };
     use crate::metadata::RegionMetadata;
     use crate::sst::parquet::ParquetWriter;
-    use crate::sst::{self, FileId, FileMeta, FsAccessLayer, Source, SstInfo, WriteOptions};
+    use crate::sst::{
+        self, FileId, FileMeta, FileTier, FsAccessLayer, Source, SstInfo, WriteOptions,
+    };
     use crate::test_util::descriptor_util::RegionDescBuilder;
 
     fn schema_for_test() -> RegionSchemaRef {
@@ -269,6 +271,7 @@ mod tests {
                 time_range,
                 level: 0,
                 file_size,
+                tier: FileTier::Hot,
             },
             Arc::new(crate::test_util::access_layer_util::MockAccessLayer {}),
             new_noop_file_purger(),
@@ -509,6 +512,7 @@ mod tests {
                         level: 1,
                         time_range: None,
                         file_size: 0,
+                        tier: FileTier::Hot,
                     },
                     Arc::new(crate::test_util::access_layer_util::MockAccessLayer {}),
                     new_noop_file_purger(),
@@ -26,6 +26,12 @@ pub const DEFAULT_REGION_WRITE_BUFFER_SIZE: ReadableSize = ReadableSize::mb(32);
 pub const DEFAULT_AUTO_FLUSH_INTERVAL: u32 = 60 * 60 * 1000;
 /// Default interval to schedule the picker to flush automatically in millis.
 pub const DEFAULT_PICKER_SCHEDULE_INTERVAL: u32 = 5 * 60 * 1000;
+/// Default interval to refresh manifests of read-only regions in millis.
+pub const DEFAULT_MANIFEST_REFRESH_INTERVAL: u32 = 10 * 1000;
+/// Default interval to check regions for SSTs to move to the cold object store in millis.
+pub const DEFAULT_COLD_CHECK_INTERVAL: u32 = 60 * 60 * 1000;
+/// Default delay to delete SST files that are no longer used in millis.
+pub const DEFAULT_SST_PURGE_DELAY: u32 = 60 * 1000;
 
 #[derive(Debug, Clone)]
 pub struct EngineConfig {
@@ -35,6 +41,9 @@ pub struct EngineConfig {
     pub manifest_gc_duration: Option<Duration>,
     pub max_files_in_l0: usize,
     pub max_purge_tasks: usize,
+    /// Delay to delete SST files that are no longer used, which must cover the manifest
+    /// refresh interval plus the longest query on follower regions.
+    pub sst_purge_delay: Duration,
     pub sst_write_buffer_size: ReadableSize,
     /// Max inflight flush tasks.
     pub max_flush_tasks: usize,
@@ -50,6 +59,11 @@ pub struct EngineConfig {
     ///
     /// The precedence order is: region ttl > global ttl.
     pub global_ttl: Option<Duration>,
+    /// Interval to refresh manifests of read-only regions.
+    pub manifest_refresh_interval: Duration,
+    /// Interval to check regions for SSTs to move to the cold object store, so SSTs of
+    /// regions without new writes are also moved.
+    pub cold_check_interval: Duration,
 }
 
 impl Default for EngineConfig {
@@ -61,6 +75,7 @@ impl Default for EngineConfig {
             manifest_gc_duration: Some(Duration::from_secs(30)),
             max_files_in_l0: 8,
             max_purge_tasks: 32,
+            sst_purge_delay: Duration::from_millis(DEFAULT_SST_PURGE_DELAY.into()),
             sst_write_buffer_size: ReadableSize::mb(8),
             max_flush_tasks: DEFAULT_MAX_FLUSH_TASKS,
             region_write_buffer_size: DEFAULT_REGION_WRITE_BUFFER_SIZE,
@@ -70,6 +85,10 @@ impl Default for EngineConfig {
             auto_flush_interval: Duration::from_millis(DEFAULT_AUTO_FLUSH_INTERVAL.into()),
             global_write_buffer_size: None,
             global_ttl: None,
+            manifest_refresh_interval: Duration::from_millis(
+                DEFAULT_MANIFEST_REFRESH_INTERVAL.into(),
+            ),
+            cold_check_interval: Duration::from_millis(DEFAULT_COLD_CHECK_INTERVAL.into()),
         }
     }
 }
@@ -17,6 +17,7 @@ use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
 use async_trait::async_trait;
+use common_runtime::{RepeatedTask, TaskFunction};
 use common_telemetry::logging::{self, debug};
 use object_store::{util, ObjectStore};
 use snafu::ResultExt;
@@ -30,6 +31,7 @@ use store_api::storage::{
 use crate::compaction::CompactionSchedulerRef;
 use crate::config::EngineConfig;
 use crate::error::{self, Error, Result};
+use crate::file_purger::noop::new_noop_file_purger;
 use crate::file_purger::{FilePurgeHandler, FilePurgerRef};
 use crate::flush::{
     FlushScheduler, FlushSchedulerRef, FlushStrategyRef, PickerConfig, SizeBasedStrategy,
@@ -38,7 +40,7 @@ use crate::manifest::region::RegionManifest;
 use crate::manifest::storage::manifest_compress_type;
 use crate::memtable::{DefaultMemtableBuilder, MemtableBuilderRef};
 use crate::metadata::RegionMetadata;
-use crate::region::{RegionImpl, StoreConfig};
+use crate::region::{CompactContext, RegionImpl, StoreConfig};
 use crate::scheduler::{LocalScheduler, Scheduler, SchedulerConfig};
 use crate::sst::FsAccessLayer;
 
@@ -114,12 +116,25 @@ impl<S: LogStore> EngineImpl<S> {
         log_store: Arc<S>,
         object_store: ObjectStore,
         compaction_scheduler: CompactionSchedulerRef<S>,
+    ) -> Result<Self> {
+        Self::with_cold_object_store(config, log_store, object_store, None, compaction_scheduler)
+    }
+
+    /// Creates the engine with an object store that SSTs older than the `cold_after`
+    /// of their regions are moved to.
+    pub fn with_cold_object_store(
+        config: EngineConfig,
+        log_store: Arc<S>,
+        object_store: ObjectStore,
+        cold_object_store: Option<ObjectStore>,
+        compaction_scheduler: CompactionSchedulerRef<S>,
     ) -> Result<Self> {
         Ok(Self {
             inner: Arc::new(EngineInner::new(
                 config,
                 log_store,
                 object_store,
+                cold_object_store,
                 compaction_scheduler,
             )?),
         })
@@ -307,6 +322,8 @@ impl<S: LogStore> Default for RegionMap<S> {
 
 struct EngineInner<S: LogStore> {
     object_store: ObjectStore,
+    /// Object store to move cold SSTs to.
+    cold_object_store: Option<ObjectStore>,
     log_store: Arc<S>,
     regions: Arc<RegionMap<S>>,
     memtable_builder: MemtableBuilderRef,
@@ -314,6 +331,12 @@ struct EngineInner<S: LogStore> {
     flush_strategy: FlushStrategyRef,
     compaction_scheduler: CompactionSchedulerRef<S>,
     file_purger: FilePurgerRef,
+    /// Purger of read-only regions, which must not delete files owned by their leaders.
+    noop_file_purger: FilePurgerRef,
+    /// Task to refresh manifests of read-only regions.
+    refresh_manifest_task: RepeatedTask<Error>,
+    /// Task to move cold SSTs of regions, only started with a cold object store.
+    cold_check_task: Option<RepeatedTask<Error>>,
     config: Arc<EngineConfig>,
 }
 
@@ -322,6 +345,7 @@ impl<S: LogStore> EngineInner<S> {
         config: EngineConfig,
         log_store: Arc<S>,
         object_store: ObjectStore,
+        cold_object_store: Option<ObjectStore>,
         compaction_scheduler: CompactionSchedulerRef<S>,
     ) -> Result<Self> {
         let regions = Arc::new(RegionMap::new());
@@ -337,12 +361,42 @@ impl<S: LogStore> EngineInner<S> {
             },
         )?);
 
+        if config.sst_purge_delay < config.manifest_refresh_interval {
+            logging::warn!(
+                "SST purge delay {:?} is shorter than the manifest refresh interval {:?}, \
+                 follower regions may read deleted SST files",
+                config.sst_purge_delay,
+                config.manifest_refresh_interval
+            );
+        }
         let file_purger = Arc::new(LocalScheduler::new(
             SchedulerConfig {
                 max_inflight_tasks: config.max_purge_tasks,
             },
-            FilePurgeHandler,
+            FilePurgeHandler::new(config.sst_purge_delay),
         ));
+        let refresh_manifest_task = RepeatedTask::new(
+            config.manifest_refresh_interval,
+            Box::new(RefreshManifestFunction {
+                regions: regions.clone(),
+            }),
+        );
+        refresh_manifest_task
+            .start(common_runtime::bg_runtime())
+            .context(error::StartRefreshTaskSnafu)?;
+        let cold_check_task = if cold_object_store.is_some() {
+            let task = RepeatedTask::new(
+                config.cold_check_interval,
+                Box::new(ColdCheckFunction {
+                    regions: regions.clone(),
+                }),
+            );
+            task.start(common_runtime::bg_runtime())
+                .context(error::StartColdCheckTaskSnafu)?;
+            Some(task)
+        } else {
+            None
+        };
         let flush_strategy = Arc::new(SizeBasedStrategy::new(
             config
                 .global_write_buffer_size
@@ -357,6 +411,7 @@ impl<S: LogStore> EngineInner<S> {
         };
         Ok(Self {
             object_store,
+            cold_object_store,
             log_store,
             regions,
             memtable_builder: Arc::new(memtable_builder),
@@ -364,6 +419,9 @@ impl<S: LogStore> EngineInner<S> {
             flush_strategy,
             compaction_scheduler,
             file_purger,
+            noop_file_purger: new_noop_file_purger(),
+            refresh_manifest_task,
+            cold_check_task,
             config: Arc::new(config),
         })
     }
@@ -393,8 +451,9 @@ impl<S: LogStore> EngineInner<S> {
                 &opts.parent_dir,
                 opts.write_buffer_size,
                 name,
-                &self.config,
                 opts.ttl,
+                opts.cold_after,
+                opts.read_only,
             )
             .await?;
 
@@ -438,8 +497,9 @@ impl<S: LogStore> EngineInner<S> {
                 &opts.parent_dir,
                 opts.write_buffer_size,
                 &region_name,
-                &self.config,
                 opts.ttl,
+                opts.cold_after,
+                false,
             )
             .await?;
 
@@ -469,26 +529,52 @@ impl<S: LogStore> EngineInner<S> {
         parent_dir: &str,
         write_buffer_size: Option<usize>,
         region_name: &str,
-        config: &EngineConfig,
         region_ttl: Option<Duration>,
+        region_cold_after: Option<Duration>,
+        read_only: bool,
     ) -> Result<StoreConfig<S>> {
+        let config = &self.config;
         let parent_dir = util::normalize_dir(parent_dir);
 
         let sst_dir = &region_sst_dir(&parent_dir, region_name);
-        let sst_layer = Arc::new(FsAccessLayer::new(sst_dir, self.object_store.clone()));
-        let manifest_dir = region_manifest_dir(&parent_dir, region_name);
-        let manifest = RegionManifest::with_checkpointer(
-            &manifest_dir,
-            self.object_store.clone(),
-            manifest_compress_type(config.compress_manifest),
-            config.manifest_checkpoint_margin,
-            config.manifest_gc_duration,
+        let sst_layer = Arc::new(
+            FsAccessLayer::new(sst_dir, self.object_store.clone())
+                .with_cold_object_store(self.cold_object_store.clone()),
         );
-        manifest.start().await?;
+        let manifest_dir = region_manifest_dir(&parent_dir, region_name);
+        let compress_type = manifest_compress_type(config.compress_manifest);
+        // Checkpoints and GC of the manifest are left to the leader of a read-only region.
+        let (manifest, file_purger) = if read_only {
+            let manifest =
+                RegionManifest::create(&manifest_dir, self.object_store.clone(), compress_type);
+            (manifest, self.noop_file_purger.clone())
+        } else {
+            let manifest = RegionManifest::with_checkpointer(
+                &manifest_dir,
+                self.object_store.clone(),
+                compress_type,
+                config.manifest_checkpoint_margin,
+                config.manifest_gc_duration,
+            );
+            manifest.start().await?;
+            (manifest, self.file_purger.clone())
+        };
         let flush_strategy = self.flush_strategy.clone();
 
         // If region_ttl is `None`, the global ttl takes effect.
         let ttl = region_ttl.or(self.config.global_ttl);
+        // SSTs can't be moved without a cold object store.
+        let cold_after = if self.cold_object_store.is_some() {
+            region_cold_after
+        } else {
+            if region_cold_after.is_some() {
+                logging::warn!(
+                    "Cold object store is not configured, ignore cold_after of region {}",
+                    region_name
+                );
+            }
+            None
+        };
 
         Ok(StoreConfig {
             log_store: self.log_store.clone(),
@@ -499,14 +585,23 @@ impl<S: LogStore> EngineInner<S> {
             flush_strategy,
             compaction_scheduler: self.compaction_scheduler.clone(),
             engine_config: self.config.clone(),
-            file_purger: self.file_purger.clone(),
+            file_purger,
             ttl,
+            cold_after,
             write_buffer_size: write_buffer_size
                 .unwrap_or(self.config.region_write_buffer_size.as_bytes() as usize),
         })
     }
 
     async fn close(&self) -> Result<()> {
+        self.refresh_manifest_task
+            .stop()
+            .await
+            .context(error::StopRefreshTaskSnafu)?;
+        if let Some(task) = &self.cold_check_task {
+            task.stop().await.context(error::StopColdCheckTaskSnafu)?;
+        }
+
         let regions = self.regions.list_regions();
         let ctx = CloseContext::default();
         for region in regions {
@@ -520,10 +615,67 @@ impl<S: LogStore> EngineInner<S> {
 
         self.compaction_scheduler.stop(true).await?;
         self.flush_scheduler.stop().await?;
+        self.noop_file_purger.stop(true).await?;
         self.file_purger.stop(true).await
     }
 }
 
+/// Task function to refresh manifests of read-only regions.
+struct RefreshManifestFunction<S: LogStore> {
+    /// Regions of the engine.
+    regions: Arc<RegionMap<S>>,
+}
+
+#[async_trait]
+impl<S: LogStore> TaskFunction<Error> for RefreshManifestFunction<S> {
+    async fn call(&mut self) -> Result<()> {
+        let regions = self.regions.list_regions();
+        for region in regions.iter().filter(|region| region.is_read_only()) {
+            if let Err(e) = region.refresh_manifest().await {
+                logging::error!(e; "Failed to refresh manifest of region {}", region.id());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "RefreshManifest-task"
+    }
+}
+
+/// Task function to compact regions with cold SSTs, as regions without new writes
+/// are never compacted after flushes.
+struct ColdCheckFunction<S: LogStore> {
+    /// Regions of the engine.
+    regions: Arc<RegionMap<S>>,
+}
+
+#[async_trait]
+impl<S: LogStore> TaskFunction<Error> for ColdCheckFunction<S> {
+    async fn call(&mut self) -> Result<()> {
+        let regions = self.regions.list_regions();
+        for region in regions
+            .iter()
+            .filter(|region| !region.is_read_only() && region.has_cold_ssts())
+        {
+            let ctx = CompactContext {
+                wait: false,
+                ..Default::default()
+            };
+            if let Err(e) = region.compact(ctx).await {
+                logging::error!(e; "Failed to move cold SSTs of region {}", region.id());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "ColdCheck-task"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::ffi::OsStr;
@@ -77,6 +77,13 @@ pub enum Error {
         source: object_store::Error,
     },
 
+    #[snafu(display("Fail to copy object into path: {}, source: {}", path, source))]
+    CopyObject {
+        path: String,
+        location: Location,
+        source: std::io::Error,
+    },
+
     #[snafu(display("Fail to delete object from path: {}, source: {}", path, source))]
     DeleteObject {
         path: String,
@@ -528,6 +535,39 @@ pub enum Error {
         source: table::error::Error,
         location: Location,
     },
+
+    #[snafu(display("Region {} is read-only", region_id))]
+    ReadOnlyRegion {
+        region_id: RegionId,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to start manifest refresh task: {}", source))]
+    StartRefreshTask {
+        location: Location,
+        source: RuntimeError,
+    },
+
+    #[snafu(display("Failed to stop manifest refresh task: {}", source))]
+    StopRefreshTask {
+        location: Location,
+        source: RuntimeError,
+    },
+
+    #[snafu(display("Failed to start cold SST check task: {}", source))]
+    StartColdCheckTask {
+        location: Location,
+        source: RuntimeError,
+    },
+
+    #[snafu(display("Failed to stop cold SST check task: {}", source))]
+    StopColdCheckTask {
+        location: Location,
+        source: RuntimeError,
+    },
+
+    #[snafu(display("Cold object store is not configured"))]
+    ColdStoreNotFound { location: Location },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -590,6 +630,7 @@ impl ErrorExt for Error {
             WriteParquet { .. }
             | ReadObject { .. }
             | WriteObject { .. }
+            | CopyObject { .. }
             | ListObjects { .. }
             | DeleteObject { .. }
             | WriteWal { .. }
@@ -623,11 +664,17 @@ impl ErrorExt for Error {
             | IllegalSchedulerState { .. }
             | DuplicateFlush { .. }
             | StartPickTask { .. }
-            | StopPickTask { .. } => StatusCode::Unexpected,
+            | StopPickTask { .. }
+            | StartRefreshTask { .. }
+            | StopRefreshTask { .. }
+            | StartColdCheckTask { .. }
+            | StopColdCheckTask { .. } => StatusCode::Unexpected,
 
             TtlCalculation { source, .. } => source.status_code(),
             ConvertColumnsToRows { .. } | SortArrays { .. } => StatusCode::Unexpected,
             BuildPredicate { source, .. } => source.status_code(),
+            ReadOnlyRegion { .. } => StatusCode::Unsupported,
+            ColdStoreNotFound { .. } => StatusCode::StorageUnavailable,
         }
     }
 
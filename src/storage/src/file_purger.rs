@@ -12,20 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
 
-use common_telemetry::{debug, error};
+use common_telemetry::{debug, error, info};
 use store_api::storage::RegionId;
-use tokio::sync::Notify;
+use tokio::sync::{Notify, Semaphore};
 
 use crate::error::Result;
 use crate::scheduler::rate_limit::{BoxedRateLimitToken, RateLimitToken};
-use crate::scheduler::{Handler, LocalScheduler, Request};
-use crate::sst::{AccessLayerRef, FileId};
+use crate::scheduler::{Handler, LocalScheduler, Request, Scheduler};
+use crate::sst::{AccessLayerRef, FileId, FileTier, LevelMetas};
+
+/// Maximum number of deletions waiting for the purge delay at the same time.
+const MAX_DELAYED_PURGES: usize = 1024;
 
 pub struct FilePurgeRequest {
     pub region_id: RegionId,
     pub file_id: FileId,
+    pub tier: FileTier,
     pub sst_layer: AccessLayerRef,
 }
 
@@ -39,7 +45,31 @@ impl Request for FilePurgeRequest {
     fn complete(self, _result: Result<()>) {}
 }
 
-pub struct FilePurgeHandler;
+/// Handler to delete SST files.
+///
+/// Deletions are deferred by the purge delay, as follower regions may still read the
+/// files until they refresh their manifests and finish the queries on the old version.
+#[derive(Debug)]
+pub struct FilePurgeHandler {
+    purge_delay: Duration,
+    /// Bounds the deletions waiting for the purge delay.
+    delayed_purges: Arc<Semaphore>,
+}
+
+impl Default for FilePurgeHandler {
+    fn default() -> Self {
+        Self::new(Duration::ZERO)
+    }
+}
+
+impl FilePurgeHandler {
+    pub fn new(purge_delay: Duration) -> Self {
+        Self {
+            purge_delay,
+            delayed_purges: Arc::new(Semaphore::new(MAX_DELAYED_PURGES)),
+        }
+    }
+}
 
 #[async_trait::async_trait]
 impl Handler for FilePurgeHandler {
@@ -51,25 +81,89 @@ impl Handler for FilePurgeHandler {
         token: BoxedRateLimitToken,
         finish_notifier: Arc<Notify>,
     ) -> Result<()> {
-        req.sst_layer.delete_sst(req.file_id).await.map_err(|e| {
-            error!(e; "Failed to delete SST file, file: {}, region: {}", 
-                req.file_id.as_parquet(), req.region_id);
-            e
-        })?;
-        debug!(
-            "Successfully deleted SST file: {}, region: {}",
-            req.file_id.as_parquet(),
-            req.region_id
-        );
+        if self.purge_delay.is_zero() {
+            delete_sst(req).await?;
+        } else {
+            // Waits in background so delayed files don't occupy the inflight tasks, but
+            // only up to `MAX_DELAYED_PURGES` files, further deletions wait for a slot here.
+            let permit = self.delayed_purges.clone().acquire_owned().await.ok();
+            let purge_delay = self.purge_delay;
+            let _handle = common_runtime::spawn_bg(async move {
+                let _permit = permit;
+                tokio::time::sleep(purge_delay).await;
+                let _ = delete_sst(req).await;
+            });
+        }
         token.try_release();
         finish_notifier.notify_one();
         Ok(())
     }
 }
 
+async fn delete_sst(req: FilePurgeRequest) -> Result<()> {
+    req.sst_layer
+        .delete_sst(req.file_id, req.tier)
+        .await
+        .map_err(|e| {
+            error!(e; "Failed to delete SST file, file: {}, tier: {}, region: {}",
+                req.file_id.as_parquet(), req.tier, req.region_id);
+            e
+        })?;
+    debug!(
+        "Successfully deleted SST file: {}, region: {}",
+        req.file_id.as_parquet(),
+        req.region_id
+    );
+    Ok(())
+}
+
 pub type FilePurgerRef = Arc<LocalScheduler<FilePurgeRequest>>;
 
-#[cfg(test)]
+/// Purges the SSTs in the directory of a region that its manifest doesn't reference.
+///
+/// Purge requests only live in memory, so files removed from the manifest are left
+/// behind if the datanode stops before deleting them. A writable region sweeps them
+/// on open, before it writes new SSTs that are not in the manifest yet.
+pub(crate) async fn purge_unreferenced_files(
+    region_id: RegionId,
+    ssts: &LevelMetas,
+    sst_layer: &AccessLayerRef,
+    file_purger: &FilePurgerRef,
+) -> Result<()> {
+    let referenced = ssts
+        .levels()
+        .iter()
+        .flat_map(|level| level.files())
+        .map(|file| (file.file_id(), file.tier()))
+        .collect::<HashSet<_>>();
+    for tier in [FileTier::Hot, FileTier::Cold] {
+        for file_id in sst_layer.list_ssts(tier).await? {
+            if referenced.contains(&(file_id, tier)) {
+                continue;
+            }
+            info!(
+                "Purge unreferenced SST file, region: {}, file: {}, tier: {}",
+                region_id,
+                file_id.as_parquet(),
+                tier
+            );
+            let request = FilePurgeRequest {
+                region_id,
+                file_id,
+                tier,
+                sst_layer: sst_layer.clone(),
+            };
+            if let Err(e) = file_purger.schedule(request) {
+                error!(e; "Failed to schedule SST purge task, region: {}, name: {}",
+                    region_id, file_id.as_parquet());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Purger that never deletes files, used by read-only regions as SSTs are
+/// owned by the leader of the region.
 pub mod noop {
     use std::sync::Arc;
 
@@ -160,6 +254,7 @@ mod tests {
                     time_range: None,
                     level: 0,
                     file_size: sst_info.file_size,
+                    tier: FileTier::Hot,
                 },
                 layer.clone(),
                 file_purger,
@@ -187,10 +282,11 @@ mod tests {
         let request = FilePurgeRequest {
             region_id: 0,
             file_id: sst_file_id,
+            tier: FileTier::Hot,
             sst_layer: layer,
         };
 
-        let handler = FilePurgeHandler;
+        let handler = FilePurgeHandler::default();
         let notify = Arc::new(Notify::new());
         handler
             .handle_request(request, Box::new(MockRateLimitToken {}), notify.clone())
@@ -215,7 +311,7 @@ mod tests {
         let sst_file_id = FileId::random();
         let scheduler = Arc::new(LocalScheduler::new(
             SchedulerConfig::default(),
-            FilePurgeHandler,
+            FilePurgeHandler::default(),
         ));
         let (handle, path, _layer) =
             create_sst_file(object_store.clone(), sst_file_id, scheduler.clone()).await;
@@ -232,4 +328,87 @@ mod tests {
             .await
             .unwrap());
     }
+
+    #[tokio::test]
+    async fn test_file_purge_delay() {
+        let dir = create_temp_dir("file-purge");
+        let mut builder = Fs::default();
+        let _ = builder.root(dir.path().to_str().unwrap());
+        let object_store = ObjectStore::new(builder).unwrap().finish();
+
+        let sst_file_id = FileId::random();
+
+        let noop_file_purger = Arc::new(LocalScheduler::new(
+            SchedulerConfig::default(),
+            NoopFilePurgeHandler,
+        ));
+        let (_file, path, layer) =
+            create_sst_file(object_store.clone(), sst_file_id, noop_file_purger).await;
+        let request = FilePurgeRequest {
+            region_id: 0,
+            file_id: sst_file_id,
+            tier: FileTier::Hot,
+            sst_layer: layer,
+        };
+
+        let handler = FilePurgeHandler::new(Duration::from_millis(200));
+        let notify = Arc::new(Notify::new());
+        handler
+            .handle_request(request, Box::new(MockRateLimitToken {}), notify.clone())
+            .await
+            .unwrap();
+
+        // The request finishes at once but the file is kept during the delay.
+        notify.notified().await;
+        let file_path = format!("{}/{}", path, sst_file_id.as_parquet());
+        assert!(object_store.is_exist(&file_path).await.unwrap());
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(!object_store.is_exist(&file_path).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_purge_unreferenced_files() {
+        let dir = create_temp_dir("file-purge");
+        let mut builder = Fs::default();
+        let _ = builder.root(dir.path().to_str().unwrap());
+        let object_store = ObjectStore::new(builder).unwrap().finish();
+
+        let noop_file_purger = Arc::new(LocalScheduler::new(
+            SchedulerConfig::default(),
+            NoopFilePurgeHandler,
+        ));
+        let referenced_id = FileId::random();
+        let unreferenced_id = FileId::random();
+        let (referenced, path, layer) = create_sst_file(
+            object_store.clone(),
+            referenced_id,
+            noop_file_purger.clone(),
+        )
+        .await;
+        let _ = create_sst_file(object_store.clone(), unreferenced_id, noop_file_purger).await;
+
+        let scheduler = Arc::new(LocalScheduler::new(
+            SchedulerConfig::default(),
+            FilePurgeHandler::default(),
+        ));
+        let ssts = LevelMetas::new(layer.clone(), scheduler.clone()).merge(
+            std::iter::once(referenced.meta()),
+            std::iter::empty(),
+            None,
+        );
+        purge_unreferenced_files(0, &ssts, &layer, &scheduler)
+            .await
+            .unwrap();
+        scheduler.stop(true).await.unwrap();
+
+        assert!(object_store
+            .is_exist(&format!("{}/{}", path, referenced_id.as_parquet()))
+            .await
+            .unwrap());
+        assert!(!object_store
+            .is_exist(&format!("{}/{}", path, unreferenced_id.as_parquet()))
+            .await
+            .unwrap());
+    }
 }
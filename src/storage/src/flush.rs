@@ -18,6 +18,7 @@ mod scheduler;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
+use common_telemetry::tracing::{info_span, Instrument};
 use common_telemetry::{logging, timer};
 use metrics::counter;
 pub use picker::{FlushPicker, PickerConfig};
@@ -35,7 +36,7 @@ use crate::manifest::region::RegionManifest;
 use crate::memtable::{IterContext, MemtableId, MemtableRef};
 use crate::metrics::{FLUSH_BYTES_TOTAL, FLUSH_ELAPSED};
 use crate::region::{RegionWriterRef, SharedDataRef};
-use crate::sst::{AccessLayerRef, FileId, FileMeta, Source, SstInfo, WriteOptions};
+use crate::sst::{AccessLayerRef, FileId, FileMeta, FileTier, Source, SstInfo, WriteOptions};
 use crate::wal::Wal;
 
 /// Current flush-related status of a region.
@@ -238,13 +239,19 @@ impl<S: LogStore> FlushJob<S> {
     /// Execute the flush job.
     async fn run(&mut self) -> Result<()> {
         let _timer = timer!(FLUSH_ELAPSED);
+        let span = info_span!("flush", region_id = self.shared.id());
 
-        let file_metas = self.write_memtables_to_layer().await?;
+        let file_metas = self
+            .write_memtables_to_layer()
+            .instrument(span.clone())
+            .await?;
         if file_metas.is_empty() {
             // skip writing manifest and wal if no files are flushed.
             return Ok(());
         }
-        self.write_manifest_and_apply(&file_metas).await?;
+        self.write_manifest_and_apply(&file_metas)
+            .instrument(span)
+            .await?;
 
         Ok(())
     }
@@ -287,6 +294,7 @@ impl<S: LogStore> FlushJob<S> {
                             time_range,
                             level: 0,
                             file_size,
+                            tier: FileTier::Hot,
                         },
                     ))
             });
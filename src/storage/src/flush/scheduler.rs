@@ -107,6 +107,8 @@ pub struct FlushRegionRequest<S: LogStore> {
     // Compaction related options:
     /// TTL of the region.
     pub ttl: Option<Duration>,
+    /// Age after which SSTs are moved to the cold object store.
+    pub cold_after: Option<Duration>,
     /// Time window for compaction.
     pub compaction_time_window: Option<i64>,
 }
@@ -144,6 +146,7 @@ impl<S: LogStore> From<&FlushRegionRequest<S>> for CompactionRequestImpl<S> {
             manifest: req.manifest.clone(),
             wal: req.wal.clone(),
             ttl: req.ttl,
+            cold_after: req.cold_after,
             compaction_time_window: req.compaction_time_window,
             sender: None,
             sst_write_buffer_size: req.engine_config.sst_write_buffer_size,
@@ -0,0 +1,384 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Offline inspection of region files, e.g. to diagnose a region that fails to open.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use async_compat::CompatExt;
+use common_datasource::compression::CompressionType;
+use common_recordbatch::{RecordBatch, RecordBatches};
+use common_time::Timestamp;
+use futures_util::TryStreamExt;
+use object_store::ObjectStore;
+use parquet::arrow::ParquetRecordBatchStreamBuilder;
+use parquet::file::metadata::KeyValue;
+use snafu::ResultExt;
+use store_api::logstore::LogStore;
+use store_api::manifest::{
+    Manifest, ManifestVersion, MetaActionIterator, MAX_VERSION, MIN_VERSION,
+};
+use store_api::storage::{RegionId, SequenceNumber};
+use tokio::io::BufReader;
+
+use crate::error::{self, CreateRecordBatchSnafu, ReadObjectSnafu, ReadParquetSnafu, Result};
+use crate::manifest::action::{
+    RegionCheckpoint, RegionManifestData, RegionManifestDataBuilder, RegionMetaAction,
+    RegionMetaActionList,
+};
+use crate::manifest::region::RegionManifest;
+use crate::proto::wal::WalHeader;
+use crate::schema::{StoreSchema, StoreSchemaRef};
+use crate::sst::parquet::decode_row_groups_timestamp_range;
+use crate::sst::{FileMeta, Level};
+use crate::wal::Wal;
+use crate::write_batch::Payload;
+
+/// Checkpoint and action lists in the manifest of a region.
+#[derive(Debug)]
+pub struct ManifestDump {
+    /// The last checkpoint of the manifest.
+    pub checkpoint: Option<RegionCheckpoint>,
+    /// Action lists after the checkpoint, ordered by manifest version.
+    pub actions: Vec<(ManifestVersion, RegionMetaActionList)>,
+}
+
+impl ManifestDump {
+    /// Returns the manifest data after applying all actions to the checkpoint.
+    pub fn manifest_data(&self) -> RegionManifestData {
+        let checkpoint = self
+            .checkpoint
+            .as_ref()
+            .and_then(|checkpoint| checkpoint.checkpoint.clone());
+        let mut builder = RegionManifestDataBuilder::with_checkpoint(checkpoint);
+        for (version, action_list) in &self.actions {
+            for action in &action_list.actions {
+                match action {
+                    RegionMetaAction::Change(change) => builder.apply_change(change.clone()),
+                    RegionMetaAction::Edit(edit) => builder.apply_edit(*version, edit.clone()),
+                    RegionMetaAction::Protocol(_) | RegionMetaAction::Remove(_) => (),
+                }
+            }
+        }
+        builder.build()
+    }
+
+    /// Returns metas of the files in the region, grouped by level and ordered by
+    /// start timestamp.
+    pub fn level_files(&self) -> BTreeMap<Level, Vec<FileMeta>> {
+        let mut levels: BTreeMap<Level, Vec<FileMeta>> = BTreeMap::new();
+        if let Some(version) = self.manifest_data().version {
+            for file in version.files.into_values() {
+                levels.entry(file.level).or_default().push(file);
+            }
+        }
+        for files in levels.values_mut() {
+            files.sort_by_key(|file| file.time_range.map(|(start, _)| start));
+        }
+        levels
+    }
+}
+
+/// Reads the last checkpoint and all following actions of the manifest in `manifest_dir`.
+pub async fn read_manifest(
+    manifest_dir: &str,
+    object_store: ObjectStore,
+    compress_type: CompressionType,
+) -> Result<ManifestDump> {
+    let manifest = RegionManifest::create(manifest_dir, object_store, compress_type);
+    let checkpoint = manifest.last_checkpoint().await?;
+    let start = checkpoint
+        .as_ref()
+        .map(|checkpoint| checkpoint.last_version + 1)
+        .unwrap_or(MIN_VERSION);
+
+    let mut iter = manifest.scan(start, MAX_VERSION).await?;
+    let mut actions = Vec::new();
+    while let Some(action) = iter.next_action().await? {
+        actions.push(action);
+    }
+
+    Ok(ManifestDump {
+        checkpoint,
+        actions,
+    })
+}
+
+/// Statistics of a SST file.
+#[derive(Debug)]
+pub struct SstStats {
+    pub num_rows: i64,
+    pub num_row_groups: usize,
+    /// Timestamp range decoded from the statistics of row groups.
+    pub time_range: Option<(Timestamp, Timestamp)>,
+    /// Key value metadata of the parquet file.
+    pub key_value_metadata: Vec<KeyValue>,
+    /// Schema of the file, including internal columns.
+    pub schema: StoreSchemaRef,
+}
+
+/// Reads statistics of the SST file in `path` from its parquet metadata.
+pub async fn read_sst_stats(object_store: &ObjectStore, path: &str) -> Result<SstStats> {
+    let builder = new_sst_stream_builder(object_store, path).await?;
+    let metadata = builder.metadata();
+    let schema = Arc::new(
+        StoreSchema::try_from(builder.schema().clone())
+            .context(error::ConvertStoreSchemaSnafu { file: path })?,
+    );
+
+    let row_groups = metadata
+        .row_groups()
+        .iter()
+        .map(|row_group| row_group.to_thrift())
+        .collect::<Vec<_>>();
+    let time_range = decode_row_groups_timestamp_range(&row_groups, schema.schema())?;
+
+    let file_metadata = metadata.file_metadata();
+    Ok(SstStats {
+        num_rows: file_metadata.num_rows(),
+        num_row_groups: metadata.num_row_groups(),
+        time_range,
+        key_value_metadata: file_metadata
+            .key_value_metadata()
+            .cloned()
+            .unwrap_or_default(),
+        schema,
+    })
+}
+
+/// Reads at most `limit` rows of the SST file in `path`, including internal columns.
+pub async fn read_sst_rows(
+    object_store: &ObjectStore,
+    path: &str,
+    limit: Option<usize>,
+) -> Result<RecordBatches> {
+    let builder = new_sst_stream_builder(object_store, path).await?;
+    let schema = StoreSchema::try_from(builder.schema().clone())
+        .context(error::ConvertStoreSchemaSnafu { file: path })?
+        .schema()
+        .clone();
+
+    let mut stream = builder.build().context(ReadParquetSnafu { file: path })?;
+    let mut batches = Vec::new();
+    let mut remaining = limit.unwrap_or(usize::MAX);
+    while remaining > 0 {
+        let Some(batch) = stream
+            .try_next()
+            .await
+            .context(ReadParquetSnafu { file: path })? else { break };
+        let batch = batch.slice(0, batch.num_rows().min(remaining));
+        remaining -= batch.num_rows();
+        batches.push(
+            RecordBatch::try_from_df_record_batch(schema.clone(), batch)
+                .context(CreateRecordBatchSnafu)?,
+        );
+    }
+
+    RecordBatches::try_new(schema, batches).context(CreateRecordBatchSnafu)
+}
+
+async fn new_sst_stream_builder(
+    object_store: &ObjectStore,
+    path: &str,
+) -> Result<ParquetRecordBatchStreamBuilder<BufReader<async_compat::Compat<object_store::Reader>>>>
+{
+    let reader = object_store
+        .reader(path)
+        .await
+        .context(ReadObjectSnafu { path })?
+        .compat();
+    ParquetRecordBatchStreamBuilder::new(BufReader::new(reader))
+        .await
+        .context(ReadParquetSnafu { file: path })
+}
+
+/// A decoded WAL entry of a region.
+#[derive(Debug)]
+pub struct WalEntry {
+    pub sequence: SequenceNumber,
+    pub header: WalHeader,
+    pub payload: Option<Payload>,
+}
+
+/// Reads WAL entries of the region whose sequences are not less than `start_seq`.
+pub async fn read_wal_entries<S: LogStore>(
+    store: Arc<S>,
+    region_id: RegionId,
+    start_seq: SequenceNumber,
+) -> Result<Vec<WalEntry>> {
+    let wal = Wal::new(region_id, store);
+    let stream = wal.read_from_wal(start_seq).await?;
+    stream
+        .map_ok(|(sequence, header, payload)| WalEntry {
+            sequence,
+            header,
+            payload,
+        })
+        .try_collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use common_test_util::temp_dir::create_temp_dir;
+    use log_store::test_util::log_store_util;
+    use object_store::services::Fs;
+    use store_api::storage::OpType;
+
+    use super::*;
+    use crate::manifest::action::RegionChange;
+    use crate::manifest::test_utils;
+    use crate::memtable::{
+        tests as memtable_tests, DefaultMemtableBuilder, IterContext, MemtableBuilder,
+    };
+    use crate::sst::parquet::ParquetWriter;
+    use crate::sst::{FileId, Source, WriteOptions};
+
+    #[tokio::test]
+    async fn test_read_manifest() {
+        let tmp_dir = create_temp_dir("test_inspect_manifest");
+        let mut builder = Fs::default();
+        let _ = builder.root(&tmp_dir.path().to_string_lossy());
+        let object_store = ObjectStore::new(builder).unwrap().finish();
+
+        let manifest = RegionManifest::create(
+            "/manifest/",
+            object_store.clone(),
+            CompressionType::Uncompressed,
+        );
+        let region_meta = test_utils::build_region_meta();
+        let _ = manifest
+            .update(RegionMetaActionList::with_action(RegionMetaAction::Change(
+                RegionChange {
+                    metadata: (&region_meta).into(),
+                    committed_sequence: 1,
+                },
+            )))
+            .await
+            .unwrap();
+        let (file0, file1) = (FileId::random(), FileId::random());
+        let _ = manifest
+            .update(RegionMetaActionList::with_action(RegionMetaAction::Edit(
+                test_utils::build_region_edit(2, &[file0, file1], &[]),
+            )))
+            .await
+            .unwrap();
+        let _ = manifest
+            .update(RegionMetaActionList::with_action(RegionMetaAction::Edit(
+                test_utils::build_region_edit(3, &[], &[file0]),
+            )))
+            .await
+            .unwrap();
+
+        let dump = read_manifest("/manifest/", object_store, CompressionType::Uncompressed)
+            .await
+            .unwrap();
+        assert!(dump.checkpoint.is_none());
+        assert_eq!(
+            vec![0, 1, 2],
+            dump.actions.iter().map(|(v, _)| *v).collect::<Vec<_>>()
+        );
+
+        let data = dump.manifest_data();
+        assert_eq!(1, data.committed_sequence);
+        assert_eq!(region_meta.name(), data.metadata.name);
+
+        let levels = dump.level_files();
+        assert_eq!(1, levels.len());
+        let files = &levels[&0];
+        assert_eq!(1, files.len());
+        assert_eq!(file1, files[0].file_id);
+    }
+
+    #[tokio::test]
+    async fn test_read_sst() {
+        let schema = memtable_tests::schema_for_test();
+        let memtable = DefaultMemtableBuilder::default().build(schema);
+        memtable_tests::write_kvs(
+            &*memtable,
+            10, // sequence
+            OpType::Put,
+            &[1000, 1002, 2002], // keys
+            &[
+                (Some(1), Some(1234)),
+                (Some(2), Some(1234)),
+                (Some(3), None),
+            ], // values
+        );
+
+        let tmp_dir = create_temp_dir("test_inspect_sst");
+        let mut builder = Fs::default();
+        let _ = builder.root(&tmp_dir.path().to_string_lossy());
+        let object_store = ObjectStore::new(builder).unwrap().finish();
+        let file_name = "test.parquet";
+        let iter = memtable.iter(IterContext::default()).unwrap();
+        let _ = ParquetWriter::new(file_name, Source::Iter(iter), object_store.clone())
+            .write_sst(&WriteOptions::default())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let stats = read_sst_stats(&object_store, file_name).await.unwrap();
+        assert_eq!(3, stats.num_rows);
+        assert_eq!(1, stats.num_row_groups);
+        assert_eq!(
+            Some((
+                Timestamp::new_millisecond(1000),
+                Timestamp::new_millisecond(2002)
+            )),
+            stats.time_range
+        );
+        // Internal columns are included.
+        assert!(stats
+            .schema
+            .schema()
+            .column_schema_by_name("__sequence")
+            .is_some());
+
+        let rows = read_sst_rows(&object_store, file_name, Some(2))
+            .await
+            .unwrap();
+        assert_eq!(2, rows.iter().map(|batch| batch.num_rows()).sum::<usize>());
+        let rows = read_sst_rows(&object_store, file_name, None).await.unwrap();
+        assert_eq!(3, rows.iter().map(|batch| batch.num_rows()).sum::<usize>());
+    }
+
+    #[tokio::test]
+    async fn test_read_wal_entries() {
+        let tmp_dir = create_temp_dir("test_inspect_wal");
+        let log_store = Arc::new(
+            log_store_util::create_tmp_local_file_log_store(&tmp_dir.path().to_string_lossy())
+                .await,
+        );
+        let wal = Wal::new(0, log_store.clone());
+        for sequence in 1..=3 {
+            let header = WalHeader::with_last_manifest_version(sequence * 10);
+            let _ = wal.write_to_wal(sequence, header, None).await.unwrap();
+        }
+
+        let entries = read_wal_entries(log_store.clone(), 0, 2).await.unwrap();
+        assert_eq!(
+            vec![(2, 20), (3, 30)],
+            entries
+                .iter()
+                .map(|entry| (entry.sequence, entry.header.last_manifest_version))
+                .collect::<Vec<_>>()
+        );
+        assert!(entries.iter().all(|entry| entry.payload.is_none()));
+
+        // Entries of other regions are not read.
+        assert!(read_wal_entries(log_store, 1, 0).await.unwrap().is_empty());
+    }
+}
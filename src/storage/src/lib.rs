@@ -23,6 +23,7 @@ pub mod config;
 mod engine;
 pub mod error;
 mod flush;
+pub mod inspect;
 pub mod manifest;
 pub mod memtable;
 pub mod metadata;
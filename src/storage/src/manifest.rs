@@ -13,7 +13,7 @@
 // limitations under the License.
 
 //! manifest storage
-pub(crate) mod action;
+pub mod action;
 pub mod checkpoint;
 pub mod helper;
 mod impl_;
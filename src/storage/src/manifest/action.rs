@@ -292,7 +292,7 @@ mod tests {
     use super::*;
     use crate::manifest::test_utils;
     use crate::metadata::RegionMetadata;
-    use crate::sst::FileId;
+    use crate::sst::{FileId, FileTier};
     use crate::test_util::descriptor_util::RegionDescBuilder;
 
     #[test]
@@ -357,6 +357,7 @@ mod tests {
             time_range: None,
             level: 0,
             file_size: 1024,
+            tier: FileTier::Hot,
         }
     }
 
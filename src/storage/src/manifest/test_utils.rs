@@ -17,7 +17,7 @@ use store_api::storage::SequenceNumber;
 
 use crate::manifest::action::*;
 use crate::metadata::RegionMetadata;
-use crate::sst::{FileId, FileMeta};
+use crate::sst::{FileId, FileMeta, FileTier};
 use crate::test_util::descriptor_util::RegionDescBuilder;
 
 pub const DEFAULT_TEST_FILE_SIZE: u64 = 1024;
@@ -59,6 +59,7 @@ pub fn build_region_edit(
                 time_range: None,
                 level: 0,
                 file_size: DEFAULT_TEST_FILE_SIZE,
+                tier: FileTier::Hot,
             })
             .collect(),
         files_to_remove: files_to_remove
@@ -69,6 +70,7 @@ pub fn build_region_edit(
                 time_range: None,
                 level: 0,
                 file_size: DEFAULT_TEST_FILE_SIZE,
+                tier: FileTier::Hot,
             })
             .collect(),
         compaction_time_window: None,
@@ -26,7 +26,7 @@ use async_trait::async_trait;
 use common_telemetry::{info, logging};
 use common_time::util;
 use metrics::{decrement_gauge, increment_gauge};
-use snafu::ResultExt;
+use snafu::{ensure, ResultExt};
 use store_api::logstore::LogStore;
 use store_api::manifest::{self, Manifest, ManifestVersion, MetaActionIterator};
 use store_api::storage::{
@@ -37,7 +37,7 @@ use store_api::storage::{
 use crate::compaction::CompactionSchedulerRef;
 use crate::config::EngineConfig;
 use crate::error::{self, Error, Result};
-use crate::file_purger::FilePurgerRef;
+use crate::file_purger::{self, FilePurgerRef};
 use crate::flush::{FlushSchedulerRef, FlushStrategyRef};
 use crate::manifest::action::{
     RawRegionMetadata, RegionChange, RegionCheckpoint, RegionMetaAction, RegionMetaActionList,
@@ -104,6 +104,8 @@ impl<S: LogStore> Region for RegionImpl<S> {
     }
 
     async fn write(&self, ctx: &WriteContext, mut request: WriteBatch) -> Result<WriteResponse> {
+        self.inner.ensure_writable()?;
+
         // Compat the schema of the write batch outside of the write lock.
         self.inner.compat_write_batch(&mut request)?;
 
@@ -123,10 +125,15 @@ impl<S: LogStore> Region for RegionImpl<S> {
     }
 
     async fn alter(&self, request: AlterRequest) -> Result<()> {
+        self.inner.ensure_writable()?;
+
         self.inner.alter(request).await
     }
 
     async fn drop_region(&self) -> Result<()> {
+        // Files of a read-only region belong to its leader.
+        self.inner.ensure_writable()?;
+
         self.inner.drop_region().await
     }
 
@@ -140,6 +147,10 @@ impl<S: LogStore> Region for RegionImpl<S> {
             .sum()
     }
 
+    fn is_read_only(&self) -> bool {
+        self.inner.read_only
+    }
+
     async fn flush(&self, ctx: &FlushContext) -> Result<()> {
         self.inner.flush(ctx).await
     }
@@ -160,6 +171,7 @@ pub struct StoreConfig<S: LogStore> {
     pub engine_config: Arc<EngineConfig>,
     pub file_purger: FilePurgerRef,
     pub ttl: Option<Duration>,
+    pub cold_after: Option<Duration>,
     pub write_buffer_size: usize,
 }
 
@@ -240,9 +252,10 @@ impl<S: LogStore> RegionImpl<S> {
                 last_flush_millis: AtomicI64::new(0),
             }),
             writer: Arc::new(RegionWriter::new(
-                store_config.memtable_builder,
+                store_config.memtable_builder.clone(),
                 store_config.engine_config.clone(),
                 store_config.ttl,
+                store_config.cold_after,
                 store_config.write_buffer_size,
             )),
             wal,
@@ -251,6 +264,10 @@ impl<S: LogStore> RegionImpl<S> {
             compaction_scheduler: store_config.compaction_scheduler,
             sst_layer: store_config.sst_layer,
             manifest: store_config.manifest,
+            memtable_builder: store_config.memtable_builder,
+            file_purger: store_config.file_purger,
+            cold_after: store_config.cold_after,
+            read_only: false,
         });
 
         RegionImpl { inner }
@@ -258,11 +275,14 @@ impl<S: LogStore> RegionImpl<S> {
 
     /// Open an existing region and recover its data.
     ///
+    /// A region opened with [OpenOptions::read_only] only recovers flushed data from the
+    /// manifest, and relies on [RegionImpl::refresh_manifest] to pick up new SSTs.
+    ///
     /// The caller should avoid calling this method simultaneously.
     pub async fn open(
         name: String,
         store_config: StoreConfig<S>,
-        _opts: &OpenOptions,
+        opts: &OpenOptions,
     ) -> Result<Option<RegionImpl<S>>> {
         // Load version meta data from manifest.
         let (version, mut recovered_metadata) = match Self::recover_from_manifest(
@@ -286,8 +306,12 @@ impl<S: LogStore> RegionImpl<S> {
         let flushed_sequence = version.flushed_sequence();
         let version_control = Arc::new(VersionControl::with_version(version));
 
-        let recovered_metadata_after_flushed =
+        let mut recovered_metadata_after_flushed =
             recovered_metadata.split_off(&(flushed_sequence + 1));
+        if opts.read_only {
+            // A read-only region never replays the WAL, so it applies the latest metadata.
+            recovered_metadata.append(&mut recovered_metadata_after_flushed);
+        }
         // apply the last flushed metadata
         if let Some((sequence, (manifest_version, metadata))) = recovered_metadata.pop_last() {
             let metadata: RegionMetadataRef = Arc::new(
@@ -313,12 +337,14 @@ impl<S: LogStore> RegionImpl<S> {
         }
 
         let wal = Wal::new(metadata.id(), store_config.log_store);
-        wal.obsolete(flushed_sequence).await?;
-        info!(
-            "Obsolete WAL entries on startup, region: {}, flushed sequence: {}",
-            metadata.id(),
-            flushed_sequence
-        );
+        if !opts.read_only {
+            wal.obsolete(flushed_sequence).await?;
+            info!(
+                "Obsolete WAL entries on startup, region: {}, flushed sequence: {}",
+                metadata.id(),
+                flushed_sequence
+            );
+        }
 
         let shared = Arc::new(SharedData {
             id: metadata.id(),
@@ -328,30 +354,51 @@ impl<S: LogStore> RegionImpl<S> {
         });
 
         let writer = Arc::new(RegionWriter::new(
-            store_config.memtable_builder,
+            store_config.memtable_builder.clone(),
             store_config.engine_config.clone(),
             store_config.ttl,
+            store_config.cold_after,
             store_config.write_buffer_size,
         ));
-        let writer_ctx = WriterContext {
-            shared: &shared,
-            flush_strategy: &store_config.flush_strategy,
-            flush_scheduler: &store_config.flush_scheduler,
-            compaction_scheduler: &store_config.compaction_scheduler,
-            sst_layer: &store_config.sst_layer,
-            wal: &wal,
-            writer: &writer,
-            manifest: &store_config.manifest,
-        };
-        // Replay all unflushed data.
-        writer
-            .replay(recovered_metadata_after_flushed, writer_ctx)
-            .await?;
-
-        // Try to do a manifest checkpoint on opening
-        if store_config.engine_config.manifest_checkpoint_on_startup {
-            let manifest = &store_config.manifest;
-            manifest.may_do_checkpoint(manifest.last_version()).await?;
+        if opts.read_only {
+            // Only flushed data is visible to a read-only region.
+            shared
+                .version_control
+                .set_committed_sequence(flushed_sequence);
+        } else {
+            // Sweeps the files left by purges lost in the last shutdown, before replaying the
+            // WAL may flush new SSTs.
+            if let Err(e) = file_purger::purge_unreferenced_files(
+                shared.id,
+                shared.version_control.current().ssts(),
+                &store_config.sst_layer,
+                &store_config.file_purger,
+            )
+            .await
+            {
+                logging::error!(e; "Failed to purge unreferenced SST files, region: {}", shared.id);
+            }
+
+            let writer_ctx = WriterContext {
+                shared: &shared,
+                flush_strategy: &store_config.flush_strategy,
+                flush_scheduler: &store_config.flush_scheduler,
+                compaction_scheduler: &store_config.compaction_scheduler,
+                sst_layer: &store_config.sst_layer,
+                wal: &wal,
+                writer: &writer,
+                manifest: &store_config.manifest,
+            };
+            // Replay all unflushed data.
+            writer
+                .replay(recovered_metadata_after_flushed, writer_ctx)
+                .await?;
+
+            // Try to do a manifest checkpoint on opening
+            if store_config.engine_config.manifest_checkpoint_on_startup {
+                let manifest = &store_config.manifest;
+                manifest.may_do_checkpoint(manifest.last_version()).await?;
+            }
         }
 
         let inner = Arc::new(RegionInner {
@@ -363,6 +410,10 @@ impl<S: LogStore> RegionImpl<S> {
             compaction_scheduler: store_config.compaction_scheduler,
             sst_layer: store_config.sst_layer,
             manifest: store_config.manifest,
+            memtable_builder: store_config.memtable_builder,
+            file_purger: store_config.file_purger,
+            cold_after: store_config.cold_after,
+            read_only: opts.read_only,
         });
 
         increment_gauge!(crate::metrics::REGION_COUNT, 1.0);
@@ -379,6 +430,17 @@ impl<S: LogStore> RegionImpl<S> {
         self.inner.shared.last_flush_millis()
     }
 
+    /// Returns true if the region has hot SSTs older than its `cold_after`, which are
+    /// waiting for a compaction to move them to the cold object store.
+    pub(crate) fn has_cold_ssts(&self) -> bool {
+        self.inner.cold_after.map_or(false, |cold_after| {
+            self.version_control()
+                .current()
+                .ssts()
+                .has_cold_files(cold_after)
+        })
+    }
+
     /// Returns the [VersionControl] of the region.
     pub(crate) fn version_control(&self) -> &VersionControl {
         self.inner.version_control()
@@ -529,9 +591,89 @@ impl<S: LogStore> RegionImpl<S> {
 
     /// Compact the region manually.
     pub async fn compact(&self, ctx: CompactContext) -> Result<()> {
+        self.inner.ensure_writable()?;
+
         self.inner.compact(ctx).await
     }
 
+    /// Catch up with the manifest actions committed by the leader of a read-only region.
+    pub async fn refresh_manifest(&self) -> Result<()> {
+        let inner = &self.inner;
+        let manifest = &inner.manifest;
+        let mut next_version = manifest.last_version();
+        let mut iter = manifest.scan(next_version, manifest::MAX_VERSION).await?;
+
+        while let Some((manifest_version, action_list)) = iter.next_action().await? {
+            if manifest_version != next_version {
+                // The actions we need have been purged after a checkpoint.
+                return self.reload_from_manifest().await;
+            }
+
+            for action in action_list.actions {
+                match action {
+                    RegionMetaAction::Edit(e) => {
+                        let edit = VersionEdit {
+                            files_to_add: e.files_to_add,
+                            files_to_remove: e.files_to_remove,
+                            flushed_sequence: e.flushed_sequence,
+                            manifest_version,
+                            max_memtable_id: None,
+                            compaction_time_window: e.compaction_time_window,
+                        };
+                        inner.version_control().apply_edit(edit);
+                    }
+                    RegionMetaAction::Change(_) => return self.reload_from_manifest().await,
+                    RegionMetaAction::Protocol(_) | RegionMetaAction::Remove(_) => (),
+                }
+            }
+            next_version = manifest_version + 1;
+        }
+
+        manifest.update_state(next_version, iter.last_protocol().clone());
+        let version_control = inner.version_control();
+        version_control.set_committed_sequence(version_control.current().flushed_sequence());
+
+        Ok(())
+    }
+
+    /// Rebuild the version of a read-only region from the manifest.
+    async fn reload_from_manifest(&self) -> Result<()> {
+        let inner = &self.inner;
+        let (version, mut recovered_metadata) = Self::recover_from_manifest(
+            &inner.manifest,
+            &inner.memtable_builder,
+            &inner.sst_layer,
+            &inner.file_purger,
+        )
+        .await?;
+        let Some(version) = version else { return Ok(()) };
+
+        let flushed_sequence = version.flushed_sequence();
+        let version_control = inner.version_control();
+        version_control.reset(version);
+        if let Some((_, (manifest_version, metadata))) = recovered_metadata.pop_last() {
+            let metadata: RegionMetadataRef =
+                Arc::new(metadata.try_into().context(error::InvalidRawRegionSnafu {
+                    region: &inner.shared.name,
+                })?);
+            let mutable_memtable = inner.memtable_builder.build(metadata.schema().clone());
+            version_control.freeze_mutable_and_apply_metadata(
+                metadata,
+                manifest_version,
+                mutable_memtable,
+            );
+        }
+        version_control.set_committed_sequence(flushed_sequence);
+
+        logging::info!(
+            "Reloaded read-only region {} from manifest, manifest version: {}",
+            inner.shared.id,
+            version_control.current_manifest_version()
+        );
+
+        Ok(())
+    }
+
     pub async fn close(&self, ctx: &CloseContext) -> Result<()> {
         decrement_gauge!(crate::metrics::REGION_COUNT, 1.0);
         self.inner.close(ctx).await
@@ -625,6 +767,13 @@ struct RegionInner<S: LogStore> {
     compaction_scheduler: CompactionSchedulerRef<S>,
     sst_layer: AccessLayerRef,
     manifest: RegionManifest,
+    /// Builds memtables when a read-only region reloads its version.
+    memtable_builder: MemtableBuilderRef,
+    file_purger: FilePurgerRef,
+    /// Age after which SSTs are moved to the cold object store.
+    cold_after: Option<Duration>,
+    /// Whether the region is opened as a read-only follower.
+    read_only: bool,
 }
 
 impl<S: LogStore> RegionInner<S> {
@@ -633,6 +782,16 @@ impl<S: LogStore> RegionInner<S> {
         &self.shared.version_control
     }
 
+    fn ensure_writable(&self) -> Result<()> {
+        ensure!(
+            !self.read_only,
+            error::ReadOnlyRegionSnafu {
+                region_id: self.shared.id,
+            }
+        );
+        Ok(())
+    }
+
     fn in_memory_metadata(&self) -> RegionMetaImpl {
         let metadata = self.version_control().metadata();
 
@@ -691,7 +850,7 @@ impl<S: LogStore> RegionInner<S> {
 
     async fn close(&self, ctx: &CloseContext) -> Result<()> {
         self.writer.close().await?;
-        if ctx.flush {
+        if ctx.flush && !self.read_only {
             let ctx = FlushContext {
                 wait: true,
                 reason: FlushReason::Manually,
@@ -718,6 +877,11 @@ impl<S: LogStore> RegionInner<S> {
     }
 
     async fn flush(&self, ctx: &FlushContext) -> Result<()> {
+        if self.read_only {
+            // Nothing to flush as a read-only region never writes memtables.
+            return Ok(());
+        }
+
         let writer_ctx = WriterContext {
             shared: &self.shared,
             flush_strategy: &self.flush_strategy,
@@ -556,6 +556,7 @@ async fn create_store_config(region_name: &str, root: &str) -> StoreConfig<NoopL
         engine_config: Default::default(),
         file_purger,
         ttl: None,
+        cold_after: None,
         write_buffer_size: ReadableSize::mb(32).0 as usize,
     }
 }
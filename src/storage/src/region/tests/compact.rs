@@ -17,6 +17,7 @@
 use std::env;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use common_telemetry::logging;
 use common_test_util::temp_dir::create_temp_dir;
@@ -28,12 +29,14 @@ use tokio::sync::{Notify, RwLock};
 
 use crate::compaction::{CompactionHandler, SimplePicker};
 use crate::config::EngineConfig;
+use crate::engine;
 use crate::error::Result;
 use crate::file_purger::{FilePurgeHandler, FilePurgeRequest};
 use crate::region::tests::{self, FileTesterBase};
 use crate::region::{CompactContext, FlushStrategyRef, RegionImpl};
 use crate::scheduler::rate_limit::BoxedRateLimitToken;
 use crate::scheduler::{Handler, LocalScheduler, SchedulerConfig};
+use crate::sst::{FileTier, FsAccessLayer};
 use crate::test_util::config_util;
 use crate::test_util::flush_switch::FlushSwitch;
 
@@ -138,7 +141,7 @@ impl Handler for MockFilePurgeHandler {
             self.num_deleted
         );
 
-        let handler = FilePurgeHandler;
+        let handler = FilePurgeHandler::default();
         handler
             .handle_request(req, token, finish_notifier)
             .await
@@ -460,3 +463,82 @@ async fn test_persist_region_compaction_time_window() {
             .unwrap()
     );
 }
+
+#[tokio::test]
+async fn test_move_cold_ssts() {
+    common_telemetry::init_default_ut_logging();
+    let dir = create_temp_dir("move-cold-ssts");
+    let store_dir = dir.path().to_str().unwrap();
+    let cold_dir = create_temp_dir("move-cold-ssts-cold");
+    let cold_store = new_object_store(cold_dir.path().to_str().unwrap(), None);
+
+    let object_store = new_object_store(store_dir, None);
+    let (mut store_config, _) = config_util::new_store_config_with_object_store(
+        REGION_NAME,
+        store_dir,
+        object_store.clone(),
+        EngineConfig::default(),
+    )
+    .await;
+    let sst_dir = engine::region_sst_dir("", REGION_NAME);
+    store_config.sst_layer = Arc::new(
+        FsAccessLayer::new(&sst_dir, object_store.clone())
+            .with_cold_object_store(Some(cold_store.clone())),
+    );
+    store_config.cold_after = Some(Duration::from_secs(60));
+    store_config.flush_strategy = Arc::new(FlushSwitch::default());
+    let handler = CompactionHandler {
+        picker: SimplePicker::default(),
+        #[cfg(test)]
+        pending_tasks: Arc::new(Default::default()),
+    };
+    store_config.compaction_scheduler =
+        Arc::new(LocalScheduler::new(SchedulerConfig::default(), handler));
+    let metadata = tests::new_metadata(REGION_NAME);
+    let region = RegionImpl::create(metadata, store_config).await.unwrap();
+    let tester = FileTesterBase::with_region(region);
+
+    // All data are older than `cold_after`.
+    let data: Vec<_> = (0..100).map(|v| (v, Some(v.to_string()))).collect();
+    let _ = tester.put(&data).await;
+    tester
+        .region
+        .flush(&FlushContext {
+            wait: true,
+            reason: FlushReason::Manually,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    // The compaction after the flush moves the cold SST even if there are not
+    // enough files in level 0 to merge.
+    let mut files = vec![];
+    for _ in 0..50 {
+        let ssts = tester
+            .region
+            .inner
+            .shared
+            .version_control
+            .current()
+            .ssts()
+            .clone();
+        files = ssts
+            .levels()
+            .iter()
+            .flat_map(|l| l.files())
+            .cloned()
+            .collect::<Vec<_>>();
+        if files.iter().all(|f| f.tier() == FileTier::Cold) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(1, files.len());
+    assert_eq!(FileTier::Cold, files[0].tier());
+    assert!(!tester.region.has_cold_ssts());
+    assert!(cold_store.is_exist(&files[0].file_path()).await.unwrap());
+
+    // Data are read from the cold store.
+    assert_eq!(data, tester.full_scan().await);
+}
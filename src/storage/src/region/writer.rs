@@ -72,6 +72,7 @@ impl RegionWriter {
         memtable_builder: MemtableBuilderRef,
         config: Arc<EngineConfig>,
         ttl: Option<Duration>,
+        cold_after: Option<Duration>,
         write_buffer_size: usize,
     ) -> RegionWriter {
         RegionWriter {
@@ -79,6 +80,7 @@ impl RegionWriter {
                 memtable_builder,
                 config,
                 ttl,
+                cold_after,
                 write_buffer_size,
             )),
             version_mutex: Mutex::new(()),
@@ -455,6 +457,7 @@ struct WriterInner {
     closed: bool,
     engine_config: Arc<EngineConfig>,
     ttl: Option<Duration>,
+    cold_after: Option<Duration>,
     /// Size in bytes to freeze the mutable memtable.
     write_buffer_size: usize,
 }
@@ -464,6 +467,7 @@ impl WriterInner {
         memtable_builder: MemtableBuilderRef,
         engine_config: Arc<EngineConfig>,
         ttl: Option<Duration>,
+        cold_after: Option<Duration>,
         write_buffer_size: usize,
     ) -> WriterInner {
         WriterInner {
@@ -472,6 +476,7 @@ impl WriterInner {
             engine_config,
             closed: false,
             ttl,
+            cold_after,
             write_buffer_size,
         }
     }
@@ -772,6 +777,7 @@ impl WriterInner {
             manifest: ctx.manifest.clone(),
             engine_config: self.engine_config.clone(),
             ttl: self.ttl,
+            cold_after: self.cold_after,
             compaction_time_window: current_version.ssts().compaction_time_window(),
         };
 
@@ -808,6 +814,7 @@ impl WriterInner {
             manifest: writer_ctx.manifest.clone(),
             wal: writer_ctx.wal.clone(),
             ttl: self.ttl,
+            cold_after: self.cold_after,
             compaction_time_window,
             sender: None,
             sst_write_buffer_size,
@@ -883,7 +890,15 @@ pub(crate) fn schedule_compaction<S: LogStore>(
         .level(0)
         .file_num();
 
-    if level0_file_num <= max_files_in_l0 {
+    // Cold SSTs are moved by compactions even if there are not enough files to merge.
+    let has_cold_files = compaction_request.cold_after.map_or(false, |cold_after| {
+        shared_data
+            .version_control
+            .current()
+            .ssts()
+            .has_cold_files(cold_after)
+    });
+    if level0_file_num <= max_files_in_l0 && !has_cold_files {
         logging::debug!(
             "No enough SST files in level 0 (threshold: {}), skip compaction",
             max_files_in_l0
@@ -15,6 +15,7 @@
 use std::cmp;
 
 use async_trait::async_trait;
+use common_telemetry::tracing::{info_span, Instrument};
 use store_api::storage::{
     GetRequest, GetResponse, ReadContext, ScanRequest, ScanResponse, SchemaRef, SequenceNumber,
     Snapshot,
@@ -67,7 +68,14 @@ impl Snapshot for SnapshotImpl {
             builder = builder.pick_memtables(memtable.clone());
         }
 
-        let reader = builder.pick_all_ssts(self.version.ssts())?.build().await?;
+        // The span covers both building the reader and reading chunks from it.
+        let span = info_span!("region_scan", region_id = self.version.metadata().id());
+        let reader = builder
+            .pick_all_ssts(self.version.ssts())?
+            .build()
+            .instrument(span.clone())
+            .await?
+            .with_span(span);
 
         Ok(ScanResponse { reader })
     }
@@ -21,6 +21,7 @@ use std::fmt;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use common_base::readable_size::ReadableSize;
@@ -29,12 +30,13 @@ use common_telemetry::{debug, error};
 use common_time::range::TimestampRange;
 use common_time::Timestamp;
 use datatypes::schema::SchemaRef;
-use futures_util::StreamExt;
+use futures_util::{StreamExt, TryStreamExt};
 use object_store::{util, ObjectStore};
 use serde::{Deserialize, Deserializer, Serialize};
 use snafu::{ResultExt, Snafu};
 use store_api::storage::{ChunkReader, RegionId};
 use table::predicate::Predicate;
+use tokio::io::AsyncWriteExt;
 use uuid::Uuid;
 
 use crate::chunk::ChunkReaderImpl;
@@ -105,6 +107,17 @@ impl LevelMetas {
         &self.levels[level as usize]
     }
 
+    /// Returns true if there are hot SSTs older than `cold_after`, which are waiting
+    /// to be moved to the cold object store.
+    pub fn has_cold_files(&self, cold_after: Duration) -> bool {
+        let Ok(cold_time) = Timestamp::current_millis().sub_duration(cold_after) else {
+            return false;
+        };
+        self.levels
+            .iter()
+            .any(|level| !level.get_cold_files(&cold_time).is_empty())
+    }
+
     /// Merge `self` with files to add/remove to create a new [LevelMetas].
     ///
     /// # Panics
@@ -213,6 +226,22 @@ impl LevelMeta {
             .collect()
     }
 
+    /// Returns hot SSTs from current level whose data are older than `cold_time`.
+    pub fn get_cold_files(&self, cold_time: &Timestamp) -> Vec<FileHandle> {
+        self.files
+            .values()
+            .filter(|v| {
+                v.tier() == FileTier::Hot
+                    && !v.compacting()
+                    && v.time_range()
+                        .as_ref()
+                        .map(|(_, end)| end < cold_time)
+                        .unwrap_or(false)
+            })
+            .cloned()
+            .collect()
+    }
+
     pub fn files(&self) -> impl Iterator<Item = &FileHandle> {
         self.files.values()
     }
@@ -301,6 +330,11 @@ impl FileHandle {
     pub fn file_size(&self) -> u64 {
         self.inner.meta.file_size
     }
+
+    #[inline]
+    pub fn tier(&self) -> FileTier {
+        self.inner.meta.tier
+    }
 }
 
 /// Actually data of [FileHandle].
@@ -330,6 +364,7 @@ impl Drop for FileHandleInner {
             let request = FilePurgeRequest {
                 sst_layer: self.sst_layer.clone(),
                 file_id: self.meta.file_id,
+                tier: self.meta.tier,
                 region_id: self.meta.region_id,
             };
             match self.file_purger.schedule(request) {
@@ -422,6 +457,27 @@ pub struct FileMeta {
     pub level: Level,
     /// Size of the file.
     pub file_size: u64,
+    /// Storage tier of the file.
+    pub tier: FileTier,
+}
+
+/// Storage tier of a SST file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub enum FileTier {
+    /// The file is stored in the object store of the engine.
+    #[default]
+    Hot,
+    /// The file has been moved to the cold object store.
+    Cold,
+}
+
+impl fmt::Display for FileTier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileTier::Hot => write!(f, "hot"),
+            FileTier::Cold => write!(f, "cold"),
+        }
+    }
 }
 
 fn deserialize_from_string<'de, D>(deserializer: D) -> std::result::Result<FileId, D::Error>
@@ -487,8 +543,14 @@ pub trait AccessLayer: Send + Sync + std::fmt::Debug {
         opts: &ReadOptions,
     ) -> Result<BoxedBatchReader>;
 
-    /// Deletes a SST file with given name.
-    async fn delete_sst(&self, file_id: FileId) -> Result<()>;
+    /// Deletes a SST file with given name from the object store of `tier`.
+    async fn delete_sst(&self, file_id: FileId, tier: FileTier) -> Result<()>;
+
+    /// Copies the hot SST file `file_id` to the cold object store as `cold_file_id`.
+    async fn copy_sst_to_cold(&self, file_id: FileId, cold_file_id: FileId) -> Result<()>;
+
+    /// Lists the ids of SST files in the object store of `tier`.
+    async fn list_ssts(&self, tier: FileTier) -> Result<Vec<FileId>>;
 }
 
 pub type AccessLayerRef = Arc<dyn AccessLayer>;
@@ -536,12 +598,15 @@ impl Source {
 pub struct FsAccessLayer {
     sst_dir: String,
     object_store: ObjectStore,
+    /// Object store of the cold SSTs.
+    cold_object_store: Option<ObjectStore>,
 }
 
 impl fmt::Debug for FsAccessLayer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("FsAccessLayer")
             .field("sst_dir", &self.sst_dir)
+            .field("has_cold_store", &self.cold_object_store.is_some())
             .finish()
     }
 }
@@ -551,6 +616,23 @@ impl FsAccessLayer {
         FsAccessLayer {
             sst_dir: util::normalize_dir(sst_dir),
             object_store,
+            cold_object_store: None,
+        }
+    }
+
+    /// Sets the object store to move cold SSTs to.
+    pub fn with_cold_object_store(mut self, cold_object_store: Option<ObjectStore>) -> Self {
+        self.cold_object_store = cold_object_store;
+        self
+    }
+
+    fn object_store_of(&self, tier: FileTier) -> Result<&ObjectStore> {
+        match tier {
+            FileTier::Hot => Ok(&self.object_store),
+            FileTier::Cold => self
+                .cold_object_store
+                .as_ref()
+                .context(error::ColdStoreNotFoundSnafu),
         }
     }
 }
@@ -581,9 +663,10 @@ impl AccessLayer for FsAccessLayer {
         file_handle: FileHandle,
         opts: &ReadOptions,
     ) -> Result<BoxedBatchReader> {
+        let object_store = self.object_store_of(file_handle.tier())?.clone();
         let reader = ParquetReader::new(
             file_handle,
-            self.object_store.clone(),
+            object_store,
             opts.projected_schema.clone(),
             opts.predicate.clone(),
             opts.time_range,
@@ -593,13 +676,62 @@ impl AccessLayer for FsAccessLayer {
     }
 
     /// Deletes a SST file with given file id.
-    async fn delete_sst(&self, file_id: FileId) -> Result<()> {
+    async fn delete_sst(&self, file_id: FileId, tier: FileTier) -> Result<()> {
         let path = self.sst_file_path(&file_id.as_parquet());
-        self.object_store
+        self.object_store_of(tier)?
             .delete(&path)
             .await
             .context(DeleteSstSnafu)
     }
+
+    async fn copy_sst_to_cold(&self, file_id: FileId, cold_file_id: FileId) -> Result<()> {
+        let cold_object_store = self.object_store_of(FileTier::Cold)?;
+        let path = self.sst_file_path(&file_id.as_parquet());
+        let cold_path = self.sst_file_path(&cold_file_id.as_parquet());
+        let mut reader = self
+            .object_store
+            .reader(&path)
+            .await
+            .context(error::ReadObjectSnafu { path: &path })?;
+        let mut writer = cold_object_store
+            .writer(&cold_path)
+            .await
+            .context(error::WriteObjectSnafu { path: &cold_path })?;
+        // Streams the file so large SSTs are never buffered in memory as a whole.
+        let _ = tokio::io::copy(&mut reader, &mut writer)
+            .await
+            .context(error::CopyObjectSnafu { path: &cold_path })?;
+        writer
+            .shutdown()
+            .await
+            .context(error::CopyObjectSnafu { path: &cold_path })
+    }
+
+    async fn list_ssts(&self, tier: FileTier) -> Result<Vec<FileId>> {
+        let object_store = match (tier, &self.cold_object_store) {
+            (FileTier::Hot, _) => &self.object_store,
+            (FileTier::Cold, Some(cold_object_store)) => cold_object_store,
+            (FileTier::Cold, None) => return Ok(Vec::new()),
+        };
+        let lister = object_store
+            .list(&self.sst_dir)
+            .await
+            .context(error::ListObjectsSnafu {
+                path: &self.sst_dir,
+            })?;
+        lister
+            .try_filter_map(|entry| async move {
+                Ok(entry
+                    .name()
+                    .strip_suffix(".parquet")
+                    .and_then(|id| FileId::parse_str(id).ok()))
+            })
+            .try_collect()
+            .await
+            .context(error::ListObjectsSnafu {
+                path: &self.sst_dir,
+            })
+    }
 }
 
 struct LazyParquetBatchReader {
@@ -716,6 +848,7 @@ mod tests {
             time_range: None,
             level,
             file_size: 0,
+            tier: FileTier::Hot,
         }
     }
 
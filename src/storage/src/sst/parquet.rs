@@ -33,7 +33,7 @@ use parquet::arrow::{ParquetRecordBatchStreamBuilder, ProjectionMask};
 use parquet::basic::{Compression, Encoding, ZstdLevel};
 use parquet::file::metadata::KeyValue;
 use parquet::file::properties::WriterProperties;
-use parquet::format::FileMetaData;
+use parquet::format::{FileMetaData, RowGroup};
 use parquet::schema::types::ColumnPath;
 use snafu::{OptionExt, ResultExt};
 use store_api::storage::consts::SEQUENCE_COLUMN_NAME;
@@ -144,14 +144,22 @@ impl<'a> ParquetWriter<'a> {
 fn decode_timestamp_range(
     file_meta: &FileMetaData,
     schema: &datatypes::schema::SchemaRef,
+) -> Result<Option<(Timestamp, Timestamp)>> {
+    decode_row_groups_timestamp_range(&file_meta.row_groups, schema)
+}
+
+/// Decodes the timestamp range of the SST from the statistics of its row groups.
+pub(crate) fn decode_row_groups_timestamp_range(
+    row_groups: &[RowGroup],
+    schema: &datatypes::schema::SchemaRef,
 ) -> Result<Option<(Timestamp, Timestamp)>> {
     let (Some(ts_col_idx), Some(ts_col)) = (schema.timestamp_index(), schema.timestamp_column()) else { return Ok(None); };
     let ts_datatype = &ts_col.data_type;
-    decode_timestamp_range_inner(file_meta, ts_col_idx, ts_datatype)
+    decode_timestamp_range_inner(row_groups, ts_col_idx, ts_datatype)
 }
 
 fn decode_timestamp_range_inner(
-    file_meta: &FileMetaData,
+    row_groups: &[RowGroup],
     ts_index: usize,
     ts_datatype: &ConcreteDataType,
 ) -> Result<Option<(Timestamp, Timestamp)>> {
@@ -169,7 +177,7 @@ fn decode_timestamp_range_inner(
         }
     };
 
-    for rg in &file_meta.row_groups {
+    for rg in row_groups {
         let Some(ref metadata) = rg
             .columns
             .get(ts_index)
@@ -208,8 +216,8 @@ fn decode_timestamp_range_inner(
 
     assert!(
         start <= end,
-        "Illegal timestamp range decoded from SST file {:?}, start: {}, end: {}",
-        file_meta,
+        "Illegal timestamp range decoded from SST row groups {:?}, start: {}, end: {}",
+        row_groups,
         start,
         end
     );
@@ -360,7 +368,7 @@ mod tests {
         tests as memtable_tests, DefaultMemtableBuilder, IterContext, MemtableBuilder,
     };
     use crate::schema::ProjectedSchema;
-    use crate::sst::{FileId, FileMeta};
+    use crate::sst::{FileId, FileMeta, FileTier};
 
     fn create_object_store(root: &str) -> ObjectStore {
         let mut builder = Fs::default();
@@ -570,6 +578,7 @@ mod tests {
                 )),
                 level: 0,
                 file_size: 0,
+                tier: FileTier::Hot,
             },
             layer,
             file_purger,
@@ -13,7 +13,9 @@
 // limitations under the License.
 
 use crate::read::BoxedBatchReader;
-use crate::sst::{AccessLayer, FileHandle, FileId, ReadOptions, Source, SstInfo, WriteOptions};
+use crate::sst::{
+    AccessLayer, FileHandle, FileId, FileTier, ReadOptions, Source, SstInfo, WriteOptions,
+};
 
 #[derive(Debug)]
 pub struct MockAccessLayer;
@@ -41,7 +43,19 @@ impl AccessLayer for MockAccessLayer {
         unimplemented!()
     }
 
-    async fn delete_sst(&self, _file_id: FileId) -> crate::error::Result<()> {
+    async fn delete_sst(&self, _file_id: FileId, _tier: FileTier) -> crate::error::Result<()> {
         Ok(())
     }
+
+    async fn copy_sst_to_cold(
+        &self,
+        _file_id: FileId,
+        _cold_file_id: FileId,
+    ) -> crate::error::Result<()> {
+        unimplemented!()
+    }
+
+    async fn list_ssts(&self, _tier: FileTier) -> crate::error::Result<Vec<FileId>> {
+        Ok(Vec::new())
+    }
 }
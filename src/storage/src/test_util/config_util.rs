@@ -124,6 +124,7 @@ pub async fn new_store_config_with_object_store(
             engine_config: Arc::new(engine_config),
             file_purger,
             ttl: None,
+            cold_after: None,
             write_buffer_size: DEFAULT_REGION_WRITE_BUFFER_SIZE.as_bytes() as usize,
         },
         regions,
@@ -107,6 +107,14 @@ impl VersionControl {
         version_to_update.commit();
     }
 
+    /// Replace the whole version, used by read-only regions to reload the version
+    /// from the manifest.
+    pub fn reset(&self, version: Version) {
+        let mut version_to_update = self.version.lock();
+        *version_to_update = version;
+        version_to_update.commit();
+    }
+
     /// Freeze all mutable memtables and then apply the new metadata to the version.
     pub fn freeze_mutable_and_apply_metadata(
         &self,
@@ -17,6 +17,7 @@ use std::sync::Arc;
 
 use common_error::prelude::BoxedError;
 use common_telemetry::timer;
+use common_telemetry::tracing::{info_span, Instrument};
 use futures::{stream, Stream, TryStreamExt};
 use prost::Message;
 use snafu::{ensure, Location, ResultExt};
@@ -141,7 +142,9 @@ impl<S: LogStore> Wal<S> {
         }
 
         // write bytes to wal
-        self.write(seq, &buf).await
+        self.write(seq, &buf)
+            .instrument(info_span!("wal_write", region_id = self.region_id(), seq))
+            .await
     }
 
     pub async fn read_from_wal(&self, start_seq: SequenceNumber) -> Result<PayloadStream<'_>> {
@@ -92,6 +92,8 @@ pub struct CreateOptions {
     pub write_buffer_size: Option<usize>,
     /// Region SST files TTL
     pub ttl: Option<Duration>,
+    /// Age after which region SST files are moved to the cold object store
+    pub cold_after: Option<Duration>,
 }
 
 /// Options to open a region.
@@ -103,6 +105,11 @@ pub struct OpenOptions {
     pub write_buffer_size: Option<usize>,
     /// Region SST files TTL
     pub ttl: Option<Duration>,
+    /// Age after which region SST files are moved to the cold object store
+    pub cold_after: Option<Duration>,
+    /// Open the region as a read-only follower, which doesn't replay the WAL
+    /// and rejects writes.
+    pub read_only: bool,
 }
 
 /// Options to close a region.
@@ -77,10 +77,16 @@ pub trait Region: Send + Sync + Clone + std::fmt::Debug + 'static {
 
     fn disk_usage_bytes(&self) -> u64;
 
+    /// Returns true if the region is opened as a read-only follower.
+    fn is_read_only(&self) -> bool {
+        false
+    }
+
     fn region_stat(&self) -> RegionStat {
         RegionStat {
             region_id: self.id(),
             disk_usage_bytes: self.disk_usage_bytes(),
+            read_only: self.is_read_only(),
         }
     }
 
@@ -92,6 +98,7 @@ pub trait Region: Send + Sync + Clone + std::fmt::Debug + 'static {
 pub struct RegionStat {
     pub region_id: u64,
     pub disk_usage_bytes: u64,
+    pub read_only: bool,
 }
 
 /// Context for write operations.
@@ -235,6 +235,7 @@ impl CreateTableProcedure {
             table_name: self.data.request.table_name.clone(),
             table_id: self.data.request.id,
             region_numbers: self.data.request.region_numbers.clone(),
+            read_only: false,
         };
         // Safety: The table is already created.
         let table = self
@@ -76,12 +76,16 @@ pub struct TableOptions {
     /// Time-to-live of table. Expired data will be automatically purged.
     #[serde(with = "humantime_serde")]
     pub ttl: Option<Duration>,
+    /// Age after which SSTs are moved to the cold object store.
+    #[serde(with = "humantime_serde")]
+    pub cold_after: Option<Duration>,
     /// Extra options that may not applicable to all table engines.
     pub extra_options: HashMap<String, String>,
 }
 
 pub const WRITE_BUFFER_SIZE_KEY: &str = "write_buffer_size";
 pub const TTL_KEY: &str = "ttl";
+pub const COLD_AFTER_KEY: &str = "cold_after";
 pub const REGIONS_KEY: &str = "regions";
 
 impl TryFrom<&HashMap<String, String>> for TableOptions {
@@ -113,8 +117,23 @@ impl TryFrom<&HashMap<String, String>> for TableOptions {
                 .into();
             options.ttl = Some(ttl_value);
         }
+
+        if let Some(cold_after) = value.get(COLD_AFTER_KEY) {
+            let cold_after_value = cold_after
+                .parse::<humantime::Duration>()
+                .map_err(|_| {
+                    ParseTableOptionSnafu {
+                        key: COLD_AFTER_KEY,
+                        value: cold_after,
+                    }
+                    .build()
+                })?
+                .into();
+            options.cold_after = Some(cold_after_value);
+        }
         options.extra_options = HashMap::from_iter(value.iter().filter_map(|(k, v)| {
-            if k != WRITE_BUFFER_SIZE_KEY && k != REGIONS_KEY && k != TTL_KEY {
+            if k != WRITE_BUFFER_SIZE_KEY && k != REGIONS_KEY && k != TTL_KEY && k != COLD_AFTER_KEY
+            {
                 Some((k.clone(), v.clone()))
             } else {
                 None
@@ -126,7 +145,7 @@ impl TryFrom<&HashMap<String, String>> for TableOptions {
 
 impl From<&TableOptions> for HashMap<String, String> {
     fn from(opts: &TableOptions) -> Self {
-        let mut res = HashMap::with_capacity(2 + opts.extra_options.len());
+        let mut res = HashMap::with_capacity(3 + opts.extra_options.len());
         if let Some(write_buffer_size) = opts.write_buffer_size {
             let _ = res.insert(
                 WRITE_BUFFER_SIZE_KEY.to_string(),
@@ -137,6 +156,10 @@ impl From<&TableOptions> for HashMap<String, String> {
             let ttl_str = humantime::format_duration(ttl).to_string();
             let _ = res.insert(TTL_KEY.to_string(), ttl_str);
         }
+        if let Some(cold_after) = opts.cold_after {
+            let cold_after_str = humantime::format_duration(cold_after).to_string();
+            let _ = res.insert(COLD_AFTER_KEY.to_string(), cold_after_str);
+        }
         res.extend(
             opts.extra_options
                 .iter()
@@ -154,6 +177,8 @@ pub struct OpenTableRequest {
     pub table_name: String,
     pub table_id: TableId,
     pub region_numbers: Vec<RegionNumber>,
+    /// Whether to open the regions as read-only followers.
+    pub read_only: bool,
 }
 
 /// Alter table request
@@ -317,6 +342,7 @@ mod tests {
         let options = TableOptions {
             write_buffer_size: None,
             ttl: Some(Duration::from_secs(1000)),
+            cold_after: Some(Duration::from_secs(100)),
             extra_options: HashMap::new(),
         };
         let serialized = serde_json::to_string(&options).unwrap();
@@ -329,6 +355,7 @@ mod tests {
         let options = TableOptions {
             write_buffer_size: Some(ReadableSize::mb(128)),
             ttl: Some(Duration::from_secs(1000)),
+            cold_after: Some(Duration::from_secs(100)),
             extra_options: HashMap::new(),
         };
         let serialized_map = HashMap::from(&options);
@@ -338,6 +365,7 @@ mod tests {
         let options = TableOptions {
             write_buffer_size: None,
             ttl: None,
+            cold_after: None,
             extra_options: HashMap::new(),
         };
         let serialized_map = HashMap::from(&options);
@@ -347,6 +375,7 @@ mod tests {
         let options = TableOptions {
             write_buffer_size: Some(ReadableSize::mb(128)),
             ttl: Some(Duration::from_secs(1000)),
+            cold_after: None,
             extra_options: HashMap::from([("a".to_string(), "A".to_string())]),
         };
         let serialized_map = HashMap::from(&options);
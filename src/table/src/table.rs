@@ -15,6 +15,7 @@
 pub mod adapter;
 pub mod numbers;
 pub mod scan;
+pub mod view;
 
 use std::any::Any;
 use std::sync::Arc;
@@ -135,6 +136,12 @@ pub trait Table: Send + Sync {
     fn statistics(&self) -> Option<TableStatistics> {
         None
     }
+
+    /// Returns a table that scans follower regions instead of leader regions, or
+    /// `None` if the table doesn't support follower reads.
+    fn with_follower_reads(&self) -> Option<TableRef> {
+        None
+    }
 }
 
 pub type TableRef = Arc<dyn Table>;
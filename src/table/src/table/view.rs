@@ -0,0 +1,164 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_query::physical_plan::PhysicalPlanRef;
+use common_recordbatch::SendableRecordBatchStream;
+use datatypes::schema::SchemaRef;
+use store_api::storage::ScanRequest;
+
+use crate::error::{Result, UnsupportedSnafu};
+use crate::metadata::{
+    TableId, TableInfo, TableInfoBuilder, TableInfoRef, TableMetaBuilder, TableType,
+};
+use crate::requests::TableOptions;
+use crate::table::{Expr, Table};
+
+/// Key of the query that defines the view, in the view's table options.
+pub const VIEW_DEFINITION_KEY: &str = "view_definition";
+
+/// A view has no data of its own. Instead of being scanned, it is expanded into
+/// the logical plan of its definition when queried.
+pub struct ViewTable {
+    table_info: TableInfoRef,
+}
+
+impl ViewTable {
+    pub fn new(table_info: TableInfoRef) -> Self {
+        Self { table_info }
+    }
+
+    /// Builds the table info of a view with its `definition` query, whose output
+    /// schema is `schema`. Views have no regions, and their table id is allocated
+    /// by the catalog manager when the view is registered.
+    pub fn build_table_info(
+        catalog_name: &str,
+        schema_name: &str,
+        view_name: &str,
+        definition: String,
+        schema: SchemaRef,
+    ) -> TableInfo {
+        let options = TableOptions {
+            extra_options: HashMap::from([(VIEW_DEFINITION_KEY.to_string(), definition)]),
+            ..Default::default()
+        };
+        // Safety: all required fields are set.
+        let meta = TableMetaBuilder::new_external_table()
+            .schema(schema)
+            .options(options)
+            .build()
+            .unwrap();
+        TableInfoBuilder::new(view_name, meta)
+            .catalog_name(catalog_name)
+            .schema_name(schema_name)
+            .table_type(TableType::View)
+            .build()
+            .unwrap()
+    }
+
+    /// Creates a view from `table_info` with its table id set to `table_id`.
+    pub fn new_with_table_id(table_info: &TableInfo, table_id: TableId) -> Self {
+        let mut table_info = table_info.clone();
+        table_info.ident.table_id = table_id;
+        Self::new(Arc::new(table_info))
+    }
+
+    /// Returns the query that defines the view.
+    pub fn definition(&self) -> &str {
+        self.table_info
+            .meta
+            .options
+            .extra_options
+            .get(VIEW_DEFINITION_KEY)
+            .map(|s| s.as_str())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for ViewTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.table_info.meta.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        self.table_info.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    async fn scan(
+        &self,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<PhysicalPlanRef> {
+        UnsupportedSnafu {
+            operation: format!("scan view {}", self.table_info.name),
+        }
+        .fail()
+    }
+
+    async fn scan_to_stream(&self, _request: ScanRequest) -> Result<SendableRecordBatchStream> {
+        UnsupportedSnafu {
+            operation: format!("scan view {}", self.table_info.name),
+        }
+        .fail()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+
+    use super::*;
+
+    #[test]
+    fn test_view_table() {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "number",
+            ConcreteDataType::uint32_datatype(),
+            false,
+        )]));
+        let table_info = ViewTable::build_table_info(
+            "greptime",
+            "public",
+            "v",
+            "SELECT number FROM numbers".to_string(),
+            schema.clone(),
+        );
+        assert_eq!(TableType::View, table_info.table_type);
+        assert_eq!(0, table_info.ident.table_id);
+        assert!(table_info.meta.region_numbers.is_empty());
+
+        let view = ViewTable::new(Arc::new(table_info));
+        assert_eq!(schema, view.schema());
+        assert_eq!(TableType::View, view.table_type());
+        assert_eq!("SELECT number FROM numbers", view.definition());
+
+        let view = ViewTable::new_with_table_id(&view.table_info(), 1024);
+        assert_eq!(1024, view.table_info().ident.table_id);
+        assert_eq!("SELECT number FROM numbers", view.definition());
+    }
+}
@@ -21,6 +21,7 @@ mod tests {
 
     use catalog::helper::{TableGlobalKey, TableGlobalValue};
     use common_base::Plugins;
+    use common_catalog::consts::INTERNAL_SCHEMA_NAME;
     use common_query::Output;
     use common_recordbatch::RecordBatches;
     use frontend::error::{self, Error, Result};
@@ -29,7 +30,7 @@ mod tests {
     use query::parser::QueryLanguageParser;
     use servers::interceptor::{SqlQueryInterceptor, SqlQueryInterceptorRef};
     use servers::query_handler::sql::SqlQueryHandler;
-    use session::context::{QueryContext, QueryContextRef};
+    use session::context::{QueryContext, QueryContextRef, UserInfo};
     use sql::statements::statement::Statement;
 
     use crate::tests;
@@ -403,4 +404,80 @@ mod tests {
             unreachable!();
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_rbac_internal_tables() {
+        let (opts, _guard) = crate::test_util::create_tmp_dir_and_datanode_opts(
+            crate::test_util::StorageType::File,
+            "test_rbac_internal_tables",
+        );
+        let (dn_instance, _) = datanode::instance::Instance::with_opts(&opts, Default::default())
+            .await
+            .unwrap();
+        let mut instance = Instance::try_new_standalone(dn_instance.clone())
+            .await
+            .unwrap();
+        // System tables of RBAC are created when the datanode starts.
+        instance.enable_rbac("cmd:root=123").await.unwrap();
+        dn_instance.start().await.unwrap();
+
+        let query_ctx = |username: &str| {
+            let query_ctx = QueryContext::arc();
+            query_ctx.set_current_user(UserInfo::new(username));
+            query_ctx
+        };
+        let exec = |sql: &'static str, query_ctx: QueryContextRef| {
+            let instance = &instance;
+            async move {
+                SqlQueryHandler::do_query(instance, sql, query_ctx)
+                    .await
+                    .remove(0)
+            }
+        };
+
+        for sql in [
+            "CREATE USER alice IDENTIFIED BY 'secret'",
+            "GRANT ALL ON *.* TO alice",
+        ] {
+            let _ = exec(sql, query_ctx("root")).await.unwrap();
+        }
+
+        let output = exec("SHOW DATABASES", query_ctx("root")).await.unwrap();
+        let Output::RecordBatches(batches) = output else { unreachable!() };
+        assert!(!batches
+            .pretty_print()
+            .unwrap()
+            .contains(INTERNAL_SCHEMA_NAME));
+
+        // Even granted all privileges on all databases, a non-superuser can
+        // neither read nor write the internal tables.
+        for sql in [
+            "SELECT * FROM greptime_private.users",
+            "SELECT grantee FROM public.numbers, greptime_private.privileges",
+            "INSERT INTO greptime_private.privileges VALUES ('alice', 'ROLE', 'admin', 0, 0)",
+            "INSERT INTO greptime_private.users SELECT * FROM greptime_private.users",
+            "DELETE FROM greptime_private.users WHERE name = 'alice'",
+            "DESC TABLE greptime_private.users",
+            "SHOW TABLES FROM greptime_private",
+        ] {
+            let result = exec(sql, query_ctx("alice")).await;
+            assert!(matches!(result, Err(Error::Auth { .. })), "{sql}");
+        }
+        // Neither can an anonymous user.
+        let result = exec("SELECT * FROM greptime_private.users", QueryContext::arc()).await;
+        assert!(matches!(result, Err(Error::Auth { .. })));
+
+        let output = exec("SELECT name FROM greptime_private.users", query_ctx("root"))
+            .await
+            .unwrap();
+        let Output::Stream(stream) = output else { unreachable!() };
+        let batches = RecordBatches::try_collect(stream).await.unwrap();
+        let expected = "\
++-------+
+| name  |
++-------+
+| alice |
++-------+";
+        assert_eq!(expected, batches.pretty_print().unwrap());
+    }
 }
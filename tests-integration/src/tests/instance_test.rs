@@ -15,10 +15,11 @@
 use std::env;
 use std::sync::Arc;
 
-use common_catalog::consts::DEFAULT_CATALOG_NAME;
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, MIN_USER_TABLE_ID};
 use common_query::Output;
 use common_recordbatch::util;
 use common_telemetry::logging;
+use common_test_util::temp_dir::create_temp_dir;
 use datatypes::vectors::{Int64Vector, StringVector, UInt64Vector, VectorRef};
 use frontend::error::{Error, Result};
 use frontend::instance::Instance;
@@ -584,6 +585,97 @@ async fn test_execute_query_external_table_parquet(instance: Arc<dyn MockInstanc
     check_output_stream(output, expect).await;
 }
 
+#[apply(both_instances_cases)]
+async fn test_execute_query_external_table_orc(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+    let format = "orc";
+    let location = get_data_dir("../src/common/datasource/tests/orc/test.orc")
+        .canonicalize()
+        .unwrap()
+        .display()
+        .to_string();
+
+    let table_name = "various_type_orc";
+
+    let output = execute_sql(
+        &instance,
+        &format!(
+            r#"create external table {table_name} with (location='{location}', format='{format}');"#,
+        ),
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = execute_sql(
+        &instance,
+        &format!("select str_direct, double_a, utf8_increase from {table_name} order by double_a;"),
+    )
+    .await;
+    let expect = "\
++------------+----------+---------------+
+| str_direct | double_a | utf8_increase |
++------------+----------+---------------+
+| a          | 1.0      | a             |
+| cccccc     | 2.0      | bb            |
+|            | 3.0      | ccc           |
+| ddd        | 4.0      | dddd          |
+| ee         | 5.0      | eeeee         |
++------------+----------+---------------+";
+    check_output_stream(output, expect).await;
+
+    let dir = create_temp_dir("test_execute_query_external_table_orc");
+    let dir = dir.path().to_str().unwrap();
+    let output = execute_sql(
+        &instance,
+        &format!("copy {table_name} to '{dir}/{table_name}.orc' with (format='orc')"),
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(5)));
+    let output = execute_sql(
+        &instance,
+        &format!("copy database public to '{dir}/db/' with (format='orc')"),
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(5)));
+
+    // Reads the exported files back, filters are used to prune stripes.
+    for (exported_table, location) in [
+        ("exported_orc", format!("{dir}/{table_name}.orc")),
+        (
+            "exported_database_orc",
+            format!("{dir}/db/{table_name}.orc"),
+        ),
+    ] {
+        let output = execute_sql(
+            &instance,
+            &format!(
+                r#"create external table {exported_table} with (location='{location}', format='{format}');"#,
+            ),
+        )
+        .await;
+        assert!(matches!(output, Output::AffectedRows(0)));
+
+        let output = execute_sql(
+            &instance,
+            &format!(
+                "select str_direct, double_a, utf8_increase from {exported_table} \
+                where double_a > 1.5 order by double_a;"
+            ),
+        )
+        .await;
+        let expect = "\
++------------+----------+---------------+
+| str_direct | double_a | utf8_increase |
++------------+----------+---------------+
+| cccccc     | 2.0      | bb            |
+|            | 3.0      | ccc           |
+| ddd        | 4.0      | dddd          |
+| ee         | 5.0      | eeeee         |
++------------+----------+---------------+";
+        check_output_stream(output, expect).await;
+    }
+}
+
 #[apply(both_instances_cases)]
 async fn test_execute_query_external_table_csv(instance: Arc<dyn MockInstance>) {
     let instance = instance.frontend();
@@ -872,6 +964,193 @@ async fn test_create_table_after_rename_table(instance: Arc<dyn MockInstance>) {
     check_output_stream(output, expect).await;
 }
 
+#[apply(standalone_instance_case)]
+async fn test_create_and_drop_view(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+
+    let output = execute_sql(&instance, "create database db").await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    let query_ctx = Arc::new(QueryContext::with(DEFAULT_CATALOG_NAME, "db"));
+    let output = execute_sql_with(
+        &instance,
+        "create table demo(host string, cpu double, memory double, ts timestamp, time index(ts))",
+        query_ctx.clone(),
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    let output = execute_sql_with(
+        &instance,
+        "insert into demo(host, cpu, memory, ts) values ('host1', 1.1, 100, 1000), ('host2', 2.2, 200, 2000)",
+        query_ctx.clone(),
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+
+    let output = execute_sql_with(
+        &instance,
+        "create view busy_hosts as select host, cpu from demo where cpu > 2",
+        query_ctx.clone(),
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = execute_sql_with(&instance, "select * from busy_hosts", query_ctx.clone()).await;
+    let expected = "\
++-------+-----+
+| host  | cpu |
++-------+-----+
+| host2 | 2.2 |
++-------+-----+";
+    check_output_stream(output, expected).await;
+
+    let output = execute_sql_with(&instance, "show tables", query_ctx.clone()).await;
+    let expected = "\
++------------+
+| Tables     |
++------------+
+| busy_hosts |
+| demo       |
++------------+";
+    check_output_stream(output, expected).await;
+
+    // views take table ids from the same allocator as tables
+    let table_id = |name: &'static str| {
+        let instance = instance.clone();
+        async move {
+            instance
+                .catalog_manager()
+                .table(DEFAULT_CATALOG_NAME, "db", name)
+                .await
+                .unwrap()
+                .unwrap()
+                .table_info()
+                .ident
+                .table_id
+        }
+    };
+    let view_id = table_id("busy_hosts").await;
+    assert!(view_id >= MIN_USER_TABLE_ID);
+    assert_ne!(table_id("demo").await, view_id);
+
+    // views have no data to copy
+    let dir = create_temp_dir("test_create_and_drop_view");
+    let location = format!("{}/", dir.path().display());
+    let output = execute_sql_with(
+        &instance,
+        &format!("copy database db to '{location}' with (format='parquet')"),
+        query_ctx.clone(),
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+    assert!(!dir.path().join("busy_hosts.parquet").exists());
+
+    // views are not replaced unless asked to
+    assert!(try_execute_sql_with(
+        &instance,
+        "create view busy_hosts as select host from demo",
+        query_ctx.clone(),
+    )
+    .await
+    .is_err());
+    let output = execute_sql_with(
+        &instance,
+        "create or replace view busy_hosts as select host from demo where memory > 150",
+        query_ctx.clone(),
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = execute_sql_with(&instance, "select * from busy_hosts", query_ctx.clone()).await;
+    let expected = "\
++-------+
+| host  |
++-------+
+| host2 |
++-------+";
+    check_output_stream(output, expected).await;
+    assert_eq!(view_id, table_id("busy_hosts").await);
+
+    assert!(
+        try_execute_sql_with(&instance, "drop table busy_hosts", query_ctx.clone())
+            .await
+            .is_err()
+    );
+    let output = execute_sql_with(&instance, "drop view busy_hosts", query_ctx.clone()).await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    assert!(
+        try_execute_sql_with(&instance, "select * from busy_hosts", query_ctx.clone())
+            .await
+            .is_err()
+    );
+    let output = execute_sql_with(&instance, "drop view if exists busy_hosts", query_ctx).await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+}
+
+#[apply(standalone_instance_case)]
+async fn test_create_and_drop_materialized_view(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+
+    let output = execute_sql(
+        &instance,
+        "create table demo(host string, cpu double, ts timestamp, time index(ts), primary key(host))",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    let output = execute_sql(
+        &instance,
+        "insert into demo(host, cpu, ts) values ('host1', 1.0, 0), ('host1', 3.0, 30000), ('host2', 2.0, 30000), ('host1', 5.0, 60000)",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(4)));
+
+    // the materialized view is grouped by a date_bin time bucket
+    assert!(try_execute_sql(
+        &instance,
+        "create materialized view cpu_avg as select host, avg(cpu) from demo group by host",
+    )
+    .await
+    .is_err());
+    assert!(try_execute_sql(
+        &instance,
+        "create materialized view cpu_1m with (refresh_interval = 'soon') as select date_bin(INTERVAL '1 minute', ts) as ts, host, avg(cpu) as cpu from demo group by 1, host",
+    )
+    .await
+    .is_err());
+
+    let output = execute_sql(
+        &instance,
+        "create materialized view cpu_1m with (refresh_interval = '10s') as select date_bin(INTERVAL '1 minute', ts) as ts, host, avg(cpu) as cpu from demo group by 1, host",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = execute_sql(&instance, "select * from cpu_1m order by ts, host").await;
+    let expected = "\
++---------------------+-------+-----+
+| ts                  | host  | cpu |
++---------------------+-------+-----+
+| 1970-01-01T00:00:00 | host1 | 2.0 |
+| 1970-01-01T00:00:00 | host2 | 2.0 |
+| 1970-01-01T00:01:00 | host1 | 5.0 |
++---------------------+-------+-----+";
+    check_output_stream(output, expected).await;
+
+    assert!(try_execute_sql(&instance, "drop table cpu_1m")
+        .await
+        .is_err());
+    let output = execute_sql(&instance, "drop materialized view cpu_1m").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    assert!(try_execute_sql(&instance, "select * from cpu_1m")
+        .await
+        .is_err());
+    assert!(try_execute_sql(&instance, "drop materialized view cpu_1m")
+        .await
+        .is_err());
+    let output = execute_sql(&instance, "drop materialized view if exists cpu_1m").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+}
+
 #[ignore = "https://github.com/GreptimeTeam/greptimedb/issues/1681"]
 #[apply(both_instances_cases)]
 async fn test_alter_table(instance: Arc<dyn MockInstance>) {